@@ -0,0 +1,289 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_quote, Attribute, Block, Expr, ForeignItem, ItemFn, ItemForeignMod, ItemMacro,
+    ReturnType, Signature, Stmt, Type,
+};
+
+use crate::{
+    attr::{self, Attr},
+    msg_send::c_str,
+    versions::Versions,
+};
+
+/// Item split into outer attributes and the rest
+struct Item {
+    attrs: Vec<Attribute>,
+    rest: TokenStream,
+}
+
+impl Parse for Item {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            attrs: input.call(Attribute::parse_outer)?,
+            rest: input.parse()?,
+        })
+    }
+}
+
+/// `fooBar` -> `FOO_BAR`
+fn upper_case(str: &str) -> String {
+    let len = str.len();
+    let mut res = Vec::<u8>::with_capacity(len + 10);
+    let bytes = str.as_bytes();
+    let mut was_lowercase = false;
+    for ch in bytes {
+        let is_upper = ch.is_ascii_uppercase();
+        if was_lowercase && is_upper {
+            res.push(b'_');
+        }
+        res.push(ch.to_ascii_uppercase());
+        was_lowercase = !is_upper;
+    }
+
+    String::from_utf8(res).unwrap()
+}
+
+fn upper_ident(ident: &Ident) -> Ident {
+    Ident::new(&upper_case(&ident.to_string()), ident.span())
+}
+
+fn wrap_option(output: &mut ReturnType) {
+    if let ReturnType::Type(_, ty) = output {
+        let inner = ty.clone();
+        **ty = parse_quote!(Option<#inner>);
+    }
+}
+
+/// `VAR` or `unsafe { VAR }`
+fn static_var(block: &Block) -> Option<&Ident> {
+    let [Stmt::Expr(expr, None)] = block.stmts.as_slice() else {
+        return None;
+    };
+    match expr {
+        Expr::Path(p) => p.path.get_ident(),
+        Expr::Unsafe(u) => match u.block.stmts.as_slice() {
+            [Stmt::Expr(Expr::Path(p), None)] => p.path.get_ident(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `fn var() -> &'static T { unsafe { VAR } }` becomes
+/// `fn var() -> Option<&'static T> { unsafe { VAR.get_var() } }`
+fn try_replace_return(f: &mut ItemFn) {
+    if !f.sig.inputs.is_empty() {
+        return;
+    }
+    let ReturnType::Type(_, ty) = &f.sig.output else {
+        return;
+    };
+    let Type::Reference(r) = &**ty else {
+        return;
+    };
+    if !matches!(&r.lifetime, Some(l) if l.ident == "static") {
+        return;
+    }
+    let Some(var) = static_var(&f.block) else {
+        return;
+    };
+    let var = upper_ident(var);
+    *f.block = parse_quote!({ unsafe { #var.get_var() } });
+    wrap_option(&mut f.sig.output);
+}
+
+/// `Self::alloc()` at the start of call chain becomes `Self::alloc()?`
+fn try_alloc(expr: &mut Expr) -> bool {
+    match expr {
+        Expr::MethodCall(m) => try_alloc(&mut m.receiver),
+        Expr::Field(f) => try_alloc(&mut f.base),
+        Expr::Try(t) => try_alloc(&mut t.expr),
+        Expr::Await(a) => try_alloc(&mut a.base),
+        Expr::Call(c) => {
+            let Expr::Path(p) = &*c.func else {
+                return false;
+            };
+            let segments = &p.path.segments;
+            if p.qself.is_some()
+                || segments.len() != 2
+                || segments[0].ident != "Self"
+                || segments[1].ident != "alloc"
+            {
+                return false;
+            }
+            let call = expr.clone();
+            *expr = parse_quote!(#call?);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Rewrites body of weakly linked function, returns `false` if body is unknown.
+/// `optional` is set when return type should be wrapped in `Option`.
+fn try_replace_fn(block: &mut Block, optional: &mut bool) -> bool {
+    match block.stmts.as_slice() {
+        [Stmt::Expr(Expr::Block(b), _), ..] if b.label.is_none() && b.attrs.is_empty() => {
+            *optional = true;
+            true
+        }
+        // `unsafe { ffi_fn(args) }`
+        [Stmt::Expr(Expr::Unsafe(u), _), ..] => {
+            let [Stmt::Expr(Expr::Call(call), None)] = u.block.stmts.as_slice() else {
+                return false;
+            };
+            let Expr::Path(func) = &*call.func else {
+                return false;
+            };
+            let Some(func) = func.path.get_ident() else {
+                return false;
+            };
+            let var = upper_ident(func);
+            let args = &call.args;
+            *block = parse_quote!({ unsafe { #var.get_fn().unwrap()(#args) } });
+            true
+        }
+        [Stmt::Expr(expr, None)] => {
+            let mut expr = expr.clone();
+            if !try_alloc(&mut expr) {
+                return false;
+            }
+            *block = parse_quote!({ Some(#expr) });
+            *optional = true;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Variant of item for targets where symbol may be missing
+fn weak_item(rest: TokenStream) -> Option<TokenStream> {
+    if let Ok(mut f) = syn::parse2::<ItemFn>(rest.clone()) {
+        try_replace_return(&mut f);
+        let mut optional = false;
+        if try_replace_fn(&mut f.block, &mut optional) {
+            if optional {
+                wrap_option(&mut f.sig.output);
+            }
+        } else if f.sig.unsafety.is_none() {
+            f.sig.unsafety = Some(Default::default());
+        }
+        return Some(f.into_token_stream());
+    }
+    let mut mac = syn::parse2::<ItemMacro>(rest).ok()?;
+    let last = &mut mac.mac.path.segments.last_mut()?.ident;
+    let weak = match last.to_string().as_str() {
+        "define_cls" => "define_weak_cls",
+        "define_cls_init" => "define_weak_cls_init",
+        _ => return None,
+    };
+    *last = Ident::new(weak, last.span());
+    Some(mac.into_token_stream())
+}
+
+pub fn available(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let versions: Versions = syn::parse2(args)?;
+    let Item { attrs, rest } = syn::parse2(item)?;
+
+    let available = versions.available_cfg();
+    let available_doc = versions.available_doc();
+    let mut res = quote! {
+        #available
+        #(#attrs)*
+        #available_doc
+        #rest
+    };
+
+    if let Some(rest) = weak_item(rest) {
+        let unavailable = versions.unavailable_cfg();
+        let unavailable_doc = versions.unavailable_doc();
+        res.extend(quote! {
+            #unavailable
+            #(#attrs)*
+            #unavailable_doc
+            #rest
+        });
+    }
+
+    Ok(res)
+}
+
+/// `extern "C" fn(args) -> R` type of foreign function
+fn fn_ptr_type(sig: &Signature) -> TokenStream {
+    let inputs = &sig.inputs;
+    let output = &sig.output;
+    let variadic = sig.variadic.as_ref().map(|v| {
+        let dots = &v.dots;
+        if inputs.empty_or_trailing() {
+            quote!(#dots)
+        } else {
+            quote!(, #dots)
+        }
+    });
+    quote!(extern "C" fn(#inputs #variadic) #output)
+}
+
+pub fn weak(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    attr::no_args(args, "api::weak")?;
+    let block: ItemForeignMod = syn::parse2(item.clone())?;
+
+    let mut syms = Vec::new();
+    for foreign in &block.items {
+        let attrs = match foreign {
+            ForeignItem::Static(s) => &s.attrs,
+            ForeignItem::Fn(f) => &f.attrs,
+            _ => continue,
+        };
+        let Some(a) = attrs.iter().rfind(|a| Attr::is_available(a)) else {
+            continue;
+        };
+        let versions: Versions = attr::args(a)?;
+        let (name, ty) = match foreign {
+            ForeignItem::Static(s) => match &*s.ty {
+                Type::Reference(r) if matches!(&r.lifetime, Some(l) if l.ident == "static") => {
+                    (&s.ident, r.elem.to_token_stream())
+                }
+                ty => {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "weakly linked statics should be `&'static` references",
+                    ));
+                }
+            },
+            ForeignItem::Fn(f) => (&f.sig.ident, fn_ptr_type(&f.sig)),
+            _ => unreachable!(),
+        };
+        let unavailable = versions.unavailable_cfg();
+        let var = upper_ident(name);
+        let sym = c_str(&name.to_string());
+        syms.push(quote! {
+            #unavailable
+            static #var: api::DlSym<#ty> = api::DlSym::new(#sym);
+        });
+    }
+
+    Ok(quote! {
+        #item
+        #(#syms)*
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upper() {
+        assert_eq!(
+            upper_case("kCVPixelBufferWidthKey"),
+            "K_CVPIXEL_BUFFER_WIDTH_KEY"
+        );
+        assert_eq!(
+            upper_case("SCStreamFrameInfoStatus"),
+            "SCSTREAM_FRAME_INFO_STATUS"
+        );
+        assert_eq!(upper_case("kCMTimeZero"), "K_CMTIME_ZERO");
+    }
+}
@@ -0,0 +1,180 @@
+use proc_macro2::{Span, TokenStream, TokenTree};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Attribute, Expr, Lit, LitStr, Meta,
+};
+
+use crate::versions::Versions;
+
+/// Objective-C selector like `new`, `objectForKey:` or `setObject:forKey:`
+pub struct Selector {
+    pub name: String,
+    pub args: usize,
+    tokens: TokenStream,
+}
+
+impl Selector {
+    /// Error spanning the whole selector
+    pub fn error(&self, msg: impl std::fmt::Display) -> syn::Error {
+        syn::Error::new_spanned(&self.tokens, msg)
+    }
+}
+
+impl Parse for Selector {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected selector, like `objectForKey:`",
+            ));
+        }
+        let tokens: TokenStream = input.parse()?;
+        let mut name = String::new();
+        let mut args = 0;
+        let mut after_ident = false;
+        for tt in tokens.clone() {
+            match tt {
+                TokenTree::Ident(ident) if !after_ident => {
+                    name.push_str(&ident.to_string());
+                    after_ident = true;
+                }
+                TokenTree::Ident(ident) => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        "expected `:` between selector parts",
+                    ));
+                }
+                TokenTree::Punct(p) if p.as_char() == ':' => {
+                    name.push(':');
+                    args += 1;
+                    after_ident = false;
+                }
+                tt => {
+                    return Err(syn::Error::new(
+                        tt.span(),
+                        format!("unexpected `{tt}` in selector"),
+                    ));
+                }
+            }
+        }
+        let sel = Self { name, args, tokens };
+        if args > 0 && after_ident {
+            return Err(sel.error(format_args!(
+                "selector `{}` with arguments must end with `:`",
+                sel.name
+            )));
+        }
+        Ok(sel)
+    }
+}
+
+/// Attributes of this crate found on functions
+pub enum Attr {
+    /// `#[objc::optional]`
+    Optional,
+
+    /// `#[objc::msg_send(sel)]`
+    MsgSend(Selector),
+
+    /// `#[api::available(...)]`
+    Available(Versions),
+
+    /// `/// # Availability` doc section generated by `api::available`.
+    /// Versions are in the next doc attribute.
+    DocAvailable,
+}
+
+impl Attr {
+    pub fn classify(attr: &Attribute) -> syn::Result<Option<Self>> {
+        if attr.path().is_ident("doc") {
+            let is_section = doc_str(attr).as_deref() == Some(" # Availability");
+            return Ok(is_section.then_some(Self::DocAvailable));
+        }
+        let segments = &attr.path().segments;
+        if segments.len() != 2 {
+            return Ok(None);
+        }
+        let ns = &segments[0].ident;
+        if ns != "objc" && ns != "api" {
+            return Ok(None);
+        }
+        let name = segments[1].ident.to_string();
+        Ok(Some(match name.as_str() {
+            "optional" => Self::Optional,
+            "msg_send" | "msg_send_debug" | "msg_send_x86_64" => Self::MsgSend(args(attr)?),
+            "available" => Self::Available(args(attr)?),
+            _ => return Ok(None),
+        }))
+    }
+
+    pub fn is_available(attr: &Attribute) -> bool {
+        let path = attr.path();
+        match path.segments.len() {
+            1 => path.is_ident("available"),
+            2 => {
+                let ns = &path.segments[0].ident;
+                (ns == "objc" || ns == "api") && path.segments[1].ident == "available"
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Parses arguments of `#[path(args)]`, spanning missing arguments to attribute
+pub fn args<T: Parse>(attr: &Attribute) -> syn::Result<T> {
+    match &attr.meta {
+        Meta::List(list) => list.parse_args().map_err(|e| {
+            if list.tokens.is_empty() {
+                syn::Error::new(attr.span(), e.to_string())
+            } else {
+                e
+            }
+        }),
+        meta => {
+            syn::parse2(TokenStream::new()).map_err(|e| syn::Error::new(meta.span(), e.to_string()))
+        }
+    }
+}
+
+pub fn doc_str(attr: &Attribute) -> Option<String> {
+    let Meta::NameValue(nv) = &attr.meta else {
+        return None;
+    };
+    let Expr::Lit(lit) = &nv.value else {
+        return None;
+    };
+    let Lit::Str(s) = &lit.lit else {
+        return None;
+    };
+    Some(s.value())
+}
+
+/// Value of `#[doc(alias = "...")]`
+pub fn doc_alias(attr: &Attribute) -> Option<String> {
+    if !attr.path().is_ident("doc") {
+        return None;
+    }
+    let mut res = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("alias") {
+            let s: LitStr = meta.value()?.parse()?;
+            res = Some(s.value());
+        }
+        Ok(())
+    })
+    .ok()?;
+    res
+}
+
+/// Errors if arguments of attribute without parameters are not empty
+pub fn no_args(args: TokenStream, attr: &str) -> syn::Result<()> {
+    if args.is_empty() {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            args,
+            format!("`#[{attr}]` takes no arguments"),
+        ))
+    }
+}
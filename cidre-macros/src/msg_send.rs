@@ -0,0 +1,421 @@
+use std::ffi::CString;
+
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    token,
+    visit_mut::{self, VisitMut},
+    Attribute, Block, FnArg, Meta, Pat, PathArguments, ReturnType, Signature, Token, Type,
+    Visibility,
+};
+
+use crate::{
+    attr::{self, Attr, Selector},
+    versions::Versions,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arch {
+    /// `objc_msgSend$sel` stubs, selector is baked in
+    Arm64,
+    /// `objc_msgSend` with selector registered at runtime
+    X86_64,
+}
+
+/// Function with optional body: `pub fn name(&self, arg: T) -> R;`
+pub struct Method {
+    pub attrs: Vec<Attribute>,
+    pub vis: Visibility,
+    pub sig: Signature,
+    pub body: Option<Block>,
+}
+
+impl Parse for Method {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        let sig = input.parse()?;
+        let body = if input.peek(token::Brace) {
+            Some(input.parse()?)
+        } else {
+            input.parse::<Token![;]>()?;
+            None
+        };
+        Ok(Self {
+            attrs,
+            vis,
+            sig,
+            body,
+        })
+    }
+}
+
+/// `c"..."` literal
+pub fn c_str(str: &str) -> Literal {
+    Literal::c_string(&CString::new(str).unwrap())
+}
+
+/// `fn sel_<name>() -> &'static objc::Sel`
+pub fn sel_fn(fn_name: &Ident, sel: &str) -> TokenStream {
+    let sel_name = format_ident!("sel_{}", fn_name);
+    let sel = c_str(sel);
+    quote! {
+        fn #sel_name() -> &'static objc::Sel {
+            unsafe { objc::sel_reg_name(#sel.as_ptr()) }
+        }
+    }
+}
+
+fn sel_fn_doc(sel: &str) -> TokenStream {
+    let doc = format!(" `@selector({sel})` but dynamic");
+    quote! {
+        #[doc = #doc]
+        #[doc = " use this function to check if object responds to selector"]
+    }
+}
+
+fn count(n: usize) -> String {
+    match n {
+        1 => "1 argument".to_string(),
+        n => format!("{n} arguments"),
+    }
+}
+
+/// Replaces first `arc::R<T>` with `arc::Rar<T>`
+struct RarOutput(bool);
+
+impl VisitMut for RarOutput {
+    fn visit_path_mut(&mut self, path: &mut syn::Path) {
+        if self.0 {
+            return;
+        }
+        let n = path.segments.len();
+        if n >= 2
+            && path.segments[n - 2].ident == "arc"
+            && path.segments[n - 1].ident == "R"
+            && matches!(
+                path.segments[n - 1].arguments,
+                PathArguments::AngleBracketed(_)
+            )
+        {
+            let last = &mut path.segments[n - 1].ident;
+            *last = Ident::new("Rar", last.span());
+            self.0 = true;
+            return;
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+}
+
+fn is_option(output: &ReturnType) -> bool {
+    let ReturnType::Type(_, ty) = output else {
+        return false;
+    };
+    let Type::Path(path) = &**ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path.path.leading_colon.is_none()
+        && path.path.segments.len() == 1
+        && path.path.segments[0].ident == "Option"
+}
+
+pub fn msg_send(
+    args: TokenStream,
+    item: TokenStream,
+    arch: Arch,
+    debug: bool,
+) -> syn::Result<TokenStream> {
+    let sel: Selector = syn::parse2(args)?;
+    let Method {
+        attrs: all_attrs,
+        vis,
+        sig,
+        body,
+    } = syn::parse2(item)?;
+
+    if let Some(body) = body {
+        return Err(syn::Error::new_spanned(
+            body,
+            "expected `;`, body is generated by `#[objc::msg_send]`",
+        ));
+    }
+
+    if sig.constness.is_some()
+        || sig.asyncness.is_some()
+        || sig.abi.is_some()
+        || sig.variadic.is_some()
+    {
+        return Err(syn::Error::new_spanned(
+            &sig,
+            "`#[objc::msg_send]` functions can't be `const`, `async`, `extern` or variadic",
+        ));
+    }
+
+    let mut versions = Versions::default();
+    let mut optional = false;
+    let mut attrs = Vec::with_capacity(all_attrs.len());
+    let mut iter = all_attrs.into_iter();
+    while let Some(a) = iter.next() {
+        match Attr::classify(&a)? {
+            Some(Attr::Available(v)) => {
+                versions = v;
+                continue;
+            }
+            Some(Attr::DocAvailable) => {
+                if let Some(next) = iter.next() {
+                    if let Some(doc) = attr::doc_str(&next) {
+                        versions = Versions::from_doc_str(&doc);
+                    }
+                    attrs.push(a);
+                    attrs.push(next);
+                    continue;
+                }
+            }
+            Some(Attr::MsgSend(_)) => {
+                return Err(syn::Error::new_spanned(
+                    a,
+                    "only one `#[objc::msg_send]` is allowed",
+                ));
+            }
+            Some(Attr::Optional) => optional = true,
+            None => {
+                // `#[inline]` and selector alias are generated
+                if matches!(&a.meta, Meta::Path(p) if p.is_ident("inline")) {
+                    continue;
+                }
+                if sig.ident != sel.name && attr::doc_alias(&a).as_deref() == Some(&sel.name) {
+                    continue;
+                }
+            }
+        }
+        attrs.push(a);
+    }
+
+    let mut inputs = sig.inputs.iter().peekable();
+    let receiver = match inputs.peek() {
+        Some(FnArg::Receiver(r)) => {
+            inputs.next();
+            Some(r)
+        }
+        _ => None,
+    };
+    let mut vars = Vec::new();
+    let mut tys = Vec::new();
+    for arg in inputs {
+        match arg {
+            FnArg::Typed(arg) => match &*arg.pat {
+                Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                    vars.push(&pat.ident);
+                    tys.push(&*arg.ty);
+                }
+                pat => return Err(syn::Error::new_spanned(pat, "expected argument name")),
+            },
+            FnArg::Receiver(r) => {
+                return Err(syn::Error::new_spanned(
+                    r,
+                    "`self` should be the first argument",
+                ));
+            }
+        }
+    }
+
+    let fn_name = &sig.ident;
+    if vars.len() != sel.args {
+        return Err(sel.error(format_args!(
+            "selector `{}` takes {}, but `{fn_name}` has {}",
+            sel.name,
+            count(sel.args),
+            count(vars.len())
+        )));
+    }
+
+    let mut impl_output = sig.output.clone();
+    let rar = !sel.name.starts_with("new") && !sel.name.starts_with("initWith") && {
+        let mut v = RarOutput(false);
+        v.visit_return_type_mut(&mut impl_output);
+        v.0
+    };
+    if !rar {
+        impl_output = sig.output.clone();
+    }
+    let impl_name = if rar {
+        format_ident!("{}_ar", fn_name)
+    } else {
+        fn_name.clone()
+    };
+
+    let c_void = quote!(*const std::ffi::c_void);
+    let mut params = Vec::with_capacity(tys.len() + 2);
+    let mut call_args = Vec::with_capacity(vars.len() + 2);
+    match receiver {
+        Some(r) => {
+            params.push(r.ty.to_token_stream());
+            call_args.push(quote!(self));
+        }
+        None => {
+            params.push(c_void.clone());
+            call_args.push(quote!(Self::cls_ptr()));
+        }
+    }
+    match arch {
+        Arch::X86_64 => {
+            params.push(c_void.clone());
+            call_args.push(quote!(x86_64_sel));
+        }
+        Arch::Arm64 if !vars.is_empty() => {
+            params.push(c_void.clone());
+            call_args.push(quote!(std::ptr::null()));
+        }
+        Arch::Arm64 => {}
+    }
+    params.extend(tys.iter().map(ToTokens::to_token_stream));
+    call_args.extend(vars.iter().map(ToTokens::to_token_stream));
+
+    let sel_c_str = c_str(&sel.name);
+    let stmts = {
+        let sel_stmt = match arch {
+            Arch::X86_64 => Some(quote!(let x86_64_sel = sel_registerName(#sel_c_str.as_ptr());)),
+            Arch::Arm64 => None,
+        };
+        quote! {
+            #sel_stmt
+            let fn_ptr = msg_send as *const std::ffi::c_void;
+            let sig: extern "C" fn(#(#params),*) #impl_output = std::mem::transmute(fn_ptr);
+            sig(#(#call_args),*)
+        }
+    };
+    let (body, unsafe_body) = match arch {
+        Arch::Arm64 => {
+            let link_name = format!("objc_msgSend${}", sel.name);
+            let imp = quote! {
+                extern "C" {
+                    #[link_name = #link_name]
+                    fn msg_send();
+                }
+            };
+            (quote!({ #imp unsafe { #stmts } }), quote!({ #imp #stmts }))
+        }
+        Arch::X86_64 => {
+            let body = quote!({
+                extern "C" {
+                    #[link_name = "objc_msgSend"]
+                    fn msg_send();
+                }
+                extern "C-unwind" {
+                    fn sel_registerName(name: *const i8) -> *const std::ffi::c_void;
+                }
+
+                unsafe { #stmts }
+            });
+            (body.clone(), body)
+        }
+    };
+
+    let available = versions.available_cfg();
+    let unavailable = versions.unavailable_cfg();
+    let doc_alias = if fn_name != &sel.name {
+        let sel = &sel.name;
+        quote!(#[doc(alias = #sel)])
+    } else {
+        TokenStream::new()
+    };
+    let unsafety = &sig.unsafety;
+    let unsafe_token = <Token![unsafe]>::default();
+    let generics = &sig.generics;
+    let where_clause = &sig.generics.where_clause;
+    let fn_inputs = &sig.inputs;
+    let output = &sig.output;
+
+    let mut res = quote! {
+        #available
+        #doc_alias
+        #[inline]
+        #(#attrs)*
+        #vis #unsafety fn #impl_name #generics(#fn_inputs) #impl_output #where_clause #body
+    };
+
+    if versions.any() {
+        if !optional {
+            let doc = sel_fn_doc(&sel.name);
+            let sel_fn = sel_fn(fn_name, &sel.name);
+            res.extend(quote! {
+                #doc
+                #[inline]
+                pub #sel_fn
+            });
+        }
+        res.extend(quote! {
+            #unavailable
+            #doc_alias
+            #[inline]
+            #(#attrs)*
+            #vis #unsafe_token fn #impl_name #generics(#fn_inputs) #impl_output #where_clause #unsafe_body
+        });
+    }
+
+    if rar {
+        let retain = if is_option(output) {
+            quote!(arc::rar_retain_option)
+        } else {
+            quote!(arc::rar_retain)
+        };
+        let call = match receiver {
+            Some(_) => quote!(#retain(self.#impl_name(#(#vars),*))),
+            None => quote!(#retain(Self::#impl_name(#(#vars),*))),
+        };
+        res.extend(quote! {
+            #available
+            #doc_alias
+            #[inline]
+            #(#attrs)*
+            #vis #unsafety fn #fn_name #generics(#fn_inputs) #output #where_clause {
+                #call
+            }
+        });
+        if versions.any() {
+            let check = format!(" Check availability with selector `Self::sel_{fn_name}()`");
+            res.extend(quote! {
+                #unavailable
+                #doc_alias
+                #[inline]
+                #[doc = #check]
+                #(#attrs)*
+                #vis #unsafe_token fn #fn_name #generics(#fn_inputs) #output #where_clause {
+                    #call
+                }
+            });
+        }
+    }
+
+    if debug {
+        println!("{res}");
+    }
+
+    Ok(res)
+}
+
+pub fn optional(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    attr::no_args(args, "objc::optional")?;
+    let method: Method = syn::parse2(item.clone())?;
+    let mut sel = None;
+    for a in &method.attrs {
+        if let Some(Attr::MsgSend(s)) = Attr::classify(a)? {
+            sel = Some(s);
+        }
+    }
+    let Some(sel) = sel else {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`#[objc::optional]` expects function with `#[objc::msg_send(...)]`",
+        ));
+    };
+    let doc = sel_fn_doc(&sel.name);
+    let sel_fn = sel_fn(&method.sig.ident, &sel.name);
+    Ok(quote! {
+        #item
+        #doc
+        #sel_fn
+    })
+}
@@ -0,0 +1,169 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_quote, AttrStyle, FnArg, ImplItem, ItemImpl, ItemTrait, TraitItem};
+
+use crate::{
+    attr::{self, Attr},
+    msg_send::{c_str, sel_fn},
+};
+
+/// `cls_add_methods` which registers `impl_<name>` functions for `sel_<name>()` selectors
+fn add_methods_fn(names: &[Ident]) -> TokenStream {
+    let sels = names.iter().map(|n| format_ident!("sel_{}", n));
+    let impls = names.iter().map(|n| format_ident!("impl_{}", n));
+    quote! {
+        fn cls_add_methods<O: objc::Obj>(cls: &objc::Class<O>) {
+            let cls: &objc::Class<objc::Id> = unsafe { std::mem::transmute(cls) };
+            #(
+                let sel = Self::#sels();
+                unsafe {
+                    let imp: extern "C" fn() = std::mem::transmute(Self::#impls as *const u8);
+                    objc::class_addMethod(cls, sel, imp, std::ptr::null());
+                }
+            )*
+        }
+    }
+}
+
+pub fn protocol(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let proto: Ident = syn::parse2(args).map_err(|e| {
+        syn::Error::new(
+            e.span(),
+            "expected protocol name, like `#[objc::protocol(NSCopying)]`",
+        )
+    })?;
+    let tr: ItemTrait = syn::parse2(item.clone())?;
+
+    let mut fns = Vec::with_capacity(tr.items.len());
+    let mut names = Vec::new();
+    let mut has_optionals = false;
+    for item in &tr.items {
+        let TraitItem::Fn(f) = item else {
+            return Err(syn::Error::new_spanned(
+                item,
+                "`#[objc::protocol]` traits may contain only functions",
+            ));
+        };
+        let mut optional = None;
+        let mut sel = None;
+        for a in &f.attrs {
+            match Attr::classify(a)? {
+                Some(Attr::Optional) => optional = Some(a),
+                Some(Attr::MsgSend(s)) => sel = Some(s),
+                _ => {}
+            }
+        }
+        has_optionals |= optional.is_some();
+
+        let name = &f.sig.ident;
+        let generics = &f.sig.generics;
+        let where_clause = &f.sig.generics.where_clause;
+        let output = &f.sig.output;
+        // copies of default bodies are linted in the original trait already,
+        // stubs don't use their arguments
+        let (lints, body) = match &f.default {
+            Some(body) => (
+                quote!(#[allow(clippy::not_unsafe_ptr_arg_deref)]),
+                body.to_token_stream(),
+            ),
+            None if optional.is_some() => (
+                quote!(#[allow(unused_variables)]),
+                quote!({ unimplemented!() }),
+            ),
+            None => (TokenStream::new(), quote!(;)),
+        };
+
+        let Some(sel) = sel else {
+            if let Some(optional) = optional {
+                return Err(syn::Error::new_spanned(
+                    optional,
+                    "`#[objc::optional]` expects function with `#[objc::msg_send(...)]`",
+                ));
+            }
+            let inputs = &f.sig.inputs;
+            fns.push(quote!(#lints fn #name #generics(#inputs) #output #where_clause #body));
+            continue;
+        };
+
+        let mut inputs = f.sig.inputs.clone();
+        if let Some(FnArg::Receiver(r)) = inputs.first() {
+            if r.colon_token.is_none() && matches!(r.reference, Some((_, None))) {
+                inputs.insert(1, parse_quote!(_cmd: Option<&objc::Sel>));
+            }
+        }
+        let impl_name = format_ident!("impl_{}", name);
+        fns.push(quote!(
+            #lints
+            extern "C" fn #impl_name #generics(#inputs) #output #where_clause #body
+        ));
+        if optional.is_none() {
+            fns.push(sel_fn(name, &sel.name));
+        }
+        names.push(name.clone());
+    }
+
+    let add_methods = if has_optionals {
+        quote!(
+            fn cls_add_methods<O: objc::Obj>(cls: &objc::Class<O>);
+        )
+    } else {
+        add_methods_fn(&names)
+    };
+
+    let name = &tr.ident;
+    let impl_name = format_ident!("{}Impl", name);
+    let alias = if name != &proto {
+        let proto = proto.to_string();
+        quote!(#[doc(alias = #proto)])
+    } else {
+        TokenStream::new()
+    };
+    let attrs = tr
+        .attrs
+        .iter()
+        .filter(|a| matches!(a.style, AttrStyle::Outer));
+    let vis = &tr.vis;
+    let unsafety = &tr.unsafety;
+    let auto_token = &tr.auto_token;
+    let proto = c_str(&proto.to_string());
+
+    Ok(quote! {
+        #item
+
+        #alias
+        #(#attrs)*
+        #vis #unsafety #auto_token trait #impl_name: #name {
+            #(#fns)*
+
+            #add_methods
+
+            fn cls_add_protocol<O: objc::Obj>(cls: &objc::Class<O>) {
+                unsafe {
+                    let cls: &objc::Class<objc::Id> = std::mem::transmute(cls);
+                    if let Some(proto) = objc::objc_getProtocol(#proto.as_ptr()) {
+                        cls.add_protocol(proto);
+                    }
+                }
+            }
+        }
+    })
+}
+
+pub fn add_methods(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    attr::no_args(args, "objc::add_methods")?;
+    let mut imp: ItemImpl = syn::parse2(item)?;
+    let names: Vec<_> = imp
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(f) => {
+                let name = f.sig.ident.to_string();
+                let name = name.strip_prefix("impl_")?;
+                Some(Ident::new(name, f.sig.ident.span()))
+            }
+            _ => None,
+        })
+        .collect();
+    imp.items.push(ImplItem::Verbatim(add_methods_fn(&names)));
+    Ok(imp.into_token_stream())
+}
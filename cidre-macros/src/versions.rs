@@ -0,0 +1,256 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, Lit, Token,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    MacOs,
+    Ios,
+    TvOs,
+    WatchOs,
+    VisionOs,
+    MacCatalyst,
+}
+
+impl Platform {
+    pub const ALL: [Self; 6] = [
+        Self::MacOs,
+        Self::Ios,
+        Self::TvOs,
+        Self::WatchOs,
+        Self::VisionOs,
+        Self::MacCatalyst,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::MacOs => "macos",
+            Self::Ios => "ios",
+            Self::TvOs => "tvos",
+            Self::WatchOs => "watchos",
+            Self::VisionOs => "visionos",
+            Self::MacCatalyst => "maccatalyst",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    /// `target_os = "..."` predicates of platform
+    fn target(self) -> TokenStream {
+        match self {
+            Self::MacCatalyst => quote!(target_os = "ios", target_abi = "macabi"),
+            _ => {
+                let os = self.name();
+                quote!(target_os = #os)
+            }
+        }
+    }
+}
+
+/// `major.minor` deployment target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl Version {
+    /// Parses `15_0` and `15.0`
+    fn from_str(str: &str) -> Option<Self> {
+        let (major, minor) = str.split_once(['.', '_'])?;
+        Some(Self {
+            major: major.parse().ok()?,
+            minor: minor.parse().ok()?,
+        })
+    }
+}
+
+impl Parse for Version {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        const MSG: &str = "expected `major.minor` or `major` version, like `15.0` or `26`";
+        let lit: Lit = input.parse().map_err(|e| syn::Error::new(e.span(), MSG))?;
+        let res = match &lit {
+            Lit::Float(f) if f.suffix().is_empty() => Self::from_str(f.base10_digits()),
+            // bare major, `26` is `26.0`
+            Lit::Int(i) if i.suffix().is_empty() => {
+                i.base10_parse().ok().map(|major| Self { major, minor: 0 })
+            }
+            _ => None,
+        };
+        res.ok_or_else(|| syn::Error::new(lit.span(), MSG))
+    }
+}
+
+/// Minimal deployment targets per platform, like `macos = 15.0, ios = 18.0`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Versions([Option<Version>; 6]);
+
+impl Parse for Versions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut res = Self::default();
+        if input.is_empty() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "expected platform versions, like `macos = 15.0, ios = 18.0`",
+            ));
+        }
+        while !input.is_empty() {
+            let ident: Ident = input.parse()?;
+            let Some(platform) = Platform::from_name(&ident.to_string()) else {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!(
+                        "unknown platform `{ident}`, expected one of \
+                         `macos`, `ios`, `tvos`, `watchos`, `visionos` or `maccatalyst`"
+                    ),
+                ));
+            };
+            input.parse::<Token![=]>()?;
+            let version: Version = input.parse()?;
+            let slot = &mut res.0[platform as usize];
+            if slot.is_some() {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("duplicate `{ident}` version"),
+                ));
+            }
+            *slot = Some(version);
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(res)
+    }
+}
+
+impl Versions {
+    /// Parses `macos_15_0, ios_18_0` list from `# Availability` doc section
+    pub fn from_doc_str(str: &str) -> Self {
+        let mut res = Self::default();
+        for item in str.split([' ', ',']) {
+            for platform in Platform::ALL {
+                if let Some(v) = item
+                    .strip_prefix(platform.name())
+                    .and_then(|v| v.strip_prefix('_'))
+                {
+                    res.0[platform as usize] = Version::from_str(v);
+                }
+            }
+        }
+        res
+    }
+
+    pub fn any(&self) -> bool {
+        self.0.iter().any(Option::is_some)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (Platform, Version)> + '_ {
+        Platform::ALL
+            .into_iter()
+            .filter_map(|p| self.0[p as usize].map(|v| (p, v)))
+    }
+
+    fn features(&self) -> Vec<String> {
+        self.iter()
+            .map(|(p, v)| format!("{}_{}_{}", p.name(), v.major, v.minor))
+            .collect()
+    }
+
+    /// `#[cfg(...)]` which is true when deployment target is at least one of versions
+    pub fn available_cfg(&self) -> TokenStream {
+        self.cfg(true)
+    }
+
+    /// `#[cfg(...)]` which is true when deployment target is below one of versions
+    pub fn unavailable_cfg(&self) -> TokenStream {
+        self.cfg(false)
+    }
+
+    fn cfg(&self, available: bool) -> TokenStream {
+        let preds: Vec<_> = self
+            .iter()
+            .zip(self.features())
+            .map(|((p, _), feature)| {
+                let target = p.target();
+                if available {
+                    quote!(all(#target, feature = #feature))
+                } else {
+                    quote!(all(#target, not(feature = #feature)))
+                }
+            })
+            .collect();
+        match preds.as_slice() {
+            [] => TokenStream::new(),
+            [pred] => quote!(#[cfg(#pred)]),
+            preds => quote!(#[cfg(any(#(#preds),*))]),
+        }
+    }
+
+    /// `# Availability` doc section
+    pub fn available_doc(&self) -> TokenStream {
+        self.doc("")
+    }
+
+    pub fn unavailable_doc(&self) -> TokenStream {
+        self.doc("Not ")
+    }
+
+    fn doc(&self, prefix: &str) -> TokenStream {
+        if !self.any() {
+            return TokenStream::new();
+        }
+        let list = format!(" {prefix}{}", self.features().join(", "));
+        quote! {
+            #[doc = " # Availability"]
+            #[doc = #list]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let v: Versions = syn::parse_quote!(macos = 15.0, ios = 18.2, maccatalyst = 18.0,);
+        assert!(v.any());
+        assert_eq!(v.features(), ["macos_15_0", "ios_18_2", "maccatalyst_18_0"]);
+        assert_eq!(
+            v.unavailable_cfg().to_string(),
+            quote! {
+                #[cfg(any(
+                    all(target_os = "macos", not(feature = "macos_15_0")),
+                    all(target_os = "ios", not(feature = "ios_18_2")),
+                    all(target_os = "ios", target_abi = "macabi", not(feature = "maccatalyst_18_0"))
+                ))]
+            }
+            .to_string()
+        );
+
+        let doc = Versions::from_doc_str("visionos_2_0, macos_26_0");
+        let v: Versions = syn::parse_quote!(visionos = 2.0, macos = 26.0);
+        assert_eq!(doc, v);
+        let v: Versions = syn::parse_quote!(visionos = 2, macos = 26);
+        assert_eq!(doc, v);
+
+        for bad in [
+            "",
+            "macos",
+            "macos = 15u32",
+            "macos = 15.0f32",
+            "macos = \"15.0\"",
+            "mac = 15.0",
+            "macos = 15.0 ios = 18.0",
+        ] {
+            assert!(syn::parse_str::<Versions>(bad).is_err(), "{bad}");
+        }
+        assert!(syn::parse_str::<Versions>("macos = 15.0, macos = 14.0").is_err());
+    }
+}
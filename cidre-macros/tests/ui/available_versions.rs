@@ -0,0 +1,24 @@
+#[cidre_macros::api_available]
+pub fn empty() {}
+
+#[cidre_macros::api_available(linux = 6.0)]
+pub fn unknown_platform() {}
+
+#[cidre_macros::api_available(macos = 15u32)]
+pub fn suffixed() {}
+
+#[cidre_macros::api_available(macos = 15.0, ios = 18.0, macos = 14.0)]
+pub fn duplicate() {}
+
+#[cidre_macros::api_available(macos = 15.0 ios = 18.0)]
+pub fn missing_comma() {}
+
+struct Foo;
+
+impl Foo {
+    #[cidre_macros::msg_send(count)]
+    #[cidre_macros::api_available(macos = "15.0")]
+    fn count(&self) -> usize;
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+struct Foo;
+
+impl Foo {
+    #[cidre_macros::msg_send(setObject:forKey:)]
+    fn set_obj(&self, obj: usize);
+
+    #[cidre_macros::msg_send(count)]
+    fn count(&self, extra: usize) -> usize;
+
+    #[cidre_macros::msg_send(setCount:)]
+    fn set_count(&self, (a, b): (usize, usize));
+
+    #[cidre_macros::msg_send(count)]
+    fn with_body(&self) -> usize {
+        0
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+struct Foo;
+
+impl Foo {
+    #[cidre_macros::msg_send]
+    fn missing(&self);
+
+    #[cidre_macros::msg_send(object forKey:)]
+    fn no_colon(&self, key: usize);
+
+    #[cidre_macros::msg_send(drawPrimitives:baseInstance:usize)]
+    fn trailing(&self, a: usize, b: usize);
+
+    #[cidre_macros::msg_send("count")]
+    fn literal(&self) -> usize;
+}
+
+fn main() {}
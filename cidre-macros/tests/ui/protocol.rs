@@ -0,0 +1,13 @@
+#[cidre_macros::protocol]
+trait Copying {
+    #[cidre_macros::msg_send(copy)]
+    fn copy(&self) -> usize;
+}
+
+#[cidre_macros::protocol(NSCoding)]
+trait Coding {
+    #[cidre_macros::optional]
+    fn encode(&self, coder: usize);
+}
+
+fn main() {}
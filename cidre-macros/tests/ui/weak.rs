@@ -0,0 +1,7 @@
+#[cidre_macros::api_weak]
+unsafe extern "C" {
+    #[api::available(macos = 15.0)]
+    static kFooKey: usize;
+}
+
+fn main() {}
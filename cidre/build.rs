@@ -230,6 +230,11 @@ fn parse_deployment_targets() -> DeploymentTargets {
 }
 
 fn main() {
+    // pomace and the clang runtime only exist for Apple targets
+    if env::var("CARGO_CFG_TARGET_VENDOR").as_deref() != Ok("apple") {
+        return;
+    }
+
     let versions = parse_deployment_targets();
 
     let sdk = match env::var("TARGET").unwrap().as_ref() {
@@ -1,10 +1,10 @@
 #[cfg(target_os = "macos")]
 mod macos {
-    use std::{fs, io::Write, path::PathBuf};
+    use std::{fs, path::PathBuf};
 
     use cidre::{
-        arc, av, av::AssetWriterDelegate, cm, define_obj_type, dispatch, ns, objc, objc::Obj, sc,
-        sc::StreamOutput, ut,
+        arc, av, av::AssetWriterDelegate, cm, define_obj_type, dispatch, hls, ns, objc, objc::Obj,
+        sc, sc::StreamOutput, ut,
     };
 
     #[repr(C)]
@@ -53,12 +53,16 @@ mod macos {
         n: u32,
         dir: PathBuf,
         base_name: String,
-        target_dur: u32,
+        playlist: hls::MediaPlaylist,
     }
 
     impl SegmentWriter {
         fn init_path(&self) -> PathBuf {
-            self.dir.join(format!("{}.mp4", self.base_name))
+            self.dir.join(self.init_name())
+        }
+
+        fn init_name(&self) -> String {
+            format!("{}.mp4", self.base_name)
         }
 
         fn playlist_path(&self) -> PathBuf {
@@ -73,41 +77,27 @@ mod macos {
             format!("{}{}.m4s", self.base_name, self.n)
         }
 
+        fn write_playlist(&self) {
+            fs::write(self.playlist_path(), self.playlist.to_string()).unwrap();
+        }
+
         fn write_init(&mut self, data: &[u8]) {
             fs::write(self.init_path(), data).unwrap();
-
-            let target_dur = self.target_dur;
-            let base_name = &self.base_name;
-            fs::write(
-                self.playlist_path(),
-                format!(
-                    "#EXTM3U
-#EXT-X-VERSION:7
-#EXT-X-TARGETDURATION:{target_dur}
-#EXT-X-MEDIA-SEQUENCE:0
-#EXT-X-MAP:URI=\"{base_name}.mp4\"
-"
-                ),
-            )
-            .unwrap();
+            self.playlist.set_map(Some(hls::Map::new(self.init_name())));
+            self.write_playlist();
         }
 
-        fn write_segment(&mut self, data: &[u8], duration: f64) {
+        fn write_segment(&mut self, data: &[u8], report: &av::AssetSegmentReport) {
             fs::write(self.segment_path(), data).unwrap();
-            let mut file = fs::File::options()
-                .append(true)
-                .open(self.playlist_path())
-                .unwrap();
-            let segment_name = self.segment_name();
-            writeln!(file, "\n#EXTINF:{duration},\n{segment_name}").unwrap();
+            let segment = hls::Segment::with_report(self.segment_name(), report);
+            eprintln!("[{}]{}", self.n, segment.duration);
+            self.playlist.push(segment);
+            self.write_playlist();
         }
 
         fn write_end(&mut self) {
-            let mut file = fs::File::options()
-                .append(true)
-                .open(self.playlist_path())
-                .unwrap();
-            writeln!(file, "\n#EXT-X-ENDLIST").unwrap();
+            self.playlist.finish();
+            self.write_playlist();
         }
     }
 
@@ -135,15 +125,7 @@ mod macos {
                     ctx.write_init(segment_data.as_slice());
                 }
                 av::AssetSegmentType::Separable => {
-                    let duration = segment_report
-                        .unwrap()
-                        .track_reports()
-                        .get(0)
-                        .unwrap()
-                        .duration()
-                        .as_secs();
-                    ctx.write_segment(segment_data.as_slice(), duration);
-                    eprintln!("[{}]{duration}", ctx.n);
+                    ctx.write_segment(segment_data.as_slice(), segment_report.unwrap());
                     ctx.n += 1;
                 }
             }
@@ -177,11 +159,13 @@ mod macos {
         const FPS: i32 = 30;
         const TARGET_DUR: u32 = 6;
 
+        let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Event);
+        playlist.target_duration = TARGET_DUR;
         let mut delegate = WriterDelegate::with(SegmentWriter {
             n: 0,
             dir: "/tmp/".into(),
             base_name: "hls".into(),
-            target_dur: TARGET_DUR,
+            playlist,
         });
 
         let mut input = av::AssetWriterInput::with_media_type_and_output_settings(
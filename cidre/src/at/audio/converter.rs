@@ -226,38 +226,7 @@ impl DitherAlgorithm {
     pub const NOISE_SHAPING: Self = Self(2);
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Copy)]
-#[repr(transparent)]
-pub struct Quality(pub u32);
-
-/// kAudioConverterSampleRateConverterQuality
-impl Quality {
-    /// maximum quality
-    #[doc(alias = "kAudioConverterQuality_Max")]
-    pub const MAX: Self = Self(0x7F);
-
-    /// high quality
-    #[doc(alias = "kAudioConverterQuality_High")]
-    pub const HIGH: Self = Self(0x60);
-
-    /// medium quality
-    #[doc(alias = "kAudioConverterQuality_Medium")]
-    pub const MEDIUM: Self = Self(0x40);
-
-    /// low quality
-    #[doc(alias = "kAudioConverterQuality_Low")]
-    pub const LOW: Self = Self(0x20);
-
-    /// minimum quality
-    #[doc(alias = "kAudioConverterQuality_Min")]
-    pub const MIN: Self = Self(0);
-}
-
-impl Default for Quality {
-    fn default() -> Self {
-        Self::MEDIUM
-    }
-}
+pub use audio::resample::Quality;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[repr(transparent)]
@@ -0,0 +1,716 @@
+//! Native WAVE (including RF64), CAF and AIFF/AIFC audio files.
+//!
+//! [`Reader`] and [`Writer`] mirror `at::audio::FileId` without AudioToolbox: they report
+//! the same [`FileTypeId`], `StreamBasicDesc`, packet descriptions, magic cookie and
+//! info dictionary keys.
+//!
+//! ```
+//! use std::io::Cursor;
+//!
+//! use cidre::{audio_file, cat::audio};
+//!
+//! let asbd = audio::StreamBasicDesc {
+//!     sample_rate: 48_000.0,
+//!     format: audio::Format::LINEAR_PCM,
+//!     format_flags: audio::FormatFlags::IS_SIGNED_INTEGER | audio::FormatFlags::IS_PACKED,
+//!     bytes_per_packet: 4,
+//!     frames_per_packet: 1,
+//!     bytes_per_frame: 4,
+//!     channels_per_frame: 2,
+//!     bits_per_channel: 16,
+//!     reserved: 0,
+//! };
+//!
+//! let mut buf = Cursor::new(Vec::new());
+//! let mut file = audio_file::Writer::new(&mut buf, audio::FileTypeId::WAVE, &asbd).unwrap();
+//! file.set_info(audio_file::info_key::TITLE, "silence");
+//! file.write_packets(&[0u8; 400], None, 0, 100).unwrap();
+//! file.close().unwrap();
+//! drop(file);
+//!
+//! let file = audio_file::Reader::new(buf).unwrap();
+//! assert_eq!(file.file_format(), audio::FileTypeId::WAVE);
+//! assert_eq!(file.data_format(), asbd);
+//! assert_eq!(file.data_packet_count(), 100);
+//! assert_eq!(file.info_dictionary()[audio_file::info_key::TITLE], "silence");
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    ops::Deref,
+    path::Path,
+};
+
+use crate::{
+    FourCc,
+    cat::audio::{self, FileTypeId},
+};
+
+mod aiff;
+mod caf;
+mod wav;
+
+/// Keys of info dictionary, same as `kAFInfoDictionary_*`
+pub mod info_key {
+    #[doc(alias = "kAFInfoDictionary_Artist")]
+    pub const ARTIST: &str = "artist";
+
+    #[doc(alias = "kAFInfoDictionary_Album")]
+    pub const ALBUM: &str = "album";
+
+    #[doc(alias = "kAFInfoDictionary_Tempo")]
+    pub const TEMPO: &str = "tempo";
+
+    #[doc(alias = "kAFInfoDictionary_KeySignature")]
+    pub const KEY_SIGNATURE: &str = "key signature";
+
+    #[doc(alias = "kAFInfoDictionary_TimeSignature")]
+    pub const TIME_SIGNATURE: &str = "time signature";
+
+    #[doc(alias = "kAFInfoDictionary_TrackNumber")]
+    pub const TRACK_NUMBER: &str = "track number";
+
+    #[doc(alias = "kAFInfoDictionary_Year")]
+    pub const YEAR: &str = "year";
+
+    #[doc(alias = "kAFInfoDictionary_Composer")]
+    pub const COMPOSER: &str = "composer";
+
+    #[doc(alias = "kAFInfoDictionary_Lyricist")]
+    pub const LYRICIST: &str = "lyricist";
+
+    #[doc(alias = "kAFInfoDictionary_Genre")]
+    pub const GENRE: &str = "genre";
+
+    #[doc(alias = "kAFInfoDictionary_Title")]
+    pub const TITLE: &str = "title";
+
+    #[doc(alias = "kAFInfoDictionary_RecordedDate")]
+    pub const RECORDED_DATE: &str = "recorded date";
+
+    #[doc(alias = "kAFInfoDictionary_Comments")]
+    pub const COMMENTS: &str = "comments";
+
+    #[doc(alias = "kAFInfoDictionary_Copyright")]
+    pub const COPYRIGHT: &str = "copyright";
+
+    #[doc(alias = "kAFInfoDictionary_SourceEncoder")]
+    pub const SOURCE_ENCODER: &str = "source encoder";
+
+    #[doc(alias = "kAFInfoDictionary_EncodingApplication")]
+    pub const ENCODING_APPLICATION: &str = "encoding application";
+
+    #[doc(alias = "kAFInfoDictionary_NominalBitRate")]
+    pub const NOMINAL_BIT_RATE: &str = "nominal bit rate";
+
+    #[doc(alias = "kAFInfoDictionary_ChannelLayout")]
+    pub const CHANNEL_LAYOUT: &str = "channel layout";
+
+    #[doc(alias = "kAFInfoDictionary_ApproximateDurationInSeconds")]
+    pub const APPROXIMATE_DURATION_IN_SECONDS: &str = "approximate duration in seconds";
+
+    #[doc(alias = "kAFInfoDictionary_SourceBitDepth")]
+    pub const SOURCE_BIT_DEPTH: &str = "source bit depth";
+
+    #[doc(alias = "kAFInfoDictionary_ISRC")]
+    pub const ISRC: &str = "ISRC";
+
+    #[doc(alias = "kAFInfoDictionary_SubTitle")]
+    pub const SUB_TITLE: &str = "subtitle";
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+
+    /// Not a WAVE, CAF or AIFF file
+    InvalidFile,
+
+    /// File type can't be written natively
+    UnsupportedFileType(FileTypeId),
+
+    /// Data format can't be stored in file type
+    UnsupportedDataFormat(audio::Format),
+
+    /// Chunk is malformed or missing
+    InvalidChunk(FourCc),
+
+    /// Packets are written sequentially and read within the file
+    InvalidPacketOffset(i64),
+
+    /// Variable bit rate packets require packet descriptions
+    MissingPacketDescs,
+
+    /// Buffer doesn't contain all packets
+    BufTooSmall {
+        required: usize,
+        actual: usize,
+    },
+
+    /// Property is not supported by file type or can't be changed after audio data is written
+    OperationNotSupported,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => e.fmt(f),
+            Self::InvalidFile => f.write_str("not a WAVE, CAF or AIFF file"),
+            Self::UnsupportedFileType(t) => {
+                write!(f, "unsupported file type '{}'", FourCc::from(*t))
+            }
+            Self::UnsupportedDataFormat(format) => write!(f, "unsupported data format {format:?}"),
+            Self::InvalidChunk(id) => write!(f, "invalid or missing '{id}' chunk"),
+            Self::InvalidPacketOffset(p) => write!(f, "invalid packet offset {p}"),
+            Self::MissingPacketDescs => f.write_str("packet descriptions required"),
+            Self::BufTooSmall { required, actual } => {
+                write!(f, "buffer of {actual} bytes, {required} bytes required")
+            }
+            Self::OperationNotSupported => f.write_str("operation not supported"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+/// Same as `at::AudioFile` packet table info
+#[doc(alias = "AudioFilePacketTableInfo")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PacketTableInfo {
+    pub valid_frames: i64,
+    pub priming_frames: i32,
+    pub remainder_frames: i32,
+}
+
+/// Properties of audio file, shared by [`Reader`] and [`Writer`]
+#[derive(Debug, Clone)]
+pub struct Props {
+    type_id: FileTypeId,
+    asbd: audio::StreamBasicDesc,
+    data_offset: u64,
+    data_size: u64,
+    packet_count: u64,
+
+    /// Descriptions of variable bit rate packets, offsets are relative to `data_offset`
+    packets: Vec<audio::StreamPacketDesc>,
+    packet_table: Option<PacketTableInfo>,
+    cookie: Vec<u8>,
+    info: BTreeMap<String, String>,
+}
+
+impl Props {
+    fn new(type_id: FileTypeId, asbd: audio::StreamBasicDesc) -> Self {
+        Self {
+            type_id,
+            asbd,
+            data_offset: 0,
+            data_size: 0,
+            packet_count: 0,
+            packets: Vec::new(),
+            packet_table: None,
+            cookie: Vec::new(),
+            info: BTreeMap::new(),
+        }
+    }
+
+    /// Sets data range of constant bit rate packets
+    fn set_data(&mut self, offset: u64, size: u64) {
+        self.data_offset = offset;
+        self.data_size = size;
+        if self.asbd.bytes_per_packet != 0 {
+            self.packet_count = size / self.asbd.bytes_per_packet as u64;
+        }
+    }
+
+    #[inline]
+    fn is_vbr(&self) -> bool {
+        self.asbd.bytes_per_packet == 0
+    }
+
+    fn frames(&self) -> u64 {
+        if self.asbd.frames_per_packet != 0 {
+            self.packet_count * self.asbd.frames_per_packet as u64
+        } else {
+            self.packets
+                .iter()
+                .map(|p| p.variable_frames_in_packet as u64)
+                .sum()
+        }
+    }
+
+    #[doc(alias = "kAudioFilePropertyFileFormat")]
+    #[inline]
+    pub fn file_format(&self) -> FileTypeId {
+        self.type_id
+    }
+
+    #[doc(alias = "kAudioFilePropertyDataFormat")]
+    #[inline]
+    pub fn data_format(&self) -> audio::StreamBasicDesc {
+        self.asbd
+    }
+
+    #[doc(alias = "kAudioFilePropertyMagicCookieData")]
+    #[inline]
+    pub fn magic_cookie_data(&self) -> &[u8] {
+        &self.cookie
+    }
+
+    #[doc(alias = "kAudioFilePropertyDataOffset")]
+    #[inline]
+    pub fn data_offset(&self) -> u64 {
+        self.data_offset
+    }
+
+    #[doc(alias = "kAudioFilePropertyAudioDataByteCount")]
+    #[inline]
+    pub fn data_byte_count(&self) -> u64 {
+        self.data_size
+    }
+
+    #[doc(alias = "kAudioFilePropertyAudioDataPacketCount")]
+    #[inline]
+    pub fn data_packet_count(&self) -> u64 {
+        self.packet_count
+    }
+
+    #[doc(alias = "kAudioFilePropertyMaximumPacketSize")]
+    pub fn maximum_packet_size(&self) -> u32 {
+        if self.is_vbr() {
+            self.packets
+                .iter()
+                .map(|p| p.data_byte_size)
+                .max()
+                .unwrap_or(0)
+        } else {
+            self.asbd.bytes_per_packet
+        }
+    }
+
+    #[doc(alias = "kAudioFilePropertyPacketTableInfo")]
+    #[inline]
+    pub fn packet_table_info(&self) -> Option<PacketTableInfo> {
+        self.packet_table
+    }
+
+    /// Duration in seconds, valid frames of packet table are used if present
+    #[doc(alias = "kAudioFilePropertyEstimatedDuration")]
+    pub fn estimated_duration(&self) -> f64 {
+        if self.asbd.sample_rate <= 0.0 {
+            return 0.0;
+        }
+        let frames = match self.packet_table {
+            Some(table) => table.valid_frames as f64,
+            None => self.frames() as f64,
+        };
+        frames / self.asbd.sample_rate
+    }
+
+    /// Info stored in file plus approximate duration
+    #[doc(alias = "kAudioFilePropertyInfoDictionary")]
+    pub fn info_dictionary(&self) -> BTreeMap<String, String> {
+        let mut res = self.info.clone();
+        res.insert(
+            info_key::APPROXIMATE_DURATION_IN_SECONDS.to_string(),
+            format!("{:.3}", self.estimated_duration()),
+        );
+        res
+    }
+}
+
+/// Reads packets of WAVE, CAF or AIFF file
+#[derive(Debug)]
+pub struct Reader<R> {
+    io: R,
+    props: Props,
+}
+
+impl Reader<BufReader<fs::File>> {
+    #[doc(alias = "AudioFileOpenURL")]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(BufReader::new(fs::File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> Reader<R> {
+    /// Detects file type by signature and reads headers
+    pub fn new(mut io: R) -> Result<Self> {
+        let len = io.seek(SeekFrom::End(0))?;
+        io.seek(SeekFrom::Start(0))?;
+        let mut sig = [0u8; 12];
+        io.read_exact(&mut sig).map_err(|_| Error::InvalidFile)?;
+        let props = match (&sig[..4], &sig[8..]) {
+            (b"RIFF" | b"RF64" | b"BW64", b"WAVE") => wav::read(&mut io, &sig, len)?,
+            (b"caff", _) => caf::read(&mut io, len)?,
+            (b"FORM", b"AIFF" | b"AIFC") => aiff::read(&mut io, &sig, len)?,
+            _ => return Err(Error::InvalidFile),
+        };
+        Ok(Self { io, props })
+    }
+
+    /// Reads up to `num_packets` whole packets starting from `starting_packet` which fit
+    /// into `buf`. Variable bit rate formats require `packet_descriptions`, their offsets
+    /// are relative to `buf`.
+    ///
+    /// Returns number of bytes and packets read, zero packets at the end of file.
+    #[doc(alias = "AudioFileReadPacketData")]
+    pub fn read_packets(
+        &mut self,
+        buf: &mut [u8],
+        packet_descriptions: Option<&mut [audio::StreamPacketDesc]>,
+        starting_packet: i64,
+        num_packets: u32,
+    ) -> Result<(u32, u32)> {
+        let props = &self.props;
+        if starting_packet < 0 || starting_packet as u64 > props.packet_count {
+            return Err(Error::InvalidPacketOffset(starting_packet));
+        }
+        let start = starting_packet as u64;
+        let available = (props.packet_count - start).min(num_packets as u64) as usize;
+
+        let (offset, bytes, packets) = if props.is_vbr() {
+            let Some(descs) = packet_descriptions else {
+                return Err(Error::MissingPacketDescs);
+            };
+            let packets = &props.packets[start as usize..];
+            let first = packets.first().map(|p| p.start_offset).unwrap_or(0);
+            let mut n = 0;
+            let mut bytes = 0usize;
+            for (src, dst) in packets.iter().zip(descs.iter_mut()).take(available) {
+                let size = src.data_byte_size as usize;
+                if bytes + size > buf.len() {
+                    break;
+                }
+                *dst = audio::StreamPacketDesc {
+                    start_offset: bytes as i64,
+                    ..*src
+                };
+                bytes += size;
+                n += 1;
+            }
+            (first as u64, bytes, n)
+        } else {
+            let bpp = props.asbd.bytes_per_packet as usize;
+            let n = available.min(buf.len() / bpp);
+            (start * bpp as u64, n * bpp, n)
+        };
+
+        if packets == 0 {
+            return Ok((0, 0));
+        }
+        self.io.seek(SeekFrom::Start(props.data_offset + offset))?;
+        self.io.read_exact(&mut buf[..bytes])?;
+        Ok((bytes as u32, packets as u32))
+    }
+
+    pub fn into_inner(self) -> R {
+        self.io
+    }
+}
+
+impl<R> Deref for Reader<R> {
+    type Target = Props;
+
+    fn deref(&self) -> &Self::Target {
+        &self.props
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Headers are not written yet
+    Created,
+    Writing,
+    Closed,
+}
+
+/// Writes packets of WAVE, RF64, CAF or AIFF file. Headers are written with
+/// first packets, sizes and trailing chunks on [`Writer::close`] or drop.
+#[derive(Debug)]
+pub struct Writer<W: Write + Seek> {
+    io: W,
+    props: Props,
+    state: State,
+}
+
+impl Writer<BufWriter<fs::File>> {
+    #[doc(alias = "AudioFileCreateWithURL")]
+    pub fn create<P: AsRef<Path>>(
+        path: P,
+        type_id: FileTypeId,
+        format: &audio::StreamBasicDesc,
+    ) -> Result<Self> {
+        Self::new(BufWriter::new(fs::File::create(path)?), type_id, format)
+    }
+}
+
+impl<W: Write + Seek> Writer<W> {
+    /// WAVE files which grow past 4 GiB are written as RF64.
+    pub fn new(io: W, type_id: FileTypeId, format: &audio::StreamBasicDesc) -> Result<Self> {
+        match type_id {
+            FileTypeId::WAVE | FileTypeId::RF64 | FileTypeId::BW64 => _ = wav::check(format)?,
+            FileTypeId::CAF => caf::check(format)?,
+            FileTypeId::AIFF | FileTypeId::AIFC => _ = aiff::compression(type_id, format)?,
+            _ => return Err(Error::UnsupportedFileType(type_id)),
+        };
+        Ok(Self {
+            io,
+            props: Props::new(type_id, *format),
+            state: State::Created,
+        })
+    }
+
+    /// Only CAF files store magic cookies, it should be set before packets are written.
+    #[doc(alias = "kAudioFilePropertyMagicCookieData")]
+    pub fn set_magic_cookie_data(&mut self, val: &[u8]) -> Result {
+        if self.props.type_id != FileTypeId::CAF || self.state != State::Created {
+            return Err(Error::OperationNotSupported);
+        }
+        self.props.cookie = val.to_vec();
+        Ok(())
+    }
+
+    /// Only CAF files store packet table info
+    #[doc(alias = "kAudioFilePropertyPacketTableInfo")]
+    pub fn set_packet_table_info(&mut self, val: PacketTableInfo) -> Result {
+        if self.props.type_id != FileTypeId::CAF || self.state == State::Closed {
+            return Err(Error::OperationNotSupported);
+        }
+        self.props.packet_table = Some(val);
+        Ok(())
+    }
+
+    /// Info is written on close. WAVE and AIFF files store only some keys.
+    pub fn set_info(&mut self, key: &str, val: &str) {
+        self.props.info.insert(key.to_string(), val.to_string());
+    }
+
+    /// Appends `num_packets` packets from `buf`. Variable bit rate formats require
+    /// `packet_descriptions` with offsets relative to `buf`.
+    ///
+    /// Returns number of written packets.
+    #[doc(alias = "AudioFileWritePackets")]
+    pub fn write_packets(
+        &mut self,
+        buf: &[u8],
+        packet_descriptions: Option<&[audio::StreamPacketDesc]>,
+        starting_packet: i64,
+        num_packets: u32,
+    ) -> Result<u32> {
+        if self.state == State::Closed {
+            return Err(Error::OperationNotSupported);
+        }
+        if starting_packet < 0 || starting_packet as u64 != self.props.packet_count {
+            return Err(Error::InvalidPacketOffset(starting_packet));
+        }
+        let n = num_packets as usize;
+        // (range in buf, description with file offset) of each packet
+        let mut packets = Vec::with_capacity(n);
+        if self.props.is_vbr() {
+            let descs = packet_descriptions
+                .filter(|d| d.len() >= n)
+                .ok_or(Error::MissingPacketDescs)?;
+            let mut offset = self.props.data_size as i64;
+            for desc in &descs[..n] {
+                let start = desc.start_offset.max(0) as usize;
+                let end = start + desc.data_byte_size as usize;
+                if desc.start_offset < 0 || end > buf.len() {
+                    return Err(Error::BufTooSmall {
+                        required: end,
+                        actual: buf.len(),
+                    });
+                }
+                packets.push((
+                    start..end,
+                    audio::StreamPacketDesc {
+                        start_offset: offset,
+                        ..*desc
+                    },
+                ));
+                offset += desc.data_byte_size as i64;
+            }
+        } else {
+            let required = n * self.props.asbd.bytes_per_packet as usize;
+            if buf.len() < required {
+                return Err(Error::BufTooSmall {
+                    required,
+                    actual: buf.len(),
+                });
+            }
+        }
+
+        if self.state == State::Created {
+            self.write_header()?;
+        }
+        self.io.seek(SeekFrom::Start(
+            self.props.data_offset + self.props.data_size,
+        ))?;
+        if self.props.is_vbr() {
+            for (range, desc) in packets {
+                self.io.write_all(&buf[range])?;
+                self.props.data_size += desc.data_byte_size as u64;
+                self.props.packets.push(desc);
+            }
+        } else {
+            let bytes = &buf[..n * self.props.asbd.bytes_per_packet as usize];
+            self.io.write_all(bytes)?;
+            self.props.data_size += bytes.len() as u64;
+        }
+        self.props.packet_count += n as u64;
+        Ok(num_packets)
+    }
+
+    fn write_header(&mut self) -> Result {
+        match self.props.type_id {
+            FileTypeId::CAF => caf::write_header(&mut self.io, &mut self.props)?,
+            FileTypeId::AIFF | FileTypeId::AIFC => {
+                aiff::write_header(&mut self.io, &mut self.props)?
+            }
+            _ => wav::write_header(&mut self.io, &mut self.props)?,
+        }
+        self.state = State::Writing;
+        Ok(())
+    }
+
+    /// Writes sizes and trailing chunks
+    #[doc(alias = "AudioFileClose")]
+    pub fn close(&mut self) -> Result {
+        match self.state {
+            State::Closed => return Ok(()),
+            State::Created => self.write_header()?,
+            State::Writing => {}
+        }
+        self.state = State::Closed;
+        match self.props.type_id {
+            FileTypeId::CAF => caf::finish(&mut self.io, &mut self.props)?,
+            FileTypeId::AIFF | FileTypeId::AIFC => aiff::finish(&mut self.io, &self.props)?,
+            _ => wav::finish(&mut self.io, &mut self.props)?,
+        }
+        self.io.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Deref for Writer<W> {
+    type Target = Props;
+
+    fn deref(&self) -> &Self::Target {
+        &self.props
+    }
+}
+
+impl<W: Write + Seek> Drop for Writer<W> {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+fn read_array<const N: usize, R: Read>(io: &mut R) -> Result<[u8; N]> {
+    let mut res = [0u8; N];
+    io.read_exact(&mut res)?;
+    Ok(res)
+}
+
+/// Reads chunk payload which should fit into `remaining` bytes of file
+fn read_payload<R: Read>(io: &mut R, id: FourCc, size: u64, remaining: u64) -> Result<Vec<u8>> {
+    if size > remaining {
+        return Err(Error::InvalidChunk(id));
+    }
+    let mut res = vec![0u8; size as usize];
+    io.read_exact(&mut res)?;
+    Ok(res)
+}
+
+/// Text up to first nul
+fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        audio_file::{self, Error},
+        cat::audio,
+    };
+
+    fn asbd_i16() -> audio::StreamBasicDesc {
+        audio::StreamBasicDesc {
+            sample_rate: 44_100.0,
+            format: audio::Format::LINEAR_PCM,
+            format_flags: audio::FormatFlags::IS_SIGNED_INTEGER | audio::FormatFlags::IS_PACKED,
+            bytes_per_packet: 4,
+            frames_per_packet: 1,
+            bytes_per_frame: 4,
+            channels_per_frame: 2,
+            bits_per_channel: 16,
+            reserved: 0,
+        }
+    }
+
+    #[test]
+    fn packets() {
+        let mut buf = Cursor::new(Vec::new());
+        let data: Vec<u8> = (0..=255).collect();
+        {
+            let mut f =
+                audio_file::Writer::new(&mut buf, audio::FileTypeId::WAVE, &asbd_i16()).unwrap();
+            assert_eq!(f.write_packets(&data, None, 0, 64).unwrap(), 64);
+            assert!(matches!(
+                f.write_packets(&data, None, 0, 1),
+                Err(Error::InvalidPacketOffset(0))
+            ));
+            assert!(matches!(
+                f.write_packets(&data, None, 64, 65),
+                Err(Error::BufTooSmall { .. })
+            ));
+            assert!(matches!(
+                f.set_magic_cookie_data(&[1]),
+                Err(Error::OperationNotSupported)
+            ));
+        }
+
+        let mut f = audio_file::Reader::new(buf).unwrap();
+        assert_eq!(f.data_packet_count(), 64);
+        assert_eq!(f.maximum_packet_size(), 4);
+        assert!((f.estimated_duration() - 64.0 / 44_100.0).abs() < 1e-9);
+
+        let mut out = [0u8; 10];
+        assert_eq!(f.read_packets(&mut out, None, 1, 100).unwrap(), (8, 2));
+        assert_eq!(out[..8], data[4..12]);
+        assert_eq!(f.read_packets(&mut out, None, 63, 2).unwrap(), (4, 1));
+        assert_eq!(f.read_packets(&mut out, None, 64, 2).unwrap(), (0, 0));
+        assert!(matches!(
+            f.read_packets(&mut out, None, 65, 2),
+            Err(Error::InvalidPacketOffset(65))
+        ));
+
+        assert!(matches!(
+            audio_file::Reader::new(Cursor::new(b"not an audio file".to_vec())),
+            Err(Error::InvalidFile)
+        ));
+        assert!(matches!(
+            audio_file::Writer::new(Cursor::new(Vec::new()), audio::FileTypeId::MP3, &asbd_i16()),
+            Err(Error::UnsupportedFileType(audio::FileTypeId::MP3))
+        ));
+    }
+}
@@ -0,0 +1,412 @@
+//! Audio Interchange File Format, AIFF and AIFF-C
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::{
+    FourCc,
+    cat::audio::{self, FileTypeId, FormatFlags},
+};
+
+use super::{Error, Props, Result, info_key, read_array, read_payload, text};
+
+/// `FVER` timestamp of AIFF-C version 1
+const AIFC_VERSION_1: u32 = 0xA280_5140;
+
+const COMM: FourCc = FourCc::from_bytes(b"COMM");
+const SSND: FourCc = FourCc::from_bytes(b"SSND");
+
+/// Text chunks
+const INFO: [(&[u8; 4], &str); 4] = [
+    (b"NAME", info_key::TITLE),
+    (b"AUTH", info_key::ARTIST),
+    (b"(c) ", info_key::COPYRIGHT),
+    (b"ANNO", info_key::COMMENTS),
+];
+
+fn u16_at(buf: &[u8], i: usize) -> u16 {
+    u16::from_be_bytes([buf[i], buf[i + 1]])
+}
+
+fn u32_at(buf: &[u8], i: usize) -> u32 {
+    u32::from_be_bytes(buf[i..i + 4].try_into().unwrap())
+}
+
+/// 80-bit IEEE 754 extended precision
+fn f64_from_ext80(b: &[u8; 10]) -> f64 {
+    let exp = u16::from_be_bytes([b[0], b[1]]);
+    let mantissa = u64::from_be_bytes(b[2..].try_into().unwrap());
+    if mantissa == 0 {
+        return 0.0;
+    }
+    let res = mantissa as f64 * 2f64.powi((exp & 0x7FFF) as i32 - 16383 - 63);
+    if exp & 0x8000 != 0 { -res } else { res }
+}
+
+fn ext80_from_f64(val: f64) -> [u8; 10] {
+    let mut res = [0u8; 10];
+    if val == 0.0 || !val.is_normal() {
+        return res;
+    }
+    let bits = val.to_bits();
+    let exp = ((bits >> 52) & 0x7FF) as u16 + 16383 - 1023;
+    let sign = ((bits >> 63) as u16) << 15;
+    let mantissa = (1 << 63) | ((bits & ((1 << 52) - 1)) << 11);
+    res[..2].copy_from_slice(&(sign | exp).to_be_bytes());
+    res[2..].copy_from_slice(&mantissa.to_be_bytes());
+    res
+}
+
+pub(super) fn read<R: Read + Seek>(io: &mut R, sig: &[u8; 12], len: u64) -> Result<Props> {
+    let type_id = if &sig[8..] == b"AIFC" {
+        FileTypeId::AIFC
+    } else {
+        FileTypeId::AIFF
+    };
+    let mut comm = None;
+    let mut ssnd = None;
+    let mut props = Props::new(type_id, Default::default());
+
+    let mut pos = 12u64;
+    while pos + 8 <= len {
+        io.seek(SeekFrom::Start(pos))?;
+        let header = read_array::<8, _>(io)?;
+        let id = FourCc::from_bytes(header[..4].try_into().unwrap());
+        let size = u32_at(&header, 4) as u64;
+        let remaining = len - pos - 8;
+        match &id.to_bytes() {
+            b"COMM" => {
+                comm = Some(parse_comm(
+                    type_id,
+                    &read_payload(io, id, size, remaining)?,
+                )?)
+            }
+            b"SSND" => {
+                let p = read_array::<8, _>(io)?;
+                let offset = u32_at(&p, 0) as u64;
+                if size < 8 + offset {
+                    return Err(Error::InvalidChunk(id));
+                }
+                // recordings which were not closed have truncated data
+                ssnd = Some((pos + 16 + offset, (size - 8 - offset).min(remaining)));
+            }
+            other => {
+                if let Some((_, key)) = INFO.iter().find(|(i, _)| *i == other) {
+                    let val = text(&read_payload(io, id, size, remaining)?);
+                    props.info.entry(key.to_string()).or_insert(val);
+                }
+            }
+        }
+        pos += 8 + size + (size & 1);
+    }
+
+    let (asbd, frames) = comm.ok_or(Error::InvalidChunk(COMM))?;
+    let (offset, size) = ssnd.ok_or(Error::InvalidChunk(SSND))?;
+    props.asbd = asbd;
+    props.set_data(
+        offset,
+        size.min(frames as u64 * asbd.bytes_per_frame as u64),
+    );
+    Ok(props)
+}
+
+/// Data format and number of frames
+fn parse_comm(type_id: FileTypeId, p: &[u8]) -> Result<(audio::StreamBasicDesc, u32)> {
+    if p.len() < 18 || (type_id == FileTypeId::AIFC && p.len() < 22) {
+        return Err(Error::InvalidChunk(COMM));
+    }
+    let channels = u16_at(p, 0) as u32;
+    let frames = u32_at(p, 2);
+    let mut bits = u16_at(p, 6) as u32;
+    let sample_rate = f64_from_ext80(p[8..18].try_into().unwrap());
+    let compression = if type_id == FileTypeId::AIFC {
+        &p[18..22]
+    } else {
+        b"NONE"
+    };
+    if channels == 0 || bits == 0 || bits > 64 {
+        return Err(Error::InvalidChunk(COMM));
+    }
+
+    let pcm = FormatFlags::IS_SIGNED_INTEGER
+        | if bits % 8 == 0 {
+            FormatFlags::IS_PACKED
+        } else {
+            // samples are left justified in whole bytes
+            FormatFlags::IS_ALIGNED_HIGH
+        };
+    let (format, format_flags) = match compression {
+        b"NONE" | b"twos" | b"in24" | b"in32" => {
+            (audio::Format::LINEAR_PCM, pcm | FormatFlags::IS_BIG_ENDIAN)
+        }
+        b"sowt" | b"23ni" | b"42ni" => (audio::Format::LINEAR_PCM, pcm),
+        b"fl32" | b"FL32" | b"fl64" | b"FL64" => {
+            bits = if compression[2] == b'3' { 32 } else { 64 };
+            (
+                audio::Format::LINEAR_PCM,
+                FormatFlags::IS_FLOAT | FormatFlags::IS_BIG_ENDIAN | FormatFlags::IS_PACKED,
+            )
+        }
+        b"ulaw" | b"ULAW" | b"alaw" | b"ALAW" => {
+            bits = 8;
+            let format = if compression[0] | 0x20 == b'u' {
+                audio::Format::U_LAW
+            } else {
+                audio::Format::A_LAW
+            };
+            (format, FormatFlags(0))
+        }
+        c => {
+            let c: [u8; 4] = c.try_into().unwrap();
+            return Err(Error::UnsupportedDataFormat(audio::Format(
+                u32::from_be_bytes(c),
+            )));
+        }
+    };
+    let bytes_per_frame = bits.div_ceil(8) * channels;
+    let asbd = audio::StreamBasicDesc {
+        sample_rate,
+        format,
+        format_flags,
+        bytes_per_packet: bytes_per_frame,
+        frames_per_packet: 1,
+        bytes_per_frame,
+        channels_per_frame: channels,
+        bits_per_channel: bits,
+        reserved: 0,
+    };
+    Ok((asbd, frames))
+}
+
+/// Compression type of data format, `NONE` for AIFF
+pub(super) fn compression(type_id: FileTypeId, asbd: &audio::StreamBasicDesc) -> Result<[u8; 4]> {
+    let flags = asbd.format_flags;
+    let unsupported = Err(Error::UnsupportedDataFormat(asbd.format));
+    if asbd.channels_per_frame == 0
+        || asbd.channels_per_frame > i16::MAX as u32
+        || asbd.frames_per_packet != 1
+        || asbd.bytes_per_frame == 0
+        || asbd.bytes_per_packet != asbd.bytes_per_frame
+        || asbd.bytes_per_frame % asbd.channels_per_frame != 0
+        || flags.contains(FormatFlags::IS_NON_INTERLEAVED)
+    {
+        return unsupported;
+    }
+    let container = asbd.bytes_per_frame / asbd.channels_per_frame * 8;
+    let big_endian = flags.contains(FormatFlags::IS_BIG_ENDIAN);
+    let res = match asbd.format {
+        audio::Format::LINEAR_PCM if flags.contains(FormatFlags::IS_FLOAT) => {
+            match (big_endian, asbd.bits_per_channel, container) {
+                (true, 32, 32) => b"fl32",
+                (true, 64, 64) => b"fl64",
+                _ => return unsupported,
+            }
+        }
+        audio::Format::LINEAR_PCM => {
+            if !flags.contains(FormatFlags::IS_SIGNED_INTEGER)
+                || asbd.bits_per_channel == 0
+                || asbd.bits_per_channel > container
+                || container != asbd.bits_per_channel.div_ceil(8) * 8
+                || container > 32
+            {
+                return unsupported;
+            }
+            if big_endian { b"NONE" } else { b"sowt" }
+        }
+        audio::Format::U_LAW if container == 8 => b"ulaw",
+        audio::Format::A_LAW if container == 8 => b"alaw",
+        _ => return unsupported,
+    };
+    if type_id == FileTypeId::AIFF && res != b"NONE" {
+        return unsupported;
+    }
+    Ok(*res)
+}
+
+pub(super) fn write_header<W: Write + Seek>(io: &mut W, props: &mut Props) -> Result {
+    let asbd = &props.asbd;
+    let compression = compression(props.type_id, asbd)?;
+    let aifc = props.type_id == FileTypeId::AIFC;
+
+    let mut h = Vec::with_capacity(72);
+    h.extend_from_slice(b"FORM");
+    h.extend_from_slice(&0u32.to_be_bytes());
+    h.extend_from_slice(if aifc { b"AIFC" } else { b"AIFF" });
+    if aifc {
+        h.extend_from_slice(b"FVER");
+        h.extend_from_slice(&4u32.to_be_bytes());
+        h.extend_from_slice(&AIFC_VERSION_1.to_be_bytes());
+    }
+
+    h.extend_from_slice(b"COMM");
+    // compression name is an empty pascal string padded to even length
+    h.extend_from_slice(&(if aifc { 24u32 } else { 18 }).to_be_bytes());
+    h.extend_from_slice(&(asbd.channels_per_frame as u16).to_be_bytes());
+    // number of frames is written on close
+    h.extend_from_slice(&0u32.to_be_bytes());
+    h.extend_from_slice(&(asbd.bits_per_channel as u16).to_be_bytes());
+    h.extend_from_slice(&ext80_from_f64(asbd.sample_rate));
+    if aifc {
+        h.extend_from_slice(&compression);
+        h.extend_from_slice(&[0, 0]);
+    }
+
+    h.extend_from_slice(b"SSND");
+    h.extend_from_slice(&8u32.to_be_bytes());
+    // offset and block size
+    h.extend_from_slice(&[0u8; 8]);
+
+    io.seek(SeekFrom::Start(0))?;
+    io.write_all(&h)?;
+    props.data_offset = h.len() as u64;
+    Ok(())
+}
+
+pub(super) fn finish<W: Write + Seek>(io: &mut W, props: &Props) -> Result {
+    io.seek(SeekFrom::Start(props.data_offset + props.data_size))?;
+    if props.data_size & 1 != 0 {
+        io.write_all(&[0])?;
+    }
+    for (id, key) in INFO {
+        if let Some(val) = props.info.get(key) {
+            io.write_all(id)?;
+            io.write_all(&(val.len() as u32).to_be_bytes())?;
+            io.write_all(val.as_bytes())?;
+            if val.len() & 1 != 0 {
+                io.write_all(&[0])?;
+            }
+        }
+    }
+
+    let form_size = io.stream_position()? - 8;
+    if form_size > u32::MAX as u64 {
+        return Err(Error::Io(std::io::ErrorKind::FileTooLarge.into()));
+    }
+    io.seek(SeekFrom::Start(4))?;
+    io.write_all(&(form_size as u32).to_be_bytes())?;
+    let frames_pos = if props.type_id == FileTypeId::AIFC {
+        34
+    } else {
+        22
+    };
+    io.seek(SeekFrom::Start(frames_pos))?;
+    io.write_all(&(props.packet_count as u32).to_be_bytes())?;
+    io.seek(SeekFrom::Start(props.data_offset - 12))?;
+    io.write_all(&(props.data_size as u32 + 8).to_be_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        audio_file::{self, Error, info_key},
+        cat::audio::{self, FileTypeId, FormatFlags},
+    };
+
+    #[test]
+    fn ext80() {
+        for rate in [
+            8_000.0, 22_050.0, 44_100.0, 48_000.0, 96_000.0, 11_025.5, 0.5,
+        ] {
+            assert_eq!(super::f64_from_ext80(&super::ext80_from_f64(rate)), rate);
+        }
+        assert_eq!(
+            super::ext80_from_f64(44_100.0),
+            [0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn formats() {
+        let mut asbd = audio::StreamBasicDesc {
+            sample_rate: 44_100.0,
+            format: audio::Format::LINEAR_PCM,
+            format_flags: FormatFlags::IS_SIGNED_INTEGER
+                | FormatFlags::IS_BIG_ENDIAN
+                | FormatFlags::IS_PACKED,
+            bytes_per_packet: 3,
+            frames_per_packet: 1,
+            bytes_per_frame: 3,
+            channels_per_frame: 1,
+            bits_per_channel: 24,
+            reserved: 0,
+        };
+        let data: Vec<u8> = (0..15).collect();
+        for (type_id, flags) in [
+            (FileTypeId::AIFF, asbd.format_flags),
+            (FileTypeId::AIFC, asbd.format_flags),
+            (
+                FileTypeId::AIFC,
+                FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_PACKED,
+            ),
+        ] {
+            asbd.format_flags = flags;
+            let mut buf = Cursor::new(Vec::new());
+            {
+                let mut f = audio_file::Writer::new(&mut buf, type_id, &asbd).unwrap();
+                f.set_info(info_key::TITLE, "odd");
+                f.set_info(info_key::COPYRIGHT, "cidre");
+                f.write_packets(&data, None, 0, 5).unwrap();
+            }
+            assert_eq!(buf.get_ref().len() % 2, 0);
+            let mut f = audio_file::Reader::new(buf).unwrap();
+            assert_eq!(f.file_format(), type_id);
+            assert_eq!(f.data_format(), asbd);
+            assert_eq!(f.data_packet_count(), 5);
+            assert_eq!(f.info_dictionary()[info_key::TITLE], "odd");
+            assert_eq!(f.info_dictionary()[info_key::COPYRIGHT], "cidre");
+            let mut out = [0u8; 15];
+            assert_eq!(f.read_packets(&mut out, None, 0, 5).unwrap(), (15, 5));
+            assert_eq!(out[..], data[..]);
+        }
+
+        // little endian and float samples require AIFF-C
+        assert!(matches!(
+            audio_file::Writer::new(Cursor::new(Vec::new()), FileTypeId::AIFF, &asbd),
+            Err(Error::UnsupportedDataFormat(audio::Format::LINEAR_PCM))
+        ));
+        asbd.format_flags =
+            FormatFlags::IS_FLOAT | FormatFlags::IS_BIG_ENDIAN | FormatFlags::IS_PACKED;
+        asbd.bits_per_channel = 64;
+        asbd.bytes_per_frame = 16;
+        asbd.bytes_per_packet = 16;
+        asbd.channels_per_frame = 2;
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut f = audio_file::Writer::new(&mut buf, FileTypeId::AIFC, &asbd).unwrap();
+            f.write_packets(&[0u8; 32], None, 0, 2).unwrap();
+        }
+        assert_eq!(&buf.get_ref()[50..54], b"fl64");
+        let f = audio_file::Reader::new(buf).unwrap();
+        assert_eq!(f.data_format(), asbd);
+        assert_eq!(f.data_packet_count(), 2);
+    }
+
+    #[test]
+    fn external() {
+        // 12 bit stereo with SSND offset, frames count less than data
+        let mut aiff = Vec::new();
+        aiff.extend_from_slice(b"FORM\0\0\0\x3EAIFFCOMM\0\0\0\x12");
+        aiff.extend_from_slice(&[0, 2, 0, 0, 0, 2, 0, 12]);
+        aiff.extend_from_slice(&super::ext80_from_f64(8_000.0));
+        aiff.extend_from_slice(b"SSND\0\0\0\x14\0\0\0\x02\0\0\0\0");
+        aiff.extend_from_slice(&[0xEE, 0xEE, 1, 0, 2, 0, 3, 0, 4, 0, 5, 0]);
+
+        let mut f = audio_file::Reader::new(Cursor::new(aiff)).unwrap();
+        let asbd = f.data_format();
+        assert_eq!(asbd.sample_rate, 8_000.0);
+        assert_eq!(asbd.bits_per_channel, 12);
+        assert_eq!(asbd.bytes_per_frame, 4);
+        assert_eq!(
+            asbd.format_flags,
+            FormatFlags::IS_SIGNED_INTEGER
+                | FormatFlags::IS_BIG_ENDIAN
+                | FormatFlags::IS_ALIGNED_HIGH
+        );
+        assert_eq!(f.data_packet_count(), 2);
+        let mut out = [0u8; 16];
+        assert_eq!(f.read_packets(&mut out, None, 0, 4).unwrap(), (8, 2));
+        assert_eq!(out[..8], [1, 0, 2, 0, 3, 0, 4, 0]);
+    }
+}
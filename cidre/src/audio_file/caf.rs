@@ -0,0 +1,503 @@
+//! Core Audio Format
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use crate::{
+    FourCc,
+    cat::audio::{self, FileTypeId, FormatFlags},
+};
+
+use super::{Error, PacketTableInfo, Props, Result, read_array, read_payload};
+
+/// `kCAFLinearPCMFormatFlagIsFloat`
+const LPCM_IS_FLOAT: u32 = 1 << 0;
+
+/// `kCAFLinearPCMFormatFlagIsLittleEndian`
+const LPCM_IS_LITTLE_ENDIAN: u32 = 1 << 1;
+
+const DESC: FourCc = FourCc::from_bytes(b"desc");
+const DATA: FourCc = FourCc::from_bytes(b"data");
+const PAKT: FourCc = FourCc::from_bytes(b"pakt");
+
+fn u32_at(buf: &[u8], i: usize) -> u32 {
+    u32::from_be_bytes(buf[i..i + 4].try_into().unwrap())
+}
+
+fn i32_at(buf: &[u8], i: usize) -> i32 {
+    i32::from_be_bytes(buf[i..i + 4].try_into().unwrap())
+}
+
+fn i64_at(buf: &[u8], i: usize) -> i64 {
+    i64::from_be_bytes(buf[i..i + 8].try_into().unwrap())
+}
+
+pub(super) fn read<R: Read + Seek>(io: &mut R, len: u64) -> Result<Props> {
+    io.seek(SeekFrom::Start(4))?;
+    let version = u16::from_be_bytes(read_array(io)?);
+    if version != 1 {
+        return Err(Error::InvalidFile);
+    }
+
+    let mut asbd = None;
+    let mut data = None;
+    let mut pakt = None;
+    let mut cookie = Vec::new();
+    let mut info = Vec::new();
+
+    let mut pos = 8u64;
+    while len.saturating_sub(pos) >= 12 {
+        io.seek(SeekFrom::Start(pos))?;
+        let header = read_array::<12, _>(io)?;
+        let id = FourCc::from_bytes(header[..4].try_into().unwrap());
+        let size = i64_at(&header, 4);
+        let remaining = len - pos - 12;
+        // only data chunk may have unknown size, it runs to the end of file
+        let size = match (&id.to_bytes(), size) {
+            (b"data", -1) => remaining,
+            (_, 0..) => size as u64,
+            _ => return Err(Error::InvalidChunk(id)),
+        };
+        match &id.to_bytes() {
+            b"desc" => asbd = Some(parse_desc(&read_payload(io, id, size, remaining)?)?),
+            b"data" => {
+                if size < 4 {
+                    return Err(Error::InvalidChunk(id));
+                }
+                // skips edit count, truncated data of unfinished recordings is allowed
+                let size = size.min(remaining).checked_sub(4);
+                data = Some((pos + 16, size.ok_or(Error::InvalidChunk(id))?));
+            }
+            b"kuki" => cookie = read_payload(io, id, size, remaining)?,
+            b"pakt" => pakt = Some(read_payload(io, id, size, remaining)?),
+            b"info" => info = read_payload(io, id, size, remaining)?,
+            _ => {}
+        }
+        pos = pos.saturating_add(size.saturating_add(12));
+    }
+
+    let asbd = asbd.ok_or(Error::InvalidChunk(DESC))?;
+    let (offset, size) = data.ok_or(Error::InvalidChunk(DATA))?;
+    let mut props = Props::new(FileTypeId::CAF, asbd);
+    props.set_data(offset, size);
+    props.cookie = cookie;
+    if let Some(pakt) = pakt {
+        parse_pakt(&pakt, &mut props)?;
+    } else if props.is_vbr() {
+        return Err(Error::InvalidChunk(PAKT));
+    }
+    parse_info(&info, &mut props);
+    Ok(props)
+}
+
+fn parse_desc(p: &[u8]) -> Result<audio::StreamBasicDesc> {
+    if p.len() < 32 {
+        return Err(Error::InvalidChunk(DESC));
+    }
+    let mut asbd = audio::StreamBasicDesc {
+        sample_rate: f64::from_be_bytes(p[..8].try_into().unwrap()),
+        format: audio::Format(u32_at(p, 8)),
+        format_flags: FormatFlags(u32_at(p, 12)),
+        bytes_per_packet: u32_at(p, 16),
+        frames_per_packet: u32_at(p, 20),
+        bytes_per_frame: 0,
+        channels_per_frame: u32_at(p, 24),
+        bits_per_channel: u32_at(p, 28),
+        reserved: 0,
+    };
+    if asbd.format == audio::Format::LINEAR_PCM {
+        let flags = asbd.format_flags.0;
+        let mut format_flags = if flags & LPCM_IS_FLOAT != 0 {
+            FormatFlags::IS_FLOAT
+        } else {
+            FormatFlags::IS_SIGNED_INTEGER
+        };
+        if flags & LPCM_IS_LITTLE_ENDIAN == 0 {
+            format_flags |= FormatFlags::IS_BIG_ENDIAN;
+        }
+        let bits = asbd.bits_per_channel as u64 * asbd.channels_per_frame as u64;
+        format_flags |= if bits == asbd.bytes_per_packet as u64 * 8 {
+            FormatFlags::IS_PACKED
+        } else {
+            FormatFlags::IS_ALIGNED_HIGH
+        };
+        asbd.format_flags = format_flags;
+        asbd.bytes_per_frame = asbd.bytes_per_packet;
+    }
+    Ok(asbd)
+}
+
+/// Big endian base 128 integer
+fn read_varint(p: &mut &[u8]) -> Result<u64> {
+    let mut res = 0u64;
+    for (i, &b) in p.iter().enumerate().take(10) {
+        res = (res << 7) | (b & 0x7F) as u64;
+        if b & 0x80 == 0 {
+            *p = &p[i + 1..];
+            return Ok(res);
+        }
+    }
+    Err(Error::InvalidChunk(PAKT))
+}
+
+fn write_varint(buf: &mut Vec<u8>, val: u64) {
+    let groups = (64 - val.leading_zeros()).div_ceil(7).max(1);
+    for i in (0..groups).rev() {
+        let b = (val >> (i * 7)) as u8 & 0x7F;
+        buf.push(if i == 0 { b } else { b | 0x80 });
+    }
+}
+
+fn parse_pakt(p: &[u8], props: &mut Props) -> Result {
+    if p.len() < 24 {
+        return Err(Error::InvalidChunk(PAKT));
+    }
+    let packets = i64_at(p, 0);
+    props.packet_table = Some(PacketTableInfo {
+        valid_frames: i64_at(p, 8),
+        priming_frames: i32_at(p, 16),
+        remainder_frames: i32_at(p, 20),
+    });
+    if !props.is_vbr() && props.asbd.frames_per_packet != 0 {
+        return Ok(());
+    }
+    if packets < 0 {
+        return Err(Error::InvalidChunk(PAKT));
+    }
+
+    let mut p = &p[24..];
+    let mut offset = 0i64;
+    // entries are at least one byte
+    props.packets = Vec::with_capacity((packets as usize).min(p.len()));
+    for _ in 0..packets {
+        let data_byte_size = if props.is_vbr() {
+            read_varint(&mut p)? as u32
+        } else {
+            props.asbd.bytes_per_packet
+        };
+        let variable_frames_in_packet = if props.asbd.frames_per_packet == 0 {
+            read_varint(&mut p)? as u32
+        } else {
+            0
+        };
+        props.packets.push(audio::StreamPacketDesc {
+            start_offset: offset,
+            variable_frames_in_packet,
+            data_byte_size,
+        });
+        offset += data_byte_size as i64;
+    }
+    if offset as u64 > props.data_size {
+        return Err(Error::InvalidChunk(PAKT));
+    }
+    props.packet_count = packets as u64;
+    Ok(())
+}
+
+/// Number of entries and key/value strings, each terminated by nul
+fn parse_info(p: &[u8], props: &mut Props) {
+    let Some(strings) = p.get(4..) else {
+        return;
+    };
+    let mut strings = strings.split(|&b| b == 0).map(super::text);
+    while let (Some(key), Some(val)) = (strings.next(), strings.next()) {
+        if key.is_empty() {
+            break;
+        }
+        props.info.insert(key, val);
+    }
+}
+
+pub(super) fn check(asbd: &audio::StreamBasicDesc) -> Result {
+    let unsupported = Err(Error::UnsupportedDataFormat(asbd.format));
+    if asbd.channels_per_frame == 0
+        || asbd.sample_rate <= 0.0
+        || asbd.format_flags.contains(FormatFlags::IS_NON_INTERLEAVED)
+    {
+        return unsupported;
+    }
+    if asbd.format == audio::Format::LINEAR_PCM
+        && (asbd.frames_per_packet != 1
+            || asbd.bytes_per_packet == 0
+            || asbd.bytes_per_packet != asbd.bytes_per_frame)
+    {
+        return unsupported;
+    }
+    Ok(())
+}
+
+pub(super) fn write_header<W: Write + Seek>(io: &mut W, props: &mut Props) -> Result {
+    let asbd = &props.asbd;
+    let format_flags = if asbd.format == audio::Format::LINEAR_PCM {
+        let flags = asbd.format_flags;
+        let mut res = 0;
+        if flags.contains(FormatFlags::IS_FLOAT) {
+            res |= LPCM_IS_FLOAT;
+        }
+        if !flags.contains(FormatFlags::IS_BIG_ENDIAN) {
+            res |= LPCM_IS_LITTLE_ENDIAN;
+        }
+        res
+    } else {
+        asbd.format_flags.0
+    };
+
+    let mut h = Vec::with_capacity(80 + props.cookie.len());
+    h.extend_from_slice(b"caff");
+    h.extend_from_slice(&1u16.to_be_bytes());
+    h.extend_from_slice(&0u16.to_be_bytes());
+
+    h.extend_from_slice(b"desc");
+    h.extend_from_slice(&32i64.to_be_bytes());
+    h.extend_from_slice(&asbd.sample_rate.to_be_bytes());
+    h.extend_from_slice(&asbd.format.0.to_be_bytes());
+    h.extend_from_slice(&format_flags.to_be_bytes());
+    h.extend_from_slice(&asbd.bytes_per_packet.to_be_bytes());
+    h.extend_from_slice(&asbd.frames_per_packet.to_be_bytes());
+    h.extend_from_slice(&asbd.channels_per_frame.to_be_bytes());
+    h.extend_from_slice(&asbd.bits_per_channel.to_be_bytes());
+
+    if !props.cookie.is_empty() {
+        h.extend_from_slice(b"kuki");
+        h.extend_from_slice(&(props.cookie.len() as i64).to_be_bytes());
+        h.extend_from_slice(&props.cookie);
+    }
+
+    // size is unknown until close, edit count is zero
+    h.extend_from_slice(b"data");
+    h.extend_from_slice(&(-1i64).to_be_bytes());
+    h.extend_from_slice(&0u32.to_be_bytes());
+
+    io.seek(SeekFrom::Start(0))?;
+    io.write_all(&h)?;
+    props.data_offset = h.len() as u64;
+    Ok(())
+}
+
+pub(super) fn finish<W: Write + Seek>(io: &mut W, props: &mut Props) -> Result {
+    io.seek(SeekFrom::Start(props.data_offset + props.data_size))?;
+
+    if props.is_vbr() || props.asbd.frames_per_packet == 0 || props.packet_table.is_some() {
+        let table = *props.packet_table.get_or_insert(PacketTableInfo {
+            valid_frames: props.frames() as i64,
+            priming_frames: 0,
+            remainder_frames: 0,
+        });
+        let mut pakt = Vec::with_capacity(24 + props.packets.len() * 2);
+        pakt.extend_from_slice(&(props.packet_count as i64).to_be_bytes());
+        pakt.extend_from_slice(&table.valid_frames.to_be_bytes());
+        pakt.extend_from_slice(&table.priming_frames.to_be_bytes());
+        pakt.extend_from_slice(&table.remainder_frames.to_be_bytes());
+        for desc in &props.packets {
+            if props.is_vbr() {
+                write_varint(&mut pakt, desc.data_byte_size as u64);
+            }
+            if props.asbd.frames_per_packet == 0 {
+                write_varint(&mut pakt, desc.variable_frames_in_packet as u64);
+            }
+        }
+        io.write_all(b"pakt")?;
+        io.write_all(&(pakt.len() as i64).to_be_bytes())?;
+        io.write_all(&pakt)?;
+    }
+
+    if !props.info.is_empty() {
+        let mut info = Vec::new();
+        info.extend_from_slice(&(props.info.len() as u32).to_be_bytes());
+        for (key, val) in &props.info {
+            info.extend_from_slice(key.as_bytes());
+            info.push(0);
+            info.extend_from_slice(val.as_bytes());
+            info.push(0);
+        }
+        io.write_all(b"info")?;
+        io.write_all(&(info.len() as i64).to_be_bytes())?;
+        io.write_all(&info)?;
+    }
+
+    io.seek(SeekFrom::Start(props.data_offset - 12))?;
+    io.write_all(&(props.data_size as i64 + 4).to_be_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        audio_file::{self, Error, PacketTableInfo, info_key},
+        cat::audio::{self, FileTypeId, FormatFlags},
+    };
+
+    #[test]
+    fn varint() {
+        for val in [0, 1, 127, 128, 300, 16_383, 16_384, u32::MAX as u64] {
+            let mut buf = Vec::new();
+            super::write_varint(&mut buf, val);
+            let mut p = &buf[..];
+            assert_eq!(super::read_varint(&mut p).unwrap(), val);
+            assert!(p.is_empty());
+        }
+        let mut buf = Vec::new();
+        super::write_varint(&mut buf, 300);
+        assert_eq!(buf, [0x82, 0x2C]);
+    }
+
+    #[test]
+    fn vbr() {
+        let asbd = audio::StreamBasicDesc {
+            sample_rate: 44_100.0,
+            format: audio::Format::MPEG4_AAC,
+            format_flags: FormatFlags(0),
+            bytes_per_packet: 0,
+            frames_per_packet: 1024,
+            bytes_per_frame: 0,
+            channels_per_frame: 2,
+            bits_per_channel: 0,
+            reserved: 0,
+        };
+        let data: Vec<u8> = (0..100).collect();
+        let descs = [
+            audio::StreamPacketDesc {
+                start_offset: 0,
+                variable_frames_in_packet: 0,
+                data_byte_size: 10,
+            },
+            audio::StreamPacketDesc {
+                start_offset: 10,
+                variable_frames_in_packet: 0,
+                data_byte_size: 200,
+            },
+            audio::StreamPacketDesc {
+                start_offset: 50,
+                variable_frames_in_packet: 0,
+                data_byte_size: 50,
+            },
+        ];
+        let table = PacketTableInfo {
+            valid_frames: 2048 - 500,
+            priming_frames: 400,
+            remainder_frames: 100,
+        };
+
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut f = audio_file::Writer::new(&mut buf, FileTypeId::CAF, &asbd).unwrap();
+            f.set_magic_cookie_data(&[1, 2, 3]).unwrap();
+            f.set_info(info_key::TITLE, "vbr");
+            f.set_info(info_key::TEMPO, "120");
+            assert!(matches!(
+                f.write_packets(&data, None, 0, 1),
+                Err(Error::MissingPacketDescs)
+            ));
+            assert!(matches!(
+                f.write_packets(&data, Some(&descs), 0, 2),
+                Err(Error::BufTooSmall { required: 210, .. })
+            ));
+            f.write_packets(&data, Some(&descs[..1]), 0, 1).unwrap();
+            assert!(matches!(
+                f.set_magic_cookie_data(&[1]),
+                Err(Error::OperationNotSupported)
+            ));
+            f.write_packets(&data, Some(&descs[2..]), 1, 1).unwrap();
+            f.set_packet_table_info(table).unwrap();
+            f.close().unwrap();
+            assert_eq!(f.data_packet_count(), 2);
+        }
+
+        let mut f = audio_file::Reader::new(buf).unwrap();
+        assert_eq!(f.file_format(), FileTypeId::CAF);
+        assert_eq!(f.data_format(), asbd);
+        assert_eq!(f.magic_cookie_data(), [1, 2, 3]);
+        assert_eq!(f.packet_table_info(), Some(table));
+        assert_eq!(f.data_packet_count(), 2);
+        assert_eq!(f.data_byte_count(), 60);
+        assert_eq!(f.maximum_packet_size(), 50);
+        let info = f.info_dictionary();
+        assert_eq!(info[info_key::TITLE], "vbr");
+        assert_eq!(info[info_key::TEMPO], "120");
+        assert_eq!(info[info_key::APPROXIMATE_DURATION_IN_SECONDS], "0.035");
+
+        let mut out = [0u8; 64];
+        let mut out_descs = [audio::StreamPacketDesc::default(); 4];
+        assert!(matches!(
+            f.read_packets(&mut out, None, 0, 2),
+            Err(Error::MissingPacketDescs)
+        ));
+        assert_eq!(
+            f.read_packets(&mut out, Some(&mut out_descs), 0, 4)
+                .unwrap(),
+            (60, 2)
+        );
+        assert_eq!(out[..10], data[..10]);
+        assert_eq!(out[10..60], data[50..]);
+        assert_eq!(out_descs[1].start_offset, 10);
+        assert_eq!(out_descs[1].data_byte_size, 50);
+
+        // only whole packets fit
+        assert_eq!(
+            f.read_packets(&mut out[..20], Some(&mut out_descs), 0, 2)
+                .unwrap(),
+            (10, 1)
+        );
+        assert_eq!(
+            f.read_packets(&mut out, Some(&mut out_descs), 1, 2)
+                .unwrap(),
+            (50, 1)
+        );
+        assert_eq!(out[..50], data[50..]);
+    }
+
+    #[test]
+    fn lpcm() {
+        let asbd = audio::StreamBasicDesc {
+            sample_rate: 96_000.0,
+            format: audio::Format::LINEAR_PCM,
+            format_flags: FormatFlags::IS_FLOAT | FormatFlags::IS_PACKED,
+            bytes_per_packet: 8,
+            frames_per_packet: 1,
+            bytes_per_frame: 8,
+            channels_per_frame: 2,
+            bits_per_channel: 32,
+            reserved: 0,
+        };
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut f = audio_file::Writer::new(&mut buf, FileTypeId::CAF, &asbd).unwrap();
+            f.write_packets(&[7u8; 80], None, 0, 10).unwrap();
+        }
+        let f = audio_file::Reader::new(buf).unwrap();
+        assert_eq!(f.data_format(), asbd);
+        assert_eq!(f.data_packet_count(), 10);
+        assert_eq!(f.packet_table_info(), None);
+        assert!(f.magic_cookie_data().is_empty());
+
+        let non_interleaved = audio::StreamBasicDesc {
+            format_flags: asbd.format_flags | FormatFlags::IS_NON_INTERLEAVED,
+            ..asbd
+        };
+        assert!(matches!(
+            audio_file::Writer::new(Cursor::new(Vec::new()), FileTypeId::CAF, &non_interleaved),
+            Err(Error::UnsupportedDataFormat(audio::Format::LINEAR_PCM))
+        ));
+    }
+
+    #[test]
+    fn malformed() {
+        let mut caf = b"caff\0\x01\0\0desc".to_vec();
+        caf.extend_from_slice(&32i64.to_be_bytes());
+        caf.extend_from_slice(&44_100f64.to_be_bytes());
+        caf.extend_from_slice(b"lpcm");
+        // flags, bytes per packet, frames per packet, channels, bits
+        for v in [0, u32::MAX, 1, 2, u32::MAX] {
+            caf.extend_from_slice(&v.to_be_bytes());
+        }
+        // data chunk runs past the end of file
+        caf.extend_from_slice(b"data");
+        caf.extend_from_slice(&8i64.to_be_bytes());
+        caf.extend_from_slice(&[0, 0]);
+        assert!(matches!(
+            audio_file::Reader::new(Cursor::new(caf)),
+            Err(Error::InvalidChunk(super::DATA))
+        ));
+    }
+}
@@ -0,0 +1,501 @@
+//! RIFF WAVE, RF64 and BW64
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+use crate::{
+    FourCc,
+    cat::audio::{self, FileTypeId, FormatFlags},
+};
+
+use super::{Error, Props, Result, info_key, read_array, read_payload, text};
+
+const PCM: u16 = 1;
+const IEEE_FLOAT: u16 = 3;
+const A_LAW: u16 = 6;
+const MU_LAW: u16 = 7;
+const EXTENSIBLE: u16 = 0xFFFE;
+
+/// `KSDATAFORMAT_SUBTYPE_*` GUID after format tag
+const SUBTYPE_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+const FMT: FourCc = FourCc::from_bytes(b"fmt ");
+const DATA: FourCc = FourCc::from_bytes(b"data");
+const DS64: FourCc = FourCc::from_bytes(b"ds64");
+
+/// `LIST` `INFO` subchunks
+const INFO: [(&[u8; 4], &str); 9] = [
+    (b"INAM", info_key::TITLE),
+    (b"IART", info_key::ARTIST),
+    (b"IPRD", info_key::ALBUM),
+    (b"ICMT", info_key::COMMENTS),
+    (b"ICOP", info_key::COPYRIGHT),
+    (b"ICRD", info_key::RECORDED_DATE),
+    (b"IGNR", info_key::GENRE),
+    (b"ISFT", info_key::ENCODING_APPLICATION),
+    (b"ITRK", info_key::TRACK_NUMBER),
+];
+
+fn u16_at(buf: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([buf[i], buf[i + 1]])
+}
+
+fn u32_at(buf: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes(buf[i..i + 4].try_into().unwrap())
+}
+
+fn u64_at(buf: &[u8], i: usize) -> u64 {
+    u64::from_le_bytes(buf[i..i + 8].try_into().unwrap())
+}
+
+pub(super) fn read<R: Read + Seek>(io: &mut R, sig: &[u8; 12], len: u64) -> Result<Props> {
+    let type_id = match &sig[..4] {
+        b"RIFF" => FileTypeId::WAVE,
+        b"RF64" => FileTypeId::RF64,
+        _ => FileTypeId::BW64,
+    };
+    let mut ds64_data_size = None;
+    let mut asbd = None;
+    let mut data = None;
+    let mut info = BTreeMap::new();
+
+    let mut pos = 12u64;
+    while len.saturating_sub(pos) >= 8 {
+        io.seek(SeekFrom::Start(pos))?;
+        let header = read_array::<8, _>(io)?;
+        let id = FourCc::from_bytes(header[..4].try_into().unwrap());
+        let mut size = u32_at(&header, 4) as u64;
+        let remaining = len - pos - 8;
+        match &id.to_bytes() {
+            b"ds64" => {
+                let p = read_payload(io, id, size, remaining)?;
+                if p.len() < 24 {
+                    return Err(Error::InvalidChunk(id));
+                }
+                ds64_data_size = Some(u64_at(&p, 8));
+            }
+            b"fmt " => asbd = Some(parse_fmt(&read_payload(io, id, size, remaining)?)?),
+            b"data" => {
+                if size == u32::MAX as u64 && type_id != FileTypeId::WAVE {
+                    size = ds64_data_size.ok_or(Error::InvalidChunk(DS64))?;
+                }
+                // recordings which were not closed have truncated data
+                data = Some((pos + 8, size.min(remaining)));
+            }
+            b"LIST" => {
+                let p = read_payload(io, id, size, remaining)?;
+                if p.starts_with(b"INFO") {
+                    parse_info(&p[4..], &mut info);
+                }
+            }
+            _ => {}
+        }
+        pos = pos.saturating_add(size.saturating_add(8 + (size & 1)));
+    }
+
+    let asbd = asbd.ok_or(Error::InvalidChunk(FMT))?;
+    let (offset, size) = data.ok_or(Error::InvalidChunk(DATA))?;
+    let mut props = Props::new(type_id, asbd);
+    props.set_data(offset, size);
+    props.info = info;
+    Ok(props)
+}
+
+fn parse_fmt(p: &[u8]) -> Result<audio::StreamBasicDesc> {
+    if p.len() < 16 {
+        return Err(Error::InvalidChunk(FMT));
+    }
+    let mut tag = u16_at(p, 0);
+    let channels = u16_at(p, 2) as u32;
+    let sample_rate = u32_at(p, 4) as f64;
+    let block_align = u16_at(p, 12) as u32;
+    let mut bits = u16_at(p, 14) as u32;
+    if tag == EXTENSIBLE && p.len() >= 40 {
+        let valid_bits = u16_at(p, 18) as u32;
+        if valid_bits != 0 {
+            bits = valid_bits;
+        }
+        tag = u16_at(p, 24);
+    }
+    if channels == 0 || block_align == 0 {
+        return Err(Error::InvalidChunk(FMT));
+    }
+    let container = block_align / channels * 8;
+    let (format, format_flags) = match tag {
+        PCM => {
+            let mut flags = if container == 8 {
+                FormatFlags(0)
+            } else {
+                FormatFlags::IS_SIGNED_INTEGER
+            };
+            // samples with less valid bits are left justified
+            flags |= if bits == container {
+                FormatFlags::IS_PACKED
+            } else {
+                FormatFlags::IS_ALIGNED_HIGH
+            };
+            (audio::Format::LINEAR_PCM, flags)
+        }
+        IEEE_FLOAT => (
+            audio::Format::LINEAR_PCM,
+            FormatFlags::IS_FLOAT | FormatFlags::IS_PACKED,
+        ),
+        A_LAW => (audio::Format::A_LAW, FormatFlags(0)),
+        MU_LAW => (audio::Format::U_LAW, FormatFlags(0)),
+        tag => {
+            // AudioToolbox maps other tags to 'ms\0\0' | tag
+            return Err(Error::UnsupportedDataFormat(audio::Format(
+                0x6D73_0000 | tag as u32,
+            )));
+        }
+    };
+    Ok(audio::StreamBasicDesc {
+        sample_rate,
+        format,
+        format_flags,
+        bytes_per_packet: block_align,
+        frames_per_packet: 1,
+        bytes_per_frame: block_align,
+        channels_per_frame: channels,
+        bits_per_channel: bits,
+        reserved: 0,
+    })
+}
+
+fn parse_info(mut p: &[u8], info: &mut BTreeMap<String, String>) {
+    while p.len() >= 8 {
+        let id = &p[..4];
+        let size = u32_at(p, 4) as usize;
+        let Some(value) = p.get(8..8 + size) else {
+            break;
+        };
+        if let Some((_, key)) = INFO.iter().find(|(i, _)| *i == id) {
+            info.insert(key.to_string(), text(value));
+        }
+        p = p.get(8 + size + (size & 1)..).unwrap_or_default();
+    }
+}
+
+/// Format tag of data format
+pub(super) fn check(asbd: &audio::StreamBasicDesc) -> Result<u16> {
+    let flags = asbd.format_flags;
+    let unsupported = Err(Error::UnsupportedDataFormat(asbd.format));
+    if asbd.channels_per_frame == 0
+        || asbd.channels_per_frame > u16::MAX as u32
+        || asbd.frames_per_packet != 1
+        || asbd.bytes_per_frame == 0
+        || asbd.bytes_per_packet != asbd.bytes_per_frame
+        || asbd.bytes_per_frame > u16::MAX as u32
+        || asbd.bytes_per_frame % asbd.channels_per_frame != 0
+        || flags.contains(FormatFlags::IS_NON_INTERLEAVED)
+    {
+        return unsupported;
+    }
+    // sample rate and byte rate are u32, block align and sample sizes u16
+    let rate = asbd.sample_rate.round();
+    if !(1.0..=u32::MAX as f64).contains(&rate)
+        || (rate as u32).checked_mul(asbd.bytes_per_frame).is_none()
+    {
+        return unsupported;
+    }
+    let container = asbd.bytes_per_frame / asbd.channels_per_frame * 8;
+    if container > u16::MAX as u32 {
+        return unsupported;
+    }
+    match asbd.format {
+        audio::Format::LINEAR_PCM if !flags.contains(FormatFlags::IS_BIG_ENDIAN) => {
+            if flags.contains(FormatFlags::IS_FLOAT) {
+                if matches!(asbd.bits_per_channel, 32 | 64) && container == asbd.bits_per_channel {
+                    return Ok(IEEE_FLOAT);
+                }
+                return unsupported;
+            }
+            // 8 bit samples are unsigned, wider are signed
+            let signed = flags.contains(FormatFlags::IS_SIGNED_INTEGER);
+            if signed == (container > 8) && asbd.bits_per_channel <= container {
+                Ok(PCM)
+            } else {
+                unsupported
+            }
+        }
+        audio::Format::A_LAW if container == 8 => Ok(A_LAW),
+        audio::Format::U_LAW if container == 8 => Ok(MU_LAW),
+        _ => unsupported,
+    }
+}
+
+pub(super) fn write_header<W: Write + Seek>(io: &mut W, props: &mut Props) -> Result {
+    let asbd = &props.asbd;
+    let tag = check(asbd)?;
+    let channels = asbd.channels_per_frame;
+    let block_align = asbd.bytes_per_frame;
+    let container = block_align / channels * 8;
+    let rate = asbd.sample_rate.round() as u32;
+
+    let mut h = Vec::with_capacity(128);
+    if props.type_id == FileTypeId::WAVE {
+        h.extend_from_slice(b"RIFF");
+        h.extend_from_slice(&0u32.to_le_bytes());
+        h.extend_from_slice(b"WAVE");
+        // reserves space for ds64 in case data grows past 4 GiB
+        h.extend_from_slice(b"JUNK");
+    } else {
+        h.extend_from_slice(&FourCc::from(props.type_id).to_bytes());
+        h.extend_from_slice(&u32::MAX.to_le_bytes());
+        h.extend_from_slice(b"WAVE");
+        h.extend_from_slice(b"ds64");
+    }
+    h.extend_from_slice(&28u32.to_le_bytes());
+    h.extend_from_slice(&[0u8; 28]);
+
+    let extensible = tag != A_LAW
+        && tag != MU_LAW
+        && (channels > 2 || container > 16 || container != asbd.bits_per_channel);
+    let mut fmt = Vec::with_capacity(40);
+    fmt.extend_from_slice(&(if extensible { EXTENSIBLE } else { tag }).to_le_bytes());
+    fmt.extend_from_slice(&(channels as u16).to_le_bytes());
+    fmt.extend_from_slice(&rate.to_le_bytes());
+    fmt.extend_from_slice(&(rate * block_align).to_le_bytes());
+    fmt.extend_from_slice(&(block_align as u16).to_le_bytes());
+    fmt.extend_from_slice(&(container as u16).to_le_bytes());
+    if extensible {
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&(asbd.bits_per_channel as u16).to_le_bytes());
+        // unspecified channel mask
+        fmt.extend_from_slice(&0u32.to_le_bytes());
+        fmt.extend_from_slice(&tag.to_le_bytes());
+        fmt.extend_from_slice(&SUBTYPE_TAIL);
+    } else if tag != PCM {
+        fmt.extend_from_slice(&0u16.to_le_bytes());
+    }
+    h.extend_from_slice(b"fmt ");
+    h.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    h.extend_from_slice(&fmt);
+
+    h.extend_from_slice(b"data");
+    let data_size = if props.type_id == FileTypeId::WAVE {
+        0
+    } else {
+        u32::MAX
+    };
+    h.extend_from_slice(&data_size.to_le_bytes());
+
+    io.seek(SeekFrom::Start(0))?;
+    io.write_all(&h)?;
+    props.data_offset = h.len() as u64;
+    Ok(())
+}
+
+pub(super) fn finish<W: Write + Seek>(io: &mut W, props: &mut Props) -> Result {
+    io.seek(SeekFrom::Start(props.data_offset + props.data_size))?;
+    if props.data_size & 1 != 0 {
+        io.write_all(&[0])?;
+    }
+
+    let mut list = Vec::new();
+    for (id, key) in INFO {
+        if let Some(val) = props.info.get(key) {
+            let size = val.len() as u32 + 1;
+            list.extend_from_slice(id);
+            list.extend_from_slice(&size.to_le_bytes());
+            list.extend_from_slice(val.as_bytes());
+            list.push(0);
+            if size & 1 != 0 {
+                list.push(0);
+            }
+        }
+    }
+    if !list.is_empty() {
+        io.write_all(b"LIST")?;
+        io.write_all(&(list.len() as u32 + 4).to_le_bytes())?;
+        io.write_all(b"INFO")?;
+        io.write_all(&list)?;
+    }
+
+    let riff_size = io.stream_position()? - 8;
+    if props.type_id == FileTypeId::WAVE && riff_size > u32::MAX as u64 {
+        props.type_id = FileTypeId::RF64;
+    }
+    if props.type_id == FileTypeId::WAVE {
+        io.seek(SeekFrom::Start(4))?;
+        io.write_all(&(riff_size as u32).to_le_bytes())?;
+        io.seek(SeekFrom::Start(props.data_offset - 4))?;
+        io.write_all(&(props.data_size as u32).to_le_bytes())?;
+    } else {
+        let mut ds64 = Vec::with_capacity(44);
+        ds64.extend_from_slice(&FourCc::from(props.type_id).to_bytes());
+        ds64.extend_from_slice(&u32::MAX.to_le_bytes());
+        ds64.extend_from_slice(b"WAVEds64");
+        ds64.extend_from_slice(&28u32.to_le_bytes());
+        ds64.extend_from_slice(&riff_size.to_le_bytes());
+        ds64.extend_from_slice(&props.data_size.to_le_bytes());
+        ds64.extend_from_slice(&props.packet_count.to_le_bytes());
+        ds64.extend_from_slice(&0u32.to_le_bytes());
+        io.seek(SeekFrom::Start(0))?;
+        io.write_all(&ds64)?;
+        io.seek(SeekFrom::Start(props.data_offset - 4))?;
+        io.write_all(&u32::MAX.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{
+        audio_file::{self, Error, info_key},
+        cat::audio::{self, FileTypeId, FormatFlags},
+    };
+
+    fn roundtrip(
+        type_id: FileTypeId,
+        asbd: &audio::StreamBasicDesc,
+    ) -> audio_file::Reader<Cursor<Vec<u8>>> {
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut f = audio_file::Writer::new(&mut buf, type_id, asbd).unwrap();
+            f.set_info(info_key::ARTIST, "cidre");
+            f.set_info(info_key::TEMPO, "120");
+            let data = vec![1u8; asbd.bytes_per_packet as usize * 3];
+            f.write_packets(&data, None, 0, 3).unwrap();
+        }
+        audio_file::Reader::new(buf).unwrap()
+    }
+
+    #[test]
+    fn formats() {
+        let mut asbd = audio::StreamBasicDesc {
+            sample_rate: 48_000.0,
+            format: audio::Format::LINEAR_PCM,
+            format_flags: FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_PACKED,
+            bytes_per_packet: 18,
+            frames_per_packet: 1,
+            bytes_per_frame: 18,
+            channels_per_frame: 6,
+            bits_per_channel: 24,
+            reserved: 0,
+        };
+        let f = roundtrip(FileTypeId::WAVE, &asbd);
+        assert_eq!(f.file_format(), FileTypeId::WAVE);
+        assert_eq!(f.data_format(), asbd);
+        assert_eq!(f.data_packet_count(), 3);
+        assert_eq!(f.data_byte_count(), 54);
+        let info = f.info_dictionary();
+        assert_eq!(info[info_key::ARTIST], "cidre");
+        assert!(!info.contains_key(info_key::TEMPO));
+        assert_eq!(info[info_key::APPROXIMATE_DURATION_IN_SECONDS], "0.000");
+
+        // 20 bits in 24 bit containers
+        asbd.bits_per_channel = 20;
+        asbd.format_flags = FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_ALIGNED_HIGH;
+        assert_eq!(roundtrip(FileTypeId::WAVE, &asbd).data_format(), asbd);
+
+        asbd.format_flags = FormatFlags::IS_FLOAT | FormatFlags::IS_PACKED;
+        asbd.bits_per_channel = 32;
+        asbd.channels_per_frame = 1;
+        asbd.bytes_per_frame = 4;
+        asbd.bytes_per_packet = 4;
+        let f = roundtrip(FileTypeId::RF64, &asbd);
+        assert_eq!(f.file_format(), FileTypeId::RF64);
+        assert_eq!(f.data_format(), asbd);
+        assert_eq!(f.data_byte_count(), 12);
+
+        asbd.format = audio::Format::U_LAW;
+        asbd.format_flags = FormatFlags(0);
+        asbd.bits_per_channel = 8;
+        asbd.bytes_per_frame = 1;
+        asbd.bytes_per_packet = 1;
+        assert_eq!(roundtrip(FileTypeId::WAVE, &asbd).data_format(), asbd);
+
+        asbd.format = audio::Format::LINEAR_PCM;
+        asbd.format_flags = FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_BIG_ENDIAN;
+        asbd.bits_per_channel = 16;
+        asbd.bytes_per_frame = 2;
+        asbd.bytes_per_packet = 2;
+        assert!(matches!(
+            audio_file::Writer::new(Cursor::new(Vec::new()), FileTypeId::WAVE, &asbd),
+            Err(Error::UnsupportedDataFormat(audio::Format::LINEAR_PCM))
+        ));
+    }
+
+    #[test]
+    fn external() {
+        // 8 bit unsigned mono, 3 frames, truncated data size of unfinished recording
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF\0\0\0\0WAVEfmt \x10\0\0\0");
+        wav.extend_from_slice(&[1, 0, 1, 0, 0x40, 0x1F, 0, 0, 0x40, 0x1F, 0, 0, 1, 0, 8, 0]);
+        wav.extend_from_slice(b"LIST\x12\0\0\0INFOINAM\x05\0\0\0test\0\0");
+        wav.extend_from_slice(b"data\xFF\xFF\xFF\x7F");
+        wav.extend_from_slice(&[0x80, 0x81, 0x82]);
+
+        let mut f = audio_file::Reader::new(Cursor::new(wav)).unwrap();
+        let asbd = f.data_format();
+        assert_eq!(asbd.sample_rate, 8000.0);
+        assert_eq!(asbd.format_flags, FormatFlags::IS_PACKED);
+        assert_eq!(asbd.bits_per_channel, 8);
+        assert_eq!(f.data_packet_count(), 3);
+        assert_eq!(f.info_dictionary()[info_key::TITLE], "test");
+        let mut buf = [0u8; 4];
+        assert_eq!(f.read_packets(&mut buf, None, 0, 4).unwrap(), (3, 3));
+        assert_eq!(buf[..3], [0x80, 0x81, 0x82]);
+    }
+
+    #[test]
+    fn malformed() {
+        // ds64 data size which overflows chunk position
+        let mut wav = b"RF64\xFF\xFF\xFF\xFFWAVEds64\x1C\0\0\0".to_vec();
+        wav.extend_from_slice(&0u64.to_le_bytes());
+        wav.extend_from_slice(&(u64::MAX - 4).to_le_bytes());
+        wav.extend_from_slice(&[0; 12]);
+        wav.extend_from_slice(b"fmt \x10\0\0\0");
+        wav.extend_from_slice(&[1, 0, 1, 0, 0x40, 0x1F, 0, 0, 0x40, 0x1F, 0, 0, 1, 0, 8, 0]);
+        wav.extend_from_slice(b"data\xFF\xFF\xFF\xFF");
+        wav.extend_from_slice(&[0x80, 0x81]);
+        let f = audio_file::Reader::new(Cursor::new(wav)).unwrap();
+        assert_eq!(f.data_byte_count(), 2);
+
+        // rates and sizes which don't fit in fmt chunk fields
+        let asbd = audio::StreamBasicDesc {
+            sample_rate: 48_000.0,
+            format: audio::Format::LINEAR_PCM,
+            format_flags: FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_PACKED,
+            bytes_per_packet: 2,
+            frames_per_packet: 1,
+            bytes_per_frame: 2,
+            channels_per_frame: 1,
+            bits_per_channel: 16,
+            reserved: 0,
+        };
+        let wide = 0x1_0000 * 2;
+        for asbd in [
+            audio::StreamBasicDesc {
+                sample_rate: 3e9,
+                ..asbd
+            },
+            audio::StreamBasicDesc {
+                sample_rate: 1e10,
+                ..asbd
+            },
+            audio::StreamBasicDesc {
+                sample_rate: f64::NAN,
+                ..asbd
+            },
+            audio::StreamBasicDesc {
+                bytes_per_packet: wide,
+                bytes_per_frame: wide,
+                channels_per_frame: 2,
+                ..asbd
+            },
+        ] {
+            assert!(matches!(
+                audio_file::Writer::new(Cursor::new(Vec::new()), FileTypeId::WAVE, &asbd),
+                Err(Error::UnsupportedDataFormat(audio::Format::LINEAR_PCM))
+            ));
+        }
+    }
+}
@@ -2219,7 +2219,7 @@ pub mod notifications {
 
     /// Posted when a device becomes unavailable on the system.
     #[doc(alias = "AVCaptureDeviceWasDisconnectedNotification")]
-    #[api::available(macos = 10.7, ios = 4.0, maccatalyst = 14.0, tvos = 17)]
+    #[api::available(macos = 10.7, ios = 4.0, maccatalyst = 14.0, tvos = 17.0)]
     pub fn was_disconnected() -> &'static ns::NotificationName {
         unsafe { AVCaptureDeviceWasDisconnectedNotification }
     }
@@ -2237,7 +2237,7 @@ pub mod notifications {
     unsafe extern "C" {
         #[api::available(macos = 10.7, ios = 4.0, maccatalyst = 14.0, tvos = 17.0)]
         static AVCaptureDeviceWasConnectedNotification: &'static ns::NotificationName;
-        #[api::available(macos = 10.7, ios = 4.0, maccatalyst = 14.0, tvos = 17)]
+        #[api::available(macos = 10.7, ios = 4.0, maccatalyst = 14.0, tvos = 17.0)]
         static AVCaptureDeviceWasDisconnectedNotification: &'static ns::NotificationName;
         #[api::available(ios = 5.0, maccatalyst = 14.0, tvos = 17.0)]
         static AVCaptureDeviceSubjectAreaDidChangeNotification: &'static ns::NotificationName;
@@ -4,9 +4,11 @@ use crate::cg;
 use crate::{arc, cm, ns, objc};
 
 impl cg::Rect {
+    /// Same as [`cg::Rect::aspect_fit`]
     #[doc(alias = "AVMakeRectWithAspectRatioInsideRect")]
+    #[inline]
     pub fn with_aspect_ratio_inside_rect(aspect_ratio: cg::Size, bounding_rect: cg::Rect) -> Self {
-        unsafe { AVMakeRectWithAspectRatioInsideRect(aspect_ratio, bounding_rect) }
+        bounding_rect.aspect_fit(aspect_ratio)
     }
 }
 
@@ -22,13 +24,6 @@ impl ns::Value {
     pub fn cm_video_dimensions_value(&self) -> cm::VideoDimensions;
 }
 
-unsafe extern "C-unwind" {
-    fn AVMakeRectWithAspectRatioInsideRect(
-        aspect_ratio: cg::Size,
-        bounding_rect: cg::Rect,
-    ) -> cg::Rect;
-}
-
 #[cfg(test)]
 mod tests {
     use crate::{cg, cm, ns};
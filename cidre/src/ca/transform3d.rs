@@ -30,87 +30,380 @@ pub struct Transform3d {
 
 impl Transform3d {
     /// The identity transform: [1 0 0 0; 0 1 0 0; 0 0 1 0; 0 0 0 1].
+    #[doc(alias = "CATransform3DIdentity")]
     #[inline]
-    pub fn identity() -> Self {
-        unsafe { CATransform3DIdentity }
+    pub const fn identity() -> Self {
+        Self::new_scale(1.0, 1.0, 1.0)
     }
 
     /// Returns a transform that translates by '(tx, ty, tz)':
     /// self =  [1 0 0 0; 0 1 0 0; 0 0 1 0; tx ty tz 1].
+    #[doc(alias = "CATransform3DMakeTranslation")]
     #[inline]
-    pub fn new_translation(tx: f64, ty: f64, tz: f64) -> Self {
-        unsafe { CATransform3DMakeTranslation(tx, ty, tz) }
+    pub const fn new_translation(tx: f64, ty: f64, tz: f64) -> Self {
+        let mut t = Self::identity();
+        t.m41 = tx;
+        t.m42 = ty;
+        t.m43 = tz;
+        t
     }
 
     /// Returns a transform that scales by `(sx, sy, sz)':
     /// self = [sx 0 0 0; 0 sy 0 0; 0 0 sz 0; 0 0 0 1].
+    #[doc(alias = "CATransform3DMakeScale")]
     #[inline]
-    pub fn new_scale(sx: f64, sy: f64, sz: f64) -> Self {
-        unsafe { CATransform3DMakeScale(sx, sy, sz) }
+    pub const fn new_scale(sx: f64, sy: f64, sz: f64) -> Self {
+        Self::with_rows([
+            [sx, 0.0, 0.0, 0.0],
+            [0.0, sy, 0.0, 0.0],
+            [0.0, 0.0, sz, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
     }
 
     /// Returns a transform that rotates by 'angle' radians about the vector
     /// '(x, y, z)'. If the vector has length zero the identity transform is
     /// returned.
-    #[inline]
+    #[doc(alias = "CATransform3DMakeRotation")]
     pub fn new_rotation(angle: f64, x: f64, y: f64, z: f64) -> Self {
-        unsafe { CATransform3DMakeRotation(angle, x, y, z) }
+        let len = (x * x + y * y + z * z).sqrt();
+        if len == 0.0 {
+            return Self::identity();
+        }
+        let (x, y, z) = (x / len, y / len, z / len);
+        let (s, c) = angle.sin_cos();
+        let t = 1.0 - c;
+        Self::with_rows([
+            [t * x * x + c, t * x * y + z * s, t * x * z - y * s, 0.0],
+            [t * x * y - z * s, t * y * y + c, t * y * z + x * s, 0.0],
+            [t * x * z + y * s, t * y * z - x * s, t * z * z + c, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Rows `[m11 m12 m13 m14]` to `[m41 m42 m43 m44]`, points are row vectors.
+    #[inline]
+    pub const fn with_rows(rows: [[f64; 4]; 4]) -> Self {
+        let [
+            [m11, m12, m13, m14],
+            [m21, m22, m23, m24],
+            [m31, m32, m33, m34],
+            [m41, m42, m43, m44],
+        ] = rows;
+        Self {
+            m11,
+            m12,
+            m13,
+            m14,
+            m21,
+            m22,
+            m23,
+            m24,
+            m31,
+            m32,
+            m33,
+            m34,
+            m41,
+            m42,
+            m43,
+            m44,
+        }
+    }
+
+    #[inline]
+    pub const fn rows(&self) -> [[f64; 4]; 4] {
+        [
+            [self.m11, self.m12, self.m13, self.m14],
+            [self.m21, self.m22, self.m23, self.m24],
+            [self.m31, self.m32, self.m33, self.m34],
+            [self.m41, self.m42, self.m43, self.m44],
+        ]
     }
 
+    #[doc(alias = "CATransform3DMakeAffineTransform")]
     #[inline]
     pub fn from_cg_affine_transform(m: cg::AffineTransform) -> Self {
-        unsafe { CATransform3DMakeAffineTransform(m) }
+        Self::with_rows([
+            [m.a as _, m.b as _, 0.0, 0.0],
+            [m.c as _, m.d as _, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [m.tx as _, m.ty as _, 0.0, 1.0],
+        ])
     }
 
     /// Returns true if 'self' is the identity transform.
+    #[doc(alias = "CATransform3DIsIdentity")]
     #[inline]
     pub fn is_identity(&self) -> bool {
-        unsafe { CATransform3DIsIdentity(*self) }
+        *self == Self::identity()
     }
 
+    /// Translates before applying `self`
+    #[doc(alias = "CATransform3DTranslate")]
     #[inline]
     pub fn translate(&self, tx: f64, ty: f64, tz: f64) -> Self {
-        unsafe { CATransform3DTranslate(*self, tx, ty, tz) }
+        Self::new_translation(tx, ty, tz).concat(self)
     }
 
+    /// Scales before applying `self`
+    #[doc(alias = "CATransform3DScale")]
     #[inline]
     pub fn scale(&self, sx: f64, sy: f64, sz: f64) -> Self {
-        unsafe { CATransform3DScale(*self, sx, sy, sz) }
+        Self::new_scale(sx, sy, sz).concat(self)
     }
 
+    /// Rotates before applying `self`
+    #[doc(alias = "CATransform3DRotate")]
     #[inline]
     pub fn rotate(&self, angle: f64, x: f64, y: f64, z: f64) -> Self {
-        unsafe { CATransform3DRotate(*self, angle, x, y, z) }
+        Self::new_rotation(angle, x, y, z).concat(self)
     }
 
-    #[inline]
+    /// Applies `self` first and `other` second, same as `self * other`
+    #[doc(alias = "CATransform3DConcat")]
     pub fn concat(&self, other: &Transform3d) -> Self {
-        unsafe { CATransform3DConcat(*self, *other) }
+        let (a, b) = (self.rows(), other.rows());
+        Self::with_rows(std::array::from_fn(|i| {
+            std::array::from_fn(|j| (0..4).map(|k| a[i][k] * b[k][j]).sum())
+        }))
     }
 
+    /// Returns true if 'self' can be exactly represented by an affine transform.
+    #[doc(alias = "CATransform3DIsAffine")]
     #[inline]
     pub fn is_affine(&self) -> bool {
-        unsafe { CATransform3DIsAffine(*self) }
+        self.m13 == 0.0
+            && self.m14 == 0.0
+            && self.m23 == 0.0
+            && self.m24 == 0.0
+            && self.m31 == 0.0
+            && self.m32 == 0.0
+            && self.m33 == 1.0
+            && self.m34 == 0.0
+            && self.m43 == 0.0
+            && self.m44 == 1.0
     }
 
+    /// The affine part of `self`, the other values are ignored.
+    #[doc(alias = "CATransform3DGetAffineTransform")]
     #[inline]
     pub fn to_affine_transform(&self) -> cg::AffineTransform {
-        unsafe { CATransform3DGetAffineTransform(*self) }
+        cg::AffineTransform {
+            a: self.m11 as _,
+            b: self.m12 as _,
+            c: self.m21 as _,
+            d: self.m22 as _,
+            tx: self.m41 as _,
+            ty: self.m42 as _,
+        }
+    }
+}
+
+impl Default for Transform3d {
+    #[inline]
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Same as [`Transform3d::concat`], `a * b` applies `a` first
+impl std::ops::Mul for Transform3d {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self.concat(&rhs)
+    }
+}
+
+impl std::ops::MulAssign for Transform3d {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.concat(&rhs)
+    }
+}
+
+impl From<cg::AffineTransform> for Transform3d {
+    #[inline]
+    fn from(m: cg::AffineTransform) -> Self {
+        Self::from_cg_affine_transform(m)
     }
 }
 
-#[link(name = "QuartzCore", kind = "framework")]
-unsafe extern "C-unwind" {
-    static CATransform3DIdentity: Transform3d;
-    fn CATransform3DIsIdentity(t: Transform3d) -> bool;
-    fn CATransform3DMakeTranslation(tx: f64, ty: f64, tz: f64) -> Transform3d;
-    fn CATransform3DMakeScale(sx: f64, sy: f64, sz: f64) -> Transform3d;
-    fn CATransform3DMakeRotation(angle: f64, x: f64, y: f64, z: f64) -> Transform3d;
-    fn CATransform3DTranslate(t: Transform3d, tx: f64, ty: f64, tz: f64) -> Transform3d;
-    fn CATransform3DScale(t: Transform3d, sx: f64, sy: f64, sz: f64) -> Transform3d;
-    fn CATransform3DRotate(t: Transform3d, angle: f64, x: f64, y: f64, z: f64) -> Transform3d;
-    fn CATransform3DConcat(a: Transform3d, b: Transform3d) -> Transform3d;
-    fn CATransform3DMakeAffineTransform(m: cg::AffineTransform) -> Transform3d;
-    fn CATransform3DIsAffine(t: Transform3d) -> bool;
-    fn CATransform3DGetAffineTransform(t: Transform3d) -> cg::AffineTransform;
+/// Rows of `Transform3d` are the columns of `f32x4x4`, so `t * p` in simd
+/// is the same as `p * t` with row vectors.
+#[cfg(feature = "simd")]
+impl From<Transform3d> for crate::simd::f32x4x4 {
+    #[inline]
+    fn from(t: Transform3d) -> Self {
+        Self(t.rows().map(|r| crate::simd::Simd(r.map(|v| v as f32))))
+    }
+}
+
+#[cfg(feature = "simd")]
+impl From<crate::simd::f32x4x4> for Transform3d {
+    #[inline]
+    fn from(m: crate::simd::f32x4x4) -> Self {
+        Self::with_rows(m.0.map(|c| c.0.map(f64::from)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_3};
+
+    use crate::{ca::Transform3d, cg};
+
+    fn close(a: &Transform3d, b: &Transform3d) -> bool {
+        let (a, b) = (a.rows(), b.rows());
+        (0..16).all(|i| (a[i / 4][i % 4] - b[i / 4][i % 4]).abs() < 1e-9)
+    }
+
+    fn samples() -> Vec<Transform3d> {
+        let t = Transform3d::identity();
+        let mut perspective = t;
+        perspective.m34 = -1.0 / 500.0;
+        vec![
+            t,
+            t.translate(1.0, -2.0, 3.0),
+            t.scale(2.0, 3.0, 4.0),
+            t.rotate(0.7, 1.0, 2.0, 3.0),
+            perspective
+                .rotate(FRAC_PI_3, 0.0, 1.0, 0.0)
+                .translate(5.0, 0.0, -1.0),
+        ]
+    }
+
+    #[test]
+    fn algebra() {
+        let t = Transform3d::new_translation(1.0, 2.0, 3.0);
+        let s = Transform3d::new_scale(2.0, 2.0, 2.0);
+        assert_eq!(Transform3d::default(), Transform3d::identity());
+        assert!(Transform3d::identity().is_identity());
+        assert!(!t.is_identity());
+
+        // scale first, translate second
+        let st = s * t;
+        assert_eq!([st.m41, st.m42, st.m43], [1.0, 2.0, 3.0]);
+        let ts = t * s;
+        assert_eq!([ts.m41, ts.m42, ts.m43], [2.0, 4.0, 6.0]);
+        assert_eq!(t.scale(2.0, 2.0, 2.0), st);
+        assert_eq!(s.translate(1.0, 2.0, 3.0), ts);
+
+        let mut m = s;
+        m *= t;
+        assert_eq!(m, st);
+
+        // quarter turn around z matches the affine rotation
+        let r = Transform3d::new_rotation(FRAC_PI_2, 0.0, 0.0, 2.0);
+        let a = cg::AffineTransform::new_rotation(FRAC_PI_2);
+        assert!(close(&r, &Transform3d::from(a)));
+        assert!(r.is_affine());
+        assert_eq!(
+            Transform3d::new_rotation(1.0, 0.0, 0.0, 0.0),
+            Transform3d::identity()
+        );
+        assert_eq!(t.rotate(1.0, 0.0, 0.0, 0.0), t);
+        assert!(!Transform3d::new_rotation(0.5, 1.0, 0.0, 0.0).is_affine());
+
+        let a = cg::AffineTransform::new_translation(1.0, 2.0).scale(3.0, 4.0);
+        let t = Transform3d::from_cg_affine_transform(a);
+        assert!(t.is_affine());
+        assert_eq!(t.to_affine_transform(), a);
+        assert!(!samples()[4].is_affine());
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd() {
+        use crate::simd::{f32x4, f32x4x4};
+
+        let t = Transform3d::new_scale(2.0, 3.0, 4.0).translate(1.0, 1.0, 1.0);
+        let m = f32x4x4::from(t);
+        // t applies the translation first
+        assert_eq!(
+            m * f32x4::with_xyzw(1.0, 1.0, 1.0, 1.0),
+            f32x4::with_xyzw(4.0, 6.0, 8.0, 1.0)
+        );
+        assert_eq!(Transform3d::from(m), t);
+        assert_eq!(f32x4x4::from(t * t), m * m);
+        assert_eq!(
+            f32x4x4::from(Transform3d::new_translation(1.0, 2.0, 3.0)),
+            f32x4x4::translate(1.0, 2.0, 3.0)
+        );
+    }
+
+    /// Native implementation matches QuartzCore
+    #[cfg(target_vendor = "apple")]
+    #[test]
+    fn parity() {
+        for t in samples() {
+            unsafe {
+                assert_eq!(t.is_identity(), CATransform3DIsIdentity(t));
+                assert_eq!(t.is_affine(), CATransform3DIsAffine(t));
+                assert!(close(
+                    &t.translate(1.0, 2.0, 3.0),
+                    &CATransform3DTranslate(t, 1.0, 2.0, 3.0)
+                ));
+                assert!(close(
+                    &t.scale(2.0, -1.0, 0.5),
+                    &CATransform3DScale(t, 2.0, -1.0, 0.5)
+                ));
+                assert!(close(
+                    &t.rotate(0.3, -1.0, 0.5, 2.0),
+                    &CATransform3DRotate(t, 0.3, -1.0, 0.5, 2.0)
+                ));
+                for o in samples() {
+                    assert!(close(&(t * o), &CATransform3DConcat(t, o)));
+                }
+                assert_eq!(t.to_affine_transform(), CATransform3DGetAffineTransform(t));
+            }
+        }
+        unsafe {
+            assert_eq!(Transform3d::identity(), CATransform3DIdentity);
+            assert_eq!(
+                Transform3d::new_translation(1.0, 2.0, 3.0),
+                CATransform3DMakeTranslation(1.0, 2.0, 3.0)
+            );
+            assert_eq!(
+                Transform3d::new_scale(1.0, 2.0, 3.0),
+                CATransform3DMakeScale(1.0, 2.0, 3.0)
+            );
+            assert!(close(
+                &Transform3d::new_rotation(1.1, 1.0, -2.0, 0.5),
+                &CATransform3DMakeRotation(1.1, 1.0, -2.0, 0.5)
+            ));
+            assert_eq!(
+                Transform3d::new_rotation(1.1, 0.0, 0.0, 0.0),
+                CATransform3DMakeRotation(1.1, 0.0, 0.0, 0.0)
+            );
+            let a = cg::AffineTransform::new_rotation(0.4).translate(1.0, 2.0);
+            assert_eq!(
+                Transform3d::from_cg_affine_transform(a),
+                CATransform3DMakeAffineTransform(a)
+            );
+        }
+
+        #[link(name = "QuartzCore", kind = "framework")]
+        unsafe extern "C-unwind" {
+            static CATransform3DIdentity: Transform3d;
+            fn CATransform3DIsIdentity(t: Transform3d) -> bool;
+            fn CATransform3DMakeTranslation(tx: f64, ty: f64, tz: f64) -> Transform3d;
+            fn CATransform3DMakeScale(sx: f64, sy: f64, sz: f64) -> Transform3d;
+            fn CATransform3DMakeRotation(angle: f64, x: f64, y: f64, z: f64) -> Transform3d;
+            fn CATransform3DTranslate(t: Transform3d, tx: f64, ty: f64, tz: f64) -> Transform3d;
+            fn CATransform3DScale(t: Transform3d, sx: f64, sy: f64, sz: f64) -> Transform3d;
+            fn CATransform3DRotate(
+                t: Transform3d,
+                angle: f64,
+                x: f64,
+                y: f64,
+                z: f64,
+            ) -> Transform3d;
+            fn CATransform3DConcat(a: Transform3d, b: Transform3d) -> Transform3d;
+            fn CATransform3DMakeAffineTransform(m: cg::AffineTransform) -> Transform3d;
+            fn CATransform3DIsAffine(t: Transform3d) -> bool;
+            fn CATransform3DGetAffineTransform(t: Transform3d) -> cg::AffineTransform;
+        }
+    }
 }
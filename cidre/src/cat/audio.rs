@@ -4,6 +4,24 @@ pub type UInteger = usize;
 mod base_types;
 pub use base_types::*;
 
+pub mod asbd;
+pub use asbd::Builder as AsbdBuilder;
+
+pub mod pcm;
+pub use pcm::Converter as PcmConverter;
+
+pub mod channel_layout;
+pub use channel_layout::ChannelLayoutBuf;
+
+pub mod mix;
+pub use mix::Matrix as MixMatrix;
+
+pub mod resample;
+pub use resample::Resampler;
+
+mod file_type;
+pub use file_type::FileTypeId;
+
 mod session_types;
 pub use session_types::ErrorCode as SessionErrorCode;
 pub use session_types::SessionId;
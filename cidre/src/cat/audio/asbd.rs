@@ -0,0 +1,764 @@
+//! Builder, validator and textual notation of [`StreamBasicDesc`].
+//!
+//! Notation is `<sample or format>@<sample rate>x<channels>` followed by optional words:
+//!
+//! - linear PCM samples are `F` (float), `I` (signed) or `U` (unsigned) with valid bits
+//!   and container bits if they differ: `F32`, `I16`, `I24/32`;
+//! - other formats are four char codes: `aac `, `ulaw`, `.mp3`;
+//! - `big-endian`, `non-interleaved` and `non-mixable` set linear PCM format flags;
+//! - `flags=0x..`, `bpp=`, `fpp=`, `bpf=` and `bits=` override fields which differ from
+//!   what the builder derives.
+//!
+//! ```
+//! use cidre::cat::audio;
+//!
+//! let asbd: audio::StreamBasicDesc = "F32@48000x2 non-interleaved".parse().unwrap();
+//! assert_eq!(asbd, audio::StreamBasicDesc::common_f32(48_000.0, 2, false));
+//! assert_eq!(asbd.to_string(), "F32@48000x2 non-interleaved");
+//!
+//! let aac = audio::AsbdBuilder::compressed(audio::Format::MPEG4_AAC, 44_100.0, 2)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(aac.frames_per_packet, 1024);
+//! assert_eq!(aac.to_string(), "aac @44100x2");
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use crate::{
+    FourCc,
+    cat::audio::{Format, FormatFlags, StreamBasicDesc},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Sample rate is negative or not finite
+    InvalidSampleRate(f64),
+
+    NoChannels,
+
+    /// Linear PCM packets hold exactly one frame
+    InvalidFramesPerPacket(u32),
+
+    InvalidBytesPerFrame {
+        expected: u32,
+        actual: u32,
+    },
+
+    InvalidBytesPerPacket {
+        expected: u32,
+        actual: u32,
+    },
+
+    /// Valid bits don't fit sample container
+    InvalidBitsPerChannel {
+        bits: u32,
+        container: u32,
+    },
+
+    /// Float samples are 32 or 64 bits
+    InvalidFloatBits(u32),
+
+    /// Format flags contradict each other or sample size
+    ConflictingFlags(&'static str),
+
+    NonZeroReserved(u32),
+
+    /// Text is not a valid notation
+    InvalidNotation(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSampleRate(rate) => write!(f, "invalid sample rate {rate}"),
+            Self::NoChannels => f.write_str("channels_per_frame is 0"),
+            Self::InvalidFramesPerPacket(n) => {
+                write!(f, "frames_per_packet is {n}, linear PCM requires 1")
+            }
+            Self::InvalidBytesPerFrame { expected, actual } => {
+                write!(f, "bytes_per_frame is {actual}, expected {expected}")
+            }
+            Self::InvalidBytesPerPacket { expected, actual } => {
+                write!(f, "bytes_per_packet is {actual}, expected {expected}")
+            }
+            Self::InvalidBitsPerChannel { bits, container } => {
+                write!(
+                    f,
+                    "bits_per_channel is {bits}, samples are {container} bits wide"
+                )
+            }
+            Self::InvalidFloatBits(bits) => {
+                write!(
+                    f,
+                    "bits_per_channel is {bits}, float samples are 32 or 64 bits"
+                )
+            }
+            Self::ConflictingFlags(reason) => write!(f, "format_flags conflict: {reason}"),
+            Self::NonZeroReserved(val) => write!(f, "reserved is {val}, expected 0"),
+            Self::InvalidNotation(text) => write!(f, "invalid stream description '{text}'"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+/// Linear PCM sample bits, samples which are not whole bytes are aligned high
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sample {
+    Unsigned(u32),
+    Signed(u32),
+    Float(u32),
+}
+
+impl Sample {
+    pub const U8: Self = Self::Unsigned(8);
+    pub const I16: Self = Self::Signed(16);
+    pub const I24: Self = Self::Signed(24);
+    pub const I32: Self = Self::Signed(32);
+    pub const F32: Self = Self::Float(32);
+    pub const F64: Self = Self::Float(64);
+
+    #[inline]
+    pub fn bits(self) -> u32 {
+        match self {
+            Self::Unsigned(bits) | Self::Signed(bits) | Self::Float(bits) => bits,
+        }
+    }
+
+    fn flags(self) -> FormatFlags {
+        match self {
+            Self::Unsigned(_) => FormatFlags::IS_PACKED,
+            Self::Signed(_) => FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_PACKED,
+            Self::Float(_) => FormatFlags::IS_FLOAT | FormatFlags::IS_PACKED,
+        }
+    }
+}
+
+/// Frames per packet, bytes per packet per channel and bits of constant
+/// bit rate formats with well known packets
+fn packet_layout(format: Format) -> (u32, u32, u32) {
+    match format {
+        Format::U_LAW | Format::A_LAW => (1, 1, 8),
+        Format::APPLE_IMA4 => (64, 34, 0),
+        Format::MPEG4_AAC | Format::MPEG4_AAC_ELD_SBR => (1024, 0, 0),
+        Format::MPEG4_AAC_HE | Format::MPEG4_AAC_HE_V2 => (2048, 0, 0),
+        Format::MPEG4_AAC_LD | Format::MPEG4_AAC_ELD => (512, 0, 0),
+        Format::MPEGLAYER1 => (384, 0, 0),
+        Format::MPEGLAYER2 | Format::MPEGLAYER3 => (1152, 0, 0),
+        Format::AC3 | Format::ENHANCED_AC3 => (1536, 0, 0),
+        Format::APPLE_LOSSLESS => (4096, 0, 0),
+        Format::OPUS => (960, 0, 0),
+        _ => (0, 0, 0),
+    }
+}
+
+/// Builds consistent [`StreamBasicDesc`]
+///
+/// ```
+/// use cidre::cat::audio;
+///
+/// let asbd = audio::AsbdBuilder::lpcm(audio::asbd::Sample::I32, 48_000.0, 2)
+///     .valid_bits(24)
+///     .build()
+///     .unwrap();
+/// assert_eq!(asbd.bytes_per_frame, 8);
+/// assert!(asbd.format_flags.contains(audio::FormatFlags::IS_ALIGNED_HIGH));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Builder {
+    asbd: StreamBasicDesc,
+
+    /// Bytes of linear PCM sample
+    sample_size: u32,
+}
+
+impl Builder {
+    /// Interleaved native endian samples, bytes per frame which don't fit in u32
+    /// are left zero and rejected by [`Self::build`]
+    pub fn lpcm(sample: Sample, sample_rate: f64, channels: u32) -> Self {
+        let sample_size = sample.bits().div_ceil(8);
+        let bytes_per_frame = sample_size.checked_mul(channels).unwrap_or(0);
+        let mut res = Self {
+            asbd: StreamBasicDesc {
+                sample_rate,
+                format: Format::LINEAR_PCM,
+                format_flags: sample.flags(),
+                bytes_per_packet: bytes_per_frame,
+                frames_per_packet: 1,
+                bytes_per_frame,
+                channels_per_frame: channels,
+                bits_per_channel: sample.bits(),
+                reserved: 0,
+            },
+            sample_size,
+        };
+        res.valid_bits(sample.bits());
+        res
+    }
+
+    /// Packet layout of well known formats is filled in, other fields are zero.
+    /// Bytes per packet saturate when they don't fit in u32.
+    pub fn compressed(format: Format, sample_rate: f64, channels: u32) -> Self {
+        let (frames_per_packet, bytes_per_packet, bits) = packet_layout(format);
+        let bytes_per_packet = bytes_per_packet.saturating_mul(channels);
+        Self {
+            asbd: StreamBasicDesc {
+                sample_rate,
+                format,
+                format_flags: FormatFlags(0),
+                bytes_per_packet,
+                frames_per_packet,
+                bytes_per_frame: if frames_per_packet == 1 {
+                    bytes_per_packet
+                } else {
+                    0
+                },
+                channels_per_frame: channels,
+                bits_per_channel: bits,
+                reserved: 0,
+            },
+            sample_size: 0,
+        }
+    }
+
+    /// Samples with less bits are aligned high in their containers
+    pub fn valid_bits(&mut self, bits: u32) -> &mut Self {
+        self.asbd.bits_per_channel = bits;
+        if self.asbd.format == Format::LINEAR_PCM {
+            let flags = &mut self.asbd.format_flags;
+            let aligned = self.sample_size.checked_mul(8) != Some(bits);
+            flags.set(FormatFlags::IS_PACKED, !aligned);
+            flags.set(FormatFlags::IS_ALIGNED_HIGH, aligned);
+        }
+        self
+    }
+
+    pub fn big_endian(&mut self) -> &mut Self {
+        self.asbd.format_flags |= FormatFlags::IS_BIG_ENDIAN;
+        self
+    }
+
+    /// Each buffer holds one channel
+    pub fn non_interleaved(&mut self) -> &mut Self {
+        self.asbd.format_flags |= FormatFlags::IS_NON_INTERLEAVED;
+        if self.asbd.format == Format::LINEAR_PCM {
+            self.asbd.bytes_per_frame = self.sample_size;
+            self.asbd.bytes_per_packet = self.sample_size;
+        }
+        self
+    }
+
+    pub fn non_mixable(&mut self) -> &mut Self {
+        self.asbd.format_flags |= FormatFlags::IS_NON_MIXABLE;
+        self
+    }
+
+    /// Replaces derived flags, for example with object type of AAC
+    pub fn format_flags(&mut self, val: FormatFlags) -> &mut Self {
+        self.asbd.format_flags = val;
+        self
+    }
+
+    pub fn bytes_per_packet(&mut self, val: u32) -> &mut Self {
+        self.asbd.bytes_per_packet = val;
+        self
+    }
+
+    pub fn frames_per_packet(&mut self, val: u32) -> &mut Self {
+        self.asbd.frames_per_packet = val;
+        self
+    }
+
+    pub fn bytes_per_frame(&mut self, val: u32) -> &mut Self {
+        self.asbd.bytes_per_frame = val;
+        self
+    }
+
+    pub fn bits_per_channel(&mut self, val: u32) -> &mut Self {
+        self.asbd.bits_per_channel = val;
+        self
+    }
+
+    /// Validated description
+    pub fn build(&self) -> Result<StreamBasicDesc> {
+        self.asbd.validate()?;
+        Ok(self.asbd)
+    }
+
+    /// Description as is, even inconsistent
+    #[inline]
+    pub fn build_unchecked(&self) -> StreamBasicDesc {
+        self.asbd
+    }
+}
+
+impl StreamBasicDesc {
+    /// Checks fields are consistent with each other the way AudioToolbox expects,
+    /// error explains the first inconsistency.
+    pub fn validate(&self) -> Result {
+        if self.reserved != 0 {
+            return Err(Error::NonZeroReserved(self.reserved));
+        }
+        if !self.sample_rate.is_finite() || self.sample_rate < 0.0 {
+            return Err(Error::InvalidSampleRate(self.sample_rate));
+        }
+        if self.channels_per_frame == 0 {
+            return Err(Error::NoChannels);
+        }
+        if self.format != Format::LINEAR_PCM {
+            let expected = if self.frames_per_packet == 1 {
+                self.bytes_per_packet
+            } else {
+                0
+            };
+            if self.bytes_per_frame != expected {
+                return Err(Error::InvalidBytesPerFrame {
+                    expected,
+                    actual: self.bytes_per_frame,
+                });
+            }
+            return Ok(());
+        }
+
+        let flags = self.format_flags;
+        let is_float = flags.contains(FormatFlags::IS_FLOAT);
+        if is_float && flags.contains(FormatFlags::IS_SIGNED_INTEGER) {
+            return Err(Error::ConflictingFlags("float and signed integer"));
+        }
+        if self.frames_per_packet != 1 {
+            return Err(Error::InvalidFramesPerPacket(self.frames_per_packet));
+        }
+        let bits = self.bits_per_channel;
+        if bits == 0 {
+            return Err(Error::InvalidBitsPerChannel { bits, container: 0 });
+        }
+        let channels = self.interleaved_channels_num();
+        let bpf = self.bytes_per_frame;
+        // containers wider than u32 bits fit any valid bits but never match them
+        let container = (bpf / channels).checked_mul(8);
+        if bpf == 0 || bpf % channels != 0 || container.is_some_and(|c| c < bits) {
+            return Err(Error::InvalidBytesPerFrame {
+                expected: bits.div_ceil(8).saturating_mul(channels),
+                actual: bpf,
+            });
+        }
+        if self.bytes_per_packet != bpf {
+            return Err(Error::InvalidBytesPerPacket {
+                expected: bpf,
+                actual: self.bytes_per_packet,
+            });
+        }
+        let filled = container == Some(bits);
+        if is_float {
+            if bits != 32 && bits != 64 {
+                return Err(Error::InvalidFloatBits(bits));
+            }
+            if !filled {
+                return Err(Error::InvalidBitsPerChannel {
+                    bits,
+                    container: container.unwrap_or(u32::MAX),
+                });
+            }
+        }
+        if flags.contains(FormatFlags::IS_PACKED) && !filled {
+            return Err(Error::ConflictingFlags(
+                "packed samples fill their containers",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Notation without overrides and description it stands for
+    fn notation_head(&self) -> (String, StreamBasicDesc) {
+        let mut res = String::new();
+        if self.format == Format::LINEAR_PCM {
+            let flags = self.format_flags;
+            let kind = if flags.contains(FormatFlags::IS_FLOAT) {
+                'F'
+            } else if flags.contains(FormatFlags::IS_SIGNED_INTEGER) {
+                'I'
+            } else {
+                'U'
+            };
+            let bits = self.bits_per_channel;
+            let channels = self.interleaved_channels_num();
+            // byte containers are written when derived bytes per frame would overflow u32
+            let container = match self.bytes_per_frame {
+                bpf if channels != 0 && bpf != 0 && bpf % channels == 0 => {
+                    (bpf / channels).checked_mul(8)
+                }
+                _ => None,
+            }
+            .or_else(|| bits.div_ceil(8).checked_mul(channels).map(|_| bits))
+            .unwrap_or(8);
+            res.push(kind);
+            res.push_str(&bits.to_string());
+            if container != bits {
+                res.push('/');
+                res.push_str(&container.to_string());
+            }
+        } else {
+            let fcc = FourCc::from(self.format).to_string();
+            if parse_sample(&fcc).is_some() {
+                // `F123` code would be read as float sample
+                res.push_str(&format!("0x{:08x}", self.format.0));
+            } else {
+                res.push_str(&fcc);
+            }
+        }
+        res.push_str(&format!(
+            "@{}x{}",
+            self.sample_rate, self.channels_per_frame
+        ));
+        for (flag, word) in WORDS {
+            if self.format == Format::LINEAR_PCM && self.format_flags.contains(flag) {
+                res.push(' ');
+                res.push_str(word);
+            }
+        }
+        let derived = match parse_head(&res) {
+            Some(builder) => builder.asbd,
+            // packet size of so many channels overflows, every field is written
+            None => StreamBasicDesc {
+                sample_rate: self.sample_rate,
+                format: self.format,
+                channels_per_frame: self.channels_per_frame,
+                ..Default::default()
+            },
+        };
+        (res, derived)
+    }
+}
+
+/// Linear PCM flags with their notation words, flags of other formats are
+/// specific to them
+const WORDS: [(FormatFlags, &str); 3] = [
+    (FormatFlags::IS_BIG_ENDIAN, "big-endian"),
+    (FormatFlags::IS_NON_INTERLEAVED, "non-interleaved"),
+    (FormatFlags::IS_NON_MIXABLE, "non-mixable"),
+];
+
+/// Builder of sample or format, rate, channels and flag words, overrides are left
+/// for caller
+fn parse_head(s: &str) -> Option<Builder> {
+    let (head, tail) = s.rsplit_once('@')?;
+    let mut words = tail.split_ascii_whitespace();
+    let (rate, channels) = words.next()?.split_once('x')?;
+    let rate: f64 = rate.parse().ok()?;
+    let channels: u32 = channels.parse().ok()?;
+
+    let mut res = match parse_sample(head) {
+        Some((sample, bits)) => {
+            let mut res = Builder::lpcm(sample, rate, channels);
+            res.valid_bits(bits);
+            res
+        }
+        None => {
+            // short codes are padded, `aac` is the same as `aac `
+            let fcc = if head.len() < 4 && head.is_ascii() && !head.is_empty() {
+                format!("{head:<4}").parse()
+            } else {
+                head.parse::<FourCc>()
+            };
+            Builder::compressed(Format::from(fcc.ok()?), rate, channels)
+        }
+    };
+    for word in words.filter(|w| !w.contains('=')) {
+        if res.asbd.format != Format::LINEAR_PCM {
+            return None;
+        }
+        let (flag, _) = WORDS.iter().find(|(_, w)| *w == word)?;
+        if *flag == FormatFlags::IS_NON_INTERLEAVED {
+            res.non_interleaved();
+        } else {
+            res.asbd.format_flags |= *flag;
+        }
+    }
+    // derived bytes per frame or packet don't fit in u32
+    let size = if res.asbd.format == Format::LINEAR_PCM {
+        res.sample_size
+    } else {
+        packet_layout(res.asbd.format).1
+    };
+    size.checked_mul(res.asbd.interleaved_channels_num())?;
+    Some(res)
+}
+
+/// `I24/32` is a signed sample with 24 valid bits in 32 bit container
+fn parse_sample(s: &str) -> Option<(Sample, u32)> {
+    let mut chars = s.chars();
+    let kind = chars.next()?;
+    let (bits, container) = match chars.as_str().split_once('/') {
+        Some((bits, container)) => (bits, container),
+        None => (chars.as_str(), chars.as_str()),
+    };
+    if !bits.bytes().all(|b| b.is_ascii_digit()) || !container.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let bits = bits.parse().ok()?;
+    let container = container.parse().ok()?;
+    let sample = match kind {
+        'F' => Sample::Float(container),
+        'I' => Sample::Signed(container),
+        'U' => Sample::Unsigned(container),
+        _ => return None,
+    };
+    Some((sample, bits))
+}
+
+impl fmt::Display for StreamBasicDesc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (head, derived) = self.notation_head();
+        f.write_str(&head)?;
+        if self.format_flags != derived.format_flags {
+            write!(f, " flags=0x{:x}", self.format_flags.0)?;
+        }
+        let fields = [
+            ("bpp", self.bytes_per_packet, derived.bytes_per_packet),
+            ("fpp", self.frames_per_packet, derived.frames_per_packet),
+            ("bpf", self.bytes_per_frame, derived.bytes_per_frame),
+            ("bits", self.bits_per_channel, derived.bits_per_channel),
+        ];
+        for (name, val, derived) in fields {
+            if val != derived {
+                write!(f, " {name}={val}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for StreamBasicDesc {
+    type Err = Error;
+
+    /// Parses notation written by `Display`, the result is not validated
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidNotation(s.to_string());
+        let mut res = parse_head(s.trim()).ok_or_else(invalid)?;
+        let (_, tail) = s.rsplit_once('@').ok_or_else(invalid)?;
+        for (name, val) in tail
+            .split_ascii_whitespace()
+            .filter_map(|w| w.split_once('='))
+        {
+            let val = match val.strip_prefix("0x") {
+                Some(hex) => u32::from_str_radix(hex, 16),
+                None => val.parse(),
+            }
+            .map_err(|_| invalid())?;
+            match name {
+                "flags" => res.format_flags(FormatFlags(val)),
+                "bpp" => res.bytes_per_packet(val),
+                "fpp" => res.frames_per_packet(val),
+                "bpf" => res.bytes_per_frame(val),
+                "bits" => res.bits_per_channel(val),
+                _ => return Err(invalid()),
+            };
+        }
+        Ok(res.build_unchecked())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cat::audio::{
+        AsbdBuilder, Format, FormatFlags, StreamBasicDesc,
+        asbd::{Error, Sample},
+    };
+
+    #[test]
+    fn builder() {
+        let asbd = AsbdBuilder::lpcm(Sample::F32, 48_000.0, 2).build().unwrap();
+        assert_eq!(asbd, StreamBasicDesc::common_f32(48_000.0, 2, true));
+        let asbd = AsbdBuilder::lpcm(Sample::F32, 48_000.0, 2)
+            .non_interleaved()
+            .build()
+            .unwrap();
+        assert_eq!(asbd, StreamBasicDesc::common_f32(48_000.0, 2, false));
+
+        let asbd = AsbdBuilder::lpcm(Sample::I16, 44_100.0, 2)
+            .big_endian()
+            .build()
+            .unwrap();
+        assert_eq!(asbd.bytes_per_frame, 4);
+        assert_eq!(
+            asbd.format_flags,
+            FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_PACKED | FormatFlags::IS_BIG_ENDIAN
+        );
+
+        let asbd = AsbdBuilder::compressed(Format::U_LAW, 8_000.0, 2)
+            .build()
+            .unwrap();
+        assert_eq!(
+            (
+                asbd.bytes_per_packet,
+                asbd.bytes_per_frame,
+                asbd.bits_per_channel
+            ),
+            (2, 2, 8)
+        );
+        let asbd = AsbdBuilder::compressed(Format::APPLE_IMA4, 44_100.0, 2)
+            .build()
+            .unwrap();
+        assert_eq!((asbd.frames_per_packet, asbd.bytes_per_packet), (64, 68));
+
+        let wide = AsbdBuilder::lpcm(Sample::I32, 48_000.0, 1 << 30);
+        assert_eq!(wide.build_unchecked().bytes_per_frame, 0);
+        assert!(wide.build().is_err());
+    }
+
+    #[test]
+    fn validate() {
+        let valid = StreamBasicDesc::common_f32(48_000.0, 2, true);
+        assert_eq!(valid.validate(), Ok(()));
+
+        let check = |f: fn(&mut StreamBasicDesc), err: Error| {
+            let mut asbd = valid;
+            f(&mut asbd);
+            assert_eq!(asbd.validate(), Err(err));
+        };
+        check(|a| a.sample_rate = -1.0, Error::InvalidSampleRate(-1.0));
+        check(|a| a.channels_per_frame = 0, Error::NoChannels);
+        check(
+            |a| a.frames_per_packet = 0,
+            Error::InvalidFramesPerPacket(0),
+        );
+        check(
+            |a| a.bytes_per_frame = 4,
+            Error::InvalidBytesPerFrame {
+                expected: 8,
+                actual: 4,
+            },
+        );
+        check(
+            |a| a.bytes_per_packet = 0,
+            Error::InvalidBytesPerPacket {
+                expected: 8,
+                actual: 0,
+            },
+        );
+        check(|a| a.bits_per_channel = 16, Error::InvalidFloatBits(16));
+        check(
+            |a| a.format_flags |= FormatFlags::IS_SIGNED_INTEGER,
+            Error::ConflictingFlags("float and signed integer"),
+        );
+        check(|a| a.reserved = 1, Error::NonZeroReserved(1));
+
+        let mut asbd = AsbdBuilder::lpcm(Sample::I32, 48_000.0, 1)
+            .valid_bits(24)
+            .build()
+            .unwrap();
+        asbd.format_flags = FormatFlags::IS_SIGNED_INTEGER | FormatFlags::IS_PACKED;
+        assert!(matches!(asbd.validate(), Err(Error::ConflictingFlags(_))));
+
+        let mut aac = AsbdBuilder::compressed(Format::MPEG4_AAC, 44_100.0, 2)
+            .build()
+            .unwrap();
+        aac.bytes_per_frame = 8;
+        assert_eq!(
+            aac.validate(),
+            Err(Error::InvalidBytesPerFrame {
+                expected: 0,
+                actual: 8
+            })
+        );
+    }
+
+    #[test]
+    fn notation() {
+        let cases = [
+            "F32@48000x2",
+            "F32@48000x2 non-interleaved",
+            "F64@96000x1",
+            "I16@44100x2 big-endian",
+            "I24@48000x6",
+            "I24/32@48000x2",
+            "U8@8000x1",
+            "I16@22050.5x2 non-interleaved non-mixable",
+            "aac @44100x2",
+            "aac @44100x2 flags=0x2",
+            "ulaw@8000x1",
+            ".mp3@44100x2",
+            "alac@48000x2 flags=0x1",
+            "ima4@44100x1",
+            "opus@48000x2 fpp=480",
+            "I20/24@48000x2 flags=0xc",
+            "abcd@0x2 bpp=100 fpp=8",
+            "\\x00\\x00\\x00\\x01@48000x2",
+            "0x46313233@48000x2",
+        ];
+        for case in cases {
+            let asbd: StreamBasicDesc = case.parse().unwrap();
+            assert_eq!(asbd.to_string(), case);
+        }
+
+        let parsed: StreamBasicDesc = "aac@44100x2".parse().unwrap();
+        assert_eq!(parsed.format, Format::MPEG4_AAC);
+        assert_eq!(parsed.frames_per_packet, 1024);
+
+        let asbd: StreamBasicDesc = "I24/32@48000x2".parse().unwrap();
+        assert_eq!(asbd.bytes_per_frame, 8);
+        assert_eq!(asbd.bits_per_channel, 24);
+        assert_eq!(asbd.validate(), Ok(()));
+
+        // inconsistent descriptions round trip too
+        let mut odd = StreamBasicDesc::common_f32(44_100.0, 2, true);
+        odd.bytes_per_packet = 0;
+        odd.bits_per_channel = 16;
+        let text = odd.to_string();
+        assert_eq!(text, "F16/32@44100x2 flags=0x9 bpp=0");
+        assert_eq!(text.parse::<StreamBasicDesc>().unwrap(), odd);
+
+        for invalid in [
+            "",
+            "F32",
+            "F32@48000",
+            "F32@x2",
+            "F32@48000x2 mono",
+            "toolong@48000x2",
+            "aac @44100x2 big-endian",
+            "I4294967295@48000x8",
+            "ima4@44100x4294967295",
+        ] {
+            assert_eq!(
+                invalid.parse::<StreamBasicDesc>(),
+                Err(Error::InvalidNotation(invalid.to_string()))
+            );
+        }
+        assert!("aac @44100x2 bits=x".parse::<StreamBasicDesc>().is_err());
+
+        // sizes which overflow u32 are printed without panics
+        let asbd: StreamBasicDesc = "I4294967295@48000x2".parse().unwrap();
+        assert_eq!(asbd.bytes_per_frame, 1 << 30);
+        assert_eq!(asbd.to_string(), "I4294967295@48000x2");
+        let mut wide = StreamBasicDesc::common_f32(48_000.0, 1, true);
+        wide.bytes_per_frame = u32::MAX;
+        wide.bytes_per_packet = u32::MAX;
+        let text = wide.to_string();
+        assert_eq!(text, "F32@48000x1 bpp=4294967295 bpf=4294967295");
+        assert_eq!(text.parse::<StreamBasicDesc>().unwrap(), wide);
+        assert_eq!(
+            wide.validate(),
+            Err(Error::InvalidBitsPerChannel {
+                bits: 32,
+                container: u32::MAX
+            })
+        );
+        wide.bits_per_channel = u32::MAX;
+        wide.channels_per_frame = 8;
+        assert!(wide.validate().is_err());
+        let text = wide.to_string();
+        assert!(text.starts_with("F4294967295/8@48000x8 "));
+        assert_eq!(text.parse::<StreamBasicDesc>().unwrap(), wide);
+
+        let ima4 =
+            AsbdBuilder::compressed(Format::APPLE_IMA4, 44_100.0, u32::MAX).build_unchecked();
+        assert_eq!(ima4.bytes_per_packet, u32::MAX);
+        assert_eq!(
+            ima4.to_string(),
+            "ima4@44100x4294967295 bpp=4294967295 fpp=64"
+        );
+    }
+}
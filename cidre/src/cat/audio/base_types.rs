@@ -3,10 +3,13 @@ use std::{
     ptr::{slice_from_raw_parts, slice_from_raw_parts_mut},
 };
 
-use crate::{cf, define_opts, four_cc_to_str, objc::Obj, os};
+use crate::{define_opts, four_cc_to_str, os};
+
+#[cfg(all(feature = "cf", target_vendor = "apple"))]
+use crate::cf;
 
 #[cfg(feature = "ns")]
-use crate::ns;
+use crate::{ns, objc::Obj};
 
 /// These are the error codes returned from the APIs found through Core Audio related frameworks.
 pub mod err {
@@ -226,6 +229,20 @@ impl std::fmt::Debug for Format {
     }
 }
 
+impl From<crate::FourCc> for Format {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<Format> for crate::FourCc {
+    #[inline]
+    fn from(value: Format) -> Self {
+        Self(value.0)
+    }
+}
+
 /// The AudioFormatIDs used to identify individual formats of audio data.
 impl Format {
     /// Linear PCM, uses the standard flags.
@@ -414,30 +431,35 @@ impl Format {
     }
 }
 
+#[cfg(all(feature = "cf", target_vendor = "apple"))]
 impl AsRef<cf::Number> for Format {
     fn as_ref(&self) -> &'static cf::Number {
         cf::Number::tagged_i32(self.0 as _)
     }
 }
 
+#[cfg(all(feature = "cf", target_vendor = "apple"))]
 impl AsRef<cf::Type> for Format {
     fn as_ref(&self) -> &'static cf::Type {
         cf::Number::tagged_i32(self.0 as _).as_type_ref()
     }
 }
 
+#[cfg(feature = "ns")]
 impl AsRef<ns::Id> for Format {
     fn as_ref(&self) -> &'static ns::Id {
         self.to_ns_number().as_id_ref()
     }
 }
 
+#[cfg(feature = "ns")]
 impl AsRef<ns::Number> for Format {
     fn as_ref(&self) -> &'static ns::Number {
         self.to_ns_number()
     }
 }
 
+#[cfg(feature = "ns")]
 impl AsRef<ns::Id> for u32 {
     fn as_ref(&self) -> &ns::Id {
         &ns::Number::tagged_u32(*self).as_id_ref()
@@ -1082,7 +1104,7 @@ impl ChannelLabel {
 /// These constants are for use in the mChannelBitmap field of an
 /// AudioChannelLayout structure
 #[doc(alias = "AudioChannelBitmap")]
-#[derive(Debug, PartialEq, Eq, Default)]
+#[derive(Debug, PartialEq, Eq, Default, Copy, Clone)]
 #[repr(transparent)]
 pub struct ChannelBitmap(pub u32);
 
@@ -1627,7 +1649,7 @@ impl ChannelLayoutTag {
 
 /// This structure describes a single channel.
 #[doc(alias = "AudioChannelDescription")]
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 #[repr(C)]
 pub struct ChannelDesc {
     /// The AudioChannelLabel that describes the channel.
@@ -1692,7 +1714,7 @@ impl Mpeg4Object {
     pub const HVXC: Self = Self(9);
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "at"))]
 mod tests {
     use crate::at;
 
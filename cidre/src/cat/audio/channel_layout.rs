@@ -0,0 +1,335 @@
+//! Owned variable length `AudioChannelLayout`.
+//!
+//! `ChannelLayout<N>` mirrors the C struct with a fixed number of descriptions.
+//! Properties of files, devices and format descriptions return any number of them,
+//! [`ChannelLayoutBuf`] parses such bytes and writes them back in the same layout.
+
+use crate::cat::audio::{
+    ChannelBitmap, ChannelDesc, ChannelFlags, ChannelLabel, ChannelLayout, ChannelLayoutTag,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Bytes don't hold header and all channel descriptions
+    TooShort { required: usize, actual: usize },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooShort { required, actual } => {
+                write!(
+                    f,
+                    "channel layout of {actual} bytes, {required} bytes required"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+/// Tag, bitmap and number of descriptions
+const HEADER_SIZE: usize = 12;
+
+/// Label, flags and coordinates
+const DESC_SIZE: usize = std::mem::size_of::<ChannelDesc>();
+
+/// `AudioChannelLayout` with any number of channel descriptions
+#[doc(alias = "AudioChannelLayout")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelLayoutBuf {
+    tag: ChannelLayoutTag,
+    bitmap: ChannelBitmap,
+    descs: Vec<ChannelDesc>,
+}
+
+impl ChannelLayoutBuf {
+    pub fn with_tag(tag: ChannelLayoutTag) -> Self {
+        Self {
+            tag,
+            bitmap: ChannelBitmap(0),
+            descs: Vec::new(),
+        }
+    }
+
+    pub fn with_bitmap(bitmap: ChannelBitmap) -> Self {
+        Self {
+            tag: ChannelLayoutTag::USE_CHANNEL_BITMAP,
+            bitmap,
+            descs: Vec::new(),
+        }
+    }
+
+    pub fn with_descs(descs: Vec<ChannelDesc>) -> Self {
+        Self {
+            tag: ChannelLayoutTag::USE_CHANNEL_DESCRIPTIONS,
+            bitmap: ChannelBitmap(0),
+            descs,
+        }
+    }
+
+    /// Descriptions without coordinates
+    pub fn with_labels(labels: &[ChannelLabel]) -> Self {
+        Self::with_descs(
+            labels
+                .iter()
+                .map(|&channel_label| ChannelDesc {
+                    channel_label,
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    /// Parses native endian bytes of `AudioChannelLayout`, trailing bytes are ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let u32_at = |i: usize| u32::from_ne_bytes(bytes[i..i + 4].try_into().unwrap());
+        if bytes.len() < HEADER_SIZE {
+            return Err(Error::TooShort {
+                required: HEADER_SIZE,
+                actual: bytes.len(),
+            });
+        }
+        let n = u32_at(8) as usize;
+        let required = n
+            .checked_mul(DESC_SIZE)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .unwrap_or(usize::MAX);
+        if bytes.len() < required {
+            return Err(Error::TooShort {
+                required,
+                actual: bytes.len(),
+            });
+        }
+        let descs = (0..n)
+            .map(|i| {
+                let at = HEADER_SIZE + i * DESC_SIZE;
+                let f32_at = |j: usize| f32::from_bits(u32_at(at + 8 + j * 4));
+                ChannelDesc {
+                    channel_label: ChannelLabel(u32_at(at)),
+                    channel_flags: ChannelFlags(u32_at(at + 4)),
+                    coordinates: [f32_at(0), f32_at(1), f32_at(2)],
+                }
+            })
+            .collect();
+        Ok(Self {
+            tag: ChannelLayoutTag(u32_at(0)),
+            bitmap: ChannelBitmap(u32_at(4)),
+            descs,
+        })
+    }
+
+    /// Copies fixed size layout, `number_channel_descriptions` is clamped to `N`
+    pub fn from_layout<const N: usize>(layout: &ChannelLayout<N>) -> Self {
+        let n = N.min(layout.number_channel_descriptions as usize);
+        Self {
+            tag: layout.channel_layout_tag,
+            bitmap: layout.channel_bitmap,
+            descs: layout.channel_descriptions[..n].to_vec(),
+        }
+    }
+
+    /// Size of serialized layout, at least the size of `AudioChannelLayout` with one
+    /// description like `CAAudioChannelLayout::CalculateByteSize`
+    pub fn byte_size(&self) -> usize {
+        HEADER_SIZE + self.descs.len().max(1) * DESC_SIZE
+    }
+
+    /// Native endian bytes expected by `set_prop` calls
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.byte_size());
+        res.extend_from_slice(&self.tag.0.to_ne_bytes());
+        res.extend_from_slice(&self.bitmap.0.to_ne_bytes());
+        res.extend_from_slice(&(self.descs.len() as u32).to_ne_bytes());
+        for desc in &self.descs {
+            res.extend_from_slice(&desc.channel_label.0.to_ne_bytes());
+            res.extend_from_slice(&desc.channel_flags.0.to_ne_bytes());
+            for c in desc.coordinates {
+                res.extend_from_slice(&c.to_ne_bytes());
+            }
+        }
+        res.resize(self.byte_size(), 0);
+        res
+    }
+
+    /// Calls `f` with aligned C layout and its size in bytes, for APIs taking
+    /// `&ChannelLayout<1>` with size
+    pub fn with_raw<R>(&self, f: impl FnOnce(&ChannelLayout<1>, usize) -> R) -> R {
+        let bytes = self.to_bytes();
+        let words: Vec<u32> = bytes
+            .chunks_exact(4)
+            .map(|w| u32::from_ne_bytes(w.try_into().unwrap()))
+            .collect();
+        // words are 4 byte aligned and hold at least one description
+        let layout = unsafe { &*(words.as_ptr() as *const ChannelLayout<1>) };
+        f(layout, bytes.len())
+    }
+
+    #[inline]
+    pub fn tag(&self) -> ChannelLayoutTag {
+        self.tag
+    }
+
+    #[inline]
+    pub fn bitmap(&self) -> ChannelBitmap {
+        self.bitmap
+    }
+
+    /// Descriptions, used when tag is `USE_CHANNEL_DESCRIPTIONS`
+    #[inline]
+    pub fn descs(&self) -> &[ChannelDesc] {
+        &self.descs
+    }
+
+    #[inline]
+    pub fn descs_mut(&mut self) -> &mut Vec<ChannelDesc> {
+        &mut self.descs
+    }
+
+    /// Number of channels described by tag, bitmap or descriptions
+    #[doc(alias = "kAudioFormatProperty_NumberOfChannelsForLayout")]
+    pub fn number_of_channels(&self) -> u32 {
+        match self.tag {
+            ChannelLayoutTag::USE_CHANNEL_DESCRIPTIONS => self.descs.len() as u32,
+            ChannelLayoutTag::USE_CHANNEL_BITMAP => self.bitmap.channel_labels().len() as u32,
+            tag => tag.number_of_channels(),
+        }
+    }
+
+    /// Labels of channels in order, `None` for unknown layout tags
+    pub fn channel_labels(&self) -> Option<Vec<ChannelLabel>> {
+        match self.tag {
+            ChannelLayoutTag::USE_CHANNEL_DESCRIPTIONS => {
+                Some(self.descs.iter().map(|d| d.channel_label).collect())
+            }
+            ChannelLayoutTag::USE_CHANNEL_BITMAP => Some(self.bitmap.channel_labels()),
+            tag => tag.channel_labels(),
+        }
+    }
+
+    /// Same layout with explicit descriptions, `None` for unknown layout tags
+    pub fn resolved(&self) -> Option<Self> {
+        if self.tag == ChannelLayoutTag::USE_CHANNEL_DESCRIPTIONS {
+            return Some(self.clone());
+        }
+        self.channel_labels()
+            .map(|labels| Self::with_labels(&labels))
+    }
+}
+
+impl<const N: usize> From<&ChannelLayout<N>> for ChannelLayoutBuf {
+    #[inline]
+    fn from(value: &ChannelLayout<N>) -> Self {
+        Self::from_layout(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cat::audio::{
+        ChannelBitmap, ChannelDesc, ChannelFlags, ChannelLabel, ChannelLayout, ChannelLayoutBuf,
+        ChannelLayoutTag, channel_layout::Error,
+    };
+
+    #[test]
+    fn bytes() {
+        let layout = ChannelLayout::<2> {
+            channel_layout_tag: ChannelLayoutTag::USE_CHANNEL_DESCRIPTIONS,
+            channel_bitmap: ChannelBitmap(0),
+            number_channel_descriptions: 2,
+            channel_descriptions: [
+                ChannelDesc {
+                    channel_label: ChannelLabel::LEFT,
+                    channel_flags: ChannelFlags::SPHERICAL_COORDINATES,
+                    coordinates: [-30.0, 0.0, 1.0],
+                },
+                ChannelDesc {
+                    channel_label: ChannelLabel::RIGHT,
+                    channel_flags: ChannelFlags::SPHERICAL_COORDINATES,
+                    coordinates: [30.0, 0.0, 1.0],
+                },
+            ],
+        };
+        let raw = unsafe {
+            std::slice::from_raw_parts(
+                &layout as *const _ as *const u8,
+                std::mem::size_of_val(&layout),
+            )
+        };
+        let buf = ChannelLayoutBuf::from_bytes(raw).unwrap();
+        assert_eq!(buf, ChannelLayoutBuf::from(&layout));
+        assert_eq!(buf.descs()[1].coordinates, [30.0, 0.0, 1.0]);
+        assert_eq!(buf.byte_size(), raw.len());
+        assert_eq!(buf.to_bytes(), raw);
+        assert_eq!(buf.number_of_channels(), 2);
+
+        buf.with_raw(|raw, size| {
+            assert_eq!(size, 52);
+            assert_eq!(raw.number_channel_descriptions, 2);
+            assert_eq!(raw.channel_descriptions[0].coordinates[0], -30.0);
+        });
+
+        assert_eq!(
+            ChannelLayoutBuf::from_bytes(&raw[..40]),
+            Err(Error::TooShort {
+                required: 52,
+                actual: 40
+            })
+        );
+        assert!(ChannelLayoutBuf::from_bytes(&raw[..8]).is_err());
+        let mut huge = raw.to_vec();
+        huge[8..12].copy_from_slice(&u32::MAX.to_ne_bytes());
+        assert!(ChannelLayoutBuf::from_bytes(&huge).is_err());
+
+        // tag only layouts still have room for one description
+        let tag = ChannelLayoutBuf::with_tag(ChannelLayoutTag::STEREO);
+        let bytes = tag.to_bytes();
+        assert_eq!(bytes.len(), std::mem::size_of::<ChannelLayout<1>>());
+        assert_eq!(ChannelLayoutBuf::from_bytes(&bytes).unwrap(), tag);
+        assert_eq!(
+            ChannelLayoutBuf::from_bytes(&bytes).unwrap().to_bytes(),
+            bytes
+        );
+    }
+
+    #[test]
+    fn resolve() {
+        let layout = ChannelLayoutBuf::with_tag(ChannelLayoutTag::MPEG_5_1_A);
+        assert_eq!(layout.number_of_channels(), 6);
+        let resolved = layout.resolved().unwrap();
+        assert_eq!(resolved.tag(), ChannelLayoutTag::USE_CHANNEL_DESCRIPTIONS);
+        assert_eq!(resolved.channel_labels(), layout.channel_labels());
+        assert_eq!(resolved.descs()[3].channel_label, ChannelLabel::LFE_SCREEN);
+
+        let bitmap = ChannelLayoutBuf::with_bitmap(ChannelBitmap(
+            ChannelBitmap::LEFT.0 | ChannelBitmap::RIGHT.0 | ChannelBitmap::LFE_SCREEN.0,
+        ));
+        assert_eq!(bitmap.number_of_channels(), 3);
+        assert_eq!(
+            bitmap.resolved().unwrap().channel_labels().unwrap(),
+            [
+                ChannelLabel::LEFT,
+                ChannelLabel::RIGHT,
+                ChannelLabel::LFE_SCREEN
+            ]
+        );
+
+        let discrete =
+            ChannelLayoutBuf::with_tag(ChannelLayoutTag(ChannelLayoutTag::DISCRETE_IN_ORDER.0 | 3));
+        assert_eq!(
+            discrete.resolved().unwrap().channel_labels().unwrap(),
+            [
+                ChannelLabel::DISCRETE_0,
+                ChannelLabel::DISCRETE_1,
+                ChannelLabel::DISCRETE_2
+            ]
+        );
+
+        let unknown = ChannelLayoutBuf::with_tag(ChannelLayoutTag(0xABCD_0002));
+        assert_eq!(unknown.number_of_channels(), 2);
+        assert_eq!(unknown.resolved(), None);
+    }
+}
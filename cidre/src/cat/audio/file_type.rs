@@ -0,0 +1,109 @@
+/// Identifier for an audio file type.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[doc(alias = "AudioFileTypeID")]
+#[repr(transparent)]
+pub struct FileTypeId(pub u32);
+
+impl From<crate::FourCc> for FileTypeId {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<FileTypeId> for crate::FourCc {
+    #[inline]
+    fn from(value: FileTypeId) -> Self {
+        Self(value.0)
+    }
+}
+
+impl FileTypeId {
+    /// Audio Interchange File Format (AIFF)
+    #[doc(alias = "kAudioFileAIFFType")]
+    pub const AIFF: Self = Self(u32::from_be_bytes(*b"AIFF"));
+
+    /// Audio Interchange File Format Compressed (AIFF-C)
+    #[doc(alias = "kAudioFileAIFCType")]
+    pub const AIFC: Self = Self(u32::from_be_bytes(*b"AIFC"));
+
+    /// Microsoft WAVE
+    #[doc(alias = "kAudioFileWAVEType")]
+    pub const WAVE: Self = Self(u32::from_be_bytes(*b"WAVE"));
+
+    /// File Format specified in EBU Tech 3306
+    #[doc(alias = "kAudioFileRF64Type")]
+    pub const RF64: Self = Self(u32::from_be_bytes(*b"RF64"));
+
+    /// File Format specified in ITU-R BS.2088
+    #[doc(alias = "kAudioFileBW64Type")]
+    pub const BW64: Self = Self(u32::from_be_bytes(*b"BW64"));
+
+    /// Sony Pictures Digital Wave 64
+    #[doc(alias = "kAudioFileWave64Type")]
+    pub const WAVE64: Self = Self(u32::from_be_bytes(*b"W64f"));
+
+    /// Sound Designer II
+    #[doc(alias = "kAudioFileSoundDesigner2Type")]
+    pub const SOUND_DESIGNER2: Self = Self(u32::from_be_bytes(*b"Sd2f"));
+
+    /// NeXT / Sun
+    #[doc(alias = "kAudioFileNextType")]
+    pub const NEXT: Self = Self(u32::from_be_bytes(*b"NeXT"));
+
+    /// MPEG Audio Layer 3 (.mp3)
+    #[doc(alias = "kAudioFileMP3Type")]
+    pub const MP3: Self = Self(u32::from_be_bytes(*b"MPG3"));
+
+    /// MPEG Audio Layer 2 (.mp2)
+    #[doc(alias = "kAudioFileMP2Type")]
+    pub const MP2: Self = Self(u32::from_be_bytes(*b"MPG2"));
+
+    /// MPEG Audio Layer 1 (.mp1)
+    #[doc(alias = "kAudioFileMP1Type")]
+    pub const MP1: Self = Self(u32::from_be_bytes(*b"MPG1"));
+
+    /// AC-3
+    #[doc(alias = "kAudioFileAC3Type")]
+    pub const AC3: Self = Self(u32::from_be_bytes(*b"ac-3"));
+
+    /// Advanced Audio Coding (AAC) Audio Data Transport Stream (ADTS)
+    #[doc(alias = "kAudioFileAAC_ADTSType")]
+    pub const AAC_ADTS: Self = Self(u32::from_be_bytes(*b"adts"));
+
+    /// An MPEG 4 file.
+    #[doc(alias = "kAudioFileMPEG4Type")]
+    pub const MPEG4: Self = Self(u32::from_be_bytes(*b"mp4f"));
+
+    /// An M4A file.
+    #[doc(alias = "kAudioFileM4AType")]
+    pub const M4A: Self = Self(u32::from_be_bytes(*b"m4af"));
+
+    #[doc(alias = "kAudioFileM4BType")]
+    pub const M4B: Self = Self(u32::from_be_bytes(*b"m4bf"));
+
+    /// A Core Audio File Format file.
+    #[doc(alias = "kAudioFileCAFType")]
+    pub const CAF: Self = Self(u32::from_be_bytes(*b"caff"));
+
+    /// A 3GPP file, suitable for video content on GSM mobile phones.
+    #[doc(alias = "kAudioFile3GPType")]
+    pub const _3GP: Self = Self(u32::from_be_bytes(*b"3gpp"));
+
+    /// A 3GPP2 file, suitable for video content on CDMA mobile phones.
+    #[doc(alias = "kAudioFile3GP2Type")]
+    pub const _3GP2: Self = Self(u32::from_be_bytes(*b"3gp2"));
+
+    /// An AMR (Adaptive Multi-Rate) file suitable for compressed speech.
+    #[doc(alias = "kAudioFileAMRType")]
+    pub const AMR: Self = Self(u32::from_be_bytes(*b"amrf"));
+
+    /// Free Lossless Audio Codec
+    #[doc(alias = "kAudioFileFLACType")]
+    pub const FLAC: Self = Self(u32::from_be_bytes(*b"flac"));
+
+    /// Low-overhead audio stream with low-overhead audio transport multiplex, per ISO/IEC 14496-3.
+    /// Support is limited to AudioSyncStream using AudioMuxElement with mux config present.
+    #[doc(alias = "kAudioFileLATMInLOASType")]
+    pub const LATM_IN_LOAS: Self = Self(u32::from_be_bytes(*b"loas"));
+}
@@ -0,0 +1,874 @@
+//! Channel reordering and down/up-mixing of `f32` samples.
+//!
+//! [`Matrix`] holds gains from source to destination channels. It can be built
+//! from layout tags (or channel labels) using standard ITU-R BS.775 downmix
+//! coefficients, from a channel map like `at::AudioConverter` channel map
+//! property, or from custom gains.
+
+use std::f32::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+use super::{BufList, ChannelBitmap, ChannelLabel, ChannelLayout, ChannelLayoutTag};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Channel labels of layout tag are unknown
+    UnknownLayout(ChannelLayoutTag),
+
+    /// Number of gains doesn't match `src_channels * dst_channels`
+    GainsCount { expected: usize, actual: usize },
+
+    /// Channel map refers to missing source channel
+    InvalidChannel(i32),
+
+    /// Number of buffers doesn't match number of channels
+    BufsCount { expected: usize, actual: usize },
+
+    /// Output buffer can't hold mixed frames
+    OutputTooSmall { required: usize, actual: usize },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownLayout(tag) => write!(f, "unknown channel layout {tag:?}"),
+            Self::GainsCount { expected, actual } => {
+                write!(f, "expected {expected} gains, got {actual}")
+            }
+            Self::InvalidChannel(ch) => write!(f, "invalid source channel {ch}"),
+            Self::BufsCount { expected, actual } => {
+                write!(f, "expected {expected} buffers, got {actual}")
+            }
+            Self::OutputTooSmall { required, actual } => {
+                write!(
+                    f,
+                    "output buffer of {actual} samples, {required} samples required"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+const L: ChannelLabel = ChannelLabel::LEFT;
+const R: ChannelLabel = ChannelLabel::RIGHT;
+const C: ChannelLabel = ChannelLabel::CENTER;
+const LFE: ChannelLabel = ChannelLabel::LFE_SCREEN;
+const LS: ChannelLabel = ChannelLabel::LEFT_SURROUND;
+const RS: ChannelLabel = ChannelLabel::RIGHT_SURROUND;
+const LC: ChannelLabel = ChannelLabel::LEFT_CENTER;
+const RC: ChannelLabel = ChannelLabel::RIGHT_CENTER;
+const CS: ChannelLabel = ChannelLabel::CENTER_SURROUND;
+const LSD: ChannelLabel = ChannelLabel::LEFT_SURROUND_DIRECT;
+const RSD: ChannelLabel = ChannelLabel::RIGHT_SURROUND_DIRECT;
+const TS: ChannelLabel = ChannelLabel::TOP_CENTER_SURROUND;
+const VHL: ChannelLabel = ChannelLabel::VERTICAL_HEIGHT_LEFT;
+const VHC: ChannelLabel = ChannelLabel::VERTICAL_HEIGHT_CENTER;
+const VHR: ChannelLabel = ChannelLabel::VERTICAL_HEIGHT_RIGHT;
+const TBL: ChannelLabel = ChannelLabel::TOP_BACK_LEFT;
+const TBC: ChannelLabel = ChannelLabel::TOP_BACK_CENTER;
+const TBR: ChannelLabel = ChannelLabel::TOP_BACK_RIGHT;
+const RLS: ChannelLabel = ChannelLabel::REAR_SURROUND_LEFT;
+const RRS: ChannelLabel = ChannelLabel::REAR_SURROUND_RIGHT;
+const LW: ChannelLabel = ChannelLabel::LEFT_WIDE;
+const RW: ChannelLabel = ChannelLabel::RIGHT_WIDE;
+const LFE2: ChannelLabel = ChannelLabel::LFE2;
+const LT: ChannelLabel = ChannelLabel::LEFT_TOTAL;
+const RT: ChannelLabel = ChannelLabel::RIGHT_TOTAL;
+const MONO: ChannelLabel = ChannelLabel::MONO;
+const LTM: ChannelLabel = ChannelLabel::LEFT_TOP_MIDDLE;
+const RTM: ChannelLabel = ChannelLabel::RIGHT_TOP_MIDDLE;
+const LTR: ChannelLabel = ChannelLabel::LEFT_TOP_REAR;
+const CTR: ChannelLabel = ChannelLabel::CENTER_TOP_REAR;
+const RTR: ChannelLabel = ChannelLabel::RIGHT_TOP_REAR;
+const AW: ChannelLabel = ChannelLabel::AMBISONIC_W;
+const AX: ChannelLabel = ChannelLabel::AMBISONIC_X;
+const AY: ChannelLabel = ChannelLabel::AMBISONIC_Y;
+const AZ: ChannelLabel = ChannelLabel::AMBISONIC_Z;
+const MID: ChannelLabel = ChannelLabel::MS_MID;
+const SIDE: ChannelLabel = ChannelLabel::MS_SIDE;
+const XY_X: ChannelLabel = ChannelLabel::XY_X;
+const XY_Y: ChannelLabel = ChannelLabel::XY_Y;
+const BL: ChannelLabel = ChannelLabel::BINAURAL_LEFT;
+const BR: ChannelLabel = ChannelLabel::BINAURAL_RIGHT;
+const HL: ChannelLabel = ChannelLabel::HEADPHONES_LEFT;
+const HR: ChannelLabel = ChannelLabel::HEADPHONES_RIGHT;
+const ACN_0: ChannelLabel = ChannelLabel::HOA_ACN_0;
+const ACN_1: ChannelLabel = ChannelLabel::HOA_ACN_1;
+const N3D_0: ChannelLabel = ChannelLabel::HOA_N3D;
+const N3D_1: ChannelLabel = ChannelLabel(ChannelLabel::HOA_N3D.0 | 1);
+
+type Tag = ChannelLayoutTag;
+
+/// Channel order of layout tags
+const LAYOUTS: &[(Tag, &[ChannelLabel])] = &[
+    (Tag::MONO, &[MONO]),
+    (Tag::STEREO, &[L, R]),
+    (Tag::STEREO_HEADPHONES, &[HL, HR]),
+    (Tag::MATRIX_STEREO, &[LT, RT]),
+    (Tag::MID_SIDE, &[MID, SIDE]),
+    (Tag::XY, &[XY_X, XY_Y]),
+    (Tag::BINAURAL, &[BL, BR]),
+    (Tag::AMBISONIC_B_FORMAT, &[AW, AX, AY, AZ]),
+    (Tag::QUADRAPHONIC, &[L, R, LS, RS]),
+    (Tag::PENTAGONAL, &[L, R, LS, RS, C]),
+    (Tag::HEXAGONAL, &[L, R, LS, RS, C, CS]),
+    (Tag::OCTAGONAL, &[L, R, LS, RS, C, CS, LW, RW]),
+    (Tag::CUBE, &[L, R, LS, RS, VHL, VHR, TBL, TBR]),
+    (Tag::MPEG_3_0_A, &[L, R, C]),
+    (Tag::MPEG_3_0_B, &[C, L, R]),
+    (Tag::MPEG_4_0_A, &[L, R, C, CS]),
+    (Tag::MPEG_4_0_B, &[C, L, R, CS]),
+    (Tag::MPEG_5_0_A, &[L, R, C, LS, RS]),
+    (Tag::MPEG_5_0_B, &[L, R, LS, RS, C]),
+    (Tag::MPEG_5_0_C, &[L, C, R, LS, RS]),
+    (Tag::MPEG_5_0_D, &[C, L, R, LS, RS]),
+    (Tag::MPEG_5_1_A, &[L, R, C, LFE, LS, RS]),
+    (Tag::MPEG_5_1_B, &[L, R, LS, RS, C, LFE]),
+    (Tag::MPEG_5_1_C, &[L, C, R, LS, RS, LFE]),
+    (Tag::MPEG_5_1_D, &[C, L, R, LS, RS, LFE]),
+    (Tag::MPEG_6_1_A, &[L, R, C, LFE, LS, RS, CS]),
+    (Tag::MPEG_7_1_A, &[L, R, C, LFE, LS, RS, LC, RC]),
+    (Tag::MPEG_7_1_B, &[C, LC, RC, L, R, LS, RS, LFE]),
+    (Tag::MPEG_7_1_C, &[L, R, C, LFE, LS, RS, RLS, RRS]),
+    (Tag::EMAGIC_DEFAULT_7_1, &[L, R, LS, RS, C, LFE, LC, RC]),
+    (Tag::SMPTE_DTV, &[L, R, C, LFE, LS, RS, LT, RT]),
+    (Tag::ITU_2_1, &[L, R, CS]),
+    (Tag::ITU_2_2, &[L, R, LS, RS]),
+    (Tag::DVD_4, &[L, R, LFE]),
+    (Tag::DVD_5, &[L, R, LFE, CS]),
+    (Tag::DVD_6, &[L, R, LFE, LS, RS]),
+    (Tag::DVD_10, &[L, R, C, LFE]),
+    (Tag::DVD_11, &[L, R, C, LFE, CS]),
+    (Tag::DVD_18, &[L, R, LS, RS, LFE]),
+    (Tag::AUDIO_UNIT_6_0, &[L, R, LS, RS, C, CS]),
+    (Tag::AUDIO_UNIT_7_0, &[L, R, LS, RS, C, RLS, RRS]),
+    (Tag::AUDIO_UNIT_7_0_FRONT, &[L, R, LS, RS, C, LC, RC]),
+    (Tag::AAC_6_0, &[C, L, R, LS, RS, CS]),
+    (Tag::AAC_6_1, &[C, L, R, LS, RS, CS, LFE]),
+    (Tag::AAC_7_0, &[C, L, R, LS, RS, RLS, RRS]),
+    (Tag::AAC_7_1_B, &[C, L, R, LS, RS, RLS, RRS, LFE]),
+    (Tag::AAC_7_1_C, &[C, L, R, LS, RS, LFE, VHL, VHR]),
+    (Tag::AAC_OCTAGONAL, &[C, L, R, LS, RS, RLS, RRS, CS]),
+    (Tag::AC3_1_0_1, &[C, LFE]),
+    (Tag::AC3_3_0, &[L, C, R]),
+    (Tag::AC3_3_1, &[L, C, R, CS]),
+    (Tag::AC3_3_0_1, &[L, C, R, LFE]),
+    (Tag::AC3_2_1_1, &[L, R, CS, LFE]),
+    (Tag::AC3_3_1_1, &[L, C, R, CS, LFE]),
+    (Tag::EAC_6_0_A, &[L, C, R, LS, RS, CS]),
+    (Tag::EAC_7_0_A, &[L, C, R, LS, RS, RLS, RRS]),
+    (Tag::EAC3_6_1_A, &[L, C, R, LS, RS, LFE, CS]),
+    (Tag::EAC3_6_1_B, &[L, C, R, LS, RS, LFE, TS]),
+    (Tag::EAC3_6_1_C, &[L, C, R, LS, RS, LFE, VHC]),
+    (Tag::EAC3_7_1_A, &[L, C, R, LS, RS, LFE, RLS, RRS]),
+    (Tag::EAC3_7_1_B, &[L, C, R, LS, RS, LFE, LC, RC]),
+    (Tag::EAC3_7_1_C, &[L, C, R, LS, RS, LFE, LSD, RSD]),
+    (Tag::EAC3_7_1_D, &[L, C, R, LS, RS, LFE, LW, RW]),
+    (Tag::EAC3_7_1_E, &[L, C, R, LS, RS, LFE, VHL, VHR]),
+    (Tag::EAC3_7_1_F, &[L, C, R, LS, RS, LFE, CS, TS]),
+    (Tag::EAC3_7_1_G, &[L, C, R, LS, RS, LFE, CS, VHC]),
+    (Tag::EAC3_7_1_H, &[L, C, R, LS, RS, LFE, TS, VHC]),
+    (Tag::DTS_3_1, &[C, L, R, LFE]),
+    (Tag::DTS_4_1, &[C, L, R, CS, LFE]),
+    (Tag::DTS_6_0_A, &[LC, RC, L, R, LS, RS]),
+    (Tag::DTS_6_0_B, &[C, L, R, RLS, RRS, TS]),
+    (Tag::DTS_6_0_C, &[C, CS, L, R, RLS, RRS]),
+    (Tag::DTS_6_1_A, &[LC, RC, L, R, LS, RS, LFE]),
+    (Tag::DTS_6_1_B, &[C, L, R, RLS, RRS, TS, LFE]),
+    (Tag::DTS_6_1_C, &[C, CS, L, R, RLS, RRS, LFE]),
+    (Tag::DTS_7_0, &[LC, C, RC, L, R, LS, RS]),
+    (Tag::DTS_7_1, &[LC, C, RC, L, R, LS, RS, LFE]),
+    (Tag::DTS_8_0_A, &[LC, RC, L, R, LS, RS, RLS, RRS]),
+    (Tag::DTS_8_0_B, &[LC, C, RC, L, R, LS, CS, RS]),
+    (Tag::DTS_8_1_A, &[LC, RC, L, R, LS, RS, RLS, RRS, LFE]),
+    (Tag::DTS_8_1_B, &[LC, C, RC, L, R, LS, CS, RS, LFE]),
+    (Tag::DTS_6_1_D, &[C, L, R, LS, RS, LFE, CS]),
+    (Tag::WAVE_4_0_B, &[L, R, RLS, RRS]),
+    (Tag::WAVE_5_0_B, &[L, R, C, RLS, RRS]),
+    (Tag::WAVE_5_1_B, &[L, R, C, LFE, RLS, RRS]),
+    (Tag::WAVE_6_1, &[L, R, C, LFE, CS, LS, RS]),
+    (Tag::WAVE_7_1, &[L, R, C, LFE, RLS, RRS, LS, RS]),
+    (Tag::ATMOS_5_1_2, &[L, R, C, LFE, LS, RS, LTM, RTM]),
+    (
+        Tag::ATMOS_5_1_4,
+        &[L, R, C, LFE, LS, RS, VHL, VHR, LTR, RTR],
+    ),
+    (
+        Tag::ATMOS_7_1_2,
+        &[L, R, C, LFE, LS, RS, RLS, RRS, LTM, RTM],
+    ),
+    (
+        Tag::ATMOS_7_1_4,
+        &[L, R, C, LFE, LS, RS, RLS, RRS, VHL, VHR, LTR, RTR],
+    ),
+    (
+        Tag::ATMOS_9_1_6,
+        &[
+            L, R, C, LFE, LS, RS, RLS, RRS, LW, RW, VHL, VHR, LTM, RTM, LTR, RTR,
+        ],
+    ),
+    (Tag::LOGIC_4_0_C, &[L, R, CS, C]),
+    (Tag::LOGIC_6_0_B, &[L, R, LS, RS, CS, C]),
+    (Tag::LOGIC_6_1_B, &[L, R, LS, RS, CS, C, LFE]),
+    (Tag::LOGIC_6_1_D, &[L, C, R, LS, CS, RS, LFE]),
+    (Tag::LOGIC_7_1_B, &[L, R, LS, RS, RLS, RRS, C, LFE]),
+    (
+        Tag::LOGIC_ATMOS_7_1_4_B,
+        &[L, R, RLS, RRS, LS, RS, C, LFE, VHL, VHR, LTR, RTR],
+    ),
+    (
+        Tag::LOGIC_ATMOS_7_1_6,
+        &[L, R, RLS, RRS, LS, RS, C, LFE, VHL, VHR, LTM, RTM, LTR, RTR],
+    ),
+];
+
+impl ChannelLayoutTag {
+    /// Labels of channels in order of layout, `None` for unknown or
+    /// description/bitmap based layouts.
+    pub fn channel_labels(&self) -> Option<Vec<ChannelLabel>> {
+        if let Some((_, labels)) = LAYOUTS.iter().find(|(tag, _)| tag == self) {
+            return Some(labels.to_vec());
+        }
+        let base = match self.0 & 0xFFFF_0000 {
+            x if x == Self::DISCRETE_IN_ORDER.0 => ChannelLabel::DISCRETE_0.0,
+            x if x == Self::HOA_ACN_SN3D.0 => ChannelLabel::HOA_ACN_0.0,
+            x if x == Self::HOA_ACN_N3D.0 => ChannelLabel::HOA_N3D.0,
+            _ => return None,
+        };
+        Some(
+            (0..self.number_of_channels())
+                .map(|i| ChannelLabel(base | i))
+                .collect(),
+        )
+    }
+}
+
+impl ChannelBitmap {
+    /// Labels of set bits in channel order
+    pub fn channel_labels(&self) -> Vec<ChannelLabel> {
+        (0..32u32)
+            .filter(|bit| self.0 & (1 << bit) != 0)
+            .filter_map(|bit| match bit {
+                0..=17 => Some(ChannelLabel(bit + 1)),
+                21 | 23..=26 => Some(ChannelLabel(bit + 28)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl<const N: usize> ChannelLayout<N> {
+    /// Labels of channels from descriptions, bitmap or layout tag
+    pub fn channel_labels(&self) -> Option<Vec<ChannelLabel>> {
+        match self.channel_layout_tag {
+            Tag::USE_CHANNEL_DESCRIPTIONS => {
+                let n = N.min(self.number_channel_descriptions as usize);
+                Some(
+                    self.channel_descriptions[..n]
+                        .iter()
+                        .map(|d| d.channel_label)
+                        .collect(),
+                )
+            }
+            Tag::USE_CHANNEL_BITMAP => Some(self.channel_bitmap.channel_labels()),
+            tag => tag.channel_labels(),
+        }
+    }
+}
+
+/// Downmix coefficients
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Opts {
+    /// Gain of LFE folded into front channels, LFE is dropped by default
+    pub lfe_gain: f32,
+
+    /// Scale rows so the sum of absolute gains doesn't exceed `1.0`, preventing clipping
+    pub normalize: bool,
+}
+
+impl Default for Opts {
+    fn default() -> Self {
+        Self {
+            lfe_gain: 0.0,
+            normalize: false,
+        }
+    }
+}
+
+/// Channels to fold `label` into when destination lacks it
+fn fold(label: ChannelLabel) -> &'static [(ChannelLabel, f32)] {
+    const G: f32 = FRAC_1_SQRT_2;
+    match label {
+        C => &[(L, G), (R, G)],
+        LS => &[(L, G)],
+        RS => &[(R, G)],
+        CS => &[(LS, G), (RS, G)],
+        RLS | LSD => &[(LS, 1.0)],
+        RRS | RSD => &[(RS, 1.0)],
+        LC => &[(L, G), (C, G)],
+        RC => &[(R, G), (C, G)],
+        LW => &[(L, G), (LS, G)],
+        RW => &[(R, G), (RS, G)],
+        VHL | LTM => &[(L, G)],
+        VHR | RTM => &[(R, G)],
+        VHC => &[(C, G)],
+        TS => &[(LTM, G), (RTM, G)],
+        LTR | TBL => &[(LS, G)],
+        RTR | TBR => &[(RS, G)],
+        CTR | TBC => &[(LTR, G), (RTR, G)],
+        LT | BL | HL | XY_X => &[(L, 1.0)],
+        RT | BR | HR | XY_Y => &[(R, 1.0)],
+        MID => &[(L, G), (R, G)],
+        SIDE => &[(L, G), (R, -G)],
+        // FuMa W is attenuated by 3 dB
+        AW => &[(ACN_0, SQRT_2)],
+        AY => &[(ACN_1, 1.0)],
+        N3D_0 => &[(ACN_0, 1.0)],
+        // first order N3D is `sqrt(3)` times SN3D
+        N3D_1 => &[(ACN_1, 0.577_350_3)],
+        ACN_0 => &[(C, 1.0)],
+        // Y is positive to the left
+        ACN_1 => &[(L, 0.5), (R, -0.5)],
+        LFE2 => &[(LFE, 1.0)],
+        _ => &[],
+    }
+}
+
+/// Gains from source to destination channels
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    src_channels: usize,
+    dst_channels: usize,
+
+    /// Row per destination channel
+    gains: Vec<f32>,
+}
+
+impl Matrix {
+    /// Silent matrix
+    pub fn new(src_channels: usize, dst_channels: usize) -> Self {
+        Self {
+            src_channels,
+            dst_channels,
+            gains: vec![0.0; src_channels * dst_channels],
+        }
+    }
+
+    pub fn identity(channels: usize) -> Self {
+        let mut res = Self::new(channels, channels);
+        for ch in 0..channels {
+            res.set_gain(ch, ch, 1.0);
+        }
+        res
+    }
+
+    /// Custom matrix with row of `src_channels` gains per destination channel
+    pub fn with_gains(src_channels: usize, dst_channels: usize, gains: Vec<f32>) -> Result<Self> {
+        let expected = src_channels * dst_channels;
+        if gains.len() != expected {
+            return Err(Error::GainsCount {
+                expected,
+                actual: gains.len(),
+            });
+        }
+        Ok(Self {
+            src_channels,
+            dst_channels,
+            gains,
+        })
+    }
+
+    /// Routes source channel `map[i]` to destination channel `i`, `-1` leaves it silent.
+    /// Same semantics as `at::AudioConverter` channel map.
+    pub fn with_channel_map(src_channels: usize, map: &[i32]) -> Result<Self> {
+        let mut res = Self::new(src_channels, map.len());
+        for (dst, &src) in map.iter().enumerate() {
+            match src {
+                -1 => {}
+                0.. if (src as usize) < src_channels => res.set_gain(src as usize, dst, 1.0),
+                _ => return Err(Error::InvalidChannel(src)),
+            }
+        }
+        Ok(res)
+    }
+
+    /// Reorders matching channels and folds missing ones with ITU downmix coefficients.
+    /// Upmixing only routes matching channels, mono and center are spread to left and right.
+    pub fn with_labels(src: &[ChannelLabel], dst: &[ChannelLabel], opts: &Opts) -> Self {
+        let mut res = Self::new(src.len(), dst.len());
+        let mut row = vec![0.0f32; dst.len()];
+        for (s, &label) in src.iter().enumerate() {
+            row.fill(0.0);
+            route(label, 1.0, dst, opts, &mut row, 0);
+            for (d, gain) in row.iter().enumerate() {
+                res.set_gain(s, d, *gain);
+            }
+        }
+        if opts.normalize {
+            res.normalize();
+        }
+        res
+    }
+
+    pub fn with_tags(src: ChannelLayoutTag, dst: ChannelLayoutTag, opts: &Opts) -> Result<Self> {
+        let src_labels = src.channel_labels().ok_or(Error::UnknownLayout(src))?;
+        let dst_labels = dst.channel_labels().ok_or(Error::UnknownLayout(dst))?;
+        Ok(Self::with_labels(&src_labels, &dst_labels, opts))
+    }
+
+    #[inline]
+    pub fn src_channels(&self) -> usize {
+        self.src_channels
+    }
+
+    #[inline]
+    pub fn dst_channels(&self) -> usize {
+        self.dst_channels
+    }
+
+    /// Row per destination channel
+    #[inline]
+    pub fn gains(&self) -> &[f32] {
+        &self.gains
+    }
+
+    #[inline]
+    pub fn gain(&self, src: usize, dst: usize) -> f32 {
+        self.gains[dst * self.src_channels + src]
+    }
+
+    #[inline]
+    pub fn set_gain(&mut self, src: usize, dst: usize, val: f32) {
+        self.gains[dst * self.src_channels + src] = val;
+    }
+
+    /// Scales rows so the sum of absolute gains doesn't exceed `1.0`
+    pub fn normalize(&mut self) {
+        let max = self
+            .gains
+            .chunks(self.src_channels.max(1))
+            .map(|row| row.iter().map(|g| g.abs()).sum::<f32>())
+            .fold(0.0f32, f32::max);
+        if max > 1.0 {
+            self.gains.iter_mut().for_each(|g| *g /= max);
+        }
+    }
+
+    /// Mixes interleaved frames, returns number of mixed frames
+    pub fn apply(&self, input: &[f32], output: &mut [f32]) -> Result<usize> {
+        if self.src_channels == 0 || self.dst_channels == 0 {
+            return Ok(0);
+        }
+        let frames = input.len() / self.src_channels;
+        let required = frames * self.dst_channels;
+        if output.len() < required {
+            return Err(Error::OutputTooSmall {
+                required,
+                actual: output.len(),
+            });
+        }
+        for (i, o) in input
+            .chunks_exact(self.src_channels)
+            .zip(output.chunks_exact_mut(self.dst_channels))
+        {
+            for (o, row) in o.iter_mut().zip(self.gains.chunks_exact(self.src_channels)) {
+                *o = row.iter().zip(i).map(|(g, s)| g * s).sum();
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Mixes deinterleaved channels, returns number of mixed frames
+    pub fn apply_planar<I: AsRef<[f32]>, O: AsMut<[f32]>>(
+        &self,
+        input: &[I],
+        output: &mut [O],
+    ) -> Result<usize> {
+        check_count(self.src_channels, input.len())?;
+        check_count(self.dst_channels, output.len())?;
+        let frames = input.iter().map(|i| i.as_ref().len()).min().unwrap_or(0);
+        for (d, o) in output.iter_mut().enumerate() {
+            let o = o.as_mut();
+            if o.len() < frames {
+                return Err(Error::OutputTooSmall {
+                    required: frames,
+                    actual: o.len(),
+                });
+            }
+            let o = &mut o[..frames];
+            o.fill(0.0);
+            for (s, i) in input.iter().enumerate() {
+                let gain = self.gain(s, d);
+                if gain == 0.0 {
+                    continue;
+                }
+                for (o, i) in o.iter_mut().zip(i.as_ref()) {
+                    *o += gain * i;
+                }
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Mixes interleaved frames into deinterleaved channels
+    fn deinterleave(&self, input: &[f32], output: &mut [&mut [f32]]) -> Result<usize> {
+        if self.src_channels == 0 || self.dst_channels == 0 {
+            return Ok(0);
+        }
+        check_count(self.dst_channels, output.len())?;
+        let frames = input.len() / self.src_channels;
+        for (o, row) in output
+            .iter_mut()
+            .zip(self.gains.chunks_exact(self.src_channels))
+        {
+            if o.len() < frames {
+                return Err(Error::OutputTooSmall {
+                    required: frames,
+                    actual: o.len(),
+                });
+            }
+            for (o, i) in o.iter_mut().zip(input.chunks_exact(self.src_channels)) {
+                *o = row.iter().zip(i).map(|(g, s)| g * s).sum();
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Mixes deinterleaved channels into interleaved frames
+    fn interleave(&self, input: &[&[f32]], output: &mut [f32]) -> Result<usize> {
+        if self.src_channels == 0 || self.dst_channels == 0 {
+            return Ok(0);
+        }
+        check_count(self.src_channels, input.len())?;
+        let frames = input.iter().map(|i| i.len()).min().unwrap_or(0);
+        let required = frames * self.dst_channels;
+        if output.len() < required {
+            return Err(Error::OutputTooSmall {
+                required,
+                actual: output.len(),
+            });
+        }
+        for (f, o) in output[..required]
+            .chunks_exact_mut(self.dst_channels)
+            .enumerate()
+        {
+            for (o, row) in o.iter_mut().zip(self.gains.chunks_exact(self.src_channels)) {
+                *o = row.iter().zip(input).map(|(g, i)| g * i[f]).sum();
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Mixes native `f32` samples of buffer lists, either single interleaved buffer
+    /// or buffer per channel, layouts of input and output may differ. Updates `data_bytes_size` of output buffers and
+    /// returns number of mixed frames.
+    ///
+    /// # Safety
+    ///
+    /// Buffers should point to `data_bytes_size` valid bytes aligned for `f32`, output
+    /// buffers shouldn't overlap with each other or with input buffers.
+    pub unsafe fn apply_buf_list<const N: usize, const M: usize>(
+        &self,
+        input: &BufList<N>,
+        output: &mut BufList<M>,
+    ) -> Result<usize> {
+        let n = N.min(input.number_buffers as usize);
+        let m = M.min(output.number_buffers as usize);
+        let bufs_in: [&[f32]; N] = std::array::from_fn(|i| {
+            let buf = &input.buffers[i];
+            if i >= n || buf.data.is_null() {
+                return &[][..];
+            }
+            let len = buf.data_bytes_size as usize / size_of::<f32>();
+            unsafe { std::slice::from_raw_parts(buf.data as *const f32, len) }
+        });
+        let mut bufs_out: [&mut [f32]; M] = std::array::from_fn(|i| {
+            let buf = &output.buffers[i];
+            if i >= m || buf.data.is_null() {
+                return &mut [][..];
+            }
+            let len = buf.data_bytes_size as usize / size_of::<f32>();
+            unsafe { std::slice::from_raw_parts_mut(buf.data as *mut f32, len) }
+        });
+
+        let interleaved_in = n == 1 && self.src_channels != 1;
+        let interleaved_out = m == 1 && self.dst_channels != 1;
+        let frames = match (interleaved_in, interleaved_out) {
+            (true, true) => self.apply(bufs_in[0], bufs_out[0])?,
+            (false, false) => self.apply_planar(&bufs_in[..n], &mut bufs_out[..m])?,
+            (true, false) => self.deinterleave(bufs_in[0], &mut bufs_out[..m])?,
+            (false, true) => self.interleave(&bufs_in[..n], bufs_out[0])?,
+        };
+
+        let channels = if interleaved_out {
+            self.dst_channels
+        } else {
+            1
+        };
+        for buf in output.buffers[..m].iter_mut() {
+            buf.data_bytes_size = (frames * channels * size_of::<f32>()) as u32;
+            buf.number_channels = channels as u32;
+        }
+        Ok(frames)
+    }
+}
+
+/// Adds `gain` of `label` to destination channels, folding channels missing in destination
+fn route(
+    label: ChannelLabel,
+    gain: f32,
+    dst: &[ChannelLabel],
+    opts: &Opts,
+    row: &mut [f32],
+    depth: u32,
+) {
+    if let Some(i) = dst.iter().position(|&d| d == label) {
+        row[i] += gain;
+        return;
+    }
+    // guards against folding cycles
+    if depth > 8 {
+        return;
+    }
+    let has = |l: ChannelLabel| dst.contains(&l);
+    match label {
+        // mono destination
+        L | R if !has(L) && !has(R) => {
+            for target in [C, MONO] {
+                if has(target) {
+                    return route(target, gain * FRAC_1_SQRT_2, dst, opts, row, depth + 1);
+                }
+            }
+        }
+        C if has(MONO) => route(MONO, gain, dst, opts, row, depth + 1),
+        MONO => route(C, gain, dst, opts, row, depth + 1),
+        // LFE is folded into center, or front channels when center is missing
+        LFE if opts.lfe_gain != 0.0 => route(C, gain * opts.lfe_gain, dst, opts, row, depth + 1),
+        _ => {
+            for &(target, g) in fold(label) {
+                route(target, gain * g, dst, opts, row, depth + 1);
+            }
+        }
+    }
+}
+
+fn check_count(expected: usize, actual: usize) -> Result {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::BufsCount { expected, actual })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_1_SQRT_2 as G;
+
+    use crate::cat::audio::{
+        Buf, BufList, ChannelBitmap, ChannelLabel, ChannelLayoutTag as Tag, mix,
+    };
+
+    #[test]
+    fn labels() {
+        for (tag, labels) in mix::LAYOUTS {
+            assert_eq!(labels.len(), tag.number_of_channels() as usize, "{tag:?}");
+        }
+        assert_eq!(
+            Tag::MPEG_5_1_C.channel_labels().unwrap(),
+            [
+                ChannelLabel::LEFT,
+                ChannelLabel::CENTER,
+                ChannelLabel::RIGHT,
+                ChannelLabel::LEFT_SURROUND,
+                ChannelLabel::RIGHT_SURROUND,
+                ChannelLabel::LFE_SCREEN
+            ]
+        );
+        let discrete = Tag(Tag::DISCRETE_IN_ORDER.0 | 3).channel_labels().unwrap();
+        assert_eq!(discrete[2], ChannelLabel(ChannelLabel::DISCRETE_0.0 | 2));
+        assert!(Tag(Tag::UNKNOWN.0 | 2).channel_labels().is_none());
+
+        let bitmap = ChannelBitmap(
+            ChannelBitmap::LEFT.0 | ChannelBitmap::RIGHT.0 | ChannelBitmap::LEFT_TOP_REAR.0,
+        );
+        assert_eq!(
+            bitmap.channel_labels(),
+            [
+                ChannelLabel::LEFT,
+                ChannelLabel::RIGHT,
+                ChannelLabel::LEFT_TOP_REAR
+            ]
+        );
+    }
+
+    #[test]
+    fn downmix() {
+        let opts = mix::Opts::default();
+        // C L R Ls Rs LFE
+        let m = mix::Matrix::with_tags(Tag::MPEG_5_1_D, Tag::STEREO, &opts).unwrap();
+        assert_eq!(m.src_channels(), 6);
+        assert_eq!(m.dst_channels(), 2);
+        assert_eq!(
+            m.gains(),
+            &[G, 1.0, 0.0, G, 0.0, 0.0, G, 0.0, 1.0, 0.0, G, 0.0]
+        );
+
+        let m = mix::Matrix::with_tags(Tag::MPEG_5_1_A, Tag::MONO, &opts).unwrap();
+        let g = m.gains();
+        assert!((g[0] - G).abs() < 1e-6);
+        assert!((g[2] - 1.0).abs() < 1e-6);
+        assert_eq!(g[3], 0.0);
+        assert!((g[4] - 0.5).abs() < 1e-6);
+
+        let m = mix::Matrix::with_tags(Tag::MONO, Tag::STEREO, &opts).unwrap();
+        assert_eq!(m.gains(), &[G, G]);
+
+        // rear surrounds fold into side surrounds, LFE into center
+        let opts = mix::Opts {
+            lfe_gain: 0.5,
+            normalize: false,
+        };
+        let m = mix::Matrix::with_tags(Tag::MPEG_7_1_C, Tag::MPEG_5_1_A, &opts).unwrap();
+        assert_eq!(m.gain(6, 4), 1.0);
+        assert_eq!(m.gain(7, 5), 1.0);
+        assert_eq!(m.gain(3, 3), 1.0);
+        assert_eq!(m.gain(3, 2), 0.0);
+
+        let m = mix::Matrix::with_tags(Tag::MPEG_5_1_A, Tag::STEREO, &opts).unwrap();
+        assert_eq!(m.gain(3, 0), 0.5 * G);
+
+        let opts = mix::Opts {
+            lfe_gain: 0.0,
+            normalize: true,
+        };
+        let m = mix::Matrix::with_tags(Tag::MPEG_5_1_A, Tag::STEREO, &opts).unwrap();
+        let sum: f32 = m.gains()[..6].iter().sum();
+        assert!((sum - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn ambisonic() {
+        let m = mix::Matrix::with_tags(Tag::AMBISONIC_B_FORMAT, Tag::STEREO, &Default::default())
+            .unwrap();
+        // source on the left: W = 1/sqrt(2) (FuMa), X = 0, Y = 1
+        let mut out = [0.0f32; 2];
+        m.apply(&[G, 0.0, 1.0, 0.0], &mut out).unwrap();
+        assert!((out[0] - (G + 0.5)).abs() < 1e-6);
+        assert!((out[1] - (G - 0.5)).abs() < 1e-6);
+
+        let sn3d = Tag(Tag::HOA_ACN_SN3D.0 | 4);
+        let m = mix::Matrix::with_tags(sn3d, Tag::STEREO, &Default::default()).unwrap();
+        assert_eq!(m.gain(1, 0), 0.5);
+        assert_eq!(m.gain(2, 0), 0.0);
+    }
+
+    #[test]
+    fn apply() {
+        let opts = mix::Opts::default();
+        // L R C LFE Ls Rs -> C L R Ls Rs LFE
+        let m = mix::Matrix::with_tags(Tag::MPEG_5_1_A, Tag::MPEG_5_1_D, &opts).unwrap();
+        let input = [
+            1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0,
+        ];
+        let mut output = [0.0f32; 12];
+        assert_eq!(m.apply(&input, &mut output), Ok(2));
+        assert_eq!(
+            output,
+            [
+                3.0, 1.0, 2.0, 5.0, 6.0, 4.0, 9.0, 7.0, 8.0, 11.0, 12.0, 10.0
+            ]
+        );
+        assert_eq!(
+            m.apply(&input, &mut output[..11]),
+            Err(mix::Error::OutputTooSmall {
+                required: 12,
+                actual: 11
+            })
+        );
+
+        let m = mix::Matrix::with_channel_map(3, &[2, -1, 0]).unwrap();
+        let input = [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]];
+        let mut output = [[9.0f32; 3]; 3];
+        assert_eq!(m.apply_planar(&input, &mut output), Ok(2));
+        assert_eq!(output, [[5.0, 6.0, 9.0], [0.0, 0.0, 9.0], [1.0, 2.0, 9.0]]);
+        assert_eq!(
+            m.apply_planar(&input[..2], &mut output),
+            Err(mix::Error::BufsCount {
+                expected: 3,
+                actual: 2
+            })
+        );
+
+        assert_eq!(
+            mix::Matrix::with_channel_map(2, &[0, 2]),
+            Err(mix::Error::InvalidChannel(2))
+        );
+        assert_eq!(
+            mix::Matrix::with_gains(2, 2, vec![1.0; 3]),
+            Err(mix::Error::GainsCount {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert_eq!(
+            mix::Matrix::with_tags(Tag::UNKNOWN, Tag::STEREO, &opts),
+            Err(mix::Error::UnknownLayout(Tag::UNKNOWN))
+        );
+    }
+
+    #[test]
+    fn apply_buf_list() {
+        fn buf(data: &mut [f32], channels: u32) -> Buf {
+            Buf {
+                number_channels: channels,
+                data_bytes_size: size_of_val(data) as u32,
+                data: data.as_mut_ptr().cast(),
+            }
+        }
+
+        // stereo to swapped mono pair plus silence
+        let m = mix::Matrix::with_channel_map(2, &[1, 0, -1]).unwrap();
+        let mut interleaved = [1.0f32, 2.0, 3.0, 4.0];
+        let input = BufList::<1> {
+            number_buffers: 1,
+            buffers: [buf(&mut interleaved, 2)],
+        };
+        let mut planar = [[9.0f32; 3]; 3];
+        let [a, b, c] = &mut planar;
+        let mut output = BufList::<3> {
+            number_buffers: 3,
+            buffers: [buf(a, 1), buf(b, 1), buf(c, 1)],
+        };
+        assert_eq!(unsafe { m.apply_buf_list(&input, &mut output) }, Ok(2));
+        assert_eq!(planar, [[2.0, 4.0, 9.0], [1.0, 3.0, 9.0], [0.0, 0.0, 9.0]]);
+        assert!(output.buffers.iter().all(|b| b.data_bytes_size == 8));
+
+        // and back to interleaved
+        let m = mix::Matrix::with_channel_map(3, &[1, 0]).unwrap();
+        let mut back = [0.0f32; 6];
+        let mut back_list = BufList::<1> {
+            number_buffers: 1,
+            buffers: [buf(&mut back, 0)],
+        };
+        assert_eq!(unsafe { m.apply_buf_list(&output, &mut back_list) }, Ok(2));
+        assert_eq!(back[..4], [1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(back_list.buffers[0].data_bytes_size, 16);
+        assert_eq!(back_list.buffers[0].number_channels, 2);
+
+        let mut small = [0.0f32; 3];
+        let mut small_list = BufList::<1> {
+            number_buffers: 1,
+            buffers: [buf(&mut small, 0)],
+        };
+        assert_eq!(
+            unsafe { m.apply_buf_list(&output, &mut small_list) },
+            Err(mix::Error::OutputTooSmall {
+                required: 4,
+                actual: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,726 @@
+//! Native linear PCM conversion.
+//!
+//! Converts sample format, endianness, alignment and interleaving between
+//! layouts described by [`StreamBasicDesc`] without `at::AudioConverter`.
+//! Conversion doesn't allocate, so it can be used in real-time IO callbacks.
+
+use super::{BufList, Format, FormatFlags, StreamBasicDesc};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// Format is not linear PCM
+    NotLinearPcm(Format),
+
+    /// Linear PCM layout which can't be converted natively, like fixed point samples
+    Unsupported(&'static str),
+
+    /// Channel remapping is not performed by [`Converter`]
+    ChannelsMismatch { src: u32, dst: u32 },
+
+    /// Sample rate conversion is not performed by [`Converter`]
+    SampleRateMismatch,
+
+    /// Number of buffers doesn't match layout
+    BufsCount { expected: usize, actual: usize },
+
+    /// Output buffer can't hold converted frames
+    OutputTooSmall { required: usize, actual: usize },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotLinearPcm(format) => write!(f, "{format:?} is not linear PCM"),
+            Self::Unsupported(what) => write!(f, "unsupported linear PCM {what}"),
+            Self::ChannelsMismatch { src, dst } => {
+                write!(f, "can't convert {src} channels to {dst} channels")
+            }
+            Self::SampleRateMismatch => f.write_str("sample rates are different"),
+            Self::BufsCount { expected, actual } => {
+                write!(f, "expected {expected} buffers, got {actual}")
+            }
+            Self::OutputTooSmall { required, actual } => {
+                write!(
+                    f,
+                    "output buffer of {actual} bytes, {required} bytes required"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleKind {
+    SignedInt,
+    UnsignedInt,
+    Float,
+}
+
+/// Encoding of a single sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleFormat {
+    pub kind: SampleKind,
+
+    /// Number of significant bits
+    pub bits: u32,
+
+    /// Number of bytes sample occupies in buffer
+    pub size: u32,
+
+    pub big_endian: bool,
+
+    /// Significant bits are placed in high bits of sample, only matters if `bits < size * 8`
+    pub aligned_high: bool,
+}
+
+impl SampleFormat {
+    fn with_flags(flags: FormatFlags, bits: u32, size: u32) -> Result<Self> {
+        let kind = if flags.contains(FormatFlags::IS_FLOAT) {
+            if !matches!((bits, size), (32, 4) | (64, 8)) {
+                return Err(Error::Unsupported("float size"));
+            }
+            SampleKind::Float
+        } else {
+            if flags.any(FormatFlags::LINEAR_PCM_SAMPLE_FRACTION_MASK) {
+                return Err(Error::Unsupported("fixed point"));
+            }
+            if bits == 0 || size == 0 || size > 4 || bits > size * 8 {
+                return Err(Error::Unsupported("integer size"));
+            }
+            if flags.contains(FormatFlags::IS_SIGNED_INTEGER) {
+                SampleKind::SignedInt
+            } else {
+                SampleKind::UnsignedInt
+            }
+        };
+        Ok(Self {
+            kind,
+            bits,
+            size,
+            big_endian: flags.contains(FormatFlags::IS_BIG_ENDIAN),
+            aligned_high: flags.contains(FormatFlags::IS_ALIGNED_HIGH),
+        })
+    }
+
+    #[inline]
+    pub fn is_float(&self) -> bool {
+        self.kind == SampleKind::Float
+    }
+
+    /// Full scale of integer samples, `2^(bits - 1)`
+    #[inline]
+    fn scale(&self) -> f64 {
+        (1u64 << (self.bits - 1)) as f64
+    }
+
+    #[inline]
+    fn mask(&self) -> u32 {
+        u32::MAX >> (32 - self.bits)
+    }
+
+    #[inline]
+    fn shift(&self) -> u32 {
+        if self.aligned_high {
+            self.size * 8 - self.bits
+        } else {
+            0
+        }
+    }
+
+    #[inline]
+    fn load(&self, bytes: &[u8]) -> u64 {
+        let mut raw = 0u64;
+        if self.big_endian {
+            for &b in bytes {
+                raw = raw << 8 | b as u64;
+            }
+        } else {
+            for &b in bytes.iter().rev() {
+                raw = raw << 8 | b as u64;
+            }
+        }
+        raw
+    }
+
+    #[inline]
+    fn store(&self, mut raw: u64, bytes: &mut [u8]) {
+        if self.big_endian {
+            for b in bytes.iter_mut().rev() {
+                *b = raw as u8;
+                raw >>= 8;
+            }
+        } else {
+            for b in bytes.iter_mut() {
+                *b = raw as u8;
+                raw >>= 8;
+            }
+        }
+    }
+
+    /// Integer sample value in `-2^(bits - 1)..2^(bits - 1)`
+    #[inline]
+    fn read_int(&self, bytes: &[u8]) -> i64 {
+        let raw = (self.load(bytes) as u32 >> self.shift()) & self.mask();
+        match self.kind {
+            SampleKind::UnsignedInt => raw as i64 - (1i64 << (self.bits - 1)),
+            _ => {
+                let unused = 32 - self.bits;
+                ((raw << unused) as i32 >> unused) as i64
+            }
+        }
+    }
+
+    #[inline]
+    fn write_int(&self, val: i64, bytes: &mut [u8]) {
+        let raw = match self.kind {
+            SampleKind::UnsignedInt => (val + (1i64 << (self.bits - 1))) as u32 & self.mask(),
+            // low aligned signed samples keep sign extension in unused bits
+            _ if !self.aligned_high => val as i32 as u32,
+            _ => val as u32 & self.mask(),
+        };
+        self.store((raw << self.shift()) as u64, bytes);
+    }
+
+    /// Sample value, integers are normalized to `-1.0..1.0`
+    #[inline]
+    fn read(&self, bytes: &[u8]) -> f64 {
+        match (self.kind, self.size) {
+            (SampleKind::Float, 4) => f32::from_bits(self.load(bytes) as u32) as f64,
+            (SampleKind::Float, _) => f64::from_bits(self.load(bytes)),
+            _ => self.read_int(bytes) as f64 / self.scale(),
+        }
+    }
+}
+
+/// Layout of frames in buffers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    pub sample: SampleFormat,
+    pub channels: u32,
+    pub interleaved: bool,
+
+    /// Distance between frames in bytes, `bytes_per_frame` of ASBD
+    pub frame_size: u32,
+}
+
+impl Layout {
+    pub fn with_asbd(asbd: &StreamBasicDesc) -> Result<Self> {
+        if asbd.format != Format::LINEAR_PCM {
+            return Err(Error::NotLinearPcm(asbd.format));
+        }
+        if asbd.frames_per_packet != 1 || asbd.bytes_per_packet != asbd.bytes_per_frame {
+            return Err(Error::Unsupported("packet size"));
+        }
+        if asbd.channels_per_frame == 0 || asbd.bytes_per_frame == 0 {
+            return Err(Error::Unsupported("frame size"));
+        }
+        let interleaved = asbd.is_interleaved();
+        let channels = asbd.interleaved_channels_num();
+        if asbd.bytes_per_frame % channels != 0 {
+            return Err(Error::Unsupported("frame size"));
+        }
+        let size = asbd.bytes_per_frame / channels;
+        let sample = SampleFormat::with_flags(asbd.format_flags, asbd.bits_per_channel, size)?;
+        Ok(Self {
+            sample,
+            channels: asbd.channels_per_frame,
+            interleaved,
+            frame_size: asbd.bytes_per_frame,
+        })
+    }
+
+    /// Number of `cat::AudioBuf`s holding frames
+    #[inline]
+    pub fn bufs_count(&self) -> usize {
+        if self.interleaved {
+            1
+        } else {
+            self.channels as usize
+        }
+    }
+
+    /// Buffer index and byte offset of channel sample in frame
+    #[inline]
+    fn locate(&self, frame: usize, channel: usize) -> (usize, usize) {
+        let offset = frame * self.frame_size as usize;
+        if self.interleaved {
+            (0, offset + channel * self.sample.size as usize)
+        } else {
+            (channel, offset)
+        }
+    }
+
+    fn frames_in<B: AsRef<[u8]>>(&self, bufs: &[B]) -> usize {
+        bufs.iter()
+            .map(|b| b.as_ref().len() / self.frame_size as usize)
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Dither {
+    #[default]
+    None,
+
+    /// Triangular probability density noise of ±1 LSB, applied when integer
+    /// output has less precision than input
+    Tpdf,
+}
+
+/// Converts frames between two linear PCM layouts with the same channels and sample rate
+#[derive(Debug, Clone)]
+pub struct Converter {
+    src: Layout,
+    dst: Layout,
+    dither: Dither,
+    seed: u32,
+}
+
+impl Converter {
+    const DEFAULT_SEED: u32 = 0x9e37_79b9;
+
+    pub fn new(src: &StreamBasicDesc, dst: &StreamBasicDesc) -> Result<Self> {
+        if src.sample_rate != 0.0 && dst.sample_rate != 0.0 && src.sample_rate != dst.sample_rate {
+            return Err(Error::SampleRateMismatch);
+        }
+        Self::with_layouts(Layout::with_asbd(src)?, Layout::with_asbd(dst)?)
+    }
+
+    pub fn with_layouts(src: Layout, dst: Layout) -> Result<Self> {
+        if src.channels != dst.channels {
+            return Err(Error::ChannelsMismatch {
+                src: src.channels,
+                dst: dst.channels,
+            });
+        }
+        Ok(Self {
+            src,
+            dst,
+            dither: Dither::None,
+            seed: Self::DEFAULT_SEED,
+        })
+    }
+
+    #[inline]
+    pub fn src(&self) -> &Layout {
+        &self.src
+    }
+
+    #[inline]
+    pub fn dst(&self) -> &Layout {
+        &self.dst
+    }
+
+    #[inline]
+    pub fn dither(&self) -> Dither {
+        self.dither
+    }
+
+    #[inline]
+    pub fn set_dither(&mut self, val: Dither) {
+        self.dither = val;
+    }
+
+    /// Restarts dither noise sequence, so output is reproducible
+    #[inline]
+    pub fn set_dither_seed(&mut self, val: u32) {
+        self.seed = if val == 0 { Self::DEFAULT_SEED } else { val };
+    }
+
+    fn is_dithering(&self) -> bool {
+        let (src, dst) = (&self.src.sample, &self.dst.sample);
+        self.dither == Dither::Tpdf && !dst.is_float() && (src.is_float() || src.bits > dst.bits)
+    }
+
+    /// xorshift32 in `0.0..1.0`
+    #[inline]
+    fn rand(&mut self) -> f64 {
+        let mut x = self.seed;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.seed = x;
+        x as f64 / 4294967296.0
+    }
+
+    /// Converts as many frames as fit in input buffers and returns number of converted frames.
+    ///
+    /// There should be one buffer for interleaved layout and one buffer per channel otherwise.
+    pub fn convert<I: AsRef<[u8]>, O: AsMut<[u8]>>(
+        &mut self,
+        input: &[I],
+        output: &mut [O],
+    ) -> Result<usize> {
+        check_count(self.src.bufs_count(), input.len())?;
+        check_count(self.dst.bufs_count(), output.len())?;
+        let frames = self.src.frames_in(input);
+        let required = frames * self.dst.frame_size as usize;
+        for buf in output.iter_mut() {
+            let actual = buf.as_mut().len();
+            if actual < required {
+                return Err(Error::OutputTooSmall { required, actual });
+            }
+        }
+
+        if self.src == self.dst {
+            for (i, o) in input.iter().zip(output.iter_mut()) {
+                o.as_mut()[..required].copy_from_slice(&i.as_ref()[..required]);
+            }
+            return Ok(frames);
+        }
+
+        let src = self.src;
+        let dst = self.dst;
+        let (src_size, dst_size) = (src.sample.size as usize, dst.sample.size as usize);
+        let dithering = self.is_dithering();
+        let int_to_int = !src.sample.is_float() && !dst.sample.is_float() && !dithering;
+        let (min, max) = (-dst.sample.scale(), dst.sample.scale() - 1.0);
+
+        for frame in 0..frames {
+            for ch in 0..src.channels as usize {
+                let (ib, io) = src.locate(frame, ch);
+                let (ob, oo) = dst.locate(frame, ch);
+                let i = &input[ib].as_ref()[io..io + src_size];
+                let o = &mut output[ob].as_mut()[oo..oo + dst_size];
+
+                if int_to_int {
+                    dst.sample.write_int(
+                        requantize(src.sample.read_int(i), &src.sample, &dst.sample),
+                        o,
+                    );
+                    continue;
+                }
+
+                let val = src.sample.read(i);
+                match (dst.sample.kind, dst.sample.size) {
+                    (SampleKind::Float, 4) => dst.sample.store((val as f32).to_bits() as u64, o),
+                    (SampleKind::Float, _) => dst.sample.store(val.to_bits(), o),
+                    _ => {
+                        let mut val = val * dst.sample.scale();
+                        if dithering {
+                            val += self.rand() - self.rand();
+                        }
+                        dst.sample.write_int(val.round().clamp(min, max) as i64, o);
+                    }
+                }
+            }
+        }
+
+        Ok(frames)
+    }
+
+    /// Converts frames of `input` buffers into `output` and updates `data_bytes_size`
+    /// of output buffers. Returns number of converted frames.
+    ///
+    /// # Safety
+    ///
+    /// Buffers should point to `data_bytes_size` valid bytes, output buffers
+    /// shouldn't overlap with each other or with input buffers.
+    pub unsafe fn convert_buf_list<const N: usize, const M: usize>(
+        &mut self,
+        input: &BufList<N>,
+        output: &mut BufList<M>,
+    ) -> Result<usize> {
+        let n = N.min(input.number_buffers as usize);
+        let m = M.min(output.number_buffers as usize);
+        let bufs_in: [&[u8]; N] = std::array::from_fn(|i| {
+            let buf = &input.buffers[i];
+            if i >= n || buf.data.is_null() {
+                return &[][..];
+            }
+            unsafe { std::slice::from_raw_parts(buf.data, buf.data_bytes_size as usize) }
+        });
+        let mut bufs_out: [&mut [u8]; M] = std::array::from_fn(|i| {
+            let buf = &output.buffers[i];
+            if i >= m || buf.data.is_null() {
+                return &mut [][..];
+            }
+            unsafe { std::slice::from_raw_parts_mut(buf.data, buf.data_bytes_size as usize) }
+        });
+        let frames = self.convert(&bufs_in[..n], &mut bufs_out[..m])?;
+        let channels = if self.dst.interleaved {
+            self.dst.channels
+        } else {
+            1
+        };
+        for buf in output.buffers[..m].iter_mut() {
+            buf.data_bytes_size = (frames * self.dst.frame_size as usize) as u32;
+            buf.number_channels = channels;
+        }
+        Ok(frames)
+    }
+}
+
+fn check_count(expected: usize, actual: usize) -> Result {
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(Error::BufsCount { expected, actual })
+    }
+}
+
+/// Changes integer sample precision, rounding to nearest when bits are dropped
+#[inline]
+fn requantize(val: i64, src: &SampleFormat, dst: &SampleFormat) -> i64 {
+    if dst.bits >= src.bits {
+        return val << (dst.bits - src.bits);
+    }
+    let shift = src.bits - dst.bits;
+    let max = (1i64 << (dst.bits - 1)) - 1;
+    ((val + (1 << (shift - 1))) >> shift).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cat::audio::{
+        Buf, BufList, Format, FormatFlags, StreamBasicDesc,
+        pcm::{Converter, Dither, Error, SampleKind},
+    };
+
+    fn asbd(flags: FormatFlags, bits: u32, size: u32, channels: u32) -> StreamBasicDesc {
+        let interleaved = !flags.contains(FormatFlags::IS_NON_INTERLEAVED);
+        let bytes_per_frame = if interleaved { size * channels } else { size };
+        StreamBasicDesc {
+            sample_rate: 48_000.0,
+            format: Format::LINEAR_PCM,
+            format_flags: flags,
+            bytes_per_packet: bytes_per_frame,
+            frames_per_packet: 1,
+            bytes_per_frame,
+            channels_per_frame: channels,
+            bits_per_channel: bits,
+            reserved: 0,
+        }
+    }
+
+    const INT: FormatFlags =
+        FormatFlags(FormatFlags::IS_SIGNED_INTEGER.0 | FormatFlags::IS_PACKED.0);
+    const FLOAT: FormatFlags = FormatFlags::NATIVE_FLOAT_PACKED;
+    const NON_INTERLEAVED: FormatFlags = FormatFlags::IS_NON_INTERLEAVED;
+    const BIG: FormatFlags = FormatFlags::IS_BIG_ENDIAN;
+
+    #[test]
+    fn i16_to_f32_deinterleaved() {
+        let src = asbd(INT, 16, 2, 2);
+        let dst = asbd(FLOAT | NON_INTERLEAVED, 32, 4, 2);
+        let samples: [i16; 6] = [0, -32768, 16384, 32767, -1, 1];
+        let input: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let mut left = [0u8; 12];
+        let mut right = [0u8; 16];
+        let mut conv = Converter::new(&src, &dst).unwrap();
+        let frames = conv
+            .convert(&[&input], &mut [&mut left[..], &mut right[..]])
+            .unwrap();
+        assert_eq!(frames, 3);
+        let f = |b: &[u8]| -> Vec<f32> {
+            b.chunks(4)
+                .map(|c| f32::from_le_bytes(c.try_into().unwrap()))
+                .collect()
+        };
+        assert_eq!(f(&left), [0.0, 0.5, -1.0 / 32768.0]);
+        assert_eq!(f(&right[..12]), [-1.0, 32767.0 / 32768.0, 1.0 / 32768.0]);
+
+        let mut back = [0u8; 12];
+        let mut conv = Converter::new(&dst, &src).unwrap();
+        conv.convert(&[&left[..], &right[..12]], &mut [&mut back])
+            .unwrap();
+        assert_eq!(back[..], input[..]);
+    }
+
+    #[test]
+    fn i24_layouts() {
+        // packed big endian 24 bit
+        let src = asbd(INT | BIG, 24, 3, 1);
+        // 24 bit in high bits of 32 bit little endian
+        let high = asbd(
+            FormatFlags(FormatFlags::IS_SIGNED_INTEGER.0 | FormatFlags::IS_ALIGNED_HIGH.0),
+            24,
+            4,
+            1,
+        );
+        // 24 bit in low bits of 32 bit little endian
+        let low = asbd(FormatFlags::IS_SIGNED_INTEGER, 24, 4, 1);
+
+        let input = [0x80, 0x00, 0x01, 0x12, 0x34, 0x56];
+        let mut out = [0u8; 8];
+        let mut conv = Converter::new(&src, &high).unwrap();
+        assert_eq!(conv.src().sample.kind, SampleKind::SignedInt);
+        conv.convert(&[&input], &mut [&mut out]).unwrap();
+        assert_eq!(out, [0x00, 0x01, 0x00, 0x80, 0x00, 0x56, 0x34, 0x12]);
+
+        let mut low_out = [0u8; 8];
+        Converter::new(&high, &low)
+            .unwrap()
+            .convert(&[&out], &mut [&mut low_out])
+            .unwrap();
+        assert_eq!(
+            low_out,
+            [0x01, 0x00, 0x80, 0xff, 0x56, 0x34, 0x12, 0x00],
+            "sign extended"
+        );
+
+        let mut i32_out = [0u8; 8];
+        Converter::new(&low, &asbd(INT, 32, 4, 1))
+            .unwrap()
+            .convert(&[&low_out], &mut [&mut i32_out])
+            .unwrap();
+        assert_eq!(i32_out, out);
+    }
+
+    #[test]
+    fn unsigned_and_rounding() {
+        let u8_asbd = asbd(FormatFlags::IS_PACKED, 8, 1, 1);
+        let i16_asbd = asbd(INT, 16, 2, 1);
+
+        let mut out = [0u8; 6];
+        Converter::new(&u8_asbd, &i16_asbd)
+            .unwrap()
+            .convert(&[&[0x00, 0x80, 0xff]], &mut [&mut out])
+            .unwrap();
+        assert_eq!(out, [0x00, 0x80, 0x00, 0x00, 0x00, 0x7f]);
+
+        // 0x7fff rounds up, but clips to max
+        let input: Vec<u8> = [0x7fffi16, 0x0080, -0x0081, -0x8000]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let mut out = [0u8; 4];
+        Converter::new(&i16_asbd, &u8_asbd)
+            .unwrap()
+            .convert(&[&input], &mut [&mut out])
+            .unwrap();
+        assert_eq!(out, [0xff, 0x81, 0x7f, 0x00]);
+    }
+
+    #[test]
+    fn float_clipping_and_dither() {
+        let f64_asbd = asbd(FLOAT, 64, 8, 1);
+        let i16_asbd = asbd(INT | BIG, 16, 2, 1);
+        let input: Vec<u8> = [1.5f64, -1.5, 0.25, 0.0]
+            .iter()
+            .flat_map(|s| s.to_ne_bytes())
+            .collect();
+        let mut out = [0u8; 8];
+        let mut conv = Converter::new(&f64_asbd, &i16_asbd).unwrap();
+        conv.convert(&[&input], &mut [&mut out]).unwrap();
+        assert_eq!(out, [0x7f, 0xff, 0x80, 0x00, 0x20, 0x00, 0x00, 0x00]);
+
+        let input: Vec<u8> = (0..256)
+            .flat_map(|i| (i as f64 / 1024.0).to_ne_bytes())
+            .collect();
+        let mut plain = vec![0u8; 512];
+        let mut dithered = vec![0u8; 512];
+        conv.convert(&[&input], &mut [&mut plain]).unwrap();
+        conv.set_dither(Dither::Tpdf);
+        conv.convert(&[&input], &mut [&mut dithered]).unwrap();
+        assert_ne!(plain, dithered);
+        for (p, d) in plain.chunks(2).zip(dithered.chunks(2)) {
+            let p = i16::from_be_bytes([p[0], p[1]]);
+            let d = i16::from_be_bytes([d[0], d[1]]);
+            assert!((p - d).abs() <= 1);
+        }
+
+        let mut again = vec![0u8; 512];
+        conv.set_dither_seed(0);
+        conv.convert(&[&input], &mut [&mut again]).unwrap();
+        assert_eq!(again, dithered);
+    }
+
+    #[test]
+    fn buf_list_round_trip() {
+        let src = asbd(INT, 16, 2, 2);
+        let dst = asbd(FLOAT | NON_INTERLEAVED, 32, 4, 2);
+        let samples: [i16; 8] = [0, -32768, 16384, 32767, -1, 1, 12345, -12345];
+        let mut input: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let buf = |data: &mut [u8], channels| Buf {
+            number_channels: channels,
+            data_bytes_size: data.len() as u32,
+            data: data.as_mut_ptr(),
+        };
+
+        let interleaved = BufList::<1> {
+            number_buffers: 1,
+            buffers: [buf(&mut input, 2)],
+        };
+        let mut left = [0u8; 32];
+        let mut right = [0u8; 32];
+        let mut planar = BufList::<2> {
+            number_buffers: 2,
+            buffers: [buf(&mut left, 1), buf(&mut right, 1)],
+        };
+        let mut conv = Converter::new(&src, &dst).unwrap();
+        let frames = unsafe { conv.convert_buf_list(&interleaved, &mut planar) }.unwrap();
+        assert_eq!(frames, 4);
+        for b in &planar.buffers {
+            assert_eq!(b.data_bytes_size, 16);
+            assert_eq!(b.number_channels, 1);
+        }
+        assert_eq!(f32::from_le_bytes(left[4..8].try_into().unwrap()), 0.5);
+        assert_eq!(f32::from_le_bytes(right[..4].try_into().unwrap()), -1.0);
+
+        let mut output = [0u8; 24];
+        let mut back = BufList::<1> {
+            number_buffers: 1,
+            buffers: [buf(&mut output, 0)],
+        };
+        let mut conv = Converter::new(&dst, &src).unwrap();
+        let frames = unsafe { conv.convert_buf_list(&planar, &mut back) }.unwrap();
+        assert_eq!(frames, 4);
+        assert_eq!(back.buffers[0].data_bytes_size, 16);
+        assert_eq!(back.buffers[0].number_channels, 2);
+        assert_eq!(output[..16], input);
+    }
+
+    #[test]
+    fn errors() {
+        let i16_asbd = asbd(INT, 16, 2, 2);
+        let mut aac = i16_asbd;
+        aac.format = Format::MPEG4_AAC;
+        assert_eq!(
+            Converter::new(&aac, &i16_asbd).unwrap_err(),
+            Error::NotLinearPcm(Format::MPEG4_AAC)
+        );
+        assert_eq!(
+            Converter::new(&i16_asbd, &asbd(INT, 16, 2, 1)).unwrap_err(),
+            Error::ChannelsMismatch { src: 2, dst: 1 }
+        );
+        let mut fixed = i16_asbd;
+        fixed.format_flags.0 |= 12 << 7;
+        assert_eq!(
+            Converter::new(&fixed, &i16_asbd).unwrap_err(),
+            Error::Unsupported("fixed point")
+        );
+        let mut rate = i16_asbd;
+        rate.sample_rate = 44_100.0;
+        assert_eq!(
+            Converter::new(&rate, &i16_asbd).unwrap_err(),
+            Error::SampleRateMismatch
+        );
+
+        let dst = asbd(FLOAT | NON_INTERLEAVED, 32, 4, 2);
+        let mut conv = Converter::new(&i16_asbd, &dst).unwrap();
+        let mut out = [0u8; 4];
+        assert_eq!(
+            conv.convert(&[&[0u8; 8]], &mut [&mut out]).unwrap_err(),
+            Error::BufsCount {
+                expected: 2,
+                actual: 1
+            }
+        );
+        let mut other = [0u8; 8];
+        assert_eq!(
+            conv.convert(&[&[0u8; 8]], &mut [&mut out[..], &mut other[..]])
+                .unwrap_err(),
+            Error::OutputTooSmall {
+                required: 8,
+                actual: 4
+            }
+        );
+    }
+}
@@ -0,0 +1,562 @@
+//! Streaming sample rate conversion of interleaved `f32` samples.
+//!
+//! [`Resampler`] is a polyphase windowed-sinc (Kaiser) interpolator. Filter length
+//! and stopband attenuation are picked by [`Quality`], the same type
+//! `at::AudioConverter` uses for its sample rate converter quality. Conversion is
+//! deterministic, output doesn't depend on how input is split into chunks.
+
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// Sample rates and ratios should be finite and positive
+    InvalidRate(f64),
+
+    /// Output to input ratio is outside of `1/64..=64`
+    UnsupportedRatio(f64),
+
+    /// At least one channel is required
+    NoChannels,
+
+    /// Number of samples is not a multiple of number of channels
+    PartialFrame { samples: usize, channels: usize },
+
+    /// Output buffer can't hold pending frames
+    OutputTooSmall { required: usize, actual: usize },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRate(rate) => write!(f, "invalid sample rate or ratio {rate}"),
+            Self::UnsupportedRatio(ratio) => {
+                write!(f, "unsupported ratio {ratio}, expected 1/64..=64")
+            }
+            Self::NoChannels => write!(f, "no channels"),
+            Self::PartialFrame { samples, channels } => {
+                write!(
+                    f,
+                    "{samples} samples is not a whole number of {channels} channel frames"
+                )
+            }
+            Self::OutputTooSmall { required, actual } => {
+                write!(
+                    f,
+                    "output buffer of {actual} samples, {required} samples required"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+/// Sample rate converter and codec quality of `at::AudioConverter`
+#[doc(alias = "kAudioConverterSampleRateConverterQuality")]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[repr(transparent)]
+pub struct Quality(pub u32);
+
+impl Quality {
+    /// 64 zero crossings, about -120 dB stopband
+    #[doc(alias = "kAudioConverterQuality_Max")]
+    pub const MAX: Self = Self(0x7F);
+
+    /// 32 zero crossings, about -100 dB stopband
+    #[doc(alias = "kAudioConverterQuality_High")]
+    pub const HIGH: Self = Self(0x60);
+
+    /// 16 zero crossings, about -80 dB stopband
+    #[doc(alias = "kAudioConverterQuality_Medium")]
+    pub const MEDIUM: Self = Self(0x40);
+
+    /// 8 zero crossings, about -60 dB stopband
+    #[doc(alias = "kAudioConverterQuality_Low")]
+    pub const LOW: Self = Self(0x20);
+
+    /// 4 zero crossings, about -40 dB stopband
+    #[doc(alias = "kAudioConverterQuality_Min")]
+    pub const MIN: Self = Self(0);
+
+    fn filter(&self) -> Filter {
+        match self.0 {
+            0..0x20 => Filter::new(4, 4.5, 32, 0.80),
+            0x20..0x40 => Filter::new(8, 6.0, 64, 0.86),
+            0x40..0x60 => Filter::new(16, 8.0, 128, 0.91),
+            0x60..0x7F => Filter::new(32, 10.0, 256, 0.94),
+            _ => Filter::new(64, 12.0, 512, 0.96),
+        }
+    }
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Self::MEDIUM
+    }
+}
+
+/// Mirrors `at::AudioConverter` prime info: number of input frames before and after
+/// each output frame which contribute to it.
+///
+/// Leading frames before the first input frame are assumed to be silence,
+/// so output is not delayed, but `trailing_frames` of input are buffered before
+/// corresponding output is produced. Use [`Resampler::flush`] to get output for
+/// the last `trailing_frames` frames at the end of the stream.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct PrimeInfo {
+    pub leading_frames: u32,
+    pub trailing_frames: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Filter {
+    zero_crossings: usize,
+    beta: f64,
+    phases: usize,
+    rolloff: f64,
+}
+
+impl Filter {
+    const fn new(zero_crossings: usize, beta: f64, phases: usize, rolloff: f64) -> Self {
+        Self {
+            zero_crossings,
+            beta,
+            phases,
+            rolloff,
+        }
+    }
+
+    /// Half of kernel taps and kernel table for distances `0..=half` sampled at `phases`
+    fn kernel(&self, ratio: f64) -> (usize, Vec<f32>) {
+        let cutoff = self.rolloff * ratio.min(1.0);
+        let half = (self.zero_crossings as f64 / cutoff).ceil() as usize;
+        let len = half * self.phases + 2;
+        let i0_beta = bessel_i0(self.beta);
+        let table = (0..len)
+            .map(|i| {
+                let x = i as f64 / self.phases as f64;
+                let u = x / half as f64;
+                if u >= 1.0 {
+                    return 0.0;
+                }
+                let window = bessel_i0(self.beta * (1.0 - u * u).sqrt()) / i0_beta;
+                (cutoff * sinc(cutoff * x) * window) as f32
+            })
+            .collect();
+        (half, table)
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Zeroth order modified Bessel function of the first kind
+fn bessel_i0(x: f64) -> f64 {
+    let q = x * x / 4.0;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-17 {
+        term *= q / (k * k);
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+/// Input position step per output frame, `int + num / den`
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    int: usize,
+    num: u64,
+    den: u64,
+}
+
+impl Step {
+    /// Exact step for integer rates, otherwise 32 bits of fraction
+    fn new(src_rate: f64, dst_rate: f64) -> Self {
+        let exact = |r: f64| r.fract() == 0.0 && r <= u32::MAX as f64;
+        if exact(src_rate) && exact(dst_rate) {
+            let (src, dst) = (src_rate as u64, dst_rate as u64);
+            let gcd = gcd(src, dst);
+            let (src, dst) = (src / gcd, dst / gcd);
+            return Self {
+                int: (src / dst) as usize,
+                num: src % dst,
+                den: dst,
+            };
+        }
+        let step = src_rate / dst_rate;
+        let den = 1u64 << 32;
+        let fixed = (step * den as f64).round() as u64;
+        Self {
+            int: (fixed / den) as usize,
+            num: fixed % den,
+            den,
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        self.int as f64 + self.num as f64 / self.den as f64
+    }
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Bounds output to input ratio, kernel table of `1 / MAX_RATIO` downsampling
+/// with [`Quality::MAX`] has about 2M taps
+const MAX_RATIO: f64 = 64.0;
+
+fn check_ratio(ratio: f64) -> Result {
+    if (1.0 / MAX_RATIO..=MAX_RATIO).contains(&ratio) {
+        Ok(())
+    } else {
+        Err(Error::UnsupportedRatio(ratio))
+    }
+}
+
+/// Streaming sample rate converter
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    src_rate: f64,
+    dst_rate: f64,
+    channels: usize,
+    quality: Quality,
+    phases: usize,
+    half: usize,
+    kernel: Vec<f32>,
+    step: Step,
+
+    /// Buffered interleaved input frames
+    buf: Vec<f32>,
+
+    /// Position of next output frame in `buf`, `pos + num / step.den`
+    pos: usize,
+    num: u64,
+
+    /// Tap weights of current output frame
+    weights: Vec<f32>,
+}
+
+impl Resampler {
+    pub fn new(src_rate: f64, dst_rate: f64, channels: usize, quality: Quality) -> Result<Self> {
+        for rate in [src_rate, dst_rate] {
+            if !rate.is_finite() || rate <= 0.0 {
+                return Err(Error::InvalidRate(rate));
+            }
+        }
+        check_ratio(dst_rate / src_rate)?;
+        if channels == 0 {
+            return Err(Error::NoChannels);
+        }
+        let filter = quality.filter();
+        let (half, kernel) = filter.kernel(dst_rate / src_rate);
+        let mut res = Self {
+            src_rate,
+            dst_rate,
+            channels,
+            quality,
+            phases: filter.phases,
+            half,
+            kernel,
+            step: Step::new(src_rate, dst_rate),
+            buf: Vec::new(),
+            pos: 0,
+            num: 0,
+            weights: vec![0.0; 2 * half],
+        };
+        res.reset();
+        Ok(res)
+    }
+
+    #[inline]
+    pub fn src_rate(&self) -> f64 {
+        self.src_rate
+    }
+
+    #[inline]
+    pub fn dst_rate(&self) -> f64 {
+        self.dst_rate
+    }
+
+    #[inline]
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    #[inline]
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Current output to input frames ratio, `dst_rate / src_rate` unless changed
+    /// with [`Self::set_ratio`]
+    pub fn ratio(&self) -> f64 {
+        1.0 / self.step.as_f64()
+    }
+
+    /// Changes output to input ratio keeping stream position, e.g. to compensate
+    /// clock drift between devices. Filter designed for initial rates is kept, so
+    /// ratio should stay close to `dst_rate / src_rate`.
+    pub fn set_ratio(&mut self, ratio: f64) -> Result {
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(Error::InvalidRate(ratio));
+        }
+        check_ratio(ratio)?;
+        let step = Step::new(1.0, ratio);
+        // keep fractional position in new denominator
+        self.num = ((self.num as u128 * step.den as u128) / self.step.den as u128) as u64;
+        self.step = step;
+        Ok(())
+    }
+
+    pub fn prime_info(&self) -> PrimeInfo {
+        PrimeInfo {
+            leading_frames: self.half as u32 - 1,
+            trailing_frames: self.half as u32,
+        }
+    }
+
+    /// Upper bound of output frames for next `input_frames` including buffered input
+    pub fn max_output_frames(&self, input_frames: usize) -> usize {
+        let frames = self.buf.len() / self.channels + input_frames;
+        self.frames_before(frames) + 1
+    }
+
+    /// Drops buffered input and starts new stream
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.buf.resize((self.half - 1) * self.channels, 0.0);
+        self.pos = self.half - 1;
+        self.num = 0;
+    }
+
+    /// Consumes interleaved `input` and writes as many output frames as `output`
+    /// can hold. Input which doesn't fit is kept for next call. Returns number of
+    /// output frames.
+    pub fn process(&mut self, input: &[f32], output: &mut [f32]) -> Result<usize> {
+        if input.len() % self.channels != 0 {
+            return Err(Error::PartialFrame {
+                samples: input.len(),
+                channels: self.channels,
+            });
+        }
+        self.buf.extend_from_slice(input);
+        let available = (self.buf.len() / self.channels).saturating_sub(self.half);
+        let frames = self.frames_before(available);
+        Ok(self.render(frames, output))
+    }
+
+    /// Writes output of buffered input assuming silence after it, then resets.
+    /// Returns number of output frames.
+    pub fn flush(&mut self, output: &mut [f32]) -> Result<usize> {
+        let end = self.buf.len() / self.channels;
+        let frames = self.frames_before(end);
+        let required = frames * self.channels;
+        if output.len() < required {
+            return Err(Error::OutputTooSmall {
+                required,
+                actual: output.len(),
+            });
+        }
+        self.buf.resize((end + self.half) * self.channels, 0.0);
+        let res = self.render(frames, output);
+        self.reset();
+        Ok(res)
+    }
+
+    /// Number of output frames with positions before buffered frame `end`
+    fn frames_before(&self, end: usize) -> usize {
+        if end <= self.pos {
+            return 0;
+        }
+        let den = self.step.den as u128;
+        let dist = (end - self.pos) as u128 * den - self.num as u128;
+        let step = self.step.int as u128 * den + self.step.num as u128;
+        dist.div_ceil(step) as usize
+    }
+
+    fn render(&mut self, frames: usize, output: &mut [f32]) -> usize {
+        let ch = self.channels;
+        let frames = frames.min(output.len() / ch);
+        let half = self.half;
+        let phases = self.phases as f32;
+        for out in output.chunks_exact_mut(ch).take(frames) {
+            let frac = (self.num as f64 / self.step.den as f64) as f32;
+            // taps from `pos + half` down to `pos - half + 1`
+            for (j, w) in self.weights.iter_mut().enumerate() {
+                let d = (frac + j as f32 - half as f32).abs() * phases;
+                let i = d as usize;
+                let t = d - i as f32;
+                *w = self.kernel[i] + t * (self.kernel[i + 1] - self.kernel[i]);
+            }
+            let first = self.pos + 1 - half;
+            let taps = &self.buf[first * ch..(self.pos + half + 1) * ch];
+            out.fill(0.0);
+            for (frame, w) in taps.chunks_exact(ch).rev().zip(&self.weights) {
+                for (o, s) in out.iter_mut().zip(frame) {
+                    *o += w * s;
+                }
+            }
+
+            self.pos += self.step.int;
+            self.num += self.step.num;
+            if self.num >= self.step.den {
+                self.num -= self.step.den;
+                self.pos += 1;
+            }
+        }
+
+        // keep history needed by next output frame
+        let drop = (self.pos + 1).saturating_sub(half);
+        let drop = drop.min(self.buf.len() / ch);
+        self.buf.drain(..drop * ch);
+        self.pos -= drop;
+        frames
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use crate::cat::audio::resample::{self, Quality, Resampler};
+
+    fn sine(freq: f64, rate: f64, frames: usize) -> Vec<f32> {
+        (0..frames)
+            .map(|i| (2.0 * PI * freq * i as f64 / rate).sin() as f32)
+            .collect()
+    }
+
+    fn resample(r: &mut Resampler, input: &[f32], chunk: usize) -> Vec<f32> {
+        let mut res = Vec::new();
+        for chunk in input.chunks(chunk * r.channels()) {
+            let mut out = vec![0.0; r.max_output_frames(chunk.len()) * r.channels()];
+            let n = r.process(chunk, &mut out).unwrap();
+            res.extend_from_slice(&out[..n * r.channels()]);
+        }
+        let mut out = vec![0.0; r.max_output_frames(0) * r.channels()];
+        let n = r.flush(&mut out).unwrap();
+        res.extend_from_slice(&out[..n * r.channels()]);
+        res
+    }
+
+    #[test]
+    fn frames_count() {
+        let mut r = Resampler::new(44_100.0, 48_000.0, 2, Quality::HIGH).unwrap();
+        let input = vec![0.5f32; 4410 * 2];
+        assert_eq!(resample(&mut r, &input, 4410).len(), 4800 * 2);
+        assert_eq!(resample(&mut r, &input, 100).len(), 4800 * 2);
+
+        let mut r = Resampler::new(48_000.0, 44_100.0, 1, Quality::MIN).unwrap();
+        assert_eq!(resample(&mut r, &[0.0; 4800], 333).len(), 4410);
+
+        let mut r = Resampler::new(48_000.0, 16_000.0, 1, Quality::LOW).unwrap();
+        assert_eq!(resample(&mut r, &[0.0; 100], 7).len(), 34);
+    }
+
+    #[test]
+    fn deterministic() {
+        let input = sine(1000.0, 44_100.0, 10_000);
+        let mut r = Resampler::new(44_100.0, 48_000.0, 1, Quality::MEDIUM).unwrap();
+        let a = resample(&mut r, &input, 10_000);
+        let b = resample(&mut r, &input, 17);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn sine_accuracy() {
+        for (quality, tolerance) in [
+            (Quality::MIN, 3e-2),
+            (Quality::LOW, 3e-3),
+            (Quality::MEDIUM, 3e-4),
+            (Quality::MAX, 3e-5),
+        ] {
+            for (src, dst) in [(44_100.0, 48_000.0), (48_000.0, 44_100.0)] {
+                let mut r = Resampler::new(src, dst, 1, quality).unwrap();
+                let out = resample(&mut r, &sine(1000.0, src, 8000), 512);
+                let expected = sine(1000.0, dst, out.len());
+                // skip edges, there is silence around input
+                let skip = 200;
+                let err = out[skip..out.len() - skip]
+                    .iter()
+                    .zip(&expected[skip..])
+                    .map(|(a, b)| (a - b).abs())
+                    .fold(0.0f32, f32::max);
+                assert!(err < tolerance, "{quality:?} {src} -> {dst}: {err}");
+            }
+        }
+    }
+
+    #[test]
+    fn anti_aliasing() {
+        // 12 kHz is above 8 kHz nyquist of 16 kHz output
+        let mut r = Resampler::new(48_000.0, 16_000.0, 1, Quality::HIGH).unwrap();
+        let out = resample(&mut r, &sine(12_000.0, 48_000.0, 9600), 960);
+        let peak = out[200..out.len() - 200]
+            .iter()
+            .fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak < 1e-3, "{peak}");
+    }
+
+    #[test]
+    fn drift() {
+        let mut r = Resampler::new(48_000.0, 48_000.0, 2, Quality::LOW).unwrap();
+        assert_eq!(r.ratio(), 1.0);
+        r.set_ratio(1.00001).unwrap();
+        assert!((r.ratio() - 1.00001).abs() < 1e-9);
+        let out = resample(&mut r, &vec![0.0; 48_000 * 2], 480);
+        assert_eq!(out.len(), 48_001 * 2);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Resampler::new(0.0, 48_000.0, 1, Quality::MIN).err(),
+            Some(resample::Error::InvalidRate(0.0))
+        );
+        for (src, dst) in [(1e6, 1.0), (1.0, 1e6), (48_000.0, 44_100.0 / 65.0)] {
+            assert_eq!(
+                Resampler::new(src, dst, 1, Quality::MAX).err(),
+                Some(resample::Error::UnsupportedRatio(dst / src))
+            );
+        }
+        assert!(Resampler::new(64.0, 1.0, 1, Quality::MAX).is_ok());
+        assert_eq!(
+            Resampler::new(44_100.0, 48_000.0, 0, Quality::MIN).err(),
+            Some(resample::Error::NoChannels)
+        );
+        let mut r = Resampler::new(44_100.0, 48_000.0, 2, Quality::MIN).unwrap();
+        let info = r.prime_info();
+        assert_eq!(info.trailing_frames, info.leading_frames + 1);
+        assert_eq!(
+            r.process(&[0.0; 3], &mut []),
+            Err(resample::Error::PartialFrame {
+                samples: 3,
+                channels: 2
+            })
+        );
+        r.process(&[0.0; 100], &mut []).unwrap();
+        assert!(matches!(
+            r.flush(&mut [0.0; 2]),
+            Err(resample::Error::OutputTooSmall { .. })
+        ));
+        assert!(r.set_ratio(f64::NAN).is_err());
+        assert_eq!(
+            r.set_ratio(65.0),
+            Err(resample::Error::UnsupportedRatio(65.0))
+        );
+    }
+}
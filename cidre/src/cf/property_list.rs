@@ -206,6 +206,22 @@ impl cf::Plist {
     }
 }
 
+#[cfg(feature = "plist")]
+impl cf::Plist {
+    /// Converts native value via binary encoding.
+    pub fn with_value(value: &crate::plist::Value) -> Option<arc::R<Self>> {
+        let bytes = value.to_binary().ok()?;
+        let data = cf::Data::from_slice(&bytes)?;
+        Self::from_data(&data, cf::PlistMutabilityOpts::IMMUTABLE).ok()
+    }
+
+    /// Converts to native value via binary encoding.
+    pub fn to_value(&self) -> Option<crate::plist::Value> {
+        let data = self.to_cf_data(cf::PlistFormat::BinaryV1_0).ok()?;
+        crate::plist::Value::from_binary(data.as_slice()).ok()
+    }
+}
+
 impl From<&cf::String> for &cf::Plist {
     fn from(value: &cf::String) -> Self {
         unsafe { std::mem::transmute(value) }
@@ -303,6 +319,39 @@ unsafe extern "C-unwind" {
 mod tests {
     use crate::cf;
 
+    #[cfg(feature = "plist")]
+    #[test]
+    fn native_values() {
+        use crate::plist;
+
+        let val = plist::Value::Dict(vec![
+            ("name".into(), "cidre ✓".into()),
+            ("int".into(), (-7).into()),
+            ("big".into(), u64::MAX.into()),
+            ("real".into(), 0.25.into()),
+            ("data".into(), vec![1u8, 2, 3].into()),
+            ("date".into(), plist::Date(10.0).into()),
+            ("list".into(), vec![plist::Value::from(true)].into()),
+        ]);
+        let cf_val = cf::Plist::with_value(&val).unwrap();
+        let dict = cf_val.as_dictionary();
+        assert_eq!(dict.len(), 7);
+
+        // CoreFoundation doesn't keep key order
+        let check = |res: &plist::Value| {
+            assert_eq!(res.as_dict().unwrap().len(), 7);
+            for (key, v) in val.as_dict().unwrap() {
+                assert_eq!(res.get(key), Some(v), "{key}");
+            }
+        };
+        check(&cf_val.to_value().unwrap());
+
+        for format in [cf::PlistFormat::XmlV1_0, cf::PlistFormat::BinaryV1_0] {
+            let data = cf_val.to_cf_data(format).unwrap();
+            check(&plist::Value::from_bytes(data.as_slice()).unwrap());
+        }
+    }
+
     #[test]
     fn basics() {
         let num = cf::Number::from_i16(10);
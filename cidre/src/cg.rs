@@ -2,33 +2,50 @@ mod geometry;
 pub use geometry::Float;
 pub use geometry::Point;
 pub use geometry::Rect;
+pub use geometry::RectEdge;
 pub use geometry::Size;
 pub use geometry::Vector;
 
+#[cfg(target_vendor = "apple")]
 pub mod color_space;
+#[cfg(target_vendor = "apple")]
 pub use color_space::ColorRenderingIntent;
+#[cfg(target_vendor = "apple")]
 pub use color_space::ColorSpace;
+#[cfg(target_vendor = "apple")]
 pub use color_space::ColorSpaceModel;
 
+#[cfg(target_vendor = "apple")]
 mod color;
+#[cfg(target_vendor = "apple")]
 pub use color::Color;
 
+#[cfg(target_vendor = "apple")]
 mod window;
+#[cfg(target_vendor = "apple")]
 pub use window::Id as WindowId;
 
+#[cfg(target_vendor = "apple")]
 mod window_level;
+#[cfg(target_vendor = "apple")]
 pub use window_level::WindowLevel;
 
+#[cfg(target_vendor = "apple")]
 pub mod direct_display;
+#[cfg(target_vendor = "apple")]
 pub use direct_display::Id as DirectDisplayId;
 
 pub mod affine_transform;
 pub use affine_transform::AffineTransform;
 pub use affine_transform::Components as AffineTransformComponents;
 
+#[cfg(target_vendor = "apple")]
 mod error;
+#[cfg(target_vendor = "apple")]
 pub use error::Cb as ErrorCallback;
+#[cfg(target_vendor = "apple")]
 pub use error::Error;
+#[cfg(target_vendor = "apple")]
 pub use error::Status;
 
 #[cfg(target_os = "macos")]
@@ -48,48 +65,76 @@ pub mod event_types;
 #[cfg(target_os = "macos")]
 pub use event_types::*;
 
+#[cfg(target_vendor = "apple")]
 pub mod image;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::AnimationBlock as ImageAnimationBlock;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::AnimationOptKey as ImageAnimationOptKey;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::ImageDst;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::ImageSrc;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::animate_image_at_url;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::animate_image_at_url_with_block;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::animate_image_data;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::animate_image_data_with_block;
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "iio")]
 pub use image::animation_err as image_animation_err;
 
+#[cfg(target_vendor = "apple")]
 pub use image::AlphaInfo as ImageAlphaInfo;
+#[cfg(target_vendor = "apple")]
 pub use image::Image;
 
+#[cfg(target_vendor = "apple")]
 pub mod image_properties;
+#[cfg(target_vendor = "apple")]
 pub use image_properties::Orientation as ImagePropOrientation;
 
+#[cfg(target_vendor = "apple")]
 mod font;
+#[cfg(target_vendor = "apple")]
 pub use font::Font;
+#[cfg(target_vendor = "apple")]
 pub use font::FontPostScriptFormat;
+#[cfg(target_vendor = "apple")]
 pub use font::Glyph;
+#[cfg(target_vendor = "apple")]
 pub use font::Index as FontIndex;
 
+#[cfg(target_vendor = "apple")]
 mod path;
 
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "blocks")]
 pub use path::ApplyBlock as PathApplyBlock;
+#[cfg(target_vendor = "apple")]
 pub use path::Element as PathElement;
+#[cfg(target_vendor = "apple")]
 pub use path::ElementType as PathElementType;
+#[cfg(target_vendor = "apple")]
 pub use path::LineCap;
+#[cfg(target_vendor = "apple")]
 pub use path::LineJoin;
+#[cfg(target_vendor = "apple")]
 pub use path::Path;
+#[cfg(target_vendor = "apple")]
 pub use path::PathApplierFn;
+#[cfg(target_vendor = "apple")]
 pub use path::PathMut;
 
 #[cfg(target_os = "macos")]
@@ -109,5 +154,6 @@ pub use display_stream::UpdateRectType as DisplayStreamUpdateRectType;
 #[cfg(target_os = "macos")]
 pub use display_stream::YCbCrMatrix as DisplayStreamYCbCrMatrix;
 
+#[cfg(target_vendor = "apple")]
 #[link(name = "CoreGraphics", kind = "framework")]
 unsafe extern "C" {}
@@ -42,69 +42,171 @@ pub struct Components {
 }
 
 impl AffineTransform {
+    /// Transform from scale, shear, rotation and translation applied in that order.
+    #[doc(alias = "CGAffineTransformMakeWithComponents")]
     #[inline]
     pub fn with_components(components: Components) -> Self {
-        unsafe { CGAffineTransformMakeWithComponents(components) }
+        let Components {
+            scale,
+            horizontal_shear: sh,
+            rotation,
+            translation,
+        } = components;
+        let (sin, cos) = rotation.sin_cos();
+        Self {
+            a: scale.width * cos,
+            b: scale.width * sin,
+            c: scale.height * (sh * cos - sin),
+            d: scale.height * (sh * sin + cos),
+            tx: translation.dx,
+            ty: translation.dy,
+        }
     }
 
+    #[doc(alias = "CGAffineTransformIdentity")]
     #[inline]
-    pub fn identity() -> Self {
-        unsafe { CGAffineTransformIdentity }
+    pub const fn identity() -> Self {
+        Self::new_scale(1.0, 1.0)
     }
 
+    #[doc(alias = "CGAffineTransformMakeTranslation")]
     #[inline]
-    pub fn new_translation(tx: f64, ty: f64) -> Self {
-        unsafe { CGAffineTransformMakeTranslation(tx, ty) }
+    pub const fn new_translation(tx: cg::Float, ty: cg::Float) -> Self {
+        Self {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+            d: 1.0,
+            tx,
+            ty,
+        }
     }
 
+    #[doc(alias = "CGAffineTransformMakeScale")]
     #[inline]
-    pub fn new_scale(sx: f64, sy: f64) -> Self {
-        unsafe { CGAffineTransformMakeScale(sx, sy) }
+    pub const fn new_scale(sx: cg::Float, sy: cg::Float) -> Self {
+        Self {
+            a: sx,
+            b: 0.0,
+            c: 0.0,
+            d: sy,
+            tx: 0.0,
+            ty: 0.0,
+        }
     }
 
+    /// Counterclockwise rotation by `angle` radians in a y-up coordinate system
+    #[doc(alias = "CGAffineTransformMakeRotation")]
     #[inline]
-    pub fn new_rotation(angle: f64) -> Self {
-        unsafe { CGAffineTransformMakeRotation(angle) }
+    pub fn new_rotation(angle: cg::Float) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            tx: 0.0,
+            ty: 0.0,
+        }
     }
 
+    #[doc(alias = "CGAffineTransformIsIdentity")]
     #[inline]
     pub fn is_identity(&self) -> bool {
-        unsafe { CGAffineTransformIsIdentity(*self) }
+        self.equal_to(&Self::identity())
     }
 
+    /// Translates before applying `self`
+    #[doc(alias = "CGAffineTransformTranslate")]
     #[inline]
-    pub fn translate(&self, tx: f64, ty: f64) -> Self {
-        unsafe { CGAffineTransformTranslate(*self, tx, ty) }
+    pub fn translate(&self, tx: cg::Float, ty: cg::Float) -> Self {
+        Self::new_translation(tx, ty).concat(self)
     }
 
+    /// Scales before applying `self`
+    #[doc(alias = "CGAffineTransformScale")]
     #[inline]
-    pub fn scale(&self, sx: f64, sy: f64) -> Self {
-        unsafe { CGAffineTransformScale(*self, sx, sy) }
+    pub fn scale(&self, sx: cg::Float, sy: cg::Float) -> Self {
+        Self::new_scale(sx, sy).concat(self)
     }
 
+    /// Rotates before applying `self`
+    #[doc(alias = "CGAffineTransformRotate")]
     #[inline]
-    pub fn rotate(&self, angle: f64) -> Self {
-        unsafe { CGAffineTransformRotate(*self, angle) }
+    pub fn rotate(&self, angle: cg::Float) -> Self {
+        Self::new_rotation(angle).concat(self)
     }
 
+    /// Inverse transform, `self` unchanged if it is not invertible
+    #[doc(alias = "CGAffineTransformInvert")]
     #[inline]
     pub fn invert(&self) -> Self {
-        unsafe { CGAffineTransformInvert(*self) }
+        let det = self.a * self.d - self.b * self.c;
+        if det == 0.0 {
+            return *self;
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Self {
+            a,
+            b,
+            c,
+            d,
+            tx: -(self.tx * a + self.ty * c),
+            ty: -(self.tx * b + self.ty * d),
+        }
     }
 
+    /// Applies `self` first and `other` second, same as `self * other`
+    #[doc(alias = "CGAffineTransformConcat")]
     #[inline]
     pub fn concat(&self, other: &Self) -> Self {
-        unsafe { CGAffineTransformConcat(*self, *other) }
+        Self {
+            a: self.a * other.a + self.b * other.c,
+            b: self.a * other.b + self.b * other.d,
+            c: self.c * other.a + self.d * other.c,
+            d: self.c * other.b + self.d * other.d,
+            tx: self.tx * other.a + self.ty * other.c + other.tx,
+            ty: self.tx * other.b + self.ty * other.d + other.ty,
+        }
     }
 
+    #[doc(alias = "CGAffineTransformEqualToTransform")]
     #[inline]
     pub fn equal_to(&self, other: &Self) -> bool {
-        unsafe { CGAffineTransformEqualToTransform(*self, *other) }
+        self.a == other.a
+            && self.b == other.b
+            && self.c == other.c
+            && self.d == other.d
+            && self.tx == other.tx
+            && self.ty == other.ty
     }
 
+    /// Inverse of [`AffineTransform::with_components`].
+    ///
+    /// `scale.width` is never negative, a flip is reported as negative `scale.height`,
+    /// so a horizontal flip is a half turn with a vertical flip. CoreGraphics may put
+    /// the sign of a flip elsewhere, both decompositions compose back to the same transform.
+    #[doc(alias = "CGAffineTransformDecompose")]
     #[inline]
     pub fn decompose(&self) -> Components {
-        unsafe { CGAffineTransformDecompose(*self) }
+        let sx = self.a.hypot(self.b);
+        let rotation = self.b.atan2(self.a);
+        let (sin, cos) = rotation.sin_cos();
+        let sy = self.d * cos - self.c * sin;
+        let horizontal_shear = if sy == 0.0 {
+            0.0
+        } else {
+            (self.c * cos + self.d * sin) / sy
+        };
+        Components {
+            scale: cg::Size::new(sx, sy),
+            horizontal_shear,
+            rotation,
+            translation: cg::Vector {
+                dx: self.tx,
+                dy: self.ty,
+            },
+        }
     }
 }
 
@@ -122,6 +224,37 @@ impl PartialEq for AffineTransform {
     }
 }
 
+impl Default for AffineTransform {
+    #[inline]
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Same as [`AffineTransform::concat`], `a * b` applies `a` first
+///
+/// ```
+/// use cidre::cg;
+///
+/// let t = cg::AffineTransform::new_scale(2.0, 2.0) * cg::AffineTransform::new_translation(1.0, 0.0);
+/// assert_eq!(cg::Point::new(1.0, 1.0) * t, cg::Point::new(3.0, 2.0));
+/// ```
+impl std::ops::Mul for AffineTransform {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        self.concat(&rhs)
+    }
+}
+
+impl std::ops::MulAssign for AffineTransform {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = self.concat(&rhs)
+    }
+}
+
 impl cg::Point {
     #[doc(alias = "CGPointApplyAffineTransform")]
     #[inline]
@@ -145,28 +278,317 @@ impl cg::Size {
 }
 
 impl cg::Rect {
+    /// Smallest rect containing the transformed corners, null rect stays null.
     #[doc(alias = "CGRectApplyAffineTransform")]
     #[inline]
     pub fn apply_affine_transform(&self, t: &AffineTransform) -> Self {
-        unsafe { CGRectApplyAffineTransform(*self, *t) }
+        if self.is_null() {
+            return *self;
+        }
+        let (x0, y0) = (self.origin.x, self.origin.y);
+        let (x1, y1) = (x0 + self.size.width, y0 + self.size.height);
+        let corners = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)]
+            .map(|(x, y)| cg::Point::new(x, y).apply_affine_transform(t));
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for p in &corners[1..] {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+        cg::Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
     }
 }
 
-unsafe extern "C-unwind" {
-    static CGAffineTransformIdentity: AffineTransform;
-
-    fn CGAffineTransformMakeTranslation(tx: f64, ty: f64) -> AffineTransform;
-    fn CGAffineTransformMakeScale(sx: f64, sy: f64) -> AffineTransform;
-    fn CGAffineTransformMakeRotation(angle: f64) -> AffineTransform;
-    fn CGAffineTransformIsIdentity(t: AffineTransform) -> bool;
-    fn CGAffineTransformTranslate(t: AffineTransform, tx: f64, ty: f64) -> AffineTransform;
-    fn CGAffineTransformScale(t: AffineTransform, sx: f64, sy: f64) -> AffineTransform;
-    fn CGAffineTransformRotate(t: AffineTransform, angle: f64) -> AffineTransform;
-    fn CGAffineTransformInvert(t: AffineTransform) -> AffineTransform;
-    fn CGAffineTransformConcat(t: AffineTransform, other: AffineTransform) -> AffineTransform;
-    fn CGAffineTransformEqualToTransform(t: AffineTransform, other: AffineTransform) -> bool;
-    fn CGRectApplyAffineTransform(rect: cg::Rect, t: AffineTransform) -> cg::Rect;
-    fn CGAffineTransformDecompose(t: AffineTransform) -> Components;
-    fn CGAffineTransformMakeWithComponents(components: Components) -> AffineTransform;
+macro_rules! apply_op {
+    ($t:ty) => {
+        impl std::ops::Mul<AffineTransform> for $t {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: AffineTransform) -> Self {
+                self.apply_affine_transform(&rhs)
+            }
+        }
+
+        impl std::ops::MulAssign<AffineTransform> for $t {
+            #[inline]
+            fn mul_assign(&mut self, rhs: AffineTransform) {
+                *self = self.apply_affine_transform(&rhs)
+            }
+        }
+    };
+}
+
+apply_op!(cg::Point);
+apply_op!(cg::Size);
+apply_op!(cg::Rect);
 
+/// Column-major with column vectors, `(a, b, 0)`, `(c, d, 0)` and `(tx, ty, 1)`
+#[cfg(feature = "simd")]
+impl From<AffineTransform> for crate::simd::f32x3x3 {
+    #[inline]
+    fn from(t: AffineTransform) -> Self {
+        use crate::simd::f32x3;
+        Self::with_columns(
+            f32x3::with_xyz(t.a as _, t.b as _, 0.0),
+            f32x3::with_xyz(t.c as _, t.d as _, 0.0),
+            f32x3::with_xyz(t.tx as _, t.ty as _, 1.0),
+        )
+    }
+}
+
+/// Drops the projective row
+#[cfg(feature = "simd")]
+impl From<crate::simd::f32x3x3> for AffineTransform {
+    #[inline]
+    fn from(m: crate::simd::f32x3x3) -> Self {
+        let [c0, c1, c2] = m.0;
+        Self {
+            a: c0.x() as _,
+            b: c0.y() as _,
+            c: c1.x() as _,
+            d: c1.y() as _,
+            tx: c2.x() as _,
+            ty: c2.y() as _,
+        }
+    }
+}
+
+/// Same layout as [`crate::ca::Transform3d::from_cg_affine_transform`]
+#[cfg(feature = "simd")]
+impl From<AffineTransform> for crate::simd::f32x4x4 {
+    #[inline]
+    fn from(t: AffineTransform) -> Self {
+        use crate::simd::f32x4;
+        Self::with_columns(
+            f32x4::with_xyzw(t.a as _, t.b as _, 0.0, 0.0),
+            f32x4::with_xyzw(t.c as _, t.d as _, 0.0, 0.0),
+            f32x4::with_xyzw(0.0, 0.0, 1.0, 0.0),
+            f32x4::with_xyzw(t.tx as _, t.ty as _, 0.0, 1.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_6};
+
+    use crate::cg::{self, AffineTransform, AffineTransformComponents};
+
+    fn close(a: &AffineTransform, b: &AffineTransform) -> bool {
+        [
+            a.a - b.a,
+            a.b - b.b,
+            a.c - b.c,
+            a.d - b.d,
+            a.tx - b.tx,
+            a.ty - b.ty,
+        ]
+        .iter()
+        .all(|v| v.abs() < 1e-9)
+    }
+
+    fn samples() -> Vec<AffineTransform> {
+        let t = AffineTransform::identity();
+        vec![
+            t,
+            t.translate(3.0, -2.0),
+            t.scale(2.0, 0.5),
+            t.rotate(FRAC_PI_6),
+            t.translate(1.0, 2.0).rotate(-1.2).scale(3.0, -1.5),
+            AffineTransform {
+                a: 1.0,
+                b: 0.3,
+                c: 0.7,
+                d: 2.0,
+                tx: 5.0,
+                ty: -4.0,
+            },
+        ]
+    }
+
+    #[test]
+    fn algebra() {
+        let t = AffineTransform::new_translation(10.0, 20.0);
+        let s = AffineTransform::new_scale(2.0, 3.0);
+        let r = AffineTransform::new_rotation(FRAC_PI_2);
+        let p = cg::Point::new(1.0, 1.0);
+
+        assert_eq!(AffineTransform::default(), AffineTransform::identity());
+        assert!(!t.is_identity());
+        assert_eq!(p * (s * t), cg::Point::new(12.0, 23.0));
+        assert_eq!(p * (t * s), cg::Point::new(22.0, 63.0));
+        assert_eq!(s.translate(10.0, 20.0), t * s);
+        assert_eq!(t.scale(2.0, 3.0), s * t);
+        assert!(close(&t.rotate(FRAC_PI_2), &(r * t)));
+
+        let q = p * r;
+        assert!((q.x + 1.0).abs() < 1e-12 && (q.y - 1.0).abs() < 1e-12);
+
+        let mut m = s;
+        m *= t;
+        assert_eq!(m, s * t);
+        assert_eq!(cg::Size::new(1.0, 1.0) * (s * t), cg::Size::new(2.0, 3.0));
+
+        for t in samples() {
+            assert!(close(&(t * t.invert()), &AffineTransform::identity()));
+            let c = t.decompose();
+            assert!(close(&AffineTransform::with_components(c), &t), "{c:?}");
+        }
+        let singular = AffineTransform::new_scale(0.0, 1.0);
+        assert_eq!(singular.invert(), singular);
+
+        let c = AffineTransformComponents {
+            scale: cg::Size::new(2.0, 3.0),
+            horizontal_shear: 0.5,
+            rotation: FRAC_PI_6,
+            translation: cg::Vector { dx: 1.0, dy: 2.0 },
+        };
+        let d = AffineTransform::with_components(c).decompose();
+        assert!((d.scale.width - 2.0).abs() < 1e-12);
+        assert!((d.scale.height - 3.0).abs() < 1e-12);
+        assert!((d.horizontal_shear - 0.5).abs() < 1e-12);
+        assert!((d.rotation - FRAC_PI_6).abs() < 1e-12);
+        assert_eq!(d.translation, c.translation);
+
+        // flips are negative height, horizontal ones with a half turn
+        let d = AffineTransform::new_scale(1.0, -1.0).decompose();
+        assert_eq!((d.scale, d.rotation), (cg::Size::new(1.0, -1.0), 0.0));
+        let d = AffineTransform::new_scale(-1.0, 1.0).decompose();
+        assert_eq!(d.scale, cg::Size::new(1.0, -1.0));
+        assert!((d.rotation - std::f64::consts::PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rects() {
+        let r = cg::Rect::new(1.0, 2.0, 3.0, 4.0);
+        let t = AffineTransform::new_translation(1.0, 1.0).scale(2.0, -1.0);
+        assert_eq!(r * t, cg::Rect::new(3.0, -5.0, 6.0, 4.0));
+        assert_eq!(
+            r * AffineTransform::new_scale(-1.0, 1.0),
+            cg::Rect::new(-4.0, 2.0, 3.0, 4.0)
+        );
+        let mut n = cg::Rect::new(4.0, 6.0, -3.0, -4.0);
+        n *= AffineTransform::identity();
+        assert_eq!(n, r);
+        let null = cg::Rect::new(cg::Float::INFINITY, cg::Float::INFINITY, 0.0, 0.0);
+        assert_eq!(null * t, null);
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn simd() {
+        use crate::simd::{f32x3, f32x3x3, f32x4, f32x4x4};
+
+        let t = AffineTransform::new_translation(1.0, 2.0).scale(2.0, 3.0);
+        let p = cg::Point::new(5.0, 7.0) * t;
+
+        let m3 = f32x3x3::from(t);
+        assert_eq!(
+            m3 * f32x3::with_xyz(5.0, 7.0, 1.0),
+            f32x3::with_xyz(p.x as _, p.y as _, 1.0)
+        );
+        assert_eq!(AffineTransform::from(m3), t);
+
+        let m4 = f32x4x4::from(t);
+        assert_eq!(
+            m4 * f32x4::with_xyzw(5.0, 7.0, 0.0, 1.0),
+            f32x4::with_xyzw(p.x as _, p.y as _, 0.0, 1.0)
+        );
+    }
+
+    /// Native implementation matches CoreGraphics
+    #[cfg(target_vendor = "apple")]
+    #[test]
+    fn parity() {
+        use super::Components;
+
+        let p = cg::Point::new(1.5, -2.5);
+        let r = cg::Rect::new(-1.0, 2.0, 3.0, 5.0);
+        for t in samples() {
+            unsafe {
+                assert!(t.is_identity() == CGAffineTransformIsIdentity(t));
+                assert!(close(
+                    &t.translate(2.0, 3.0),
+                    &CGAffineTransformTranslate(t, 2.0, 3.0)
+                ));
+                assert!(close(
+                    &t.scale(-2.0, 0.5),
+                    &CGAffineTransformScale(t, -2.0, 0.5)
+                ));
+                assert!(close(&t.rotate(1.0), &CGAffineTransformRotate(t, 1.0)));
+                assert!(close(&t.invert(), &CGAffineTransformInvert(t)));
+                for o in samples() {
+                    assert!(close(&(t * o), &CGAffineTransformConcat(t, o)));
+                }
+                let rect = r * t;
+                let want = CGRectApplyAffineTransform(r, t);
+                assert!((rect.origin.x - want.origin.x).abs() < 1e-9);
+                assert!((rect.origin.y - want.origin.y).abs() < 1e-9);
+                assert!((rect.size.width - want.size.width).abs() < 1e-9);
+                assert!((rect.size.height - want.size.height).abs() < 1e-9);
+                let q = p * t;
+                let want = CGPointApplyAffineTransform(p, t);
+                assert!((q.x - want.x).abs() < 1e-9 && (q.y - want.y).abs() < 1e-9);
+
+                let c = t.decompose();
+                let want = CGAffineTransformDecompose(t);
+                if t.a * t.d - t.b * t.c > 0.0 {
+                    assert!((c.scale.width - want.scale.width).abs() < 1e-9);
+                    assert!((c.scale.height - want.scale.height).abs() < 1e-9);
+                    assert!((c.horizontal_shear - want.horizontal_shear).abs() < 1e-9);
+                    assert!((c.rotation - want.rotation).abs() < 1e-9);
+                } else {
+                    // flips may be reported differently, both compose back
+                    assert!(close(&AffineTransform::with_components(c), &t));
+                    assert!(close(&AffineTransform::with_components(want), &t));
+                }
+                let c = want;
+                assert!(close(
+                    &AffineTransform::with_components(c),
+                    &CGAffineTransformMakeWithComponents(c)
+                ));
+            }
+        }
+        unsafe {
+            assert_eq!(AffineTransform::identity(), CGAffineTransformIdentity);
+            assert!(close(
+                &AffineTransform::new_rotation(0.3),
+                &CGAffineTransformMakeRotation(0.3)
+            ));
+            assert_eq!(
+                AffineTransform::new_translation(1.0, 2.0),
+                CGAffineTransformMakeTranslation(1.0, 2.0)
+            );
+            assert_eq!(
+                AffineTransform::new_scale(1.0, 2.0),
+                CGAffineTransformMakeScale(1.0, 2.0)
+            );
+            let null = cg::Rect::null();
+            let t = AffineTransform::new_scale(2.0, 2.0);
+            assert_eq!(null * t, CGRectApplyAffineTransform(null, t));
+        }
+
+        unsafe extern "C-unwind" {
+            static CGAffineTransformIdentity: AffineTransform;
+
+            fn CGAffineTransformMakeTranslation(tx: f64, ty: f64) -> AffineTransform;
+            fn CGAffineTransformMakeScale(sx: f64, sy: f64) -> AffineTransform;
+            fn CGAffineTransformMakeRotation(angle: f64) -> AffineTransform;
+            fn CGAffineTransformIsIdentity(t: AffineTransform) -> bool;
+            fn CGAffineTransformTranslate(t: AffineTransform, tx: f64, ty: f64) -> AffineTransform;
+            fn CGAffineTransformScale(t: AffineTransform, sx: f64, sy: f64) -> AffineTransform;
+            fn CGAffineTransformRotate(t: AffineTransform, angle: f64) -> AffineTransform;
+            fn CGAffineTransformInvert(t: AffineTransform) -> AffineTransform;
+            fn CGAffineTransformConcat(
+                t: AffineTransform,
+                other: AffineTransform,
+            ) -> AffineTransform;
+            fn CGPointApplyAffineTransform(point: cg::Point, t: AffineTransform) -> cg::Point;
+            fn CGRectApplyAffineTransform(rect: cg::Rect, t: AffineTransform) -> cg::Rect;
+            fn CGAffineTransformDecompose(t: AffineTransform) -> Components;
+            fn CGAffineTransformMakeWithComponents(components: Components) -> AffineTransform;
+        }
+    }
 }
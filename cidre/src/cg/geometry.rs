@@ -1,3 +1,4 @@
+#[cfg(target_vendor = "apple")]
 use crate::{arc, cf};
 
 #[cfg(target_pointer_width = "32")]
@@ -25,6 +26,7 @@ impl Point {
     /// let d = cg::Point::zero().dictionary_representaion();
     /// assert_eq!(d.len(), 2);
     /// ```
+    #[cfg(target_vendor = "apple")]
     pub fn dictionary_representaion(&self) -> arc::R<cf::Dictionary> {
         unsafe { CGPointCreateDictionaryRepresentation(*self) }
     }
@@ -57,6 +59,7 @@ impl Size {
     /// let d = cg::Size::zero().dictionary_representaion();
     /// assert_eq!(d.len(), 2);
     /// ```
+    #[cfg(target_vendor = "apple")]
     pub fn dictionary_representaion(&self) -> arc::R<cf::Dictionary> {
         unsafe { CGSizeCreateDictionaryRepresentation(*self) }
     }
@@ -65,6 +68,32 @@ impl Size {
     pub const fn new(width: Float, height: Float) -> Self {
         Self { width, height }
     }
+
+    /// Largest size with the aspect ratio of `self` that fits into `bounds`
+    ///
+    /// ```
+    /// use cidre::cg;
+    ///
+    /// let s = cg::Size::new(16.0, 9.0).aspect_fit(cg::Size::new(1000.0, 1000.0));
+    /// assert_eq!(s, cg::Size::new(1000.0, 562.5));
+    /// ```
+    pub fn aspect_fit(&self, bounds: Size) -> Self {
+        self.aspect_scaled(bounds, Float::min)
+    }
+
+    /// Smallest size with the aspect ratio of `self` that covers `bounds`
+    pub fn aspect_fill(&self, bounds: Size) -> Self {
+        self.aspect_scaled(bounds, Float::max)
+    }
+
+    fn aspect_scaled(&self, bounds: Size, pick: fn(Float, Float) -> Float) -> Self {
+        let (w, h) = (self.width.abs(), self.height.abs());
+        if w == 0.0 || h == 0.0 {
+            return Self::zero();
+        }
+        let scale = pick(bounds.width.abs() / w, bounds.height.abs() / h);
+        Self::new(w * scale, h * scale)
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Default)]
@@ -83,9 +112,18 @@ impl Rect {
         }
     }
 
+    /// The rect returned for empty intersections, absorbed by unions.
+    #[doc(alias = "CGRectNull")]
+    #[inline]
+    pub const fn null() -> Self {
+        Self::new(Float::INFINITY, Float::INFINITY, 0.0, 0.0)
+    }
+
+    /// The rect with no bounds.
+    #[doc(alias = "CGRectInfinite")]
     #[inline]
-    pub fn null() -> Self {
-        unsafe { CGRectNull }
+    pub const fn infinite() -> Self {
+        Self::new(Float::MIN / 2.0, Float::MIN / 2.0, Float::MAX, Float::MAX)
     }
 
     /// ```
@@ -95,6 +133,7 @@ impl Rect {
     /// assert_eq!(d.len(), 4);
     /// ```
     #[inline]
+    #[cfg(target_vendor = "apple")]
     pub fn dictionary_representaion(&self) -> arc::R<cf::Dictionary> {
         unsafe { CGRectCreateDictionaryRepresentation(*self) }
     }
@@ -114,6 +153,311 @@ impl Rect {
             size: Size { width, height },
         }
     }
+
+    #[doc(alias = "CGRectIsNull")]
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.origin.x == Float::INFINITY || self.origin.y == Float::INFINITY
+    }
+
+    #[doc(alias = "CGRectIsInfinite")]
+    #[inline]
+    pub fn is_infinite(&self) -> bool {
+        *self == Self::infinite()
+    }
+
+    /// Null rect or rect without area
+    #[doc(alias = "CGRectIsEmpty")]
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.is_null() || self.size.width == 0.0 || self.size.height == 0.0
+    }
+
+    #[doc(alias = "CGRectGetMinX")]
+    #[inline]
+    pub fn min_x(&self) -> Float {
+        self.origin.x.min(self.origin.x + self.size.width)
+    }
+
+    #[doc(alias = "CGRectGetMidX")]
+    #[inline]
+    pub fn mid_x(&self) -> Float {
+        self.origin.x + self.size.width * 0.5
+    }
+
+    #[doc(alias = "CGRectGetMaxX")]
+    #[inline]
+    pub fn max_x(&self) -> Float {
+        self.origin.x.max(self.origin.x + self.size.width)
+    }
+
+    #[doc(alias = "CGRectGetMinY")]
+    #[inline]
+    pub fn min_y(&self) -> Float {
+        self.origin.y.min(self.origin.y + self.size.height)
+    }
+
+    #[doc(alias = "CGRectGetMidY")]
+    #[inline]
+    pub fn mid_y(&self) -> Float {
+        self.origin.y + self.size.height * 0.5
+    }
+
+    #[doc(alias = "CGRectGetMaxY")]
+    #[inline]
+    pub fn max_y(&self) -> Float {
+        self.origin.y.max(self.origin.y + self.size.height)
+    }
+
+    #[doc(alias = "CGRectGetWidth")]
+    #[inline]
+    pub fn width(&self) -> Float {
+        self.size.width.abs()
+    }
+
+    #[doc(alias = "CGRectGetHeight")]
+    #[inline]
+    pub fn height(&self) -> Float {
+        self.size.height.abs()
+    }
+
+    #[inline]
+    pub fn center(&self) -> Point {
+        Point::new(self.mid_x(), self.mid_y())
+    }
+
+    #[inline]
+    fn with_bounds(min_x: Float, min_y: Float, max_x: Float, max_y: Float) -> Self {
+        Self::new(min_x, min_y, max_x - min_x, max_y - min_y)
+    }
+
+    /// Same rect with non-negative width and height
+    #[doc(alias = "CGRectStandardize")]
+    #[inline]
+    pub fn standardize(&self) -> Self {
+        if self.is_null() {
+            return *self;
+        }
+        Self::with_bounds(self.min_x(), self.min_y(), self.max_x(), self.max_y())
+    }
+
+    /// Smallest rect with integral origin and size containing `self`
+    #[doc(alias = "CGRectIntegral")]
+    #[inline]
+    pub fn integral(&self) -> Self {
+        if self.is_null() {
+            return *self;
+        }
+        Self::with_bounds(
+            self.min_x().floor(),
+            self.min_y().floor(),
+            self.max_x().ceil(),
+            self.max_y().ceil(),
+        )
+    }
+
+    /// Shrinks the rect by `dx` on the left and right and by `dy` on the top and bottom,
+    /// negative values grow it. Null if nothing is left.
+    #[doc(alias = "CGRectInset")]
+    #[inline]
+    pub fn inset(&self, dx: Float, dy: Float) -> Self {
+        if self.is_null() {
+            return *self;
+        }
+        let r = self.standardize();
+        let (w, h) = (r.size.width - dx * 2.0, r.size.height - dy * 2.0);
+        if w < 0.0 || h < 0.0 {
+            return Self::null();
+        }
+        Self::new(r.origin.x + dx, r.origin.y + dy, w, h)
+    }
+
+    #[doc(alias = "CGRectOffset")]
+    #[inline]
+    pub fn offset(&self, dx: Float, dy: Float) -> Self {
+        if self.is_null() {
+            return *self;
+        }
+        let r = self.standardize();
+        Self::new(
+            r.origin.x + dx,
+            r.origin.y + dy,
+            r.size.width,
+            r.size.height,
+        )
+    }
+
+    /// Smallest rect containing both, null rects are ignored
+    ///
+    /// ```
+    /// use cidre::cg;
+    ///
+    /// let a = cg::Rect::new(0.0, 0.0, 10.0, 10.0);
+    /// let b = cg::Rect::new(5.0, -5.0, 10.0, 10.0);
+    /// assert_eq!(a.union(&b), cg::Rect::new(0.0, -5.0, 15.0, 15.0));
+    /// assert_eq!(a.union(&cg::Rect::null()), a);
+    /// ```
+    #[doc(alias = "CGRectUnion")]
+    #[inline]
+    pub fn union(&self, other: &Self) -> Self {
+        if self.is_null() {
+            return other.standardize();
+        }
+        if other.is_null() {
+            return self.standardize();
+        }
+        Self::with_bounds(
+            self.min_x().min(other.min_x()),
+            self.min_y().min(other.min_y()),
+            self.max_x().max(other.max_x()),
+            self.max_y().max(other.max_y()),
+        )
+    }
+
+    /// Common part of both rects, null if they don't intersect.
+    /// Rects sharing an edge intersect in an empty rect.
+    ///
+    /// ```
+    /// use cidre::cg;
+    ///
+    /// let a = cg::Rect::new(0.0, 0.0, 10.0, 10.0);
+    /// let b = cg::Rect::new(5.0, -5.0, 10.0, 10.0);
+    /// assert_eq!(a.intersection(&b), cg::Rect::new(5.0, 0.0, 5.0, 5.0));
+    /// assert!(a.intersection(&b.offset(20.0, 0.0)).is_null());
+    /// ```
+    #[doc(alias = "CGRectIntersection")]
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Self {
+        if self.is_null() || other.is_null() {
+            return Self::null();
+        }
+        let min_x = self.min_x().max(other.min_x());
+        let min_y = self.min_y().max(other.min_y());
+        let max_x = self.max_x().min(other.max_x());
+        let max_y = self.max_y().min(other.max_y());
+        if min_x > max_x || min_y > max_y {
+            return Self::null();
+        }
+        Self::with_bounds(min_x, min_y, max_x, max_y)
+    }
+
+    #[doc(alias = "CGRectIntersectsRect")]
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        !self.intersection(other).is_null()
+    }
+
+    /// Points on the max x and max y edges are outside
+    #[doc(alias = "CGRectContainsPoint")]
+    #[inline]
+    pub fn contains_point(&self, point: Point) -> bool {
+        !self.is_null()
+            && point.x >= self.min_x()
+            && point.x < self.max_x()
+            && point.y >= self.min_y()
+            && point.y < self.max_y()
+    }
+
+    #[doc(alias = "CGRectContainsRect")]
+    #[inline]
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        !self.is_null()
+            && !other.is_null()
+            && other.min_x() >= self.min_x()
+            && other.max_x() <= self.max_x()
+            && other.min_y() >= self.min_y()
+            && other.max_y() <= self.max_y()
+    }
+
+    /// Splits the rect into a slice of `amount` along `edge` and the remainder.
+    ///
+    /// ```
+    /// use cidre::cg;
+    ///
+    /// let r = cg::Rect::new(0.0, 0.0, 100.0, 50.0);
+    /// let (slice, rest) = r.divide(30.0, cg::RectEdge::MaxX);
+    /// assert_eq!(slice, cg::Rect::new(70.0, 0.0, 30.0, 50.0));
+    /// assert_eq!(rest, cg::Rect::new(0.0, 0.0, 70.0, 50.0));
+    /// ```
+    #[doc(alias = "CGRectDivide")]
+    pub fn divide(&self, amount: Float, edge: RectEdge) -> (Self, Self) {
+        if self.is_null() {
+            return (Self::null(), Self::null());
+        }
+        let r = self.standardize();
+        let (x0, y0, x1, y1) = (r.min_x(), r.min_y(), r.max_x(), r.max_y());
+        let amount = amount.max(0.0);
+        match edge {
+            RectEdge::MinX => {
+                let x = (x0 + amount).min(x1);
+                (
+                    Self::with_bounds(x0, y0, x, y1),
+                    Self::with_bounds(x, y0, x1, y1),
+                )
+            }
+            RectEdge::MaxX => {
+                let x = (x1 - amount).max(x0);
+                (
+                    Self::with_bounds(x, y0, x1, y1),
+                    Self::with_bounds(x0, y0, x, y1),
+                )
+            }
+            RectEdge::MinY => {
+                let y = (y0 + amount).min(y1);
+                (
+                    Self::with_bounds(x0, y0, x1, y),
+                    Self::with_bounds(x0, y, x1, y1),
+                )
+            }
+            RectEdge::MaxY => {
+                let y = (y1 - amount).max(y0);
+                (
+                    Self::with_bounds(x0, y, x1, y1),
+                    Self::with_bounds(x0, y0, x1, y),
+                )
+            }
+        }
+    }
+
+    /// Largest rect with `aspect_ratio` centered inside `self`
+    ///
+    /// ```
+    /// use cidre::cg;
+    ///
+    /// let r = cg::Rect::with_size(1000.0, 1000.0).aspect_fit(cg::Size::new(16.0, 9.0));
+    /// assert_eq!(r, cg::Rect::new(0.0, 218.75, 1000.0, 562.5));
+    /// ```
+    #[doc(alias = "AVMakeRectWithAspectRatioInsideRect")]
+    #[inline]
+    pub fn aspect_fit(&self, aspect_ratio: Size) -> Self {
+        self.centered(aspect_ratio.aspect_fit(self.size))
+    }
+
+    /// Smallest rect with `aspect_ratio` centered on `self` covering it
+    #[inline]
+    pub fn aspect_fill(&self, aspect_ratio: Size) -> Self {
+        self.centered(aspect_ratio.aspect_fill(self.size))
+    }
+
+    fn centered(&self, size: Size) -> Self {
+        Self::new(
+            self.mid_x() - size.width * 0.5,
+            self.mid_y() - size.height * 0.5,
+            size.width,
+            size.height,
+        )
+    }
+}
+
+/// Edges used by [`Rect::divide`]
+#[doc(alias = "CGRectEdge")]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u32)]
+pub enum RectEdge {
+    MinX,
+    MinY,
+    MaxX,
+    MaxY,
 }
 
 #[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Default)]
@@ -123,10 +467,201 @@ pub struct Vector {
     pub dy: Float,
 }
 
+#[cfg(target_vendor = "apple")]
 unsafe extern "C" {
-    static CGRectNull: Rect;
-
     fn CGPointCreateDictionaryRepresentation(point: Point) -> arc::R<cf::Dictionary>;
     fn CGSizeCreateDictionaryRepresentation(size: Size) -> arc::R<cf::Dictionary>;
     fn CGRectCreateDictionaryRepresentation(rect: Rect) -> arc::R<cf::Dictionary>;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::cg::{Point, Rect, RectEdge, Size};
+
+    #[test]
+    fn rects() {
+        let r = Rect::new(10.0, 20.0, -4.0, 6.0);
+        assert_eq!(r.min_x(), 6.0);
+        assert_eq!(r.max_x(), 10.0);
+        assert_eq!(r.mid_x(), 8.0);
+        assert_eq!(r.min_y(), 20.0);
+        assert_eq!(r.max_y(), 26.0);
+        assert_eq!(r.width(), 4.0);
+        assert_eq!(r.center(), Point::new(8.0, 23.0));
+        assert_eq!(r.standardize(), Rect::new(6.0, 20.0, 4.0, 6.0));
+        assert_eq!(r.offset(1.0, 1.0), Rect::new(7.0, 21.0, 4.0, 6.0));
+        assert_eq!(r.inset(1.0, 2.0), Rect::new(7.0, 22.0, 2.0, 2.0));
+        assert_eq!(r.inset(-1.0, 0.0), Rect::new(5.0, 20.0, 6.0, 6.0));
+        assert!(r.inset(3.0, 0.0).is_null());
+        assert_eq!(
+            Rect::new(0.4, -0.5, 1.2, 1.0).integral(),
+            Rect::new(0.0, -1.0, 2.0, 2.0)
+        );
+
+        let null = Rect::null();
+        assert!(null.is_null() && null.is_empty());
+        assert!(!Rect::zero().is_null() && Rect::zero().is_empty());
+        assert!(Rect::infinite().is_infinite());
+        assert!(Rect::infinite().contains_rect(&r));
+        for f in [Rect::standardize, Rect::integral] {
+            assert!(f(&null).is_null());
+        }
+        assert!(null.offset(1.0, 1.0).is_null());
+        assert!(null.inset(-1.0, -1.0).is_null());
+        assert_eq!(null.union(&r), r.standardize());
+        assert!(null.intersection(&r).is_null());
+        assert!(!null.contains_point(Point::zero()));
+
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0);
+        assert!(a.contains_point(Point::zero()));
+        assert!(!a.contains_point(Point::new(10.0, 5.0)));
+        assert!(a.contains_rect(&Rect::new(2.0, 2.0, 8.0, 8.0)));
+        assert!(a.contains_rect(&a));
+        assert!(!a.contains_rect(&Rect::new(2.0, 2.0, 9.0, 8.0)));
+
+        let touching = Rect::new(10.0, 0.0, 5.0, 5.0);
+        assert_eq!(a.intersection(&touching), Rect::new(10.0, 0.0, 0.0, 5.0));
+        assert!(a.intersects(&touching));
+        assert!(!a.intersects(&touching.offset(0.1, 0.0)));
+        assert_eq!(a.union(&touching), Rect::new(0.0, 0.0, 15.0, 10.0));
+
+        let corner = Rect::new(13.0, 13.0, -3.0, -3.0);
+        assert_eq!(a.intersection(&corner), Rect::new(10.0, 10.0, 0.0, 0.0));
+        assert!(a.intersects(&corner) && corner.intersects(&a));
+        assert!(!a.contains_rect(&corner));
+        assert_eq!(a.union(&corner), Rect::new(0.0, 0.0, 13.0, 13.0));
+    }
+
+    #[test]
+    fn divide() {
+        let r = Rect::new(0.0, 0.0, 100.0, 50.0);
+        let (slice, rest) = r.divide(10.0, RectEdge::MinX);
+        assert_eq!(slice, Rect::new(0.0, 0.0, 10.0, 50.0));
+        assert_eq!(rest, Rect::new(10.0, 0.0, 90.0, 50.0));
+        let (slice, rest) = r.divide(10.0, RectEdge::MinY);
+        assert_eq!(slice, Rect::new(0.0, 0.0, 100.0, 10.0));
+        assert_eq!(rest, Rect::new(0.0, 10.0, 100.0, 40.0));
+        let (slice, rest) = r.divide(10.0, RectEdge::MaxY);
+        assert_eq!(slice, Rect::new(0.0, 40.0, 100.0, 10.0));
+        assert_eq!(rest, Rect::new(0.0, 0.0, 100.0, 40.0));
+
+        // amount is clamped to the rect
+        let (slice, rest) = r.divide(80.0, RectEdge::MaxY);
+        assert_eq!(slice, r);
+        assert_eq!(rest, Rect::new(0.0, 0.0, 100.0, 0.0));
+        let (slice, rest) = r.divide(-5.0, RectEdge::MinX);
+        assert_eq!(slice, Rect::new(0.0, 0.0, 0.0, 50.0));
+        assert_eq!(rest, r);
+    }
+
+    #[test]
+    fn aspect() {
+        let wide = Size::new(16.0, 9.0);
+        let bounds = Rect::new(100.0, 100.0, 900.0, 900.0);
+        assert_eq!(
+            bounds.aspect_fit(wide),
+            Rect::new(100.0, 296.875, 900.0, 506.25)
+        );
+        assert_eq!(
+            bounds.aspect_fill(wide),
+            Rect::new(-250.0, 100.0, 1600.0, 900.0)
+        );
+        assert_eq!(
+            wide.aspect_fill(Size::new(8.0, 8.0)),
+            Size::new(128.0 / 9.0, 8.0)
+        );
+        assert_eq!(Size::zero().aspect_fit(Size::new(8.0, 8.0)), Size::zero());
+        assert_eq!(
+            Rect::with_size(10.0, 10.0).aspect_fit(Size::zero()),
+            Rect::new(5.0, 5.0, 0.0, 0.0)
+        );
+    }
+
+    /// Native implementation matches CoreGraphics
+    #[cfg(target_vendor = "apple")]
+    #[test]
+    fn parity() {
+        let rects = [
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            Rect::new(5.0, -5.0, 10.0, 10.0),
+            Rect::new(10.5, 20.25, -4.0, 6.5),
+            Rect::new(-3.7, 1.2, 2.9, 0.3),
+            Rect::new(100.0, 100.0, 1.0, 1.0),
+            // touch (0, 0, 10, 10) along an edge and at a corner
+            Rect::new(10.0, 2.0, 5.0, 5.0),
+            Rect::new(2.0, 10.0, 5.0, 0.0),
+            Rect::new(10.0, 10.0, 3.0, 3.0),
+            Rect::new(0.0, 0.0, -2.0, -2.0),
+            Rect::zero(),
+            Rect::null(),
+        ];
+        let edges = [
+            RectEdge::MinX,
+            RectEdge::MinY,
+            RectEdge::MaxX,
+            RectEdge::MaxY,
+        ];
+        unsafe {
+            assert_eq!(Rect::null(), CGRectNull);
+            assert_eq!(Rect::infinite(), CGRectInfinite);
+            for r in rects {
+                assert_eq!(r.is_null(), CGRectIsNull(r));
+                assert_eq!(r.is_empty(), CGRectIsEmpty(r));
+                assert_eq!(r.is_infinite(), CGRectIsInfinite(r));
+                assert_eq!(r.standardize(), CGRectStandardize(r));
+                assert_eq!(r.integral(), CGRectIntegral(r));
+                assert_eq!(r.inset(1.0, 0.5), CGRectInset(r, 1.0, 0.5));
+                assert_eq!(r.offset(1.0, -2.0), CGRectOffset(r, 1.0, -2.0));
+                if !r.is_null() {
+                    assert_eq!(r.min_x(), CGRectGetMinX(r));
+                    assert_eq!(r.max_y(), CGRectGetMaxY(r));
+                    assert_eq!(r.mid_x(), CGRectGetMidX(r));
+                    assert_eq!(r.height(), CGRectGetHeight(r));
+                }
+                for p in [Point::zero(), Point::new(6.0, 22.0), Point::new(10.0, 5.0)] {
+                    assert_eq!(r.contains_point(p), CGRectContainsPoint(r, p));
+                }
+                for o in rects {
+                    assert_eq!(r.union(&o), CGRectUnion(r, o));
+                    assert_eq!(r.intersection(&o), CGRectIntersection(r, o));
+                    assert_eq!(r.intersects(&o), CGRectIntersectsRect(r, o));
+                    assert_eq!(r.contains_rect(&o), CGRectContainsRect(r, o));
+                }
+                for edge in edges {
+                    let (mut slice, mut rest) = (Rect::zero(), Rect::zero());
+                    CGRectDivide(r, &mut slice, &mut rest, 3.0, edge);
+                    assert_eq!(r.divide(3.0, edge), (slice, rest));
+                }
+            }
+        }
+
+        unsafe extern "C" {
+            static CGRectNull: Rect;
+            static CGRectInfinite: Rect;
+
+            fn CGRectIsNull(rect: Rect) -> bool;
+            fn CGRectIsEmpty(rect: Rect) -> bool;
+            fn CGRectIsInfinite(rect: Rect) -> bool;
+            fn CGRectGetMinX(rect: Rect) -> f64;
+            fn CGRectGetMidX(rect: Rect) -> f64;
+            fn CGRectGetMaxY(rect: Rect) -> f64;
+            fn CGRectGetHeight(rect: Rect) -> f64;
+            fn CGRectStandardize(rect: Rect) -> Rect;
+            fn CGRectIntegral(rect: Rect) -> Rect;
+            fn CGRectInset(rect: Rect, dx: f64, dy: f64) -> Rect;
+            fn CGRectOffset(rect: Rect, dx: f64, dy: f64) -> Rect;
+            fn CGRectUnion(r1: Rect, r2: Rect) -> Rect;
+            fn CGRectIntersection(r1: Rect, r2: Rect) -> Rect;
+            fn CGRectIntersectsRect(r1: Rect, r2: Rect) -> bool;
+            fn CGRectContainsPoint(rect: Rect, point: Point) -> bool;
+            fn CGRectContainsRect(r1: Rect, r2: Rect) -> bool;
+            fn CGRectDivide(
+                rect: Rect,
+                slice: *mut Rect,
+                remainder: *mut Rect,
+                amount: f64,
+                edge: RectEdge,
+            );
+        }
+    }
+}
@@ -1,45 +1,83 @@
+#[cfg(target_vendor = "apple")]
 mod base;
+#[cfg(target_vendor = "apple")]
 pub use base::ItemCount;
+#[cfg(target_vendor = "apple")]
 pub use base::ItemIndex;
+#[cfg(target_vendor = "apple")]
 pub use base::PersistentTrackId;
 
+#[cfg(target_vendor = "apple")]
 mod format_description;
+#[cfg(target_vendor = "apple")]
 pub use format_description::AudioFormatDesc;
+#[cfg(target_vendor = "apple")]
 pub use format_description::FormatDesc;
+#[cfg(target_vendor = "apple")]
 pub use format_description::FormatDescExtKey;
+#[cfg(target_vendor = "apple")]
 pub use format_description::LogTransferFn;
+#[cfg(target_vendor = "apple")]
 pub use format_description::MediaType;
+#[cfg(target_vendor = "apple")]
 pub use format_description::MuxedFormatDesc;
+#[cfg(target_vendor = "apple")]
 pub use format_description::MuxedStreamType;
+#[cfg(target_vendor = "apple")]
 pub use format_description::PixelFormat;
+#[cfg(target_vendor = "apple")]
 pub use format_description::TaggedBufGroupFormatDesc;
+#[cfg(target_vendor = "apple")]
 pub use format_description::TaggedBufGroupFormatType;
+#[cfg(target_vendor = "apple")]
 pub use format_description::VideoCodec;
+#[cfg(target_vendor = "apple")]
 pub use format_description::VideoDimensions;
+#[cfg(target_vendor = "apple")]
 pub use format_description::VideoFormatDesc;
 
+#[cfg(target_vendor = "apple")]
 mod format_description_bridge;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::ImageDescFlavor;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::SoundDescFlavor;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::err as format_desc_bridge_err;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::swap_be_image_desc_to_host;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::swap_be_sound_desc_to_host;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::swap_host_image_desc_to_be;
+#[cfg(target_vendor = "apple")]
 pub use format_description_bridge::swap_host_sound_desc_to_be;
 
+#[cfg(target_vendor = "apple")]
 mod tag;
+#[cfg(target_vendor = "apple")]
 pub use tag::Tag;
+#[cfg(target_vendor = "apple")]
 pub use tag::TagCategory;
+#[cfg(target_vendor = "apple")]
 pub use tag::TagDataType;
+#[cfg(target_vendor = "apple")]
 pub use tag::err as tag_err;
 
+#[cfg(target_vendor = "apple")]
 mod tag_collection;
+#[cfg(target_vendor = "apple")]
 pub use tag_collection::TagCollection;
+#[cfg(target_vendor = "apple")]
 pub use tag_collection::TagCollectionMut;
+#[cfg(target_vendor = "apple")]
 pub use tag_collection::err as tag_collection_err;
 
+#[cfg(target_vendor = "apple")]
 mod tagged_buffer_group;
+#[cfg(target_vendor = "apple")]
 pub use tagged_buffer_group::TaggedBufGroup;
+#[cfg(target_vendor = "apple")]
 pub use tagged_buffer_group::err as tagged_buf_group_err;
 
 mod time;
@@ -52,37 +90,61 @@ pub use time::TimeRoundingMethod;
 pub use time::TimeScale;
 pub use time::TimeValue;
 
+#[cfg(target_vendor = "apple")]
 pub mod sample_buffer;
 
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "cat")]
 pub use sample_buffer::BlockBufAudioBufList;
+#[cfg(target_vendor = "apple")]
 pub use sample_buffer::Flags as SampleBufFlags;
+#[cfg(target_vendor = "apple")]
 pub use sample_buffer::SampleBuf;
+#[cfg(target_vendor = "apple")]
 pub use sample_buffer::SampleTimingInfo;
+#[cfg(target_vendor = "apple")]
 pub use sample_buffer::err as sample_buf_err;
 
+#[cfg(target_vendor = "apple")]
 pub mod attachment;
+#[cfg(target_vendor = "apple")]
 pub use attachment::Bearer as AttachBearer;
+#[cfg(target_vendor = "apple")]
 pub use attachment::Mode as AttachMode;
 
+#[cfg(target_vendor = "apple")]
 pub mod block_buffer;
+#[cfg(target_vendor = "apple")]
 pub use block_buffer::BlockBuf;
+#[cfg(target_vendor = "apple")]
 pub use block_buffer::Flags as BlockBufFlags;
+#[cfg(target_vendor = "apple")]
 pub use block_buffer::err as block_buf_err;
 
+#[cfg(target_vendor = "apple")]
 pub mod sync;
+#[cfg(target_vendor = "apple")]
 pub use sync::Clock;
+#[cfg(target_vendor = "apple")]
 pub use sync::ClockOrTimebase;
+#[cfg(target_vendor = "apple")]
 pub use sync::Timebase;
 
+#[cfg(target_vendor = "apple")]
 pub mod memory_pool;
+#[cfg(target_vendor = "apple")]
 pub use memory_pool::MemPool;
+#[cfg(target_vendor = "apple")]
 pub use memory_pool::keys as memory_pool_options;
 
+#[cfg(target_vendor = "apple")]
 pub mod simple_queue;
+#[cfg(target_vendor = "apple")]
 pub use simple_queue::SimpleQueue;
+#[cfg(target_vendor = "apple")]
 pub use simple_queue::err as simple_queue_err;
 
+#[cfg(target_vendor = "apple")]
 #[link(name = "CoreMedia", kind = "framework")]
 unsafe extern "C" {}
 
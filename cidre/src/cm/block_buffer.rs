@@ -320,6 +320,24 @@ impl BlockBuf {
     pub fn assure_block_mem(&mut self) -> os::Result {
         unsafe { CMBlockBufferAssureBlockMemory(self).result() }
     }
+
+    /// Copies bytes starting at offset into destination slice.
+    ///
+    /// Works for noncontiguous cm::BlockBuf too.
+    #[doc(alias = "CMBlockBufferCopyDataBytes")]
+    #[inline]
+    pub fn copy_data_bytes(&self, offset: usize, dst: &mut [u8]) -> os::Result {
+        unsafe {
+            CMBlockBufferCopyDataBytes(self, offset, dst.len(), dst.as_mut_ptr().cast()).result()
+        }
+    }
+
+    /// Copies all data into Vec.
+    pub fn to_vec(&self) -> os::Result<Vec<u8>> {
+        let mut res = vec![0u8; self.data_len()];
+        self.copy_data_bytes(0, &mut res)?;
+        Ok(res)
+    }
 }
 
 unsafe extern "C-unwind" {
@@ -368,6 +386,13 @@ unsafe extern "C-unwind" {
 
     fn CMBlockBufferAssureBlockMemory(buffer: &mut BlockBuf) -> os::Status;
 
+    fn CMBlockBufferCopyDataBytes(
+        the_source_buffer: &BlockBuf,
+        offset_to_data: usize,
+        data_length: usize,
+        destination: *mut c_void,
+    ) -> os::Status;
+
 }
 
 pub mod err {
@@ -60,6 +60,20 @@ impl PixelFormat {
     }
 }
 
+impl From<crate::FourCc> for PixelFormat {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<PixelFormat> for crate::FourCc {
+    #[inline]
+    fn from(value: PixelFormat) -> Self {
+        Self(value.0)
+    }
+}
+
 #[doc(alias = "CMVideoDimensions")]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 #[repr(C)]
@@ -115,6 +129,20 @@ impl std::fmt::Debug for MediaType {
     }
 }
 
+impl From<crate::FourCc> for MediaType {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<MediaType> for crate::FourCc {
+    #[inline]
+    fn from(value: MediaType) -> Self {
+        Self(value.0)
+    }
+}
+
 #[doc(alias = "CMVideoCodecType")]
 #[derive(Eq, PartialEq, Clone, Copy, Hash)]
 #[repr(transparent)]
@@ -186,6 +214,20 @@ impl std::fmt::Debug for VideoCodec {
     }
 }
 
+impl From<crate::FourCc> for VideoCodec {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<VideoCodec> for crate::FourCc {
+    #[inline]
+    fn from(value: VideoCodec) -> Self {
+        Self(value.0)
+    }
+}
+
 define_cf_type!(
     #[doc(alias = "CMFormatDescriptionRef")]
     FormatDesc(cf::Type)
@@ -491,6 +533,55 @@ impl VideoFormatDesc {
         }
     }
 
+    /// Parses `avcC` sample description extension.
+    #[cfg(feature = "nal")]
+    pub fn avc_config(&self) -> Option<crate::nal::Result<crate::nal::AvcConfig>> {
+        self.avcc()
+            .map(|bytes| crate::nal::AvcConfig::parse(&bytes))
+    }
+
+    /// Parses `hvcC` sample description extension.
+    #[cfg(feature = "nal")]
+    pub fn hevc_config(&self) -> Option<crate::nal::Result<crate::nal::HevcConfig>> {
+        self.hvcc()
+            .map(|bytes| crate::nal::HevcConfig::parse(&bytes))
+    }
+
+    #[doc(alias = "CMVideoFormatDescriptionCreateFromH264ParameterSets")]
+    #[cfg(feature = "nal")]
+    pub fn with_avc_config(config: &crate::nal::AvcConfig) -> os::Result<arc::R<VideoFormatDesc>> {
+        let (pointers, sizes): (Vec<_>, Vec<_>) =
+            config.param_sets().map(|n| (n.as_ptr(), n.len())).unzip();
+        unsafe {
+            os::result_unchecked(|res| {
+                CMVideoFormatDescriptionCreateFromH264ParameterSets(
+                    None,
+                    pointers.len(),
+                    pointers.as_ptr(),
+                    sizes.as_ptr(),
+                    config.nal_len_size as i32,
+                    res,
+                )
+            })
+        }
+    }
+
+    #[doc(alias = "CMVideoFormatDescriptionCreateFromHEVCParameterSets")]
+    #[cfg(feature = "nal")]
+    pub fn with_hevc_config(
+        config: &crate::nal::HevcConfig,
+    ) -> os::Result<arc::R<VideoFormatDesc>> {
+        let (pointers, sizes): (Vec<_>, Vec<_>) =
+            config.param_sets().map(|n| (n.as_ptr(), n.len())).unzip();
+        Self::with_hevc_param_sets(
+            pointers.len(),
+            &pointers,
+            &sizes,
+            config.nal_len_size as i32,
+            None,
+        )
+    }
+
     #[doc(alias = "CMVideoFormatDescriptionGetH264ParameterSetAtIndex")]
     #[inline]
     pub fn h264_params_count_and_header_len(&self) -> os::Result<(usize, i32)> {
@@ -594,6 +685,32 @@ impl VideoFormatDesc {
         }
     }
 
+    /// Whether sub type is one of HEVC codecs.
+    fn is_hevc(&self) -> bool {
+        matches!(
+            VideoCodec(self.media_sub_type()),
+            VideoCodec::HEVC | VideoCodec::HEVC_WITH_ALPHA | VideoCodec::DOLBY_VISION_HEVC
+        )
+    }
+
+    /// H.264 or HEVC parameter sets in order and NAL unit header length
+    /// of length-prefixed samples.
+    pub fn param_sets_and_header_len(&self) -> os::Result<(Vec<&[u8]>, i32)> {
+        if self.is_hevc() {
+            let (count, header_len) = self.hevc_params_count_and_header_len()?;
+            let sets = (0..count)
+                .map(|i| self.hevc_param_set_at(i))
+                .collect::<os::Result<_>>()?;
+            Ok((sets, header_len))
+        } else {
+            let (count, header_len) = self.h264_params_count_and_header_len()?;
+            let sets = (0..count)
+                .map(|i| self.h264_param_set_at(i))
+                .collect::<os::Result<_>>()?;
+            Ok((sets, header_len))
+        }
+    }
+
     /// Returns an array of the keys that are used both as [`cm::VideoFormatDesc`] extensions
     /// and [`cv::ImageBuf`] attachments and attributes.
     #[doc(alias = "CMVideoFormatDescriptionGetExtensionKeysCommonWithImageBuffers")]
@@ -612,6 +729,17 @@ impl AudioFormatDesc {
         }
     }
 
+    pub fn with_asbd_and_layout(
+        asbd: &cat::audio::StreamBasicDesc,
+        layout: &cat::audio::ChannelLayoutBuf,
+    ) -> os::Result<arc::R<Self>> {
+        layout.with_raw(|layout, size| unsafe {
+            os::result_unchecked(|res| {
+                Self::audio_in(asbd, size, Some(layout), 0, None, None, res, None)
+            })
+        })
+    }
+
     pub fn audio_in(
         asbd: &cat::audio::StreamBasicDesc,
         layout_size: usize,
@@ -643,6 +771,20 @@ impl AudioFormatDesc {
     pub fn stream_basic_desc(&self) -> Option<&cat::audio::StreamBasicDesc> {
         unsafe { CMAudioFormatDescriptionGetStreamBasicDescription(self) }
     }
+
+    /// Copy of the channel layout, `None` if description has no layout or it is malformed.
+    #[doc(alias = "CMAudioFormatDescriptionGetChannelLayout")]
+    pub fn channel_layout(&self) -> Option<cat::audio::ChannelLayoutBuf> {
+        let mut size = 0;
+        unsafe {
+            let layout = CMAudioFormatDescriptionGetChannelLayout(self, &mut size);
+            if layout.is_null() {
+                return None;
+            }
+            let bytes = std::slice::from_raw_parts(layout.cast::<u8>(), size);
+            cat::audio::ChannelLayoutBuf::from_bytes(bytes).ok()
+        }
+    }
 }
 
 define_cf_type!(
@@ -1089,6 +1231,12 @@ unsafe extern "C-unwind" {
         desc: &AudioFormatDesc,
     ) -> Option<&cat::audio::StreamBasicDesc>;
 
+    #[cfg(feature = "cat")]
+    fn CMAudioFormatDescriptionGetChannelLayout(
+        desc: &AudioFormatDesc,
+        size_out: *mut usize,
+    ) -> *const cat::AudioChannelLayout<1>;
+
     fn CMFormatDescriptionCreate(
         allocator: Option<&cf::Allocator>,
         media_type: MediaType,
@@ -1155,4 +1303,46 @@ mod tests {
         eprintln!("{keys:?}");
         assert!(!keys.is_empty());
     }
+
+    #[cfg(feature = "cat")]
+    #[test]
+    fn channel_layout() {
+        use crate::cat::audio::{
+            ChannelLabel, ChannelLayoutBuf, ChannelLayoutTag, StreamBasicDesc,
+        };
+
+        let asbd = StreamBasicDesc::common_f32(48_000.0, 2, true);
+        let desc = cm::AudioFormatDesc::with_asbd(&asbd).unwrap();
+        assert!(desc.channel_layout().is_none());
+
+        for layout in [
+            ChannelLayoutBuf::with_tag(ChannelLayoutTag::STEREO),
+            ChannelLayoutBuf::with_labels(&[ChannelLabel::LEFT, ChannelLabel::RIGHT]),
+        ] {
+            let desc = cm::AudioFormatDesc::with_asbd_and_layout(&asbd, &layout).unwrap();
+            assert_eq!(desc.channel_layout(), Some(layout));
+        }
+    }
+
+    #[cfg(feature = "nal")]
+    #[test]
+    fn nal_config() {
+        use crate::nal;
+
+        let sps = [
+            0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40, 0x50, 0x05, 0xbb, 0x01, 0x10, 0x00, 0x00,
+            0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x03, 0xc0, 0xf1, 0x83, 0x19, 0x60,
+        ];
+        let pps = [0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+        let config = nal::AvcConfig::with_param_sets(&[&sps], &[&pps], 4).unwrap();
+        let desc = cm::VideoFormatDesc::with_avc_config(&config).unwrap();
+        let dims = desc.dimensions();
+        assert_eq!((dims.width, dims.height), (1280, 720));
+        let parsed = desc.avc_config().unwrap().unwrap();
+        assert_eq!(parsed.nal_len_size, 4);
+        assert_eq!(parsed.sps, config.sps);
+        assert_eq!(parsed.pps, config.pps);
+        assert_eq!(parsed.parse_sps().unwrap().width(), 1280);
+        assert!(desc.hevc_config().is_none());
+    }
 }
@@ -396,6 +396,34 @@ impl SampleBuf {
         }
     }
 
+    /// Writes H.264 or HEVC sample as Annex-B byte stream to `out`.
+    ///
+    /// Parameter sets from format description are prepended on key frames.
+    #[cfg(feature = "nal")]
+    pub fn to_annex_b(&self, out: &mut Vec<u8>) -> os::Result {
+        let Some(desc) = self.format_desc() else {
+            return Err(err::INVALID_MEDIA_FORMAT);
+        };
+        let Some(data) = self.data_buf() else {
+            return Err(err::REQUIRED_PARAMETER_MISSING);
+        };
+        let (param_sets, header_len) = desc.param_sets_and_header_len()?;
+        let param_sets = if self.is_key_frame() {
+            &param_sets[..]
+        } else {
+            &[]
+        };
+        let len = data.data_len();
+        let res = if data.is_range_contiguous(0, len) {
+            let bytes = data.as_slice()?;
+            crate::nal::to_annex_b(bytes, header_len as u8, param_sets, out)
+        } else {
+            let bytes = data.to_vec()?;
+            crate::nal::to_annex_b(&bytes, header_len as u8, param_sets, out)
+        };
+        res.map_err(|_| err::INVALID_SAMPLE_DATA)
+    }
+
     #[inline]
     pub unsafe fn contains_not_sync(&self) -> bool {
         let arr = unsafe { self.attaches(true).unwrap_unchecked() };
@@ -1068,4 +1096,31 @@ mod tests {
             }
         }
     }
+
+    #[cfg(feature = "nal")]
+    #[test]
+    fn annex_b() {
+        use crate::nal;
+
+        let sps = [
+            0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40, 0x50, 0x05, 0xbb, 0x01, 0x10, 0x00, 0x00,
+            0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x03, 0xc0, 0xf1, 0x83, 0x19, 0x60,
+        ];
+        let pps = [0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+        let idr = [0x65, 0x88, 0x84, 0x00, 0x21];
+        let config = nal::AvcConfig::with_param_sets(&[&sps], &[&pps], 4).unwrap();
+        let desc = cm::VideoFormatDesc::with_avc_config(&config).unwrap();
+
+        let mut sample = Vec::new();
+        nal::write_len_prefixed(&mut sample, &idr, 4).unwrap();
+        let mut data = cm::BlockBuf::with_mem_block(sample.len(), None).unwrap();
+        data.as_mut_slice().unwrap().copy_from_slice(&sample);
+        let sample_buf = cm::SampleBuf::new(Some(&data), true, Some(&desc)).unwrap();
+        assert!(sample_buf.is_key_frame());
+
+        let mut stream = Vec::new();
+        sample_buf.to_annex_b(&mut stream).unwrap();
+        let units: Vec<_> = nal::annex_b_units(&stream).collect();
+        assert_eq!(units, [&sps[..], &pps, &idr]);
+    }
 }
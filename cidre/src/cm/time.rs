@@ -1,4 +1,7 @@
-use crate::{arc, cf, define_opts};
+use crate::define_opts;
+
+#[cfg(target_vendor = "apple")]
+use crate::{arc, cf};
 
 pub mod range;
 pub use range::Mapping as TimeMapping;
@@ -30,7 +33,7 @@ impl TimeFlags {
 
 #[doc(alias = "CMTime")]
 #[repr(C)]
-#[derive(Hash, Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct Time {
     pub value: TimeValue,
     pub scale: TimeScale,
@@ -38,25 +41,61 @@ pub struct Time {
     pub epoch: TimeEpoch,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[doc(alias = "CMTimeRoundingMethod")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
 pub enum TimeRoundingMethod {
+    /// Round towards zero if abs(fraction) is < 0.5, away from 0 if abs(fraction) is >= 0.5.
+    #[doc(alias = "kCMTimeRoundingMethod_RoundHalfAwayFromZero")]
     RoundHalfAwayFromZero = 1,
+
+    /// Round towards zero if fraction is != 0.
+    #[doc(alias = "kCMTimeRoundingMethod_RoundTowardZero")]
     RoundTowardZero = 2,
+
+    /// Round away from zero if abs(fraction) is > 0.
+    #[doc(alias = "kCMTimeRoundingMethod_RoundAwayFromZero")]
     RoundAwayFromZero = 3,
+
+    /// Use `RoundTowardZero` if converting from larger to smaller scale
+    /// (ie. from more precision to less precision), but use `RoundAwayFromZero`
+    /// if converting from smaller to larger scale. Also, never round a negative
+    /// number down to 0; always return the smallest magnitude negative time
+    /// in this case (-1/new_time_scale).
+    #[doc(alias = "kCMTimeRoundingMethod_QuickTime")]
     QuickTime = 4,
+
+    /// Round towards +infinity if fraction is != 0.
+    #[doc(alias = "kCMTimeRoundingMethod_RoundTowardPositiveInfinity")]
     RoundTowardPositiveInfinity = 5,
+
+    /// Round towards -infinity if fraction is != 0.
+    #[doc(alias = "kCMTimeRoundingMethod_RoundTowardNegativeInfinity")]
     RoundTowardNegativeInfinity = 6,
 }
 
+impl TimeRoundingMethod {
+    /// Same as CoreMedia's default, used by arithmetic when a result has to be rounded.
+    #[doc(alias = "kCMTimeRoundingMethod_Default")]
+    pub const DEFAULT: Self = Self::RoundHalfAwayFromZero;
+}
+
 impl Default for TimeRoundingMethod {
+    /// [`TimeRoundingMethod::DEFAULT`], that is `RoundHalfAwayFromZero`.
+    ///
+    /// Earlier versions returned `RoundAwayFromZero` here.
     #[inline]
     fn default() -> Self {
-        Self::RoundAwayFromZero
+        Self::DEFAULT
     }
 }
 
+/// Arithmetic and comparison are implemented natively and follow CMTime.h semantics,
+/// so they don't need CoreMedia at runtime.
 impl Time {
+    #[doc(alias = "kCMTimeMaxTimescale")]
+    pub const MAX_SCALE: TimeScale = 1_000_000_000;
+
     /// Returns the absolute value of a Time.
     /// ```
     /// use cidre::cm;
@@ -69,9 +108,21 @@ impl Time {
     #[doc(alias = "CMTimeAbsoluteValue")]
     #[inline]
     pub fn abs(self) -> Time {
-        unsafe { CMTimeAbsoluteValue(self) }
+        if self.is_neg_infinity() || (self.is_numeric() && self.value < 0) {
+            -self
+        } else {
+            self
+        }
     }
 
+    /// Adds two times.
+    ///
+    /// The result timescale is the least common multiple of operand timescales
+    /// clamped to [`Time::MAX_SCALE`]. If the value overflows, the timescale is halved
+    /// until it fits. Rounding sets [`TimeFlags::HAS_BEEN_ROUNDED`].
+    ///
+    /// Times in different nonzero epochs can't be added, the result is invalid.
+    /// Times in the same nonzero epoch produce a duration (epoch 0).
     /// ```
     /// use cidre::cm;
     ///
@@ -81,52 +132,105 @@ impl Time {
     /// assert!(t3.is_valid());
     /// assert_eq!(t3.scale, 10);
     /// assert_eq!(t3.as_secs(), 300.0);
+    ///
+    /// let t4 = cm::Time::new(1, 2) + cm::Time::new(1, 3);
+    /// assert_eq!(t4.value, 5);
+    /// assert_eq!(t4.scale, 6);
     /// ```
+    #[doc(alias = "CMTimeAdd")]
     #[inline]
     pub fn add(self, rhs: Time) -> Time {
-        unsafe { CMTimeAdd(self, rhs) }
+        self.add_signed(rhs, false)
     }
 
+    /// Converts time to new timescale using rounding method.
+    ///
+    /// Non-numeric times are returned unchanged. If the converted value
+    /// doesn't fit in `TimeValue` the result is invalid.
     /// ```
     /// use cidre::cm;
     ///
     /// let time = cm::Time::default().convert_scale(100, cm::TimeRoundingMethod::default());
     /// assert!(time.is_valid());
     /// assert_eq!(time.scale, 100);
+    ///
+    /// let time = cm::Time::new(1, 3).convert_scale(10, cm::TimeRoundingMethod::RoundTowardZero);
+    /// assert_eq!(time.value, 3);
+    /// assert!(time.flags.contains(cm::TimeFlags::HAS_BEEN_ROUNDED));
     /// ```
-    #[inline]
+    #[doc(alias = "CMTimeConvertScale")]
     pub fn convert_scale(
         self,
         new_time_scale: TimeScale,
         rounding_method: TimeRoundingMethod,
     ) -> Time {
-        unsafe { CMTimeConvertScale(self, new_time_scale, rounding_method) }
+        if !self.is_numeric() || new_time_scale == self.scale {
+            return self;
+        }
+        if new_time_scale <= 0 || self.scale <= 0 {
+            return Self::invalid();
+        }
+        let (value, rounded) = rescale(
+            self.value as i128,
+            self.scale as i128,
+            new_time_scale as i128,
+            rounding_method,
+        );
+        let Ok(value) = TimeValue::try_from(value) else {
+            return Self::invalid();
+        };
+        let mut flags = self.flags;
+        flags.set(
+            TimeFlags::HAS_BEEN_ROUNDED,
+            rounded || self.has_been_rounded(),
+        );
+        Time {
+            value,
+            scale: new_time_scale,
+            flags,
+            epoch: self.epoch,
+        }
     }
 
+    #[cfg(target_vendor = "apple")]
     #[inline]
     pub fn desc_in(self, allocator: Option<&cf::Allocator>) -> Option<arc::R<cf::String>> {
         unsafe { CMTimeCopyDescription(allocator, self) }
     }
 
+    #[cfg(target_vendor = "apple")]
     #[inline]
     pub fn desc(self) -> Option<arc::R<cf::String>> {
         unsafe { CMTimeCopyDescription(None, self) }
     }
 
     /// Converts a Time to seconds.
+    ///
+    /// Invalid and indefinite times are NaN, infinite times are +/-infinity.
+    #[doc(alias = "CMTimeGetSeconds")]
     #[inline]
     pub fn as_secs(self) -> f64 {
-        unsafe { CMTimeGetSeconds(self) }
+        if self.is_pos_infinity() {
+            f64::INFINITY
+        } else if self.is_neg_infinity() {
+            f64::NEG_INFINITY
+        } else if self.is_numeric() {
+            self.value as f64 / self.scale as f64
+        } else {
+            f64::NAN
+        }
     }
 
+    #[doc(alias = "kCMTimeIndefinite")]
     #[inline]
-    pub fn indefinit() -> Time {
-        unsafe { kCMTimeIndefinite }
+    pub const fn indefinit() -> Time {
+        Self::with_flags(TimeFlags(TimeFlags::VALID.0 | TimeFlags::INDEFINITE.0))
     }
 
+    #[doc(alias = "kCMTimeInvalid")]
     #[inline]
-    pub fn invalid() -> Time {
-        unsafe { kCMTimeInvalid }
+    pub const fn invalid() -> Time {
+        Self::with_flags(TimeFlags(0))
     }
 
     #[inline]
@@ -166,12 +270,22 @@ impl Time {
         self.is_valid() && (self.flags.0 & TimeFlags::POS_INFINITY.0) != 0
     }
 
+    #[inline]
+    pub const fn is_infinite(&self) -> bool {
+        self.is_pos_infinity() || self.is_neg_infinity()
+    }
+
     #[inline]
     pub const fn is_numeric(&self) -> bool {
         (self.flags.0 & (TimeFlags::VALID.0 | TimeFlags::IMPLIED_VALUE_FLAGS_MASK.0))
             == TimeFlags::VALID.0
     }
 
+    #[inline]
+    pub const fn has_been_rounded(&self) -> bool {
+        (self.flags.0 & TimeFlags::HAS_BEEN_ROUNDED.0) != 0
+    }
+
     /// Returns Time from a f64 number of seconds, and a preferred timescale.
     ///
     /// ```
@@ -191,6 +305,9 @@ impl Time {
         self.is_valid()
     }
 
+    /// Multiplies time by integer keeping the timescale.
+    ///
+    /// If the value overflows, the timescale is halved until it fits.
     /// ```
     /// use cidre::cm;
     ///
@@ -203,13 +320,71 @@ impl Time {
     #[doc(alias = "CMTimeMultiply")]
     #[inline]
     pub fn mul_i32(self, multiplier: i32) -> Time {
-        unsafe { CMTimeMultiply(self, multiplier) }
+        if self.is_invalid() {
+            return self;
+        }
+        if let Some(sign) = self.infinity_sign() {
+            return Self::infinity_with_sign(if multiplier < 0 { -sign } else { sign });
+        }
+        if self.is_indefinite() {
+            return Self::indefinit();
+        }
+        if self.scale <= 0 {
+            return Self::invalid();
+        }
+        fit(
+            self.value as i128 * multiplier as i128,
+            self.scale as i128,
+            self.scale,
+            self.has_been_rounded(),
+            self.epoch,
+        )
     }
 
+    /// Multiplies time by f64 keeping the timescale.
+    ///
+    /// If the value overflows, the timescale is halved until it fits.
     #[doc(alias = "CMTimeMultiplyByFloat64")]
-    #[inline]
     pub fn mul_f64(self, multiplier: f64) -> Time {
-        unsafe { CMTimeMultiplyByFloat64(self, multiplier) }
+        if self.is_invalid() || multiplier.is_nan() {
+            return Self::invalid();
+        }
+        if let Some(sign) = self.infinity_sign() {
+            return Self::infinity_with_sign(if multiplier < 0.0 { -sign } else { sign });
+        }
+        if self.is_indefinite() {
+            return Self::indefinit();
+        }
+        if self.scale <= 0 {
+            return Self::invalid();
+        }
+        let mut scale = self.scale;
+        while scale > 0 {
+            let val = self.value as f64 * multiplier * (scale as f64 / self.scale as f64);
+            if val.is_nan() {
+                return Self::invalid();
+            }
+            if val.abs() < TimeValue::MAX as f64 {
+                let rounded = val.round();
+                let mut flags = TimeFlags::VALID;
+                flags.set(
+                    TimeFlags::HAS_BEEN_ROUNDED,
+                    rounded != val || self.has_been_rounded(),
+                );
+                return Time {
+                    value: rounded as TimeValue,
+                    scale,
+                    flags,
+                    epoch: self.epoch,
+                };
+            }
+            scale /= 2;
+        }
+        Self::infinity_with_sign(if (self.value < 0) != (multiplier < 0.0) {
+            -1
+        } else {
+            1
+        })
     }
 
     /// Returns valid Time with value and timescale. Epoch is implied to be 0.
@@ -223,27 +398,29 @@ impl Time {
     /// ```
     #[doc(alias = "CMTimeMake")]
     #[inline]
-    pub fn new(value: TimeValue, timescale: i32) -> Time {
-        unsafe { CMTimeMake(value, timescale) }
+    pub const fn new(value: TimeValue, timescale: i32) -> Time {
+        Self::with_epoch(value, timescale, 0)
     }
 
     #[doc(alias = "kCMTimePositiveInfinity")]
     #[inline]
-    pub fn infinity() -> Time {
-        unsafe { kCMTimePositiveInfinity }
+    pub const fn infinity() -> Time {
+        Self::with_flags(TimeFlags(TimeFlags::VALID.0 | TimeFlags::POS_INFINITY.0))
     }
 
     #[doc(alias = "kCMTimeNegativeInfinity")]
     #[inline]
-    pub fn neg_infinity() -> Time {
-        unsafe { kCMTimeNegativeInfinity }
+    pub const fn neg_infinity() -> Time {
+        Self::with_flags(TimeFlags(TimeFlags::VALID.0 | TimeFlags::NEG_INFINITY.0))
     }
 
+    #[cfg(target_vendor = "apple")]
     #[inline]
     pub fn show(self) {
         unsafe { CMTimeShow(self) }
     }
 
+    /// Subtracts `rhs` from time. Same rules as for [`Time::add`] apply.
     /// ```
     /// use cidre::cm;
     ///
@@ -254,9 +431,10 @@ impl Time {
     /// assert_eq!(t3.scale, 10);
     /// assert_eq!(t3.as_secs(), 0.0);
     /// ```
+    #[doc(alias = "CMTimeSubtract")]
     #[inline]
     pub fn sub(self, rhs: Time) -> Time {
-        unsafe { CMTimeSubtract(self, rhs) }
+        self.add_signed(rhs, true)
     }
 
     /// ```
@@ -266,13 +444,21 @@ impl Time {
     /// assert!(time.is_valid());
     /// assert_eq!(time.epoch, 5);
     /// ```
+    #[doc(alias = "CMTimeMakeWithEpoch")]
     #[inline]
-    pub fn with_epoch(value: TimeValue, timescale: i32, epoch: TimeEpoch) -> Time {
-        unsafe { CMTimeMakeWithEpoch(value, timescale, epoch) }
+    pub const fn with_epoch(value: TimeValue, timescale: i32, epoch: TimeEpoch) -> Time {
+        Time {
+            value,
+            scale: timescale,
+            flags: TimeFlags::VALID,
+            epoch,
+        }
     }
 
     /// Returns Time from a f64 number of seconds, and a preferred timescale.
     ///
+    /// If the value overflows, the timescale is halved until it fits.
+    ///
     /// ```
     /// use cidre::cm;
     ///
@@ -281,27 +467,228 @@ impl Time {
     /// assert_eq!(time.scale, 10);
     /// assert_eq!(time.as_secs(), 100.0);
     /// ```
-    #[inline]
+    #[doc(alias = "CMTimeMakeWithSeconds")]
     pub fn with_secs(seconds: f64, preferred_timescale: TimeScale) -> Time {
-        unsafe { CMTimeMakeWithSeconds(seconds, preferred_timescale) }
+        if seconds.is_nan() || preferred_timescale <= 0 {
+            return Self::invalid();
+        }
+        if seconds.is_infinite() {
+            return Self::infinity_with_sign(if seconds < 0.0 { -1 } else { 1 });
+        }
+        let mut scale = preferred_timescale;
+        while scale > 0 {
+            let val = seconds * scale as f64;
+            if val.abs() < TimeValue::MAX as f64 {
+                let rounded = val.round();
+                let mut flags = TimeFlags::VALID;
+                flags.set(TimeFlags::HAS_BEEN_ROUNDED, rounded != val);
+                return Time {
+                    value: rounded as TimeValue,
+                    scale,
+                    flags,
+                    epoch: 0,
+                };
+            }
+            scale /= 2;
+        }
+        Self::infinity_with_sign(if seconds < 0.0 { -1 } else { 1 })
     }
 
+    #[doc(alias = "kCMTimeZero")]
     #[inline]
     pub const fn zero() -> Time {
-        unsafe { kCMTimeZero }
+        Self::new(0, 1)
     }
 
+    /// Returns the greater of two times as defined by time comparison.
+    #[doc(alias = "CMTimeMaximum")]
     #[inline]
     pub fn max(l: Time, r: Time) -> Time {
-        unsafe { CMTimeMaximum(l, r) }
+        if r > l { r } else { l }
     }
 
+    /// Returns the lesser of two times as defined by time comparison.
+    #[doc(alias = "CMTimeMinimum")]
     #[inline]
     pub fn min(l: Time, r: Time) -> Time {
-        unsafe { CMTimeMinimum(l, r) }
+        if r < l { r } else { l }
+    }
+
+    #[inline]
+    const fn with_flags(flags: TimeFlags) -> Time {
+        Time {
+            value: 0,
+            scale: 0,
+            flags,
+            epoch: 0,
+        }
+    }
+
+    #[inline]
+    const fn infinity_sign(&self) -> Option<i8> {
+        if self.is_pos_infinity() {
+            Some(1)
+        } else if self.is_neg_infinity() {
+            Some(-1)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    const fn infinity_with_sign(sign: i8) -> Time {
+        if sign < 0 {
+            Self::neg_infinity()
+        } else {
+            Self::infinity()
+        }
+    }
+
+    fn add_signed(self, rhs: Time, negate_rhs: bool) -> Time {
+        if self.is_invalid() || rhs.is_invalid() {
+            return Self::invalid();
+        }
+        let rhs_sign = rhs.infinity_sign().map(|s| if negate_rhs { -s } else { s });
+        match (self.infinity_sign(), rhs_sign) {
+            (Some(l), Some(r)) if l != r => return Self::invalid(),
+            (Some(s), _) | (None, Some(s)) => return Self::infinity_with_sign(s),
+            (None, None) => {}
+        }
+        if self.is_indefinite() || rhs.is_indefinite() {
+            return Self::indefinit();
+        }
+        let epoch = match (self.epoch, rhs.epoch) {
+            (l, r) if l == r => 0,
+            (0, e) | (e, 0) => e,
+            _ => return Self::invalid(),
+        };
+        if self.scale <= 0 || rhs.scale <= 0 {
+            return Self::invalid();
+        }
+
+        let (l_scale, r_scale) = (self.scale as i128, rhs.scale as i128);
+        let lcm = l_scale / gcd(l_scale, r_scale) * r_scale;
+        let r_value = rhs.value as i128 * (lcm / r_scale);
+        let l_value = self.value as i128 * (lcm / l_scale);
+        let num = if negate_rhs {
+            l_value - r_value
+        } else {
+            l_value + r_value
+        };
+        let scale = lcm.min(Self::MAX_SCALE as i128) as TimeScale;
+        fit(
+            num,
+            lcm,
+            scale,
+            self.has_been_rounded() || rhs.has_been_rounded(),
+            epoch,
+        )
+    }
+
+    /// Rank of non-numeric times in comparison order:
+    /// -infinity < numeric < indefinite < +infinity < invalid.
+    /// Numeric times without a positive scale have no value and rank as invalid.
+    #[inline]
+    const fn cmp_rank(&self) -> u8 {
+        if self.is_invalid() || (self.is_numeric() && self.scale <= 0) {
+            4
+        } else if self.is_pos_infinity() {
+            3
+        } else if self.is_indefinite() {
+            2
+        } else if self.is_neg_infinity() {
+            0
+        } else {
+            1
+        }
     }
 }
 
+/// Divides `num` by positive `den` rounding the result.
+/// Returns quotient and whether rounding took place.
+fn div_round(num: i128, den: i128, method: TimeRoundingMethod, to_larger: bool) -> (i128, bool) {
+    let quot = num / den;
+    let rem = num % den;
+    if rem == 0 {
+        return (quot, false);
+    }
+    let away = quot + num.signum();
+    let quot = match method {
+        TimeRoundingMethod::RoundHalfAwayFromZero => {
+            if rem.unsigned_abs() * 2 >= den.unsigned_abs() {
+                away
+            } else {
+                quot
+            }
+        }
+        TimeRoundingMethod::RoundTowardZero => quot,
+        TimeRoundingMethod::RoundAwayFromZero => away,
+        TimeRoundingMethod::QuickTime => {
+            if to_larger {
+                away
+            } else {
+                quot
+            }
+        }
+        TimeRoundingMethod::RoundTowardPositiveInfinity => {
+            if num > 0 {
+                away
+            } else {
+                quot
+            }
+        }
+        TimeRoundingMethod::RoundTowardNegativeInfinity => {
+            if num < 0 {
+                away
+            } else {
+                quot
+            }
+        }
+    };
+    (quot, true)
+}
+
+/// Converts exact `num / den` value into `scale` units.
+fn rescale(num: i128, den: i128, scale: i128, method: TimeRoundingMethod) -> (i128, bool) {
+    // split to keep intermediate products in i128 range
+    let whole = num / den;
+    let (frac, rounded) = div_round(num % den * scale, den, method, scale > den);
+    let mut res = whole * scale + frac;
+    if res == 0 && num < 0 && method == TimeRoundingMethod::QuickTime {
+        res = -1;
+    }
+    (res, rounded)
+}
+
+/// Makes numeric time from exact `num / den` in `scale` units,
+/// halving scale with default rounding until value fits in `TimeValue`.
+fn fit(num: i128, den: i128, mut scale: TimeScale, rounded: bool, epoch: TimeEpoch) -> Time {
+    while scale > 0 {
+        let (value, r) = rescale(num, den, scale as i128, TimeRoundingMethod::DEFAULT);
+        if let Ok(value) = TimeValue::try_from(value) {
+            let mut flags = TimeFlags::VALID;
+            flags.set(TimeFlags::HAS_BEEN_ROUNDED, rounded || r);
+            return Time {
+                value,
+                scale,
+                flags,
+                epoch,
+            };
+        }
+        scale /= 2;
+    }
+    Time::infinity_with_sign(if num < 0 { -1 } else { 1 })
+}
+
+const fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
 impl PartialEq for Time {
     /// ```
     /// use cidre::cm;
@@ -315,7 +702,7 @@ impl PartialEq for Time {
     /// ```
     #[inline]
     fn eq(&self, other: &Self) -> bool {
-        unsafe { CMTimeCompare(*self, *other) == 0 }
+        self.cmp(other).is_eq()
     }
 }
 
@@ -334,7 +721,120 @@ impl PartialOrd for Time {
     /// ```
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        unsafe { std::mem::transmute(CMTimeCompare(*self, *other) as i8) }
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Time {
+    /// Numeric times are compared by epoch first, then by value.
+    /// Non-numeric times are ordered as
+    /// -infinity < numeric < indefinite < +infinity < invalid.
+    /// Numeric times with zero or negative scale are ordered as invalid.
+    #[doc(alias = "CMTimeCompare")]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let (l_rank, r_rank) = (self.cmp_rank(), other.cmp_rank());
+        if l_rank != 1 || r_rank != 1 {
+            return l_rank.cmp(&r_rank);
+        }
+        self.epoch.cmp(&other.epoch).then_with(|| {
+            let l = self.value as i128 * other.scale as i128;
+            let r = other.value as i128 * self.scale as i128;
+            l.cmp(&r)
+        })
+    }
+}
+
+impl std::hash::Hash for Time {
+    /// Consistent with `eq`: numeric times are hashed in lowest terms,
+    /// so `5/10` and `50/100` hash the same.
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let rank = self.cmp_rank();
+        rank.hash(state);
+        if rank != 1 {
+            return;
+        }
+        self.epoch.hash(state);
+        // numeric rank implies positive scale
+        let (value, scale) = (self.value as i128, self.scale as i128);
+        let g = gcd(value, scale).abs();
+        (value / g).hash(state);
+        (scale / g).hash(state);
+    }
+}
+
+impl std::ops::Add for Time {
+    type Output = Time;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Time::add(self, rhs)
+    }
+}
+
+impl std::ops::AddAssign for Time {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = Time::add(*self, rhs)
+    }
+}
+
+impl std::ops::Sub for Time {
+    type Output = Time;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Time::sub(self, rhs)
+    }
+}
+
+impl std::ops::SubAssign for Time {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = Time::sub(*self, rhs)
+    }
+}
+
+impl std::ops::Neg for Time {
+    type Output = Time;
+
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// assert_eq!(-cm::Time::new(5, 10), cm::Time::new(-5, 10));
+    /// assert!((-cm::Time::infinity()).is_neg_infinity());
+    /// ```
+    fn neg(self) -> Self::Output {
+        if let Some(sign) = self.infinity_sign() {
+            return Self::infinity_with_sign(-sign);
+        }
+        if !self.is_numeric() {
+            return self;
+        }
+        fit(
+            -(self.value as i128),
+            self.scale as i128,
+            self.scale,
+            self.has_been_rounded(),
+            self.epoch,
+        )
+    }
+}
+
+impl std::ops::Mul<i32> for Time {
+    type Output = Time;
+
+    #[inline]
+    fn mul(self, rhs: i32) -> Self::Output {
+        self.mul_i32(rhs)
+    }
+}
+
+impl std::ops::Mul<f64> for Time {
+    type Output = Time;
+
+    #[inline]
+    fn mul(self, rhs: f64) -> Self::Output {
+        self.mul_f64(rhs)
     }
 }
 
@@ -363,8 +863,347 @@ mod tests {
         assert!(valid.is_valid());
         assert!(valid.is_numeric());
     }
+
+    #[test]
+    fn hash() {
+        use std::hash::{BuildHasher, RandomState};
+
+        let state = RandomState::new();
+        let hash = |t: cm::Time| state.hash_one(t);
+
+        let pairs = [
+            (cm::Time::new(5, 10), cm::Time::new(50, 100)),
+            (cm::Time::new(-3, 9), cm::Time::new(-1, 3)),
+            (cm::Time::zero(), cm::Time::new(0, 600)),
+            (cm::Time::infinity(), cm::Time::infinity()),
+            (
+                cm::Time::invalid(),
+                cm::Time {
+                    value: 3,
+                    ..cm::Time::invalid()
+                },
+            ),
+        ];
+        for (a, b) in pairs {
+            assert_eq!(a, b);
+            assert_eq!(hash(a), hash(b), "{a:?} {b:?}");
+        }
+        assert_ne!(
+            hash(cm::Time::new(1, 2)),
+            hash(cm::Time::with_epoch(1, 2, 1))
+        );
+
+        // no positive scale, no value: equal to invalid, not to zero
+        for t in [
+            cm::Time::new(0, 0),
+            cm::Time::new(5, 0),
+            cm::Time::new(1, -2),
+        ] {
+            assert_eq!(t, cm::Time::invalid());
+            assert_ne!(t, cm::Time::zero());
+            assert_eq!(hash(t), hash(cm::Time::invalid()), "{t:?}");
+        }
+    }
+
+    fn same(a: cm::Time, b: cm::Time) -> bool {
+        a.value == b.value && a.scale == b.scale && a.flags == b.flags && a.epoch == b.epoch
+    }
+
+    #[test]
+    fn arithmetic() {
+        let t = cm::Time::new(1, 2) + cm::Time::new(1, 3);
+        assert!(same(t, cm::Time::new(5, 6)));
+
+        let t = cm::Time::new(1, 2) - cm::Time::new(1, 3);
+        assert!(same(t, cm::Time::new(1, 6)));
+
+        let t = cm::Time::new(1, 600_000_000) + cm::Time::new(1, 700_000_000);
+        assert_eq!(t.scale, cm::Time::MAX_SCALE);
+        assert_eq!(t.value, 3);
+        assert!(t.has_been_rounded());
+
+        // value overflow halves timescale
+        let t = cm::Time::new(i64::MAX, 10) + cm::Time::new(10, 10);
+        assert_eq!(t.scale, 5);
+        assert!(t.has_been_rounded());
+
+        let t = cm::Time::new(i64::MAX, 1) + cm::Time::new(1, 1);
+        assert!(t.is_pos_infinity());
+
+        let t = cm::Time::new(3, 10) * 3;
+        assert!(same(t, cm::Time::new(9, 10)));
+
+        let t = cm::Time::new(i64::MAX / 2 + 1, 10) * 2;
+        assert_eq!(t.scale, 5);
+        assert_eq!(t.value, i64::MAX / 2 + 1);
+        assert!(!t.has_been_rounded());
+
+        let t = cm::Time::new(3, 10) * 0.5;
+        assert_eq!(t.value, 2);
+        assert!(t.has_been_rounded());
+
+        let t = -cm::Time::new(i64::MIN, 2);
+        assert_eq!(t.scale, 1);
+        assert_eq!(t.value, 1 << 62);
+
+        assert!(same(cm::Time::new(-4, 3).abs(), cm::Time::new(4, 3)));
+        assert!(cm::Time::neg_infinity().abs().is_pos_infinity());
+
+        let mut t = cm::Time::zero();
+        t += cm::Time::new(1, 1);
+        t -= cm::Time::new(1, 2);
+        assert!(same(t, cm::Time::new(1, 2)));
+    }
+
+    #[test]
+    fn non_numeric() {
+        let inf = cm::Time::infinity();
+        let neg_inf = cm::Time::neg_infinity();
+        let indefinite = cm::Time::indefinit();
+        let invalid = cm::Time::invalid();
+        let one = cm::Time::new(1, 1);
+
+        assert!((inf + one).is_pos_infinity());
+        assert!((one - inf).is_neg_infinity());
+        assert!((inf + inf).is_pos_infinity());
+        assert!((neg_inf + neg_inf).is_neg_infinity());
+        assert!((inf + neg_inf).is_invalid());
+        assert!((inf - inf).is_invalid());
+        assert!((inf - neg_inf).is_pos_infinity());
+        assert!((indefinite + one).is_indefinite());
+        assert!((indefinite + inf).is_pos_infinity());
+        assert!((invalid + inf).is_invalid());
+
+        assert!((inf * -2).is_neg_infinity());
+        assert!((neg_inf * -2.0).is_pos_infinity());
+        assert!((indefinite * 2).is_indefinite());
+        assert!((invalid * 2).is_invalid());
+        assert!((one * f64::NAN).is_invalid());
+
+        assert!(cm::Time::with_secs(f64::NAN, 10).is_invalid());
+        assert!(cm::Time::with_secs(f64::NEG_INFINITY, 10).is_neg_infinity());
+        assert!(indefinite.as_secs().is_nan());
+        assert_eq!(inf.as_secs(), f64::INFINITY);
+
+        assert!(same(inf.convert_scale(10, Default::default()), inf));
+        assert!(one.convert_scale(0, Default::default()).is_invalid());
+        assert!(
+            cm::Time::new(i64::MAX, 1)
+                .convert_scale(10, Default::default())
+                .is_invalid()
+        );
+    }
+
+    #[test]
+    fn epochs() {
+        let a = cm::Time::with_epoch(10, 1, 1);
+        let b = cm::Time::with_epoch(4, 1, 1);
+        let d = cm::Time::new(2, 1);
+
+        let t = a - b;
+        assert_eq!(t.epoch, 0);
+        assert_eq!(t.value, 6);
+
+        let t = a + d;
+        assert_eq!(t.epoch, 1);
+        assert_eq!(t.value, 12);
+
+        assert!((a + cm::Time::with_epoch(1, 1, 2)).is_invalid());
+
+        assert!(cm::Time::with_epoch(0, 1, 2) > a);
+        assert!(cm::Time::with_epoch(100, 1, 0) < a);
+    }
+
+    #[test]
+    fn compare() {
+        let order = [
+            cm::Time::neg_infinity(),
+            cm::Time::new(-1, 1),
+            cm::Time::zero(),
+            cm::Time::new(1, 3),
+            cm::Time::new(1, 2),
+            cm::Time::indefinit(),
+            cm::Time::infinity(),
+            cm::Time::invalid(),
+        ];
+        for (i, l) in order.iter().enumerate() {
+            for (j, r) in order.iter().enumerate() {
+                assert_eq!(l.cmp(r), i.cmp(&j), "{l:?} vs {r:?}");
+            }
+        }
+        assert_eq!(cm::Time::new(1, 2), cm::Time::new(50, 100));
+
+        // ordering stays transitive with zero and negative scales
+        let mut times = [
+            cm::Time::new(1, -1),
+            cm::Time::new(2, 1),
+            cm::Time::new(0, 0),
+            cm::Time::new(-1, 1),
+            cm::Time::new(7, 0),
+            cm::Time::new(1, 1),
+        ];
+        times.sort();
+        for w in times.windows(2) {
+            assert!(w[0] <= w[1], "{:?} {:?}", w[0], w[1]);
+        }
+        for a in &times {
+            for b in &times {
+                for c in &times {
+                    if a <= b && b <= c {
+                        assert!(a <= c, "{a:?} {b:?} {c:?}");
+                    }
+                }
+            }
+        }
+        assert!(same(times[0], cm::Time::new(-1, 1)));
+        assert!(same(times[2], cm::Time::new(2, 1)));
+
+        assert!(same(
+            cm::Time::max(cm::Time::new(1, 2), cm::Time::new(1, 3)),
+            cm::Time::new(1, 2)
+        ));
+        assert!(same(
+            cm::Time::min(cm::Time::new(1, 2), cm::Time::new(1, 3)),
+            cm::Time::new(1, 3)
+        ));
+    }
+
+    #[test]
+    fn rounding() {
+        use cm::TimeRoundingMethod as M;
+
+        assert_eq!(M::default(), M::RoundHalfAwayFromZero);
+
+        let cases = [
+            // (value, method, expected for 5/10 -> 1/1 and -5/10 -> 1/1)
+            (M::RoundHalfAwayFromZero, 1, -1),
+            (M::RoundTowardZero, 0, 0),
+            (M::RoundAwayFromZero, 1, -1),
+            (M::QuickTime, 0, -1),
+            (M::RoundTowardPositiveInfinity, 1, 0),
+            (M::RoundTowardNegativeInfinity, 0, -1),
+        ];
+        for (method, pos, neg) in cases {
+            let t = cm::Time::new(5, 10).convert_scale(1, method);
+            assert_eq!(t.value, pos, "{method:?}");
+            assert!(t.has_been_rounded());
+            let t = cm::Time::new(-5, 10).convert_scale(1, method);
+            assert_eq!(t.value, neg, "{method:?}");
+        }
+
+        let t = cm::Time::new(4, 10).convert_scale(1, M::RoundHalfAwayFromZero);
+        assert_eq!(t.value, 0);
+
+        // QuickTime rounds away from zero when converting to a larger scale
+        let t = cm::Time::new(1, 3).convert_scale(10, M::QuickTime);
+        assert_eq!(t.value, 4);
+        let t = cm::Time::new(-15, 10).convert_scale(1, M::QuickTime);
+        assert_eq!(t.value, -1);
+
+        let t = cm::Time::new(3, 10).convert_scale(100, M::RoundTowardZero);
+        assert!(same(t, cm::Time::new(30, 100)));
+    }
+
+    #[cfg(target_vendor = "apple")]
+    #[test]
+    fn ffi_parity() {
+        use super::*;
+        use cm::TimeRoundingMethod as M;
+
+        unsafe {
+            assert!(same(cm::Time::invalid(), kCMTimeInvalid));
+            assert!(same(cm::Time::indefinit(), kCMTimeIndefinite));
+            assert!(same(cm::Time::infinity(), kCMTimePositiveInfinity));
+            assert!(same(cm::Time::neg_infinity(), kCMTimeNegativeInfinity));
+            assert!(same(cm::Time::zero(), kCMTimeZero));
+            assert!(same(cm::Time::new(7, 3), CMTimeMake(7, 3)));
+            assert!(same(
+                cm::Time::with_epoch(7, 3, 2),
+                CMTimeMakeWithEpoch(7, 3, 2)
+            ));
+        }
+
+        let times = [
+            cm::Time::invalid(),
+            cm::Time::indefinit(),
+            cm::Time::infinity(),
+            cm::Time::neg_infinity(),
+            cm::Time::zero(),
+            cm::Time::new(1, 3),
+            cm::Time::new(-7, 10),
+            cm::Time::new(5, 600),
+            cm::Time::new(1001, 30000),
+            cm::Time::new(1, 999_999_937),
+            cm::Time::new(i64::MAX - 3, 600),
+            cm::Time::new(i64::MIN + 3, 44100),
+            cm::Time::with_epoch(90, 30, 1),
+            cm::Time::with_epoch(15, 30, 2),
+            cm::Time::with_secs(1.0 / 3.0, 600),
+        ];
+        let methods = [
+            M::RoundHalfAwayFromZero,
+            M::RoundTowardZero,
+            M::RoundAwayFromZero,
+            M::QuickTime,
+            M::RoundTowardPositiveInfinity,
+            M::RoundTowardNegativeInfinity,
+        ];
+
+        for &a in &times {
+            unsafe {
+                assert!(same(a.abs(), CMTimeAbsoluteValue(a)), "abs {a:?}");
+                for m in [0, 1, -1, 3, i32::MAX, i32::MIN] {
+                    assert!(same(a.mul_i32(m), CMTimeMultiply(a, m)), "{a:?} * {m}");
+                }
+                for m in [0.0, 0.5, -1.5, 1e10] {
+                    let native = a.mul_f64(m);
+                    let ffi = CMTimeMultiplyByFloat64(a, m);
+                    assert!(same(native, ffi), "{a:?} * {m}: {native:?} != {ffi:?}");
+                }
+                for scale in [1, 7, 600, 44100, 1_000_000_000] {
+                    for method in methods {
+                        let native = a.convert_scale(scale, method);
+                        let ffi = CMTimeConvertScale(a, scale, method);
+                        assert!(same(native, ffi), "{a:?} {scale} {method:?}");
+                    }
+                }
+                let secs = a.as_secs();
+                let ffi = CMTimeGetSeconds(a);
+                assert!(secs == ffi || (secs.is_nan() && ffi.is_nan()), "{a:?}");
+            }
+            for &b in &times {
+                unsafe {
+                    assert!(same(a.add(b), CMTimeAdd(a, b)), "{a:?} + {b:?}");
+                    assert!(same(a.sub(b), CMTimeSubtract(a, b)), "{a:?} - {b:?}");
+                    assert_eq!(a.cmp(&b) as i32, CMTimeCompare(a, b), "{a:?} <=> {b:?}");
+                    assert!(same(cm::Time::max(a, b), CMTimeMaximum(a, b)));
+                    assert!(same(cm::Time::min(a, b), CMTimeMinimum(a, b)));
+                }
+            }
+        }
+
+        for secs in [0.0, 1.0 / 3.0, -2.5, 1e12, 1e30, f64::NAN, f64::INFINITY] {
+            for scale in [1, 600, 1_000_000_000] {
+                let native = cm::Time::with_secs(secs, scale);
+                let ffi = unsafe { CMTimeMakeWithSeconds(secs, scale) };
+                assert!(same(native, ffi), "{secs} {scale}: {native:?} != {ffi:?}");
+            }
+        }
+    }
 }
 
+#[cfg(target_vendor = "apple")]
+#[link(name = "CoreMedia", kind = "framework")]
+unsafe extern "C-unwind" {
+    fn CMTimeShow(time: Time);
+
+    fn CMTimeCopyDescription(
+        allocator: Option<&cf::Allocator>,
+        time: Time,
+    ) -> Option<arc::R<cf::String>>;
+}
+
+#[cfg(all(test, target_vendor = "apple"))]
 #[link(name = "CoreMedia", kind = "framework")]
 unsafe extern "C-unwind" {
     static kCMTimeInvalid: Time;
@@ -390,12 +1229,6 @@ unsafe extern "C-unwind" {
 
     fn CMTimeCompare(time1: Time, time2: Time) -> i32;
     fn CMTimeAbsoluteValue(time: Time) -> Time;
-    fn CMTimeShow(time: Time);
     fn CMTimeMaximum(time1: Time, time2: Time) -> Time;
     fn CMTimeMinimum(time1: Time, time2: Time) -> Time;
-
-    fn CMTimeCopyDescription(
-        allocator: Option<&cf::Allocator>,
-        time: Time,
-    ) -> Option<arc::R<cf::String>>;
 }
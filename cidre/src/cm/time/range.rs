@@ -1,5 +1,11 @@
 use crate::cm;
 
+/// A time range represented as start time and duration.
+///
+/// Range algebra is implemented natively. Ranges are half-open: start is
+/// contained, end is not. Operations on invalid ranges produce invalid results,
+/// operations on indefinite ranges produce indefinite results.
+/// Ranges with +infinity duration extend forever.
 #[doc(alias = "CMTimeRange")]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 #[repr(C)]
@@ -9,6 +15,29 @@ pub struct Range {
 }
 
 impl Range {
+    #[doc(alias = "CMTimeRangeMake")]
+    #[inline]
+    pub const fn new(start: cm::Time, duration: cm::Time) -> Self {
+        Self { start, duration }
+    }
+
+    /// Makes range from start and end times.
+    ///
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// let range = cm::TimeRange::with_start_end(cm::Time::new(1, 2), cm::Time::new(3, 2));
+    /// assert_eq!(range.duration, cm::Time::new(1, 1));
+    /// ```
+    #[doc(alias = "CMTimeRangeFromTimeToTime")]
+    #[inline]
+    pub fn with_start_end(start: cm::Time, end: cm::Time) -> Self {
+        Self {
+            start,
+            duration: end.sub(start),
+        }
+    }
+
     #[doc(alias = "CMTIMERANGE_IS_INVALID")]
     #[inline]
     pub const fn is_valid(&self) -> bool {
@@ -28,42 +57,379 @@ impl Range {
         self.is_valid() && self.duration == cm::Time::zero()
     }
 
+    /// Valid range with numeric start and numeric or +infinity duration.
+    #[inline]
+    pub const fn is_definite(&self) -> bool {
+        self.is_valid()
+            && self.start.is_numeric()
+            && (self.duration.is_numeric() || self.duration.is_pos_infinity())
+    }
+
+    /// Returns `start + duration`.
+    #[doc(alias = "CMTimeRangeGetEnd")]
+    #[inline]
+    pub fn end(&self) -> cm::Time {
+        if self.is_valid() {
+            self.start.add(self.duration)
+        } else {
+            cm::Time::invalid()
+        }
+    }
+
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// let range = cm::TimeRange::new(cm::Time::new(1, 1), cm::Time::new(2, 1));
+    /// assert!(range.contains_time(&cm::Time::new(1, 1)));
+    /// assert!(range.contains_time(&cm::Time::new(5, 2)));
+    /// assert!(!range.contains_time(&cm::Time::new(3, 1)));
+    /// ```
     #[doc(alias = "CMTimeRangeContainsTime")]
     #[inline]
     pub fn contains_time(&self, time: &cm::Time) -> bool {
-        unsafe { CMTimeRangeContainsTime(*self, *time) }
+        if !self.is_definite() || !(time.is_numeric() || time.is_infinite()) {
+            return false;
+        }
+        *time >= self.start && *time < self.end()
+    }
+
+    /// Returns true if `other` range is fully inside of the range.
+    #[doc(alias = "CMTimeRangeContainsTimeRange")]
+    #[inline]
+    pub fn contains_range(&self, other: &Self) -> bool {
+        if !self.is_definite() || !other.is_definite() {
+            return false;
+        }
+        other.start >= self.start && other.end() <= self.end()
+    }
+
+    /// Returns the smallest range that includes all times in both ranges.
+    ///
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// let a = cm::TimeRange::new(cm::Time::new(0, 1), cm::Time::new(2, 1));
+    /// let b = cm::TimeRange::new(cm::Time::new(5, 1), cm::Time::new(1, 1));
+    /// let u = a.union(&b);
+    /// assert_eq!(u.start, cm::Time::new(0, 1));
+    /// assert_eq!(u.end(), cm::Time::new(6, 1));
+    /// ```
+    #[doc(alias = "CMTimeRangeGetUnion")]
+    pub fn union(&self, other: &Self) -> Self {
+        if !self.is_valid() || !other.is_valid() {
+            return Self::invalid();
+        }
+        if self.is_indefinite() || other.is_indefinite() {
+            return Self::indefinite();
+        }
+        let start = cm::Time::min(self.start, other.start);
+        let end = cm::Time::max(self.end(), other.end());
+        Self::with_start_end(start, end)
+    }
+
+    /// Returns the range of times contained in both ranges.
+    ///
+    /// If ranges don't overlap the result is [`Range::zero()`].
+    ///
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// let a = cm::TimeRange::new(cm::Time::new(0, 1), cm::Time::new(4, 1));
+    /// let b = cm::TimeRange::new(cm::Time::new(3, 1), cm::Time::infinity());
+    /// let i = a.intersection(&b);
+    /// assert_eq!(i.start, cm::Time::new(3, 1));
+    /// assert_eq!(i.duration, cm::Time::new(1, 1));
+    /// ```
+    #[doc(alias = "CMTimeRangeGetIntersection")]
+    pub fn intersection(&self, other: &Self) -> Self {
+        if !self.is_valid() || !other.is_valid() {
+            return Self::invalid();
+        }
+        if self.is_indefinite() || other.is_indefinite() {
+            return Self::indefinite();
+        }
+        let start = cm::Time::max(self.start, other.start);
+        let end = cm::Time::min(self.end(), other.end());
+        if end <= start {
+            return Self::zero();
+        }
+        Self::with_start_end(start, end)
+    }
+
+    /// Returns the nearest time inside the range.
+    ///
+    /// Times before the start return the start, times after the end return the end.
+    /// If the range is empty, not definite or the time is invalid or indefinite,
+    /// the result is invalid.
+    #[doc(alias = "CMTimeClampToRange")]
+    pub fn clamp_time(&self, time: cm::Time) -> cm::Time {
+        if !self.is_definite() || self.is_empty() || time.is_invalid() || time.is_indefinite() {
+            return cm::Time::invalid();
+        }
+        let end = self.end();
+        if time < self.start {
+            self.start
+        } else if time > end {
+            end
+        } else {
+            time
+        }
+    }
+
+    /// Splits range into `[start, time)` and `[time, end)`.
+    ///
+    /// Returns `None` if the range doesn't contain the time.
+    ///
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// let range = cm::TimeRange::new(cm::Time::new(0, 1), cm::Time::new(10, 1));
+    /// let (a, b) = range.split_at(cm::Time::new(4, 1)).unwrap();
+    /// assert_eq!(a.duration, cm::Time::new(4, 1));
+    /// assert_eq!(b.start, cm::Time::new(4, 1));
+    /// assert_eq!(b.duration, cm::Time::new(6, 1));
+    /// assert!(range.split_at(cm::Time::new(10, 1)).is_none());
+    /// ```
+    pub fn split_at(&self, time: cm::Time) -> Option<(Self, Self)> {
+        if !self.contains_time(&time) {
+            return None;
+        }
+        let head = Self::with_start_end(self.start, time);
+        let tail = if self.duration.is_pos_infinity() {
+            Self::new(time, self.duration)
+        } else {
+            Self::with_start_end(time, self.end())
+        };
+        Some((head, tail))
     }
 
     #[doc(alias = "kCMTimeRangeZero")]
     #[inline]
     pub const fn zero() -> Self {
-        unsafe { kCMTimeRangeZero }
+        Self::new(cm::Time::zero(), cm::Time::zero())
     }
 
     #[doc(alias = "kCMTimeRangeInvalid")]
     #[inline]
     pub const fn invalid() -> Self {
-        unsafe { kCMTimeRangeInvalid }
+        Self::new(cm::Time::invalid(), cm::Time::invalid())
+    }
+
+    #[inline]
+    pub const fn indefinite() -> Self {
+        Self::new(cm::Time::indefinit(), cm::Time::indefinit())
     }
 }
 
+/// Maps times in the source range to the target range linearly.
+///
+/// Empty mapping (invalid source, valid target) represents an empty edit.
+#[doc(alias = "CMTimeMapping")]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[repr(C)]
 pub struct Mapping {
     pub source: cm::TimeRange,
     pub target: cm::TimeRange,
 }
 
+impl Mapping {
+    #[doc(alias = "CMTimeMappingMake")]
+    #[inline]
+    pub const fn new(source: cm::TimeRange, target: cm::TimeRange) -> Self {
+        Self { source, target }
+    }
+
+    #[doc(alias = "CMTimeMappingMakeEmpty")]
+    #[inline]
+    pub const fn empty(target: cm::TimeRange) -> Self {
+        Self::new(cm::TimeRange::invalid(), target)
+    }
+
+    #[doc(alias = "kCMTimeMappingInvalid")]
+    #[inline]
+    pub const fn invalid() -> Self {
+        Self::new(cm::TimeRange::invalid(), cm::TimeRange::invalid())
+    }
+
+    #[doc(alias = "CMTIMEMAPPING_IS_VALID")]
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        self.target.is_valid()
+    }
+
+    #[doc(alias = "CMTIMEMAPPING_IS_EMPTY")]
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        !self.source.is_valid() && self.target.is_valid()
+    }
+
+    /// Playback rate of the mapping: source duration / target duration.
+    ///
+    /// NaN for empty and invalid mappings.
+    pub fn rate(&self) -> f64 {
+        if !self.is_valid() || self.is_empty() {
+            return f64::NAN;
+        }
+        self.source.duration.as_secs() / self.target.duration.as_secs()
+    }
+
+    /// Maps source time to target time.
+    ///
+    /// ```
+    /// use cidre::cm;
+    ///
+    /// // source plays at 2x
+    /// let mapping = cm::TimeMapping::new(
+    ///     cm::TimeRange::new(cm::Time::new(10, 1), cm::Time::new(4, 1)),
+    ///     cm::TimeRange::new(cm::Time::new(0, 1), cm::Time::new(2, 1)),
+    /// );
+    /// assert_eq!(mapping.map_time(cm::Time::new(12, 1)), cm::Time::new(1, 1));
+    /// assert_eq!(mapping.unmap_time(cm::Time::new(1, 1)), cm::Time::new(12, 1));
+    /// ```
+    #[doc(alias = "CMTimeMapTimeFromRangeToRange")]
+    #[inline]
+    pub fn map_time(&self, time: cm::Time) -> cm::Time {
+        map_time(time, &self.source, &self.target)
+    }
+
+    /// Maps target time back to source time.
+    #[inline]
+    pub fn unmap_time(&self, time: cm::Time) -> cm::Time {
+        map_time(time, &self.target, &self.source)
+    }
+
+    /// Maps source duration to target duration.
+    #[doc(alias = "CMTimeMapDurationFromRangeToRange")]
+    #[inline]
+    pub fn map_duration(&self, duration: cm::Time) -> cm::Time {
+        map_duration(duration, &self.source, &self.target)
+    }
+
+    /// Maps source time to target time, clamping it into the source range first.
+    #[inline]
+    pub fn map_time_clamped(&self, time: cm::Time) -> cm::Time {
+        self.map_time(self.source.clamp_time(time))
+    }
+}
+
+/// result = (time - from.start) * (to.duration / from.duration) + to.start
+fn map_time(time: cm::Time, from: &Range, to: &Range) -> cm::Time {
+    if !from.is_definite() || !to.is_definite() || from.is_empty() || to.is_empty() {
+        return cm::Time::invalid();
+    }
+    if time.is_infinite() {
+        return time;
+    }
+    if !time.is_numeric() || time.epoch != from.start.epoch {
+        return cm::Time::invalid();
+    }
+    let offset = time.sub(from.start);
+    match (
+        from.duration.is_pos_infinity(),
+        to.duration.is_pos_infinity(),
+    ) {
+        (true, true) => offset.add(to.start),
+        (false, false) => scale_duration(offset, to.duration, from.duration).add(to.start),
+        _ => cm::Time::invalid(),
+    }
+}
+
+/// result = duration * (to.duration / from.duration)
+fn map_duration(duration: cm::Time, from: &Range, to: &Range) -> cm::Time {
+    if !from.is_definite() || !to.is_definite() || from.is_empty() || to.is_empty() {
+        return cm::Time::invalid();
+    }
+    if !duration.is_numeric() {
+        return duration;
+    }
+    match (
+        from.duration.is_pos_infinity(),
+        to.duration.is_pos_infinity(),
+    ) {
+        (true, true) => duration,
+        (false, false) => scale_duration(duration, to.duration, from.duration),
+        _ => cm::Time::invalid(),
+    }
+}
+
+/// Returns `time * num / den`.
+///
+/// Exact result is used when its reduced timescale fits, otherwise
+/// the result is rounded to `num` timescale.
+fn scale_duration(time: cm::Time, num: cm::Time, den: cm::Time) -> cm::Time {
+    if time.scale <= 0 || num.scale <= 0 || den.scale <= 0 || den.value == 0 {
+        return cm::Time::invalid();
+    }
+    let mut flags = cm::TimeFlags::VALID;
+    flags.set(cm::TimeFlags::HAS_BEEN_ROUNDED, time.has_been_rounded());
+
+    // secs = (tv / ts) * (nv / ns) / (dv / ds) = (tv * nv * ds) / (ts * dv) / ns
+    let n = (time.value as i128)
+        .checked_mul(num.value as i128)
+        .and_then(|v| v.checked_mul(den.scale as i128));
+    let d = time.scale as i128 * den.value as i128;
+    if let Some(n) = n {
+        let (n, d) = if d < 0 { (-n, -d) } else { (n, d) };
+        let exact_d = d * num.scale as i128;
+        let g = super::gcd(n, exact_d).abs();
+        if let (Ok(value), Ok(scale @ 1..=cm::Time::MAX_SCALE)) = (
+            cm::TimeValue::try_from(n / g),
+            cm::TimeScale::try_from(exact_d / g),
+        ) {
+            return cm::Time {
+                value,
+                scale,
+                flags,
+                epoch: time.epoch,
+            };
+        }
+        let (value, rounded) = super::div_round(n, d, cm::TimeRoundingMethod::DEFAULT, false);
+        if let Ok(value) = cm::TimeValue::try_from(value) {
+            if rounded {
+                flags |= cm::TimeFlags::HAS_BEEN_ROUNDED;
+            }
+            return cm::Time {
+                value,
+                scale: num.scale,
+                flags,
+                epoch: time.epoch,
+            };
+        }
+    }
+    let mut res = cm::Time::with_secs(time.as_secs() * num.as_secs() / den.as_secs(), num.scale);
+    res.flags |= cm::TimeFlags::HAS_BEEN_ROUNDED;
+    res.epoch = time.epoch;
+    res
+}
+
+#[cfg(all(test, target_vendor = "apple"))]
 #[link(name = "CoreMedia", kind = "framework")]
 unsafe extern "C-unwind" {
     static kCMTimeRangeZero: Range;
     static kCMTimeRangeInvalid: Range;
 
     fn CMTimeRangeContainsTime(range: Range, time: cm::Time) -> bool;
+    fn CMTimeRangeContainsTimeRange(range: Range, other_range: Range) -> bool;
+    fn CMTimeRangeGetEnd(range: Range) -> cm::Time;
+    fn CMTimeRangeGetUnion(range: Range, other_range: Range) -> Range;
+    fn CMTimeRangeGetIntersection(range: Range, other_range: Range) -> Range;
+    fn CMTimeClampToRange(time: cm::Time, range: Range) -> cm::Time;
+    fn CMTimeMapTimeFromRangeToRange(t: cm::Time, from_range: Range, to_range: Range) -> cm::Time;
+    fn CMTimeMapDurationFromRangeToRange(
+        dur: cm::Time,
+        from_range: Range,
+        to_range: Range,
+    ) -> cm::Time;
 }
 
 #[cfg(test)]
 mod tests {
     use crate::cm;
 
+    fn range(start: i64, duration: i64) -> cm::TimeRange {
+        cm::TimeRange::new(cm::Time::new(start, 1), cm::Time::new(duration, 1))
+    }
+
     #[test]
     fn basics() {
         let range = cm::TimeRange::zero();
@@ -74,4 +440,202 @@ mod tests {
         assert!(!range.is_valid());
         assert!(!range.is_empty());
     }
+
+    #[test]
+    fn algebra() {
+        let a = range(0, 10);
+        let b = range(5, 10);
+        let c = range(20, 5);
+
+        assert_eq!(a.union(&b), range(0, 15));
+        assert_eq!(a.union(&c), range(0, 25));
+        assert_eq!(a.intersection(&b), range(5, 5));
+        assert!(a.intersection(&c).is_empty());
+        assert!(a.intersection(&range(10, 1)).is_empty());
+
+        assert!(a.contains_range(&range(2, 8)));
+        assert!(!a.contains_range(&b));
+        assert!(a.contains_time(&cm::Time::zero()));
+        assert!(!a.contains_time(&cm::Time::new(10, 1)));
+        assert!(!a.contains_time(&cm::Time::invalid()));
+        assert!(!cm::TimeRange::zero().contains_time(&cm::Time::zero()));
+
+        assert_eq!(a.clamp_time(cm::Time::new(-1, 1)), cm::Time::zero());
+        assert_eq!(a.clamp_time(cm::Time::new(11, 1)), cm::Time::new(10, 1));
+        assert_eq!(a.clamp_time(cm::Time::new(3, 1)), cm::Time::new(3, 1));
+        assert!(
+            cm::TimeRange::zero()
+                .clamp_time(cm::Time::zero())
+                .is_invalid()
+        );
+
+        let (head, tail) = a.split_at(cm::Time::zero()).unwrap();
+        assert!(head.is_empty());
+        assert_eq!(tail, a);
+    }
+
+    #[test]
+    fn non_numeric() {
+        let a = range(0, 10);
+        let open = cm::TimeRange::new(cm::Time::new(5, 1), cm::Time::infinity());
+
+        assert!(open.end().is_pos_infinity());
+        assert!(open.contains_time(&cm::Time::new(i64::MAX, 1)));
+        assert!(!open.contains_time(&cm::Time::infinity()));
+        assert!(a.union(&open).duration.is_pos_infinity());
+        assert_eq!(a.intersection(&open), range(5, 5));
+        assert_eq!(open.clamp_time(cm::Time::infinity()), cm::Time::infinity());
+
+        let (head, tail) = open.split_at(cm::Time::new(7, 1)).unwrap();
+        assert_eq!(head, range(5, 2));
+        assert!(tail.duration.is_pos_infinity());
+
+        let indefinite = cm::TimeRange::indefinite();
+        assert!(indefinite.is_indefinite());
+        assert!(a.union(&indefinite).is_indefinite());
+        assert!(a.intersection(&indefinite).is_indefinite());
+        assert!(!indefinite.contains_time(&cm::Time::zero()));
+        assert!(indefinite.clamp_time(cm::Time::zero()).is_invalid());
+
+        let invalid = cm::TimeRange::invalid();
+        assert!(!a.union(&invalid).is_valid());
+        assert!(!a.intersection(&invalid).is_valid());
+        assert!(a.split_at(cm::Time::indefinit()).is_none());
+    }
+
+    #[test]
+    fn mapping() {
+        let mapping = cm::TimeMapping::new(range(10, 4), range(100, 2));
+        assert_eq!(mapping.rate(), 2.0);
+        assert_eq!(
+            mapping.map_time(cm::Time::new(10, 1)),
+            cm::Time::new(100, 1)
+        );
+        assert_eq!(
+            mapping.map_time(cm::Time::new(14, 1)),
+            cm::Time::new(102, 1)
+        );
+        assert_eq!(
+            mapping.map_time(cm::Time::new(11, 1)),
+            cm::Time::new(201, 2)
+        );
+        assert_eq!(
+            mapping.map_duration(cm::Time::new(3, 1)),
+            cm::Time::new(3, 2)
+        );
+        assert_eq!(
+            mapping.unmap_time(cm::Time::new(101, 1)),
+            cm::Time::new(12, 1)
+        );
+        assert_eq!(
+            mapping.map_time_clamped(cm::Time::new(20, 1)),
+            cm::Time::new(102, 1)
+        );
+
+        let t = mapping.map_time(cm::Time::new(1, 3));
+        assert_eq!(t, cm::Time::new(571, 6));
+        assert!(!t.has_been_rounded());
+
+        assert!(
+            mapping
+                .map_time(cm::Time::with_epoch(11, 1, 1))
+                .is_invalid()
+        );
+
+        let empty = cm::TimeMapping::empty(range(0, 5));
+        assert!(empty.is_empty());
+        assert!(empty.is_valid());
+        assert!(empty.map_time(cm::Time::zero()).is_invalid());
+        assert!(empty.rate().is_nan());
+
+        let open = cm::TimeMapping::new(
+            cm::TimeRange::new(cm::Time::new(5, 1), cm::Time::infinity()),
+            cm::TimeRange::new(cm::Time::new(7, 1), cm::Time::infinity()),
+        );
+        assert_eq!(open.map_time(cm::Time::new(6, 1)), cm::Time::new(8, 1));
+
+        // intermediate products overflow i128, falls back to f64
+        let huge = cm::TimeMapping::new(
+            cm::TimeRange::new(cm::Time::zero(), cm::Time::new(i64::MAX / 2, 999_999_937)),
+            cm::TimeRange::new(cm::Time::zero(), cm::Time::new(i64::MAX / 3, 999_999_929)),
+        );
+        let t = huge.map_time(cm::Time::new(i64::MAX / 4, 999_999_937));
+        assert!(t.is_numeric());
+        let expected = huge.target.duration.as_secs() / 2.0;
+        assert!((t.as_secs() - expected).abs() / expected < 1e-9);
+        assert!(t.has_been_rounded());
+    }
+
+    #[cfg(target_vendor = "apple")]
+    #[test]
+    fn ffi_parity() {
+        use super::*;
+
+        let times = [
+            cm::Time::invalid(),
+            cm::Time::indefinit(),
+            cm::Time::infinity(),
+            cm::Time::neg_infinity(),
+            cm::Time::zero(),
+            cm::Time::new(7, 3),
+            cm::Time::new(-1, 2),
+            cm::Time::new(25, 1),
+        ];
+        let ranges = [
+            Range::invalid(),
+            Range::zero(),
+            range(0, 10),
+            range(5, 10),
+            range(20, 5),
+            Range::new(cm::Time::new(1, 3), cm::Time::new(7, 600)),
+            Range::new(cm::Time::new(5, 1), cm::Time::infinity()),
+        ];
+
+        unsafe {
+            assert_eq!(Range::zero(), kCMTimeRangeZero);
+            assert!(!kCMTimeRangeInvalid.is_valid());
+
+            for a in ranges {
+                if a.is_valid() {
+                    assert_eq!(a.end(), CMTimeRangeGetEnd(a), "{a:?}");
+                }
+                for t in times {
+                    assert_eq!(
+                        a.contains_time(&t),
+                        CMTimeRangeContainsTime(a, t),
+                        "{a:?} {t:?}"
+                    );
+                    if a.is_valid() && !a.is_empty() && !t.is_invalid() {
+                        assert_eq!(a.clamp_time(t), CMTimeClampToRange(t, a), "{a:?} {t:?}");
+                    }
+                }
+                for b in ranges {
+                    if !a.is_valid() || !b.is_valid() {
+                        continue;
+                    }
+                    assert_eq!(a.union(&b), CMTimeRangeGetUnion(a, b), "{a:?} {b:?}");
+                    assert_eq!(
+                        a.contains_range(&b),
+                        CMTimeRangeContainsTimeRange(a, b),
+                        "{a:?} {b:?}"
+                    );
+                    let native = a.intersection(&b);
+                    let ffi = CMTimeRangeGetIntersection(a, b);
+                    assert!(native == ffi || (native.is_empty() && ffi.is_empty()));
+                    for t in times {
+                        if !t.is_numeric() || a.is_empty() || b.is_empty() {
+                            continue;
+                        }
+                        // native mapping keeps exact results where CoreMedia rounds
+                        let native = map_time(t, &a, &b).as_secs();
+                        let ffi = CMTimeMapTimeFromRangeToRange(t, a, b).as_secs();
+                        assert!((native - ffi).abs() < 1e-6, "{t:?} {a:?} {b:?}");
+                        let native = map_duration(t, &a, &b).as_secs();
+                        let ffi = CMTimeMapDurationFromRangeToRange(t, a, b).as_secs();
+                        assert!((native - ffi).abs() < 1e-6, "{t:?} {a:?} {b:?}");
+                    }
+                }
+            }
+        }
+    }
 }
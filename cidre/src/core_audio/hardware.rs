@@ -3,11 +3,11 @@ use std::ffi::c_void;
 use crate::{
     arc,
     at::{AudioBufListN, audio::ValueRange},
-    cat::{self, AudioBasicStreamDesc},
+    cat::{self, AudioBasicStreamDesc, audio::ChannelLayoutBuf},
     cf,
     core_audio::{
         Class, DeviceTransportType, Obj, PropAddr, PropElement, PropScope, PropSelector,
-        StreamRangedDesc, StreamTerminalType,
+        StreamRangedDesc, StreamTerminalType, err,
     },
     os, sys,
 };
@@ -573,6 +573,34 @@ impl Device {
         self.prop_vec(&PropSelector::DEVICE_STREAMS.global_addr())
     }
 
+    /// How each channel of the device should be used
+    #[doc(alias = "kAudioDevicePropertyPreferredChannelLayout")]
+    pub fn preferred_channel_layout(&self, scope: PropScope) -> os::Result<ChannelLayoutBuf> {
+        let addr = PropSelector::DEVICE_PREFERRED_CHANNEL_LAYOUT.addr(scope, PropElement::MAIN);
+        let bytes: Vec<u8> = self.prop_vec(&addr)?;
+        ChannelLayoutBuf::from_bytes(&bytes).map_err(|_| err::BAD_PROPERTY_SIZE)
+    }
+
+    #[doc(alias = "kAudioDevicePropertyPreferredChannelLayout")]
+    pub fn set_preferred_channel_layout(
+        &mut self,
+        scope: PropScope,
+        val: &ChannelLayoutBuf,
+    ) -> os::Result {
+        let addr = PropSelector::DEVICE_PREFERRED_CHANNEL_LAYOUT.addr(scope, PropElement::MAIN);
+        val.with_raw(|layout, size| unsafe {
+            AudioObjectSetPropertyData(
+                self.0,
+                &addr,
+                0,
+                std::ptr::null(),
+                size as u32,
+                layout as *const _ as _,
+            )
+            .result()
+        })
+    }
+
     pub fn transport_type(&self) -> os::Result<DeviceTransportType> {
         self.prop(&PropSelector::DEVICE_TRANSPORT_TYPE.global_addr())
     }
@@ -1720,8 +1748,8 @@ mod tests {
     use crate::{
         cat, cf,
         core_audio::{
-            AggregateDevice, Class, Device, DeviceTransportType, Obj, Process, PropSelector,
-            System, TapDesc, aggregate_device_keys as agg_keys,
+            AggregateDevice, Class, Device, DeviceTransportType, Obj, Process, PropScope,
+            PropSelector, System, TapDesc, aggregate_device_keys as agg_keys,
         },
         ns, os,
     };
@@ -1740,6 +1768,19 @@ mod tests {
         assert_eq!(device.base_class().unwrap(), Class::OBJECT);
     }
 
+    #[test]
+    fn preferred_channel_layout() {
+        let uid = cf::str!(c"BuiltInSpeakerDevice");
+        let device = Device::with_uid(uid.retained()).unwrap();
+        let layout = device.preferred_channel_layout(PropScope::OUTPUT).unwrap();
+        assert!(layout.number_of_channels() > 0);
+        let bytes = layout.to_bytes();
+        assert_eq!(
+            cat::audio::ChannelLayoutBuf::from_bytes(&bytes).unwrap(),
+            layout
+        );
+    }
+
     #[test]
     fn list_devices() {
         let addr = PropSelector::HW_DEFAULT_INPUT_DEVICE.global_addr();
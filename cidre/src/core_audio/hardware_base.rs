@@ -712,6 +712,15 @@ impl PropSelector {
     pub const DEVICE_PREFERRED_CHANNEL_LAYOUT: Self = Self(u32::from_be_bytes(*b"srnd"));
 }
 
+pub mod err {
+    use crate::os::Error;
+
+    /// 0x2173697A, 561211770
+    /// The size of the property data was not correct.
+    #[doc(alias = "kAudioHardwareBadPropertySizeError")]
+    pub const BAD_PROPERTY_SIZE: Error = Error::from_be_bytes(*b"!siz");
+}
+
 #[doc(alias = "AudioStreamRangedDescription")]
 #[derive(Debug, PartialEq, Clone, Copy)]
 #[repr(C)]
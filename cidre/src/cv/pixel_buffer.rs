@@ -1,6 +1,10 @@
+#[cfg(target_vendor = "apple")]
 use std::ffi::c_void;
 
-use crate::{arc, cf, cv, define_opts, four_cc_fmt_debug, os};
+use crate::{four_cc_fmt_debug, os};
+
+#[cfg(target_vendor = "apple")]
+use crate::{arc, cf, cv, define_opts};
 
 #[cfg(feature = "io_surface")]
 use crate::io;
@@ -8,12 +12,15 @@ use crate::io;
 #[cfg(feature = "ns")]
 use crate::ns;
 
+#[cfg(target_vendor = "apple")]
 #[doc(alias = "CVPixelBuffer")]
 pub type PixelBuf = cv::ImageBuf;
 
+#[cfg(target_vendor = "apple")]
 pub type ReleaseCallback =
     extern "C" fn(release_ref_con: *mut c_void, base_address: *const *const c_void);
 
+#[cfg(target_vendor = "apple")]
 impl PixelBuf {
     #[doc(alias = "CVPixelBufferGetTypeID")]
     #[inline]
@@ -232,8 +239,10 @@ impl PixelBuf {
     }
 }
 
+#[cfg(target_vendor = "apple")]
 pub struct BaseAddrLockGuard<'a>(&'a mut PixelBuf, LockFlags);
 
+#[cfg(target_vendor = "apple")]
 impl<'a> Drop for BaseAddrLockGuard<'a> {
     #[inline]
     fn drop(&mut self) {
@@ -243,8 +252,10 @@ impl<'a> Drop for BaseAddrLockGuard<'a> {
     }
 }
 
+#[cfg(target_vendor = "apple")]
 define_opts!(pub LockFlags(cv::OptionFlags));
 
+#[cfg(target_vendor = "apple")]
 impl LockFlags {
     pub const DEFAULT: Self = Self(0);
     pub const READ_ONLY: Self = Self(1);
@@ -473,26 +484,30 @@ impl PixelFormat {
     #[doc(alias = "kCVPixelFormatType_128RGBAFloat")]
     pub const _128_RGBA_FLOAT: Self = Self(os::Type::from_be_bytes(*b"RGfA"));
 
+    #[cfg(target_vendor = "apple")]
     pub fn from_cf_number(number: &cf::Number) -> Self {
         Self(number.to_i32().unwrap_or(0) as u32)
     }
 
+    #[cfg(target_vendor = "apple")]
     pub fn to_desc(&self) -> Option<arc::R<cf::Dictionary>> {
         cv::pixel_format_desc_create(*self)
     }
 
+    #[cfg(target_vendor = "apple")]
     #[inline]
     pub fn to_cf_number(&self) -> &'static cf::Number {
         cf::Number::from_four_char_code(self.0)
     }
 
-    #[cfg(feature = "ns")]
+    #[cfg(all(feature = "ns", target_vendor = "apple"))]
     #[inline]
     pub fn to_ns_number(&self) -> &'static ns::Number {
         cf::Number::from_four_char_code(self.0).as_ns()
     }
 
     /// Checks if a compressed pixel format is supported on the current platform.
+    #[cfg(target_vendor = "apple")]
     #[doc(alias = "CVIsCompressedPixelFormatAvailable")]
     #[inline]
     pub fn is_compressed_avaliable(&self) -> bool {
@@ -633,19 +648,35 @@ impl std::fmt::Debug for PixelFormat {
     }
 }
 
+impl From<crate::FourCc> for PixelFormat {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<PixelFormat> for crate::FourCc {
+    #[inline]
+    fn from(value: PixelFormat) -> Self {
+        Self(value.0)
+    }
+}
+
+#[cfg(target_vendor = "apple")]
 impl AsRef<cf::Type> for PixelFormat {
     fn as_ref(&self) -> &'static cf::Type {
         self.to_cf_number().as_type_ref()
     }
 }
 
-#[cfg(feature = "ns")]
+#[cfg(all(feature = "ns", target_vendor = "apple"))]
 impl AsRef<ns::Id> for PixelFormat {
     fn as_ref(&self) -> &'static ns::Id {
         self.to_ns_number().as_id_ref()
     }
 }
 
+#[cfg(target_vendor = "apple")]
 #[link(name = "CoreVideo", kind = "framework")]
 unsafe extern "C-unwind" {
     fn CVPixelBufferGetTypeID() -> cf::TypeId;
@@ -704,6 +735,7 @@ unsafe extern "C-unwind" {
     fn CVIsCompressedPixelFormatAvailable(pixel_format: PixelFormat) -> bool;
 }
 
+#[cfg(target_vendor = "apple")]
 pub mod keys {
     use crate::cf;
 
@@ -768,7 +800,7 @@ pub mod keys {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, target_vendor = "apple"))]
 mod tests {
     use crate::{cv::PixelFormat, objc::Obj};
 
@@ -0,0 +1,648 @@
+//! Static plane layout of common [`cv::PixelFormat`] values.
+//!
+//! The same information `cv::pixel_format_desc_create` returns at runtime,
+//! so buffer sizes can be computed without CoreVideo.
+
+use crate::cv;
+
+#[cfg(feature = "io_surface")]
+use crate::io;
+
+#[cfg(feature = "mtl")]
+use crate::mtl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Component {
+    Alpha,
+    Red,
+    Green,
+    Blue,
+    Luma,
+    ChromaBlue,
+    ChromaRed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Range {
+    Full,
+    /// Luma and chroma use the video levels, `16..=235` and `16..=240` for 8 bits.
+    Video,
+}
+
+/// Chroma subsampling of the whole image, `None` for RGB formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Subsampling {
+    None,
+    /// Chroma downsampled by 2x1
+    _422,
+    /// Chroma downsampled by 2x2
+    _420,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compression {
+    None,
+    Lossless,
+    Lossy,
+}
+
+/// Metal formats used for CoreVideo planes, kept private so the table
+/// doesn't depend on the `mtl` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Mtl {
+    None,
+    R8UNorm,
+    R16UNorm,
+    R16Float,
+    R32Float,
+    Rg8UNorm,
+    Rg16UNorm,
+    Rg16Float,
+    Rg32Float,
+    Rgba8UNorm,
+    Bgra8UNorm,
+    Rgba16UNorm,
+    Rgba16Float,
+    Rgba32Float,
+    Bgr10A2UNorm,
+    Bgrg422,
+}
+
+/// One plane of a [`Layout`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Plane {
+    /// Components of one block in memory order, packed formats list them
+    /// from the most significant bits.
+    pub components: &'static [Component],
+    /// Significant bits of the colour components, alpha of packed formats may be narrower.
+    pub bits_per_component: u8,
+    /// How many image pixels share one plane pixel horizontally.
+    pub h_subsampling: u8,
+    /// How many image pixels share one plane pixel vertically.
+    pub v_subsampling: u8,
+    /// Plane pixels stored in one block, 1 for most formats.
+    pub block_width: u8,
+    pub block_bytes: u8,
+    #[cfg_attr(not(feature = "mtl"), allow(dead_code))]
+    mtl: Mtl,
+}
+
+impl Plane {
+    const fn new(
+        components: &'static [Component],
+        bits_per_component: u8,
+        block_width: u8,
+        block_bytes: u8,
+        mtl: Mtl,
+    ) -> Self {
+        Self {
+            components,
+            bits_per_component,
+            h_subsampling: 1,
+            v_subsampling: 1,
+            block_width,
+            block_bytes,
+            mtl,
+        }
+    }
+
+    const fn subsampled(mut self, h: u8, v: u8) -> Self {
+        self.h_subsampling = h;
+        self.v_subsampling = v;
+        self
+    }
+
+    /// Plane width for an image `width` pixels wide, odd sizes round up.
+    #[inline]
+    pub const fn width(&self, width: usize) -> usize {
+        width.div_ceil(self.h_subsampling as usize)
+    }
+
+    /// Plane height for an image `height` pixels high, odd sizes round up.
+    #[inline]
+    pub const fn height(&self, height: usize) -> usize {
+        height.div_ceil(self.v_subsampling as usize)
+    }
+
+    /// Bytes of one row for an image `width` pixels wide, rounded up to
+    /// a multiple of `alignment` (0 and 1 leave rows unpadded).
+    ///
+    /// ```
+    /// use cidre::cv;
+    ///
+    /// let layout = cv::PixelFormat::_420V.layout().unwrap();
+    /// assert_eq!(layout.planes[0].bytes_per_row(1917, 1), 1917);
+    /// assert_eq!(layout.planes[1].bytes_per_row(1917, 64), 1920);
+    /// ```
+    pub const fn bytes_per_row(&self, width: usize, alignment: usize) -> usize {
+        let bytes =
+            self.width(width).div_ceil(self.block_width as usize) * self.block_bytes as usize;
+        if alignment > 1 {
+            bytes.next_multiple_of(alignment)
+        } else {
+            bytes
+        }
+    }
+
+    /// Bytes of the plane for an image of `width` x `height` pixels.
+    #[inline]
+    pub const fn bytes(&self, width: usize, height: usize, alignment: usize) -> usize {
+        self.bytes_per_row(width, alignment) * self.height(height)
+    }
+
+    /// Metal format to create a texture over this plane with `cv::MetalTextureCache`.
+    ///
+    /// `None` when Metal has no matching format, for example for `v210`
+    /// or the big-endian RGB formats.
+    #[cfg(feature = "mtl")]
+    pub const fn mtl_pixel_format(&self) -> Option<mtl::PixelFormat> {
+        Some(match self.mtl {
+            Mtl::None => return None,
+            Mtl::R8UNorm => mtl::PixelFormat::R8UNorm,
+            Mtl::R16UNorm => mtl::PixelFormat::R16UNorm,
+            Mtl::R16Float => mtl::PixelFormat::R16Float,
+            Mtl::R32Float => mtl::PixelFormat::R32Float,
+            Mtl::Rg8UNorm => mtl::PixelFormat::Rg8UNorm,
+            Mtl::Rg16UNorm => mtl::PixelFormat::Rg16UNorm,
+            Mtl::Rg16Float => mtl::PixelFormat::Rg16Float,
+            Mtl::Rg32Float => mtl::PixelFormat::Rg32Float,
+            Mtl::Rgba8UNorm => mtl::PixelFormat::Rgba8UNorm,
+            Mtl::Bgra8UNorm => mtl::PixelFormat::Bgra8UNorm,
+            Mtl::Rgba16UNorm => mtl::PixelFormat::Rgba16UNorm,
+            Mtl::Rgba16Float => mtl::PixelFormat::Rgba16Float,
+            Mtl::Rgba32Float => mtl::PixelFormat::Rgba32Float,
+            Mtl::Bgr10A2UNorm => mtl::PixelFormat::Bgr10A2UNorm,
+            Mtl::Bgrg422 => mtl::PixelFormat::Bgrg422,
+        })
+    }
+}
+
+/// Memory layout of a [`cv::PixelFormat`]
+///
+/// Compressed formats describe their uncompressed equivalent, the real
+/// footprint of compressed buffers is device specific.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Layout {
+    /// A single plane for packed formats, CoreVideo reports
+    /// a plane count of 0 for those.
+    pub planes: &'static [Plane],
+    pub range: Range,
+    pub subsampling: Subsampling,
+    pub compression: Compression,
+}
+
+impl Layout {
+    const fn full(planes: &'static [Plane]) -> Self {
+        Self {
+            planes,
+            range: Range::Full,
+            subsampling: Subsampling::None,
+            compression: Compression::None,
+        }
+    }
+
+    const fn video(planes: &'static [Plane]) -> Self {
+        Self::full(planes).range(Range::Video)
+    }
+
+    const fn range(mut self, range: Range) -> Self {
+        self.range = range;
+        self
+    }
+
+    const fn subsampling(mut self, subsampling: Subsampling) -> Self {
+        self.subsampling = subsampling;
+        self
+    }
+
+    const fn compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    #[inline]
+    pub const fn is_planar(&self) -> bool {
+        self.planes.len() > 1
+    }
+
+    #[inline]
+    pub const fn is_compressed(&self) -> bool {
+        !matches!(self.compression, Compression::None)
+    }
+
+    /// Sum of the plane sizes for an image of `width` x `height` pixels.
+    ///
+    /// CoreVideo may add padding between planes and a planar info header,
+    /// so an allocated buffer can be larger.
+    ///
+    /// ```
+    /// use cidre::cv;
+    ///
+    /// let layout = cv::PixelFormat::_420V.layout().unwrap();
+    /// assert_eq!(layout.bytes(1920, 1080, 64), 1920 * 1080 * 3 / 2);
+    /// ```
+    pub const fn bytes(&self, width: usize, height: usize, alignment: usize) -> usize {
+        let mut bytes = 0;
+        let mut i = 0;
+        while i < self.planes.len() {
+            bytes += self.planes[i].bytes(width, height, alignment);
+            i += 1;
+        }
+        bytes
+    }
+}
+
+use Component::{
+    Alpha as A, Blue as B, ChromaBlue as Cb, ChromaRed as Cr, Green as G, Luma as Y, Red as R,
+};
+
+const ONE_8: &[Plane] = &[Plane::new(&[R], 8, 1, 1, Mtl::R8UNorm)];
+const ONE_16_HALF: &[Plane] = &[Plane::new(&[R], 16, 1, 2, Mtl::R16Float)];
+const ONE_32_FLOAT: &[Plane] = &[Plane::new(&[R], 32, 1, 4, Mtl::R32Float)];
+const TWO_16_HALF: &[Plane] = &[Plane::new(&[R, G], 16, 1, 4, Mtl::Rg16Float)];
+const TWO_32_FLOAT: &[Plane] = &[Plane::new(&[R, G], 32, 1, 8, Mtl::Rg32Float)];
+
+const RGB_24: &[Plane] = &[Plane::new(&[R, G, B], 8, 1, 3, Mtl::None)];
+const BGR_24: &[Plane] = &[Plane::new(&[B, G, R], 8, 1, 3, Mtl::None)];
+const ARGB_32: &[Plane] = &[Plane::new(&[A, R, G, B], 8, 1, 4, Mtl::None)];
+const BGRA_32: &[Plane] = &[Plane::new(&[B, G, R, A], 8, 1, 4, Mtl::Bgra8UNorm)];
+const ABGR_32: &[Plane] = &[Plane::new(&[A, B, G, R], 8, 1, 4, Mtl::None)];
+const RGBA_32: &[Plane] = &[Plane::new(&[R, G, B, A], 8, 1, 4, Mtl::Rgba8UNorm)];
+const ARGB_64: &[Plane] = &[Plane::new(&[A, R, G, B], 16, 1, 8, Mtl::None)];
+const RGBA_64_LE: &[Plane] = &[Plane::new(&[R, G, B, A], 16, 1, 8, Mtl::Rgba16UNorm)];
+const RGBA_64_HALF: &[Plane] = &[Plane::new(&[R, G, B, A], 16, 1, 8, Mtl::Rgba16Float)];
+const RGBA_128_FLOAT: &[Plane] = &[Plane::new(&[R, G, B, A], 32, 1, 16, Mtl::Rgba32Float)];
+const RGB_30: &[Plane] = &[Plane::new(&[R, G, B], 10, 1, 4, Mtl::None)];
+const ARGB_2101010: &[Plane] = &[Plane::new(&[A, R, G, B], 10, 1, 4, Mtl::Bgr10A2UNorm)];
+
+const CB_Y_CR_Y_8: Plane = Plane::new(&[Cb, Y, Cr, Y], 8, 2, 4, Mtl::Bgrg422);
+const YCBCR_422_8: &[Plane] = &[CB_Y_CR_Y_8];
+const YCBCR_422_16: &[Plane] = &[Plane::new(&[Cb, Y, Cr, Y], 16, 2, 8, Mtl::None)];
+/// v210 rows are padded to 48 pixels, 6 pixels are packed into 16 bytes.
+const YCBCR_422_10: &[Plane] = &[Plane::new(&[Cb, Y, Cr, Y], 10, 48, 128, Mtl::None)];
+const YCBCR_444_8: &[Plane] = &[Plane::new(&[Cr, Y, Cb], 8, 1, 3, Mtl::None)];
+const YCBCR_444_10: &[Plane] = &[Plane::new(&[Cr, Y, Cb], 10, 1, 4, Mtl::None)];
+const YCBCRA_8: &[Plane] = &[Plane::new(&[Cb, Y, Cr, A], 8, 1, 4, Mtl::Rgba8UNorm)];
+const AYCBCR_8: &[Plane] = &[Plane::new(&[A, Y, Cb, Cr], 8, 1, 4, Mtl::Rgba8UNorm)];
+const AYCBCR_16: &[Plane] = &[Plane::new(&[A, Y, Cb, Cr], 16, 1, 8, Mtl::Rgba16UNorm)];
+const AYCBCR_FLOAT: &[Plane] = &[Plane::new(&[A, Y, Cb, Cr], 32, 1, 16, Mtl::Rgba32Float)];
+
+const Y_8: Plane = Plane::new(&[Y], 8, 1, 1, Mtl::R8UNorm);
+const Y_10: Plane = Plane::new(&[Y], 10, 1, 2, Mtl::R16UNorm);
+const CB_CR_8: Plane = Plane::new(&[Cb, Cr], 8, 1, 2, Mtl::Rg8UNorm);
+const CB_CR_10: Plane = Plane::new(&[Cb, Cr], 10, 1, 4, Mtl::Rg16UNorm);
+const PLANAR_420_8: &[Plane] = &[
+    Y_8,
+    Plane::new(&[Cb], 8, 1, 1, Mtl::R8UNorm).subsampled(2, 2),
+    Plane::new(&[Cr], 8, 1, 1, Mtl::R8UNorm).subsampled(2, 2),
+];
+const BI_PLANAR_420_8: &[Plane] = &[Y_8, CB_CR_8.subsampled(2, 2)];
+const BI_PLANAR_420_10: &[Plane] = &[Y_10, CB_CR_10.subsampled(2, 2)];
+const BI_PLANAR_422_10: &[Plane] = &[Y_10, CB_CR_10.subsampled(2, 1)];
+const BI_PLANAR_444_10: &[Plane] = &[Y_10, CB_CR_10];
+const BI_PLANAR_422_8_A_8: &[Plane] = &[CB_Y_CR_Y_8, Plane::new(&[A], 8, 1, 1, Mtl::R8UNorm)];
+
+impl cv::PixelFormat {
+    /// Plane layout of the format, `None` for formats missing in the table
+    /// like the indexed and 16-bit RGB ones.
+    ///
+    /// ```
+    /// use cidre::cv;
+    ///
+    /// let layout = cv::PixelFormat::_420_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE.layout().unwrap();
+    /// assert_eq!(layout.planes.len(), 2);
+    /// assert_eq!(layout.planes[1].width(1921), 961);
+    /// assert_eq!(layout.planes[1].bytes_per_row(1921, 1), 961 * 4);
+    /// assert_eq!(layout.range, cv::PixelFormatRange::Video);
+    /// assert!(cv::PixelFormat::_16_LE_565.layout().is_none());
+    /// ```
+    pub const fn layout(&self) -> Option<Layout> {
+        use Subsampling::{_420, _422};
+
+        let layout = match self.uncompressed() {
+            Self::ONE_COMPONENT_8 => Layout::full(ONE_8),
+            Self::ONE_COMPONENT_16_HALF => Layout::full(ONE_16_HALF),
+            Self::ONE_COMPONENT_32_FLOAT => Layout::full(ONE_32_FLOAT),
+            Self::TWO_COMPONENT_16_HALF => Layout::full(TWO_16_HALF),
+            Self::TWO_COMPONENT_32_FLOAT => Layout::full(TWO_32_FLOAT),
+            Self::_24_RGB => Layout::full(RGB_24),
+            Self::_24_BGR => Layout::full(BGR_24),
+            Self::_32_ARGB => Layout::full(ARGB_32),
+            Self::_32_BGRA => Layout::full(BGRA_32),
+            Self::_32_ABGR => Layout::full(ABGR_32),
+            Self::_32_RGBA => Layout::full(RGBA_32),
+            Self::_64_ARGB => Layout::full(ARGB_64),
+            Self::_64_RGBALE => Layout::full(RGBA_64_LE),
+            Self::_64_RGBA_HALF => Layout::full(RGBA_64_HALF),
+            Self::_128_RGBA_FLOAT => Layout::full(RGBA_128_FLOAT),
+            Self::_30_RGB => Layout::full(RGB_30),
+            Self::_30_RGB_R210 => Layout::video(RGB_30),
+            Self::ARGB_2101010_LE_PACKED => Layout::full(ARGB_2101010),
+            Self::_422_YP_CB_CR_8 => Layout::video(YCBCR_422_8).subsampling(_422),
+            Self::_422_YP_CB_CR_16 => Layout::video(YCBCR_422_16).subsampling(_422),
+            Self::_422_YP_CB_CR_10 => Layout::video(YCBCR_422_10).subsampling(_422),
+            Self::_444_YP_CB_CR_8 => Layout::video(YCBCR_444_8),
+            Self::_444_YP_CB_CR_10 => Layout::video(YCBCR_444_10),
+            Self::_4444_YP_CB_CR_A_8 => Layout::video(YCBCRA_8),
+            Self::_4444_YP_CB_CR_A_8_R => Layout::full(AYCBCR_8),
+            Self::_4444_A_YP_CB_CR_8 => Layout::video(AYCBCR_8),
+            Self::_4444_A_YP_CB_CR_16 => Layout::video(AYCBCR_16),
+            Self::_4444_A_YP_CB_CR_FLOAT => Layout::full(AYCBCR_FLOAT),
+            Self::_420_YP_CB_CR_8_PLANAR_FULL_RANGE => Layout::full(PLANAR_420_8).subsampling(_420),
+            Self::_422_YP_CB_CR_4_A_8_BI_PLANAR => {
+                Layout::video(BI_PLANAR_422_8_A_8).subsampling(_422)
+            }
+            Self::_420V => Layout::video(BI_PLANAR_420_8).subsampling(_420),
+            Self::_420F => Layout::full(BI_PLANAR_420_8).subsampling(_420),
+            Self::_420_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE => {
+                Layout::video(BI_PLANAR_420_10).subsampling(_420)
+            }
+            Self::_420_YP_CB_CR_10_BI_PLANAR_FULL_RANGE => {
+                Layout::full(BI_PLANAR_420_10).subsampling(_420)
+            }
+            Self::_422_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE => {
+                Layout::video(BI_PLANAR_422_10).subsampling(_422)
+            }
+            Self::_422_YP_CB_CR_10_BI_PLANAR_FULL_RANGE => {
+                Layout::full(BI_PLANAR_422_10).subsampling(_422)
+            }
+            Self::_444_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE => Layout::video(BI_PLANAR_444_10),
+            Self::_444_YP_CB_CR_10_BI_PLANAR_FULL_RANGE => Layout::full(BI_PLANAR_444_10),
+            _ => return None,
+        };
+        Some(layout.compression(self.compression()))
+    }
+
+    /// Uncompressed equivalent of a lossless or lossy compressed format, `self` otherwise.
+    pub const fn uncompressed(&self) -> Self {
+        match *self {
+            Self::LOSSLESS_32_BGRA | Self::LOSSY_32_BGRA => Self::_32_BGRA,
+            Self::LOSSLESS_64_RGBA_HALF => Self::_64_RGBA_HALF,
+            Self::LOSSLESS_420V | Self::LOSSY_420V => Self::_420V,
+            Self::LOSSLESS_420F | Self::LOSSY_420F => Self::_420F,
+            Self::LOSSLESS_420_YP_CB_CR_10_PACKED_BI_PLANAR_VIDEO_RANGE
+            | Self::LOSSY_420_YP_CB_CR_10_PACKED_BI_PLANAR_VIDEO_RANGE => {
+                Self::_420_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE
+            }
+            Self::LOSSLESS_422_YP_CB_CR_10_PACKED_BI_PLANAR_VIDEO_RANGE
+            | Self::LOSSY_422_YP_CB_CR_10_PACKED_BI_PLANAR_VIDEO_RANGE => {
+                Self::_422_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE
+            }
+            other => other,
+        }
+    }
+
+    pub const fn compression(&self) -> Compression {
+        match self.0.to_be_bytes()[0] {
+            b'&' => Compression::Lossless,
+            b'-' => Compression::Lossy,
+            _ => Compression::None,
+        }
+    }
+}
+
+#[cfg(feature = "io_surface")]
+impl From<Component> for io::SurfComponentName {
+    fn from(value: Component) -> Self {
+        match value {
+            Component::Alpha => Self::Alpha,
+            Component::Red => Self::Red,
+            Component::Green => Self::Green,
+            Component::Blue => Self::Blue,
+            Component::Luma => Self::Luma,
+            Component::ChromaBlue => Self::ChromeBlue,
+            Component::ChromaRed => Self::ChromaRed,
+        }
+    }
+}
+
+#[cfg(feature = "io_surface")]
+impl From<Range> for io::SurfComponentRange {
+    fn from(value: Range) -> Self {
+        match value {
+            Range::Full => Self::Full,
+            Range::Video => Self::Video,
+        }
+    }
+}
+
+#[cfg(feature = "io_surface")]
+impl From<Subsampling> for io::SurfSubsampling {
+    fn from(value: Subsampling) -> Self {
+        match value {
+            Subsampling::None => Self::None,
+            Subsampling::_422 => Self::_422,
+            Subsampling::_420 => Self::_420,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cv::{PixelFormat, PixelFormatCompression, PixelFormatRange};
+
+    #[test]
+    fn planes() {
+        let bgra = PixelFormat::_32_BGRA.layout().unwrap();
+        assert!(!bgra.is_planar());
+        assert_eq!(bgra.planes[0].bytes_per_row(1919, 1), 1919 * 4);
+        assert_eq!(bgra.planes[0].bytes_per_row(1919, 64), 7680);
+        assert_eq!(bgra.bytes(1920, 1080, 16), 1920 * 1080 * 4);
+        assert_eq!(bgra.range, PixelFormatRange::Full);
+
+        let yuv = PixelFormat::_420F.layout().unwrap();
+        assert!(yuv.is_planar());
+        assert_eq!(yuv.range, PixelFormatRange::Full);
+        let [luma, chroma] = yuv.planes else { panic!() };
+        assert_eq!((luma.width(1279), luma.height(719)), (1279, 719));
+        assert_eq!((chroma.width(1279), chroma.height(719)), (640, 360));
+        assert_eq!(chroma.bytes(1279, 719, 1), 1280 * 360);
+        assert_eq!(yuv.bytes(1279, 719, 64), 1280 * 719 + 1280 * 360);
+
+        let planar = PixelFormat::_420_YP_CB_CR_8_PLANAR_FULL_RANGE
+            .layout()
+            .unwrap();
+        assert_eq!(planar.planes.len(), 3);
+        assert_eq!(planar.bytes(4, 4, 1), 16 + 4 + 4);
+
+        let x422 = PixelFormat::_422_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE
+            .layout()
+            .unwrap();
+        assert_eq!(x422.planes[1].height(1080), 1080);
+        assert_eq!(x422.planes[1].bytes_per_row(1920, 1), 960 * 4);
+        assert_eq!(x422.planes[0].bits_per_component, 10);
+
+        let x444 = PixelFormat::_444_YP_CB_CR_10_BI_PLANAR_FULL_RANGE
+            .layout()
+            .unwrap();
+        assert_eq!(x444.bytes(2, 2, 1), 2 * 2 * 2 + 2 * 2 * 4);
+
+        // packed 4:2:2 stores two pixels per block
+        let uyvy = PixelFormat::_2VUY.layout().unwrap();
+        assert_eq!(uyvy.planes[0].bytes_per_row(1921, 1), 961 * 4);
+        let v210 = PixelFormat::_422_YP_CB_CR_10.layout().unwrap();
+        assert_eq!(v210.planes[0].bytes_per_row(1920, 1), 5120);
+        assert_eq!(v210.planes[0].bytes_per_row(1280, 1), 27 * 128);
+
+        let alpha = PixelFormat::_422_YP_CB_CR_4_A_8_BI_PLANAR.layout().unwrap();
+        assert_eq!(alpha.bytes(4, 2, 1), 8 * 2 + 4 * 2);
+
+        assert!(PixelFormat::_8_INDEXED.layout().is_none());
+        assert!(PixelFormat(0).layout().is_none());
+    }
+
+    #[test]
+    fn compressed() {
+        let formats = [
+            (PixelFormat::LOSSLESS_32_BGRA, PixelFormat::_32_BGRA),
+            (PixelFormat::LOSSY_32_BGRA, PixelFormat::_32_BGRA),
+            (
+                PixelFormat::LOSSLESS_64_RGBA_HALF,
+                PixelFormat::_64_RGBA_HALF,
+            ),
+            (PixelFormat::LOSSLESS_420V, PixelFormat::_420V),
+            (PixelFormat::LOSSY_420F, PixelFormat::_420F),
+            (
+                PixelFormat::LOSSY_422_YP_CB_CR_10_PACKED_BI_PLANAR_VIDEO_RANGE,
+                PixelFormat::_422_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE,
+            ),
+        ];
+        for (compressed, base) in formats {
+            assert_eq!(compressed.uncompressed(), base);
+            let layout = compressed.layout().unwrap();
+            assert!(layout.is_compressed());
+            assert_eq!(layout.planes, base.layout().unwrap().planes);
+        }
+        assert_eq!(
+            PixelFormat::LOSSLESS_420V.compression(),
+            PixelFormatCompression::Lossless
+        );
+        assert_eq!(
+            PixelFormat::LOSSY_420V.compression(),
+            PixelFormatCompression::Lossy
+        );
+        assert_eq!(PixelFormat::_420V.uncompressed(), PixelFormat::_420V);
+        assert!(!PixelFormat::_420V.layout().unwrap().is_compressed());
+    }
+
+    #[cfg(feature = "mtl")]
+    #[test]
+    fn metal() {
+        use crate::mtl;
+
+        let planes = |f: PixelFormat| {
+            f.layout()
+                .unwrap()
+                .planes
+                .iter()
+                .map(|p| p.mtl_pixel_format())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            planes(PixelFormat::_32_BGRA),
+            [Some(mtl::PixelFormat::Bgra8UNorm)]
+        );
+        assert_eq!(
+            planes(PixelFormat::_420V),
+            [
+                Some(mtl::PixelFormat::R8UNorm),
+                Some(mtl::PixelFormat::Rg8UNorm)
+            ]
+        );
+        assert_eq!(
+            planes(PixelFormat::_420_YP_CB_CR_10_BI_PLANAR_FULL_RANGE),
+            [
+                Some(mtl::PixelFormat::R16UNorm),
+                Some(mtl::PixelFormat::Rg16UNorm)
+            ]
+        );
+        assert_eq!(
+            planes(PixelFormat::_2VUY),
+            [Some(mtl::PixelFormat::Bgrg422)]
+        );
+        assert_eq!(planes(PixelFormat::_422_YP_CB_CR_10), [None]);
+
+        // the table agrees with the Metal format sizes
+        for f in [
+            PixelFormat::_32_RGBA,
+            PixelFormat::_64_RGBA_HALF,
+            PixelFormat::ONE_COMPONENT_32_FLOAT,
+            PixelFormat::ARGB_2101010_LE_PACKED,
+            PixelFormat::_420V,
+            PixelFormat::_444_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE,
+        ] {
+            for plane in f.layout().unwrap().planes {
+                let mtl = plane.mtl_pixel_format().unwrap();
+                assert_eq!(
+                    plane.bytes_per_row(99, 1),
+                    mtl.bytes_per_row(plane.width(99)),
+                    "{f:?}"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "io_surface")]
+    #[test]
+    fn io_surface() {
+        use crate::io;
+
+        let layout = PixelFormat::_420V.layout().unwrap();
+        let names: Vec<io::SurfComponentName> = layout.planes[1]
+            .components
+            .iter()
+            .map(|c| (*c).into())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                io::SurfComponentName::ChromeBlue,
+                io::SurfComponentName::ChromaRed
+            ]
+        );
+        assert_eq!(
+            io::SurfComponentRange::from(layout.range),
+            io::SurfComponentRange::Video
+        );
+        assert_eq!(
+            io::SurfSubsampling::from(layout.subsampling),
+            io::SurfSubsampling::_420
+        );
+    }
+
+    #[cfg(target_vendor = "apple")]
+    #[test]
+    fn parity() {
+        let formats = [
+            PixelFormat::_32_BGRA,
+            PixelFormat::_32_RGBA,
+            PixelFormat::_64_RGBA_HALF,
+            PixelFormat::ONE_COMPONENT_8,
+            PixelFormat::TWO_COMPONENT_32_FLOAT,
+            PixelFormat::ARGB_2101010_LE_PACKED,
+            PixelFormat::_2VUY,
+            PixelFormat::_422_YP_CB_CR_10,
+            PixelFormat::_420V,
+            PixelFormat::_420F,
+            PixelFormat::_420_YP_CB_CR_8_PLANAR_FULL_RANGE,
+            PixelFormat::_420_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE,
+            PixelFormat::_422_YP_CB_CR_10_BI_PLANAR_FULL_RANGE,
+            PixelFormat::_444_YP_CB_CR_10_BI_PLANAR_VIDEO_RANGE,
+        ];
+        let (width, height) = (1281, 721);
+        for f in formats {
+            let layout = f.layout().unwrap();
+            let buf = crate::cv::PixelBuf::new(width, height, f, None).unwrap();
+            if !layout.is_planar() {
+                assert_eq!(buf.plane_count(), 0, "{f:?}");
+                continue;
+            }
+            assert_eq!(buf.plane_count(), layout.planes.len(), "{f:?}");
+            for (i, plane) in layout.planes.iter().enumerate() {
+                assert_eq!(buf.plane_width(i), plane.width(width), "{f:?} {i}");
+                assert_eq!(buf.plane_height(i), plane.height(height), "{f:?} {i}");
+                assert!(buf.plane_bytes_per_row(i) >= plane.bytes_per_row(width, 1));
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! HTTP Live Streaming playlists (RFC 8216 and its second edition draft).
+//!
+//! Builds media playlists from segments reported by `av::AssetWriter`
+//! and master playlists describing variant streams. Output is plain text,
+//! so playlists can be checked against fixtures without AVFoundation.
+//!
+//! ```
+//! use cidre::hls;
+//!
+//! let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Vod);
+//! playlist.set_map(Some(hls::Map::new("init.mp4")));
+//! playlist.push(hls::Segment::new("s0.m4s", 6.0));
+//! playlist.push(hls::Segment::new("s1.m4s", 4.5));
+//!
+//! assert_eq!(
+//!     playlist.to_string(),
+//!     "#EXTM3U
+//! #EXT-X-VERSION:6
+//! #EXT-X-TARGETDURATION:6
+//! #EXT-X-MEDIA-SEQUENCE:0
+//! #EXT-X-PLAYLIST-TYPE:VOD
+//! #EXT-X-MAP:URI=\"init.mp4\"
+//! #EXTINF:6,
+//! s0.m4s
+//! #EXTINF:4.5,
+//! s1.m4s
+//! #EXT-X-ENDLIST
+//! "
+//! );
+//! ```
+
+mod media;
+pub use media::HintType;
+pub use media::Map;
+pub use media::MediaPlaylist;
+pub use media::PartialSegment;
+pub use media::PlaylistKind;
+pub use media::PreloadHint;
+pub use media::Segment;
+
+mod master;
+pub use master::MasterPlaylist;
+pub use master::Rendition;
+pub use master::RenditionType;
+pub use master::Variant;
+
+#[cfg(all(feature = "cm", feature = "nal"))]
+pub use master::codec_string;
+
+/// Byte range of resource, `offset` defaults to the end of previous range of the same resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub len: u64,
+    pub offset: Option<u64>,
+}
+
+impl ByteRange {
+    #[inline]
+    pub const fn new(len: u64, offset: u64) -> Self {
+        Self {
+            len,
+            offset: Some(offset),
+        }
+    }
+}
+
+impl std::fmt::Display for ByteRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.offset {
+            Some(offset) => write!(f, "{}@{offset}", self.len),
+            None => write!(f, "{}", self.len),
+        }
+    }
+}
+
+/// Decimal with at most 5 fractional digits and no trailing zeros.
+pub(crate) struct Decimal(pub f64);
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = format!("{:.5}", self.0);
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(s)
+    }
+}
+
+/// URI with characters quoted strings and lines can't hold percent-encoded.
+pub(crate) struct Uri<'a>(pub &'a str);
+
+impl std::fmt::Display for Uri<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rest = self.0;
+        while let Some(i) = rest.find(['"', '\r', '\n']) {
+            f.write_str(&rest[..i])?;
+            write!(f, "%{:02X}", rest.as_bytes()[i])?;
+            rest = &rest[i + 1..];
+        }
+        f.write_str(rest)
+    }
+}
+
+/// Codec string of audio format for `CODECS` attribute (RFC 6381).
+pub fn audio_codec_string(format_id: crate::FourCc) -> Option<&'static str> {
+    Some(match &format_id.to_bytes() {
+        b"aac " => "mp4a.40.2",
+        b"aach" => "mp4a.40.5",
+        b"aacp" => "mp4a.40.29",
+        b".mp3" => "mp4a.40.34",
+        b"ac-3" => "ac-3",
+        b"ec-3" => "ec-3",
+        b"alac" => "alac",
+        b"flac" => "fLaC",
+        b"opus" => "Opus",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FourCc, hls};
+
+    #[test]
+    fn basics() {
+        assert_eq!(hls::ByteRange::new(100, 20).to_string(), "100@20");
+        let range = hls::ByteRange {
+            len: 7,
+            offset: None,
+        };
+        assert_eq!(range.to_string(), "7");
+        for (v, s) in [
+            (6.0, "6"),
+            (6.006, "6.006"),
+            (0.33334, "0.33334"),
+            (2.000004, "2"),
+        ] {
+            assert_eq!(hls::Decimal(v).to_string(), s);
+        }
+        assert_eq!(hls::Uri("a b.m4s").to_string(), "a b.m4s");
+        assert_eq!(hls::Uri("x\"y\r\nz.m4s").to_string(), "x%22y%0D%0Az.m4s");
+        assert_eq!(
+            hls::audio_codec_string(FourCc::from_bytes(b"aac ")),
+            Some("mp4a.40.2")
+        );
+        assert_eq!(hls::audio_codec_string(FourCc::from_bytes(b"lpcm")), None);
+    }
+}
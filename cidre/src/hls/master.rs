@@ -0,0 +1,290 @@
+use std::fmt::{self, Write};
+
+use crate::hls::{MediaPlaylist, Uri};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenditionType {
+    Audio,
+    Video,
+    Subtitles,
+    ClosedCaptions,
+}
+
+impl RenditionType {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Audio => "AUDIO",
+            Self::Video => "VIDEO",
+            Self::Subtitles => "SUBTITLES",
+            Self::ClosedCaptions => "CLOSED-CAPTIONS",
+        }
+    }
+}
+
+/// Alternative rendition (`EXT-X-MEDIA`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendition {
+    pub typ: RenditionType,
+    pub group_id: String,
+    pub name: String,
+
+    /// RFC 5646 language tag
+    pub language: Option<String>,
+    pub default: bool,
+    pub autoselect: bool,
+
+    /// Audio channel count, like `2` or `16/JOC`
+    pub channels: Option<String>,
+
+    /// Media playlist, none if rendition is muxed into variant stream
+    pub uri: Option<String>,
+}
+
+impl Rendition {
+    pub fn new(typ: RenditionType, group_id: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            typ,
+            group_id: group_id.into(),
+            name: name.into(),
+            language: None,
+            default: false,
+            autoselect: false,
+            channels: None,
+            uri: None,
+        }
+    }
+}
+
+/// Variant stream (`EXT-X-STREAM-INF`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub uri: String,
+
+    /// Peak bits per second
+    pub bandwidth: u64,
+    pub average_bandwidth: Option<u64>,
+
+    /// RFC 6381 codec strings of all media in stream including renditions
+    pub codecs: Vec<String>,
+    pub resolution: Option<(u32, u32)>,
+    pub frame_rate: Option<f64>,
+
+    /// Group ids of renditions
+    pub audio: Option<String>,
+    pub video: Option<String>,
+    pub subtitles: Option<String>,
+}
+
+impl Variant {
+    pub fn new(uri: impl Into<String>, bandwidth: u64) -> Self {
+        Self {
+            uri: uri.into(),
+            bandwidth,
+            average_bandwidth: None,
+            codecs: Vec::new(),
+            resolution: None,
+            frame_rate: None,
+            audio: None,
+            video: None,
+            subtitles: None,
+        }
+    }
+
+    /// Sets `BANDWIDTH` and `AVERAGE-BANDWIDTH` from segment sizes and durations.
+    ///
+    /// Returns `false` and keeps bandwidth if size of any segment is unknown.
+    pub fn set_bandwidth(&mut self, playlist: &MediaPlaylist) -> bool {
+        let Some(peak) = playlist.peak_bandwidth() else {
+            return false;
+        };
+        self.bandwidth = peak;
+        self.average_bandwidth = playlist.average_bandwidth();
+        true
+    }
+
+    /// Adds codec string and, for video, resolution of format description.
+    ///
+    /// Returns `false` if codec string is unknown.
+    #[cfg(all(feature = "cm", feature = "nal"))]
+    pub fn add_format_desc(&mut self, desc: &crate::cm::FormatDesc) -> bool {
+        let Some(codec) = codec_string(desc) else {
+            return false;
+        };
+        if desc.media_type() == crate::cm::MediaType::VIDEO {
+            let dims = desc.dimensions();
+            self.resolution = Some((dims.width as u32, dims.height as u32));
+        }
+        if !self.codecs.contains(&codec) {
+            self.codecs.push(codec);
+        }
+        true
+    }
+}
+
+/// Codec string for `CODECS` attribute from `avcC`/`hvcC` of video or format id of audio.
+#[cfg(all(feature = "cm", feature = "nal"))]
+pub fn codec_string(desc: &crate::cm::FormatDesc) -> Option<String> {
+    use crate::{FourCc, cm};
+
+    let sub_type = FourCc::new(desc.media_sub_type());
+    match desc.media_type() {
+        cm::MediaType::VIDEO => {
+            if let Some(Ok(config)) = desc.avc_config() {
+                return Some(config.codec_string());
+            }
+            if let Some(Ok(config)) = desc.hevc_config() {
+                let entry = match &sub_type.to_bytes() {
+                    b"hev1" => "hev1",
+                    _ => "hvc1",
+                };
+                return Some(config.ptl.codec_string(entry));
+            }
+            None
+        }
+        cm::MediaType::AUDIO => super::audio_codec_string(sub_type).map(String::from),
+        _ => None,
+    }
+}
+
+/// Master (multivariant) playlist
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MasterPlaylist {
+    pub version: Option<u8>,
+    pub independent_segments: bool,
+    pub renditions: Vec<Rendition>,
+    pub variants: Vec<Variant>,
+}
+
+fn yes_no(val: bool) -> &'static str {
+    if val { "YES" } else { "NO" }
+}
+
+impl fmt::Display for MasterPlaylist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "#EXTM3U")?;
+        if let Some(version) = self.version {
+            writeln!(f, "#EXT-X-VERSION:{version}")?;
+        }
+        if self.independent_segments {
+            writeln!(f, "#EXT-X-INDEPENDENT-SEGMENTS")?;
+        }
+        for r in &self.renditions {
+            write!(
+                f,
+                "#EXT-X-MEDIA:TYPE={},GROUP-ID=\"{}\",NAME=\"{}\"",
+                r.typ.as_str(),
+                r.group_id,
+                r.name
+            )?;
+            if let Some(language) = &r.language {
+                write!(f, ",LANGUAGE=\"{language}\"")?;
+            }
+            write!(
+                f,
+                ",DEFAULT={},AUTOSELECT={}",
+                yes_no(r.default),
+                yes_no(r.autoselect)
+            )?;
+            if let Some(channels) = &r.channels {
+                write!(f, ",CHANNELS=\"{channels}\"")?;
+            }
+            if let Some(uri) = &r.uri {
+                write!(f, ",URI=\"{}\"", Uri(uri))?;
+            }
+            f.write_char('\n')?;
+        }
+        for v in &self.variants {
+            write!(f, "#EXT-X-STREAM-INF:BANDWIDTH={}", v.bandwidth)?;
+            if let Some(avg) = v.average_bandwidth {
+                write!(f, ",AVERAGE-BANDWIDTH={avg}")?;
+            }
+            if !v.codecs.is_empty() {
+                write!(f, ",CODECS=\"{}\"", v.codecs.join(","))?;
+            }
+            if let Some((w, h)) = v.resolution {
+                write!(f, ",RESOLUTION={w}x{h}")?;
+            }
+            if let Some(rate) = v.frame_rate {
+                write!(f, ",FRAME-RATE={rate:.3}")?;
+            }
+            for (name, group) in [
+                ("AUDIO", &v.audio),
+                ("VIDEO", &v.video),
+                ("SUBTITLES", &v.subtitles),
+            ] {
+                if let Some(group) = group {
+                    write!(f, ",{name}=\"{group}\"")?;
+                }
+            }
+            writeln!(f, "\n{}", Uri(&v.uri))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hls;
+
+    #[test]
+    fn master() {
+        let mut playlist = hls::MasterPlaylist {
+            version: Some(6),
+            independent_segments: true,
+            ..Default::default()
+        };
+        playlist.renditions.push(hls::Rendition {
+            language: Some("en".into()),
+            default: true,
+            autoselect: true,
+            channels: Some("2".into()),
+            uri: Some("audio/en.m3u8".into()),
+            ..hls::Rendition::new(hls::RenditionType::Audio, "aac", "English")
+        });
+        for (uri, bandwidth, codec, res) in [
+            ("720p.m3u8", 3_000_000, "avc1.64001f", (1280, 720)),
+            ("1080p.m3u8", 6_000_000, "hvc1.2.4.L123.B0", (1920, 1080)),
+        ] {
+            playlist.variants.push(hls::Variant {
+                average_bandwidth: Some(bandwidth * 3 / 4),
+                codecs: vec![codec.into(), "mp4a.40.2".into()],
+                resolution: Some(res),
+                frame_rate: Some(29.97),
+                audio: Some("aac".into()),
+                ..hls::Variant::new(uri, bandwidth)
+            });
+        }
+        assert_eq!(
+            playlist.to_string(),
+            "#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"aac\",NAME=\"English\",LANGUAGE=\"en\",DEFAULT=YES,AUTOSELECT=YES,CHANNELS=\"2\",URI=\"audio/en.m3u8\"
+#EXT-X-STREAM-INF:BANDWIDTH=3000000,AVERAGE-BANDWIDTH=2250000,CODECS=\"avc1.64001f,mp4a.40.2\",RESOLUTION=1280x720,FRAME-RATE=29.970,AUDIO=\"aac\"
+720p.m3u8
+#EXT-X-STREAM-INF:BANDWIDTH=6000000,AVERAGE-BANDWIDTH=4500000,CODECS=\"hvc1.2.4.L123.B0,mp4a.40.2\",RESOLUTION=1920x1080,FRAME-RATE=29.970,AUDIO=\"aac\"
+1080p.m3u8
+"
+        );
+    }
+
+    #[cfg(all(feature = "cm", feature = "nal"))]
+    #[test]
+    fn format_desc() {
+        use crate::{cm, nal};
+
+        let sps = [
+            0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40, 0x50, 0x05, 0xbb, 0x01, 0x10, 0x00, 0x00,
+            0x03, 0x00, 0x10, 0x00, 0x00, 0x03, 0x03, 0xc0, 0xf1, 0x83, 0x19, 0x60,
+        ];
+        let pps = [0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+        let config = nal::AvcConfig::with_param_sets(&[&sps], &[&pps], 4).unwrap();
+        let desc = cm::VideoFormatDesc::with_avc_config(&config).unwrap();
+        assert_eq!(hls::codec_string(&desc).as_deref(), Some("avc1.64001f"));
+
+        let mut variant = hls::Variant::new("720p.m3u8", 3_000_000);
+        assert!(variant.add_format_desc(&desc));
+        assert_eq!(variant.codecs, ["avc1.64001f"]);
+        assert_eq!(variant.resolution, Some((1280, 720)));
+    }
+}
@@ -0,0 +1,616 @@
+use std::fmt::{self, Write};
+
+use crate::hls::{ByteRange, Decimal, Uri};
+
+/// How media playlist changes over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistKind {
+    /// Complete playlist, never changes
+    Vod,
+
+    /// Segments are only appended
+    Event,
+
+    /// Sliding window live playlist keeping at most `window` segments
+    Live { window: usize },
+}
+
+/// Media initialization section (`EXT-X-MAP`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub uri: String,
+    pub byte_range: Option<ByteRange>,
+}
+
+impl Map {
+    pub fn new(uri: impl Into<String>) -> Self {
+        Self {
+            uri: uri.into(),
+            byte_range: None,
+        }
+    }
+}
+
+/// Partial segment of low-latency playlist (`EXT-X-PART`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialSegment {
+    pub uri: String,
+
+    /// Duration in seconds
+    pub duration: f64,
+
+    /// Partial segment starts with independent frame
+    pub independent: bool,
+    pub byte_range: Option<ByteRange>,
+}
+
+impl PartialSegment {
+    pub fn new(uri: impl Into<String>, duration: f64, independent: bool) -> Self {
+        Self {
+            uri: uri.into(),
+            duration,
+            independent,
+            byte_range: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintType {
+    Part,
+    Map,
+}
+
+/// Resource the server will produce next (`EXT-X-PRELOAD-HINT`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreloadHint {
+    pub typ: HintType,
+    pub uri: String,
+    pub byte_range_start: Option<u64>,
+    pub byte_range_len: Option<u64>,
+}
+
+impl PreloadHint {
+    pub fn part(uri: impl Into<String>) -> Self {
+        Self {
+            typ: HintType::Part,
+            uri: uri.into(),
+            byte_range_start: None,
+            byte_range_len: None,
+        }
+    }
+}
+
+/// Media segment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub uri: String,
+
+    /// Duration in seconds
+    pub duration: f64,
+    pub byte_range: Option<ByteRange>,
+
+    /// Size in bytes for bandwidth, byte range length is used if none
+    pub size: Option<u64>,
+
+    /// Segment follows discontinuity in encoding parameters or timeline
+    pub discontinuity: bool,
+
+    /// Partial segments this segment consists of, set by [`MediaPlaylist::push`]
+    pub parts: Vec<PartialSegment>,
+
+    /// Initialization section in effect, set by [`MediaPlaylist::push`]
+    pub map: Option<Map>,
+}
+
+impl Segment {
+    pub fn new(uri: impl Into<String>, duration: f64) -> Self {
+        Self {
+            uri: uri.into(),
+            duration,
+            byte_range: None,
+            size: None,
+            discontinuity: false,
+            parts: Vec::new(),
+            map: None,
+        }
+    }
+
+    /// Segment described by `av::AssetWriter` delegate segment report.
+    ///
+    /// Duration is the longest track duration of the report.
+    #[cfg(all(feature = "av", feature = "cm"))]
+    pub fn with_report(uri: impl Into<String>, report: &crate::av::AssetSegmentReport) -> Self {
+        let duration = report
+            .track_reports()
+            .iter()
+            .map(|r| r.duration())
+            .filter(|d| d.is_numeric())
+            .map(|d| d.as_secs())
+            .fold(0.0, f64::max);
+        Self::new(uri, duration)
+    }
+
+    #[inline]
+    pub fn size(&self) -> Option<u64> {
+        self.size.or(self.byte_range.map(|r| r.len))
+    }
+}
+
+/// Media playlist builder
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaPlaylist {
+    pub kind: PlaylistKind,
+
+    /// Minimal target duration, the longest segment may increase it
+    pub target_duration: u32,
+
+    /// Partial segment target duration, enables low-latency mode
+    pub part_target: Option<f64>,
+    pub independent_segments: bool,
+    pub media_sequence: u64,
+    pub discontinuity_sequence: u64,
+    pub segments: std::collections::VecDeque<Segment>,
+
+    /// Partial segments of segment in progress
+    pub parts: Vec<PartialSegment>,
+    pub preload_hint: Option<PreloadHint>,
+    pub ended: bool,
+    map: Option<Map>,
+    max_duration: u32,
+}
+
+impl MediaPlaylist {
+    pub fn new(kind: PlaylistKind) -> Self {
+        Self {
+            kind,
+            target_duration: 1,
+            part_target: None,
+            independent_segments: false,
+            media_sequence: 0,
+            discontinuity_sequence: 0,
+            segments: Default::default(),
+            parts: Vec::new(),
+            preload_hint: None,
+            ended: kind == PlaylistKind::Vod,
+            map: None,
+            max_duration: 0,
+        }
+    }
+
+    /// Sets initialization section for segments pushed after this call.
+    pub fn set_map(&mut self, map: Option<Map>) {
+        self.map = map;
+    }
+
+    #[inline]
+    pub fn map(&self) -> Option<&Map> {
+        self.map.as_ref()
+    }
+
+    /// Target duration rounded the way `EXTINF` durations are.
+    pub fn effective_target_duration(&self) -> u32 {
+        self.target_duration.max(self.max_duration)
+    }
+
+    /// Appends complete segment taking pending partial segments and current map.
+    ///
+    /// Sliding window playlist drops the oldest segments advancing sequence numbers.
+    pub fn push(&mut self, mut segment: Segment) {
+        segment.parts = std::mem::take(&mut self.parts);
+        segment.map = self.map.clone();
+        self.max_duration = self.max_duration.max(segment.duration.round() as u32);
+        self.segments.push_back(segment);
+        if let PlaylistKind::Live { window } = self.kind {
+            while self.segments.len() > window.max(1) {
+                let removed = self.segments.pop_front().unwrap();
+                self.media_sequence += 1;
+                if removed.discontinuity {
+                    self.discontinuity_sequence += 1;
+                }
+            }
+        }
+    }
+
+    /// Appends partial segment of segment in progress.
+    ///
+    /// Clears preload hint as it usually points to this part.
+    pub fn push_part(&mut self, part: PartialSegment) {
+        self.preload_hint = None;
+        self.parts.push(part);
+    }
+
+    /// Marks playlist as complete (`EXT-X-ENDLIST`).
+    pub fn finish(&mut self) {
+        self.ended = true;
+        self.parts.clear();
+        self.preload_hint = None;
+    }
+
+    /// Total duration of segments in playlist.
+    pub fn duration(&self) -> f64 {
+        self.segments.iter().map(|s| s.duration).sum()
+    }
+
+    /// Peak segment bit rate for `BANDWIDTH`.
+    ///
+    /// None if playlist has no segments or size of any segment is unknown.
+    pub fn peak_bandwidth(&self) -> Option<u64> {
+        let mut peak = None;
+        for segment in &self.segments {
+            let bits = segment.size()? as f64 * 8.0;
+            if segment.duration > 0.0 {
+                let rate = (bits / segment.duration).ceil() as u64;
+                peak = Some(peak.map_or(rate, |p: u64| p.max(rate)));
+            }
+        }
+        peak
+    }
+
+    /// Average bit rate of all segments for `AVERAGE-BANDWIDTH`.
+    ///
+    /// None if playlist has no segments or size of any segment is unknown.
+    pub fn average_bandwidth(&self) -> Option<u64> {
+        let mut size = 0u64;
+        for segment in &self.segments {
+            size = size.saturating_add(segment.size()?);
+        }
+        let duration = self.duration();
+        if duration > 0.0 {
+            Some((size as f64 * 8.0 / duration).ceil() as u64)
+        } else {
+            None
+        }
+    }
+
+    fn version(&self) -> u8 {
+        let has_map = self.segments.iter().any(|s| s.map.is_some())
+            || (!self.ended && !self.parts.is_empty() && self.map.is_some());
+        let has_ranges = self.segments.iter().any(|s| s.byte_range.is_some());
+        if has_map {
+            6
+        } else if has_ranges {
+            4
+        } else {
+            3
+        }
+    }
+
+    /// Index of the first segment which partial segments are listed.
+    ///
+    /// Parts older than three target durations from the live edge are omitted.
+    fn first_with_parts(&self) -> usize {
+        let limit = 3.0 * self.effective_target_duration() as f64;
+        let mut total: f64 = self.parts.iter().map(|p| p.duration).sum();
+        let mut first = self.segments.len();
+        for segment in self.segments.iter().rev() {
+            total += segment.duration;
+            if total > limit {
+                break;
+            }
+            first -= 1;
+        }
+        first
+    }
+}
+
+fn write_map(f: &mut fmt::Formatter<'_>, map: &Map) -> fmt::Result {
+    write!(f, "#EXT-X-MAP:URI=\"{}\"", Uri(&map.uri))?;
+    if let Some(range) = map.byte_range {
+        write!(f, ",BYTERANGE=\"{range}\"")?;
+    }
+    f.write_char('\n')
+}
+
+fn write_part(f: &mut fmt::Formatter<'_>, part: &PartialSegment) -> fmt::Result {
+    write!(
+        f,
+        "#EXT-X-PART:DURATION={},URI=\"{}\"",
+        Decimal(part.duration),
+        Uri(&part.uri)
+    )?;
+    if part.independent {
+        f.write_str(",INDEPENDENT=YES")?;
+    }
+    if let Some(range) = part.byte_range {
+        write!(f, ",BYTERANGE=\"{range}\"")?;
+    }
+    f.write_char('\n')
+}
+
+impl fmt::Display for MediaPlaylist {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "#EXTM3U")?;
+        writeln!(f, "#EXT-X-VERSION:{}", self.version())?;
+        writeln!(
+            f,
+            "#EXT-X-TARGETDURATION:{}",
+            self.effective_target_duration()
+        )?;
+        if let Some(part_target) = self.part_target {
+            writeln!(
+                f,
+                "#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK={}",
+                Decimal(part_target * 3.0)
+            )?;
+            writeln!(f, "#EXT-X-PART-INF:PART-TARGET={}", Decimal(part_target))?;
+        }
+        writeln!(f, "#EXT-X-MEDIA-SEQUENCE:{}", self.media_sequence)?;
+        if self.discontinuity_sequence > 0 {
+            writeln!(
+                f,
+                "#EXT-X-DISCONTINUITY-SEQUENCE:{}",
+                self.discontinuity_sequence
+            )?;
+        }
+        match self.kind {
+            PlaylistKind::Vod => writeln!(f, "#EXT-X-PLAYLIST-TYPE:VOD")?,
+            PlaylistKind::Event => writeln!(f, "#EXT-X-PLAYLIST-TYPE:EVENT")?,
+            PlaylistKind::Live { .. } => {}
+        }
+        if self.independent_segments {
+            writeln!(f, "#EXT-X-INDEPENDENT-SEGMENTS")?;
+        }
+
+        let with_parts = if self.part_target.is_some() && !self.ended {
+            self.first_with_parts()
+        } else {
+            self.segments.len()
+        };
+        let mut map = None;
+        for (i, segment) in self.segments.iter().enumerate() {
+            if segment.discontinuity {
+                writeln!(f, "#EXT-X-DISCONTINUITY")?;
+            }
+            if segment.map.as_ref() != map {
+                map = segment.map.as_ref();
+                if let Some(map) = map {
+                    write_map(f, map)?;
+                }
+            }
+            if i >= with_parts {
+                for part in &segment.parts {
+                    write_part(f, part)?;
+                }
+            }
+            writeln!(f, "#EXTINF:{},", Decimal(segment.duration))?;
+            if let Some(range) = segment.byte_range {
+                writeln!(f, "#EXT-X-BYTERANGE:{range}")?;
+            }
+            writeln!(f, "{}", Uri(&segment.uri))?;
+        }
+
+        if !self.ended {
+            // parts of segment in progress use current map
+            if !self.parts.is_empty() && self.map.as_ref() != map {
+                if let Some(map) = &self.map {
+                    write_map(f, map)?;
+                }
+            }
+            for part in &self.parts {
+                write_part(f, part)?;
+            }
+            if let Some(hint) = &self.preload_hint {
+                let typ = match hint.typ {
+                    HintType::Part => "PART",
+                    HintType::Map => "MAP",
+                };
+                write!(
+                    f,
+                    "#EXT-X-PRELOAD-HINT:TYPE={typ},URI=\"{}\"",
+                    Uri(&hint.uri)
+                )?;
+                if let Some(start) = hint.byte_range_start {
+                    write!(f, ",BYTERANGE-START={start}")?;
+                }
+                if let Some(len) = hint.byte_range_len {
+                    write!(f, ",BYTERANGE-LENGTH={len}")?;
+                }
+                f.write_char('\n')?;
+            }
+        }
+        if self.ended {
+            writeln!(f, "#EXT-X-ENDLIST")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hls;
+
+    #[test]
+    fn event() {
+        let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Event);
+        playlist.target_duration = 6;
+        playlist.independent_segments = true;
+        playlist.push(hls::Segment {
+            byte_range: Some(hls::ByteRange::new(1000, 0)),
+            ..hls::Segment::new("main.mp4", 6.006)
+        });
+        playlist.push(hls::Segment {
+            byte_range: Some(hls::ByteRange {
+                len: 800,
+                offset: None,
+            }),
+            ..hls::Segment::new("main.mp4", 6.4)
+        });
+        assert_eq!(
+            playlist.to_string(),
+            "#EXTM3U
+#EXT-X-VERSION:4
+#EXT-X-TARGETDURATION:6
+#EXT-X-MEDIA-SEQUENCE:0
+#EXT-X-PLAYLIST-TYPE:EVENT
+#EXT-X-INDEPENDENT-SEGMENTS
+#EXTINF:6.006,
+#EXT-X-BYTERANGE:1000@0
+main.mp4
+#EXTINF:6.4,
+#EXT-X-BYTERANGE:800
+main.mp4
+"
+        );
+        playlist.push(hls::Segment::new("tail.ts", 6.6));
+        playlist.finish();
+        let text = playlist.to_string();
+        assert!(text.contains("#EXT-X-TARGETDURATION:7\n"));
+        assert!(text.ends_with("#EXTINF:6.6,\ntail.ts\n#EXT-X-ENDLIST\n"));
+    }
+
+    #[test]
+    fn sliding_window() {
+        let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Live { window: 3 });
+        playlist.target_duration = 4;
+        playlist.set_map(Some(hls::Map::new("init0.mp4")));
+        for i in 0..5 {
+            if i == 3 {
+                playlist.set_map(Some(hls::Map::new("init1.mp4")));
+            }
+            playlist.push(hls::Segment {
+                discontinuity: i == 1 || i == 3,
+                ..hls::Segment::new(format!("s{i}.m4s"), 4.0)
+            });
+        }
+        assert_eq!(playlist.media_sequence, 2);
+        assert_eq!(playlist.discontinuity_sequence, 1);
+        assert_eq!(
+            playlist.to_string(),
+            "#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-TARGETDURATION:4
+#EXT-X-MEDIA-SEQUENCE:2
+#EXT-X-DISCONTINUITY-SEQUENCE:1
+#EXT-X-MAP:URI=\"init0.mp4\"
+#EXTINF:4,
+s2.m4s
+#EXT-X-DISCONTINUITY
+#EXT-X-MAP:URI=\"init1.mp4\"
+#EXTINF:4,
+s3.m4s
+#EXTINF:4,
+s4.m4s
+"
+        );
+    }
+
+    #[test]
+    fn low_latency() {
+        let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Live { window: 10 });
+        playlist.target_duration = 2;
+        playlist.part_target = Some(0.5);
+        playlist.set_map(Some(hls::Map::new("init.mp4")));
+        for seg in 0..4 {
+            for part in 0..4 {
+                playlist.push_part(hls::PartialSegment::new(
+                    format!("s{seg}.{part}.m4s"),
+                    0.5,
+                    part == 0,
+                ));
+            }
+            playlist.push(hls::Segment::new(format!("s{seg}.m4s"), 2.0));
+        }
+        playlist.push_part(hls::PartialSegment {
+            byte_range: Some(hls::ByteRange::new(1200, 0)),
+            ..hls::PartialSegment::new("s4.m4s", 0.5, true)
+        });
+        playlist.preload_hint = Some(hls::PreloadHint {
+            byte_range_start: Some(1200),
+            ..hls::PreloadHint::part("s4.m4s")
+        });
+
+        // parts of s0 and s1 are more than 3 target durations from the end
+        let expected = "#EXTM3U
+#EXT-X-VERSION:6
+#EXT-X-TARGETDURATION:2
+#EXT-X-SERVER-CONTROL:CAN-BLOCK-RELOAD=YES,PART-HOLD-BACK=1.5
+#EXT-X-PART-INF:PART-TARGET=0.5
+#EXT-X-MEDIA-SEQUENCE:0
+#EXT-X-MAP:URI=\"init.mp4\"
+#EXTINF:2,
+s0.m4s
+#EXTINF:2,
+s1.m4s
+#EXT-X-PART:DURATION=0.5,URI=\"s2.0.m4s\",INDEPENDENT=YES
+#EXT-X-PART:DURATION=0.5,URI=\"s2.1.m4s\"
+#EXT-X-PART:DURATION=0.5,URI=\"s2.2.m4s\"
+#EXT-X-PART:DURATION=0.5,URI=\"s2.3.m4s\"
+#EXTINF:2,
+s2.m4s
+#EXT-X-PART:DURATION=0.5,URI=\"s3.0.m4s\",INDEPENDENT=YES
+#EXT-X-PART:DURATION=0.5,URI=\"s3.1.m4s\"
+#EXT-X-PART:DURATION=0.5,URI=\"s3.2.m4s\"
+#EXT-X-PART:DURATION=0.5,URI=\"s3.3.m4s\"
+#EXTINF:2,
+s3.m4s
+#EXT-X-PART:DURATION=0.5,URI=\"s4.m4s\",INDEPENDENT=YES,BYTERANGE=\"1200@0\"
+#EXT-X-PRELOAD-HINT:TYPE=PART,URI=\"s4.m4s\",BYTERANGE-START=1200
+";
+        assert_eq!(playlist.to_string(), expected);
+
+        playlist.push(hls::Segment::new("s4.m4s", 0.5));
+        playlist.finish();
+        let text = playlist.to_string();
+        assert!(!text.contains("#EXT-X-PART:"));
+        assert!(!text.contains("#EXT-X-PRELOAD-HINT"));
+        assert!(text.ends_with("s4.m4s\n#EXT-X-ENDLIST\n"));
+    }
+
+    #[test]
+    fn map_before_first_part() {
+        let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Live { window: 4 });
+        playlist.part_target = Some(0.5);
+        playlist.set_map(Some(hls::Map::new("init \"0\".mp4")));
+        playlist.push_part(hls::PartialSegment::new("s0.0.m4s", 0.5, true));
+        let text = playlist.to_string();
+        assert!(text.contains("#EXT-X-VERSION:6\n"));
+        assert!(text.ends_with(
+            "#EXT-X-MEDIA-SEQUENCE:0
+#EXT-X-MAP:URI=\"init %220%22.mp4\"
+#EXT-X-PART:DURATION=0.5,URI=\"s0.0.m4s\",INDEPENDENT=YES
+"
+        ));
+
+        // map isn't repeated once segment is complete
+        playlist.push(hls::Segment::new("s0.m4s", 0.5));
+        playlist.push_part(hls::PartialSegment::new("s1.0.m4s", 0.5, true));
+        assert_eq!(playlist.to_string().matches("#EXT-X-MAP").count(), 1);
+
+        playlist.set_map(Some(hls::Map::new("init1.mp4")));
+        let text = playlist.to_string();
+        assert!(text.ends_with(
+            "s0.m4s
+#EXT-X-MAP:URI=\"init1.mp4\"
+#EXT-X-PART:DURATION=0.5,URI=\"s1.0.m4s\",INDEPENDENT=YES
+"
+        ));
+    }
+
+    #[test]
+    fn bandwidth() {
+        let mut playlist = hls::MediaPlaylist::new(hls::PlaylistKind::Vod);
+        assert_eq!(playlist.peak_bandwidth(), None);
+        assert_eq!(playlist.average_bandwidth(), None);
+        playlist.push(hls::Segment {
+            size: Some(750_000),
+            ..hls::Segment::new("s0.m4s", 6.0)
+        });
+        playlist.push(hls::Segment {
+            byte_range: Some(hls::ByteRange::new(500_000, 0)),
+            ..hls::Segment::new("s1.m4s", 2.0)
+        });
+        assert_eq!(playlist.peak_bandwidth(), Some(2_000_000));
+        assert_eq!(playlist.average_bandwidth(), Some(1_250_000));
+
+        let mut variant = hls::Variant::new("v.m3u8", 0);
+        assert!(variant.set_bandwidth(&playlist));
+        assert_eq!(variant.bandwidth, 2_000_000);
+        assert_eq!(variant.average_bandwidth, Some(1_250_000));
+
+        playlist.push(hls::Segment::new("s2.m4s", 2.0));
+        assert_eq!(playlist.peak_bandwidth(), None);
+        assert!(!variant.set_bandwidth(&playlist));
+        assert_eq!(variant.bandwidth, 2_000_000);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod mac_types;
 
+pub use mac_types::FourCc;
+pub use mac_types::FourCcParseError;
 pub use mac_types::FourCharCode;
 pub use mac_types::ResType;
 pub use mac_types::UniChar;
@@ -41,6 +43,7 @@ pub mod ca;
 pub mod cat;
 
 /// Core Foundation
+#[cfg(target_vendor = "apple")]
 #[cfg(feature = "cf")]
 pub mod cf;
 
@@ -135,6 +138,26 @@ pub mod nl;
 #[cfg(feature = "nw")]
 pub mod nw;
 
+/// Native property list codec
+#[cfg(feature = "plist")]
+pub mod plist;
+
+/// H.264 and HEVC NAL units
+#[cfg(feature = "nal")]
+pub mod nal;
+
+/// ISO base media file format (MP4) boxes
+#[cfg(feature = "mp4")]
+pub mod mp4;
+
+/// HTTP Live Streaming playlists
+#[cfg(feature = "hls")]
+pub mod hls;
+
+/// Native WAVE, CAF and AIFF audio files
+#[cfg(feature = "audio_file")]
+pub mod audio_file;
+
 #[cfg(feature = "ns")]
 pub mod objc;
 
@@ -319,7 +342,7 @@ macro_rules! define_opts {
     };
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "cf", target_vendor = "apple"))]
 mod tests {
     use crate::cf;
 
@@ -23,20 +23,275 @@ pub fn four_cc_fmt_debug(
     name: &str,
     f: &mut std::fmt::Formatter<'_>,
 ) -> std::fmt::Result {
-    let mut fcc = val.to_be_bytes();
     f.debug_struct(name)
         .field("raw", &val)
-        .field("fcc", &four_cc_to_str(&mut fcc))
+        .field("fcc", &format_args!("\"{}\"", FourCc(val)))
         .finish()
 }
 
+/// Four character code.
+///
+/// Text form is lossless: printable ASCII is written as is, `\` is written as `\\`
+/// and every other byte is written as `\xNN`. Parsing accepts the same form
+/// and hex numbers like `0x61766331`.
+///
+/// ```
+/// use cidre::FourCc;
+///
+/// let code: FourCc = "avc1".parse().unwrap();
+/// assert_eq!(code, FourCc::from_bytes(b"avc1"));
+/// assert_eq!(code.to_string(), "avc1");
+///
+/// let err: FourCc = "0xfffffffe".parse().unwrap();
+/// assert_eq!(err.to_string(), "\\xff\\xff\\xff\\xfe");
+/// assert_eq!(err.to_string().parse::<FourCc>().unwrap(), err);
+/// ```
+#[doc(alias = "FourCharCode")]
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(transparent)]
+pub struct FourCc(pub FourCharCode);
+
+impl FourCc {
+    #[inline]
+    pub const fn new(val: FourCharCode) -> Self {
+        Self(val)
+    }
+
+    #[inline]
+    pub const fn from_bytes(bytes: &[u8; 4]) -> Self {
+        Self(FourCharCode::from_be_bytes(*bytes))
+    }
+
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
+
+    #[inline]
+    pub const fn raw(self) -> FourCharCode {
+        self.0
+    }
+
+    /// All four bytes are printable ASCII
+    pub const fn is_printable(self) -> bool {
+        let bytes = self.to_bytes();
+        let mut i = 0;
+        while i < 4 {
+            if !is_printable(bytes[i]) {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+}
+
+#[inline]
+const fn is_printable(b: u8) -> bool {
+    b >= b' ' && b <= b'~'
+}
+
+impl From<FourCharCode> for FourCc {
+    #[inline]
+    fn from(value: FourCharCode) -> Self {
+        Self(value)
+    }
+}
+
+impl From<FourCc> for FourCharCode {
+    #[inline]
+    fn from(value: FourCc) -> Self {
+        value.0
+    }
+}
+
+impl From<[u8; 4]> for FourCc {
+    #[inline]
+    fn from(value: [u8; 4]) -> Self {
+        Self::from_bytes(&value)
+    }
+}
+
+impl From<FourCc> for [u8; 4] {
+    #[inline]
+    fn from(value: FourCc) -> Self {
+        value.to_bytes()
+    }
+}
+
+impl PartialEq<FourCharCode> for FourCc {
+    #[inline]
+    fn eq(&self, other: &FourCharCode) -> bool {
+        self.0 == *other
+    }
+}
+
+impl std::fmt::Display for FourCc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use std::fmt::Write;
+        for b in self.to_bytes() {
+            match b {
+                b'\\' => f.write_str("\\\\")?,
+                b if is_printable(b) => f.write_char(b as char)?,
+                b => write!(f, "\\x{b:02x}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for FourCc {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FourCc(\"{self}\")")
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FourCcParseError {
+    /// Text is not exactly four bytes long after unescaping
+    InvalidLen(usize),
+
+    /// Unknown escape sequence or bad `\xNN` digits
+    InvalidEscape,
+
+    /// `0x` prefixed number is not a valid `u32`
+    InvalidHex,
+
+    /// Only ASCII is allowed
+    NonAscii,
+}
+
+impl std::fmt::Display for FourCcParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidLen(len) => write!(f, "four char code must be 4 bytes long, got {len}"),
+            Self::InvalidEscape => f.write_str("invalid escape sequence in four char code"),
+            Self::InvalidHex => f.write_str("invalid hex four char code"),
+            Self::NonAscii => f.write_str("four char code must be ASCII"),
+        }
+    }
+}
+
+impl std::error::Error for FourCcParseError {}
+
+impl std::str::FromStr for FourCc {
+    type Err = FourCcParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match unescape(s) {
+            Ok(bytes) => Ok(Self::from_bytes(&bytes)),
+            Err(err) => {
+                // "0xab" is a valid literal code, so hex is only tried after literal
+                let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) else {
+                    return Err(err);
+                };
+                if hex.is_empty() || hex.len() > 8 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(FourCcParseError::InvalidHex);
+                }
+                FourCharCode::from_str_radix(hex, 16)
+                    .map(Self)
+                    .map_err(|_| FourCcParseError::InvalidHex)
+            }
+        }
+    }
+}
+
+fn unescape(s: &str) -> Result<[u8; 4], FourCcParseError> {
+    if !s.is_ascii() {
+        return Err(FourCcParseError::NonAscii);
+    }
+    let mut res = [0u8; 4];
+    let mut len = 0usize;
+    let mut bytes = s.bytes();
+    while let Some(b) = bytes.next() {
+        let b = if b == b'\\' {
+            match bytes.next() {
+                Some(b'\\') => b'\\',
+                Some(b'x') => {
+                    let hi = bytes.next().and_then(hex_digit);
+                    let lo = bytes.next().and_then(hex_digit);
+                    match (hi, lo) {
+                        (Some(hi), Some(lo)) => (hi << 4) | lo,
+                        _ => return Err(FourCcParseError::InvalidEscape),
+                    }
+                }
+                _ => return Err(FourCcParseError::InvalidEscape),
+            }
+        } else {
+            b
+        };
+        if len < 4 {
+            res[len] = b;
+        }
+        len += 1;
+    }
+    if len != 4 {
+        return Err(FourCcParseError::InvalidLen(len));
+    }
+    Ok(res)
+}
+
+#[inline]
+fn hex_digit(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for FourCc {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u32(self.0)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for FourCc {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = FourCc;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("four char code string or u32")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                FourCharCode::try_from(v)
+                    .map(FourCc)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                // os::Status values are negative i32
+                i32::try_from(v)
+                    .map(|v| FourCc(v as FourCharCode))
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(v), &self))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(Visitor)
+        } else {
+            deserializer.deserialize_u32(Visitor)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::four_cc_to_str;
+    use crate::{FourCc, FourCcParseError, four_cc_to_str};
 
     #[test]
     fn basics() {
-        let mut x: [u8; 4] = (*b"24BG").into();
+        let mut x: [u8; 4] = *b"24BG";
         let s = four_cc_to_str(&mut x);
         assert_eq!(s, "24BG");
 
@@ -44,4 +299,82 @@ mod tests {
         let s = four_cc_to_str(&mut bytes);
         assert_eq!(s, "....");
     }
+
+    #[test]
+    fn four_cc() {
+        const AVC1: FourCc = FourCc::from_bytes(b"avc1");
+        assert_eq!(AVC1.0, 0x61766331);
+        assert_eq!(AVC1.to_bytes(), *b"avc1");
+        assert!(AVC1.is_printable());
+        assert_eq!(AVC1.to_string(), "avc1");
+        assert_eq!(format!("{AVC1:?}"), "FourCc(\"avc1\")");
+
+        struct Codec(u32);
+        impl std::fmt::Debug for Codec {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                crate::four_cc_fmt_debug(self.0, "Codec", f)
+            }
+        }
+        assert_eq!(
+            format!("{:?}", Codec(AVC1.0)),
+            "Codec { raw: 1635148593, fcc: \"avc1\" }"
+        );
+
+        assert_eq!("avc1".parse::<FourCc>(), Ok(AVC1));
+        assert_eq!("0x61766331".parse::<FourCc>(), Ok(AVC1));
+        assert_eq!("0X61766331".parse::<FourCc>(), Ok(AVC1));
+        assert_eq!("0x1".parse::<FourCc>(), Ok(FourCc(1)));
+        assert_eq!("0xab".parse::<FourCc>(), Ok(FourCc::from_bytes(b"0xab")));
+        assert_eq!("ac3 ".parse::<FourCc>(), Ok(FourCc::from_bytes(b"ac3 ")));
+
+        let zero = FourCc(0);
+        assert!(!zero.is_printable());
+        assert_eq!(zero.to_string(), "\\x00\\x00\\x00\\x00");
+
+        let slash = FourCc::from_bytes(b"a\\b\\");
+        assert_eq!(slash.to_string(), "a\\\\b\\\\");
+    }
+
+    #[test]
+    fn four_cc_round_trip() {
+        for val in [
+            0,
+            1,
+            0x5c5c5c5c,
+            0xffff_ffce,
+            0x7f20_7e1f,
+            u32::MAX,
+            0x61766331,
+        ] {
+            let fcc = FourCc(val);
+            assert_eq!(fcc.to_string().parse::<FourCc>(), Ok(fcc));
+        }
+    }
+
+    #[test]
+    fn four_cc_errors() {
+        assert_eq!(
+            "avc".parse::<FourCc>(),
+            Err(FourCcParseError::InvalidLen(3))
+        );
+        assert_eq!(
+            "avc12".parse::<FourCc>(),
+            Err(FourCcParseError::InvalidLen(5))
+        );
+        assert_eq!(
+            "av\\q1".parse::<FourCc>(),
+            Err(FourCcParseError::InvalidEscape)
+        );
+        assert_eq!(
+            "av\\x1".parse::<FourCc>(),
+            Err(FourCcParseError::InvalidEscape)
+        );
+        assert_eq!("avé1".parse::<FourCc>(), Err(FourCcParseError::NonAscii));
+        assert_eq!(
+            "0x123456789".parse::<FourCc>(),
+            Err(FourCcParseError::InvalidHex)
+        );
+        assert_eq!("0xzz".parse::<FourCc>(), Ok(FourCc::from_bytes(b"0xzz")));
+        assert_eq!("0xzzz".parse::<FourCc>(), Err(FourCcParseError::InvalidHex));
+    }
 }
@@ -0,0 +1,751 @@
+//! ISO base media file format (ISO/IEC 14496-12) box reader.
+//!
+//! Inspects initialization and media segments of fragmented MP4 / CMAF,
+//! like ones `av::AssetWriter` delivers to its delegate, without AVFoundation.
+//!
+//! ```
+//! use cidre::mp4;
+//!
+//! // a `free` box followed by an empty `mdat`
+//! let buf = [0, 0, 0, 9, b'f', b'r', b'e', b'e', 0, 0, 0, 0, 8, b'm', b'd', b'a', b't'];
+//! let boxes: Vec<_> = mp4::boxes(&buf).collect::<Result<_, _>>().unwrap();
+//! assert_eq!(boxes.len(), 2);
+//! assert_eq!(boxes[1].typ, mp4::box_type::MDAT);
+//! assert_eq!(boxes[1].offset, 9);
+//!
+//! let segment = mp4::Segment::parse(&buf).unwrap();
+//! assert_eq!(segment.media_data, [17..17]);
+//! ```
+
+use std::ops::Range;
+
+use crate::FourCc;
+
+mod moov;
+pub use moov::FileType;
+pub use moov::Movie;
+pub use moov::SampleEntry;
+pub use moov::SampleEntryKind;
+pub use moov::Track;
+pub use moov::TrackExtends;
+
+mod moof;
+pub use moof::Fragment;
+pub use moof::Sample;
+pub use moof::SampleFlags;
+pub use moof::TrackFragment;
+pub use moof::TrackRun;
+pub use moof::TrackRunSample;
+
+mod sidx;
+pub use sidx::SegmentIndex;
+pub use sidx::SegmentRef;
+
+/// Box types known to this module
+pub mod box_type {
+    use crate::FourCc;
+
+    pub const FTYP: FourCc = FourCc::from_bytes(b"ftyp");
+    pub const STYP: FourCc = FourCc::from_bytes(b"styp");
+    pub const MOOV: FourCc = FourCc::from_bytes(b"moov");
+    pub const MVHD: FourCc = FourCc::from_bytes(b"mvhd");
+    pub const TRAK: FourCc = FourCc::from_bytes(b"trak");
+    pub const TKHD: FourCc = FourCc::from_bytes(b"tkhd");
+    pub const MDIA: FourCc = FourCc::from_bytes(b"mdia");
+    pub const MDHD: FourCc = FourCc::from_bytes(b"mdhd");
+    pub const HDLR: FourCc = FourCc::from_bytes(b"hdlr");
+    pub const MINF: FourCc = FourCc::from_bytes(b"minf");
+    pub const STBL: FourCc = FourCc::from_bytes(b"stbl");
+    pub const STSD: FourCc = FourCc::from_bytes(b"stsd");
+    pub const MVEX: FourCc = FourCc::from_bytes(b"mvex");
+    pub const TREX: FourCc = FourCc::from_bytes(b"trex");
+    pub const MOOF: FourCc = FourCc::from_bytes(b"moof");
+    pub const MFHD: FourCc = FourCc::from_bytes(b"mfhd");
+    pub const TRAF: FourCc = FourCc::from_bytes(b"traf");
+    pub const TFHD: FourCc = FourCc::from_bytes(b"tfhd");
+    pub const TFDT: FourCc = FourCc::from_bytes(b"tfdt");
+    pub const TRUN: FourCc = FourCc::from_bytes(b"trun");
+    pub const SIDX: FourCc = FourCc::from_bytes(b"sidx");
+    pub const MDAT: FourCc = FourCc::from_bytes(b"mdat");
+    pub const UUID: FourCc = FourCc::from_bytes(b"uuid");
+}
+
+/// Handler types of `hdlr` box
+pub mod handler {
+    use crate::FourCc;
+
+    pub const VIDEO: FourCc = FourCc::from_bytes(b"vide");
+    pub const SOUND: FourCc = FourCc::from_bytes(b"soun");
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    UnexpectedEof,
+
+    /// Box size is smaller than its header or larger than its parent
+    InvalidSize(FourCc),
+
+    /// Full box version isn't supported
+    UnsupportedVersion(FourCc, u8),
+
+    /// Required child box is missing
+    MissingBox(FourCc),
+
+    /// Sample offsets or decode times don't fit in `u64`
+    Overflow,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of box"),
+            Self::InvalidSize(typ) => write!(f, "invalid size of '{typ}' box"),
+            Self::UnsupportedVersion(typ, v) => write!(f, "unsupported '{typ}' box version {v}"),
+            Self::MissingBox(typ) => write!(f, "missing '{typ}' box"),
+            Self::Overflow => f.write_str("sample offset or decode time overflow"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Box header and payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawBox<'a> {
+    pub typ: FourCc,
+
+    /// Offset of box header from start of parsed buffer
+    pub offset: u64,
+
+    /// Size including header
+    pub size: u64,
+
+    /// Box contents after header
+    pub payload: &'a [u8],
+}
+
+/// Iterator over sibling boxes.
+///
+/// Stops after the first error.
+#[derive(Debug, Clone)]
+pub struct Boxes<'a> {
+    buf: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> Boxes<'a> {
+    fn header(&mut self) -> Result<RawBox<'a>> {
+        let r = &mut Reader::new(self.buf);
+        let mut size = r.u32()? as u64;
+        let typ = r.four_cc()?;
+        if size == 1 {
+            size = r.u64()?;
+        } else if size == 0 {
+            size = self.buf.len() as u64;
+        }
+        if typ == box_type::UUID {
+            r.skip(16)?;
+        }
+        let header_len = self.buf.len() - r.buf.len();
+        if size < header_len as u64 {
+            return Err(Error::InvalidSize(typ));
+        }
+        if size > self.buf.len() as u64 {
+            return Err(Error::UnexpectedEof);
+        }
+        let (bx, rest) = self.buf.split_at(size as usize);
+        let res = RawBox {
+            typ,
+            offset: self.offset,
+            size,
+            payload: &bx[header_len..],
+        };
+        self.buf = rest;
+        self.offset += size;
+        Ok(res)
+    }
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = Result<RawBox<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let res = self.header();
+        if res.is_err() {
+            self.buf = &[];
+        }
+        Some(res)
+    }
+}
+
+impl std::iter::FusedIterator for Boxes<'_> {}
+
+/// Top level boxes of buffer.
+#[inline]
+pub fn boxes(buf: &[u8]) -> Boxes<'_> {
+    Boxes { buf, offset: 0 }
+}
+
+impl<'a> RawBox<'a> {
+    /// Offset of payload from start of parsed buffer
+    #[inline]
+    pub fn offset_of_payload(&self) -> u64 {
+        self.offset + self.size - self.payload.len() as u64
+    }
+
+    /// Iterates child boxes of container box.
+    #[inline]
+    pub fn children(&self) -> Boxes<'a> {
+        Boxes {
+            buf: self.payload,
+            offset: self.offset_of_payload(),
+        }
+    }
+}
+
+/// Big endian reader over box payload.
+pub(crate) struct Reader<'a> {
+    pub buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    #[inline]
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if n > self.buf.len() {
+            return Err(Error::UnexpectedEof);
+        }
+        let (head, rest) = self.buf.split_at(n);
+        self.buf = rest;
+        Ok(head)
+    }
+
+    #[inline]
+    pub fn skip(&mut self, n: usize) -> Result<()> {
+        self.take(n).map(|_| ())
+    }
+
+    #[inline]
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    #[inline]
+    pub fn u16(&mut self) -> Result<u16> {
+        self.array().map(u16::from_be_bytes)
+    }
+
+    #[inline]
+    pub fn u32(&mut self) -> Result<u32> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    #[inline]
+    pub fn u64(&mut self) -> Result<u64> {
+        self.array().map(u64::from_be_bytes)
+    }
+
+    #[inline]
+    pub fn four_cc(&mut self) -> Result<FourCc> {
+        self.array().map(|b| FourCc::from_bytes(&b))
+    }
+
+    /// Reads full box version and flags.
+    pub fn full_box(&mut self, typ: FourCc, max_version: u8) -> Result<(u8, u32)> {
+        let v = self.u32()?;
+        let version = (v >> 24) as u8;
+        if version > max_version {
+            return Err(Error::UnsupportedVersion(typ, version));
+        }
+        Ok((version, v & 0xff_ffff))
+    }
+
+    /// Reads `u32` for version 0 and `u64` for version 1.
+    pub fn versioned(&mut self, version: u8) -> Result<u64> {
+        if version == 0 {
+            self.u32().map(|v| v as u64)
+        } else {
+            self.u64()
+        }
+    }
+}
+
+/// Parsed initialization or media segment.
+///
+/// Boxes this module doesn't know are skipped.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Segment {
+    /// `ftyp` or `styp`
+    pub file_type: Option<FileType>,
+    pub movie: Option<Movie>,
+    pub indexes: Vec<SegmentIndex>,
+    pub fragments: Vec<Fragment>,
+
+    /// Payload ranges of `mdat` boxes
+    pub media_data: Vec<Range<u64>>,
+}
+
+impl Segment {
+    pub fn parse(buf: &[u8]) -> Result<Self> {
+        let mut res = Self::default();
+        for bx in boxes(buf) {
+            let bx = bx?;
+            match bx.typ {
+                box_type::FTYP | box_type::STYP => {
+                    res.file_type = Some(FileType::parse(&bx)?);
+                }
+                box_type::MOOV => res.movie = Some(Movie::parse(&bx)?),
+                box_type::SIDX => res.indexes.push(SegmentIndex::parse(&bx)?),
+                box_type::MOOF => res.fragments.push(Fragment::parse(&bx)?),
+                box_type::MDAT => {
+                    let start = bx.offset_of_payload();
+                    res.media_data.push(start..start + bx.payload.len() as u64);
+                }
+                _ => {}
+            }
+        }
+        Ok(res)
+    }
+
+    /// Samples of track in all fragments with resolved defaults, decode times and offsets.
+    ///
+    /// `init` is movie from initialization segment providing `trex` defaults.
+    pub fn track_samples(&self, track_id: u32, init: Option<&Movie>) -> Result<Vec<Sample>> {
+        let trex = init
+            .or(self.movie.as_ref())
+            .and_then(|m| m.track_extends(track_id));
+        let data_len = self.media_data.iter().map(|r| r.end - r.start).sum();
+        let mut res = Vec::new();
+        for fragment in &self.fragments {
+            for traf in fragment.tracks.iter().filter(|t| t.track_id == track_id) {
+                res.extend(traf.samples(trex, fragment.offset, data_len)?);
+            }
+        }
+        Ok(res)
+    }
+
+    /// Decode time range of track in its timescale.
+    pub fn decode_time_range(
+        &self,
+        track_id: u32,
+        init: Option<&Movie>,
+    ) -> Result<Option<Range<u64>>> {
+        let samples = self.track_samples(track_id, init)?;
+        let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+            return Ok(None);
+        };
+        let end = last
+            .decode_time
+            .checked_add(last.duration as u64)
+            .ok_or(Error::Overflow)?;
+        Ok(Some(first.decode_time..end))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::{FourCc, mp4};
+
+    pub fn bx(typ: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut res = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        res.extend_from_slice(typ);
+        res.extend_from_slice(payload);
+        res
+    }
+
+    pub fn full_bx(typ: &[u8; 4], version: u8, flags: u32, payload: &[u8]) -> Vec<u8> {
+        let mut body = (((version as u32) << 24) | flags).to_be_bytes().to_vec();
+        body.extend_from_slice(payload);
+        bx(typ, &body)
+    }
+
+    fn avc1() -> Vec<u8> {
+        let mut entry = vec![0; 6];
+        entry.extend_from_slice(&1u16.to_be_bytes());
+        entry.extend_from_slice(&[0; 16]);
+        entry.extend_from_slice(&1920u16.to_be_bytes());
+        entry.extend_from_slice(&1080u16.to_be_bytes());
+        entry.extend_from_slice(&0x0048_0000u32.to_be_bytes());
+        entry.extend_from_slice(&0x0048_0000u32.to_be_bytes());
+        entry.extend_from_slice(&[0; 4]);
+        entry.extend_from_slice(&1u16.to_be_bytes());
+        entry.extend_from_slice(&[0; 32]);
+        entry.extend_from_slice(&0x18u16.to_be_bytes());
+        entry.extend_from_slice(&(-1i16).to_be_bytes());
+        entry.extend(bx(b"avcC", &[1, 0x64, 0, 0x1f, 0xff, 0xe0, 0]));
+        entry.extend(bx(b"pasp", &[0, 0, 0, 1, 0, 0, 0, 1]));
+        bx(b"avc1", &entry)
+    }
+
+    fn mp4a() -> Vec<u8> {
+        let mut entry = vec![0; 6];
+        entry.extend_from_slice(&1u16.to_be_bytes());
+        entry.extend_from_slice(&[0; 8]);
+        entry.extend_from_slice(&2u16.to_be_bytes());
+        entry.extend_from_slice(&16u16.to_be_bytes());
+        entry.extend_from_slice(&[0; 4]);
+        entry.extend_from_slice(&(48_000u32 << 16).to_be_bytes());
+        entry.extend(full_bx(b"esds", 0, 0, &[3, 0x19, 0, 2, 0]));
+        bx(b"mp4a", &entry)
+    }
+
+    fn trak(id: u32, handler: &[u8; 4], timescale: u32, entry: Vec<u8>) -> Vec<u8> {
+        let mut tkhd = vec![0; 8];
+        tkhd.extend_from_slice(&id.to_be_bytes());
+        tkhd.extend_from_slice(&[0; 72]);
+        let mut mdhd = vec![0; 8];
+        mdhd.extend_from_slice(&timescale.to_be_bytes());
+        mdhd.extend_from_slice(&0u32.to_be_bytes());
+        // "und"
+        mdhd.extend_from_slice(&0x55c4u16.to_be_bytes());
+        mdhd.extend_from_slice(&[0; 2]);
+        let mut hdlr = vec![0; 4];
+        hdlr.extend_from_slice(handler);
+        hdlr.extend_from_slice(&[0; 12]);
+        hdlr.extend_from_slice(b"Core Media\0");
+        let mut stsd = 1u32.to_be_bytes().to_vec();
+        stsd.extend(entry);
+        let stbl = bx(b"stbl", &full_bx(b"stsd", 0, 0, &stsd));
+        let minf = bx(b"minf", &stbl);
+        let mdia = [
+            full_bx(b"mdhd", 0, 0, &mdhd),
+            full_bx(b"hdlr", 0, 0, &hdlr),
+            minf,
+        ]
+        .concat();
+        bx(
+            b"trak",
+            &[full_bx(b"tkhd", 0, 3, &tkhd), bx(b"mdia", &mdia)].concat(),
+        )
+    }
+
+    fn trex(id: u32, duration: u32, flags: u32) -> Vec<u8> {
+        let mut body = id.to_be_bytes().to_vec();
+        for v in [1, duration, 0, flags] {
+            body.extend_from_slice(&v.to_be_bytes());
+        }
+        full_bx(b"trex", 0, 0, &body)
+    }
+
+    pub fn init_segment() -> Vec<u8> {
+        let mut ftyp = b"iso6".to_vec();
+        ftyp.extend_from_slice(&[0, 0, 2, 0]);
+        ftyp.extend_from_slice(b"iso6cmfcmp41");
+        let mut mvhd = vec![0; 8];
+        mvhd.extend_from_slice(&600u32.to_be_bytes());
+        mvhd.extend_from_slice(&[0; 84]);
+        let mvex = bx(
+            b"mvex",
+            &[trex(1, 0, 0x0101_0000), trex(2, 1024, 0x0200_0000)].concat(),
+        );
+        let moov = [
+            full_bx(b"mvhd", 0, 0, &mvhd),
+            trak(1, b"vide", 30_000, avc1()),
+            trak(2, b"soun", 48_000, mp4a()),
+            mvex,
+        ]
+        .concat();
+        [bx(b"ftyp", &ftyp), bx(b"moov", &moov)].concat()
+    }
+
+    fn traf_video(decode_time: u64) -> Vec<u8> {
+        // default-base-is-moof, default sample duration
+        let tfhd = full_bx(b"tfhd", 0, 0x02_0008, &[0, 0, 0, 1, 0, 0, 0x03, 0xe9]);
+        let tfdt = full_bx(b"tfdt", 1, 0, &decode_time.to_be_bytes());
+        // data offset, first sample flags, sizes, composition offsets (signed)
+        let mut trun = 3u32.to_be_bytes().to_vec();
+        trun.extend_from_slice(&0u32.to_be_bytes());
+        trun.extend_from_slice(&0x0200_0000u32.to_be_bytes());
+        for (size, cto) in [(10u32, 2002i32), (4, -1001), (6, 0)] {
+            trun.extend_from_slice(&size.to_be_bytes());
+            trun.extend_from_slice(&cto.to_be_bytes());
+        }
+        let trun = full_bx(b"trun", 1, 0x0a05, &trun);
+        bx(b"traf", &[tfhd, tfdt, trun].concat())
+    }
+
+    fn traf_audio(decode_time: u32) -> Vec<u8> {
+        let tfhd = full_bx(b"tfhd", 0, 0x02_0000, &2u32.to_be_bytes());
+        let tfdt = full_bx(b"tfdt", 0, 0, &decode_time.to_be_bytes());
+        let mut trun = 2u32.to_be_bytes().to_vec();
+        trun.extend_from_slice(&0u32.to_be_bytes());
+        for size in [3u32, 2] {
+            trun.extend_from_slice(&size.to_be_bytes());
+        }
+        let trun = full_bx(b"trun", 0, 0x0201, &trun);
+        bx(b"traf", &[tfhd, tfdt, trun].concat())
+    }
+
+    /// styp, sidx, moof with video and audio runs, mdat
+    pub fn media_segment(seq: u32, video_time: u64, audio_time: u32) -> Vec<u8> {
+        let mut sidx = 1u32.to_be_bytes().to_vec();
+        sidx.extend_from_slice(&30_000u32.to_be_bytes());
+        sidx.extend_from_slice(&(video_time as u32).to_be_bytes());
+        sidx.extend_from_slice(&0u32.to_be_bytes());
+        sidx.extend_from_slice(&[0, 0, 0, 1]);
+        sidx.extend_from_slice(&1000u32.to_be_bytes());
+        sidx.extend_from_slice(&3003u32.to_be_bytes());
+        sidx.extend_from_slice(&0x9000_0000u32.to_be_bytes());
+
+        let mut moof = [
+            full_bx(b"mfhd", 0, 0, &seq.to_be_bytes()),
+            traf_video(video_time),
+            traf_audio(audio_time),
+        ]
+        .concat();
+        let moof_len = moof.len() + 8;
+        // patch data offsets: video samples first then audio
+        let video_offset = (moof_len + 8) as u32;
+        let audio_offset = video_offset + 20;
+        let pos = find(&moof, b"trun") + 12;
+        moof[pos..pos + 4].copy_from_slice(&video_offset.to_be_bytes());
+        let pos = rfind(&moof, b"trun") + 12;
+        moof[pos..pos + 4].copy_from_slice(&audio_offset.to_be_bytes());
+
+        let mdat: Vec<u8> = (0..25).collect();
+        [
+            bx(b"styp", b"msdh\0\0\0\0msdhmsix"),
+            full_bx(b"sidx", 0, 0, &sidx),
+            bx(b"moof", &moof),
+            bx(b"mdat", &mdat),
+        ]
+        .concat()
+    }
+
+    fn find(buf: &[u8], typ: &[u8; 4]) -> usize {
+        buf.windows(4).position(|w| w == typ).unwrap()
+    }
+
+    fn rfind(buf: &[u8], typ: &[u8; 4]) -> usize {
+        buf.windows(4).rposition(|w| w == typ).unwrap()
+    }
+
+    #[test]
+    fn boxes() {
+        let mut buf = mp4::tests::bx(b"free", &[1, 2, 3]);
+        // 64-bit size
+        buf.extend_from_slice(&[0, 0, 0, 1]);
+        buf.extend_from_slice(b"skip");
+        buf.extend_from_slice(&17u64.to_be_bytes());
+        buf.push(7);
+        // uuid with extended type
+        buf.extend(bx(b"uuid", &[0xab; 17]));
+        // size 0 extends to the end
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+        buf.extend_from_slice(b"mdat");
+        buf.extend_from_slice(&[9, 9]);
+
+        let boxes: Vec<_> = mp4::boxes(&buf).collect::<Result<_, _>>().unwrap();
+        let types: Vec<_> = boxes.iter().map(|b| b.typ.to_string()).collect();
+        assert_eq!(types, ["free", "skip", "uuid", "mdat"]);
+        assert_eq!(boxes[0].payload, [1, 2, 3]);
+        assert_eq!(boxes[1].offset, 11);
+        assert_eq!(boxes[1].payload, [7]);
+        assert_eq!(boxes[2].payload, [0xab]);
+        assert_eq!(boxes[3].payload, [9, 9]);
+        assert_eq!(boxes[3].offset_of_payload(), buf.len() as u64 - 2);
+    }
+
+    #[test]
+    fn init_segment_info() {
+        let segment = mp4::Segment::parse(&init_segment()).unwrap();
+        let ftyp = segment.file_type.unwrap();
+        assert_eq!(ftyp.major_brand, FourCc::from_bytes(b"iso6"));
+        assert!(ftyp.has_brand(FourCc::from_bytes(b"cmfc")));
+        assert!(segment.fragments.is_empty());
+
+        let movie = segment.movie.unwrap();
+        assert_eq!(movie.timescale, 600);
+        assert!(movie.is_fragmented());
+        assert_eq!(movie.tracks.len(), 2);
+
+        let video = movie.track(1).unwrap();
+        assert_eq!(video.handler, mp4::handler::VIDEO);
+        assert_eq!(video.timescale, 30_000);
+        assert_eq!(video.language, "und");
+        let entry = &video.sample_entries[0];
+        assert_eq!(entry.format, FourCc::from_bytes(b"avc1"));
+        assert_eq!(
+            entry.kind,
+            mp4::SampleEntryKind::Video {
+                width: 1920,
+                height: 1080
+            }
+        );
+        assert_eq!(
+            entry.child(FourCc::from_bytes(b"avcC")),
+            Some(&[1, 0x64, 0, 0x1f, 0xff, 0xe0, 0][..])
+        );
+        #[cfg(feature = "nal")]
+        assert_eq!(entry.avc_config().unwrap().unwrap().profile_idc, 100);
+
+        let audio = movie.track(2).unwrap();
+        assert_eq!(
+            audio.sample_entries[0].kind,
+            mp4::SampleEntryKind::Audio {
+                channel_count: 2,
+                sample_size: 16,
+                sample_rate: 48_000.0
+            }
+        );
+        assert_eq!(
+            movie.track_extends(2).unwrap().default_sample_duration,
+            1024
+        );
+    }
+
+    #[test]
+    fn media_segment_samples() {
+        let init = mp4::Segment::parse(&init_segment()).unwrap().movie.unwrap();
+        let buf = media_segment(7, 3003 * 30, 48_000 * 3);
+        let segment = mp4::Segment::parse(&buf).unwrap();
+        assert_eq!(
+            segment.file_type.as_ref().unwrap().major_brand.to_string(),
+            "msdh"
+        );
+        assert_eq!(segment.fragments[0].sequence_number, 7);
+        assert_eq!(segment.media_data.len(), 1);
+        let mdat = segment.media_data[0].clone();
+
+        let sidx = &segment.indexes[0];
+        assert_eq!(sidx.timescale, 30_000);
+        assert_eq!(sidx.refs[0].subsegment_duration, 3003);
+        assert!(sidx.refs[0].starts_with_sap);
+        assert_eq!(sidx.refs[0].sap_type, 1);
+
+        let video = segment.track_samples(1, Some(&init)).unwrap();
+        assert_eq!(video.len(), 3);
+        let times: Vec<_> = video.iter().map(|s| s.decode_time).collect();
+        assert_eq!(times, [90_090, 91_091, 92_092]);
+        assert_eq!(video.iter().map(|s| s.size).sum::<u32>(), 20);
+        assert!(video.iter().all(|s| s.duration == 1001));
+        let keys: Vec<_> = video.iter().map(|s| s.is_key()).collect();
+        assert_eq!(keys, [true, false, false]);
+        assert_eq!(video[0].presentation_time(), 92_092);
+        assert_eq!(video[1].presentation_time(), 90_090);
+        assert_eq!(video[0].offset, mdat.start);
+        let range = video[1].range();
+        assert_eq!(
+            &buf[range.start as usize..range.end as usize],
+            [10, 11, 12, 13]
+        );
+
+        let audio = segment.track_samples(2, Some(&init)).unwrap();
+        assert_eq!(audio.len(), 2);
+        assert_eq!(audio[1].decode_time, 48_000 * 3 + 1024);
+        assert!(audio.iter().all(|s| s.is_key()));
+        assert_eq!(audio[1].range().end, mdat.end);
+        assert_eq!(
+            segment.decode_time_range(2, Some(&init)),
+            Ok(Some(144_000..144_000 + 2048))
+        );
+
+        // without trex defaults audio samples have no duration
+        let audio = segment.track_samples(2, None).unwrap();
+        assert_eq!(audio[1].decode_time, 48_000 * 3);
+    }
+
+    #[test]
+    fn malformed() {
+        let init = init_segment();
+        let media = media_segment(1, 0, 0);
+        for buf in [&init, &media] {
+            let ends: Vec<_> = mp4::boxes(buf)
+                .map(|b| b.unwrap())
+                .map(|b| (b.offset + b.size) as usize)
+                .collect();
+            for len in 1..buf.len() {
+                if !ends.contains(&len) {
+                    // truncation is detected by enclosing box size
+                    assert!(mp4::Segment::parse(&buf[..len]).is_err(), "{len}");
+                }
+            }
+        }
+        let bad = bx(b"moof", &full_bx(b"mfhd", 0, 0, &[0, 0]));
+        assert_eq!(mp4::Segment::parse(&bad), Err(mp4::Error::UnexpectedEof));
+        let bad = [0, 0, 0, 7, b'f', b'r', b'e', b'e'];
+        assert_eq!(
+            mp4::Segment::parse(&bad),
+            Err(mp4::Error::InvalidSize(FourCc::from_bytes(b"free")))
+        );
+        let bad = bx(b"moof", &full_bx(b"mfhd", 2, 0, &[0, 0, 0, 0]));
+        assert_eq!(
+            mp4::Segment::parse(&bad),
+            Err(mp4::Error::UnsupportedVersion(
+                FourCc::from_bytes(b"mfhd"),
+                2
+            ))
+        );
+        let bad = bx(b"moov", &[]);
+        assert_eq!(
+            mp4::Segment::parse(&bad),
+            Err(mp4::Error::MissingBox(mp4::box_type::MVHD))
+        );
+        // huge sample count doesn't allocate
+        let trun = full_bx(b"trun", 0, 0x200, &u32::MAX.to_be_bytes());
+        let traf = bx(
+            b"traf",
+            &[full_bx(b"tfhd", 0, 0, &[0, 0, 0, 1]), trun].concat(),
+        );
+        let bad = bx(b"moof", &traf);
+        assert_eq!(mp4::Segment::parse(&bad), Err(mp4::Error::UnexpectedEof));
+
+        // nor does one without per sample fields
+        let mfhd = full_bx(b"mfhd", 0, 0, &[0, 0, 0, 1]);
+        let trun = full_bx(b"trun", 0, 0, &u32::MAX.to_be_bytes());
+        let traf = bx(
+            b"traf",
+            &[full_bx(b"tfhd", 0, 0, &[0, 0, 0, 1]), trun].concat(),
+        );
+        let buf = [
+            bx(b"moof", &[mfhd.clone(), traf].concat()),
+            bx(b"mdat", &[0; 16]),
+        ]
+        .concat();
+        let segment = mp4::Segment::parse(&buf).unwrap();
+        assert_eq!(
+            segment.track_samples(1, None),
+            Err(mp4::Error::InvalidSize(mp4::box_type::TRUN))
+        );
+
+        // decode time and offset overflow
+        let trun = full_bx(b"trun", 0, 0, &2u32.to_be_bytes());
+        let mut tfhd = 1u32.to_be_bytes().to_vec();
+        tfhd.extend_from_slice(&10u32.to_be_bytes());
+        let tfhd = full_bx(b"tfhd", 0, 0x08, &tfhd);
+        let tfdt = full_bx(b"tfdt", 1, 0, &(u64::MAX - 15).to_be_bytes());
+        let traf = bx(b"traf", &[tfhd, tfdt, trun.clone()].concat());
+        let buf = [
+            bx(b"moof", &[mfhd.clone(), traf].concat()),
+            bx(b"mdat", &[0; 16]),
+        ]
+        .concat();
+        let segment = mp4::Segment::parse(&buf).unwrap();
+        assert_eq!(segment.track_samples(1, None), Err(mp4::Error::Overflow));
+        assert_eq!(
+            segment.decode_time_range(1, None),
+            Err(mp4::Error::Overflow)
+        );
+
+        let mut tfhd = 1u32.to_be_bytes().to_vec();
+        tfhd.extend_from_slice(&(u64::MAX - 6).to_be_bytes());
+        tfhd.extend_from_slice(&4u32.to_be_bytes());
+        let tfhd = full_bx(b"tfhd", 0, 0x11, &tfhd);
+        let traf = bx(b"traf", &[tfhd, trun].concat());
+        let buf = [
+            bx(b"moof", &[mfhd.clone(), traf].concat()),
+            bx(b"mdat", &[0; 16]),
+        ]
+        .concat();
+        let segment = mp4::Segment::parse(&buf).unwrap();
+        assert_eq!(segment.track_samples(1, None), Err(mp4::Error::Overflow));
+    }
+}
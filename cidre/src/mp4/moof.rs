@@ -0,0 +1,350 @@
+use std::ops::Range;
+
+use crate::mp4::{Error, RawBox, Reader, Result, TrackExtends, box_type};
+
+/// Sample dependency and sync flags (ISO/IEC 14496-12 8.8.3.1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(transparent)]
+pub struct SampleFlags(pub u32);
+
+impl SampleFlags {
+    #[inline]
+    pub const fn is_leading(self) -> u8 {
+        ((self.0 >> 26) & 0b11) as u8
+    }
+
+    /// 1 means sample depends on others (not I-picture), 2 means it doesn't
+    #[inline]
+    pub const fn depends_on(self) -> u8 {
+        ((self.0 >> 24) & 0b11) as u8
+    }
+
+    /// 1 means other samples depend on this one, 2 means it is disposable
+    #[inline]
+    pub const fn is_depended_on(self) -> u8 {
+        ((self.0 >> 22) & 0b11) as u8
+    }
+
+    #[inline]
+    pub const fn has_redundancy(self) -> u8 {
+        ((self.0 >> 20) & 0b11) as u8
+    }
+
+    #[inline]
+    pub const fn padding(self) -> u8 {
+        ((self.0 >> 17) & 0b111) as u8
+    }
+
+    #[doc(alias = "sample_is_non_sync_sample")]
+    #[inline]
+    pub const fn is_non_sync(self) -> bool {
+        self.0 & 0x1_0000 != 0
+    }
+
+    #[inline]
+    pub const fn degradation_priority(self) -> u16 {
+        self.0 as u16
+    }
+
+    /// Sync sample which doesn't depend on others
+    #[inline]
+    pub const fn is_key(self) -> bool {
+        !self.is_non_sync() && self.depends_on() != 1
+    }
+}
+
+/// Movie fragment
+#[doc(alias = "moof")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    /// Offset of `moof` box, base for track fragment data offsets
+    pub offset: u64,
+    pub sequence_number: u32,
+    pub tracks: Vec<TrackFragment>,
+}
+
+impl Fragment {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let mut sequence_number = None;
+        let mut tracks = Vec::new();
+        for child in bx.children() {
+            let child = child?;
+            match child.typ {
+                box_type::MFHD => {
+                    let r = &mut Reader::new(child.payload);
+                    r.full_box(child.typ, 0)?;
+                    sequence_number = Some(r.u32()?);
+                }
+                box_type::TRAF => tracks.push(TrackFragment::parse(&child)?),
+                _ => {}
+            }
+        }
+        let Some(sequence_number) = sequence_number else {
+            return Err(Error::MissingBox(box_type::MFHD));
+        };
+        Ok(Self {
+            offset: bx.offset,
+            sequence_number,
+            tracks,
+        })
+    }
+}
+
+/// Track fragment header, decode time and runs
+#[doc(alias = "traf")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TrackFragment {
+    pub track_id: u32,
+    pub base_data_offset: Option<u64>,
+    pub sample_desc_index: Option<u32>,
+    pub default_sample_duration: Option<u32>,
+    pub default_sample_size: Option<u32>,
+    pub default_sample_flags: Option<SampleFlags>,
+    pub duration_is_empty: bool,
+    pub default_base_is_moof: bool,
+
+    /// `tfdt` decode time of the first sample in media timescale
+    pub base_media_decode_time: Option<u64>,
+    pub runs: Vec<TrackRun>,
+}
+
+impl TrackFragment {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let mut header = None;
+        let mut base_media_decode_time = None;
+        let mut runs = Vec::new();
+        for child in bx.children() {
+            let child = child?;
+            match child.typ {
+                box_type::TFHD => header = Some(child),
+                box_type::TFDT => {
+                    let r = &mut Reader::new(child.payload);
+                    let (version, _) = r.full_box(child.typ, 1)?;
+                    base_media_decode_time = Some(r.versioned(version)?);
+                }
+                box_type::TRUN => runs.push(TrackRun::parse(&child)?),
+                _ => {}
+            }
+        }
+        let Some(header) = header else {
+            return Err(Error::MissingBox(box_type::TFHD));
+        };
+        let r = &mut Reader::new(header.payload);
+        let (_, flags) = r.full_box(header.typ, 0)?;
+        let track_id = r.u32()?;
+        let base_data_offset = if flags & 0x01 != 0 {
+            Some(r.u64()?)
+        } else {
+            None
+        };
+        let mut optional = |flag: u32| -> Result<Option<u32>> {
+            if flags & flag != 0 {
+                r.u32().map(Some)
+            } else {
+                Ok(None)
+            }
+        };
+        let sample_desc_index = optional(0x02)?;
+        let default_sample_duration = optional(0x08)?;
+        let default_sample_size = optional(0x10)?;
+        let default_sample_flags = optional(0x20)?.map(SampleFlags);
+        Ok(Self {
+            track_id,
+            base_data_offset,
+            sample_desc_index,
+            default_sample_duration,
+            default_sample_size,
+            default_sample_flags,
+            duration_is_empty: flags & 0x1_0000 != 0,
+            default_base_is_moof: flags & 0x2_0000 != 0,
+            base_media_decode_time,
+            runs,
+        })
+    }
+
+    /// Number of samples in all runs.
+    pub fn sample_count(&self) -> u64 {
+        self.runs.iter().map(|r| r.sample_count as u64).sum()
+    }
+
+    /// Samples with defaults from `tfhd` and `trex` applied.
+    ///
+    /// `moof_offset` is data base when `tfhd` has no explicit base data offset.
+    /// `data_len` is size of media data, runs without per sample fields
+    /// can't have more samples than it.
+    pub fn samples(
+        &self,
+        trex: Option<&TrackExtends>,
+        moof_offset: u64,
+        data_len: u64,
+    ) -> Result<Vec<Sample>> {
+        let default_duration = self
+            .default_sample_duration
+            .or(trex.map(|t| t.default_sample_duration))
+            .unwrap_or(0);
+        let default_size = self
+            .default_sample_size
+            .or(trex.map(|t| t.default_sample_size))
+            .unwrap_or(0);
+        let default_flags = self
+            .default_sample_flags
+            .or(trex.map(|t| t.default_sample_flags))
+            .unwrap_or_default();
+
+        let mut defaulted = 0u64;
+        for run in &self.runs {
+            if run.samples.is_empty() {
+                defaulted += run.sample_count as u64;
+            }
+        }
+        if defaulted > data_len {
+            return Err(Error::InvalidSize(box_type::TRUN));
+        }
+
+        let base = self.base_data_offset.unwrap_or(moof_offset);
+        let mut offset = base;
+        let mut decode_time = self.base_media_decode_time.unwrap_or(0);
+        let mut res = Vec::with_capacity(self.sample_count().min(1 << 16) as usize);
+        for run in &self.runs {
+            if let Some(data_offset) = run.data_offset {
+                offset = base
+                    .checked_add_signed(data_offset as i64)
+                    .ok_or(Error::Overflow)?;
+            }
+            for i in 0..run.sample_count as usize {
+                let s = run.samples.get(i).copied().unwrap_or_default();
+                let flags = match run.first_sample_flags {
+                    Some(flags) if i == 0 => flags,
+                    _ => s.flags.unwrap_or(default_flags),
+                };
+                let sample = Sample {
+                    decode_time,
+                    composition_offset: s.composition_offset.unwrap_or(0),
+                    duration: s.duration.unwrap_or(default_duration),
+                    size: s.size.unwrap_or(default_size),
+                    flags,
+                    offset,
+                };
+                decode_time = decode_time
+                    .checked_add(sample.duration as u64)
+                    .ok_or(Error::Overflow)?;
+                offset = offset
+                    .checked_add(sample.size as u64)
+                    .ok_or(Error::Overflow)?;
+                res.push(sample);
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// Track run
+#[doc(alias = "trun")]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TrackRun {
+    pub sample_count: u32,
+
+    /// Offset of the first sample from data base
+    pub data_offset: Option<i32>,
+    pub first_sample_flags: Option<SampleFlags>,
+
+    /// Per sample fields, empty if run has none and all samples use defaults
+    pub samples: Vec<TrackRunSample>,
+}
+
+/// Per sample fields of track run, `None` means default value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TrackRunSample {
+    pub duration: Option<u32>,
+    pub size: Option<u32>,
+    pub flags: Option<SampleFlags>,
+    pub composition_offset: Option<i64>,
+}
+
+impl TrackRun {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let r = &mut Reader::new(bx.payload);
+        let (version, flags) = r.full_box(bx.typ, 1)?;
+        let sample_count = r.u32()?;
+        let data_offset = if flags & 0x01 != 0 {
+            Some(r.u32()? as i32)
+        } else {
+            None
+        };
+        let first_sample_flags = if flags & 0x04 != 0 {
+            Some(SampleFlags(r.u32()?))
+        } else {
+            None
+        };
+        let per_sample = 4 * (flags & 0xf00).count_ones() as usize;
+        let mut samples = Vec::new();
+        if per_sample > 0 {
+            if (sample_count as usize).saturating_mul(per_sample) > r.buf.len() {
+                return Err(Error::UnexpectedEof);
+            }
+            samples.reserve_exact(sample_count as usize);
+            let opt = |r: &mut Reader, flag: u32| -> Result<Option<u32>> {
+                if flags & flag != 0 {
+                    r.u32().map(Some)
+                } else {
+                    Ok(None)
+                }
+            };
+            for _ in 0..sample_count {
+                let duration = opt(r, 0x100)?;
+                let size = opt(r, 0x200)?;
+                let flags = opt(r, 0x400)?.map(SampleFlags);
+                let composition_offset = opt(r, 0x800)?.map(|v| match version {
+                    0 => v as i64,
+                    _ => v as i32 as i64,
+                });
+                samples.push(TrackRunSample {
+                    duration,
+                    size,
+                    flags,
+                    composition_offset,
+                });
+            }
+        }
+        Ok(Self {
+            sample_count,
+            data_offset,
+            first_sample_flags,
+            samples,
+        })
+    }
+}
+
+/// Sample of track fragment with all defaults resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Decode time in media timescale
+    pub decode_time: u64,
+    pub composition_offset: i64,
+    pub duration: u32,
+    pub size: u32,
+    pub flags: SampleFlags,
+
+    /// Offset of sample data from start of parsed buffer
+    pub offset: u64,
+}
+
+impl Sample {
+    #[inline]
+    pub fn is_key(&self) -> bool {
+        self.flags.is_key()
+    }
+
+    /// Presentation time in media timescale
+    #[inline]
+    pub fn presentation_time(&self) -> u64 {
+        self.decode_time
+            .saturating_add_signed(self.composition_offset)
+    }
+
+    /// Sample data range in parsed buffer
+    #[inline]
+    pub fn range(&self) -> Range<u64> {
+        self.offset..self.offset.saturating_add(self.size as u64)
+    }
+}
@@ -0,0 +1,355 @@
+use crate::{
+    FourCc,
+    mp4::{Error, RawBox, Reader, Result, SampleFlags, box_type, handler},
+};
+
+/// File (`ftyp`) or segment (`styp`) type
+#[doc(alias = "ftyp")]
+#[doc(alias = "styp")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileType {
+    pub major_brand: FourCc,
+    pub minor_version: u32,
+    pub compatible_brands: Vec<FourCc>,
+}
+
+impl FileType {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let r = &mut Reader::new(bx.payload);
+        let major_brand = r.four_cc()?;
+        let minor_version = r.u32()?;
+        let mut compatible_brands = Vec::with_capacity(r.buf.len() / 4);
+        while !r.buf.is_empty() {
+            compatible_brands.push(r.four_cc()?);
+        }
+        Ok(Self {
+            major_brand,
+            minor_version,
+            compatible_brands,
+        })
+    }
+
+    pub fn has_brand(&self, brand: FourCc) -> bool {
+        self.major_brand == brand || self.compatible_brands.contains(&brand)
+    }
+}
+
+/// Movie header, tracks and fragment defaults
+#[doc(alias = "moov")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Movie {
+    pub timescale: u32,
+
+    /// Duration in movie timescale, zero for fragmented movie
+    pub duration: u64,
+    pub tracks: Vec<Track>,
+
+    /// `trex` boxes of `mvex`, present only in fragmented movie
+    pub track_extends: Option<Vec<TrackExtends>>,
+}
+
+impl Movie {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let mut header = None;
+        let mut tracks = Vec::new();
+        let mut track_extends = None;
+        for child in bx.children() {
+            let child = child?;
+            match child.typ {
+                box_type::MVHD => {
+                    let r = &mut Reader::new(child.payload);
+                    let (version, _) = r.full_box(child.typ, 1)?;
+                    // creation and modification time
+                    r.versioned(version)?;
+                    r.versioned(version)?;
+                    let timescale = r.u32()?;
+                    header = Some((timescale, r.versioned(version)?));
+                }
+                box_type::TRAK => tracks.push(Track::parse(&child)?),
+                box_type::MVEX => {
+                    let mut list = Vec::new();
+                    for c in child.children() {
+                        let c = c?;
+                        if c.typ == box_type::TREX {
+                            list.push(TrackExtends::parse(&c)?);
+                        }
+                    }
+                    track_extends = Some(list);
+                }
+                _ => {}
+            }
+        }
+        let Some((timescale, duration)) = header else {
+            return Err(Error::MissingBox(box_type::MVHD));
+        };
+        Ok(Self {
+            timescale,
+            duration,
+            tracks,
+            track_extends,
+        })
+    }
+
+    #[inline]
+    pub fn is_fragmented(&self) -> bool {
+        self.track_extends.is_some()
+    }
+
+    pub fn track(&self, track_id: u32) -> Option<&Track> {
+        self.tracks.iter().find(|t| t.id == track_id)
+    }
+
+    pub fn track_extends(&self, track_id: u32) -> Option<&TrackExtends> {
+        self.track_extends
+            .as_ref()?
+            .iter()
+            .find(|t| t.track_id == track_id)
+    }
+}
+
+/// Track header, media header, handler and sample descriptions
+#[doc(alias = "trak")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Track {
+    pub id: u32,
+
+    /// Media timescale from `mdhd`
+    pub timescale: u32,
+
+    /// Media duration in media timescale
+    pub duration: u64,
+
+    /// ISO 639-2/T language code
+    pub language: String,
+    pub handler: FourCc,
+    pub sample_entries: Vec<SampleEntry>,
+}
+
+impl Track {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let mut id = None;
+        let mut media = None;
+        for child in bx.children() {
+            let child = child?;
+            match child.typ {
+                box_type::TKHD => {
+                    let r = &mut Reader::new(child.payload);
+                    let (version, _) = r.full_box(child.typ, 1)?;
+                    r.versioned(version)?;
+                    r.versioned(version)?;
+                    id = Some(r.u32()?);
+                }
+                box_type::MDIA => media = Some(child),
+                _ => {}
+            }
+        }
+        let Some(id) = id else {
+            return Err(Error::MissingBox(box_type::TKHD));
+        };
+        let Some(media) = media else {
+            return Err(Error::MissingBox(box_type::MDIA));
+        };
+
+        let mut header = None;
+        let mut handler = None;
+        let mut stsd = None;
+        for child in media.children() {
+            let child = child?;
+            match child.typ {
+                box_type::MDHD => {
+                    let r = &mut Reader::new(child.payload);
+                    let (version, _) = r.full_box(child.typ, 1)?;
+                    r.versioned(version)?;
+                    r.versioned(version)?;
+                    let timescale = r.u32()?;
+                    let duration = r.versioned(version)?;
+                    let lang = r.u16()?;
+                    let language = [10u16, 5, 0]
+                        .iter()
+                        .map(|shift| (((lang >> shift) & 0x1f) as u8 + 0x60) as char)
+                        .collect::<String>();
+                    header = Some((timescale, duration, language));
+                }
+                box_type::HDLR => {
+                    let r = &mut Reader::new(child.payload);
+                    r.full_box(child.typ, 0)?;
+                    // pre_defined
+                    r.skip(4)?;
+                    handler = Some(r.four_cc()?);
+                }
+                box_type::MINF => stsd = find_stsd(&child)?,
+                _ => {}
+            }
+        }
+        let Some((timescale, duration, language)) = header else {
+            return Err(Error::MissingBox(box_type::MDHD));
+        };
+        let Some(handler) = handler else {
+            return Err(Error::MissingBox(box_type::HDLR));
+        };
+        let Some(stsd) = stsd else {
+            return Err(Error::MissingBox(box_type::STSD));
+        };
+
+        let r = &mut Reader::new(stsd.payload);
+        r.full_box(stsd.typ, 1)?;
+        let count = r.u32()? as usize;
+        let mut sample_entries = Vec::with_capacity(count.min(r.buf.len() / 8));
+        for entry in crate::mp4::boxes(r.buf).take(count) {
+            sample_entries.push(SampleEntry::parse(&entry?, handler)?);
+        }
+        if sample_entries.len() != count {
+            return Err(Error::UnexpectedEof);
+        }
+
+        Ok(Self {
+            id,
+            timescale,
+            duration,
+            language,
+            handler,
+            sample_entries,
+        })
+    }
+}
+
+fn find_stsd<'a>(minf: &RawBox<'a>) -> Result<Option<RawBox<'a>>> {
+    for child in minf.children() {
+        let child = child?;
+        if child.typ == box_type::STBL {
+            for c in child.children() {
+                let c = c?;
+                if c.typ == box_type::STSD {
+                    return Ok(Some(c));
+                }
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Kind specific fields of sample entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SampleEntryKind {
+    Video {
+        width: u16,
+        height: u16,
+    },
+    Audio {
+        channel_count: u16,
+        sample_size: u16,
+        sample_rate: f64,
+    },
+    Other,
+}
+
+/// Codec sample entry of `stsd`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleEntry {
+    /// Codec, like `avc1`, `hvc1` or `mp4a`
+    pub format: FourCc,
+    pub data_ref_index: u16,
+    pub kind: SampleEntryKind,
+
+    /// Child boxes like `avcC`, `hvcC`, `esds`, `colr` or `pasp`
+    pub boxes: Vec<(FourCc, Vec<u8>)>,
+}
+
+impl SampleEntry {
+    pub fn parse(bx: &RawBox, handler: FourCc) -> Result<Self> {
+        let r = &mut Reader::new(bx.payload);
+        r.skip(6)?;
+        let data_ref_index = r.u16()?;
+        let kind = match handler {
+            handler::VIDEO => {
+                // pre_defined, reserved, pre_defined
+                r.skip(16)?;
+                let width = r.u16()?;
+                let height = r.u16()?;
+                // resolution, reserved, frame count, compressor name, depth, pre_defined
+                r.skip(50)?;
+                SampleEntryKind::Video { width, height }
+            }
+            handler::SOUND => {
+                let version = r.u16()?;
+                r.skip(6)?;
+                let channel_count = r.u16()?;
+                let sample_size = r.u16()?;
+                r.skip(4)?;
+                let sample_rate = r.u32()? as f64 / 65536.0;
+                // QuickTime sound sample description v1 and v2 extensions
+                match version {
+                    1 => r.skip(16)?,
+                    2 => r.skip(36)?,
+                    _ => {}
+                }
+                SampleEntryKind::Audio {
+                    channel_count,
+                    sample_size,
+                    sample_rate,
+                }
+            }
+            _ => SampleEntryKind::Other,
+        };
+        let mut boxes = Vec::new();
+        if kind != SampleEntryKind::Other {
+            for child in crate::mp4::boxes(r.buf) {
+                let child = child?;
+                boxes.push((child.typ, child.payload.to_vec()));
+            }
+        }
+        Ok(Self {
+            format: bx.typ,
+            data_ref_index,
+            kind,
+            boxes,
+        })
+    }
+
+    /// Payload of the first child box of type.
+    pub fn child(&self, typ: FourCc) -> Option<&[u8]> {
+        self.boxes
+            .iter()
+            .find(|(t, _)| *t == typ)
+            .map(|(_, payload)| payload.as_slice())
+    }
+
+    /// Parses `avcC` box.
+    #[cfg(feature = "nal")]
+    pub fn avc_config(&self) -> Option<crate::nal::Result<crate::nal::AvcConfig>> {
+        self.child(FourCc::from_bytes(b"avcC"))
+            .map(crate::nal::AvcConfig::parse)
+    }
+
+    /// Parses `hvcC` box.
+    #[cfg(feature = "nal")]
+    pub fn hevc_config(&self) -> Option<crate::nal::Result<crate::nal::HevcConfig>> {
+        self.child(FourCc::from_bytes(b"hvcC"))
+            .map(crate::nal::HevcConfig::parse)
+    }
+}
+
+/// Track fragment defaults
+#[doc(alias = "trex")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackExtends {
+    pub track_id: u32,
+    pub default_sample_desc_index: u32,
+    pub default_sample_duration: u32,
+    pub default_sample_size: u32,
+    pub default_sample_flags: SampleFlags,
+}
+
+impl TrackExtends {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let r = &mut Reader::new(bx.payload);
+        r.full_box(bx.typ, 0)?;
+        Ok(Self {
+            track_id: r.u32()?,
+            default_sample_desc_index: r.u32()?,
+            default_sample_duration: r.u32()?,
+            default_sample_size: r.u32()?,
+            default_sample_flags: SampleFlags(r.u32()?),
+        })
+    }
+}
@@ -0,0 +1,69 @@
+use crate::mp4::{Error, RawBox, Reader, Result};
+
+/// Segment index
+#[doc(alias = "sidx")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentIndex {
+    pub reference_id: u32,
+    pub timescale: u32,
+    pub earliest_presentation_time: u64,
+
+    /// Distance from the end of this box to the first referenced byte
+    pub first_offset: u64,
+    pub refs: Vec<SegmentRef>,
+}
+
+/// Reference to subsegment or another segment index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentRef {
+    /// Reference points to `sidx` rather than media
+    pub is_index: bool,
+    pub size: u32,
+    pub subsegment_duration: u32,
+    pub starts_with_sap: bool,
+    pub sap_type: u8,
+    pub sap_delta_time: u32,
+}
+
+impl SegmentIndex {
+    pub fn parse(bx: &RawBox) -> Result<Self> {
+        let r = &mut Reader::new(bx.payload);
+        let (version, _) = r.full_box(bx.typ, 1)?;
+        let reference_id = r.u32()?;
+        let timescale = r.u32()?;
+        let earliest_presentation_time = r.versioned(version)?;
+        let first_offset = r.versioned(version)?;
+        // reserved
+        r.skip(2)?;
+        let count = r.u16()? as usize;
+        if count * 12 > r.buf.len() {
+            return Err(Error::UnexpectedEof);
+        }
+        let mut refs = Vec::with_capacity(count);
+        for _ in 0..count {
+            let size = r.u32()?;
+            let subsegment_duration = r.u32()?;
+            let sap = r.u32()?;
+            refs.push(SegmentRef {
+                is_index: size >> 31 != 0,
+                size: size & 0x7fff_ffff,
+                subsegment_duration,
+                starts_with_sap: sap >> 31 != 0,
+                sap_type: ((sap >> 28) & 0b111) as u8,
+                sap_delta_time: sap & 0x0fff_ffff,
+            });
+        }
+        Ok(Self {
+            reference_id,
+            timescale,
+            earliest_presentation_time,
+            first_offset,
+            refs,
+        })
+    }
+
+    /// Sum of subsegment durations in timescale
+    pub fn duration(&self) -> u64 {
+        self.refs.iter().map(|r| r.subsegment_duration as u64).sum()
+    }
+}
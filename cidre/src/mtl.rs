@@ -31,6 +31,9 @@ pub use acceleration_structure_types::PackedF32x3;
 pub use acceleration_structure_types::PackedF32x4x3;
 
 mod pixel_format;
+pub use pixel_format::Channels as PixelFormatChannels;
+pub use pixel_format::ComponentType as PixelFormatComponentType;
+pub use pixel_format::Info as PixelFormatInfo;
 pub use pixel_format::PixelFormat;
 
 mod argument;
@@ -1,3 +1,5 @@
+use crate::mtl;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
 #[repr(usize)]
 pub enum PixelFormat {
@@ -141,7 +143,7 @@ pub enum PixelFormat {
     Rgba8SNorm = 72,
 
     /// Ordinary format with four 8-bit unsigned integer components in RGBA order.
-    #[doc(alias = "MTLPixelFormatRGBA8Sint")]
+    #[doc(alias = "MTLPixelFormatRGBA8Uint")]
     Rgba8UInt = 73,
 
     /// Ordinary format with four 8-bit signed integer components in RGBA order.
@@ -337,6 +339,22 @@ pub enum PixelFormat {
     #[doc(alias = "MTLPixelFormatBC3_RGBA_sRGB")]
     Bc3RgbaSrgb = 135,
 
+    /// Compressed format with one normalized unsigned integer component.
+    #[doc(alias = "MTLPixelFormatBC4_RUnorm")]
+    Bc4RUNorm = 140,
+
+    /// Compressed format with one normalized signed integer component.
+    #[doc(alias = "MTLPixelFormatBC4_RSnorm")]
+    Bc4RSNorm = 141,
+
+    /// Compressed format with two normalized unsigned integer components.
+    #[doc(alias = "MTLPixelFormatBC5_RGUnorm")]
+    Bc5RgUNorm = 142,
+
+    /// Compressed format with two normalized signed integer components.
+    #[doc(alias = "MTLPixelFormatBC5_RGSnorm")]
+    Bc5RgSNorm = 143,
+
     /// Compressed format with four floating-point components.
     #[doc(alias = "MTLPixelFormatBC6H_RGBFloat")]
     Bc6HRgbFloat = 150,
@@ -354,6 +372,85 @@ pub enum PixelFormat {
     #[doc(alias = "MTLPixelFormatBC7_RGBAUnorm_sRGB")]
     Bc7RgbaUNormSrgb = 153,
 
+    /// PVRTC-compressed format with three components at 2 bits per pixel.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGB_2BPP")]
+    PvrtcRgb2Bpp = 160,
+
+    /// PVRTC-compressed format with three components at 2 bits per pixel,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGB_2BPP_sRGB")]
+    PvrtcRgb2BppSrgb = 161,
+
+    /// PVRTC-compressed format with three components at 4 bits per pixel.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGB_4BPP")]
+    PvrtcRgb4Bpp = 162,
+
+    /// PVRTC-compressed format with three components at 4 bits per pixel,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGB_4BPP_sRGB")]
+    PvrtcRgb4BppSrgb = 163,
+
+    /// PVRTC-compressed format with four components at 2 bits per pixel.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGBA_2BPP")]
+    PvrtcRgba2Bpp = 164,
+
+    /// PVRTC-compressed format with four components at 2 bits per pixel,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGBA_2BPP_sRGB")]
+    PvrtcRgba2BppSrgb = 165,
+
+    /// PVRTC-compressed format with four components at 4 bits per pixel.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGBA_4BPP")]
+    PvrtcRgba4Bpp = 166,
+
+    /// PVRTC-compressed format with four components at 4 bits per pixel,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatPVRTC_RGBA_4BPP_sRGB")]
+    PvrtcRgba4BppSrgb = 167,
+
+    /// ETC2-compressed format with one 11-bit normalized unsigned integer component.
+    #[doc(alias = "MTLPixelFormatEAC_R11Unorm")]
+    EacR11UNorm = 170,
+
+    /// ETC2-compressed format with one 11-bit normalized signed integer component.
+    #[doc(alias = "MTLPixelFormatEAC_R11Snorm")]
+    EacR11SNorm = 172,
+
+    /// ETC2-compressed format with two 11-bit normalized unsigned integer components.
+    #[doc(alias = "MTLPixelFormatEAC_RG11Unorm")]
+    EacRg11UNorm = 174,
+
+    /// ETC2-compressed format with two 11-bit normalized signed integer components.
+    #[doc(alias = "MTLPixelFormatEAC_RG11Snorm")]
+    EacRg11SNorm = 176,
+
+    /// ETC2-compressed format with four 8-bit components.
+    #[doc(alias = "MTLPixelFormatEAC_RGBA8")]
+    EacRgba8 = 178,
+
+    /// ETC2-compressed format with four 8-bit components,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatEAC_RGBA8_sRGB")]
+    EacRgba8Srgb = 179,
+
+    /// ETC2-compressed format with three 8-bit components.
+    #[doc(alias = "MTLPixelFormatETC2_RGB8")]
+    Etc2Rgb8 = 180,
+
+    /// ETC2-compressed format with three 8-bit components,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatETC2_RGB8_sRGB")]
+    Etc2Rgb8Srgb = 181,
+
+    /// ETC2-compressed format with three 8-bit color components and a 1-bit alpha component.
+    #[doc(alias = "MTLPixelFormatETC2_RGB8A1")]
+    Etc2Rgb8A1 = 182,
+
+    /// ETC2-compressed format with three 8-bit color components and a 1-bit alpha component,
+    /// with conversion between sRGB and linear space.
+    #[doc(alias = "MTLPixelFormatETC2_RGB8A1_sRGB")]
+    Etc2Rgb8A1Srgb = 183,
+
     /// ASTC-compressed format with low-dynamic-range content,
     /// conversion between sRGB and linear space, a block width of 4, and a block height of 4.
     #[doc(alias = "MTLPixelFormatASTC_4x4_sRGB")]
@@ -498,3 +595,682 @@ pub enum PixelFormat {
     #[doc(alias = "MTLPixelFormatX24_Stencil8")]
     X24Stencil8 = 262,
 }
+
+impl PixelFormat {
+    /// Static description of the format, `None` for [`PixelFormat::Invalid`].
+    ///
+    /// ```
+    /// use cidre::mtl;
+    ///
+    /// const INFO: mtl::PixelFormatInfo = mtl::PixelFormat::Bc1Rgba.info().unwrap();
+    /// assert_eq!(INFO.block_bytes, 8);
+    /// assert_eq!((INFO.block_width, INFO.block_height), (4, 4));
+    /// assert!(INFO.is_compressed());
+    /// ```
+    pub const fn info(self) -> Option<Info> {
+        Some(match self {
+            Self::Invalid => return None,
+            Self::A8UNorm => Info::plain(1, Channels::A, ComponentType::UNorm),
+            Self::R8UNorm => Info::plain(1, Channels::R, ComponentType::UNorm),
+            Self::R8UNormSrgb => Info::plain(1, Channels::R, ComponentType::UNorm).srgb(),
+            Self::R8SNorm => Info::plain(1, Channels::R, ComponentType::SNorm),
+            Self::R8UInt => Info::plain(1, Channels::R, ComponentType::UInt),
+            Self::R8SInt => Info::plain(1, Channels::R, ComponentType::SInt),
+            Self::R16UNorm => Info::plain(2, Channels::R, ComponentType::UNorm),
+            Self::R16SNorm => Info::plain(2, Channels::R, ComponentType::SNorm),
+            Self::R16UInt => Info::plain(2, Channels::R, ComponentType::UInt),
+            Self::R16SInt => Info::plain(2, Channels::R, ComponentType::SInt),
+            Self::R16Float => Info::plain(2, Channels::R, ComponentType::Float),
+            Self::Rg8UNorm => Info::plain(2, Channels::Rg, ComponentType::UNorm),
+            Self::Rg8UNormSrgb => Info::plain(2, Channels::Rg, ComponentType::UNorm).srgb(),
+            Self::Rg8SNorm => Info::plain(2, Channels::Rg, ComponentType::SNorm),
+            Self::Rg8UInt => Info::plain(2, Channels::Rg, ComponentType::UInt),
+            Self::Rg8SInt => Info::plain(2, Channels::Rg, ComponentType::SInt),
+            Self::B5G6R5UNorm => Info::packed(2, Channels::Bgr, ComponentType::UNorm),
+            Self::A1Bgr5UNorm | Self::Abgr4UNorm => {
+                Info::packed(2, Channels::Abgr, ComponentType::UNorm)
+            }
+            Self::Bgr5A1UNorm => Info::packed(2, Channels::Bgra, ComponentType::UNorm),
+            Self::R32UInt => Info::plain(4, Channels::R, ComponentType::UInt),
+            Self::R32SInt => Info::plain(4, Channels::R, ComponentType::SInt),
+            Self::R32Float => Info::plain(4, Channels::R, ComponentType::Float),
+            Self::Rg16UNorm => Info::plain(4, Channels::Rg, ComponentType::UNorm),
+            Self::Rg16SNorm => Info::plain(4, Channels::Rg, ComponentType::SNorm),
+            Self::Rg16UInt => Info::plain(4, Channels::Rg, ComponentType::UInt),
+            Self::Rg16SInt => Info::plain(4, Channels::Rg, ComponentType::SInt),
+            Self::Rg16Float => Info::plain(4, Channels::Rg, ComponentType::Float),
+            Self::Rgba8UNorm => Info::plain(4, Channels::Rgba, ComponentType::UNorm),
+            Self::Rgba8UNormSrgb => Info::plain(4, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Rgba8SNorm => Info::plain(4, Channels::Rgba, ComponentType::SNorm),
+            Self::Rgba8UInt => Info::plain(4, Channels::Rgba, ComponentType::UInt),
+            Self::Rgba8SInt => Info::plain(4, Channels::Rgba, ComponentType::SInt),
+            Self::Bgra8UNorm => Info::plain(4, Channels::Bgra, ComponentType::UNorm),
+            Self::Bgra8UNormSrgb => Info::plain(4, Channels::Bgra, ComponentType::UNorm).srgb(),
+            Self::Rgb10A2UNorm => Info::packed(4, Channels::Rgba, ComponentType::UNorm),
+            Self::Rgb10A2UInt => Info::packed(4, Channels::Rgba, ComponentType::UInt),
+            Self::Rg11B10Float | Self::Rgb9E5Float => {
+                Info::packed(4, Channels::Rgb, ComponentType::UFloat)
+            }
+            Self::Bgr10A2UNorm => Info::packed(4, Channels::Bgra, ComponentType::UNorm),
+            Self::Bgr10Xr => Info::packed(4, Channels::Bgr, ComponentType::ExtendedRange),
+            Self::Bgr10XrSrgb => {
+                Info::packed(4, Channels::Bgr, ComponentType::ExtendedRange).srgb()
+            }
+            Self::Rg32UInt => Info::plain(8, Channels::Rg, ComponentType::UInt),
+            Self::Rg32SInt => Info::plain(8, Channels::Rg, ComponentType::SInt),
+            Self::Rg32Float => Info::plain(8, Channels::Rg, ComponentType::Float),
+            Self::Rgba16UNorm => Info::plain(8, Channels::Rgba, ComponentType::UNorm),
+            Self::Rgba16SNorm => Info::plain(8, Channels::Rgba, ComponentType::SNorm),
+            Self::Rgba16UInt => Info::plain(8, Channels::Rgba, ComponentType::UInt),
+            Self::Rgba16SInt => Info::plain(8, Channels::Rgba, ComponentType::SInt),
+            Self::Rgba16Float => Info::plain(8, Channels::Rgba, ComponentType::Float),
+            Self::Bgra10Xr => Info::packed(8, Channels::Bgra, ComponentType::ExtendedRange),
+            Self::Bgra10XrSrgb => {
+                Info::packed(8, Channels::Bgra, ComponentType::ExtendedRange).srgb()
+            }
+            Self::Rgba32UInt => Info::plain(16, Channels::Rgba, ComponentType::UInt),
+            Self::Rgba32SInt => Info::plain(16, Channels::Rgba, ComponentType::SInt),
+            Self::Rgba32Float => Info::plain(16, Channels::Rgba, ComponentType::Float),
+            Self::Bc1Rgba | Self::PvrtcRgba4Bpp | Self::Etc2Rgb8A1 => {
+                Info::block(8, 4, 4, Channels::Rgba, ComponentType::UNorm)
+            }
+            Self::Bc1RgbaSrgb | Self::PvrtcRgba4BppSrgb | Self::Etc2Rgb8A1Srgb => {
+                Info::block(8, 4, 4, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Bc2Rgba
+            | Self::Bc3Rgba
+            | Self::Bc7RgbaUNorm
+            | Self::EacRgba8
+            | Self::Astc4x4Ldr => Info::block(16, 4, 4, Channels::Rgba, ComponentType::UNorm),
+            Self::Bc2RgbaSrgb
+            | Self::Bc3RgbaSrgb
+            | Self::Bc7RgbaUNormSrgb
+            | Self::EacRgba8Srgb
+            | Self::Astc4x4Srgb => {
+                Info::block(16, 4, 4, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Bc4RUNorm | Self::EacR11UNorm => {
+                Info::block(8, 4, 4, Channels::R, ComponentType::UNorm)
+            }
+            Self::Bc4RSNorm | Self::EacR11SNorm => {
+                Info::block(8, 4, 4, Channels::R, ComponentType::SNorm)
+            }
+            Self::Bc5RgUNorm | Self::EacRg11UNorm => {
+                Info::block(16, 4, 4, Channels::Rg, ComponentType::UNorm)
+            }
+            Self::Bc5RgSNorm | Self::EacRg11SNorm => {
+                Info::block(16, 4, 4, Channels::Rg, ComponentType::SNorm)
+            }
+            Self::Bc6HRgbFloat => Info::block(16, 4, 4, Channels::Rgb, ComponentType::Float),
+            Self::Bc6HRgbUFloat => Info::block(16, 4, 4, Channels::Rgb, ComponentType::UFloat),
+            Self::PvrtcRgb2Bpp => Info::block(8, 8, 4, Channels::Rgb, ComponentType::UNorm),
+            Self::PvrtcRgb2BppSrgb => {
+                Info::block(8, 8, 4, Channels::Rgb, ComponentType::UNorm).srgb()
+            }
+            Self::PvrtcRgb4Bpp | Self::Etc2Rgb8 => {
+                Info::block(8, 4, 4, Channels::Rgb, ComponentType::UNorm)
+            }
+            Self::PvrtcRgb4BppSrgb | Self::Etc2Rgb8Srgb => {
+                Info::block(8, 4, 4, Channels::Rgb, ComponentType::UNorm).srgb()
+            }
+            Self::PvrtcRgba2Bpp => Info::block(8, 8, 4, Channels::Rgba, ComponentType::UNorm),
+            Self::PvrtcRgba2BppSrgb => {
+                Info::block(8, 8, 4, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc5x4Srgb => Info::block(16, 5, 4, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc5x5Srgb => Info::block(16, 5, 5, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc6x5Srgb => Info::block(16, 6, 5, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc6x6Srgb => Info::block(16, 6, 6, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc8x5Srgb => Info::block(16, 8, 5, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc8x6Srgb => Info::block(16, 8, 6, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc8x8Srgb => Info::block(16, 8, 8, Channels::Rgba, ComponentType::UNorm).srgb(),
+            Self::Astc10x5Srgb => {
+                Info::block(16, 10, 5, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc10x6Srgb => {
+                Info::block(16, 10, 6, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc10x8Srgb => {
+                Info::block(16, 10, 8, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc10x10Srgb => {
+                Info::block(16, 10, 10, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc12x10Srgb => {
+                Info::block(16, 12, 10, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc12x12Srgb => {
+                Info::block(16, 12, 12, Channels::Rgba, ComponentType::UNorm).srgb()
+            }
+            Self::Astc5x4Ldr => Info::block(16, 5, 4, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc5x5Ldr => Info::block(16, 5, 5, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc6x5Ldr => Info::block(16, 6, 5, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc6x6Ldr => Info::block(16, 6, 6, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc8x5Ldr => Info::block(16, 8, 5, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc8x6Ldr => Info::block(16, 8, 6, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc8x8Ldr => Info::block(16, 8, 8, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc10x5Ldr => Info::block(16, 10, 5, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc10x6Ldr => Info::block(16, 10, 6, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc10x8Ldr => Info::block(16, 10, 8, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc10x10Ldr => Info::block(16, 10, 10, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc12x10Ldr => Info::block(16, 12, 10, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc12x12Ldr => Info::block(16, 12, 12, Channels::Rgba, ComponentType::UNorm),
+            Self::Astc4x4Hdr => Info::block(16, 4, 4, Channels::Rgba, ComponentType::Float),
+            Self::Astc5x4Hdr => Info::block(16, 5, 4, Channels::Rgba, ComponentType::Float),
+            Self::Astc5x5Hdr => Info::block(16, 5, 5, Channels::Rgba, ComponentType::Float),
+            Self::Astc6x5Hdr => Info::block(16, 6, 5, Channels::Rgba, ComponentType::Float),
+            Self::Astc6x6Hdr => Info::block(16, 6, 6, Channels::Rgba, ComponentType::Float),
+            Self::Astc8x5Hdr => Info::block(16, 8, 5, Channels::Rgba, ComponentType::Float),
+            Self::Astc8x6Hdr => Info::block(16, 8, 6, Channels::Rgba, ComponentType::Float),
+            Self::Astc8x8Hdr => Info::block(16, 8, 8, Channels::Rgba, ComponentType::Float),
+            Self::Astc10x5Hdr => Info::block(16, 10, 5, Channels::Rgba, ComponentType::Float),
+            Self::Astc10x6Hdr => Info::block(16, 10, 6, Channels::Rgba, ComponentType::Float),
+            Self::Astc10x8Hdr => Info::block(16, 10, 8, Channels::Rgba, ComponentType::Float),
+            Self::Astc10x10Hdr => Info::block(16, 10, 10, Channels::Rgba, ComponentType::Float),
+            Self::Astc12x10Hdr => Info::block(16, 12, 10, Channels::Rgba, ComponentType::Float),
+            Self::Asrc12x12Hdr => Info::block(16, 12, 12, Channels::Rgba, ComponentType::Float),
+            Self::Gbgr422 | Self::Bgrg422 => {
+                Info::block(4, 2, 1, Channels::Rgb, ComponentType::UNorm)
+            }
+            Self::Depth16Unorm => Info::plain(2, Channels::Depth, ComponentType::UNorm),
+            Self::Depth32Float => Info::plain(4, Channels::Depth, ComponentType::Float),
+            Self::Stencil8 => Info::plain(1, Channels::Stencil, ComponentType::UInt),
+            Self::Depth24UnormStencil8 => {
+                Info::plain(4, Channels::DepthStencil, ComponentType::UNorm)
+            }
+            Self::Depth32FloatStencil8 => {
+                Info::plain(8, Channels::DepthStencil, ComponentType::Float)
+            }
+            Self::X32Stencil8 => Info::plain(8, Channels::Stencil, ComponentType::UInt),
+            Self::X24Stencil8 => Info::plain(4, Channels::Stencil, ComponentType::UInt),
+        })
+    }
+
+    /// Linear counterpart of an sRGB format, `self` otherwise.
+    ///
+    /// ASTC sRGB formats map to their LDR variants.
+    pub const fn linear(self) -> Self {
+        match self {
+            Self::R8UNormSrgb => Self::R8UNorm,
+            Self::Rg8UNormSrgb => Self::Rg8UNorm,
+            Self::Rgba8UNormSrgb => Self::Rgba8UNorm,
+            Self::Bgra8UNormSrgb => Self::Bgra8UNorm,
+            Self::Bgr10XrSrgb => Self::Bgr10Xr,
+            Self::Bgra10XrSrgb => Self::Bgra10Xr,
+            Self::Bc1RgbaSrgb => Self::Bc1Rgba,
+            Self::Bc2RgbaSrgb => Self::Bc2Rgba,
+            Self::Bc3RgbaSrgb => Self::Bc3Rgba,
+            Self::Bc7RgbaUNormSrgb => Self::Bc7RgbaUNorm,
+            Self::PvrtcRgb2BppSrgb => Self::PvrtcRgb2Bpp,
+            Self::PvrtcRgb4BppSrgb => Self::PvrtcRgb4Bpp,
+            Self::PvrtcRgba2BppSrgb => Self::PvrtcRgba2Bpp,
+            Self::PvrtcRgba4BppSrgb => Self::PvrtcRgba4Bpp,
+            Self::EacRgba8Srgb => Self::EacRgba8,
+            Self::Etc2Rgb8Srgb => Self::Etc2Rgb8,
+            Self::Etc2Rgb8A1Srgb => Self::Etc2Rgb8A1,
+            Self::Astc4x4Srgb => Self::Astc4x4Ldr,
+            Self::Astc5x4Srgb => Self::Astc5x4Ldr,
+            Self::Astc5x5Srgb => Self::Astc5x5Ldr,
+            Self::Astc6x5Srgb => Self::Astc6x5Ldr,
+            Self::Astc6x6Srgb => Self::Astc6x6Ldr,
+            Self::Astc8x5Srgb => Self::Astc8x5Ldr,
+            Self::Astc8x6Srgb => Self::Astc8x6Ldr,
+            Self::Astc8x8Srgb => Self::Astc8x8Ldr,
+            Self::Astc10x5Srgb => Self::Astc10x5Ldr,
+            Self::Astc10x6Srgb => Self::Astc10x6Ldr,
+            Self::Astc10x8Srgb => Self::Astc10x8Ldr,
+            Self::Astc10x10Srgb => Self::Astc10x10Ldr,
+            Self::Astc12x10Srgb => Self::Astc12x10Ldr,
+            Self::Astc12x12Srgb => Self::Astc12x12Ldr,
+            _ => self,
+        }
+    }
+
+    /// sRGB counterpart of a linear format, if there is one.
+    pub const fn srgb(self) -> Option<Self> {
+        match self {
+            Self::R8UNorm => Some(Self::R8UNormSrgb),
+            Self::Rg8UNorm => Some(Self::Rg8UNormSrgb),
+            Self::Rgba8UNorm => Some(Self::Rgba8UNormSrgb),
+            Self::Bgra8UNorm => Some(Self::Bgra8UNormSrgb),
+            Self::Bgr10Xr => Some(Self::Bgr10XrSrgb),
+            Self::Bgra10Xr => Some(Self::Bgra10XrSrgb),
+            Self::Bc1Rgba => Some(Self::Bc1RgbaSrgb),
+            Self::Bc2Rgba => Some(Self::Bc2RgbaSrgb),
+            Self::Bc3Rgba => Some(Self::Bc3RgbaSrgb),
+            Self::Bc7RgbaUNorm => Some(Self::Bc7RgbaUNormSrgb),
+            Self::PvrtcRgb2Bpp => Some(Self::PvrtcRgb2BppSrgb),
+            Self::PvrtcRgb4Bpp => Some(Self::PvrtcRgb4BppSrgb),
+            Self::PvrtcRgba2Bpp => Some(Self::PvrtcRgba2BppSrgb),
+            Self::PvrtcRgba4Bpp => Some(Self::PvrtcRgba4BppSrgb),
+            Self::EacRgba8 => Some(Self::EacRgba8Srgb),
+            Self::Etc2Rgb8 => Some(Self::Etc2Rgb8Srgb),
+            Self::Etc2Rgb8A1 => Some(Self::Etc2Rgb8A1Srgb),
+            Self::Astc4x4Ldr => Some(Self::Astc4x4Srgb),
+            Self::Astc5x4Ldr => Some(Self::Astc5x4Srgb),
+            Self::Astc5x5Ldr => Some(Self::Astc5x5Srgb),
+            Self::Astc6x5Ldr => Some(Self::Astc6x5Srgb),
+            Self::Astc6x6Ldr => Some(Self::Astc6x6Srgb),
+            Self::Astc8x5Ldr => Some(Self::Astc8x5Srgb),
+            Self::Astc8x6Ldr => Some(Self::Astc8x6Srgb),
+            Self::Astc8x8Ldr => Some(Self::Astc8x8Srgb),
+            Self::Astc10x5Ldr => Some(Self::Astc10x5Srgb),
+            Self::Astc10x6Ldr => Some(Self::Astc10x6Srgb),
+            Self::Astc10x8Ldr => Some(Self::Astc10x8Srgb),
+            Self::Astc10x10Ldr => Some(Self::Astc10x10Srgb),
+            Self::Astc12x10Ldr => Some(Self::Astc12x10Srgb),
+            Self::Astc12x12Ldr => Some(Self::Astc12x12Srgb),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub const fn is_srgb(self) -> bool {
+        match self.info() {
+            Some(info) => info.srgb,
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub const fn is_depth(self) -> bool {
+        match self.info() {
+            Some(info) => info.is_depth(),
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub const fn is_stencil(self) -> bool {
+        match self.info() {
+            Some(info) => info.is_stencil(),
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub const fn is_compressed(self) -> bool {
+        match self.info() {
+            Some(info) => info.is_compressed(),
+            None => false,
+        }
+    }
+
+    /// Format class usable as a color, depth or stencil attachment.
+    ///
+    /// Some formats are renderable only on particular GPU families,
+    /// check the Metal feature set tables for the device.
+    pub const fn is_renderable(self) -> bool {
+        match self {
+            Self::A8UNorm | Self::X24Stencil8 | Self::X32Stencil8 => false,
+            _ => match self.info() {
+                Some(info) => !info.is_compressed(),
+                None => false,
+            },
+        }
+    }
+
+    /// Format class supporting blending when used as a color attachment,
+    /// integer, depth and stencil formats don't blend.
+    pub const fn is_blendable(self) -> bool {
+        match self.info() {
+            Some(info) => {
+                self.is_renderable()
+                    && !info.is_depth()
+                    && !info.is_stencil()
+                    && !info.ty.is_integer()
+            }
+            None => false,
+        }
+    }
+
+    /// Tightly packed bytes of a row of blocks covering `width` pixels.
+    ///
+    /// PVRTC images take at least 2x2 blocks. Linear textures may need the row
+    /// padded to the device alignment.
+    ///
+    /// ```
+    /// use cidre::mtl;
+    ///
+    /// assert_eq!(mtl::PixelFormat::Bgra8UNorm.bytes_per_row(1920), 7680);
+    /// assert_eq!(mtl::PixelFormat::Astc6x6Ldr.bytes_per_row(100), 17 * 16);
+    /// ```
+    pub const fn bytes_per_row(self, width: usize) -> usize {
+        match self.info() {
+            Some(info) => self.blocks(width, info.block_width) * info.block_bytes as usize,
+            None => 0,
+        }
+    }
+
+    /// Bytes of one 2D image or one slice of a 3D texture.
+    pub const fn bytes_per_image(self, width: usize, height: usize) -> usize {
+        match self.info() {
+            Some(info) => self.bytes_per_row(width) * self.blocks(height, info.block_height),
+            None => 0,
+        }
+    }
+
+    /// Blocks covering `pixels`, PVRTC needs two of them in each direction
+    const fn blocks(self, pixels: usize, block: u8) -> usize {
+        let n = pixels.div_ceil(block as usize);
+        let pvrtc = matches!(
+            self,
+            Self::PvrtcRgb2Bpp
+                | Self::PvrtcRgb2BppSrgb
+                | Self::PvrtcRgb4Bpp
+                | Self::PvrtcRgb4BppSrgb
+                | Self::PvrtcRgba2Bpp
+                | Self::PvrtcRgba2BppSrgb
+                | Self::PvrtcRgba4Bpp
+                | Self::PvrtcRgba4BppSrgb
+        );
+        if pvrtc && n == 1 { 2 } else { n }
+    }
+
+    /// Bytes of mipmap `level` of a texture with base `size`, all slices of a 3D texture included.
+    pub const fn mipmap_level_bytes(self, size: mtl::Size, level: usize) -> usize {
+        let size = size.mipmap_level(level);
+        self.bytes_per_image(size.width, size.height) * size.depth
+    }
+
+    /// Bytes of the first `mipmap_levels` levels of a texture with base `size`.
+    ///
+    /// ```
+    /// use cidre::mtl;
+    ///
+    /// let size = mtl::Size::_2d(4, 4);
+    /// let levels = size.mipmap_level_count();
+    /// assert_eq!(levels, 3);
+    /// assert_eq!(mtl::PixelFormat::R8UNorm.texture_bytes(size, levels), 16 + 4 + 1);
+    /// ```
+    pub const fn texture_bytes(self, size: mtl::Size, mipmap_levels: usize) -> usize {
+        let mut bytes = 0;
+        let mut level = 0;
+        while level < mipmap_levels {
+            bytes += self.mipmap_level_bytes(size, level);
+            level += 1;
+        }
+        bytes
+    }
+}
+
+/// Description of a [`PixelFormat`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Info {
+    /// Bytes of one block, for uncompressed formats a block is one pixel.
+    ///
+    /// For `Depth32FloatStencil8` and `X32Stencil8` this is the allocated size of 8 bytes.
+    pub block_bytes: u8,
+    pub block_width: u8,
+    pub block_height: u8,
+    pub channels: Channels,
+    pub ty: ComponentType,
+    /// Components are converted between sRGB and linear space.
+    pub srgb: bool,
+    /// Components are packed into bit fields of a single value.
+    pub packed: bool,
+}
+
+impl Info {
+    const fn plain(bytes: u8, channels: Channels, ty: ComponentType) -> Self {
+        Self::block(bytes, 1, 1, channels, ty)
+    }
+
+    const fn packed(bytes: u8, channels: Channels, ty: ComponentType) -> Self {
+        let mut info = Self::plain(bytes, channels, ty);
+        info.packed = true;
+        info
+    }
+
+    const fn block(
+        bytes: u8,
+        width: u8,
+        height: u8,
+        channels: Channels,
+        ty: ComponentType,
+    ) -> Self {
+        Self {
+            block_bytes: bytes,
+            block_width: width,
+            block_height: height,
+            channels,
+            ty,
+            srgb: false,
+            packed: false,
+        }
+    }
+
+    const fn srgb(mut self) -> Self {
+        self.srgb = true;
+        self
+    }
+
+    /// Number of components, two for combined depth and stencil formats.
+    #[inline]
+    pub const fn components(&self) -> usize {
+        self.channels.count()
+    }
+
+    /// Bits per pixel of uncompressed formats.
+    #[inline]
+    pub const fn bits_per_pixel(&self) -> Option<usize> {
+        if self.is_compressed() {
+            None
+        } else {
+            Some(self.block_bytes as usize * 8)
+        }
+    }
+
+    /// Block compressed, including the 2x1 blocks of 422 formats.
+    #[inline]
+    pub const fn is_compressed(&self) -> bool {
+        self.block_width > 1 || self.block_height > 1
+    }
+
+    #[inline]
+    pub const fn is_depth(&self) -> bool {
+        matches!(self.channels, Channels::Depth | Channels::DepthStencil)
+    }
+
+    #[inline]
+    pub const fn is_stencil(&self) -> bool {
+        matches!(self.channels, Channels::Stencil | Channels::DepthStencil)
+    }
+}
+
+/// Components of a [`PixelFormat`] in memory order, lowest bits first for packed formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channels {
+    A,
+    R,
+    Rg,
+    Rgb,
+    Rgba,
+    Bgr,
+    Bgra,
+    Abgr,
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Channels {
+    #[inline]
+    pub const fn count(self) -> usize {
+        match self {
+            Self::A | Self::R | Self::Depth | Self::Stencil => 1,
+            Self::Rg | Self::DepthStencil => 2,
+            Self::Rgb | Self::Bgr => 3,
+            Self::Rgba | Self::Bgra | Self::Abgr => 4,
+        }
+    }
+}
+
+/// How the components of a [`PixelFormat`] are stored and read by shaders.
+///
+/// Combined depth and stencil formats report the depth type, stencil is always `UInt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentType {
+    /// Unsigned integer read as float in 0..=1
+    UNorm,
+    /// Signed integer read as float in -1..=1
+    SNorm,
+    UInt,
+    SInt,
+    Float,
+    /// Float without a sign bit
+    UFloat,
+    /// Fixed point values in about -0.75..1.25 of the XR formats
+    ExtendedRange,
+}
+
+impl ComponentType {
+    #[inline]
+    pub const fn is_integer(self) -> bool {
+        matches!(self, Self::UInt | Self::SInt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::mtl::{PixelFormat, PixelFormatChannels, PixelFormatComponentType, Size};
+
+    #[test]
+    fn info() {
+        assert!(PixelFormat::Invalid.info().is_none());
+        assert!(!PixelFormat::Invalid.is_renderable());
+        assert_eq!(PixelFormat::Invalid.bytes_per_row(10), 0);
+
+        let info = PixelFormat::Rgba16Float.info().unwrap();
+        assert_eq!(info.bits_per_pixel(), Some(64));
+        assert_eq!(info.components(), 4);
+        assert_eq!(info.ty, PixelFormatComponentType::Float);
+        assert!(!info.is_compressed() && !info.packed && !info.srgb);
+
+        let info = PixelFormat::B5G6R5UNorm.info().unwrap();
+        assert_eq!(info.channels, PixelFormatChannels::Bgr);
+        assert!(info.packed);
+        assert_eq!(info.bits_per_pixel(), Some(16));
+
+        let info = PixelFormat::Astc10x6Hdr.info().unwrap();
+        assert_eq!(
+            (info.block_width, info.block_height, info.block_bytes),
+            (10, 6, 16)
+        );
+        assert_eq!(info.bits_per_pixel(), None);
+        assert_eq!(info.ty, PixelFormatComponentType::Float);
+
+        let info = PixelFormat::Depth32FloatStencil8.info().unwrap();
+        assert!(info.is_depth() && info.is_stencil());
+        assert_eq!(info.components(), 2);
+        assert!(PixelFormat::X32Stencil8.is_stencil());
+        assert!(!PixelFormat::X32Stencil8.is_depth());
+        assert!(!PixelFormat::X32Stencil8.is_renderable());
+        assert!(!PixelFormat::A8UNorm.is_renderable());
+        assert!(PixelFormat::Depth16Unorm.is_renderable());
+        assert!(!PixelFormat::Depth16Unorm.is_blendable());
+
+        assert!(PixelFormat::Gbgr422.is_compressed());
+        assert!(PixelFormat::Etc2Rgb8A1.is_compressed());
+        assert!(!PixelFormat::Bc7RgbaUNorm.is_renderable());
+        assert!(PixelFormat::Bgra8UNormSrgb.is_blendable());
+        assert!(PixelFormat::Rgb9E5Float.is_blendable());
+        assert!(PixelFormat::Rg32UInt.is_renderable());
+        assert!(!PixelFormat::Rg32UInt.is_blendable());
+    }
+
+    #[test]
+    fn srgb() {
+        assert!(PixelFormat::Bgra8UNormSrgb.is_srgb());
+        assert!(!PixelFormat::Bgra8UNorm.is_srgb());
+        assert_eq!(
+            PixelFormat::Bgra8UNormSrgb.linear(),
+            PixelFormat::Bgra8UNorm
+        );
+        assert_eq!(PixelFormat::Bgra8UNorm.linear(), PixelFormat::Bgra8UNorm);
+        assert_eq!(PixelFormat::Astc8x5Srgb.linear(), PixelFormat::Astc8x5Ldr);
+        assert_eq!(
+            PixelFormat::Astc8x5Ldr.srgb(),
+            Some(PixelFormat::Astc8x5Srgb)
+        );
+        assert_eq!(PixelFormat::Astc8x5Hdr.srgb(), None);
+        assert_eq!(PixelFormat::Rgba16Float.srgb(), None);
+
+        // every sRGB format pairs up with a linear one of the same layout
+        for v in 0..=600 {
+            let Some(f) = format(v) else { continue };
+            if !f.is_srgb() {
+                continue;
+            }
+            let linear = f.linear();
+            assert_ne!(linear, f);
+            assert_eq!(linear.srgb(), Some(f));
+            let (a, b) = (f.info().unwrap(), linear.info().unwrap());
+            assert_eq!(
+                (a.block_bytes, a.channels, a.ty),
+                (b.block_bytes, b.channels, b.ty)
+            );
+        }
+    }
+
+    #[test]
+    fn sizes() {
+        let f = PixelFormat::Bc1Rgba;
+        assert_eq!(f.bytes_per_row(1), 8);
+        assert_eq!(f.bytes_per_row(5), 16);
+        assert_eq!(f.bytes_per_image(5, 5), 32);
+
+        let size = Size::_3d(8, 4, 2);
+        assert_eq!(size.mipmap_level_count(), 4);
+        assert_eq!(size.mipmap_level(1), Size::_3d(4, 2, 1));
+        assert_eq!(size.mipmap_level(3), Size::_3d(1, 1, 1));
+        assert_eq!(size.mipmap_level(100), Size::_3d(1, 1, 1));
+        assert_eq!(Size::default().mipmap_level_count(), 0);
+        assert_eq!(Size::_3d(8, 0, 0).mipmap_level(2), Size::_3d(2, 0, 0));
+        assert_eq!(
+            PixelFormat::Rgba8UNorm.mipmap_level_bytes(Size::default(), 0),
+            0
+        );
+
+        let f = PixelFormat::Rgba8UNorm;
+        assert_eq!(f.mipmap_level_bytes(size, 0), 8 * 4 * 2 * 4);
+        assert_eq!(f.mipmap_level_bytes(size, 1), 4 * 2 * 4);
+        assert_eq!(f.texture_bytes(size, 4), (64 + 8 + 2 + 1) * 4);
+
+        // compressed levels never get smaller than a block
+        let f = PixelFormat::Astc4x4Ldr;
+        let size = Size::_2d(16, 16);
+        let levels: Vec<_> = (0..size.mipmap_level_count())
+            .map(|l| f.mipmap_level_bytes(size, l))
+            .collect();
+        assert_eq!(levels, [256, 64, 16, 16, 16]);
+
+        // PVRTC takes at least 2x2 blocks
+        let f = PixelFormat::PvrtcRgba4Bpp;
+        assert_eq!(f.bytes_per_row(1), 16);
+        assert_eq!(f.bytes_per_image(1, 1), 32);
+        assert_eq!(f.bytes_per_image(16, 16), 128);
+        assert_eq!(PixelFormat::PvrtcRgb2Bpp.bytes_per_image(8, 8), 32);
+        assert_eq!(f.bytes_per_row(0), 0);
+        assert_eq!(
+            PixelFormat::Gbgr422.bytes_per_image(1920, 1080),
+            1920 * 2 * 1080
+        );
+    }
+
+    fn format(value: usize) -> Option<PixelFormat> {
+        const ALL: &[usize] = &[
+            1, 10, 11, 12, 13, 14, 20, 22, 23, 24, 25, 30, 31, 32, 33, 34, 40, 41, 42, 43, 53, 54,
+            55, 60, 62, 63, 64, 65, 70, 71, 72, 73, 74, 80, 81, 90, 91, 92, 93, 94, 103, 104, 105,
+            110, 112, 113, 114, 115, 123, 124, 125, 130, 131, 132, 133, 134, 135, 140, 141, 142,
+            143, 150, 151, 152, 153, 160, 161, 162, 163, 164, 165, 166, 167, 170, 172, 174, 176,
+            178, 179, 180, 181, 182, 183, 186, 187, 188, 189, 190, 192, 193, 194, 195, 196, 197,
+            198, 199, 200, 204, 205, 206, 207, 208, 210, 211, 212, 213, 214, 215, 216, 217, 218,
+            222, 223, 224, 225, 226, 228, 229, 230, 231, 232, 233, 234, 235, 236, 240, 241, 250,
+            252, 253, 255, 260, 261, 262, 552, 553, 554, 555,
+        ];
+        // only values listed in the enum are transmuted
+        ALL.contains(&value)
+            .then(|| unsafe { std::mem::transmute::<usize, PixelFormat>(value) })
+    }
+}
@@ -28,6 +28,51 @@ impl Size {
             depth: 1,
         }
     }
+
+    #[inline]
+    pub const fn _3d(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// Size of mip `level`, every dimension is halved per level down to 1,
+    /// empty dimensions stay 0.
+    #[inline]
+    pub const fn mipmap_level(&self, level: usize) -> Self {
+        const fn dim(v: usize, level: usize) -> usize {
+            if v == 0 {
+                return 0;
+            }
+            match v.checked_shr(level as u32) {
+                Some(v) if v > 0 => v,
+                _ => 1,
+            }
+        }
+        Self {
+            width: dim(self.width, level),
+            height: dim(self.height, level),
+            depth: dim(self.depth, level),
+        }
+    }
+
+    /// Number of levels of a full mipmap chain down to 1x1x1.
+    #[inline]
+    pub const fn mipmap_level_count(&self) -> usize {
+        let mut max = self.width;
+        if self.height > max {
+            max = self.height;
+        }
+        if self.depth > max {
+            max = self.depth;
+        }
+        if max == 0 {
+            return 0;
+        }
+        (usize::BITS - max.leading_zeros()) as usize
+    }
 }
 
 /// Identify a pixel in an image. MTLOrigin is ususally used as the upper-left corner of a region of a texture.
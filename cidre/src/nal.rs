@@ -0,0 +1,219 @@
+//! H.264 and HEVC NAL units.
+//!
+//! Native parsing of parameter sets and `avcC`/`hvcC` decoder configuration
+//! records, so streams can be inspected without VideoToolbox, and conversion
+//! between Annex-B byte streams and length-prefixed samples.
+
+use std::borrow::Cow;
+
+mod bits;
+
+pub mod annex_b;
+pub use annex_b::{
+    START_CODE, annex_b_units, len_prefixed_units, to_annex_b, to_len_prefixed, write_len_prefixed,
+};
+
+pub mod h264;
+pub use h264::Config as AvcConfig;
+
+pub mod hevc;
+pub use hevc::Config as HevcConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    UnexpectedEof,
+
+    /// Exp-Golomb code is longer than 32 bits
+    InvalidExpGolomb,
+
+    /// NAL unit type doesn't match expected one
+    InvalidNalType(u8),
+
+    /// Syntax element value is out of allowed range
+    InvalidValue(&'static str),
+
+    /// Decoder configuration record is malformed
+    InvalidConfig(&'static str),
+
+    /// NAL unit size doesn't fit length prefix
+    TooLarge(usize),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of NAL unit"),
+            Self::InvalidExpGolomb => f.write_str("invalid exp-Golomb code"),
+            Self::InvalidNalType(t) => write!(f, "unexpected NAL unit type {t}"),
+            Self::InvalidValue(name) => write!(f, "invalid {name}"),
+            Self::InvalidConfig(msg) => write!(f, "invalid decoder configuration: {msg}"),
+            Self::TooLarge(size) => write!(f, "NAL unit of {size} bytes doesn't fit length prefix"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Removes emulation prevention bytes (`00 00 03` → `00 00`) turning NAL unit payload into RBSP.
+pub fn rbsp(nal: &[u8]) -> Cow<'_, [u8]> {
+    let has_epb = nal.windows(3).any(|w| w == [0, 0, 3]);
+    if !has_epb {
+        return Cow::Borrowed(nal);
+    }
+    let mut res = Vec::with_capacity(nal.len());
+    let mut zeros = 0;
+    for &b in nal {
+        if zeros >= 2 && b == 3 {
+            zeros = 0;
+            continue;
+        }
+        zeros = if b == 0 { zeros + 1 } else { 0 };
+        res.push(b);
+    }
+    Cow::Owned(res)
+}
+
+/// Inserts emulation prevention bytes so RBSP can't contain start codes.
+pub fn ebsp(rbsp: &[u8]) -> Cow<'_, [u8]> {
+    let needs_epb = rbsp.windows(3).any(|w| w[0] == 0 && w[1] == 0 && w[2] <= 3);
+    if !needs_epb {
+        return Cow::Borrowed(rbsp);
+    }
+    let mut res = Vec::with_capacity(rbsp.len() + rbsp.len() / 64 + 1);
+    let mut zeros = 0;
+    for &b in rbsp {
+        if zeros >= 2 && b <= 3 {
+            res.push(3);
+            zeros = 0;
+        }
+        zeros = if b == 0 { zeros + 1 } else { 0 };
+        res.push(b);
+    }
+    Cow::Owned(res)
+}
+
+/// Sample aspect ratios for `aspect_ratio_idc` 1...16 (Table E-1).
+const SAR: [(u16, u16); 16] = [
+    (1, 1),
+    (12, 11),
+    (10, 11),
+    (16, 11),
+    (40, 33),
+    (24, 11),
+    (20, 11),
+    (32, 11),
+    (80, 33),
+    (18, 11),
+    (15, 11),
+    (64, 33),
+    (160, 99),
+    (4, 3),
+    (3, 2),
+    (2, 1),
+];
+
+/// Crop rectangle in luma samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Crop {
+    pub left: u32,
+    pub right: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+/// Colour description from video signal type (ITU-T H.273 code points).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColourDesc {
+    pub primaries: u8,
+    pub transfer: u8,
+    pub matrix: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timing {
+    pub num_units_in_tick: u32,
+    pub time_scale: u32,
+
+    /// `fixed_frame_rate_flag` for H.264, `vui_poc_proportional_to_timing_flag` for HEVC
+    pub fixed: bool,
+}
+
+/// Video usability information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Vui {
+    /// Sample aspect ratio
+    pub sar: Option<(u16, u16)>,
+    pub video_format: Option<u8>,
+    pub full_range: bool,
+    pub colour: Option<ColourDesc>,
+    pub timing: Option<Timing>,
+}
+
+impl Vui {
+    /// Parses fields shared by H.264 and HEVC up to chroma location.
+    fn parse_head(r: &mut bits::Reader) -> Result<Self> {
+        let mut vui = Self::default();
+        if r.flag()? {
+            let idc = r.u8()?;
+            vui.sar = match idc {
+                1..=16 => Some(SAR[idc as usize - 1]),
+                255 => Some((r.bits(16)? as u16, r.bits(16)? as u16)),
+                _ => None,
+            };
+        }
+        if r.flag()? {
+            // overscan_appropriate_flag
+            r.skip(1)?;
+        }
+        if r.flag()? {
+            vui.video_format = Some(r.bits(3)? as u8);
+            vui.full_range = r.flag()?;
+            if r.flag()? {
+                vui.colour = Some(ColourDesc {
+                    primaries: r.u8()?,
+                    transfer: r.u8()?,
+                    matrix: r.u8()?,
+                });
+            }
+        }
+        if r.flag()? {
+            // chroma_sample_loc_type_top_field, chroma_sample_loc_type_bottom_field
+            r.ue()?;
+            r.ue()?;
+        }
+        Ok(vui)
+    }
+
+    fn parse_timing(r: &mut bits::Reader) -> Result<Timing> {
+        let num_units_in_tick = r.bits(32)?;
+        let time_scale = r.bits(32)?;
+        let fixed = r.flag()?;
+        Ok(Timing {
+            num_units_in_tick,
+            time_scale,
+            fixed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nal;
+
+    #[test]
+    fn emulation_prevention() {
+        let raw = [0x67, 0, 0, 0, 0, 0, 1, 0, 0, 3, 0, 0, 2, 5];
+        let escaped = nal::ebsp(&raw);
+        assert_eq!(
+            escaped.as_ref(),
+            [0x67, 0, 0, 3, 0, 0, 3, 0, 1, 0, 0, 3, 3, 0, 0, 3, 2, 5]
+        );
+        assert_eq!(nal::rbsp(&escaped).as_ref(), raw);
+
+        let plain = [0x67, 1, 0, 0, 4, 0];
+        assert!(matches!(nal::ebsp(&plain), std::borrow::Cow::Borrowed(_)));
+        assert!(matches!(nal::rbsp(&plain), std::borrow::Cow::Borrowed(_)));
+    }
+}
@@ -0,0 +1,278 @@
+//! Annex-B byte stream and length-prefixed (`avcC`/`hvcC` style) NAL unit framing.
+
+use crate::nal::{Error, Result};
+
+/// Four byte start code written before each NAL unit
+pub const START_CODE: [u8; 4] = [0, 0, 0, 1];
+
+/// Returns position of the next `00 00 01` start code prefix.
+fn find_start_code(buf: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i + 2 < buf.len() {
+        match buf[i + 2] {
+            // can't be part of start code, jump over it
+            b if b > 1 => i += 3,
+            1 if buf[i] == 0 && buf[i + 1] == 0 => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Iterator over NAL units of Annex-B byte stream.
+///
+/// Bytes before the first start code and zero bytes trailing each unit are skipped.
+#[derive(Debug, Clone)]
+pub struct AnnexBUnits<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for AnnexBUnits<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = find_start_code(self.buf)? + 3;
+            let rest = &self.buf[start..];
+            let end = find_start_code(rest).unwrap_or(rest.len());
+            let unit = &rest[..end];
+            self.buf = &rest[end..];
+
+            let len = unit.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+            if len > 0 {
+                return Some(&unit[..len]);
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for AnnexBUnits<'_> {}
+
+/// NAL units of Annex-B byte stream.
+#[inline]
+pub fn annex_b_units(buf: &[u8]) -> AnnexBUnits<'_> {
+    AnnexBUnits { buf }
+}
+
+/// Iterator over NAL units each prefixed with big endian length.
+///
+/// Stops after the first error.
+#[derive(Debug, Clone)]
+pub struct LenPrefixedUnits<'a> {
+    buf: &'a [u8],
+    nal_len_size: usize,
+}
+
+impl<'a> Iterator for LenPrefixedUnits<'a> {
+    type Item = Result<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let res = read_unit(&mut self.buf, self.nal_len_size);
+        if res.is_err() {
+            self.buf = &[];
+        }
+        Some(res)
+    }
+}
+
+impl std::iter::FusedIterator for LenPrefixedUnits<'_> {}
+
+/// NAL units prefixed with `nal_len_size` bytes length.
+///
+/// `nal_len_size` is NAL unit header length reported by
+/// `cm::VideoFormatDesc::h264_params_count_and_header_len` or `nal_len_size` of decoder configuration.
+#[inline]
+pub fn len_prefixed_units(buf: &[u8], nal_len_size: u8) -> LenPrefixedUnits<'_> {
+    LenPrefixedUnits {
+        buf,
+        nal_len_size: nal_len_size as usize,
+    }
+}
+
+fn check_len_size(nal_len_size: u8) -> Result<()> {
+    match nal_len_size {
+        1 | 2 | 4 => Ok(()),
+        _ => Err(Error::InvalidConfig("invalid length size")),
+    }
+}
+
+fn read_unit<'a>(buf: &mut &'a [u8], nal_len_size: usize) -> Result<&'a [u8]> {
+    check_len_size(nal_len_size as u8)?;
+    if buf.len() < nal_len_size {
+        return Err(Error::UnexpectedEof);
+    }
+    let (prefix, rest) = buf.split_at(nal_len_size);
+    let len = prefix.iter().fold(0usize, |len, b| len << 8 | *b as usize);
+    if len > rest.len() {
+        return Err(Error::UnexpectedEof);
+    }
+    let (unit, rest) = rest.split_at(len);
+    *buf = rest;
+    Ok(unit)
+}
+
+/// Appends NAL unit prefixed with `nal_len_size` bytes length.
+pub fn write_len_prefixed(out: &mut Vec<u8>, nal: &[u8], nal_len_size: u8) -> Result<()> {
+    check_len_size(nal_len_size)?;
+    let max = u32::MAX as u64 >> (32 - 8 * nal_len_size as u32);
+    if nal.len() as u64 > max {
+        return Err(Error::TooLarge(nal.len()));
+    }
+    let len = (nal.len() as u32).to_be_bytes();
+    out.extend_from_slice(&len[4 - nal_len_size as usize..]);
+    out.extend_from_slice(nal);
+    Ok(())
+}
+
+/// Converts length-prefixed NAL units to Annex-B byte stream appending it to `out`.
+///
+/// `param_sets` are written first, pass SPS/PPS (and VPS for HEVC) on key frames
+/// so decoder can join the stream, or empty slice otherwise.
+/// On error `out` is left unchanged.
+pub fn to_annex_b(
+    buf: &[u8],
+    nal_len_size: u8,
+    param_sets: &[&[u8]],
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    check_len_size(nal_len_size)?;
+    let original_len = out.len();
+    out.reserve(buf.len() + param_sets.iter().map(|p| p.len() + 4).sum::<usize>());
+    for ps in param_sets {
+        out.extend_from_slice(&START_CODE);
+        out.extend_from_slice(ps);
+    }
+    for unit in len_prefixed_units(buf, nal_len_size) {
+        match unit {
+            Ok(unit) => {
+                out.extend_from_slice(&START_CODE);
+                out.extend_from_slice(unit);
+            }
+            Err(err) => {
+                out.truncate(original_len);
+                return Err(err);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Converts Annex-B byte stream to length-prefixed NAL units appending them to `out`.
+///
+/// Only units accepted by `filter` are written, so parameter sets which belong to
+/// format description can be dropped. On error `out` is left unchanged.
+pub fn to_len_prefixed(
+    buf: &[u8],
+    nal_len_size: u8,
+    mut filter: impl FnMut(&[u8]) -> bool,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    check_len_size(nal_len_size)?;
+    let original_len = out.len();
+    out.reserve(buf.len());
+    for unit in annex_b_units(buf).filter(|u| filter(u)) {
+        if let Err(err) = write_len_prefixed(out, unit, nal_len_size) {
+            out.truncate(original_len);
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nal::{Error, annex_b, h264};
+
+    const SPS: &[u8] = &[0x67, 0x42, 0xc0, 0x1e];
+    const PPS: &[u8] = &[0x68, 0xce, 0x3c, 0x80];
+    const IDR: &[u8] = &[0x65, 0x88, 0x84, 0x00, 0x00, 0x03, 0x01, 0x00];
+
+    #[test]
+    fn annex_b_units() {
+        let stream = [
+            &[0, 0, 0, 0, 1][..],
+            SPS,
+            &[0, 0, 1],
+            PPS,
+            &[0, 0, 0, 0, 0, 1],
+            &[0, 0, 1],
+            IDR,
+            &[0, 0],
+        ]
+        .concat();
+        let units: Vec<_> = annex_b::annex_b_units(&stream).collect();
+        // trailing zero byte of IDR is indistinguishable from trailing_zero_8bits
+        assert_eq!(units, [SPS, PPS, &IDR[..7]]);
+
+        assert_eq!(annex_b::annex_b_units(&[]).count(), 0);
+        assert_eq!(annex_b::annex_b_units(&[0, 0, 2, 0, 0]).count(), 0);
+        assert_eq!(
+            annex_b::annex_b_units(&[0xff, 0, 0, 1, 9, 0xf0]).collect::<Vec<_>>(),
+            [[9, 0xf0]]
+        );
+    }
+
+    #[test]
+    fn conversion() {
+        let idr = &IDR[..7];
+        for nal_len_size in [1, 2, 4] {
+            let mut sample = Vec::new();
+            for unit in [idr, &[0x06, 0x05][..]] {
+                annex_b::write_len_prefixed(&mut sample, unit, nal_len_size).unwrap();
+            }
+            assert_eq!(sample.len(), idr.len() + 2 + 2 * nal_len_size as usize);
+
+            let mut stream = vec![0xaa];
+            annex_b::to_annex_b(&sample, nal_len_size, &[SPS, PPS], &mut stream).unwrap();
+            let units: Vec<_> = annex_b::annex_b_units(&stream).collect();
+            assert_eq!(units, [SPS, PPS, idr, &[0x06, 0x05]]);
+
+            let mut back = Vec::new();
+            annex_b::to_len_prefixed(
+                &stream,
+                nal_len_size,
+                |u| {
+                    !matches!(
+                        h264::unit_type(u),
+                        Some(h264::unit_type::SPS | h264::unit_type::PPS)
+                    )
+                },
+                &mut back,
+            )
+            .unwrap();
+            assert_eq!(back, sample);
+        }
+    }
+
+    #[test]
+    fn malformed() {
+        let mut out = vec![1, 2];
+        let sample = [0, 0, 0, 3, 0x65, 0x88];
+        assert_eq!(
+            annex_b::to_annex_b(&sample, 4, &[SPS], &mut out),
+            Err(Error::UnexpectedEof)
+        );
+        assert_eq!(out, [1, 2]);
+        assert_eq!(
+            annex_b::to_annex_b(&sample, 3, &[], &mut out),
+            Err(Error::InvalidConfig("invalid length size"))
+        );
+        let mut units = annex_b::len_prefixed_units(&[1, 9, 2, 0], 1);
+        assert_eq!(units.next(), Some(Ok(&[9][..])));
+        assert_eq!(units.next(), Some(Err(Error::UnexpectedEof)));
+        assert_eq!(units.next(), None);
+
+        let big = vec![1u8; 256];
+        let stream = [&annex_b::START_CODE[..], &big].concat();
+        assert_eq!(
+            annex_b::to_len_prefixed(&stream, 1, |_| true, &mut out),
+            Err(Error::TooLarge(256))
+        );
+        assert_eq!(out, [1, 2]);
+        annex_b::to_len_prefixed(&stream, 2, |_| true, &mut out).unwrap();
+        assert_eq!(&out[2..4], [1, 0]);
+    }
+}
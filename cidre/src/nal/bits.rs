@@ -0,0 +1,168 @@
+use crate::nal::{Error, Result};
+
+/// MSB first bit reader over RBSP bytes.
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    #[inline]
+    pub fn bits_left(&self) -> usize {
+        self.buf.len() * 8 - self.pos
+    }
+
+    pub fn skip(&mut self, n: usize) -> Result<()> {
+        if n > self.bits_left() {
+            return Err(Error::UnexpectedEof);
+        }
+        self.pos += n;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn flag(&mut self) -> Result<bool> {
+        let Some(byte) = self.buf.get(self.pos / 8) else {
+            return Err(Error::UnexpectedEof);
+        };
+        let bit = (byte >> (7 - self.pos % 8)) & 1;
+        self.pos += 1;
+        Ok(bit == 1)
+    }
+
+    /// Reads up to 32 bits.
+    pub fn bits(&mut self, n: u32) -> Result<u32> {
+        debug_assert!(n <= 32);
+        if n as usize > self.bits_left() {
+            return Err(Error::UnexpectedEof);
+        }
+        let mut val = 0u32;
+        for _ in 0..n {
+            val = (val << 1) | self.flag()? as u32;
+        }
+        Ok(val)
+    }
+
+    #[inline]
+    pub fn u8(&mut self) -> Result<u8> {
+        self.bits(8).map(|v| v as u8)
+    }
+
+    /// Unsigned exp-Golomb code.
+    pub fn ue(&mut self) -> Result<u32> {
+        let mut zeros = 0;
+        while !self.flag()? {
+            zeros += 1;
+            if zeros > 31 {
+                return Err(Error::InvalidExpGolomb);
+            }
+        }
+        let rest = self.bits(zeros)?;
+        Ok(((1u64 << zeros) - 1 + rest as u64) as u32)
+    }
+
+    /// Signed exp-Golomb code.
+    pub fn se(&mut self) -> Result<i32> {
+        let k = self.ue()? as i64;
+        let val = if k & 1 == 1 { (k + 1) / 2 } else { -(k / 2) };
+        Ok(val as i32)
+    }
+
+    /// Reads `ue` and checks it against max value.
+    pub fn ue_max(&mut self, max: u32, name: &'static str) -> Result<u32> {
+        let val = self.ue()?;
+        if val > max {
+            return Err(Error::InvalidValue(name));
+        }
+        Ok(val)
+    }
+}
+
+/// MSB first bit writer for test streams.
+#[cfg(test)]
+#[derive(Default)]
+pub(crate) struct Writer {
+    pub buf: Vec<u8>,
+    bits: u32,
+}
+
+#[cfg(test)]
+impl Writer {
+    pub fn bits(&mut self, n: u32, val: u64) {
+        for i in (0..n).rev() {
+            if self.bits % 8 == 0 {
+                self.buf.push(0);
+            }
+            let bit = ((val >> i) & 1) as u8;
+            *self.buf.last_mut().unwrap() |= bit << (7 - self.bits % 8);
+            self.bits += 1;
+        }
+    }
+
+    #[inline]
+    pub fn flag(&mut self, val: bool) {
+        self.bits(1, val as u64)
+    }
+
+    pub fn ue(&mut self, val: u32) {
+        let v = val as u64 + 1;
+        let len = 64 - v.leading_zeros();
+        self.bits(len - 1, 0);
+        self.bits(len, v);
+    }
+
+    pub fn se(&mut self, val: i32) {
+        let k = if val > 0 {
+            val as i64 * 2 - 1
+        } else {
+            -(val as i64) * 2
+        };
+        self.ue(k as u32);
+    }
+
+    /// Appends stop bit and alignment zeros.
+    pub fn trailing(&mut self) {
+        self.flag(true);
+        while self.bits % 8 != 0 {
+            self.flag(false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, Writer};
+    use crate::nal::Error;
+
+    #[test]
+    fn exp_golomb() {
+        let mut w = Writer::default();
+        for v in [0, 1, 2, 3, 7, 255, u32::MAX - 1] {
+            w.ue(v);
+        }
+        for v in [0, 1, -1, 2, -2, i32::MAX, -i32::MAX] {
+            w.se(v);
+        }
+        w.bits(5, 0b10110);
+        let mut r = Reader::new(&w.buf);
+        for v in [0, 1, 2, 3, 7, 255, u32::MAX - 1] {
+            assert_eq!(r.ue().unwrap(), v);
+        }
+        for v in [0, 1, -1, 2, -2, i32::MAX, -i32::MAX] {
+            assert_eq!(r.se().unwrap(), v);
+        }
+        assert_eq!(r.bits(5).unwrap(), 0b10110);
+        assert!(r.bits_left() < 8);
+
+        let mut r = Reader::new(&[0, 0, 0, 0, 0x80]);
+        assert_eq!(r.ue(), Err(Error::InvalidExpGolomb));
+        let mut r = Reader::new(&[0x00, 0x01]);
+        assert_eq!(r.ue(), Err(Error::UnexpectedEof));
+        let mut r = Reader::new(&[0xff]);
+        assert_eq!(r.bits(9), Err(Error::UnexpectedEof));
+    }
+}
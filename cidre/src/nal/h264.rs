@@ -0,0 +1,611 @@
+//! H.264 (ITU-T H.264) parameter sets and `avcC` record.
+
+use crate::nal::{Crop, Error, Result, Timing, Vui, bits};
+
+/// NAL unit types
+pub mod unit_type {
+    pub const NON_IDR: u8 = 1;
+    pub const IDR: u8 = 5;
+    pub const SEI: u8 = 6;
+    pub const SPS: u8 = 7;
+    pub const PPS: u8 = 8;
+    pub const AUD: u8 = 9;
+    pub const SPS_EXT: u8 = 13;
+}
+
+/// Returns `nal_unit_type` of NAL unit.
+#[inline]
+pub fn unit_type(nal: &[u8]) -> Option<u8> {
+    nal.first().map(|h| h & 0x1f)
+}
+
+/// Parses RBSP of NAL unit with expected type.
+fn rbsp(nal: &[u8], expected: u8) -> Result<std::borrow::Cow<'_, [u8]>> {
+    match unit_type(nal) {
+        Some(t) if t == expected => Ok(super::rbsp(&nal[1..])),
+        Some(t) => Err(Error::InvalidNalType(t)),
+        None => Err(Error::UnexpectedEof),
+    }
+}
+
+/// Profiles with chroma format and bit depth in SPS
+const HIGH_PROFILES: [u8; 13] = [100, 110, 122, 244, 44, 83, 86, 118, 128, 138, 139, 134, 135];
+
+/// Sequence parameter set
+#[doc(alias = "seq_parameter_set_rbsp")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sps {
+    pub profile_idc: u8,
+
+    /// `constraint_set0_flag`...`constraint_set5_flag` and reserved bits
+    pub constraint_flags: u8,
+    pub level_idc: u8,
+    pub id: u8,
+    pub chroma_format_idc: u8,
+    pub separate_colour_plane: bool,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+    pub log2_max_frame_num: u8,
+    pub pic_order_cnt_type: u8,
+    pub max_num_ref_frames: u32,
+    pub frame_mbs_only: bool,
+    pub width_in_mbs: u32,
+    pub height_in_map_units: u32,
+
+    /// Crop in luma samples
+    pub crop: Crop,
+    pub vui: Option<Vui>,
+}
+
+impl Sps {
+    /// Parses SPS NAL unit including its header.
+    pub fn parse(nal: &[u8]) -> Result<Self> {
+        let rbsp = rbsp(nal, unit_type::SPS)?;
+        let r = &mut bits::Reader::new(&rbsp);
+
+        let profile_idc = r.u8()?;
+        let constraint_flags = r.u8()?;
+        let level_idc = r.u8()?;
+        let id = r.ue_max(31, "seq_parameter_set_id")? as u8;
+
+        let mut chroma_format_idc = 1;
+        let mut separate_colour_plane = false;
+        let mut bit_depth_luma = 8;
+        let mut bit_depth_chroma = 8;
+        if HIGH_PROFILES.contains(&profile_idc) {
+            chroma_format_idc = r.ue_max(3, "chroma_format_idc")? as u8;
+            if chroma_format_idc == 3 {
+                separate_colour_plane = r.flag()?;
+            }
+            bit_depth_luma = r.ue_max(6, "bit_depth_luma_minus8")? as u8 + 8;
+            bit_depth_chroma = r.ue_max(6, "bit_depth_chroma_minus8")? as u8 + 8;
+            // qpprime_y_zero_transform_bypass_flag
+            r.skip(1)?;
+            if r.flag()? {
+                let count = if chroma_format_idc == 3 { 12 } else { 8 };
+                for i in 0..count {
+                    if r.flag()? {
+                        skip_scaling_list(r, if i < 6 { 16 } else { 64 })?;
+                    }
+                }
+            }
+        }
+
+        let log2_max_frame_num = r.ue_max(12, "log2_max_frame_num_minus4")? as u8 + 4;
+        let pic_order_cnt_type = r.ue_max(2, "pic_order_cnt_type")? as u8;
+        match pic_order_cnt_type {
+            0 => {
+                r.ue_max(12, "log2_max_pic_order_cnt_lsb_minus4")?;
+            }
+            1 => {
+                // delta_pic_order_always_zero_flag
+                r.skip(1)?;
+                // offset_for_non_ref_pic, offset_for_top_to_bottom_field
+                r.se()?;
+                r.se()?;
+                let count = r.ue_max(255, "num_ref_frames_in_pic_order_cnt_cycle")?;
+                for _ in 0..count {
+                    r.se()?;
+                }
+            }
+            _ => {}
+        }
+        let max_num_ref_frames = r.ue()?;
+        // gaps_in_frame_num_value_allowed_flag
+        r.skip(1)?;
+        let width_in_mbs = r.ue_max(u16::MAX as u32, "pic_width_in_mbs_minus1")? + 1;
+        let height_in_map_units = r.ue_max(u16::MAX as u32, "pic_height_in_map_units_minus1")? + 1;
+        let frame_mbs_only = r.flag()?;
+        if !frame_mbs_only {
+            // mb_adaptive_frame_field_flag
+            r.skip(1)?;
+        }
+        // direct_8x8_inference_flag
+        r.skip(1)?;
+
+        let mut crop = Crop::default();
+        if r.flag()? {
+            let chroma_array_type = if separate_colour_plane {
+                0
+            } else {
+                chroma_format_idc
+            };
+            let (unit_x, unit_y) = match chroma_array_type {
+                0 => (1, 1),
+                1 => (2, 2),
+                2 => (2, 1),
+                _ => (1, 1),
+            };
+            let unit_y = unit_y * (2 - frame_mbs_only as u32);
+            crop = Crop {
+                left: r.ue()?.saturating_mul(unit_x),
+                right: r.ue()?.saturating_mul(unit_x),
+                top: r.ue()?.saturating_mul(unit_y),
+                bottom: r.ue()?.saturating_mul(unit_y),
+            };
+        }
+
+        let vui = if r.flag()? {
+            let mut vui = Vui::parse_head(r)?;
+            if r.flag()? {
+                vui.timing = Some(Vui::parse_timing(r)?);
+            }
+            Some(vui)
+        } else {
+            None
+        };
+
+        let sps = Self {
+            profile_idc,
+            constraint_flags,
+            level_idc,
+            id,
+            chroma_format_idc,
+            separate_colour_plane,
+            bit_depth_luma,
+            bit_depth_chroma,
+            log2_max_frame_num,
+            pic_order_cnt_type,
+            max_num_ref_frames,
+            frame_mbs_only,
+            width_in_mbs,
+            height_in_map_units,
+            crop,
+            vui,
+        };
+        if sps.crop.left.saturating_add(sps.crop.right) >= sps.coded_width()
+            || sps.crop.top.saturating_add(sps.crop.bottom) >= sps.coded_height()
+        {
+            return Err(Error::InvalidValue("frame_crop_offset"));
+        }
+        Ok(sps)
+    }
+
+    /// Width in luma samples before cropping
+    #[inline]
+    pub fn coded_width(&self) -> u32 {
+        self.width_in_mbs * 16
+    }
+
+    /// Height in luma samples before cropping
+    #[inline]
+    pub fn coded_height(&self) -> u32 {
+        self.height_in_map_units * 16 * (2 - self.frame_mbs_only as u32)
+    }
+
+    /// Display width after cropping
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.coded_width() - self.crop.left - self.crop.right
+    }
+
+    /// Display height after cropping
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.coded_height() - self.crop.top - self.crop.bottom
+    }
+
+    #[inline]
+    pub fn timing(&self) -> Option<Timing> {
+        self.vui.and_then(|v| v.timing)
+    }
+
+    /// Frames per second from VUI timing, one frame is two ticks.
+    pub fn frame_rate(&self) -> Option<f64> {
+        let t = self.timing()?;
+        if t.num_units_in_tick == 0 || t.time_scale == 0 {
+            return None;
+        }
+        Some(t.time_scale as f64 / (2.0 * t.num_units_in_tick as f64))
+    }
+
+    /// RFC 6381 codec string, e.g. `avc1.64001f`
+    pub fn codec_string(&self) -> String {
+        format!(
+            "avc1.{:02x}{:02x}{:02x}",
+            self.profile_idc, self.constraint_flags, self.level_idc
+        )
+    }
+}
+
+fn skip_scaling_list(r: &mut bits::Reader, size: usize) -> Result<()> {
+    let mut last = 8i32;
+    let mut next = 8i32;
+    for _ in 0..size {
+        if next != 0 {
+            let delta = r.se()?;
+            next = (last + delta).rem_euclid(256);
+        }
+        if next != 0 {
+            last = next;
+        }
+    }
+    Ok(())
+}
+
+/// Picture parameter set header
+#[doc(alias = "pic_parameter_set_rbsp")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pps {
+    pub id: u8,
+    pub sps_id: u8,
+
+    /// CABAC is used
+    pub entropy_coding_mode: bool,
+}
+
+impl Pps {
+    /// Parses PPS NAL unit including its header.
+    pub fn parse(nal: &[u8]) -> Result<Self> {
+        let rbsp = rbsp(nal, unit_type::PPS)?;
+        let r = &mut bits::Reader::new(&rbsp);
+        Ok(Self {
+            id: r.ue_max(255, "pic_parameter_set_id")? as u8,
+            sps_id: r.ue_max(31, "seq_parameter_set_id")? as u8,
+            entropy_coding_mode: r.flag()?,
+        })
+    }
+}
+
+/// Optional `avcC` fields for high profiles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigExt {
+    pub chroma_format: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+    pub sps_ext: Vec<Vec<u8>>,
+}
+
+/// AVC decoder configuration record (`avcC`, ISO/IEC 14496-15 5.3.3)
+#[doc(alias = "AVCDecoderConfigurationRecord")]
+#[doc(alias = "avcC")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub profile_idc: u8,
+    pub profile_compat: u8,
+    pub level_idc: u8,
+
+    /// Size of NAL unit length prefix, 1, 2 or 4
+    pub nal_len_size: u8,
+    pub sps: Vec<Vec<u8>>,
+    pub pps: Vec<Vec<u8>>,
+    pub ext: Option<ConfigExt>,
+}
+
+/// Reads `count` NAL units prefixed with u16 length.
+fn read_nals(buf: &mut &[u8], count: usize) -> Result<Vec<Vec<u8>>> {
+    let mut res = Vec::with_capacity(count.min(buf.len() / 2));
+    for _ in 0..count {
+        let len = u16::from_be_bytes(take(buf, 2)?.try_into().unwrap()) as usize;
+        res.push(take(buf, len)?.to_vec());
+    }
+    Ok(res)
+}
+
+pub(crate) fn take<'a>(buf: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    if buf.len() < len {
+        return Err(Error::UnexpectedEof);
+    }
+    let (head, tail) = buf.split_at(len);
+    *buf = tail;
+    Ok(head)
+}
+
+pub(crate) fn write_nals(buf: &mut Vec<u8>, nals: &[Vec<u8>]) -> Result<()> {
+    for nal in nals {
+        let len =
+            u16::try_from(nal.len()).map_err(|_| Error::InvalidConfig("NAL unit is too long"))?;
+        buf.extend_from_slice(&len.to_be_bytes());
+        buf.extend_from_slice(nal);
+    }
+    Ok(())
+}
+
+impl Config {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let buf = &mut &bytes[..];
+        let head = take(buf, 6)?;
+        if head[0] != 1 {
+            return Err(Error::InvalidConfig("unsupported version"));
+        }
+        let nal_len_size = (head[4] & 0b11) + 1;
+        if nal_len_size == 3 {
+            return Err(Error::InvalidConfig("invalid length size"));
+        }
+        let sps = read_nals(buf, (head[5] & 0x1f) as usize)?;
+        let pps_count = take(buf, 1)?[0] as usize;
+        let pps = read_nals(buf, pps_count)?;
+
+        let mut config = Self {
+            profile_idc: head[1],
+            profile_compat: head[2],
+            level_idc: head[3],
+            nal_len_size,
+            sps,
+            pps,
+            ext: None,
+        };
+        // extension is often omitted even for high profiles
+        if config.has_ext() && buf.len() >= 4 {
+            let ext = take(buf, 4)?;
+            let sps_ext = read_nals(buf, ext[3] as usize)?;
+            config.ext = Some(ConfigExt {
+                chroma_format: ext[0] & 0b11,
+                bit_depth_luma: (ext[1] & 0b111) + 8,
+                bit_depth_chroma: (ext[2] & 0b111) + 8,
+                sps_ext,
+            });
+        }
+        Ok(config)
+    }
+
+    #[inline]
+    fn has_ext(&self) -> bool {
+        !matches!(self.profile_idc, 66 | 77 | 88)
+    }
+
+    /// Builds record from raw SPS and PPS NAL units taking profile from the first SPS.
+    pub fn with_param_sets(sps: &[&[u8]], pps: &[&[u8]], nal_len_size: u8) -> Result<Self> {
+        let Some(first) = sps.first() else {
+            return Err(Error::InvalidConfig("no SPS"));
+        };
+        if pps.is_empty() {
+            return Err(Error::InvalidConfig("no PPS"));
+        }
+        if !matches!(nal_len_size, 1 | 2 | 4) {
+            return Err(Error::InvalidConfig("invalid length size"));
+        }
+        let parsed = Sps::parse(first)?;
+        let ext = if HIGH_PROFILES.contains(&parsed.profile_idc) {
+            Some(ConfigExt {
+                chroma_format: parsed.chroma_format_idc,
+                bit_depth_luma: parsed.bit_depth_luma,
+                bit_depth_chroma: parsed.bit_depth_chroma,
+                sps_ext: vec![],
+            })
+        } else {
+            None
+        };
+        Ok(Self {
+            profile_idc: parsed.profile_idc,
+            profile_compat: parsed.constraint_flags,
+            level_idc: parsed.level_idc,
+            nal_len_size,
+            sps: sps.iter().map(|s| s.to_vec()).collect(),
+            pps: pps.iter().map(|s| s.to_vec()).collect(),
+            ext,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        if self.sps.len() > 31 || self.pps.len() > 255 {
+            return Err(Error::InvalidConfig("too many parameter sets"));
+        }
+        if !matches!(self.nal_len_size, 1 | 2 | 4) {
+            return Err(Error::InvalidConfig("invalid length size"));
+        }
+        let mut buf = vec![
+            1,
+            self.profile_idc,
+            self.profile_compat,
+            self.level_idc,
+            0xfc | (self.nal_len_size - 1),
+            0xe0 | self.sps.len() as u8,
+        ];
+        write_nals(&mut buf, &self.sps)?;
+        buf.push(self.pps.len() as u8);
+        write_nals(&mut buf, &self.pps)?;
+        if let Some(ext) = &self.ext {
+            if ext.sps_ext.len() > 255 {
+                return Err(Error::InvalidConfig("too many parameter sets"));
+            }
+            buf.push(0xfc | (ext.chroma_format & 0b11));
+            buf.push(0xf8 | (ext.bit_depth_luma.saturating_sub(8) & 0b111));
+            buf.push(0xf8 | (ext.bit_depth_chroma.saturating_sub(8) & 0b111));
+            buf.push(ext.sps_ext.len() as u8);
+            write_nals(&mut buf, &ext.sps_ext)?;
+        }
+        Ok(buf)
+    }
+
+    /// RFC 6381 codec string, e.g. `avc1.64001f`
+    pub fn codec_string(&self) -> String {
+        format!(
+            "avc1.{:02x}{:02x}{:02x}",
+            self.profile_idc, self.profile_compat, self.level_idc
+        )
+    }
+
+    /// Parses the first SPS.
+    pub fn parse_sps(&self) -> Result<Sps> {
+        match self.sps.first() {
+            Some(sps) => Sps::parse(sps),
+            None => Err(Error::InvalidConfig("no SPS")),
+        }
+    }
+
+    /// SPS followed by PPS, order expected by `cm::VideoFormatDesc::with_h264_param_sets`.
+    pub fn param_sets(&self) -> impl Iterator<Item = &[u8]> {
+        self.sps.iter().chain(self.pps.iter()).map(Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nal::{self, ColourDesc, Error, bits, h264};
+
+    /// x264 1280x720 High@3.1 SPS
+    const SPS_720P: &[u8] = &[
+        0x67, 0x64, 0x00, 0x1f, 0xac, 0xd9, 0x40, 0x50, 0x05, 0xbb, 0x01, 0x10, 0x00, 0x00, 0x03,
+        0x00, 0x10, 0x00, 0x00, 0x03, 0x03, 0xc0, 0xf1, 0x83, 0x19, 0x60,
+    ];
+    const PPS: &[u8] = &[0x68, 0xeb, 0xe3, 0xcb, 0x22, 0xc0];
+
+    /// Baseline 1920x1080 SPS with cropping and colour description
+    fn sps_1080p() -> Vec<u8> {
+        let mut w = bits::Writer::default();
+        w.bits(8, 66); // profile_idc
+        w.bits(8, 0xc0); // constraint flags
+        w.bits(8, 40); // level_idc
+        w.ue(0); // sps id
+        w.ue(0); // log2_max_frame_num_minus4
+        w.ue(2); // pic_order_cnt_type
+        w.ue(1); // max_num_ref_frames
+        w.flag(false); // gaps
+        w.ue(119); // width in mbs - 1
+        w.ue(67); // height in map units - 1
+        w.flag(true); // frame_mbs_only
+        w.flag(true); // direct_8x8_inference
+        w.flag(true); // cropping
+        w.ue(0);
+        w.ue(0);
+        w.ue(0);
+        w.ue(4);
+        w.flag(true); // vui
+        w.flag(true); // aspect ratio
+        w.bits(8, 1);
+        w.flag(false); // overscan
+        w.flag(true); // video signal type
+        w.bits(3, 5);
+        w.flag(false); // full range
+        w.flag(true); // colour description
+        w.bits(8, 1);
+        w.bits(8, 1);
+        w.bits(8, 1);
+        w.flag(false); // chroma loc
+        w.flag(true); // timing
+        w.bits(32, 1001);
+        w.bits(32, 60000);
+        w.flag(true);
+        w.trailing();
+        let mut nal = vec![0x67];
+        nal.extend_from_slice(&nal::ebsp(&w.buf));
+        nal
+    }
+
+    #[test]
+    fn sps() {
+        let sps = h264::Sps::parse(SPS_720P).unwrap();
+        assert_eq!(sps.profile_idc, 100);
+        assert_eq!(sps.level_idc, 31);
+        assert_eq!(sps.chroma_format_idc, 1);
+        assert_eq!(sps.bit_depth_luma, 8);
+        assert_eq!((sps.width(), sps.height()), (1280, 720));
+        assert_eq!(sps.codec_string(), "avc1.64001f");
+        let fps = sps.frame_rate().unwrap();
+        assert!((fps - 30.0).abs() < 1e-9, "{fps}");
+
+        let sps = h264::Sps::parse(&sps_1080p()).unwrap();
+        assert_eq!((sps.coded_width(), sps.coded_height()), (1920, 1088));
+        assert_eq!((sps.width(), sps.height()), (1920, 1080));
+        let vui = sps.vui.unwrap();
+        assert_eq!(vui.sar, Some((1, 1)));
+        assert_eq!(vui.video_format, Some(5));
+        assert_eq!(
+            vui.colour,
+            Some(ColourDesc {
+                primaries: 1,
+                transfer: 1,
+                matrix: 1
+            })
+        );
+        assert!((sps.frame_rate().unwrap() - 29.97).abs() < 0.001);
+        assert_eq!(sps.codec_string(), "avc1.42c028");
+
+        let pps = h264::Pps::parse(PPS).unwrap();
+        assert_eq!(pps.id, 0);
+        assert_eq!(pps.sps_id, 0);
+        assert!(pps.entropy_coding_mode);
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(h264::Sps::parse(&[]), Err(Error::UnexpectedEof));
+        assert_eq!(h264::Sps::parse(PPS), Err(Error::InvalidNalType(8)));
+        for len in 1..SPS_720P.len() - 4 {
+            assert!(h264::Sps::parse(&SPS_720P[..len]).is_err(), "{len}");
+        }
+        // crop larger than picture, and crop sums past u32::MAX
+        for crop in [
+            [8, 0, 0, 0],
+            [1 << 30, 1 << 30, 0, 0],
+            [0, 0, 1 << 30, 1 << 30],
+        ] {
+            let mut w = bits::Writer::default();
+            w.bits(24, 0x42_00_0a);
+            for v in [0, 0, 2, 1] {
+                w.ue(v);
+            }
+            w.flag(false);
+            w.ue(0);
+            w.ue(0);
+            w.flag(true);
+            w.flag(true);
+            w.flag(true);
+            for v in crop {
+                w.ue(v);
+            }
+            w.flag(false);
+            w.trailing();
+            let mut nal = vec![0x67];
+            nal.extend_from_slice(&w.buf);
+            assert_eq!(
+                h264::Sps::parse(&nal),
+                Err(Error::InvalidValue("frame_crop_offset")),
+                "{crop:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn config() {
+        let config = h264::Config::with_param_sets(&[SPS_720P], &[PPS], 4).unwrap();
+        assert_eq!(config.profile_idc, 100);
+        assert!(config.ext.is_some());
+        assert_eq!(config.codec_string(), "avc1.64001f");
+        let bytes = config.to_bytes().unwrap();
+        assert_eq!(&bytes[..6], &[1, 0x64, 0x00, 0x1f, 0xff, 0xe1]);
+        assert_eq!(h264::Config::parse(&bytes).unwrap(), config);
+        assert_eq!(config.param_sets().collect::<Vec<_>>(), [SPS_720P, PPS]);
+        assert_eq!(config.parse_sps().unwrap().width(), 1280);
+
+        // high profile without extension
+        let short = &bytes[..bytes.len() - 4];
+        let parsed = h264::Config::parse(short).unwrap();
+        assert!(parsed.ext.is_none());
+        assert_eq!(parsed.sps, config.sps);
+
+        let sps = sps_1080p();
+        let config = h264::Config::with_param_sets(&[&sps], &[PPS], 2).unwrap();
+        assert!(config.ext.is_none());
+        let bytes = config.to_bytes().unwrap();
+        assert_eq!(bytes[4], 0xfd);
+        assert_eq!(h264::Config::parse(&bytes).unwrap(), config);
+
+        assert!(h264::Config::with_param_sets(&[], &[PPS], 4).is_err());
+        assert!(h264::Config::with_param_sets(&[SPS_720P], &[PPS], 3).is_err());
+        for len in 0..bytes.len() {
+            assert!(h264::Config::parse(&bytes[..len]).is_err(), "{len}");
+        }
+    }
+}
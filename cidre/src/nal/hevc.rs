@@ -0,0 +1,825 @@
+//! HEVC (ITU-T H.265) parameter sets and `hvcC` record.
+
+use crate::nal::{
+    Crop, Error, Result, Timing, Vui, bits,
+    h264::{take, write_nals},
+};
+
+/// NAL unit types
+pub mod unit_type {
+    pub const TRAIL_N: u8 = 0;
+    pub const TRAIL_R: u8 = 1;
+    pub const BLA_W_LP: u8 = 16;
+    pub const IDR_W_RADL: u8 = 19;
+    pub const IDR_N_LP: u8 = 20;
+    pub const CRA: u8 = 21;
+    pub const VPS: u8 = 32;
+    pub const SPS: u8 = 33;
+    pub const PPS: u8 = 34;
+    pub const AUD: u8 = 35;
+    pub const PREFIX_SEI: u8 = 39;
+    pub const SUFFIX_SEI: u8 = 40;
+}
+
+/// Returns `nal_unit_type` of NAL unit.
+#[inline]
+pub fn unit_type(nal: &[u8]) -> Option<u8> {
+    nal.first().map(|h| (h >> 1) & 0x3f)
+}
+
+/// IRAP pictures (BLA, IDR, CRA) are random access points.
+#[inline]
+pub fn is_irap(unit_type: u8) -> bool {
+    (16..=23).contains(&unit_type)
+}
+
+fn rbsp(nal: &[u8], expected: u8) -> Result<std::borrow::Cow<'_, [u8]>> {
+    if nal.len() < 2 {
+        return Err(Error::UnexpectedEof);
+    }
+    match unit_type(nal) {
+        Some(t) if t == expected => Ok(super::rbsp(&nal[2..])),
+        Some(t) => Err(Error::InvalidNalType(t)),
+        None => Err(Error::UnexpectedEof),
+    }
+}
+
+/// General profile, tier and level
+#[doc(alias = "profile_tier_level")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProfileTierLevel {
+    pub profile_space: u8,
+
+    /// High tier
+    pub tier: bool,
+    pub profile_idc: u8,
+    pub profile_compat: u32,
+
+    /// 48 bits of general constraint indicator flags
+    pub constraint_flags: u64,
+    pub level_idc: u8,
+}
+
+impl ProfileTierLevel {
+    fn parse(r: &mut bits::Reader, max_sub_layers_minus1: u8) -> Result<Self> {
+        let profile_space = r.bits(2)? as u8;
+        let tier = r.flag()?;
+        let profile_idc = r.bits(5)? as u8;
+        let profile_compat = r.bits(32)?;
+        let constraint_flags = (r.bits(16)? as u64) << 32 | r.bits(32)? as u64;
+        let level_idc = r.u8()?;
+
+        let mut sub_layers = [(false, false); 7];
+        for l in sub_layers.iter_mut().take(max_sub_layers_minus1 as usize) {
+            *l = (r.flag()?, r.flag()?);
+        }
+        if max_sub_layers_minus1 > 0 {
+            r.skip(2 * (8 - max_sub_layers_minus1 as usize))?;
+        }
+        for (profile, level) in sub_layers.iter().take(max_sub_layers_minus1 as usize) {
+            if *profile {
+                r.skip(88)?;
+            }
+            if *level {
+                r.skip(8)?;
+            }
+        }
+        Ok(Self {
+            profile_space,
+            tier,
+            profile_idc,
+            profile_compat,
+            constraint_flags,
+            level_idc,
+        })
+    }
+
+    /// ISO/IEC 14496-15 Annex E codec string, e.g. `hvc1.1.6.L93.B0`
+    pub fn codec_string(&self, sample_entry: &str) -> String {
+        let space = match self.profile_space {
+            1 => "A",
+            2 => "B",
+            3 => "C",
+            _ => "",
+        };
+        // compatibility flags in reverse bit order
+        let compat = self.profile_compat.reverse_bits();
+        let tier = if self.tier { 'H' } else { 'L' };
+        let mut res = format!(
+            "{sample_entry}.{space}{}.{compat:X}.{tier}{}",
+            self.profile_idc, self.level_idc
+        );
+        // constraint bytes with trailing zero bytes omitted
+        let bytes = &self.constraint_flags.to_be_bytes()[2..];
+        let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        for b in &bytes[..len] {
+            res.push_str(&format!(".{b:X}"));
+        }
+        res
+    }
+}
+
+/// Video parameter set header
+#[doc(alias = "video_parameter_set_rbsp")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vps {
+    pub id: u8,
+    pub max_sub_layers: u8,
+    pub temporal_id_nesting: bool,
+    pub ptl: ProfileTierLevel,
+}
+
+impl Vps {
+    /// Parses VPS NAL unit including its header.
+    pub fn parse(nal: &[u8]) -> Result<Self> {
+        let rbsp = rbsp(nal, unit_type::VPS)?;
+        let r = &mut bits::Reader::new(&rbsp);
+        let id = r.bits(4)? as u8;
+        // vps_base_layer_internal_flag, vps_base_layer_available_flag, vps_max_layers_minus1
+        r.skip(8)?;
+        let max_sub_layers_minus1 = r.bits(3)? as u8;
+        if max_sub_layers_minus1 > 6 {
+            return Err(Error::InvalidValue("vps_max_sub_layers_minus1"));
+        }
+        let temporal_id_nesting = r.flag()?;
+        // vps_reserved_0xffff_16bits
+        r.skip(16)?;
+        Ok(Self {
+            id,
+            max_sub_layers: max_sub_layers_minus1 + 1,
+            temporal_id_nesting,
+            ptl: ProfileTierLevel::parse(r, max_sub_layers_minus1)?,
+        })
+    }
+}
+
+/// Sequence parameter set
+#[doc(alias = "seq_parameter_set_rbsp")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sps {
+    pub vps_id: u8,
+    pub max_sub_layers: u8,
+    pub temporal_id_nesting: bool,
+    pub ptl: ProfileTierLevel,
+    pub id: u8,
+    pub chroma_format_idc: u8,
+    pub separate_colour_plane: bool,
+
+    /// Width in luma samples before cropping
+    pub coded_width: u32,
+
+    /// Height in luma samples before cropping
+    pub coded_height: u32,
+
+    /// Conformance window in luma samples
+    pub crop: Crop,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+    pub vui: Option<Vui>,
+}
+
+impl Sps {
+    /// Parses SPS NAL unit including its header.
+    pub fn parse(nal: &[u8]) -> Result<Self> {
+        let rbsp = rbsp(nal, unit_type::SPS)?;
+        let r = &mut bits::Reader::new(&rbsp);
+
+        let vps_id = r.bits(4)? as u8;
+        let max_sub_layers_minus1 = r.bits(3)? as u8;
+        if max_sub_layers_minus1 > 6 {
+            return Err(Error::InvalidValue("sps_max_sub_layers_minus1"));
+        }
+        let temporal_id_nesting = r.flag()?;
+        let ptl = ProfileTierLevel::parse(r, max_sub_layers_minus1)?;
+        let id = r.ue_max(15, "sps_seq_parameter_set_id")? as u8;
+        let chroma_format_idc = r.ue_max(3, "chroma_format_idc")? as u8;
+        let separate_colour_plane = chroma_format_idc == 3 && r.flag()?;
+        let coded_width = r.ue_max(u16::MAX as u32, "pic_width_in_luma_samples")?;
+        let coded_height = r.ue_max(u16::MAX as u32, "pic_height_in_luma_samples")?;
+
+        let mut crop = Crop::default();
+        if r.flag()? {
+            let chroma_array_type = if separate_colour_plane {
+                0
+            } else {
+                chroma_format_idc
+            };
+            let (unit_x, unit_y) = match chroma_array_type {
+                1 => (2, 2),
+                2 => (2, 1),
+                _ => (1, 1),
+            };
+            crop = Crop {
+                left: r.ue()?.saturating_mul(unit_x),
+                right: r.ue()?.saturating_mul(unit_x),
+                top: r.ue()?.saturating_mul(unit_y),
+                bottom: r.ue()?.saturating_mul(unit_y),
+            };
+            if crop.left.saturating_add(crop.right) >= coded_width
+                || crop.top.saturating_add(crop.bottom) >= coded_height
+            {
+                return Err(Error::InvalidValue("conf_win_offset"));
+            }
+        }
+        let bit_depth_luma = r.ue_max(8, "bit_depth_luma_minus8")? as u8 + 8;
+        let bit_depth_chroma = r.ue_max(8, "bit_depth_chroma_minus8")? as u8 + 8;
+        let log2_max_poc_lsb = r.ue_max(12, "log2_max_pic_order_cnt_lsb_minus4")? + 4;
+
+        let sub_layer_ordering_info = r.flag()?;
+        let first = if sub_layer_ordering_info {
+            0
+        } else {
+            max_sub_layers_minus1
+        };
+        for _ in first..=max_sub_layers_minus1 {
+            // max_dec_pic_buffering_minus1, max_num_reorder_pics, max_latency_increase_plus1
+            r.ue()?;
+            r.ue()?;
+            r.ue()?;
+        }
+        // log2_min_luma_coding_block_size_minus3, log2_diff_max_min_luma_coding_block_size,
+        // log2_min_luma_transform_block_size_minus2, log2_diff_max_min_luma_transform_block_size,
+        // max_transform_hierarchy_depth_inter, max_transform_hierarchy_depth_intra
+        for _ in 0..6 {
+            r.ue()?;
+        }
+        // scaling_list_enabled_flag
+        if r.flag()? && r.flag()? {
+            skip_scaling_list_data(r)?;
+        }
+        // amp_enabled_flag, sample_adaptive_offset_enabled_flag
+        r.skip(2)?;
+        if r.flag()? {
+            // pcm sample bit depths
+            r.skip(8)?;
+            r.ue()?;
+            r.ue()?;
+            // pcm_loop_filter_disabled_flag
+            r.skip(1)?;
+        }
+        let num_st_rps = r.ue_max(64, "num_short_term_ref_pic_sets")? as usize;
+        let mut num_delta_pocs = [0u32; 64];
+        for i in 0..num_st_rps {
+            num_delta_pocs[i] = skip_st_ref_pic_set(r, i, &num_delta_pocs)?;
+        }
+        if r.flag()? {
+            let count = r.ue_max(32, "num_long_term_ref_pics_sps")?;
+            for _ in 0..count {
+                // lt_ref_pic_poc_lsb_sps, used_by_curr_pic_lt_sps_flag
+                r.skip(log2_max_poc_lsb as usize + 1)?;
+            }
+        }
+        // sps_temporal_mvp_enabled_flag, strong_intra_smoothing_enabled_flag
+        r.skip(2)?;
+
+        let vui = if r.flag()? { Some(parse_vui(r)?) } else { None };
+
+        Ok(Self {
+            vps_id,
+            max_sub_layers: max_sub_layers_minus1 + 1,
+            temporal_id_nesting,
+            ptl,
+            id,
+            chroma_format_idc,
+            separate_colour_plane,
+            coded_width,
+            coded_height,
+            crop,
+            bit_depth_luma,
+            bit_depth_chroma,
+            vui,
+        })
+    }
+
+    /// Display width after cropping
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.coded_width - self.crop.left - self.crop.right
+    }
+
+    /// Display height after cropping
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.coded_height - self.crop.top - self.crop.bottom
+    }
+
+    #[inline]
+    pub fn timing(&self) -> Option<Timing> {
+        self.vui.and_then(|v| v.timing)
+    }
+
+    /// Frames per second from VUI timing
+    pub fn frame_rate(&self) -> Option<f64> {
+        let t = self.timing()?;
+        if t.num_units_in_tick == 0 || t.time_scale == 0 {
+            return None;
+        }
+        Some(t.time_scale as f64 / t.num_units_in_tick as f64)
+    }
+
+    /// Codec string for `hvc1` sample entry
+    #[inline]
+    pub fn codec_string(&self) -> String {
+        self.ptl.codec_string("hvc1")
+    }
+}
+
+fn parse_vui(r: &mut bits::Reader) -> Result<Vui> {
+    let mut vui = Vui::parse_head(r)?;
+    // neutral_chroma_indication_flag, field_seq_flag, frame_field_info_present_flag
+    r.skip(3)?;
+    if r.flag()? {
+        // default display window
+        for _ in 0..4 {
+            r.ue()?;
+        }
+    }
+    if r.flag()? {
+        // poc_proportional_to_timing_flag takes place of fixed_frame_rate_flag
+        vui.timing = Some(Vui::parse_timing(r)?);
+    }
+    Ok(vui)
+}
+
+fn skip_scaling_list_data(r: &mut bits::Reader) -> Result<()> {
+    for size_id in 0..4 {
+        let step = if size_id == 3 { 3 } else { 1 };
+        for _ in (0..6).step_by(step) {
+            if !r.flag()? {
+                // scaling_list_pred_matrix_id_delta
+                r.ue()?;
+            } else {
+                let count = 64.min(1 << (4 + (size_id << 1)));
+                if size_id > 1 {
+                    // scaling_list_dc_coef_minus8
+                    r.se()?;
+                }
+                for _ in 0..count {
+                    r.se()?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Skips `st_ref_pic_set(idx)` returning its `NumDeltaPocs`.
+fn skip_st_ref_pic_set(r: &mut bits::Reader, idx: usize, num_delta_pocs: &[u32]) -> Result<u32> {
+    let inter_rps_pred = idx != 0 && r.flag()?;
+    if inter_rps_pred {
+        // delta_rps_sign, abs_delta_rps_minus1
+        r.skip(1)?;
+        r.ue()?;
+        let mut count = 0;
+        for _ in 0..=num_delta_pocs[idx - 1] {
+            let used_by_curr_pic = r.flag()?;
+            let use_delta = used_by_curr_pic || r.flag()?;
+            if use_delta {
+                count += 1;
+            }
+        }
+        Ok(count)
+    } else {
+        let negative = r.ue_max(16, "num_negative_pics")?;
+        let positive = r.ue_max(16, "num_positive_pics")?;
+        for _ in 0..negative + positive {
+            // delta_poc_minus1, used_by_curr_pic_flag
+            r.ue()?;
+            r.skip(1)?;
+        }
+        Ok(negative + positive)
+    }
+}
+
+/// Picture parameter set header
+#[doc(alias = "pic_parameter_set_rbsp")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pps {
+    pub id: u8,
+    pub sps_id: u8,
+}
+
+impl Pps {
+    /// Parses PPS NAL unit including its header.
+    pub fn parse(nal: &[u8]) -> Result<Self> {
+        let rbsp = rbsp(nal, unit_type::PPS)?;
+        let r = &mut bits::Reader::new(&rbsp);
+        Ok(Self {
+            id: r.ue_max(63, "pps_pic_parameter_set_id")? as u8,
+            sps_id: r.ue_max(15, "pps_seq_parameter_set_id")? as u8,
+        })
+    }
+}
+
+/// NAL units of the same type in `hvcC`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NalArray {
+    /// All NAL units of this type are in the array, none in the stream
+    pub complete: bool,
+    pub unit_type: u8,
+    pub nals: Vec<Vec<u8>>,
+}
+
+/// HEVC decoder configuration record (`hvcC`, ISO/IEC 14496-15 8.3.3)
+#[doc(alias = "HEVCDecoderConfigurationRecord")]
+#[doc(alias = "hvcC")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub ptl: ProfileTierLevel,
+    pub min_spatial_segmentation: u16,
+    pub parallelism_type: u8,
+    pub chroma_format: u8,
+    pub bit_depth_luma: u8,
+    pub bit_depth_chroma: u8,
+
+    /// Frames per 256 seconds, 0 if unspecified
+    pub avg_frame_rate: u16,
+    pub constant_frame_rate: u8,
+    pub num_temporal_layers: u8,
+    pub temporal_id_nested: bool,
+
+    /// Size of NAL unit length prefix, 1, 2 or 4
+    pub nal_len_size: u8,
+    pub arrays: Vec<NalArray>,
+}
+
+impl Config {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let buf = &mut &bytes[..];
+        let h = take(buf, 23)?;
+        if h[0] != 1 {
+            return Err(Error::InvalidConfig("unsupported version"));
+        }
+        let nal_len_size = (h[21] & 0b11) + 1;
+        if nal_len_size == 3 {
+            return Err(Error::InvalidConfig("invalid length size"));
+        }
+        let mut constraint_flags = 0u64;
+        for b in &h[6..12] {
+            constraint_flags = constraint_flags << 8 | *b as u64;
+        }
+        let count = h[22] as usize;
+        let mut arrays = Vec::with_capacity(count);
+        for _ in 0..count {
+            let head = take(buf, 3)?;
+            let nals_count = u16::from_be_bytes([head[1], head[2]]) as usize;
+            let mut nals = Vec::with_capacity(nals_count.min(buf.len() / 2));
+            for _ in 0..nals_count {
+                let len = u16::from_be_bytes(take(buf, 2)?.try_into().unwrap()) as usize;
+                nals.push(take(buf, len)?.to_vec());
+            }
+            arrays.push(NalArray {
+                complete: head[0] & 0x80 != 0,
+                unit_type: head[0] & 0x3f,
+                nals,
+            });
+        }
+        Ok(Self {
+            ptl: ProfileTierLevel {
+                profile_space: h[1] >> 6,
+                tier: h[1] & 0x20 != 0,
+                profile_idc: h[1] & 0x1f,
+                profile_compat: u32::from_be_bytes([h[2], h[3], h[4], h[5]]),
+                constraint_flags,
+                level_idc: h[12],
+            },
+            min_spatial_segmentation: u16::from_be_bytes([h[13], h[14]]) & 0x0fff,
+            parallelism_type: h[15] & 0b11,
+            chroma_format: h[16] & 0b11,
+            bit_depth_luma: (h[17] & 0b111) + 8,
+            bit_depth_chroma: (h[18] & 0b111) + 8,
+            avg_frame_rate: u16::from_be_bytes([h[19], h[20]]),
+            constant_frame_rate: h[21] >> 6,
+            num_temporal_layers: (h[21] >> 3) & 0b111,
+            temporal_id_nested: h[21] & 0b100 != 0,
+            nal_len_size,
+            arrays,
+        })
+    }
+
+    /// Builds record from raw VPS, SPS and PPS NAL units taking stream info from the first SPS.
+    pub fn with_param_sets(
+        vps: &[&[u8]],
+        sps: &[&[u8]],
+        pps: &[&[u8]],
+        nal_len_size: u8,
+    ) -> Result<Self> {
+        if vps.is_empty() || pps.is_empty() {
+            return Err(Error::InvalidConfig("no VPS or PPS"));
+        }
+        let Some(first) = sps.first() else {
+            return Err(Error::InvalidConfig("no SPS"));
+        };
+        if !matches!(nal_len_size, 1 | 2 | 4) {
+            return Err(Error::InvalidConfig("invalid length size"));
+        }
+        let parsed = Sps::parse(first)?;
+        let avg_frame_rate = parsed
+            .frame_rate()
+            .map_or(0, |fps| (fps * 256.0).round().min(u16::MAX as f64) as u16);
+        let array = |unit_type, nals: &[&[u8]]| NalArray {
+            complete: true,
+            unit_type,
+            nals: nals.iter().map(|n| n.to_vec()).collect(),
+        };
+        Ok(Self {
+            ptl: parsed.ptl,
+            min_spatial_segmentation: 0,
+            parallelism_type: 0,
+            chroma_format: parsed.chroma_format_idc,
+            bit_depth_luma: parsed.bit_depth_luma,
+            bit_depth_chroma: parsed.bit_depth_chroma,
+            avg_frame_rate,
+            constant_frame_rate: 0,
+            num_temporal_layers: parsed.max_sub_layers,
+            temporal_id_nested: parsed.temporal_id_nesting,
+            nal_len_size,
+            arrays: vec![
+                array(unit_type::VPS, vps),
+                array(unit_type::SPS, sps),
+                array(unit_type::PPS, pps),
+            ],
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        if !matches!(self.nal_len_size, 1 | 2 | 4) {
+            return Err(Error::InvalidConfig("invalid length size"));
+        }
+        if self.arrays.len() > 255 {
+            return Err(Error::InvalidConfig("too many arrays"));
+        }
+        let ptl = &self.ptl;
+        let mut buf = vec![
+            1,
+            (ptl.profile_space & 0b11) << 6 | (ptl.tier as u8) << 5 | (ptl.profile_idc & 0x1f),
+        ];
+        buf.extend_from_slice(&ptl.profile_compat.to_be_bytes());
+        buf.extend_from_slice(&ptl.constraint_flags.to_be_bytes()[2..]);
+        buf.push(ptl.level_idc);
+        buf.extend_from_slice(&(0xf000 | (self.min_spatial_segmentation & 0x0fff)).to_be_bytes());
+        buf.push(0xfc | (self.parallelism_type & 0b11));
+        buf.push(0xfc | (self.chroma_format & 0b11));
+        buf.push(0xf8 | (self.bit_depth_luma.saturating_sub(8) & 0b111));
+        buf.push(0xf8 | (self.bit_depth_chroma.saturating_sub(8) & 0b111));
+        buf.extend_from_slice(&self.avg_frame_rate.to_be_bytes());
+        buf.push(
+            (self.constant_frame_rate & 0b11) << 6
+                | (self.num_temporal_layers & 0b111) << 3
+                | (self.temporal_id_nested as u8) << 2
+                | (self.nal_len_size - 1),
+        );
+        buf.push(self.arrays.len() as u8);
+        for array in &self.arrays {
+            let count = u16::try_from(array.nals.len())
+                .map_err(|_| Error::InvalidConfig("too many NAL units"))?;
+            buf.push((array.complete as u8) << 7 | (array.unit_type & 0x3f));
+            buf.extend_from_slice(&count.to_be_bytes());
+            write_nals(&mut buf, &array.nals)?;
+        }
+        Ok(buf)
+    }
+
+    /// NAL units of type
+    pub fn nals(&self, unit_type: u8) -> impl Iterator<Item = &[u8]> {
+        self.arrays
+            .iter()
+            .filter(move |a| a.unit_type == unit_type)
+            .flat_map(|a| a.nals.iter().map(Vec::as_slice))
+    }
+
+    /// Parses the first SPS.
+    pub fn parse_sps(&self) -> Result<Sps> {
+        match self.nals(unit_type::SPS).next() {
+            Some(sps) => Sps::parse(sps),
+            None => Err(Error::InvalidConfig("no SPS")),
+        }
+    }
+
+    /// VPS, SPS and PPS in order expected by `cm::VideoFormatDesc::with_hevc_param_sets`.
+    pub fn param_sets(&self) -> impl Iterator<Item = &[u8]> {
+        self.nals(unit_type::VPS)
+            .chain(self.nals(unit_type::SPS))
+            .chain(self.nals(unit_type::PPS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nal::{self, Error, bits, hevc};
+
+    fn ptl(w: &mut bits::Writer) {
+        w.bits(2, 0); // profile space
+        w.flag(false); // tier
+        w.bits(5, 2); // Main 10
+        w.bits(32, 0x2000_0000);
+        w.bits(48, 0x9000_0000_0000);
+        w.bits(8, 153); // level 5.1
+    }
+
+    fn vps() -> Vec<u8> {
+        let mut w = bits::Writer::default();
+        w.bits(4, 0);
+        w.bits(2, 0b11);
+        w.bits(6, 0);
+        w.bits(3, 0); // max sub layers - 1
+        w.flag(true);
+        w.bits(16, 0xffff);
+        ptl(&mut w);
+        w.trailing();
+        let mut nal = vec![0x40, 0x01];
+        nal.extend_from_slice(&nal::ebsp(&w.buf));
+        nal
+    }
+
+    /// 3840x2160 Main 10 with conformance window, scaling lists,
+    /// inter predicted ref pic sets and VUI timing
+    fn sps() -> Vec<u8> {
+        let mut w = bits::Writer::default();
+        w.bits(4, 0); // vps id
+        w.bits(3, 1); // max sub layers - 1
+        w.flag(true); // temporal id nesting
+        ptl(&mut w);
+        w.flag(true); // sub layer profile present
+        w.flag(true); // sub layer level present
+        w.bits(14, 0); // reserved alignment for 7 layers
+        w.bits(44, 0);
+        w.bits(44, 0);
+        w.bits(8, 0);
+        w.ue(0); // sps id
+        w.ue(1); // 4:2:0
+        w.ue(3840);
+        w.ue(2176);
+        w.flag(true); // conformance window
+        w.ue(0);
+        w.ue(0);
+        w.ue(0);
+        w.ue(8); // 16 luma rows
+        w.ue(2); // bit depth luma - 8
+        w.ue(2); // bit depth chroma - 8
+        w.ue(4); // log2 max poc lsb - 4
+        w.flag(true); // sub layer ordering info
+        for _ in 0..2 {
+            w.ue(4);
+            w.ue(2);
+            w.ue(0);
+        }
+        for v in [0, 3, 0, 3, 1, 1] {
+            w.ue(v);
+        }
+        w.flag(true); // scaling list enabled
+        w.flag(true); // scaling list data present
+        for size_id in 0..4 {
+            let step = if size_id == 3 { 3 } else { 1 };
+            for matrix_id in (0..6).step_by(step) {
+                if matrix_id % 2 == 0 {
+                    w.flag(false);
+                    w.ue(0);
+                } else {
+                    w.flag(true);
+                    if size_id > 1 {
+                        w.se(8);
+                    }
+                    for _ in 0..64.min(1 << (4 + (size_id << 1))) {
+                        w.se(-1);
+                    }
+                }
+            }
+        }
+        w.flag(false); // amp
+        w.flag(true); // sao
+        w.flag(true); // pcm
+        w.bits(8, 0x77);
+        w.ue(0);
+        w.ue(1);
+        w.flag(false);
+        w.ue(2); // num short term ref pic sets
+        // set 0: 2 negative, 1 positive
+        w.ue(2);
+        w.ue(1);
+        for _ in 0..3 {
+            w.ue(0);
+            w.flag(true);
+        }
+        // set 1: predicted from set 0 with 4 entries
+        w.flag(true);
+        w.flag(false);
+        w.ue(0);
+        w.flag(true);
+        w.flag(false);
+        w.flag(true);
+        w.flag(false);
+        w.flag(false);
+        w.flag(true);
+        w.flag(true); // long term ref pics
+        w.ue(1);
+        w.bits(8, 0xff);
+        w.bits(1, 1);
+        w.flag(true); // temporal mvp
+        w.flag(true); // strong intra smoothing
+        w.flag(true); // vui
+        w.flag(false); // aspect ratio
+        w.flag(false); // overscan
+        w.flag(true); // video signal
+        w.bits(3, 5);
+        w.flag(true); // full range
+        w.flag(true);
+        w.bits(8, 9);
+        w.bits(8, 16);
+        w.bits(8, 9);
+        w.flag(false); // chroma loc
+        w.bits(3, 0);
+        w.flag(true); // default display window
+        for _ in 0..4 {
+            w.ue(0);
+        }
+        w.flag(true); // timing
+        w.bits(32, 1);
+        w.bits(32, 50);
+        w.flag(false);
+        w.flag(false); // hrd
+        w.trailing();
+        let mut nal = vec![0x42, 0x01];
+        nal.extend_from_slice(&nal::ebsp(&w.buf));
+        nal
+    }
+
+    const PPS: &[u8] = &[0x44, 0x01, 0xc1, 0x72, 0xb4, 0x62, 0x40];
+
+    #[test]
+    fn param_sets() {
+        let vps = hevc::Vps::parse(&vps()).unwrap();
+        assert_eq!(vps.max_sub_layers, 1);
+        assert_eq!(vps.ptl.profile_idc, 2);
+
+        let sps = hevc::Sps::parse(&sps()).unwrap();
+        assert_eq!(sps.max_sub_layers, 2);
+        assert_eq!(sps.ptl.level_idc, 153);
+        assert_eq!((sps.coded_width, sps.coded_height), (3840, 2176));
+        assert_eq!((sps.width(), sps.height()), (3840, 2160));
+        assert_eq!((sps.bit_depth_luma, sps.bit_depth_chroma), (10, 10));
+        let vui = sps.vui.unwrap();
+        assert!(vui.full_range);
+        assert_eq!(vui.colour.unwrap().transfer, 16);
+        assert_eq!(sps.frame_rate(), Some(50.0));
+        assert_eq!(sps.codec_string(), "hvc1.2.4.L153.90");
+
+        let pps = hevc::Pps::parse(PPS).unwrap();
+        assert_eq!((pps.id, pps.sps_id), (0, 0));
+    }
+
+    #[test]
+    fn codec_string() {
+        let ptl = hevc::ProfileTierLevel {
+            profile_space: 0,
+            tier: false,
+            profile_idc: 1,
+            profile_compat: 0x6000_0000,
+            constraint_flags: 0xb000_0000_0000,
+            level_idc: 93,
+        };
+        assert_eq!(ptl.codec_string("hvc1"), "hvc1.1.6.L93.B0");
+        let ptl = hevc::ProfileTierLevel {
+            tier: true,
+            profile_idc: 2,
+            profile_compat: 0x2000_0000,
+            constraint_flags: 0,
+            level_idc: 120,
+            ..ptl
+        };
+        assert_eq!(ptl.codec_string("hev1"), "hev1.2.4.H120");
+    }
+
+    #[test]
+    fn malformed() {
+        let sps = sps();
+        assert_eq!(hevc::Sps::parse(PPS), Err(Error::InvalidNalType(34)));
+        assert_eq!(hevc::Sps::parse(&sps[..1]), Err(Error::UnexpectedEof));
+        for len in 2..sps.len() - 6 {
+            assert!(hevc::Sps::parse(&sps[..len]).is_err(), "{len}");
+        }
+    }
+
+    #[test]
+    fn config() {
+        let (vps, sps) = (vps(), sps());
+        let config = hevc::Config::with_param_sets(&[&vps], &[&sps], &[PPS], 4).unwrap();
+        assert_eq!(config.avg_frame_rate, 50 * 256);
+        assert_eq!(config.num_temporal_layers, 2);
+        assert_eq!(config.bit_depth_luma, 10);
+        let bytes = config.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 23 + 3 * 5 + vps.len() + sps.len() + PPS.len());
+        assert_eq!(bytes[21] & 0b11, 3);
+        assert_eq!(hevc::Config::parse(&bytes).unwrap(), config);
+        assert_eq!(
+            config.param_sets().collect::<Vec<_>>(),
+            [&vps[..], &sps[..], PPS]
+        );
+        assert_eq!(config.parse_sps().unwrap().width(), 3840);
+
+        for len in 0..bytes.len() {
+            assert!(hevc::Config::parse(&bytes[..len]).is_err(), "{len}");
+        }
+        assert!(hevc::Config::with_param_sets(&[], &[&sps], &[PPS], 4).is_err());
+    }
+}
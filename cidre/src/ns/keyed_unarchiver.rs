@@ -40,4 +40,24 @@ mod tests {
         let b = ns::KeyedUnarchiver::unarchived_obj_of_cls(ns::String::cls(), &data).unwrap();
         assert_eq!(&a, &b);
     }
+
+    #[cfg(feature = "plist")]
+    #[test]
+    fn native_archive() {
+        use crate::{arc, cf, plist};
+
+        let arr: arc::R<ns::Array<ns::String>> = (&["a", "b"][..]).into();
+        let data = ns::KeyedArchiver::archived_data_with_root_obj(&arr, true).unwrap();
+        let archive = plist::KeyedArchive::from_bytes(data.as_slice()).unwrap();
+        assert_eq!(
+            archive.root(),
+            Some(&plist::ArchiveObj::Array(vec!["a".into(), "b".into()]))
+        );
+
+        let bytes = plist::KeyedArchive::with_root("native").to_bytes().unwrap();
+        let data = cf::Data::from_slice(&bytes).unwrap();
+        let str =
+            ns::KeyedUnarchiver::unarchived_obj_of_cls(ns::String::cls(), data.as_ns()).unwrap();
+        assert_eq!(str.to_string(), "native");
+    }
 }
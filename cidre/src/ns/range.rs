@@ -14,28 +14,67 @@ impl Range {
         Self { loc, len }
     }
 
+    /// End of the range, saturated at `ns::UInteger::MAX` instead of overflowing.
+    ///
+    /// ```
+    /// use cidre::ns;
+    /// assert_eq!(ns::Range::new(2, 3).max(), 5);
+    /// assert_eq!(ns::Range::new(usize::MAX - 1, 10).max(), usize::MAX);
+    /// ```
     #[doc(alias = "NSMaxRange")]
     #[inline]
     pub const fn max(&self) -> ns::UInteger {
-        self.loc + self.len
+        self.loc.saturating_add(self.len)
     }
 
+    /// Disjoint ranges give `0..0`, touching ones an empty range at the shared location.
+    ///
     /// ```
     /// use cidre::ns;
     /// let a = ns::Range::new(0, 10);
     /// let b = ns::Range::new(2, 8);
     /// assert_eq!(ns::Range::intersection(a, b), b);
+    /// assert_eq!(ns::Range::intersection(a, ns::Range::new(10, 5)), ns::Range::new(10, 0));
+    /// assert_eq!(ns::Range::intersection(a, ns::Range::new(11, 5)), ns::Range::new(0, 0));
+    ///
+    /// let big = ns::Range::new(usize::MAX - 1, usize::MAX);
+    /// assert_eq!(ns::Range::intersection(big, a), ns::Range::new(0, 0));
+    /// assert_eq!(ns::Range::intersection(big, big), ns::Range::new(usize::MAX - 1, 1));
     /// ```
     #[doc(alias = "NSIntersectionRange")]
     #[inline]
-    pub fn intersection(a: Self, b: Self) -> Self {
-        unsafe { NSIntersectionRange(a, b) }
+    pub const fn intersection(a: Self, b: Self) -> Self {
+        if a.max() < b.loc || b.max() < a.loc {
+            return Self::new(0, 0);
+        }
+        let loc = if a.loc > b.loc { a.loc } else { b.loc };
+        let max = if a.max() < b.max() { a.max() } else { b.max() };
+        Self::new(loc, max - loc)
     }
 
+    /// Smallest range covering both, including any gap between them.
+    ///
+    /// ```
+    /// use cidre::ns;
+    /// let a = ns::Range::new(0, 2);
+    /// let b = ns::Range::new(5, 5);
+    /// assert_eq!(ns::Range::union(a, b), ns::Range::new(0, 10));
+    /// assert_eq!(
+    ///     ns::Range::union(a, ns::Range::new(usize::MAX, 1)),
+    ///     ns::Range::new(0, usize::MAX)
+    /// );
+    /// ```
     #[doc(alias = "NSUnionRange")]
     #[inline]
-    pub fn union(a: Self, b: Self) -> Self {
-        unsafe { NSUnionRange(a, b) }
+    pub const fn union(a: Self, b: Self) -> Self {
+        let loc = if a.loc < b.loc { a.loc } else { b.loc };
+        let max = if a.max() > b.max() { a.max() } else { b.max() };
+        Self::new(loc, max - loc)
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
     /// ```
@@ -61,6 +100,20 @@ impl Range {
     pub const fn contains(&self, location: ns::UInteger) -> bool {
         Range::loc_in_range(location, self)
     }
+
+    /// ```
+    /// use cidre::ns;
+    ///
+    /// let a = ns::Range::new(0, 10);
+    /// assert!(a.contains_range(ns::Range::new(2, 8)));
+    /// assert!(a.contains_range(ns::Range::new(10, 0)));
+    /// assert!(!a.contains_range(ns::Range::new(2, 9)));
+    /// assert!(!a.contains_range(ns::Range::new(5, usize::MAX)));
+    /// ```
+    #[inline]
+    pub const fn contains_range(&self, other: Self) -> bool {
+        other.loc >= self.loc && other.max() <= self.max()
+    }
 }
 
 impl From<std::ops::Range<usize>> for Range {
@@ -73,15 +126,16 @@ impl From<std::ops::Range<usize>> for Range {
     }
 }
 
+impl From<Range> for std::ops::Range<usize> {
+    #[inline]
+    fn from(value: Range) -> Self {
+        value.loc..value.max()
+    }
+}
+
 /// NSValueRangeExtensions
 impl ns::Value {
     /// Creates a new value object containing the specified Foundation range structure.
     #[objc::msg_send(valueWithRange:)]
     pub fn with_range(range: ns::Range) -> arc::R<Self>;
 }
-
-#[link(name = "Foundation", kind = "framework")]
-unsafe extern "C" {
-    fn NSIntersectionRange(a: Range, b: Range) -> Range;
-    fn NSUnionRange(a: Range, b: Range) -> Range;
-}
@@ -54,6 +54,20 @@ impl From<Error> for Status {
     }
 }
 
+impl From<crate::FourCc> for Status {
+    #[inline]
+    fn from(value: crate::FourCc) -> Self {
+        Self(value.0 as i32)
+    }
+}
+
+impl From<Status> for crate::FourCc {
+    #[inline]
+    fn from(value: Status) -> Self {
+        Self(value.0 as u32)
+    }
+}
+
 impl std::fmt::Debug for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let val = self.0;
@@ -0,0 +1,144 @@
+//! Native property list codec.
+//!
+//! Reads and writes `bplist00` binary and XML property lists without CoreFoundation.
+//! [`KeyedArchive`] decodes and encodes `NSKeyedArchiver` object graphs on top of it.
+
+mod value;
+pub use value::Date;
+pub use value::Uid;
+pub use value::Value;
+
+mod binary;
+mod xml;
+
+mod keyed_archive;
+pub use keyed_archive::KeyedArchive;
+pub use keyed_archive::MAX_OBJ_DEPTH;
+pub use keyed_archive::Obj as ArchiveObj;
+pub use keyed_archive::Record as ArchiveRecord;
+
+/// Max nesting of arrays and dictionaries accepted by readers and writers.
+pub const MAX_DEPTH: usize = 512;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    #[doc(alias = "kCFPropertyListXMLFormat_v1_0")]
+    Xml,
+
+    #[doc(alias = "kCFPropertyListBinaryFormat_v1_0")]
+    Binary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    UnexpectedEof,
+    InvalidHeader,
+    InvalidTrailer,
+
+    /// Object offset points outside of object area
+    InvalidOffset(u64),
+
+    /// Object reference is out of object table
+    InvalidObjRef(u64),
+
+    InvalidMarker(u8),
+    InvalidString,
+
+    /// Dictionary key is not a string
+    InvalidKey,
+
+    /// Object references one of its parents
+    Cycle,
+
+    /// Nesting is deeper than [`MAX_DEPTH`] or [`MAX_OBJ_DEPTH`] for archives
+    TooDeep,
+
+    /// Shared objects expand to too many values
+    TooManyObjects,
+
+    /// Integer doesn't fit in `i64` or `u64`
+    IntOutOfRange,
+
+    /// Date is NaN or doesn't fit in `SystemTime`
+    DateOutOfRange,
+
+    Xml {
+        pos: usize,
+        msg: &'static str,
+    },
+
+    /// Keyed archive structure is invalid
+    Archive(&'static str),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEof => f.write_str("unexpected end of plist"),
+            Self::InvalidHeader => f.write_str("invalid plist header"),
+            Self::InvalidTrailer => f.write_str("invalid binary plist trailer"),
+            Self::InvalidOffset(off) => write!(f, "invalid object offset {off}"),
+            Self::InvalidObjRef(obj) => write!(f, "invalid object reference {obj}"),
+            Self::InvalidMarker(marker) => write!(f, "invalid object marker {marker:#04x}"),
+            Self::InvalidString => f.write_str("invalid string encoding"),
+            Self::InvalidKey => f.write_str("dictionary key is not a string"),
+            Self::Cycle => f.write_str("object graph contains a cycle"),
+            Self::TooDeep => f.write_str("nesting is too deep"),
+            Self::TooManyObjects => f.write_str("too many objects"),
+            Self::IntOutOfRange => f.write_str("integer is out of range"),
+            Self::DateOutOfRange => f.write_str("date is out of range"),
+            Self::Xml { pos, msg } => write!(f, "xml error at {pos}: {msg}"),
+            Self::Archive(msg) => write!(f, "invalid keyed archive: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T = Value> = std::result::Result<T, Error>;
+
+impl Value {
+    /// Reads binary or XML plist detecting format by header.
+    pub fn from_bytes(bytes: &[u8]) -> Result {
+        Self::from_bytes_with_format(bytes).map(|(v, _)| v)
+    }
+
+    pub fn from_bytes_with_format(bytes: &[u8]) -> Result<(Value, Format)> {
+        if bytes.starts_with(binary::MAGIC) {
+            Self::from_binary(bytes).map(|v| (v, Format::Binary))
+        } else {
+            let str = std::str::from_utf8(bytes).map_err(|_| Error::InvalidString)?;
+            Self::from_xml(str).map(|v| (v, Format::Xml))
+        }
+    }
+
+    pub fn to_bytes(&self, format: Format) -> Result<Vec<u8>> {
+        match format {
+            Format::Xml => self.to_xml().map(String::into_bytes),
+            Format::Binary => self.to_binary(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plist;
+
+    #[test]
+    fn detect_format() {
+        let val = plist::Value::Dict(vec![
+            ("name".into(), "cidre".into()),
+            ("ver".into(), 7.into()),
+        ]);
+        for format in [plist::Format::Xml, plist::Format::Binary] {
+            let bytes = val.to_bytes(format).unwrap();
+            let (res, detected) = plist::Value::from_bytes_with_format(&bytes).unwrap();
+            assert_eq!(detected, format);
+            assert_eq!(res, val);
+        }
+        assert_eq!(
+            plist::Value::from_bytes(&[0xff, 0xfe]),
+            Err(plist::Error::InvalidString)
+        );
+    }
+}
@@ -0,0 +1,740 @@
+use std::collections::HashMap;
+
+use crate::plist::{Date, Error, MAX_DEPTH, Result, Uid, Value};
+
+pub(crate) const MAGIC: &[u8] = b"bplist00";
+
+const TRAILER_LEN: usize = 32;
+
+struct Trailer {
+    offset_size: usize,
+    ref_size: usize,
+    objects_count: u64,
+    top_obj: u64,
+    offsets_start: usize,
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    trailer: Trailer,
+    /// Objects on the current path, to detect cycles
+    path: Vec<u64>,
+    /// Decoded values budget, protects from shared objects expanding exponentially
+    budget: usize,
+}
+
+#[inline]
+fn be_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Result<Self> {
+        if !buf.starts_with(MAGIC) {
+            return Err(Error::InvalidHeader);
+        }
+        if buf.len() < MAGIC.len() + TRAILER_LEN {
+            return Err(Error::UnexpectedEof);
+        }
+        let t = &buf[buf.len() - TRAILER_LEN..];
+        let offset_size = t[6] as usize;
+        let ref_size = t[7] as usize;
+        let objects_count = be_uint(&t[8..16]);
+        let top_obj = be_uint(&t[16..24]);
+        let offsets_start = be_uint(&t[24..32]);
+
+        if !(1..=8).contains(&offset_size) || !(1..=8).contains(&ref_size) {
+            return Err(Error::InvalidTrailer);
+        }
+        if objects_count == 0 || top_obj >= objects_count {
+            return Err(Error::InvalidTrailer);
+        }
+        let trailer_start = (buf.len() - TRAILER_LEN) as u64;
+        let offsets_end = objects_count
+            .checked_mul(offset_size as u64)
+            .and_then(|len| len.checked_add(offsets_start));
+        match offsets_end {
+            Some(end) if offsets_start >= MAGIC.len() as u64 && end <= trailer_start => {}
+            _ => return Err(Error::InvalidTrailer),
+        }
+        Ok(Self {
+            buf,
+            trailer: Trailer {
+                offset_size,
+                ref_size,
+                objects_count,
+                top_obj,
+                offsets_start: offsets_start as usize,
+            },
+            path: Vec::new(),
+            budget: buf.len().saturating_mul(4).max(1024),
+        })
+    }
+
+    fn obj_offset(&self, obj: u64) -> Result<usize> {
+        if obj >= self.trailer.objects_count {
+            return Err(Error::InvalidObjRef(obj));
+        }
+        let size = self.trailer.offset_size;
+        let pos = self.trailer.offsets_start + obj as usize * size;
+        let off = be_uint(&self.buf[pos..pos + size]);
+        // objects live between the header and the offset table
+        if off < MAGIC.len() as u64 || off >= self.trailer.offsets_start as u64 {
+            return Err(Error::InvalidOffset(off));
+        }
+        Ok(off as usize)
+    }
+
+    /// Bytes available for object at offset
+    #[inline]
+    fn bytes(&self, pos: usize, len: usize) -> Result<&'a [u8]> {
+        let end = pos.checked_add(len).ok_or(Error::UnexpectedEof)?;
+        if end > self.trailer.offsets_start {
+            return Err(Error::UnexpectedEof);
+        }
+        Ok(&self.buf[pos..end])
+    }
+
+    /// Reads count from marker low nibble or following int object.
+    /// Returns count and position of the payload.
+    fn count(&self, marker: u8, pos: usize) -> Result<(usize, usize)> {
+        let nibble = marker & 0x0f;
+        if nibble != 0x0f {
+            return Ok((nibble as usize, pos));
+        }
+        let int_marker = self.bytes(pos, 1)?[0];
+        if int_marker & 0xf0 != 0x10 || int_marker & 0x0f > 3 {
+            return Err(Error::InvalidMarker(int_marker));
+        }
+        let len = 1usize << (int_marker & 0x0f);
+        let count = be_uint(self.bytes(pos + 1, len)?);
+        let count = usize::try_from(count).map_err(|_| Error::UnexpectedEof)?;
+        Ok((count, pos + 1 + len))
+    }
+
+    fn obj_ref(&self, pos: usize, i: usize) -> Result<u64> {
+        let size = self.trailer.ref_size;
+        Ok(be_uint(self.bytes(pos + i * size, size)?))
+    }
+
+    fn refs(&self, count: usize, pos: usize) -> Result<()> {
+        let len = count
+            .checked_mul(self.trailer.ref_size)
+            .ok_or(Error::UnexpectedEof)?;
+        self.bytes(pos, len).map(|_| ())
+    }
+
+    fn read(&mut self, obj: u64, depth: usize) -> Result {
+        if depth > MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        if self.budget == 0 {
+            return Err(Error::TooManyObjects);
+        }
+        self.budget -= 1;
+
+        let pos = self.obj_offset(obj)?;
+        let marker = self.bytes(pos, 1)?[0];
+        let pos = pos + 1;
+
+        let val = match marker >> 4 {
+            // sets are read as arrays
+            0xa | 0xc => {
+                let (count, pos) = self.count(marker, pos)?;
+                self.refs(count, pos)?;
+                self.path.push(obj);
+                let mut arr = Vec::with_capacity(count);
+                for i in 0..count {
+                    arr.push(self.read_child(self.obj_ref(pos, i)?, depth)?);
+                }
+                self.path.pop();
+                Value::Array(arr)
+            }
+            0xd => {
+                let (count, pos) = self.count(marker, pos)?;
+                self.refs(count.checked_mul(2).ok_or(Error::UnexpectedEof)?, pos)?;
+                self.path.push(obj);
+                let mut dict = Vec::with_capacity(count);
+                for i in 0..count {
+                    let Value::String(key) = self.read_child(self.obj_ref(pos, i)?, depth)? else {
+                        return Err(Error::InvalidKey);
+                    };
+                    let val = self.read_child(self.obj_ref(pos, count + i)?, depth)?;
+                    dict.push((key, val));
+                }
+                self.path.pop();
+                Value::Dict(dict)
+            }
+            _ => self.read_scalar(marker, pos)?,
+        };
+        Ok(val)
+    }
+
+    /// Kept out of `read` to keep recursion frames small
+    #[inline(never)]
+    fn read_scalar(&self, marker: u8, pos: usize) -> Result {
+        let nibble = marker & 0x0f;
+        let val = match marker >> 4 {
+            0x0 => match marker {
+                0x08 => Value::Bool(false),
+                0x09 => Value::Bool(true),
+                _ => return Err(Error::InvalidMarker(marker)),
+            },
+            0x1 => {
+                let bytes = match nibble {
+                    0..=4 => self.bytes(pos, 1 << nibble)?,
+                    _ => return Err(Error::InvalidMarker(marker)),
+                };
+                let val = match nibble {
+                    // 1, 2 and 4 byte ints are unsigned
+                    0..=2 => be_uint(bytes) as i128,
+                    3 => be_uint(bytes) as i64 as i128,
+                    _ => i128::from_be_bytes(bytes.try_into().unwrap()),
+                };
+                if val < i64::MIN as i128 || val > u64::MAX as i128 {
+                    return Err(Error::IntOutOfRange);
+                }
+                Value::Int(val)
+            }
+            0x2 => match nibble {
+                2 => Value::Real(f32::from_be_bytes(self.bytes(pos, 4)?.try_into().unwrap()) as _),
+                3 => Value::Real(f64::from_be_bytes(self.bytes(pos, 8)?.try_into().unwrap())),
+                _ => return Err(Error::InvalidMarker(marker)),
+            },
+            0x3 if marker == 0x33 => {
+                let secs = f64::from_be_bytes(self.bytes(pos, 8)?.try_into().unwrap());
+                Value::Date(Date(secs))
+            }
+            0x4 => {
+                let (len, pos) = self.count(marker, pos)?;
+                Value::Data(self.bytes(pos, len)?.to_vec())
+            }
+            0x5 => {
+                let (len, pos) = self.count(marker, pos)?;
+                let bytes = self.bytes(pos, len)?;
+                if !bytes.is_ascii() {
+                    return Err(Error::InvalidString);
+                }
+                // SAFETY: ASCII is valid UTF-8
+                Value::String(unsafe { String::from_utf8_unchecked(bytes.to_vec()) })
+            }
+            0x6 => {
+                let (len, pos) = self.count(marker, pos)?;
+                let len = len.checked_mul(2).ok_or(Error::UnexpectedEof)?;
+                let units = self
+                    .bytes(pos, len)?
+                    .chunks_exact(2)
+                    .map(|c| u16::from_be_bytes([c[0], c[1]]));
+                let str = char::decode_utf16(units)
+                    .collect::<std::result::Result<String, _>>()
+                    .map_err(|_| Error::InvalidString)?;
+                Value::String(str)
+            }
+            0x8 => {
+                let len = nibble as usize + 1;
+                if len > 8 {
+                    return Err(Error::InvalidMarker(marker));
+                }
+                Value::Uid(Uid(be_uint(self.bytes(pos, len)?)))
+            }
+            _ => return Err(Error::InvalidMarker(marker)),
+        };
+        Ok(val)
+    }
+
+    #[inline]
+    fn read_child(&mut self, obj: u64, depth: usize) -> Result {
+        if self.path.contains(&obj) {
+            return Err(Error::Cycle);
+        }
+        self.read(obj, depth + 1)
+    }
+}
+
+impl Value {
+    /// Reads `bplist00` binary property list.
+    pub fn from_binary(bytes: &[u8]) -> Result {
+        let mut reader = Reader::new(bytes)?;
+        let top = reader.trailer.top_obj;
+        reader.read(top, 0)
+    }
+
+    /// Writes `bplist00` binary property list.
+    ///
+    /// Equal scalar values are stored once like CoreFoundation does.
+    pub fn to_binary(&self) -> Result<Vec<u8>> {
+        let mut writer = Writer::default();
+        writer.flatten(self, 0)?;
+        Ok(writer.finish())
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Key<'a> {
+    Str(&'a str),
+    Data(&'a [u8]),
+    Int(i128),
+    Real(u64),
+    Date(u64),
+    Bool(bool),
+    Uid(u64),
+}
+
+enum Obj<'a> {
+    Scalar(&'a Value),
+    /// Dictionary key
+    Str(&'a str),
+    Array(Vec<usize>),
+    Dict(Vec<usize>),
+}
+
+#[derive(Default)]
+struct Writer<'a> {
+    objs: Vec<Obj<'a>>,
+    uniq: HashMap<Key<'a>, usize>,
+}
+
+impl<'a> Writer<'a> {
+    fn flatten(&mut self, val: &'a Value, depth: usize) -> Result<usize> {
+        if depth > MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        let key = match val {
+            Value::Array(arr) => {
+                let idx = self.objs.len();
+                self.objs.push(Obj::Array(Vec::new()));
+                let mut refs = Vec::with_capacity(arr.len());
+                for v in arr {
+                    refs.push(self.flatten(v, depth + 1)?);
+                }
+                self.objs[idx] = Obj::Array(refs);
+                return Ok(idx);
+            }
+            Value::Dict(dict) => {
+                let idx = self.objs.len();
+                self.objs.push(Obj::Dict(Vec::new()));
+                let mut refs = Vec::with_capacity(dict.len() * 2);
+                for (k, _) in dict {
+                    refs.push(self.scalar(Key::Str(k), None)?);
+                }
+                for (_, v) in dict {
+                    refs.push(self.flatten(v, depth + 1)?);
+                }
+                self.objs[idx] = Obj::Dict(refs);
+                return Ok(idx);
+            }
+            Value::String(s) => Key::Str(s),
+            Value::Data(d) => Key::Data(d),
+            Value::Date(d) => Key::Date(d.0.to_bits()),
+            Value::Int(i) => {
+                if *i < i64::MIN as i128 || *i > u64::MAX as i128 {
+                    return Err(Error::IntOutOfRange);
+                }
+                Key::Int(*i)
+            }
+            Value::Real(r) => Key::Real(r.to_bits()),
+            Value::Bool(b) => Key::Bool(*b),
+            Value::Uid(u) => Key::Uid(u.0),
+        };
+        self.scalar(key, Some(val))
+    }
+
+    fn scalar(&mut self, key: Key<'a>, val: Option<&'a Value>) -> Result<usize> {
+        if let Some(idx) = self.uniq.get(&key) {
+            return Ok(*idx);
+        }
+        let idx = self.objs.len();
+        self.objs.push(match (val, &key) {
+            (Some(val), _) => Obj::Scalar(val),
+            (None, Key::Str(str)) => Obj::Str(str),
+            (None, _) => unreachable!(),
+        });
+        self.uniq.insert(key, idx);
+        Ok(idx)
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let ref_size = int_size(self.objs.len() as u64);
+        let mut buf = MAGIC.to_vec();
+        let mut offsets = Vec::with_capacity(self.objs.len());
+        for obj in self.objs.iter() {
+            offsets.push(buf.len() as u64);
+            match obj {
+                Obj::Scalar(val) => write_scalar(&mut buf, val),
+                Obj::Str(str) => write_str(&mut buf, str),
+                Obj::Array(refs) => {
+                    write_marker(&mut buf, 0xa0, refs.len());
+                    write_refs(&mut buf, refs, ref_size);
+                }
+                Obj::Dict(refs) => {
+                    write_marker(&mut buf, 0xd0, refs.len() / 2);
+                    write_refs(&mut buf, refs, ref_size);
+                }
+            }
+        }
+
+        let offsets_start = buf.len() as u64;
+        let offset_size = int_size(offsets_start);
+        for off in offsets {
+            buf.extend_from_slice(&off.to_be_bytes()[8 - offset_size..]);
+        }
+        buf.extend_from_slice(&[0; 6]);
+        buf.push(offset_size as u8);
+        buf.push(ref_size as u8);
+        buf.extend_from_slice(&(self.objs.len() as u64).to_be_bytes());
+        buf.extend_from_slice(&0u64.to_be_bytes());
+        buf.extend_from_slice(&offsets_start.to_be_bytes());
+        buf
+    }
+}
+
+/// Bytes needed to store values up to `max`
+fn int_size(max: u64) -> usize {
+    match max {
+        0..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x1_0000..=0xffff_ffff => 4,
+        _ => 8,
+    }
+}
+
+fn write_refs(buf: &mut Vec<u8>, refs: &[usize], ref_size: usize) {
+    for r in refs {
+        buf.extend_from_slice(&(*r as u64).to_be_bytes()[8 - ref_size..]);
+    }
+}
+
+fn write_int(buf: &mut Vec<u8>, val: i128) {
+    if val < 0 {
+        buf.push(0x13);
+        buf.extend_from_slice(&(val as i64).to_be_bytes());
+    } else if val > i64::MAX as i128 {
+        buf.push(0x14);
+        buf.extend_from_slice(&val.to_be_bytes());
+    } else {
+        let size = int_size(val as u64);
+        buf.push(0x10 | size.trailing_zeros() as u8);
+        buf.extend_from_slice(&(val as u64).to_be_bytes()[8 - size..]);
+    }
+}
+
+fn write_marker(buf: &mut Vec<u8>, marker: u8, count: usize) {
+    if count < 15 {
+        buf.push(marker | count as u8);
+    } else {
+        buf.push(marker | 0x0f);
+        write_int(buf, count as i128);
+    }
+}
+
+fn write_str(buf: &mut Vec<u8>, str: &str) {
+    if str.is_ascii() {
+        write_marker(buf, 0x50, str.len());
+        buf.extend_from_slice(str.as_bytes());
+    } else {
+        let units: Vec<u16> = str.encode_utf16().collect();
+        write_marker(buf, 0x60, units.len());
+        for u in units {
+            buf.extend_from_slice(&u.to_be_bytes());
+        }
+    }
+}
+
+fn write_scalar(buf: &mut Vec<u8>, val: &Value) {
+    match val {
+        Value::String(s) => write_str(buf, s),
+        Value::Data(d) => {
+            write_marker(buf, 0x40, d.len());
+            buf.extend_from_slice(d);
+        }
+        Value::Date(d) => {
+            buf.push(0x33);
+            buf.extend_from_slice(&d.0.to_be_bytes());
+        }
+        Value::Int(i) => write_int(buf, *i),
+        Value::Real(r) => {
+            buf.push(0x23);
+            buf.extend_from_slice(&r.to_be_bytes());
+        }
+        Value::Bool(b) => buf.push(if *b { 0x09 } else { 0x08 }),
+        Value::Uid(u) => {
+            let size = int_size(u.0);
+            buf.push(0x80 | (size - 1) as u8);
+            buf.extend_from_slice(&u.0.to_be_bytes()[8 - size..]);
+        }
+        Value::Array(_) | Value::Dict(_) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plist::{self, Date, Error, MAX_DEPTH, Uid, Value};
+
+    fn sample() -> Value {
+        Value::Dict(vec![
+            ("CFBundleName".into(), "cidre".into()),
+            ("unicode".into(), "héllo ✓ 𝄞".into()),
+            ("empty".into(), "".into()),
+            ("int".into(), 42.into()),
+            ("neg".into(), (-5).into()),
+            ("big".into(), u64::MAX.into()),
+            ("min".into(), i64::MIN.into()),
+            ("real".into(), 0.1.into()),
+            ("yes".into(), true.into()),
+            ("no".into(), false.into()),
+            ("date".into(), Date(123.5).into()),
+            ("data".into(), vec![0u8, 1, 2, 255].into()),
+            ("uid".into(), Uid(300).into()),
+            (
+                "list".into(),
+                Value::Array((0..20).map(|i| Value::from(i % 3)).collect()),
+            ),
+            (
+                "nested".into(),
+                Value::Dict(vec![("int".into(), 42.into())]),
+            ),
+            ("empty_list".into(), Value::Array(vec![])),
+            ("long".into(), "x".repeat(300).into()),
+        ])
+    }
+
+    #[test]
+    fn round_trip() {
+        let val = sample();
+        let bytes = val.to_binary().unwrap();
+        assert!(bytes.starts_with(b"bplist00"));
+        assert_eq!(Value::from_binary(&bytes).unwrap(), val);
+
+        for v in [Value::from(1), Value::from("a"), Value::Array(vec![])] {
+            assert_eq!(Value::from_binary(&v.to_binary().unwrap()).unwrap(), v);
+        }
+    }
+
+    #[test]
+    fn uniques_scalars() {
+        let val = Value::Array(vec!["same".into(), "same".into(), 1.into(), 1.into()]);
+        let bytes = val.to_binary().unwrap();
+        // array + string + int
+        assert_eq!(bytes[bytes.len() - 17], 3);
+        assert_eq!(Value::from_binary(&bytes).unwrap(), val);
+    }
+
+    #[test]
+    fn known_bytes() {
+        // plutil -convert binary1 of <dict><key>a</key><integer>1</integer></dict>
+        let bytes = [
+            0x62, 0x70, 0x6c, 0x69, 0x73, 0x74, 0x30, 0x30, 0xd1, 0x01, 0x02, 0x51, 0x61, 0x10,
+            0x01, 0x08, 0x0b, 0x0d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0f,
+        ];
+        let val = Value::from_binary(&bytes).unwrap();
+        assert_eq!(val, Value::Dict(vec![("a".into(), 1.into())]));
+        assert_eq!(val.to_binary().unwrap(), bytes);
+    }
+
+    fn patch_trailer(bytes: &mut [u8], at: usize, val: &[u8]) {
+        let start = bytes.len() - 32 + at;
+        bytes[start..start + val.len()].copy_from_slice(val);
+    }
+
+    #[test]
+    fn malformed() {
+        let good = sample().to_binary().unwrap();
+
+        assert_eq!(Value::from_binary(b"bplist00"), Err(Error::UnexpectedEof));
+        assert_eq!(Value::from_binary(b"bplist01"), Err(Error::InvalidHeader));
+
+        let mut bytes = good.clone();
+        patch_trailer(&mut bytes, 6, &[0]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidTrailer));
+
+        let mut bytes = good.clone();
+        patch_trailer(&mut bytes, 7, &[9]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidTrailer));
+
+        // objects count overflows offset table
+        let mut bytes = good.clone();
+        patch_trailer(&mut bytes, 8, &u64::MAX.to_be_bytes());
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidTrailer));
+
+        // top object out of range
+        let mut bytes = good.clone();
+        patch_trailer(&mut bytes, 16, &u64::MAX.to_be_bytes());
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidTrailer));
+
+        // offset table inside header
+        let mut bytes = good.clone();
+        patch_trailer(&mut bytes, 24, &2u64.to_be_bytes());
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidTrailer));
+
+        // offset table overlaps trailer
+        let mut bytes = good.clone();
+        let len = bytes.len() as u64;
+        patch_trailer(&mut bytes, 24, &(len - 33).to_be_bytes());
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidTrailer));
+
+        // first offset points into offset table
+        let mut bytes = good.clone();
+        let table = u64::from_be_bytes(bytes[bytes.len() - 8..].try_into().unwrap()) as usize;
+        let offset_size = bytes[bytes.len() - 26] as usize;
+        for b in &mut bytes[table..table + offset_size] {
+            *b = 0xff;
+        }
+        assert!(matches!(
+            Value::from_binary(&bytes),
+            Err(Error::InvalidOffset(_))
+        ));
+    }
+
+    /// Builds plist with hand written objects and 1 byte offsets and refs.
+    fn build(objs: &[&[u8]]) -> Vec<u8> {
+        let mut buf = b"bplist00".to_vec();
+        let mut offsets = vec![];
+        for obj in objs {
+            offsets.push(buf.len() as u8);
+            buf.extend_from_slice(obj);
+        }
+        let table = buf.len() as u64;
+        buf.extend_from_slice(&offsets);
+        buf.extend_from_slice(&[0, 0, 0, 0, 0, 0, 1, 1]);
+        buf.extend_from_slice(&(objs.len() as u64).to_be_bytes());
+        buf.extend_from_slice(&0u64.to_be_bytes());
+        buf.extend_from_slice(&table.to_be_bytes());
+        buf
+    }
+
+    #[test]
+    fn malformed_objects() {
+        // array referencing itself
+        let bytes = build(&[&[0xa1, 0x00]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::Cycle));
+
+        // indirect cycle
+        let bytes = build(&[&[0xa1, 0x01], &[0xa1, 0x00]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::Cycle));
+
+        // shared child is not a cycle
+        let bytes = build(&[&[0xa2, 0x01, 0x01], &[0x09]]);
+        assert_eq!(
+            Value::from_binary(&bytes),
+            Ok(Value::Array(vec![true.into(), true.into()]))
+        );
+
+        // ref out of range
+        let bytes = build(&[&[0xa1, 0x05]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidObjRef(5)));
+
+        // count larger than data
+        let bytes = build(&[&[0x4f, 0x13, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::UnexpectedEof));
+        let bytes = build(&[&[0xaf, 0x10, 0xff, 0x00]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::UnexpectedEof));
+
+        // non string key
+        let bytes = build(&[&[0xd1, 0x01, 0x01], &[0x09]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidKey));
+
+        // unpaired surrogate
+        let bytes = build(&[&[0x61, 0xd8, 0x00]]);
+        assert_eq!(Value::from_binary(&bytes), Err(Error::InvalidString));
+
+        assert_eq!(
+            Value::from_binary(&build(&[&[0x70]])),
+            Err(Error::InvalidMarker(0x70))
+        );
+        assert_eq!(
+            Value::from_binary(&build(&[&[0x15]])),
+            Err(Error::InvalidMarker(0x15))
+        );
+
+        // 16 byte ints above u64::MAX
+        let mut obj = vec![0x14];
+        obj.extend_from_slice(&(u64::MAX as i128 + 1).to_be_bytes());
+        assert_eq!(
+            Value::from_binary(&build(&[&obj])),
+            Err(Error::IntOutOfRange)
+        );
+    }
+
+    #[test]
+    fn exponential_sharing() {
+        // each array references the next one twice
+        let objs: Vec<Vec<u8>> = (0..60u8)
+            .map(|i| {
+                if i == 59 {
+                    vec![0x09]
+                } else {
+                    vec![0xa2, i + 1, i + 1]
+                }
+            })
+            .collect();
+        let objs: Vec<&[u8]> = objs.iter().map(|o| o.as_slice()).collect();
+        assert_eq!(
+            Value::from_binary(&build(&objs)),
+            Err(Error::TooManyObjects)
+        );
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let mut val = Value::Bool(true);
+        for _ in 0..MAX_DEPTH {
+            val = Value::Array(vec![val]);
+        }
+        let bytes = val.to_binary().unwrap();
+        assert_eq!(Value::from_binary(&bytes).unwrap(), val);
+
+        let too_deep = Value::Array(vec![val]);
+        assert_eq!(too_deep.to_binary(), Err(Error::TooDeep));
+
+        // hand made chain deeper than the limit
+        let mut buf = b"bplist00".to_vec();
+        let count = MAX_DEPTH + 10;
+        let mut offsets = vec![];
+        for i in 0..count {
+            offsets.push(buf.len() as u16);
+            if i + 1 == count {
+                buf.push(0x09);
+            } else {
+                buf.push(0xa1);
+                buf.extend_from_slice(&(i as u16 + 1).to_be_bytes());
+            }
+        }
+        let table = buf.len() as u64;
+        for off in offsets {
+            buf.extend_from_slice(&off.to_be_bytes());
+        }
+        buf.extend_from_slice(&[0, 0, 0, 0, 0, 0, 2, 2]);
+        buf.extend_from_slice(&(count as u64).to_be_bytes());
+        buf.extend_from_slice(&0u64.to_be_bytes());
+        buf.extend_from_slice(&table.to_be_bytes());
+        assert_eq!(Value::from_binary(&buf), Err(Error::TooDeep));
+    }
+
+    #[test]
+    fn fuzz() {
+        // xorshift, deterministic
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut rand = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let good = sample().to_binary().unwrap();
+        for _ in 0..20_000 {
+            let mut bytes = good.clone();
+            for _ in 0..(rand() % 8 + 1) {
+                let pos = (rand() % bytes.len() as u64) as usize;
+                bytes[pos] = rand() as u8;
+            }
+            if rand() % 4 == 0 {
+                let len = (rand() % bytes.len() as u64) as usize;
+                bytes.truncate(len);
+            }
+            // must never panic
+            let _ = plist::Value::from_bytes(&bytes);
+        }
+    }
+}
@@ -0,0 +1,755 @@
+use std::collections::HashMap;
+
+use crate::plist::{Date, Error, Result, Uid, Value};
+
+/// Max nesting of archived objects.
+///
+/// Lower than [`MAX_DEPTH`](crate::plist::MAX_DEPTH) since every object level
+/// takes several recursive calls.
+pub const MAX_OBJ_DEPTH: usize = 256;
+
+const ARCHIVER: &str = "NSKeyedArchiver";
+const VERSION: i128 = 100_000;
+const NULL: &str = "$null";
+
+/// Object decoded from keyed archive.
+///
+/// Foundation collections, strings, data, dates and numbers are mapped to
+/// typed variants. Other classes are kept as generic records.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Obj {
+    /// `nil`
+    Null,
+    String(String),
+    Data(Vec<u8>),
+    Date(Date),
+    Int(i128),
+    Real(f64),
+    Bool(bool),
+    Array(Vec<Obj>),
+    Set(Vec<Obj>),
+    Dict(Vec<(Obj, Obj)>),
+    Record(Record),
+}
+
+/// Instance of a class without native mapping.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Record {
+    /// Class name followed by its superclasses, e.g. `["MyObj", "NSObject"]`
+    pub classes: Vec<String>,
+
+    /// Encoded fields without `$class`
+    pub fields: Vec<(String, Obj)>,
+}
+
+impl Record {
+    pub fn new(classes: &[&str]) -> Self {
+        Self {
+            classes: classes.iter().map(|c| c.to_string()).collect(),
+            fields: vec![],
+        }
+    }
+
+    #[inline]
+    pub fn class_name(&self) -> Option<&str> {
+        self.classes.first().map(String::as_str)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Obj> {
+        self.fields
+            .iter()
+            .find_map(|(k, v)| if k == key { Some(v) } else { None })
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Obj>) {
+        let key = key.into();
+        let value = value.into();
+        match self.fields.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => self.fields.push((key, value)),
+        }
+    }
+}
+
+impl Obj {
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_array(&self) -> Option<&[Obj]> {
+        match self {
+            Self::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_record(&self) -> Option<&Record> {
+        match self {
+            Self::Record(r) => Some(r),
+            _ => None,
+        }
+    }
+
+    /// Looks up dictionary value by string key or record field.
+    pub fn get(&self, key: &str) -> Option<&Obj> {
+        match self {
+            Self::Dict(d) => d.iter().find_map(|(k, v)| {
+                if k.as_str() == Some(key) {
+                    Some(v)
+                } else {
+                    None
+                }
+            }),
+            Self::Record(r) => r.get(key),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! from {
+    ($($t:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$t> for Obj {
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+from!(
+    &str => String,
+    String => String,
+    Vec<u8> => Data,
+    Date => Date,
+    i32 => Int,
+    i64 => Int,
+    u64 => Int,
+    f64 => Real,
+    bool => Bool,
+    Vec<Obj> => Array,
+    Vec<(Obj, Obj)> => Dict,
+    Record => Record,
+);
+
+/// `NSKeyedArchiver` archive.
+///
+/// Decodes the `$objects` graph resolving uids into an object tree and encodes
+/// the tree back in the form `ns::KeyedUnarchiver` accepts.
+#[doc(alias = "NSKeyedArchiver")]
+#[doc(alias = "NSKeyedUnarchiver")]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeyedArchive {
+    /// Top level objects, `root` for archives made with root object
+    pub top: Vec<(String, Obj)>,
+}
+
+impl KeyedArchive {
+    pub fn with_root(root: impl Into<Obj>) -> Self {
+        Self {
+            top: vec![("root".to_string(), root.into())],
+        }
+    }
+
+    pub fn root(&self) -> Option<&Obj> {
+        self.top
+            .iter()
+            .find_map(|(k, v)| if k == "root" { Some(v) } else { None })
+    }
+
+    /// Reads archive from binary or XML plist.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::from_value(&Value::from_bytes(bytes)?)
+    }
+
+    /// Writes archive as binary plist.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.to_value()?.to_binary()
+    }
+
+    /// Resolves archive plist into object tree.
+    ///
+    /// Shared objects are duplicated, cyclic graphs are rejected with [`Error::Cycle`].
+    pub fn from_value(value: &Value) -> Result<Self> {
+        if value.get("$archiver").and_then(Value::as_str) != Some(ARCHIVER) {
+            return Err(Error::Archive("unsupported $archiver"));
+        }
+        let Some(objects) = value.get("$objects").and_then(Value::as_array) else {
+            return Err(Error::Archive("missing $objects"));
+        };
+        let Some(top) = value.get("$top").and_then(Value::as_dict) else {
+            return Err(Error::Archive("missing $top"));
+        };
+        let mut decoder = Decoder {
+            objects,
+            path: vec![],
+            budget: objects.len().saturating_mul(16).max(1024),
+        };
+        let mut res = Vec::with_capacity(top.len());
+        for (key, val) in top {
+            res.push((key.clone(), decoder.field(val, 0)?));
+        }
+        Ok(Self { top: res })
+    }
+
+    /// Flattens object tree into archive plist.
+    pub fn to_value(&self) -> Result<Value> {
+        let mut encoder = Encoder {
+            objects: vec![Value::from(NULL)],
+            uniq: HashMap::new(),
+            classes: HashMap::new(),
+        };
+        let mut top = Vec::with_capacity(self.top.len());
+        for (key, obj) in &self.top {
+            top.push((key.clone(), Value::Uid(encoder.obj(obj, 0)?)));
+        }
+        Ok(Value::Dict(vec![
+            ("$version".into(), Value::Int(VERSION)),
+            ("$archiver".into(), ARCHIVER.into()),
+            ("$top".into(), Value::Dict(top)),
+            ("$objects".into(), Value::Array(encoder.objects)),
+        ]))
+    }
+}
+
+struct Decoder<'a> {
+    objects: &'a [Value],
+    /// Uids on the current path, to detect cycles
+    path: Vec<u64>,
+    /// Decoded objects budget, protects from shared objects expanding exponentially
+    budget: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn get(&self, uid: Uid) -> Result<&'a Value> {
+        usize::try_from(uid.0)
+            .ok()
+            .and_then(|i| self.objects.get(i))
+            .ok_or(Error::InvalidObjRef(uid.0))
+    }
+
+    /// Decodes value stored inline or referenced by uid.
+    fn field(&mut self, val: &'a Value, depth: usize) -> Result<Obj> {
+        if depth > MAX_OBJ_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        if self.budget == 0 {
+            return Err(Error::TooManyObjects);
+        }
+        self.budget -= 1;
+        match val {
+            Value::Uid(uid) => self.obj(*uid, depth),
+            Value::Array(arr) => self.items(arr, depth + 1).map(Obj::Array),
+            Value::Dict(dict) => self.inline_dict(dict, depth + 1),
+            val => Ok(scalar(val)),
+        }
+    }
+
+    fn items(&mut self, arr: &'a [Value], depth: usize) -> Result<Vec<Obj>> {
+        let mut res = Vec::with_capacity(arr.len());
+        for v in arr {
+            res.push(self.field(v, depth)?);
+        }
+        Ok(res)
+    }
+
+    fn inline_dict(&mut self, dict: &'a [(String, Value)], depth: usize) -> Result<Obj> {
+        let mut res = Vec::with_capacity(dict.len());
+        for (k, v) in dict {
+            res.push((Obj::String(k.clone()), self.field(v, depth)?));
+        }
+        Ok(Obj::Dict(res))
+    }
+
+    fn obj(&mut self, uid: Uid, depth: usize) -> Result<Obj> {
+        let val = self.get(uid)?;
+        if uid.0 == 0 && val.as_str() == Some(NULL) {
+            return Ok(Obj::Null);
+        }
+        if self.path.contains(&uid.0) {
+            return Err(Error::Cycle);
+        }
+        let Value::Dict(dict) = val else {
+            // plain strings, numbers and data, or a uid referencing another object
+            self.path.push(uid.0);
+            let res = self.field(val, depth + 1);
+            self.path.pop();
+            return res;
+        };
+        let classes = self.classes(val)?;
+        self.path.push(uid.0);
+        let depth = depth + 1;
+        let res = match self.known(&classes[..], val, depth)? {
+            Some(obj) => obj,
+            None => self.record(classes, dict, depth)?,
+        };
+        self.path.pop();
+        Ok(res)
+    }
+
+    /// Resolves `$class` of the object into class chain.
+    #[inline(never)]
+    fn classes(&self, val: &'a Value) -> Result<Vec<String>> {
+        let Some(class) = val.get("$class").and_then(Value::as_uid) else {
+            return Err(Error::Archive("object without $class"));
+        };
+        let class = self.get(class)?;
+        match class.get("$classes").and_then(Value::as_array) {
+            Some(arr) => arr
+                .iter()
+                .map(|c| c.as_str().map(str::to_string))
+                .collect::<Option<_>>()
+                .ok_or(Error::Archive("invalid $classes")),
+            None => match class.get("$classname").and_then(Value::as_str) {
+                Some(name) => Ok(vec![name.to_string()]),
+                None => Err(Error::Archive("invalid $class")),
+            },
+        }
+    }
+
+    fn record(
+        &mut self,
+        classes: Vec<String>,
+        dict: &'a [(String, Value)],
+        depth: usize,
+    ) -> Result<Obj> {
+        let mut fields = Vec::with_capacity(dict.len());
+        for (k, v) in dict {
+            if k != "$class" {
+                fields.push((k.clone(), self.field(v, depth)?));
+            }
+        }
+        Ok(Obj::Record(Record { classes, fields }))
+    }
+
+    /// Maps Foundation classes to native variants, `None` if class or layout is unknown.
+    fn known(&mut self, classes: &[String], val: &'a Value, depth: usize) -> Result<Option<Obj>> {
+        let items = |key| val.get(key).and_then(Value::as_array);
+        let res = match classes.first().map(String::as_str).unwrap_or("") {
+            "NSArray" | "NSMutableArray" => match items("NS.objects") {
+                Some(objs) => Obj::Array(self.items(objs, depth)?),
+                None => return Ok(None),
+            },
+            "NSSet" | "NSMutableSet" => match items("NS.objects") {
+                Some(objs) => Obj::Set(self.items(objs, depth)?),
+                None => return Ok(None),
+            },
+            "NSDictionary" | "NSMutableDictionary" => match (items("NS.keys"), items("NS.objects"))
+            {
+                (Some(keys), Some(objs)) if keys.len() == objs.len() => {
+                    let keys = self.items(keys, depth)?;
+                    Obj::Dict(keys.into_iter().zip(self.items(objs, depth)?).collect())
+                }
+                (Some(_), Some(_)) => return Err(Error::Archive("keys and objects mismatch")),
+                _ => return Ok(None),
+            },
+            "NSDate" => match val.get("NS.time").and_then(Value::as_f64) {
+                Some(t) => Obj::Date(Date(t)),
+                None => return Ok(None),
+            },
+            "NSString" | "NSMutableString" => match val.get("NS.string") {
+                Some(s) => match self.field(s, depth)? {
+                    Obj::String(s) => Obj::String(s),
+                    _ => return Err(Error::Archive("invalid NS.string")),
+                },
+                None => return Ok(None),
+            },
+            "NSData" | "NSMutableData" => match val.get("NS.data") {
+                Some(d) => match self.field(d, depth)? {
+                    Obj::Data(d) => Obj::Data(d),
+                    _ => return Err(Error::Archive("invalid NS.data")),
+                },
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(res))
+    }
+}
+
+/// Converts non container plist value.
+fn scalar(val: &Value) -> Obj {
+    match val {
+        Value::String(s) => Obj::String(s.clone()),
+        Value::Data(d) => Obj::Data(d.clone()),
+        Value::Date(d) => Obj::Date(*d),
+        Value::Int(i) => Obj::Int(*i),
+        Value::Real(r) => Obj::Real(*r),
+        Value::Bool(b) => Obj::Bool(*b),
+        Value::Uid(_) | Value::Array(_) | Value::Dict(_) => unreachable!(),
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Key<'a> {
+    Str(&'a str),
+    Data(&'a [u8]),
+    Int(i128),
+    Real(u64),
+    Bool(bool),
+}
+
+struct Encoder<'a> {
+    objects: Vec<Value>,
+    uniq: HashMap<Key<'a>, Uid>,
+    classes: HashMap<Vec<&'a str>, Uid>,
+}
+
+impl<'a> Encoder<'a> {
+    fn push(&mut self, val: Value) -> Uid {
+        self.objects.push(val);
+        Uid(self.objects.len() as u64 - 1)
+    }
+
+    fn class(&mut self, classes: Vec<&'a str>) -> Result<Uid> {
+        if classes.is_empty() {
+            return Err(Error::Archive("record without class"));
+        }
+        if let Some(uid) = self.classes.get(&classes) {
+            return Ok(*uid);
+        }
+        let uid = self.push(Value::Dict(vec![
+            ("$classname".into(), classes[0].into()),
+            (
+                "$classes".into(),
+                Value::Array(classes.iter().map(|c| Value::from(*c)).collect()),
+            ),
+        ]));
+        self.classes.insert(classes, uid);
+        Ok(uid)
+    }
+
+    fn scalar(&mut self, key: Key<'a>, val: Value) -> Uid {
+        if let Some(uid) = self.uniq.get(&key) {
+            return *uid;
+        }
+        let uid = self.push(val);
+        self.uniq.insert(key, uid);
+        uid
+    }
+
+    fn uids(&mut self, objs: impl Iterator<Item = &'a Obj>, depth: usize) -> Result<Value> {
+        let mut res = vec![];
+        for obj in objs {
+            res.push(Value::Uid(self.obj(obj, depth)?));
+        }
+        Ok(Value::Array(res))
+    }
+
+    /// Encodes object with `$class`, reserving its slot before children
+    /// like `NSKeyedArchiver` does.
+    fn instance(
+        &mut self,
+        classes: Vec<&'a str>,
+        fields: impl FnOnce(&mut Self) -> Result<Vec<(String, Value)>>,
+    ) -> Result<Uid> {
+        let uid = self.push(Value::Bool(false));
+        let mut fields = fields(self)?;
+        fields.push(("$class".into(), Value::Uid(self.class(classes)?)));
+        self.objects[uid.0 as usize] = Value::Dict(fields);
+        Ok(uid)
+    }
+
+    fn obj(&mut self, obj: &'a Obj, depth: usize) -> Result<Uid> {
+        if depth > MAX_OBJ_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        let depth = depth + 1;
+        let uid = match obj {
+            Obj::Null => Uid(0),
+            Obj::String(s) => self.scalar(Key::Str(s), Value::String(s.clone())),
+            Obj::Data(d) => self.scalar(Key::Data(d), Value::Data(d.clone())),
+            Obj::Int(i) => {
+                if *i < i64::MIN as i128 || *i > u64::MAX as i128 {
+                    return Err(Error::IntOutOfRange);
+                }
+                self.scalar(Key::Int(*i), Value::Int(*i))
+            }
+            Obj::Real(r) => self.scalar(Key::Real(r.to_bits()), Value::Real(*r)),
+            Obj::Bool(b) => self.scalar(Key::Bool(*b), Value::Bool(*b)),
+            Obj::Date(d) => self.instance(vec!["NSDate", "NSObject"], |_| {
+                Ok(vec![("NS.time".into(), Value::Real(d.0))])
+            })?,
+            Obj::Array(arr) => self.instance(vec!["NSArray", "NSObject"], |e| {
+                Ok(vec![("NS.objects".into(), e.uids(arr.iter(), depth)?)])
+            })?,
+            Obj::Set(set) => self.instance(vec!["NSSet", "NSObject"], |e| {
+                Ok(vec![("NS.objects".into(), e.uids(set.iter(), depth)?)])
+            })?,
+            Obj::Dict(dict) => self.instance(vec!["NSDictionary", "NSObject"], |e| {
+                let keys = e.uids(dict.iter().map(|(k, _)| k), depth)?;
+                let objs = e.uids(dict.iter().map(|(_, v)| v), depth)?;
+                Ok(vec![("NS.keys".into(), keys), ("NS.objects".into(), objs)])
+            })?,
+            Obj::Record(rec) => {
+                let classes = rec.classes.iter().map(String::as_str).collect();
+                self.instance(classes, |e| {
+                    let mut fields = Vec::with_capacity(rec.fields.len() + 1);
+                    for (k, v) in &rec.fields {
+                        // numbers are stored inline like encodeInteger:forKey: does
+                        let v = match v {
+                            Obj::Int(i) => Value::Int(*i),
+                            Obj::Real(r) => Value::Real(*r),
+                            Obj::Bool(b) => Value::Bool(*b),
+                            v => Value::Uid(e.obj(v, depth)?),
+                        };
+                        fields.push((k.clone(), v));
+                    }
+                    Ok(fields)
+                })?
+            }
+        };
+        Ok(uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plist::{self, ArchiveObj as Obj, ArchiveRecord as Record, Error, KeyedArchive};
+
+    /// `NSKeyedArchiver` output for `@{@"list": @[@"a", @1, @YES], @"date": [NSDate dateWithTimeIntervalSinceReferenceDate:5]}`
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>$archiver</key>
+	<string>NSKeyedArchiver</string>
+	<key>$objects</key>
+	<array>
+		<string>$null</string>
+		<dict>
+			<key>$class</key>
+			<dict><key>CF$UID</key><integer>10</integer></dict>
+			<key>NS.keys</key>
+			<array>
+				<dict><key>CF$UID</key><integer>2</integer></dict>
+				<dict><key>CF$UID</key><integer>3</integer></dict>
+			</array>
+			<key>NS.objects</key>
+			<array>
+				<dict><key>CF$UID</key><integer>4</integer></dict>
+				<dict><key>CF$UID</key><integer>8</integer></dict>
+			</array>
+		</dict>
+		<string>list</string>
+		<string>date</string>
+		<dict>
+			<key>$class</key>
+			<dict><key>CF$UID</key><integer>7</integer></dict>
+			<key>NS.objects</key>
+			<array>
+				<dict><key>CF$UID</key><integer>5</integer></dict>
+				<dict><key>CF$UID</key><integer>6</integer></dict>
+				<dict><key>CF$UID</key><integer>11</integer></dict>
+			</array>
+		</dict>
+		<string>a</string>
+		<integer>1</integer>
+		<dict>
+			<key>$classes</key>
+			<array><string>NSMutableArray</string><string>NSArray</string><string>NSObject</string></array>
+			<key>$classname</key>
+			<string>NSMutableArray</string>
+		</dict>
+		<dict>
+			<key>$class</key>
+			<dict><key>CF$UID</key><integer>9</integer></dict>
+			<key>NS.time</key>
+			<real>5</real>
+		</dict>
+		<dict>
+			<key>$classes</key>
+			<array><string>NSDate</string><string>NSObject</string></array>
+			<key>$classname</key>
+			<string>NSDate</string>
+		</dict>
+		<dict>
+			<key>$classes</key>
+			<array><string>NSDictionary</string><string>NSObject</string></array>
+			<key>$classname</key>
+			<string>NSDictionary</string>
+		</dict>
+		<true/>
+	</array>
+	<key>$top</key>
+	<dict>
+		<key>root</key>
+		<dict><key>CF$UID</key><integer>1</integer></dict>
+	</dict>
+	<key>$version</key>
+	<integer>100000</integer>
+</dict>
+</plist>
+"#;
+
+    fn sample() -> Obj {
+        Obj::Dict(vec![
+            (
+                "list".into(),
+                vec![Obj::from("a"), 1.into(), true.into()].into(),
+            ),
+            ("date".into(), plist::Date(5.0).into()),
+        ])
+    }
+
+    #[test]
+    fn decode() {
+        let archive = KeyedArchive::from_bytes(XML.as_bytes()).unwrap();
+        assert_eq!(archive.root(), Some(&sample()));
+        assert_eq!(
+            archive
+                .root()
+                .unwrap()
+                .get("list")
+                .unwrap()
+                .as_array()
+                .unwrap()[0],
+            "a".into()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rec = Record::new(&["Point", "NSObject"]);
+        rec.insert("x", 1);
+        rec.insert("y", 2.5);
+        rec.insert("name", "p");
+        rec.insert("tags", Obj::Set(vec!["a".into(), "b".into()]));
+        rec.insert("parent", Obj::Null);
+        let root = Obj::Array(vec![sample(), Obj::Record(rec.clone()), Obj::Record(rec)]);
+        let archive = KeyedArchive::with_root(root);
+
+        let bytes = archive.to_bytes().unwrap();
+        assert_eq!(KeyedArchive::from_bytes(&bytes).unwrap(), archive);
+
+        let val = archive.to_value().unwrap();
+        let objects = val["$objects"].as_array().unwrap();
+        assert_eq!(objects[0].as_str(), Some("$null"));
+        // strings and classes are stored once
+        let count = |s: &str| objects.iter().filter(|o| o.as_str() == Some(s)).count();
+        assert_eq!(count("a"), 1);
+        assert_eq!(count("p"), 1);
+        let points = objects
+            .iter()
+            .filter(|o| o.get("$classname").and_then(plist::Value::as_str) == Some("Point"))
+            .count();
+        assert_eq!(points, 1);
+        // numbers in records are inline
+        let point = objects.iter().find(|o| o.get("x").is_some()).unwrap();
+        assert_eq!(point["x"], 1.into());
+        assert!(point["name"].as_uid().is_some());
+    }
+
+    #[test]
+    fn malformed() {
+        let archive = |objects: Vec<plist::Value>| {
+            plist::Value::Dict(vec![
+                ("$archiver".into(), "NSKeyedArchiver".into()),
+                (
+                    "$top".into(),
+                    plist::Value::Dict(vec![("root".into(), plist::Uid(1).into())]),
+                ),
+                ("$objects".into(), objects.into()),
+            ])
+        };
+        let class = |name: &str| {
+            plist::Value::Dict(vec![
+                ("$classname".into(), name.into()),
+                ("$classes".into(), vec![plist::Value::from(name)].into()),
+            ])
+        };
+        let array = |class: u64, refs: &[u64]| {
+            plist::Value::Dict(vec![
+                ("$class".into(), plist::Uid(class).into()),
+                (
+                    "NS.objects".into(),
+                    refs.iter()
+                        .map(|r| plist::Value::Uid(plist::Uid(*r)))
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+            ])
+        };
+
+        let ok = archive(vec!["$null".into(), array(2, &[0]), class("NSArray")]);
+        assert_eq!(
+            KeyedArchive::from_value(&ok).unwrap().root(),
+            Some(&Obj::Array(vec![Obj::Null]))
+        );
+
+        let cycle = archive(vec!["$null".into(), array(2, &[1]), class("NSArray")]);
+        assert_eq!(KeyedArchive::from_value(&cycle), Err(Error::Cycle));
+
+        // uids referencing uids, directly or through a chain
+        let self_ref = archive(vec!["$null".into(), plist::Uid(1).into()]);
+        assert_eq!(KeyedArchive::from_value(&self_ref), Err(Error::Cycle));
+        let chain = archive(vec![
+            "$null".into(),
+            plist::Uid(2).into(),
+            plist::Uid(1).into(),
+        ]);
+        assert_eq!(KeyedArchive::from_value(&chain), Err(Error::Cycle));
+        let alias = archive(vec!["$null".into(), plist::Uid(2).into(), "leaf".into()]);
+        assert_eq!(
+            KeyedArchive::from_value(&alias).unwrap().root(),
+            Some(&Obj::String("leaf".into()))
+        );
+
+        let dangling = archive(vec!["$null".into(), array(2, &[9]), class("NSArray")]);
+        assert_eq!(
+            KeyedArchive::from_value(&dangling),
+            Err(Error::InvalidObjRef(9))
+        );
+
+        let no_class = archive(vec!["$null".into(), plist::Value::Dict(vec![])]);
+        assert!(matches!(
+            KeyedArchive::from_value(&no_class),
+            Err(Error::Archive(_))
+        ));
+
+        assert!(matches!(
+            KeyedArchive::from_value(&plist::Value::Dict(vec![])),
+            Err(Error::Archive(_))
+        ));
+
+        let mut objects = vec!["$null".into(), class("NSArray")];
+        for i in 0..plist::MAX_OBJ_DEPTH as u64 + 10 {
+            objects.push(array(1, &[i + 3]));
+        }
+        objects.push("leaf".into());
+        let mut deep = archive(objects);
+        if let plist::Value::Dict(d) = &mut deep {
+            d[1].1 = plist::Value::Dict(vec![("root".into(), plist::Uid(2).into())]);
+        }
+        assert_eq!(KeyedArchive::from_value(&deep), Err(Error::TooDeep));
+
+        let mut obj = Obj::Null;
+        for _ in 0..plist::MAX_OBJ_DEPTH {
+            obj = Obj::Array(vec![obj]);
+        }
+        let ok = KeyedArchive::with_root(obj.clone()).to_bytes().unwrap();
+        assert_eq!(KeyedArchive::from_bytes(&ok).unwrap().root(), Some(&obj));
+        let deep = KeyedArchive::with_root(Obj::Array(vec![obj]));
+        assert_eq!(deep.to_value(), Err(Error::TooDeep));
+
+        // every array references the next one twice
+        let mut objects = vec!["$null".into(), class("NSArray")];
+        for i in 0..40 {
+            objects.push(array(1, &[i + 3, i + 3]));
+        }
+        objects.push("leaf".into());
+        let mut wide = archive(objects);
+        if let plist::Value::Dict(d) = &mut wide {
+            d[1].1 = plist::Value::Dict(vec![("root".into(), plist::Uid(2).into())]);
+        }
+        assert_eq!(KeyedArchive::from_value(&wide), Err(Error::TooManyObjects));
+    }
+}
@@ -0,0 +1,410 @@
+use std::time::{Duration, SystemTime};
+
+use crate::plist::Error;
+
+/// Owned property list value.
+///
+/// Dictionaries keep key order so values round-trip losslessly.
+#[doc(alias = "PlistValue")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Array(Vec<Value>),
+    Dict(Vec<(String, Value)>),
+    String(String),
+    Data(Vec<u8>),
+    Date(Date),
+
+    /// Integer in `i64::MIN..=u64::MAX` range
+    Int(i128),
+    Real(f64),
+    Bool(bool),
+
+    /// Object reference used by keyed archives
+    Uid(Uid),
+}
+
+/// Seconds since 2001-01-01 00:00:00 UTC.
+#[doc(alias = "CFAbsoluteTime")]
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Default)]
+#[repr(transparent)]
+pub struct Date(pub f64);
+
+#[doc(alias = "CFKeyedArchiverUID")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Uid(pub u64);
+
+impl Date {
+    /// Seconds between unix epoch and 2001-01-01
+    #[doc(alias = "kCFAbsoluteTimeIntervalSince1970")]
+    pub const INTERVAL_SINCE_1970: f64 = 978_307_200.0;
+
+    #[inline]
+    pub fn with_unix_secs(secs: f64) -> Self {
+        Self(secs - Self::INTERVAL_SINCE_1970)
+    }
+
+    #[inline]
+    pub fn unix_secs(&self) -> f64 {
+        self.0 + Self::INTERVAL_SINCE_1970
+    }
+
+    pub fn now() -> Self {
+        SystemTime::now().into()
+    }
+
+    /// Formats as `YYYY-MM-DDTHH:MM:SSZ` truncating fractional seconds
+    /// like CoreFoundation does in XML plists.
+    pub fn to_iso8601(&self) -> String {
+        let secs = self.unix_secs().floor() as i64;
+        let (days, secs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+        let (y, m, d) = civil_from_days(days);
+        format!(
+            "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+
+    /// Parses `YYYY-MM-DDTHH:MM:SSZ`.
+    pub fn from_iso8601(str: &str) -> Option<Self> {
+        let b = str.as_bytes();
+        if b.len() != 20 || b[4] != b'-' || b[7] != b'-' || b[10] != b'T' || b[19] != b'Z' {
+            return None;
+        }
+        if b[13] != b':' || b[16] != b':' {
+            return None;
+        }
+        let num = |range: std::ops::Range<usize>| -> Option<i64> {
+            let s = str.get(range)?;
+            if !s.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            s.parse().ok()
+        };
+        let (y, m, d) = (num(0..4)?, num(5..7)?, num(8..10)?);
+        let (h, min, s) = (num(11..13)?, num(14..16)?, num(17..19)?);
+        if !(1..=12).contains(&m) || !(1..=31).contains(&d) || h > 23 || min > 59 || s > 60 {
+            return None;
+        }
+        let days = days_from_civil(y, m, d);
+        let secs = days * 86_400 + h * 3600 + min * 60 + s;
+        Some(Self::with_unix_secs(secs as f64))
+    }
+}
+
+impl From<SystemTime> for Date {
+    fn from(value: SystemTime) -> Self {
+        let secs = match value.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => d.as_secs_f64(),
+            Err(e) => -e.duration().as_secs_f64(),
+        };
+        Self::with_unix_secs(secs)
+    }
+}
+
+/// Fails with [`Error::DateOutOfRange`] for NaN, infinite
+/// or unrepresentable dates.
+impl TryFrom<Date> for SystemTime {
+    type Error = Error;
+
+    fn try_from(value: Date) -> Result<Self, Error> {
+        let secs = value.unix_secs();
+        let dur = Duration::try_from_secs_f64(secs.abs()).map_err(|_| Error::DateOutOfRange)?;
+        let time = if secs >= 0.0 {
+            SystemTime::UNIX_EPOCH.checked_add(dur)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_sub(dur)
+        };
+        time.ok_or(Error::DateOutOfRange)
+    }
+}
+
+// http://howardhinnant.github.io/date_algorithms.html
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = if m > 2 { m - 3 } else { m + 9 };
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+impl Value {
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(i) => i64::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Int(i) => u64::try_from(*i).ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns reals and integers as f64
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Real(r) => Some(*r),
+            Self::Int(i) => Some(*i as f64),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_data(&self) -> Option<&[u8]> {
+        match self {
+            Self::Data(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_date(&self) -> Option<Date> {
+        match self {
+            Self::Date(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_uid(&self) -> Option<Uid> {
+        match self {
+            Self::Uid(u) => Some(*u),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Self::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_dict(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Self::Dict(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Looks up dictionary value by key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.as_dict()?
+            .iter()
+            .find_map(|(k, v)| if k == key { Some(v) } else { None })
+    }
+
+    /// Inserts or replaces dictionary value. Returns previous value.
+    ///
+    /// Panics if value is not a dictionary.
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<Value>) -> Option<Value> {
+        let Self::Dict(dict) = self else {
+            panic!("not a dictionary");
+        };
+        let key = key.into();
+        let value = value.into();
+        match dict.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => Some(std::mem::replace(v, value)),
+            None => {
+                dict.push((key, value));
+                None
+            }
+        }
+    }
+}
+
+impl std::ops::Index<&str> for Value {
+    type Output = Value;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key).expect("no such key")
+    }
+}
+
+impl std::ops::Index<usize> for Value {
+    type Output = Value;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.as_array().expect("not an array")[index]
+    }
+}
+
+impl From<bool> for Value {
+    #[inline]
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Value {
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Self::Int(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl From<f64> for Value {
+    #[inline]
+    fn from(value: f64) -> Self {
+        Self::Real(value)
+    }
+}
+
+impl From<f32> for Value {
+    #[inline]
+    fn from(value: f32) -> Self {
+        Self::Real(value as f64)
+    }
+}
+
+impl From<&str> for Value {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    #[inline]
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    #[inline]
+    fn from(value: Vec<u8>) -> Self {
+        Self::Data(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    #[inline]
+    fn from(value: Vec<Value>) -> Self {
+        Self::Array(value)
+    }
+}
+
+impl From<Vec<(String, Value)>> for Value {
+    #[inline]
+    fn from(value: Vec<(String, Value)>) -> Self {
+        Self::Dict(value)
+    }
+}
+
+impl From<Date> for Value {
+    #[inline]
+    fn from(value: Date) -> Self {
+        Self::Date(value)
+    }
+}
+
+impl From<Uid> for Value {
+    #[inline]
+    fn from(value: Uid) -> Self {
+        Self::Uid(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plist;
+
+    #[test]
+    fn dates() {
+        let date = plist::Date(0.0);
+        assert_eq!(date.to_iso8601(), "2001-01-01T00:00:00Z");
+        assert_eq!(
+            plist::Date::from_iso8601("2001-01-01T00:00:00Z"),
+            Some(date)
+        );
+
+        let date = plist::Date::from_iso8601("1969-07-20T20:17:40Z").unwrap();
+        assert_eq!(date.unix_secs(), -14_182_940.0);
+        assert_eq!(date.to_iso8601(), "1969-07-20T20:17:40Z");
+
+        let date = plist::Date::from_iso8601("2024-02-29T23:59:59Z").unwrap();
+        assert_eq!(date.to_iso8601(), "2024-02-29T23:59:59Z");
+
+        assert_eq!(plist::Date(0.75).to_iso8601(), "2001-01-01T00:00:00Z");
+        assert_eq!(plist::Date(-0.25).to_iso8601(), "2000-12-31T23:59:59Z");
+
+        assert!(plist::Date::from_iso8601("2001-01-01T00:00:00").is_none());
+        assert!(plist::Date::from_iso8601("2001-13-01T00:00:00Z").is_none());
+        assert!(plist::Date::from_iso8601("2001-01-01T0a:00:00Z").is_none());
+
+        let now = plist::Date::now();
+        let sys = std::time::SystemTime::try_from(now).unwrap();
+        assert!((plist::Date::from(sys).0 - now.0).abs() < 1e-6);
+
+        for date in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, f64::MAX, -1e300] {
+            assert_eq!(
+                std::time::SystemTime::try_from(plist::Date(date)),
+                Err(plist::Error::DateOutOfRange)
+            );
+        }
+    }
+
+    #[test]
+    fn accessors() {
+        let mut dict = plist::Value::Dict(vec![]);
+        assert!(dict.insert("a", 1).is_none());
+        assert_eq!(dict.insert("a", "b"), Some(plist::Value::Int(1)));
+        dict.insert("list", vec![plist::Value::from(true)]);
+        assert_eq!(dict["a"].as_str(), Some("b"));
+        assert_eq!(dict["list"][0].as_bool(), Some(true));
+        assert!(dict.get("missing").is_none());
+        assert_eq!(plist::Value::from(u64::MAX).as_i64(), None);
+        assert_eq!(plist::Value::from(u64::MAX).as_u64(), Some(u64::MAX));
+        assert_eq!(plist::Value::from(-1).as_f64(), Some(-1.0));
+    }
+}
@@ -0,0 +1,636 @@
+use std::fmt::Write;
+
+use crate::plist::{Date, Error, MAX_DEPTH, Result, Uid, Value};
+
+const HEADER: &str = concat!(
+    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+    "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+    "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+    "<plist version=\"1.0\">\n"
+);
+
+/// Key of single entry dictionary representing [`Uid`] in XML
+const UID_KEY: &str = "CF$UID";
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl Value {
+    /// Reads XML property list.
+    pub fn from_xml(str: &str) -> Result {
+        let mut parser = Parser { str, pos: 0 };
+        parser.prolog()?;
+        let val = match parser.tag()? {
+            Tag::Open("plist") => {
+                let val = parser.value(0)?;
+                parser.expect_close("plist")?;
+                val
+            }
+            // CoreFoundation accepts plists without root element
+            tag => parser.value_for(tag, 0)?,
+        };
+        parser.skip_misc()?;
+        if parser.pos != str.len() {
+            return Err(parser.err("unexpected content after root element"));
+        }
+        Ok(val)
+    }
+
+    /// Writes XML property list in the same layout as CoreFoundation.
+    pub fn to_xml(&self) -> Result<String> {
+        let mut out = String::from(HEADER);
+        write_value(&mut out, self, 0)?;
+        out.push_str("</plist>\n");
+        Ok(out)
+    }
+}
+
+fn indent(out: &mut String, depth: usize) {
+    for _ in 0..depth {
+        out.push('\t');
+    }
+}
+
+fn escape(out: &mut String, str: &str) {
+    for c in str.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn base64(out: &mut String, data: &[u8]) {
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - i * 6)) as usize & 0x3f] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+}
+
+fn write_value(out: &mut String, val: &Value, depth: usize) -> Result<()> {
+    if depth > MAX_DEPTH {
+        return Err(Error::TooDeep);
+    }
+    indent(out, depth);
+    match val {
+        Value::Array(arr) if arr.is_empty() => out.push_str("<array/>\n"),
+        Value::Array(arr) => {
+            out.push_str("<array>\n");
+            for v in arr {
+                write_value(out, v, depth + 1)?;
+            }
+            indent(out, depth);
+            out.push_str("</array>\n");
+        }
+        Value::Dict(dict) if dict.is_empty() => out.push_str("<dict/>\n"),
+        Value::Dict(dict) => {
+            out.push_str("<dict>\n");
+            for (k, v) in dict {
+                indent(out, depth + 1);
+                out.push_str("<key>");
+                escape(out, k);
+                out.push_str("</key>\n");
+                write_value(out, v, depth + 1)?;
+            }
+            indent(out, depth);
+            out.push_str("</dict>\n");
+        }
+        Value::String(s) => {
+            out.push_str("<string>");
+            escape(out, s);
+            out.push_str("</string>\n");
+        }
+        Value::Data(d) => {
+            out.push_str("<data>");
+            base64(out, d);
+            out.push_str("</data>\n");
+        }
+        Value::Date(d) => {
+            out.push_str("<date>");
+            out.push_str(&d.to_iso8601());
+            out.push_str("</date>\n");
+        }
+        Value::Int(i) => {
+            if *i < i64::MIN as i128 || *i > u64::MAX as i128 {
+                return Err(Error::IntOutOfRange);
+            }
+            let _ = writeln!(out, "<integer>{i}</integer>");
+        }
+        Value::Real(r) => {
+            out.push_str("<real>");
+            if r.is_nan() {
+                out.push_str("nan");
+            } else if r.is_infinite() {
+                out.push_str(if *r > 0.0 { "+infinity" } else { "-infinity" });
+            } else {
+                let _ = write!(out, "{r}");
+            }
+            out.push_str("</real>\n");
+        }
+        Value::Bool(true) => out.push_str("<true/>\n"),
+        Value::Bool(false) => out.push_str("<false/>\n"),
+        Value::Uid(u) => {
+            out.push_str("<dict>\n");
+            indent(out, depth + 1);
+            let _ = writeln!(out, "<key>{UID_KEY}</key>");
+            indent(out, depth + 1);
+            let _ = writeln!(out, "<integer>{}</integer>", u.0);
+            indent(out, depth);
+            out.push_str("</dict>\n");
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Tag<'a> {
+    Open(&'a str),
+    Close(&'a str),
+    Empty(&'a str),
+}
+
+struct Parser<'a> {
+    str: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    #[inline]
+    fn err(&self, msg: &'static str) -> Error {
+        Error::Xml { pos: self.pos, msg }
+    }
+
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.str[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips until `end` inclusive.
+    fn skip_past(&mut self, end: &str, msg: &'static str) -> Result<()> {
+        match self.rest().find(end) {
+            Some(i) => {
+                self.pos += i + end.len();
+                Ok(())
+            }
+            None => Err(self.err(msg)),
+        }
+    }
+
+    /// Skips whitespace, comments and processing instructions.
+    fn skip_misc(&mut self) -> Result<()> {
+        loop {
+            self.skip_ws();
+            let rest = self.rest();
+            if rest.starts_with("<!--") {
+                self.skip_past("-->", "unterminated comment")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>", "unterminated processing instruction")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn prolog(&mut self) -> Result<()> {
+        if self.rest().starts_with('\u{feff}') {
+            self.pos += '\u{feff}'.len_utf8();
+        }
+        self.skip_misc()?;
+        if self.rest().starts_with("<!DOCTYPE") {
+            self.skip_past(">", "unterminated doctype")?;
+        }
+        self.skip_misc()
+    }
+
+    fn tag(&mut self) -> Result<Tag<'a>> {
+        self.skip_misc()?;
+        let rest = self.rest();
+        if !rest.starts_with('<') {
+            return Err(self.err("expected tag"));
+        }
+        let Some(end) = rest.find('>') else {
+            return Err(self.err("unterminated tag"));
+        };
+        let inner = &rest[1..end];
+        self.pos += end + 1;
+        if let Some(name) = inner.strip_prefix('/') {
+            return Ok(Tag::Close(name.trim_end()));
+        }
+        let (inner, empty) = match inner.strip_suffix('/') {
+            Some(inner) => (inner, true),
+            None => (inner, false),
+        };
+        // attributes are ignored
+        let name = inner.split_ascii_whitespace().next().unwrap_or("");
+        if name.is_empty() {
+            return Err(self.err("empty tag name"));
+        }
+        Ok(if empty {
+            Tag::Empty(name)
+        } else {
+            Tag::Open(name)
+        })
+    }
+
+    fn expect_close(&mut self, name: &str) -> Result<()> {
+        match self.tag()? {
+            Tag::Close(n) if n == name => Ok(()),
+            _ => Err(self.err("mismatched closing tag")),
+        }
+    }
+
+    /// Reads character data up to closing `name` tag resolving entities and CDATA.
+    fn text(&mut self, name: &str) -> Result<String> {
+        let mut out = String::new();
+        loop {
+            let rest = self.rest();
+            let Some(i) = rest.find(['<', '&']) else {
+                return Err(self.err("unexpected end of text"));
+            };
+            out.push_str(&rest[..i]);
+            self.pos += i;
+            let rest = self.rest();
+            if rest.starts_with("<![CDATA[") {
+                self.pos += 9;
+                let Some(end) = self.rest().find("]]>") else {
+                    return Err(self.err("unterminated cdata"));
+                };
+                out.push_str(&self.rest()[..end]);
+                self.pos += end + 3;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "unterminated comment")?;
+            } else if rest.starts_with('&') {
+                let Some(end) = rest.find(';') else {
+                    return Err(self.err("unterminated entity"));
+                };
+                let c = match &rest[1..end] {
+                    "lt" => '<',
+                    "gt" => '>',
+                    "amp" => '&',
+                    "quot" => '"',
+                    "apos" => '\'',
+                    e => {
+                        let code = if let Some(hex) = e.strip_prefix("#x") {
+                            u32::from_str_radix(hex, 16).ok()
+                        } else if let Some(dec) = e.strip_prefix('#') {
+                            dec.parse().ok()
+                        } else {
+                            None
+                        };
+                        match code.and_then(char::from_u32) {
+                            Some(c) => c,
+                            None => return Err(self.err("invalid entity")),
+                        }
+                    }
+                };
+                out.push(c);
+                self.pos += end + 1;
+            } else {
+                self.expect_close(name)?;
+                return Ok(out);
+            }
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result {
+        let tag = self.tag()?;
+        self.value_for(tag, depth)
+    }
+
+    fn value_for(&mut self, tag: Tag<'a>, depth: usize) -> Result {
+        if depth > MAX_DEPTH {
+            return Err(Error::TooDeep);
+        }
+        let val = match tag {
+            Tag::Open("array") => {
+                let mut arr = vec![];
+                loop {
+                    match self.tag()? {
+                        Tag::Close("array") => break,
+                        tag => arr.push(self.value_for(tag, depth + 1)?),
+                    }
+                }
+                Value::Array(arr)
+            }
+            Tag::Open("dict") => {
+                let mut dict = vec![];
+                loop {
+                    match self.tag()? {
+                        Tag::Close("dict") => break,
+                        Tag::Open("key") => {
+                            let key = self.text("key")?;
+                            let val = self.value(depth + 1)?;
+                            dict.push((key, val));
+                        }
+                        Tag::Empty("key") => {
+                            let val = self.value(depth + 1)?;
+                            dict.push((String::new(), val));
+                        }
+                        _ => return Err(self.err("expected key")),
+                    }
+                }
+                match dict.as_slice() {
+                    [(key, Value::Int(uid @ 0..=0xffff_ffff_ffff_ffff))] if key == UID_KEY => {
+                        Value::Uid(Uid(*uid as u64))
+                    }
+                    _ => Value::Dict(dict),
+                }
+            }
+            tag => self.scalar(tag)?,
+        };
+        Ok(val)
+    }
+
+    /// Kept out of `value_for` to keep recursion frames small
+    #[inline(never)]
+    fn scalar(&mut self, tag: Tag<'a>) -> Result {
+        let val = match tag {
+            Tag::Empty("array") => Value::Array(vec![]),
+            Tag::Empty("dict") => Value::Dict(vec![]),
+            Tag::Empty("string") => Value::String(String::new()),
+            Tag::Empty("data") => Value::Data(vec![]),
+            Tag::Empty("true") => Value::Bool(true),
+            Tag::Empty("false") => Value::Bool(false),
+            Tag::Open("true") => {
+                self.expect_close("true")?;
+                Value::Bool(true)
+            }
+            Tag::Open("false") => {
+                self.expect_close("false")?;
+                Value::Bool(false)
+            }
+            Tag::Open("string") => Value::String(self.text("string")?),
+            Tag::Open("data") => {
+                let pos = self.pos;
+                let text = self.text("data")?;
+                Value::Data(decode_base64(&text).ok_or(Error::Xml {
+                    pos,
+                    msg: "invalid base64",
+                })?)
+            }
+            Tag::Open("date") => {
+                let pos = self.pos;
+                let text = self.text("date")?;
+                Value::Date(Date::from_iso8601(text.trim()).ok_or(Error::Xml {
+                    pos,
+                    msg: "invalid date",
+                })?)
+            }
+            Tag::Open("integer") => {
+                let pos = self.pos;
+                let text = self.text("integer")?;
+                Value::Int(parse_int(text.trim()).ok_or(Error::Xml {
+                    pos,
+                    msg: "invalid integer",
+                })?)
+            }
+            Tag::Open("real") => {
+                let pos = self.pos;
+                let text = self.text("real")?;
+                Value::Real(parse_real(text.trim()).ok_or(Error::Xml {
+                    pos,
+                    msg: "invalid real",
+                })?)
+            }
+            _ => return Err(self.err("unexpected tag")),
+        };
+        Ok(val)
+    }
+}
+
+fn parse_int(str: &str) -> Option<i128> {
+    let (neg, digits) = match str.as_bytes().first()? {
+        b'-' => (true, &str[1..]),
+        b'+' => (false, &str[1..]),
+        _ => (false, str),
+    };
+    let (radix, digits) = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => (16, hex),
+        None => (10, digits),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    let val = u128::from_str_radix(digits, radix).ok()?;
+    let val = if neg {
+        0i128.checked_sub_unsigned(val)?
+    } else {
+        i128::try_from(val).ok()?
+    };
+    if val < i64::MIN as i128 || val > u64::MAX as i128 {
+        return None;
+    }
+    Some(val)
+}
+
+fn parse_real(str: &str) -> Option<f64> {
+    match str.to_ascii_lowercase().as_str() {
+        "nan" => Some(f64::NAN),
+        "inf" | "infinity" | "+inf" | "+infinity" => Some(f64::INFINITY),
+        "-inf" | "-infinity" => Some(f64::NEG_INFINITY),
+        s => s.parse().ok(),
+    }
+}
+
+fn decode_base64(str: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(str.len() / 4 * 3);
+    let mut acc = 0u32;
+    let mut bits = 0;
+    let mut padding = false;
+    for b in str.bytes() {
+        let v = match b {
+            b'A'..=b'Z' => b - b'A',
+            b'a'..=b'z' => b - b'a' + 26,
+            b'0'..=b'9' => b - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => {
+                padding = true;
+                continue;
+            }
+            b if b.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        if padding {
+            return None;
+        }
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plist::{Date, Error, MAX_DEPTH, Uid, Value};
+
+    const SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>CFBundleName</key>
+	<string>cidre &amp; co &lt;3</string>
+	<key>data</key>
+	<data>AAEC/w==</data>
+	<key>date</key>
+	<date>2001-01-01T00:02:03Z</date>
+	<key>empty</key>
+	<array/>
+	<key>empty_dict</key>
+	<dict/>
+	<key>int</key>
+	<integer>-42</integer>
+	<key>list</key>
+	<array>
+		<true/>
+		<false/>
+		<real>0.5</real>
+	</array>
+	<key>uid</key>
+	<dict>
+		<key>CF$UID</key>
+		<integer>7</integer>
+	</dict>
+</dict>
+</plist>
+"#;
+
+    fn sample() -> Value {
+        Value::Dict(vec![
+            ("CFBundleName".into(), "cidre & co <3".into()),
+            ("data".into(), vec![0u8, 1, 2, 255].into()),
+            ("date".into(), Date(123.0).into()),
+            ("empty".into(), Value::Array(vec![])),
+            ("empty_dict".into(), Value::Dict(vec![])),
+            ("int".into(), (-42).into()),
+            (
+                "list".into(),
+                Value::Array(vec![true.into(), false.into(), 0.5.into()]),
+            ),
+            ("uid".into(), Uid(7).into()),
+        ])
+    }
+
+    #[test]
+    fn read_write() {
+        let val = Value::from_xml(SAMPLE).unwrap();
+        assert_eq!(val, sample());
+        assert_eq!(val.to_xml().unwrap(), SAMPLE);
+    }
+
+    #[test]
+    fn round_trip() {
+        let val = Value::Array(vec![
+            "héllo ✓ 𝄞 > \"'".into(),
+            u64::MAX.into(),
+            i64::MIN.into(),
+            f64::INFINITY.into(),
+            f64::NEG_INFINITY.into(),
+            1e300.into(),
+            (0..=255u8).collect::<Vec<_>>().into(),
+            vec![1u8].into(),
+            vec![1u8, 2].into(),
+            "".into(),
+        ]);
+        let xml = val.to_xml().unwrap();
+        assert_eq!(Value::from_xml(&xml).unwrap(), val);
+
+        let nan = Value::from_xml(&Value::Real(f64::NAN).to_xml().unwrap()).unwrap();
+        assert!(nan.as_f64().unwrap().is_nan());
+    }
+
+    #[test]
+    fn lenient() {
+        let val = Value::from_xml(
+            "<!-- c --><plist><array>\n<string><![CDATA[<a>]]>&#65;&#x42;&quot;</string>\
+             <integer> 0x10 </integer><string/><true></true><data>AA\n  EC</data></array></plist>",
+        )
+        .unwrap();
+        assert_eq!(
+            val,
+            Value::Array(vec![
+                "<a>AB\"".into(),
+                16.into(),
+                "".into(),
+                true.into(),
+                vec![0u8, 1, 2].into(),
+            ])
+        );
+        // no plist root element
+        assert_eq!(Value::from_xml("<integer>1</integer>"), Ok(1.into()));
+    }
+
+    #[test]
+    fn malformed() {
+        let cases = [
+            "",
+            "<plist>",
+            "<plist><array></plist>",
+            "<plist><integer>1</string></plist>",
+            "<plist><integer>x</integer></plist>",
+            "<plist><integer>18446744073709551616</integer></plist>",
+            "<plist><integer>-9223372036854775809</integer></plist>",
+            "<plist><real>1.2.3</real></plist>",
+            "<plist><date>yesterday</date></plist>",
+            "<plist><data>!!</data></plist>",
+            "<plist><dict><string>a</string></dict></plist>",
+            "<plist><string>&bogus;</string></plist>",
+            "<plist><string>&#xd800;</string></plist>",
+            "<plist><string>a</string></plist><string>b</string>",
+            "<plist><foo/></plist>",
+        ];
+        for xml in cases {
+            assert!(
+                matches!(Value::from_xml(xml), Err(Error::Xml { .. })),
+                "{xml}"
+            );
+        }
+        assert_eq!(
+            Value::Int(u64::MAX as i128 + 1).to_xml(),
+            Err(Error::IntOutOfRange)
+        );
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let depth = MAX_DEPTH + 2;
+        let xml = format!("{}{}", "<array>".repeat(depth), "</array>".repeat(depth));
+        assert_eq!(Value::from_xml(&xml), Err(Error::TooDeep));
+
+        let xml = format!(
+            "{}{}",
+            "<array>".repeat(MAX_DEPTH),
+            "</array>".repeat(MAX_DEPTH)
+        );
+        let val = Value::from_xml(&xml).unwrap();
+        assert_eq!(Value::from_xml(&val.to_xml().unwrap()).unwrap(), val);
+
+        // must fail without blowing the stack
+        let xml = "<array>".repeat(100_000);
+        assert_eq!(Value::from_xml(&xml), Err(Error::TooDeep));
+    }
+}
@@ -1,6 +1,11 @@
 pub mod vector_types;
+pub use vector_types::Mask;
 pub use vector_types::Simd;
 
+mod matrix;
+mod quat;
+mod swizzle;
+
 #[allow(non_camel_case_types)]
 pub type i8x2 = Simd<i8, 2, 2>;
 #[allow(non_camel_case_types)]
@@ -46,6 +51,9 @@ pub type f16x3 = Simd<half::f16, 4, 3>;
 #[allow(non_camel_case_types)]
 pub type f32x4 = Simd<f32, 4, 4>;
 
+#[allow(non_camel_case_types)]
+pub type f64x3 = Simd<f64, 4, 3>;
+
 #[allow(non_camel_case_types)]
 pub type f64x4 = Simd<f64, 4, 4>;
 
@@ -93,7 +101,7 @@ pub struct f32x4x2(pub [f32x2; 4]);
 #[repr(transparent)]
 pub struct f32x2x3(pub [f32x3; 2]);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 #[repr(transparent)]
 pub struct f32x3x3(pub [f32x3; 3]);
@@ -115,6 +123,8 @@ impl f32x3x3 {
         ])
     }
 
+    /// Translation stored in the z of the first two columns, for shaders which
+    /// multiply row vectors
     pub const fn translate(tx: f32, ty: f32) -> Self {
         Self([
             f32x3::with_xyz_f32(1.0, 0.0, tx),
@@ -122,7 +132,18 @@ impl f32x3x3 {
             f32x3::with_xyz_f32(0.0, 0.0, 1.0),
         ])
     }
+
+    /// 2D translation in homogeneous coordinates, in the last column like
+    /// [`f32x4x4::translate`]
+    pub const fn translation(tx: f32, ty: f32) -> Self {
+        Self([
+            f32x3::with_xyz_f32(1.0, 0.0, 0.0),
+            f32x3::with_xyz_f32(0.0, 1.0, 0.0),
+            f32x3::with_xyz_f32(tx, ty, 1.0),
+        ])
+    }
 }
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[allow(non_camel_case_types)]
 #[repr(transparent)]
@@ -382,20 +403,6 @@ impl f16quat {
     }
 }
 
-impl f32quat {
-    #[inline]
-    pub fn with_angle(angle: f32, axis: f32x3) -> Self {
-        let half_angle = angle * 0.5;
-        let sin = half_angle.sin();
-        Self(Simd([
-            sin * axis.x(),
-            sin * axis.y(),
-            sin * axis.z(),
-            half_angle.cos(),
-        ]))
-    }
-}
-
 pub mod packed {
     use super::Simd;
     #[allow(non_camel_case_types)]
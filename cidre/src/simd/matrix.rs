@@ -0,0 +1,671 @@
+//! Column-major matrix algebra, `m[c][r]` is the element in column `c` and row `r`
+//! like in `simd/matrix.h`.
+
+use std::ops::{Add, Mul, MulAssign, Neg, Sub};
+
+use super::{f32quat, f32x3, f32x3x3, f32x4, f32x4x4};
+
+impl f32x3x3 {
+    #[inline]
+    pub const fn with_columns(c0: f32x3, c1: f32x3, c2: f32x3) -> Self {
+        Self([c0, c1, c2])
+    }
+
+    #[inline]
+    pub fn with_rows(r0: f32x3, r1: f32x3, r2: f32x3) -> Self {
+        Self([r0, r1, r2]).transpose()
+    }
+
+    /// Rotation of `angle` radians around the unit `axis`
+    #[inline]
+    pub fn rotate(angle: f32, axis: f32x3) -> Self {
+        f32quat::with_angle(angle, axis).into()
+    }
+
+    #[inline]
+    pub fn transpose(&self) -> Self {
+        let m = &self.0;
+        Self([
+            f32x3::with_xyz_f32(m[0].x(), m[1].x(), m[2].x()),
+            f32x3::with_xyz_f32(m[0].y(), m[1].y(), m[2].y()),
+            f32x3::with_xyz_f32(m[0].z(), m[1].z(), m[2].z()),
+        ])
+    }
+
+    #[inline]
+    pub fn determinant(&self) -> f32 {
+        self.0[0].dot(self.0[1].cross(self.0[2]))
+    }
+
+    /// Like `simd_inverse`, singular matrices produce non-finite elements.
+    pub fn inverse(&self) -> Self {
+        let [c0, c1, c2] = self.0;
+        let r = 1.0 / self.determinant();
+        // rows of the inverse are the cross products of the columns
+        Self([c1.cross(c2) * r, c2.cross(c0) * r, c0.cross(c1) * r]).transpose()
+    }
+}
+
+impl std::ops::Index<usize> for f32x3x3 {
+    type Output = f32x3;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl std::ops::IndexMut<usize> for f32x3x3 {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl Mul<f32x3> for f32x3x3 {
+    type Output = f32x3;
+
+    #[inline]
+    fn mul(self, v: f32x3) -> f32x3 {
+        let m = &self.0;
+        m[0] * v.x() + m[1] * v.y() + m[2] * v.z()
+    }
+}
+
+impl Mul for f32x3x3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(rhs.0.map(|c| self * c))
+    }
+}
+
+impl MulAssign for f32x3x3 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl Mul<f32> for f32x3x3 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f32) -> Self {
+        Self(self.0.map(|c| c * rhs))
+    }
+}
+
+impl Add for f32x3x3 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl Sub for f32x3x3 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl Neg for f32x3x3 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.map(|c| -c))
+    }
+}
+
+impl f32x4x4 {
+    #[inline]
+    pub const fn with_columns(c0: f32x4, c1: f32x4, c2: f32x4, c3: f32x4) -> Self {
+        Self([c0, c1, c2, c3])
+    }
+
+    #[inline]
+    pub fn with_rows(r0: f32x4, r1: f32x4, r2: f32x4, r3: f32x4) -> Self {
+        Self([r0, r1, r2, r3]).transpose()
+    }
+
+    pub const fn scale(sx: f32, sy: f32, sz: f32) -> Self {
+        Self([
+            f32x4::with_xyzw_f32(sx, 0.0, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, sy, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, sz, 0.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, 0.0, 1.0),
+        ])
+    }
+
+    /// Rotation of `angle` radians around the unit `axis`
+    #[inline]
+    pub fn rotate(angle: f32, axis: f32x3) -> Self {
+        f32quat::with_angle(angle, axis).into()
+    }
+
+    /// Right-handed perspective projection into Metal clip space
+    /// (x and y in -1...1, z in 0...1), the camera looks down -z.
+    ///
+    /// `fovy` is the vertical field of view in radians.
+    pub fn perspective_rh(fovy: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let ys = 1.0 / (fovy * 0.5).tan();
+        let xs = ys / aspect;
+        let zs = far / (near - far);
+        Self([
+            f32x4::with_xyzw_f32(xs, 0.0, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, ys, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, zs, -1.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, zs * near, 0.0),
+        ])
+    }
+
+    /// Left-handed perspective projection into Metal clip space, the camera looks down +z.
+    pub fn perspective_lh(fovy: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let ys = 1.0 / (fovy * 0.5).tan();
+        let xs = ys / aspect;
+        let zs = far / (far - near);
+        Self([
+            f32x4::with_xyzw_f32(xs, 0.0, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, ys, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, zs, 1.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, -zs * near, 0.0),
+        ])
+    }
+
+    /// Right-handed orthographic projection into Metal clip space,
+    /// `near` and `far` are distances along -z.
+    pub fn orthographic_rh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        Self([
+            f32x4::with_xyzw_f32(2.0 / (right - left), 0.0, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 2.0 / (top - bottom), 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, 1.0 / (near - far), 0.0),
+            f32x4::with_xyzw_f32(
+                (left + right) / (left - right),
+                (top + bottom) / (bottom - top),
+                near / (near - far),
+                1.0,
+            ),
+        ])
+    }
+
+    /// Left-handed orthographic projection into Metal clip space,
+    /// `near` and `far` are distances along +z.
+    pub fn orthographic_lh(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        Self([
+            f32x4::with_xyzw_f32(2.0 / (right - left), 0.0, 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 2.0 / (top - bottom), 0.0, 0.0),
+            f32x4::with_xyzw_f32(0.0, 0.0, 1.0 / (far - near), 0.0),
+            f32x4::with_xyzw_f32(
+                (left + right) / (left - right),
+                (top + bottom) / (bottom - top),
+                near / (near - far),
+                1.0,
+            ),
+        ])
+    }
+
+    /// Right-handed view matrix, `center` ends up on the -z axis.
+    pub fn look_at_rh(eye: f32x3, center: f32x3, up: f32x3) -> Self {
+        let f = (center - eye).normalize();
+        let s = f.cross(up).normalize();
+        let u = s.cross(f);
+        Self::view(s, u, -f, eye)
+    }
+
+    /// Left-handed view matrix, `center` ends up on the +z axis.
+    pub fn look_at_lh(eye: f32x3, center: f32x3, up: f32x3) -> Self {
+        let f = (center - eye).normalize();
+        let s = up.cross(f).normalize();
+        let u = f.cross(s);
+        Self::view(s, u, f, eye)
+    }
+
+    /// Rows are the camera axes, the last column moves `eye` to the origin.
+    fn view(x: f32x3, y: f32x3, z: f32x3, eye: f32x3) -> Self {
+        Self([
+            f32x4::with_xyzw_f32(x.x(), y.x(), z.x(), 0.0),
+            f32x4::with_xyzw_f32(x.y(), y.y(), z.y(), 0.0),
+            f32x4::with_xyzw_f32(x.z(), y.z(), z.z(), 0.0),
+            f32x4::with_xyzw_f32(-x.dot(eye), -y.dot(eye), -z.dot(eye), 1.0),
+        ])
+    }
+
+    #[inline]
+    pub fn transpose(&self) -> Self {
+        let m = &self.0;
+        Self(std::array::from_fn(|r| {
+            f32x4::with_xyzw(m[0][r], m[1][r], m[2][r], m[3][r])
+        }))
+    }
+
+    pub fn determinant(&self) -> f32 {
+        let (s, c) = self.minors();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    /// 2x2 minors of the first two and of the last two columns
+    #[inline]
+    fn minors(&self) -> ([f32; 6], [f32; 6]) {
+        let m = &self.0;
+        let s = [
+            m[0][0] * m[1][1] - m[1][0] * m[0][1],
+            m[0][0] * m[1][2] - m[1][0] * m[0][2],
+            m[0][0] * m[1][3] - m[1][0] * m[0][3],
+            m[0][1] * m[1][2] - m[1][1] * m[0][2],
+            m[0][1] * m[1][3] - m[1][1] * m[0][3],
+            m[0][2] * m[1][3] - m[1][2] * m[0][3],
+        ];
+        let c = [
+            m[2][0] * m[3][1] - m[3][0] * m[2][1],
+            m[2][0] * m[3][2] - m[3][0] * m[2][2],
+            m[2][0] * m[3][3] - m[3][0] * m[2][3],
+            m[2][1] * m[3][2] - m[3][1] * m[2][2],
+            m[2][1] * m[3][3] - m[3][1] * m[2][3],
+            m[2][2] * m[3][3] - m[3][2] * m[2][3],
+        ];
+        (s, c)
+    }
+
+    /// Like `simd_inverse`, singular matrices produce non-finite elements.
+    pub fn inverse(&self) -> Self {
+        let m = &self.0;
+        let (s, c) = self.minors();
+        let det = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        let r = 1.0 / det;
+        let col = |a: f32, b: f32, c: f32, d: f32| f32x4::with_xyzw(a * r, b * r, c * r, d * r);
+        Self([
+            col(
+                m[1][1] * c[5] - m[1][2] * c[4] + m[1][3] * c[3],
+                -m[0][1] * c[5] + m[0][2] * c[4] - m[0][3] * c[3],
+                m[3][1] * s[5] - m[3][2] * s[4] + m[3][3] * s[3],
+                -m[2][1] * s[5] + m[2][2] * s[4] - m[2][3] * s[3],
+            ),
+            col(
+                -m[1][0] * c[5] + m[1][2] * c[2] - m[1][3] * c[1],
+                m[0][0] * c[5] - m[0][2] * c[2] + m[0][3] * c[1],
+                -m[3][0] * s[5] + m[3][2] * s[2] - m[3][3] * s[1],
+                m[2][0] * s[5] - m[2][2] * s[2] + m[2][3] * s[1],
+            ),
+            col(
+                m[1][0] * c[4] - m[1][1] * c[2] + m[1][3] * c[0],
+                -m[0][0] * c[4] + m[0][1] * c[2] - m[0][3] * c[0],
+                m[3][0] * s[4] - m[3][1] * s[2] + m[3][3] * s[0],
+                -m[2][0] * s[4] + m[2][1] * s[2] - m[2][3] * s[0],
+            ),
+            col(
+                -m[1][0] * c[3] + m[1][1] * c[1] - m[1][2] * c[0],
+                m[0][0] * c[3] - m[0][1] * c[1] + m[0][2] * c[0],
+                -m[3][0] * s[3] + m[3][1] * s[1] - m[3][2] * s[0],
+                m[2][0] * s[3] - m[2][1] * s[1] + m[2][2] * s[0],
+            ),
+        ])
+    }
+
+    /// Transforms the point `(x, y, z, 1)` and divides by the resulting w
+    #[inline]
+    pub fn transform_point(&self, p: f32x3) -> f32x3 {
+        let v = *self * p.with_w(1.0);
+        f32x3::from(v) / v.w()
+    }
+}
+
+impl Mul<f32x4> for f32x4x4 {
+    type Output = f32x4;
+
+    #[inline]
+    fn mul(self, v: f32x4) -> f32x4 {
+        let m = &self.0;
+        let mut res = f32x4::default();
+        for (c, s) in m.iter().zip(v.0) {
+            res += *c * s;
+        }
+        res
+    }
+}
+
+impl Mul for f32x4x4 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self(rhs.0.map(|c| self * c))
+    }
+}
+
+impl MulAssign for f32x4x4 {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs
+    }
+}
+
+impl Mul<f32> for f32x4x4 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: f32) -> Self {
+        Self(self.0.map(|c| c * rhs))
+    }
+}
+
+impl Add for f32x4x4 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl Sub for f32x4x4 {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl Neg for f32x4x4 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.map(|c| -c))
+    }
+}
+
+impl From<f32x3x3> for f32x4x4 {
+    /// Upper left 3x3 block of the result, the rest is identity
+    #[inline]
+    fn from(m: f32x3x3) -> Self {
+        let [c0, c1, c2] = m.0.map(f32x4::from);
+        Self([c0, c1, c2, f32x4::with_xyzw_f32(0.0, 0.0, 0.0, 1.0)])
+    }
+}
+
+impl From<f32quat> for f32x3x3 {
+    /// Rotation matrix of the unit quaternion
+    fn from(q: f32quat) -> Self {
+        let [x, y, z, w] = q.0.0;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (xw, yw, zw) = (x * w, y * w, z * w);
+        Self([
+            f32x3::with_xyz_f32(1.0 - 2.0 * (yy + zz), 2.0 * (xy + zw), 2.0 * (xz - yw)),
+            f32x3::with_xyz_f32(2.0 * (xy - zw), 1.0 - 2.0 * (xx + zz), 2.0 * (yz + xw)),
+            f32x3::with_xyz_f32(2.0 * (xz + yw), 2.0 * (yz - xw), 1.0 - 2.0 * (xx + yy)),
+        ])
+    }
+}
+
+impl From<f32quat> for f32x4x4 {
+    /// Rotation matrix of the unit quaternion
+    #[inline]
+    fn from(q: f32quat) -> Self {
+        f32x3x3::from(q).into()
+    }
+}
+
+#[cfg(feature = "half")]
+mod half_impl {
+    use std::ops::Mul;
+
+    use crate::simd::{Simd, f16x4, f16x4x4, f32x4x4};
+
+    impl From<f16x4x4> for f32x4x4 {
+        #[inline]
+        fn from(m: f16x4x4) -> Self {
+            Self(m.0.map(|c| Simd(c.0.map(half::f16::to_f32))))
+        }
+    }
+
+    impl From<f32x4x4> for f16x4x4 {
+        #[inline]
+        fn from(m: f32x4x4) -> Self {
+            Self(m.0.map(|c| Simd(c.0.map(half::f16::from_f32))))
+        }
+    }
+
+    impl f16x4x4 {
+        #[inline]
+        pub fn transpose(&self) -> Self {
+            let m = &self.0;
+            Self(std::array::from_fn(|r| {
+                f16x4::with_xyzw(m[0][r], m[1][r], m[2][r], m[3][r])
+            }))
+        }
+    }
+
+    /// Computed in single precision
+    impl Mul for f16x4x4 {
+        type Output = Self;
+
+        #[inline]
+        fn mul(self, rhs: Self) -> Self {
+            (f32x4x4::from(self) * f32x4x4::from(rhs)).into()
+        }
+    }
+
+    /// Computed in single precision
+    impl Mul<f16x4> for f16x4x4 {
+        type Output = f16x4;
+
+        #[inline]
+        fn mul(self, v: f16x4) -> f16x4 {
+            let v = f32x4x4::from(self) * Simd(v.0.map(half::f16::to_f32));
+            Simd(v.0.map(half::f16::from_f32))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_3};
+
+    use crate::simd::{f32quat, f32x3, f32x3x3, f32x4, f32x4x4};
+
+    fn close4(a: &f32x4x4, b: &f32x4x4) -> bool {
+        (0..4).all(|c| (0..4).all(|r| (a[c][r] - b[c][r]).abs() < 1e-5))
+    }
+
+    fn close3(a: f32x3, b: f32x3) -> bool {
+        (0..3).all(|i| (a[i] - b[i]).abs() < 1e-5)
+    }
+
+    fn sample() -> f32x4x4 {
+        f32x4x4::with_rows(
+            f32x4::with_xyzw(2.0, 0.5, -1.0, 3.0),
+            f32x4::with_xyzw(0.0, 1.5, 4.0, -2.0),
+            f32x4::with_xyzw(1.0, -3.0, 0.25, 0.0),
+            f32x4::with_xyzw(0.5, 0.0, 1.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn algebra4() {
+        let m = sample();
+        assert_eq!(m[0], f32x4::with_xyzw(2.0, 0.0, 1.0, 0.5));
+        assert_eq!(m.transpose().transpose(), m);
+        assert_eq!(m * f32x4x4::identity(), m);
+        assert_eq!(f32x4x4::identity() * m, m);
+
+        // column-major: translation lives in the last column
+        let t = f32x4x4::translate(1.0, 2.0, 3.0);
+        let p = t * f32x4::with_xyzw(1.0, 1.0, 1.0, 1.0);
+        assert_eq!(p, f32x4::with_xyzw(2.0, 3.0, 4.0, 1.0));
+        let s = f32x4x4::scale(2.0, 3.0, 4.0);
+        assert_eq!(s, f32x4x4::diagonal(f32x4::with_xyzw(2.0, 3.0, 4.0, 1.0)));
+        // scale first, then translate
+        let v = (t * s) * f32x4::with_xyzw(1.0, 1.0, 1.0, 1.0);
+        assert_eq!(v, f32x4::with_xyzw(3.0, 5.0, 7.0, 1.0));
+
+        assert!((f32x4x4::identity().determinant() - 1.0).abs() < 1e-6);
+        assert!((s.determinant() - 24.0).abs() < 1e-5);
+        assert!((m.determinant() - m.transpose().determinant()).abs() < 1e-4);
+        assert!(((m * m).determinant() - m.determinant().powi(2)).abs() < 1e-2);
+
+        let inv = m.inverse();
+        assert!(close4(&(m * inv), &f32x4x4::identity()));
+        assert!(close4(&(inv * m), &f32x4x4::identity()));
+        assert!(close4(&t.inverse(), &f32x4x4::translate(-1.0, -2.0, -3.0)));
+        assert!(f32x4x4::scale(1.0, 0.0, 1.0).inverse()[0][0].is_nan());
+
+        assert_eq!(m + m, m * 2.0);
+        assert_eq!(m - m, m * 0.0);
+        assert_eq!(-m, m * -1.0);
+        let mut a = m;
+        a *= inv;
+        assert!(close4(&a, &f32x4x4::identity()));
+    }
+
+    #[test]
+    fn algebra3() {
+        let m = f32x3x3::with_rows(
+            f32x3::with_xyz(2.0, -1.0, 0.0),
+            f32x3::with_xyz(1.0, 3.0, 2.0),
+            f32x3::with_xyz(0.0, 1.0, 4.0),
+        );
+        assert_eq!(m.transpose()[0], f32x3::with_xyz(2.0, -1.0, 0.0));
+        assert!((m.determinant() - 24.0).abs() < 1e-5);
+        let id = m * m.inverse();
+        for c in 0..3 {
+            assert!(close3(id[c], f32x3x3::identity()[c]));
+        }
+        assert_eq!(m * f32x3x3::identity(), m);
+        assert_eq!(
+            m * f32x3::with_xyz(1.0, 0.0, 0.0),
+            f32x3::with_xyz(2.0, 1.0, 0.0)
+        );
+
+        let t = f32x3x3::translation(2.0, -1.0);
+        let p = t * f32x3::with_xyz(1.0, 1.0, 1.0);
+        assert_eq!(p, f32x3::with_xyz(3.0, 0.0, 1.0));
+        assert_eq!(f32x3x3::translate(2.0, -1.0), t.transpose());
+        assert_eq!(m - m + m, m);
+
+        let embedded = f32x4x4::from(m);
+        assert_eq!(embedded[1], f32x4::with_xyzw(-1.0, 3.0, 1.0, 0.0));
+        assert_eq!(embedded[3], f32x4::with_xyzw(0.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn rotations() {
+        let z = f32x3::with_xyz(0.0, 0.0, 1.0);
+        let r = f32x3x3::rotate(FRAC_PI_2, z);
+        assert!(close3(
+            r * f32x3::with_xyz(1.0, 0.0, 0.0),
+            f32x3::with_xyz(0.0, 1.0, 0.0)
+        ));
+        assert!((r.determinant() - 1.0).abs() < 1e-6);
+        // rotation matrices are orthogonal
+        let rt = r * r.transpose();
+        for c in 0..3 {
+            assert!(close3(rt[c], f32x3x3::identity()[c]));
+        }
+
+        let axis = f32x3::with_xyz(1.0, 2.0, 2.0);
+        let axis = f32x3::with_xyz(axis.x() / 3.0, axis.y() / 3.0, axis.z() / 3.0);
+        let q = f32quat::with_angle(FRAC_PI_3, axis);
+        let v = f32x3::with_xyz(0.3, -1.0, 2.0);
+        assert!(close3(f32x3x3::from(q) * v, q.act(v)));
+        let m4 = f32x4x4::rotate(FRAC_PI_3, axis);
+        assert!(close3(m4.transform_point(v), q.act(v)));
+    }
+
+    #[test]
+    fn projections() {
+        let near = 0.1;
+        let far = 100.0;
+        let p = f32x4x4::perspective_rh(FRAC_PI_2, 2.0, near, far);
+        let on = |z: f32| p.transform_point(f32x3::with_xyz(0.0, 0.0, z));
+        assert!(on(-near).z().abs() < 1e-6);
+        assert!((on(-far).z() - 1.0).abs() < 1e-5);
+        // 90 degrees vertical fov: y == -z is the top edge
+        let top = p.transform_point(f32x3::with_xyz(0.0, 5.0, -5.0));
+        assert!((top.y() - 1.0).abs() < 1e-6);
+        let right = p.transform_point(f32x3::with_xyz(10.0, 0.0, -5.0));
+        assert!((right.x() - 1.0).abs() < 1e-6);
+
+        let p = f32x4x4::perspective_lh(FRAC_PI_2, 1.0, near, far);
+        let on = |z: f32| p.transform_point(f32x3::with_xyz(0.0, 0.0, z));
+        assert!(on(near).z().abs() < 1e-6);
+        assert!((on(far).z() - 1.0).abs() < 1e-5);
+
+        let o = f32x4x4::orthographic_rh(-2.0, 6.0, -1.0, 1.0, 1.0, 11.0);
+        let a = o.transform_point(f32x3::with_xyz(-2.0, -1.0, -1.0));
+        let b = o.transform_point(f32x3::with_xyz(6.0, 1.0, -11.0));
+        assert!(close3(a, f32x3::with_xyz(-1.0, -1.0, 0.0)));
+        assert!(close3(b, f32x3::with_xyz(1.0, 1.0, 1.0)));
+
+        let o = f32x4x4::orthographic_lh(-2.0, 6.0, -1.0, 1.0, 1.0, 11.0);
+        let a = o.transform_point(f32x3::with_xyz(-2.0, -1.0, 1.0));
+        let b = o.transform_point(f32x3::with_xyz(6.0, 1.0, 11.0));
+        assert!(close3(a, f32x3::with_xyz(-1.0, -1.0, 0.0)));
+        assert!(close3(b, f32x3::with_xyz(1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn look_at() {
+        let eye = f32x3::with_xyz(1.0, 2.0, 3.0);
+        let center = f32x3::with_xyz(1.0, 2.0, -7.0);
+        let up = f32x3::with_xyz(0.0, 1.0, 0.0);
+
+        let v = f32x4x4::look_at_rh(eye, center, up);
+        assert!(close3(
+            v.transform_point(eye),
+            f32x3::with_xyz(0.0, 0.0, 0.0)
+        ));
+        assert!(close3(
+            v.transform_point(center),
+            f32x3::with_xyz(0.0, 0.0, -10.0)
+        ));
+        // looking down -z from +z keeps the world axes
+        assert!(close4(&v, &f32x4x4::translate(-1.0, -2.0, -3.0)));
+
+        let v = f32x4x4::look_at_lh(eye, center, up);
+        assert!(close3(
+            v.transform_point(center),
+            f32x3::with_xyz(0.0, 0.0, 10.0)
+        ));
+        let above = f32x3::with_xyz(1.0, 3.0, 3.0);
+        assert!(close3(
+            v.transform_point(above),
+            f32x3::with_xyz(0.0, 1.0, 0.0)
+        ));
+        assert!((v.determinant() - 1.0).abs() < 1e-5);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn half() {
+        use crate::simd::{f16x4, f16x4x4};
+
+        let m = f16x4x4::from(sample());
+        assert_eq!(f32x4x4::from(m), sample());
+        let t = f16x4x4::from(f32x4x4::translate(1.0, 2.0, 3.0));
+        let o = half::f16::ONE;
+        let p = t * f16x4::with_xyzw(o, o, o, o);
+        assert_eq!(p.0.map(half::f16::to_f32), [2.0, 3.0, 4.0, 1.0]);
+        assert_eq!((m * f16x4x4::identity()).transpose(), m.transpose());
+    }
+}
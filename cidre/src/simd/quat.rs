@@ -0,0 +1,290 @@
+//! Quaternion algebra, the vector holds the imaginary part in x, y, z
+//! and the real part in w like `simd_quatf`.
+
+use std::ops::{Mul, MulAssign, Neg};
+
+use super::{Simd, f32quat, f32x3, f64quat, f64x3};
+
+macro_rules! quat {
+    ($quat:ident, $t:ty, $v3:ident) => {
+        impl $quat {
+            pub const fn identity() -> Self {
+                Self(Simd([0.0, 0.0, 0.0, 1.0]))
+            }
+
+            #[inline]
+            pub const fn with_ixyzr(ix: $t, iy: $t, iz: $t, r: $t) -> Self {
+                Self(Simd([ix, iy, iz, r]))
+            }
+
+            /// Rotation of `angle` radians around the unit `axis`
+            #[inline]
+            pub fn with_angle(angle: $t, axis: $v3) -> Self {
+                let half_angle = angle * 0.5;
+                let sin = half_angle.sin();
+                Self(Simd([
+                    sin * axis.x(),
+                    sin * axis.y(),
+                    sin * axis.z(),
+                    half_angle.cos(),
+                ]))
+            }
+
+            /// Shortest rotation taking the unit vector `from` to the unit vector `to`
+            pub fn with_from_to(from: $v3, to: $v3) -> Self {
+                let cos = from.dot(to);
+                if cos < -1.0 + <$t>::EPSILON * 8.0 {
+                    // opposite vectors, rotate half a turn around any perpendicular axis
+                    let [x, y, z, _] = from.0;
+                    let axis = if x.abs() < 0.9 {
+                        $v3::with_xyz(0.0, z, -y)
+                    } else {
+                        $v3::with_xyz(-z, 0.0, x)
+                    };
+                    return Self(axis.normalize().with_w(0.0));
+                }
+                Self(from.cross(to).with_w(1.0 + cos)).normalize()
+            }
+
+            #[inline]
+            pub fn real(&self) -> $t {
+                self.0.w()
+            }
+
+            #[inline]
+            pub fn imag(&self) -> $v3 {
+                self.0.into()
+            }
+
+            /// Rotation angle in radians of the unit quaternion
+            #[inline]
+            pub fn angle(&self) -> $t {
+                2.0 * self.imag().length().atan2(self.real())
+            }
+
+            /// Rotation axis of the unit quaternion, x axis for the identity
+            #[inline]
+            pub fn axis(&self) -> $v3 {
+                let imag = self.imag();
+                if imag.length_squared() == 0.0 {
+                    $v3::with_xyz(1.0, 0.0, 0.0)
+                } else {
+                    imag.normalize()
+                }
+            }
+
+            #[inline]
+            pub fn dot(self, other: Self) -> $t {
+                self.0.dot(other.0)
+            }
+
+            #[inline]
+            pub fn length(self) -> $t {
+                self.0.length()
+            }
+
+            #[inline]
+            pub fn normalize(self) -> Self {
+                Self(self.0.normalize())
+            }
+
+            #[inline]
+            pub fn conjugate(self) -> Self {
+                Self((-self.imag()).with_w(self.real()))
+            }
+
+            #[inline]
+            pub fn inverse(self) -> Self {
+                Self(self.conjugate().0 / self.0.length_squared())
+            }
+
+            /// Rotates `v` by the unit quaternion, `simd_act`
+            #[inline]
+            pub fn act(self, v: $v3) -> $v3 {
+                // v + 2w (u × v) + 2u × (u × v)
+                let u = self.imag();
+                let t = u.cross(v) * 2.0;
+                v + t * self.real() + u.cross(t)
+            }
+
+            /// Spherical linear interpolation along the shortest arc, `simd_slerp`
+            pub fn slerp(self, other: Self, t: $t) -> Self {
+                let mut cos = self.dot(other);
+                let mut other = other;
+                if cos < 0.0 {
+                    other = -other;
+                    cos = -cos;
+                }
+                let (wa, wb) = if cos > 1.0 - <$t>::EPSILON * 64.0 {
+                    // nearly parallel, linear interpolation is precise enough
+                    (1.0 - t, t)
+                } else {
+                    let theta = cos.acos();
+                    let sin = theta.sin();
+                    (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+                };
+                Self(self.0 * wa + other.0 * wb).normalize()
+            }
+        }
+
+        impl Default for $quat {
+            #[inline]
+            fn default() -> Self {
+                Self::identity()
+            }
+        }
+
+        /// Hamilton product, `a * b` rotates by `b` first
+        impl Mul for $quat {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: Self) -> Self {
+                let (a, b) = (self.imag(), rhs.imag());
+                let (aw, bw) = (self.real(), rhs.real());
+                Self((b * aw + a * bw + a.cross(b)).with_w(aw * bw - a.dot(b)))
+            }
+        }
+
+        impl MulAssign for $quat {
+            #[inline]
+            fn mul_assign(&mut self, rhs: Self) {
+                *self = *self * rhs
+            }
+        }
+
+        /// Rotates the vector, same as `act`
+        impl Mul<$v3> for $quat {
+            type Output = $v3;
+
+            #[inline]
+            fn mul(self, rhs: $v3) -> $v3 {
+                self.act(rhs)
+            }
+        }
+
+        impl Neg for $quat {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+    };
+}
+
+quat!(f32quat, f32, f32x3);
+quat!(f64quat, f64, f64x3);
+
+impl From<f32quat> for f64quat {
+    #[inline]
+    fn from(q: f32quat) -> Self {
+        Self(Simd(q.0.0.map(f64::from)))
+    }
+}
+
+#[cfg(feature = "half")]
+mod half_impl {
+    use crate::simd::{Simd, f16quat, f32quat};
+
+    impl From<f16quat> for f32quat {
+        #[inline]
+        fn from(q: f16quat) -> Self {
+            Self(Simd(q.0.0.map(half::f16::to_f32)))
+        }
+    }
+
+    impl From<f32quat> for f16quat {
+        #[inline]
+        fn from(q: f32quat) -> Self {
+            Self(Simd(q.0.0.map(half::f16::from_f32)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::{FRAC_PI_2, PI};
+
+    use crate::simd::{f32quat, f32x3, f64quat, f64x3};
+
+    fn close(a: f32x3, b: f32x3) -> bool {
+        (0..3).all(|i| (a[i] - b[i]).abs() < 1e-5)
+    }
+
+    #[test]
+    fn algebra() {
+        let x = f32x3::with_xyz(1.0, 0.0, 0.0);
+        let y = f32x3::with_xyz(0.0, 1.0, 0.0);
+        let z = f32x3::with_xyz(0.0, 0.0, 1.0);
+
+        let rz = f32quat::with_angle(FRAC_PI_2, z);
+        let rx = f32quat::with_angle(FRAC_PI_2, x);
+        assert!(close(rz.act(x), y));
+        assert!(close(rz * y, f32x3::with_xyz(-1.0, 0.0, 0.0)));
+
+        // rx * rz applies rz first
+        let both = rx * rz;
+        assert!(close(both.act(x), rx.act(rz.act(x))));
+        assert!(close(both.act(x), z));
+        assert!((both.length() - 1.0).abs() < 1e-6);
+
+        assert_eq!(f32quat::default(), f32quat::identity());
+        assert_eq!(rz * f32quat::identity(), rz);
+        assert!(close((rz * rz.inverse()).imag(), f32x3::default()));
+        assert!((rz.conjugate().dot(rz.inverse()) - 1.0).abs() < 1e-6);
+        let scaled = f32quat::with_ixyzr(0.0, 0.0, 2.0, 2.0);
+        assert!((scaled.normalize().dot(rz) - 1.0).abs() < 1e-6);
+        assert!(((scaled * scaled.inverse()).real() - 1.0).abs() < 1e-6);
+
+        assert!((rz.angle() - FRAC_PI_2).abs() < 1e-6);
+        assert!(close(rz.axis(), z));
+        assert!(close(f32quat::identity().axis(), x));
+
+        let mut q = rz;
+        q *= rz;
+        assert!(close(q.act(x), f32x3::with_xyz(-1.0, 0.0, 0.0)));
+        assert!((q.angle() - PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn from_to() {
+        let x = f32x3::with_xyz(1.0, 0.0, 0.0);
+        let v = f32x3::with_xyz(0.0, 0.6, 0.8);
+        assert!(close(f32quat::with_from_to(x, v).act(x), v));
+        assert_eq!(f32quat::with_from_to(v, v), f32quat::identity());
+
+        let opposite = f32x3::with_xyz(-1.0, 0.0, 0.0);
+        let q = f32quat::with_from_to(x, opposite);
+        assert!(close(q.act(x), opposite));
+        assert!((q.length() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn slerp() {
+        let z = f32x3::with_xyz(0.0, 0.0, 1.0);
+        let a = f32quat::identity();
+        let b = f32quat::with_angle(FRAC_PI_2, z);
+        assert!((a.slerp(b, 0.0).dot(a) - 1.0).abs() < 1e-6);
+        assert!((a.slerp(b, 1.0).dot(b) - 1.0).abs() < 1e-6);
+        for t in [0.25, 0.5, 0.9] {
+            let q = a.slerp(b, t);
+            assert!((q.angle() - FRAC_PI_2 * t).abs() < 1e-5, "{t}");
+            assert!((q.length() - 1.0).abs() < 1e-6);
+        }
+        // -b is the same rotation, the shortest arc is still taken
+        assert!((a.slerp(-b, 0.5).angle() - FRAC_PI_2 / 2.0).abs() < 1e-5);
+        assert_eq!(b.slerp(b, 0.3), b.normalize());
+    }
+
+    #[test]
+    fn double() {
+        let axis = f64x3::with_xyz(0.0, 1.0, 0.0);
+        let q = f64quat::with_angle(std::f64::consts::FRAC_PI_2, axis);
+        let v = q.act(f64x3::with_xyz(0.0, 0.0, 1.0));
+        assert!((v.x() - 1.0).abs() < 1e-15 && v.z().abs() < 1e-15);
+        let q32 = f32quat::with_angle(FRAC_PI_2, f32x3::with_xyz(0.0, 1.0, 0.0));
+        assert!((f64quat::from(q32).dot(q) - 1.0).abs() < 1e-7);
+    }
+}
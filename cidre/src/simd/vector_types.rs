@@ -7,6 +7,9 @@ macro_rules! accessors {
             self.0[0]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `x_unchecked` has the lane.
         #[inline]
         pub unsafe fn x_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(0) }
@@ -22,6 +25,9 @@ macro_rules! accessors {
             self.0[0]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `r_unchecked` has the lane.
         #[inline]
         pub unsafe fn r_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(0) }
@@ -38,6 +44,9 @@ macro_rules! accessors {
             self.0[1]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `y_unchecked` has the lane.
         #[inline]
         pub unsafe fn y_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(1) }
@@ -53,6 +62,9 @@ macro_rules! accessors {
             self.0[1]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `g_unchecked` has the lane.
         #[inline]
         pub unsafe fn g_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(1) }
@@ -69,6 +81,9 @@ macro_rules! accessors {
             self.0[2]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `z_unchecked` has the lane.
         #[inline]
         pub unsafe fn z_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(2) }
@@ -84,6 +99,9 @@ macro_rules! accessors {
             self.0[2]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `b_unchecked` has the lane.
         #[inline]
         pub unsafe fn b_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(2) }
@@ -100,6 +118,9 @@ macro_rules! accessors {
             self.0[3]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `w_unchecked` has the lane.
         #[inline]
         pub unsafe fn w_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(3) }
@@ -115,6 +136,9 @@ macro_rules! accessors {
             self.0[3]
         }
 
+        /// # Safety
+        ///
+        /// Skips the bounds check, every vector with `a_unchecked` has the lane.
         #[inline]
         pub unsafe fn a_unchecked(&self) -> &T {
             unsafe { self.0.get_unchecked(3) }
@@ -261,9 +285,348 @@ impl Simd<half::f16, 4, 4> {
     }
 }
 
+impl<T: Copy + Default> Simd<T, 4, 3> {
+    /// Four component vector with `w` appended
+    #[inline]
+    pub fn with_w(&self, w: T) -> Simd<T, 4, 4> {
+        Simd([self.0[0], self.0[1], self.0[2], w])
+    }
+}
+
+impl<T: Copy + Default, const LANES: usize, const N: usize> From<[T; N]> for Simd<T, LANES, N> {
+    #[inline]
+    fn from(value: [T; N]) -> Self {
+        const { assert!(N <= LANES) };
+        let mut res = Self::default();
+        res.0[..N].copy_from_slice(&value);
+        res
+    }
+}
+
+impl<T: Copy, const LANES: usize, const N: usize> From<Simd<T, LANES, N>> for [T; N] {
+    #[inline]
+    fn from(value: Simd<T, LANES, N>) -> Self {
+        std::array::from_fn(|i| value.0[i])
+    }
+}
+
+/// Drops w
+impl<T: Copy + Default> From<Simd<T, 4, 4>> for Simd<T, 4, 3> {
+    #[inline]
+    fn from(value: Simd<T, 4, 4>) -> Self {
+        Self([value.0[0], value.0[1], value.0[2], T::default()])
+    }
+}
+
+/// Sets w to zero
+impl<T: Copy + Default> From<Simd<T, 4, 3>> for Simd<T, 4, 4> {
+    #[inline]
+    fn from(value: Simd<T, 4, 3>) -> Self {
+        value.with_w(T::default())
+    }
+}
+
+impl<T: Copy, const LANES: usize, const N: usize> Simd<T, LANES, N> {
+    /// Applies `f` to the first `N` lanes, padding lanes keep their values
+    #[inline]
+    pub fn map(self, f: impl Fn(T) -> T) -> Self {
+        let mut res = self;
+        for v in res.0[..N].iter_mut() {
+            *v = f(*v);
+        }
+        res
+    }
+
+    /// Applies `f` to the first `N` lanes of both vectors, padding lanes are taken from `self`
+    #[inline]
+    pub fn zip(self, other: Self, f: impl Fn(T, T) -> T) -> Self {
+        let mut res = self;
+        for (v, o) in res.0[..N].iter_mut().zip(other.0) {
+            *v = f(*v, o);
+        }
+        res
+    }
+
+    #[inline]
+    fn mask(self, other: Self, f: impl Fn(T, T) -> bool) -> Mask<LANES, N> {
+        let mut res = Simd([false; LANES]);
+        for i in 0..N {
+            res.0[i] = f(self.0[i], other.0[i]);
+        }
+        res
+    }
+
+    /// Lanes of `a` where `mask` is set, lanes of `b` otherwise, `simd_select`
+    #[inline]
+    pub fn select(mask: Mask<LANES, N>, a: Self, b: Self) -> Self {
+        let mut res = b;
+        for i in 0..N {
+            if mask.0[i] {
+                res.0[i] = a.0[i];
+            }
+        }
+        res
+    }
+}
+
+/// Result of lane-wise comparisons, padding lanes are always `false`
+pub type Mask<const LANES: usize, const N: usize> = Simd<bool, LANES, N>;
+
+impl<const LANES: usize, const N: usize> Simd<bool, LANES, N> {
+    /// Any of the first `N` lanes is set
+    #[inline]
+    pub fn any(&self) -> bool {
+        self.0[..N].iter().any(|v| *v)
+    }
+
+    /// All of the first `N` lanes are set
+    #[inline]
+    pub fn all(&self) -> bool {
+        self.0[..N].iter().all(|v| *v)
+    }
+}
+
+impl<T: Copy + PartialEq, const LANES: usize, const N: usize> Simd<T, LANES, N> {
+    #[inline]
+    pub fn simd_eq(self, other: Self) -> Mask<LANES, N> {
+        self.mask(other, |a, b| a == b)
+    }
+
+    #[inline]
+    pub fn simd_ne(self, other: Self) -> Mask<LANES, N> {
+        self.mask(other, |a, b| a != b)
+    }
+}
+
+impl<T: Copy + PartialOrd, const LANES: usize, const N: usize> Simd<T, LANES, N> {
+    #[inline]
+    pub fn simd_lt(self, other: Self) -> Mask<LANES, N> {
+        self.mask(other, |a, b| a < b)
+    }
+
+    #[inline]
+    pub fn simd_le(self, other: Self) -> Mask<LANES, N> {
+        self.mask(other, |a, b| a <= b)
+    }
+
+    #[inline]
+    pub fn simd_gt(self, other: Self) -> Mask<LANES, N> {
+        self.mask(other, |a, b| a > b)
+    }
+
+    #[inline]
+    pub fn simd_ge(self, other: Self) -> Mask<LANES, N> {
+        self.mask(other, |a, b| a >= b)
+    }
+
+    /// Lane-wise minimum
+    #[inline]
+    pub fn min(self, other: Self) -> Self {
+        self.zip(other, |a, b| if b < a { b } else { a })
+    }
+
+    /// Lane-wise maximum
+    #[inline]
+    pub fn max(self, other: Self) -> Self {
+        self.zip(other, |a, b| if b > a { b } else { a })
+    }
+
+    /// Lane-wise clamp
+    #[inline]
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        self.max(min).min(max)
+    }
+}
+
+/// Lane-wise vector and vector operators
+macro_rules! vector_op {
+    ($op:ident, $f:ident, $op_assign:ident, $f_assign:ident) => {
+        impl<T, const LANES: usize, const N: usize> std::ops::$op for Simd<T, LANES, N>
+        where
+            T: Copy + std::ops::$op<Output = T>,
+        {
+            type Output = Self;
+
+            #[inline]
+            fn $f(self, rhs: Self) -> Self {
+                self.zip(rhs, std::ops::$op::$f)
+            }
+        }
+
+        impl<T, const LANES: usize, const N: usize> std::ops::$op_assign for Simd<T, LANES, N>
+        where
+            T: Copy + std::ops::$op<Output = T>,
+        {
+            #[inline]
+            fn $f_assign(&mut self, rhs: Self) {
+                *self = std::ops::$op::$f(*self, rhs)
+            }
+        }
+    };
+}
+
+vector_op!(Add, add, AddAssign, add_assign);
+vector_op!(Sub, sub, SubAssign, sub_assign);
+vector_op!(Mul, mul, MulAssign, mul_assign);
+vector_op!(Div, div, DivAssign, div_assign);
+vector_op!(Rem, rem, RemAssign, rem_assign);
+vector_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+vector_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+vector_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl<T, const LANES: usize, const N: usize> std::ops::Neg for Simd<T, LANES, N>
+where
+    T: Copy + std::ops::Neg<Output = T>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        self.map(std::ops::Neg::neg)
+    }
+}
+
+impl<T, const LANES: usize, const N: usize> std::ops::Not for Simd<T, LANES, N>
+where
+    T: Copy + std::ops::Not<Output = T>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        self.map(std::ops::Not::not)
+    }
+}
+
+/// Vector and scalar operators in both orders
+macro_rules! scalar_ops {
+    ($($t:ty),*) => {
+        $(
+            scalar_ops!(@op $t, Add, add, AddAssign, add_assign);
+            scalar_ops!(@op $t, Sub, sub, SubAssign, sub_assign);
+            scalar_ops!(@op $t, Mul, mul, MulAssign, mul_assign);
+            scalar_ops!(@op $t, Div, div, DivAssign, div_assign);
+            scalar_ops!(@op $t, Rem, rem, RemAssign, rem_assign);
+        )*
+    };
+    (@op $t:ty, $op:ident, $f:ident, $op_assign:ident, $f_assign:ident) => {
+        impl<const LANES: usize, const N: usize> std::ops::$op<$t> for Simd<$t, LANES, N> {
+            type Output = Self;
+
+            #[inline]
+            fn $f(self, rhs: $t) -> Self {
+                self.map(|v| std::ops::$op::$f(v, rhs))
+            }
+        }
+
+        impl<const LANES: usize, const N: usize> std::ops::$op<Simd<$t, LANES, N>> for $t {
+            type Output = Simd<$t, LANES, N>;
+
+            #[inline]
+            fn $f(self, rhs: Simd<$t, LANES, N>) -> Simd<$t, LANES, N> {
+                rhs.map(|v| std::ops::$op::$f(self, v))
+            }
+        }
+
+        impl<const LANES: usize, const N: usize> std::ops::$op_assign<$t> for Simd<$t, LANES, N> {
+            #[inline]
+            fn $f_assign(&mut self, rhs: $t) {
+                *self = std::ops::$op::$f(*self, rhs)
+            }
+        }
+    };
+}
+
+scalar_ops!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+#[cfg(feature = "half")]
+scalar_ops!(half::f16);
+
+/// Geometric functions of floating point vectors
+macro_rules! float_ops {
+    ($t:ty, $zero:expr, $sqrt:expr) => {
+        impl<const LANES: usize, const N: usize> Simd<$t, LANES, N> {
+            #[inline]
+            pub fn dot(self, other: Self) -> $t {
+                let mut res = $zero;
+                for i in 0..N {
+                    res += self.0[i] * other.0[i];
+                }
+                res
+            }
+
+            #[inline]
+            pub fn length_squared(self) -> $t {
+                self.dot(self)
+            }
+
+            #[inline]
+            pub fn length(self) -> $t {
+                $sqrt(self.length_squared())
+            }
+
+            #[inline]
+            pub fn distance(self, other: Self) -> $t {
+                (self - other).length()
+            }
+
+            /// Vector of the same direction and unit length
+            #[inline]
+            pub fn normalize(self) -> Self {
+                self / self.length()
+            }
+
+            /// `self` for t = 0 and `other` for t = 1
+            #[inline]
+            pub fn lerp(self, other: Self, t: $t) -> Self {
+                self + (other - self) * t
+            }
+
+            #[inline]
+            pub fn abs(self) -> Self {
+                self.map(|v| if v < $zero { -v } else { v })
+            }
+        }
+
+        impl Simd<$t, 4, 3> {
+            #[inline]
+            pub fn cross(self, other: Self) -> Self {
+                let ([ax, ay, az, _], [bx, by, bz, _]) = (self.0, other.0);
+                Self::with_xyz(ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx)
+            }
+        }
+    };
+}
+
+float_ops!(f32, 0.0, f32::sqrt);
+float_ops!(f64, 0.0, f64::sqrt);
+
+#[cfg(feature = "half")]
+float_ops!(half::f16, half::f16::ZERO, |v: half::f16| {
+    half::f16::from_f32(v.to_f32().sqrt())
+});
+
+#[cfg(feature = "half")]
+impl<const LANES: usize, const N: usize> From<Simd<half::f16, LANES, N>> for Simd<f32, LANES, N> {
+    #[inline]
+    fn from(value: Simd<half::f16, LANES, N>) -> Self {
+        Simd(value.0.map(half::f16::to_f32))
+    }
+}
+
+#[cfg(feature = "half")]
+impl<const LANES: usize, const N: usize> From<Simd<f32, LANES, N>> for Simd<half::f16, LANES, N> {
+    #[inline]
+    fn from(value: Simd<f32, LANES, N>) -> Self {
+        Simd(value.0.map(half::f16::from_f32))
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
-    use crate::simd::{f32x2, f32x4x4};
+    use crate::simd::{Mask, Simd, f32x2, f32x3, f32x4, f32x4x4, f64x4, i16x4, u8x2, u8x4};
+    #[cfg(feature = "half")]
+    use crate::simd::{f16x3, f16x4};
 
     #[test]
     fn basics() {
@@ -284,4 +647,151 @@ pub mod tests {
 
         assert_eq!(f, f32x4x4::translate(2.0, 3.0, 4.0));
     }
+
+    #[test]
+    fn ops() {
+        let a = f32x3::with_xyz(1.0, 2.0, 3.0);
+        let b = f32x3::with_xyz(4.0, 5.0, 6.0);
+        assert_eq!(a + b, f32x3::with_xyz(5.0, 7.0, 9.0));
+        assert_eq!(b - a, f32x3::with_xyz(3.0, 3.0, 3.0));
+        assert_eq!(a * b, f32x3::with_xyz(4.0, 10.0, 18.0));
+        assert_eq!(-a, f32x3::with_xyz(-1.0, -2.0, -3.0));
+        assert_eq!(a * 2.0, 2.0 * a);
+        assert_eq!(1.0 - a, f32x3::with_xyz(0.0, -1.0, -2.0));
+
+        // padding lane is left alone, no NaN from 0 / 0
+        let q = a / f32x3::default();
+        assert_eq!(q.0[3], 0.0);
+        assert!(q.x().is_infinite());
+        assert_eq!((a / 2.0).0[3], 0.0);
+
+        let mut c = a;
+        c += b;
+        c -= 1.0;
+        c *= 2.0;
+        c /= f32x3::with_xyz(2.0, 2.0, 2.0);
+        assert_eq!(c, f32x3::with_xyz(4.0, 6.0, 8.0));
+
+        let i = i16x4::with_xyzw(1, 2, 3, 4);
+        assert_eq!(i % 2, i16x4::with_xyzw(1, 0, 1, 0));
+        assert_eq!(
+            !u8x4::default() & u8x4::from([0x0f; 4]),
+            u8x4::with_xyzw(15, 15, 15, 15)
+        );
+    }
+
+    #[test]
+    fn geometry() {
+        let x = f32x3::with_xyz(1.0, 0.0, 0.0);
+        let y = f32x3::with_xyz(0.0, 1.0, 0.0);
+        assert_eq!(x.cross(y), f32x3::with_xyz(0.0, 0.0, 1.0));
+        assert_eq!(x.dot(y), 0.0);
+
+        let v = f32x3::with_xyz(3.0, 0.0, 4.0);
+        assert_eq!(v.length_squared(), 25.0);
+        assert_eq!(v.length(), 5.0);
+        assert_eq!(v.normalize(), f32x3::with_xyz(0.6, 0.0, 0.8));
+        assert_eq!(v.distance(x), (4.0f32 + 16.0).sqrt());
+        assert_eq!(x.lerp(y, 0.25), f32x3::with_xyz(0.75, 0.25, 0.0));
+        assert_eq!((-v).abs(), v);
+
+        let lo = f32x3::with_xyz(0.0, 1.0, 2.0);
+        let hi = f32x3::with_xyz(1.0, 2.0, 3.0);
+        assert_eq!(v.clamp(lo, hi), f32x3::with_xyz(1.0, 1.0, 3.0));
+        assert_eq!(v.min(hi), f32x3::with_xyz(1.0, 0.0, 3.0));
+        assert_eq!(v.max(lo), f32x3::with_xyz(3.0, 1.0, 4.0));
+
+        let d = f64x4::with_xyzw(1.0, 1.0, 1.0, 1.0);
+        assert_eq!(d.length(), 2.0);
+    }
+
+    #[test]
+    fn masks() {
+        let a = f32x4::with_xyzw(1.0, 2.0, 3.0, 4.0);
+        let b = f32x4::with_xyzw(4.0, 2.0, 3.0, 1.0);
+        let lt = a.simd_lt(b);
+        assert_eq!(lt, Mask::from([true, false, false, false]));
+        assert!(lt.any());
+        assert!(!lt.all());
+        assert!(a.simd_le(a).all());
+        assert!(!a.simd_ne(a).any());
+        assert_eq!(a.simd_eq(b), !a.simd_ne(b));
+        assert_eq!(a.simd_gt(b), b.simd_lt(a));
+        assert_eq!(a.simd_ge(b), Mask::from([false, true, true, true]));
+        assert_eq!(
+            f32x4::select(lt, a, b),
+            f32x4::with_xyzw(1.0, 2.0, 3.0, 1.0)
+        );
+
+        // padding never takes part in a comparison
+        let x = f32x3::with_xyz(1.0, 1.0, 1.0);
+        assert!(x.simd_eq(Simd([1.0, 1.0, 1.0, 5.0])).all());
+
+        // equality needs no order
+        #[derive(Clone, Copy, PartialEq)]
+        struct Id(u8);
+        let ids = Simd::<Id, 2, 2>([Id(1), Id(2)]);
+        assert_eq!(ids.simd_eq(Simd([Id(1), Id(1)])), Mask::from([true, false]));
+    }
+
+    #[test]
+    fn conversions() {
+        let v = f32x4::from([1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(<[f32; 4]>::from(v), [1.0, 2.0, 3.0, 4.0]);
+
+        let v3 = f32x3::from(v);
+        assert_eq!(v3, f32x3::with_xyz(1.0, 2.0, 3.0));
+        assert_eq!(v3.0[3], 0.0);
+        assert_eq!(<[f32; 3]>::from(v3), [1.0, 2.0, 3.0]);
+        assert_eq!(f32x3::from([1.0, 2.0, 3.0]), v3);
+        assert_eq!(f32x4::from(v3), f32x4::with_xyzw(1.0, 2.0, 3.0, 0.0));
+        assert_eq!(v3.with_w(4.0), v);
+    }
+
+    #[test]
+    fn swizzles() {
+        let v = f32x4::with_xyzw(1.0, 2.0, 3.0, 4.0);
+        assert_eq!(v.xyz(), Simd([1.0, 2.0, 3.0]));
+        let xyz = f32x3::from(v);
+        assert_eq!(v.xzy().xzy(), xyz);
+        assert_eq!(v.zyx(), f32x3::with_xyz(3.0, 2.0, 1.0));
+        assert_eq!(v.wx(), f32x2::with_xy(4.0, 1.0));
+        assert_eq!(v.xxyy(), f32x4::with_xyzw(1.0, 1.0, 2.0, 2.0));
+        assert_eq!(v.wzyx(), f32x4::with_xyzw(4.0, 3.0, 2.0, 1.0));
+
+        assert_eq!(xyz.xy(), f32x2::with_xy(1.0, 2.0));
+        assert_eq!(xyz.zzzz(), f32x4::with_xyzw(3.0, 3.0, 3.0, 3.0));
+        assert_eq!(
+            f32x2::with_xy(1.0, 2.0).yxy(),
+            f32x3::with_xyz(2.0, 1.0, 2.0)
+        );
+        assert_eq!(u8x4::with_xyzw(1, 2, 3, 4).yw(), u8x2::with_xy(2, 4));
+
+        // only three component swizzles need a padding value
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        struct Id(u8);
+        let ids = Simd::<Id, 4, 4>([Id(1), Id(2), Id(3), Id(4)]);
+        assert_eq!(ids.xy(), Simd([Id(1), Id(2)]));
+        assert_eq!(ids.wzyx().0, [Id(4), Id(3), Id(2), Id(1)]);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn half() {
+        use half::f16;
+
+        let a = f16x3::from(f32x3::with_xyz(3.0, 0.0, 4.0));
+        assert_eq!(a.length(), f16::from_f32(5.0));
+        assert_eq!(
+            a * f16::from_f32(2.0),
+            f16x3::from(f32x3::with_xyz(6.0, 0.0, 8.0))
+        );
+        assert_eq!(
+            f32x3::from(a.normalize()),
+            f32x3::with_xyz(0.6, 0.0, 0.8).map(|v| { f16::from_f32(v).to_f32() })
+        );
+        let b = f16x4::from(f32x4::with_xyzw(1.0, 2.0, 3.0, 4.0));
+        assert_eq!(f32x4::from(b.wzyx()), f32x4::with_xyzw(4.0, 3.0, 2.0, 1.0));
+        assert!(b.simd_lt(b + f16::ONE).all());
+    }
 }
@@ -1,5 +1,27 @@
 use std::ptr::NonNull;
 
+#[cfg(any(test, not(target_vendor = "apple")))]
+mod portable;
+
+#[cfg(not(target_vendor = "apple"))]
+pub(crate) use portable::*;
+
+pub mod window;
+pub use window::Window;
+
+pub mod stft;
+pub use stft::Frame as StftFrame;
+pub use stft::Stft;
+
+pub mod filter_bank;
+pub use filter_bank::FilterBank;
+
+pub mod filter;
+pub use filter::Biquad;
+pub use filter::BiquadCoefs;
+pub use filter::Decimator;
+pub use filter::Fir;
+
 #[doc(alias = "vDSP_Length")]
 pub type Len = usize;
 
@@ -138,9 +160,10 @@ impl FftVt<f64> {
 pub struct Fft<T>(NonNull<FftSetup<T>>, FftVt<T>);
 
 impl<T> Fft<T> {
+    /// In place real FFT of `2 * re_io.len()` samples packed in split arrays
     #[inline]
     pub fn zr_io(&mut self, re_io: &mut [T], im_io: &mut [T], direction: FftDirection) {
-        let log2n = (re_io.len() as f64).log2().ceil();
+        let log2n = (re_io.len() as f64).log2().ceil() + 1.0;
         let mut split = SplitComplex::new_mut(re_io, im_io);
         unsafe { (self.1.zrip)(self.0.as_mut(), &mut split, 1, log2n as _, direction) }
     }
@@ -210,6 +233,11 @@ impl<T> Drop for Fft<T> {
     }
 }
 
+#[doc(alias = "vDSP_biquad_SetupStruct")]
+#[doc(alias = "vDSP_biquad_SetupStructD")]
+#[repr(transparent)]
+pub struct BiquadSetup<T>(std::ffi::c_void, std::marker::PhantomData<T>);
+
 /// Vector add
 ///
 /// ```pseudo C
@@ -1269,6 +1297,294 @@ pub fn u16_f32(a: &[u16], c: &mut [f32]) {
     unsafe { _u16_f32(a.as_ptr(), 1, c.as_mut_ptr(), 1, n) };
 }
 
+/// Complex-split magnitudes squared
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = A->realp[n]**2 + A->imagp[n]**2;
+/// ```
+#[doc(alias = "vDSP_zvmags")]
+#[inline]
+pub fn zmags_f32(re: &[f32], im: &[f32], c: &mut [f32]) {
+    let n = re.len();
+    assert_eq!(n, im.len());
+    assert_eq!(n, c.len());
+    let split = SplitComplex::new(re, im);
+    unsafe { _zmags_f32(&split, 1, c.as_mut_ptr(), 1, n) }
+}
+
+/// Complex-split magnitudes squared
+#[doc(alias = "vDSP_zvmagsD")]
+#[inline]
+pub fn zmags_f64(re: &[f64], im: &[f64], c: &mut [f64]) {
+    let n = re.len();
+    assert_eq!(n, im.len());
+    assert_eq!(n, c.len());
+    let split = SplitComplex::new(re, im);
+    unsafe { _zmags_f64(&split, 1, c.as_mut_ptr(), 1, n) }
+}
+
+/// Complex-split absolute values
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = sqrt(A->realp[n]**2 + A->imagp[n]**2);
+/// ```
+#[doc(alias = "vDSP_zvabs")]
+#[inline]
+pub fn zabs_f32(re: &[f32], im: &[f32], c: &mut [f32]) {
+    let n = re.len();
+    assert_eq!(n, im.len());
+    assert_eq!(n, c.len());
+    let split = SplitComplex::new(re, im);
+    unsafe { _zabs_f32(&split, 1, c.as_mut_ptr(), 1, n) }
+}
+
+/// Complex-split absolute values
+#[doc(alias = "vDSP_zvabsD")]
+#[inline]
+pub fn zabs_f64(re: &[f64], im: &[f64], c: &mut [f64]) {
+    let n = re.len();
+    assert_eq!(n, im.len());
+    assert_eq!(n, c.len());
+    let split = SplitComplex::new(re, im);
+    unsafe { _zabs_f64(&split, 1, c.as_mut_ptr(), 1, n) }
+}
+
+/// What [`dbcon_f32`] converts
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+#[repr(u32)]
+pub enum DbKind {
+    /// 10 * log10(A / B)
+    Power = 0,
+    /// 20 * log10(A / B)
+    Amplitude = 1,
+}
+
+/// Vector convert power or amplitude to decibels
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// let mut db = [0.0f32; 3];
+/// vdsp::dbcon_f32(&[1.0, 10.0, 100.0], &1.0, vdsp::DbKind::Power, &mut db);
+/// assert_eq!(db, [0.0, 10.0, 20.0]);
+/// ```
+#[doc(alias = "vDSP_vdbcon")]
+#[inline]
+pub fn dbcon_f32(a: &[f32], zero_ref: &f32, kind: DbKind, c: &mut [f32]) {
+    let n = a.len();
+    assert_eq!(n, c.len());
+    unsafe { _dbcon_f32(a.as_ptr(), 1, zero_ref, c.as_mut_ptr(), 1, n, kind as u32) };
+}
+
+/// Vector convert power or amplitude to decibels
+#[doc(alias = "vDSP_vdbconD")]
+#[inline]
+pub fn dbcon_f64(a: &[f64], zero_ref: &f64, kind: DbKind, c: &mut [f64]) {
+    let n = a.len();
+    assert_eq!(n, c.len());
+    unsafe { _dbcon_f64(a.as_ptr(), 1, zero_ref, c.as_mut_ptr(), 1, n, kind as u32) };
+}
+
+/// Vector clip
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     D[n] = MIN(MAX(A[n], B), C);
+/// ```
+#[doc(alias = "vDSP_vclip")]
+#[inline]
+pub fn clip_f32(a: &[f32], low: &f32, high: &f32, d: &mut [f32]) {
+    let n = a.len();
+    assert_eq!(n, d.len());
+    unsafe { _clip_f32(a.as_ptr(), 1, low, high, d.as_mut_ptr(), 1, n) };
+}
+
+/// Vector clip
+#[doc(alias = "vDSP_vclipD")]
+#[inline]
+pub fn clip_f64(a: &[f64], low: &f64, high: &f64, d: &mut [f64]) {
+    let n = a.len();
+    assert_eq!(n, d.len());
+    unsafe { _clip_f64(a.as_ptr(), 1, low, high, d.as_mut_ptr(), 1, n) };
+}
+
+#[doc(alias = "vDSP_HANN_DENORM")]
+#[doc(alias = "vDSP_HANN_NORM")]
+#[doc(alias = "vDSP_HALF_WINDOW")]
+#[derive(Debug, Default, Eq, PartialEq, Copy, Clone)]
+#[repr(transparent)]
+pub struct WindowFlags(pub i32);
+
+impl WindowFlags {
+    /// Full, not normalized window
+    pub const DEFAULT: Self = Self(0);
+
+    #[doc(alias = "vDSP_HANN_DENORM")]
+    pub const HANN_DENORM: Self = Self(0);
+
+    /// Only the first (N + 1) / 2 values are computed
+    #[doc(alias = "vDSP_HALF_WINDOW")]
+    pub const HALF_WINDOW: Self = Self(1);
+
+    /// Hann window scaled so that its RMS is 1
+    #[doc(alias = "vDSP_HANN_NORM")]
+    pub const HANN_NORM: Self = Self(2);
+}
+
+impl std::ops::BitOr for WindowFlags {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Periodic Hann window
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = .5 * (1 - cos(2 * pi * n / N));
+/// ```
+#[doc(alias = "vDSP_hann_window")]
+#[inline]
+pub fn hann_window_f32(c: &mut [f32], flags: WindowFlags) {
+    unsafe { _hann_window_f32(c.as_mut_ptr(), c.len(), flags) }
+}
+
+/// Periodic Hann window
+#[doc(alias = "vDSP_hann_windowD")]
+#[inline]
+pub fn hann_window_f64(c: &mut [f64], flags: WindowFlags) {
+    unsafe { _hann_window_f64(c.as_mut_ptr(), c.len(), flags) }
+}
+
+/// Periodic Hamming window
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = .54 - .46 * cos(2 * pi * n / N);
+/// ```
+#[doc(alias = "vDSP_hamm_window")]
+#[inline]
+pub fn hamm_window_f32(c: &mut [f32], flags: WindowFlags) {
+    unsafe { _hamm_window_f32(c.as_mut_ptr(), c.len(), flags) }
+}
+
+/// Periodic Hamming window
+#[doc(alias = "vDSP_hamm_windowD")]
+#[inline]
+pub fn hamm_window_f64(c: &mut [f64], flags: WindowFlags) {
+    unsafe { _hamm_window_f64(c.as_mut_ptr(), c.len(), flags) }
+}
+
+/// Periodic Blackman window
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = .42 - .5 * cos(2 * pi * n / N) + .08 * cos(4 * pi * n / N);
+/// ```
+#[doc(alias = "vDSP_blkman_window")]
+#[inline]
+pub fn blkman_window_f32(c: &mut [f32], flags: WindowFlags) {
+    unsafe { _blkman_window_f32(c.as_mut_ptr(), c.len(), flags) }
+}
+
+/// Periodic Blackman window
+#[doc(alias = "vDSP_blkman_windowD")]
+#[inline]
+pub fn blkman_window_f64(c: &mut [f64], flags: WindowFlags) {
+    unsafe { _blkman_window_f64(c.as_mut_ptr(), c.len(), flags) }
+}
+
+/// Correlation of `a` with the filter `f`
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = sum(A[n + p] * F[p], 0 <= p < P);
+/// ```
+/// `a` has to hold at least `c.len() + f.len() - 1` elements.
+#[doc(alias = "vDSP_conv")]
+#[inline]
+pub fn corr_f32(a: &[f32], f: &[f32], c: &mut [f32]) {
+    let (n, p) = (c.len(), f.len());
+    assert!(p > 0 && a.len() + 1 >= n + p);
+    unsafe { _conv_f32(a.as_ptr(), 1, f.as_ptr(), 1, c.as_mut_ptr(), 1, n, p) }
+}
+
+/// Correlation of `a` with the filter `f`
+#[doc(alias = "vDSP_convD")]
+#[inline]
+pub fn corr_f64(a: &[f64], f: &[f64], c: &mut [f64]) {
+    let (n, p) = (c.len(), f.len());
+    assert!(p > 0 && a.len() + 1 >= n + p);
+    unsafe { _conv_f64(a.as_ptr(), 1, f.as_ptr(), 1, c.as_mut_ptr(), 1, n, p) }
+}
+
+/// Convolution of `a` with the filter `f`, only outputs where the filter fully overlaps `a`
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = sum(A[n + p] * F[P - 1 - p], 0 <= p < P);
+/// ```
+/// `a` has to hold at least `c.len() + f.len() - 1` elements.
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// let mut c = [0.0f32; 3];
+/// vdsp::conv_f32(&[1.0, 2.0, 3.0, 4.0], &[1.0, 10.0], &mut c);
+/// assert_eq!(c, [12.0, 23.0, 34.0]);
+/// ```
+#[doc(alias = "vDSP_conv")]
+#[inline]
+pub fn conv_f32(a: &[f32], f: &[f32], c: &mut [f32]) {
+    let (n, p) = (c.len(), f.len());
+    assert!(p > 0 && a.len() + 1 >= n + p);
+    // negative filter stride starting at the last tap turns correlation into convolution
+    let last = f[p - 1..].as_ptr();
+    unsafe { _conv_f32(a.as_ptr(), 1, last, -1, c.as_mut_ptr(), 1, n, p) }
+}
+
+/// Convolution of `a` with the filter `f`, only outputs where the filter fully overlaps `a`
+#[doc(alias = "vDSP_convD")]
+#[inline]
+pub fn conv_f64(a: &[f64], f: &[f64], c: &mut [f64]) {
+    let (n, p) = (c.len(), f.len());
+    assert!(p > 0 && a.len() + 1 >= n + p);
+    let last = f[p - 1..].as_ptr();
+    unsafe { _conv_f64(a.as_ptr(), 1, last, -1, c.as_mut_ptr(), 1, n, p) }
+}
+
+/// FIR filter and decimation
+///
+/// ```pseudo C
+/// for (n = 0; n < N; ++n)
+///     C[n] = sum(A[n * DF + p] * F[p], 0 <= p < P);
+/// ```
+/// `a` has to hold at least `factor * (c.len() - 1) + f.len()` elements.
+#[doc(alias = "vDSP_desamp")]
+#[inline]
+pub fn desamp_f32(a: &[f32], factor: usize, f: &[f32], c: &mut [f32]) {
+    let (n, p) = (c.len(), f.len());
+    assert!(factor > 0 && p > 0);
+    assert!(n == 0 || a.len() >= factor * (n - 1) + p);
+    unsafe { _desamp_f32(a.as_ptr(), factor as _, f.as_ptr(), c.as_mut_ptr(), n, p) }
+}
+
+/// FIR filter and decimation
+#[doc(alias = "vDSP_desampD")]
+#[inline]
+pub fn desamp_f64(a: &[f64], factor: usize, f: &[f64], c: &mut [f64]) {
+    let (n, p) = (c.len(), f.len());
+    assert!(factor > 0 && p > 0);
+    assert!(n == 0 || a.len() >= factor * (n - 1) + p);
+    unsafe { _desamp_f64(a.as_ptr(), factor as _, f.as_ptr(), c.as_mut_ptr(), n, p) }
+}
+
+#[cfg(target_vendor = "apple")]
 #[link(name = "Accelerate", kind = "framework")]
 unsafe extern "C-unwind" {
     #[link_name = "vDSP_vadd"]
@@ -2028,6 +2344,164 @@ unsafe extern "C-unwind" {
         __ID: Stride,
         __N: Len,
     );
+
+    #[doc(alias = "vDSP_zvmags")]
+    #[link_name = "vDSP_zvmags"]
+    pub fn _zmags_f32(
+        __A: *const SplitComplex<f32>,
+        __IA: Stride,
+        __C: *mut f32,
+        __IC: Stride,
+        __N: Len,
+    );
+
+    #[doc(alias = "vDSP_zvmagsD")]
+    #[link_name = "vDSP_zvmagsD"]
+    pub fn _zmags_f64(
+        __A: *const SplitComplex<f64>,
+        __IA: Stride,
+        __C: *mut f64,
+        __IC: Stride,
+        __N: Len,
+    );
+
+    #[doc(alias = "vDSP_zvabs")]
+    #[link_name = "vDSP_zvabs"]
+    pub fn _zabs_f32(
+        __A: *const SplitComplex<f32>,
+        __IA: Stride,
+        __C: *mut f32,
+        __IC: Stride,
+        __N: Len,
+    );
+
+    #[doc(alias = "vDSP_zvabsD")]
+    #[link_name = "vDSP_zvabsD"]
+    pub fn _zabs_f64(
+        __A: *const SplitComplex<f64>,
+        __IA: Stride,
+        __C: *mut f64,
+        __IC: Stride,
+        __N: Len,
+    );
+
+    #[doc(alias = "vDSP_hann_window")]
+    #[link_name = "vDSP_hann_window"]
+    pub fn _hann_window_f32(__C: *mut f32, __N: Len, __Flag: WindowFlags);
+
+    #[doc(alias = "vDSP_hann_windowD")]
+    #[link_name = "vDSP_hann_windowD"]
+    pub fn _hann_window_f64(__C: *mut f64, __N: Len, __Flag: WindowFlags);
+
+    #[doc(alias = "vDSP_hamm_window")]
+    #[link_name = "vDSP_hamm_window"]
+    pub fn _hamm_window_f32(__C: *mut f32, __N: Len, __Flag: WindowFlags);
+
+    #[doc(alias = "vDSP_hamm_windowD")]
+    #[link_name = "vDSP_hamm_windowD"]
+    pub fn _hamm_window_f64(__C: *mut f64, __N: Len, __Flag: WindowFlags);
+
+    #[doc(alias = "vDSP_blkman_window")]
+    #[link_name = "vDSP_blkman_window"]
+    pub fn _blkman_window_f32(__C: *mut f32, __N: Len, __Flag: WindowFlags);
+
+    #[doc(alias = "vDSP_blkman_windowD")]
+    #[link_name = "vDSP_blkman_windowD"]
+    pub fn _blkman_window_f64(__C: *mut f64, __N: Len, __Flag: WindowFlags);
+
+    #[doc(alias = "vDSP_conv")]
+    #[link_name = "vDSP_conv"]
+    pub fn _conv_f32(
+        __A: *const f32,
+        __IA: Stride,
+        __F: *const f32,
+        __IF: Stride,
+        __C: *mut f32,
+        __IC: Stride,
+        __N: Len,
+        __P: Len,
+    );
+
+    #[doc(alias = "vDSP_convD")]
+    #[link_name = "vDSP_convD"]
+    pub fn _conv_f64(
+        __A: *const f64,
+        __IA: Stride,
+        __F: *const f64,
+        __IF: Stride,
+        __C: *mut f64,
+        __IC: Stride,
+        __N: Len,
+        __P: Len,
+    );
+
+    #[doc(alias = "vDSP_desamp")]
+    #[link_name = "vDSP_desamp"]
+    pub fn _desamp_f32(
+        __A: *const f32,
+        __DF: Stride,
+        __F: *const f32,
+        __C: *mut f32,
+        __N: Len,
+        __P: Len,
+    );
+
+    #[doc(alias = "vDSP_desampD")]
+    #[link_name = "vDSP_desampD"]
+    pub fn _desamp_f64(
+        __A: *const f64,
+        __DF: Stride,
+        __F: *const f64,
+        __C: *mut f64,
+        __N: Len,
+        __P: Len,
+    );
+
+    #[doc(alias = "vDSP_biquad_CreateSetup")]
+    #[link_name = "vDSP_biquad_CreateSetup"]
+    pub fn _biquad_create_setup_f32(
+        __Coefficients: *const f64,
+        __M: Len,
+    ) -> Option<NonNull<BiquadSetup<f32>>>;
+
+    #[doc(alias = "vDSP_biquad_CreateSetupD")]
+    #[link_name = "vDSP_biquad_CreateSetupD"]
+    pub fn _biquad_create_setup_f64(
+        __Coefficients: *const f64,
+        __M: Len,
+    ) -> Option<NonNull<BiquadSetup<f64>>>;
+
+    #[doc(alias = "vDSP_biquad_DestroySetup")]
+    #[link_name = "vDSP_biquad_DestroySetup"]
+    pub fn _biquad_destroy_setup_f32(setup: *mut BiquadSetup<f32>);
+
+    #[doc(alias = "vDSP_biquad_DestroySetupD")]
+    #[link_name = "vDSP_biquad_DestroySetupD"]
+    pub fn _biquad_destroy_setup_f64(setup: *mut BiquadSetup<f64>);
+
+    #[doc(alias = "vDSP_biquad")]
+    #[link_name = "vDSP_biquad"]
+    pub fn _biquad_f32(
+        __Setup: *const BiquadSetup<f32>,
+        __Delay: *mut f32,
+        __X: *const f32,
+        __IX: Stride,
+        __Y: *mut f32,
+        __IY: Stride,
+        __N: Len,
+    );
+
+    #[doc(alias = "vDSP_biquadD")]
+    #[link_name = "vDSP_biquadD"]
+    pub fn _biquad_f64(
+        __Setup: *const BiquadSetup<f64>,
+        __Delay: *mut f64,
+        __X: *const f64,
+        __IX: Stride,
+        __Y: *mut f64,
+        __IY: Stride,
+        __N: Len,
+    );
 }
 
 #[cfg(test)]
@@ -2129,9 +2603,9 @@ mod tests {
     fn synth_signal(freq_amp_pairs: &[(f32, f32)], len: usize) -> Vec<f32> {
         let mut res = vec![0.0f32; len];
 
-        for i in 0..len {
+        for (i, v) in res.iter_mut().enumerate() {
             let n_index = (i as f32) / ((len - 1) as f32);
-            res[i] = freq_amp_pairs.iter().fold(0.0f32, |acc, pair| {
+            *v = freq_amp_pairs.iter().fold(0.0f32, |acc, pair| {
                 println!("{pair:?}");
                 let r = acc + (n_index + pair.0 * TAU).sin() * pair.1;
                 println!("{r:?}");
@@ -0,0 +1,691 @@
+use std::f64::consts::{FRAC_1_SQRT_2, PI, TAU};
+use std::ptr::NonNull;
+
+use crate::vdsp::{self, BiquadSetup, Len, Stride, Window};
+
+/// Coefficients of a second order section normalized by a0:
+///
+/// ```pseudo C
+/// y[n] = b0 * x[n] + b1 * x[n - 1] + b2 * x[n - 2] - a1 * y[n - 1] - a2 * y[n - 2];
+/// ```
+///
+/// Design helpers follow the RBJ audio EQ cookbook. `freq` and `sample_rate` are in Hz,
+/// `q` is the quality factor ([`BiquadCoefs::BUTTERWORTH_Q`] for a maximally flat response).
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+pub struct BiquadCoefs {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl Default for BiquadCoefs {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl BiquadCoefs {
+    /// Passes the signal unchanged
+    pub const IDENTITY: Self = Self {
+        b0: 1.0,
+        b1: 0.0,
+        b2: 0.0,
+        a1: 0.0,
+        a2: 0.0,
+    };
+
+    pub const BUTTERWORTH_Q: f64 = FRAC_1_SQRT_2;
+
+    #[inline]
+    fn normalized(b: [f64; 3], a: [f64; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+        }
+    }
+
+    /// cos(w0) and alpha of the cookbook
+    #[inline]
+    fn prewarp(freq: f64, q: f64, sample_rate: f64) -> (f64, f64) {
+        assert!(
+            freq > 0.0 && freq < sample_rate / 2.0,
+            "freq must be below Nyquist"
+        );
+        assert!(q > 0.0, "q must be positive");
+        let w0 = TAU * freq / sample_rate;
+        (w0.cos(), w0.sin() / (2.0 * q))
+    }
+
+    /// -3 dB at `freq` for the Butterworth q
+    pub fn low_pass(freq: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(freq, q, sample_rate);
+        let b1 = 1.0 - cos;
+        Self::normalized(
+            [b1 / 2.0, b1, b1 / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// -3 dB at `freq` for the Butterworth q
+    pub fn high_pass(freq: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(freq, q, sample_rate);
+        let b1 = 1.0 + cos;
+        Self::normalized(
+            [b1 / 2.0, -b1, b1 / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    /// Unity gain at `freq`, the bandwidth is `freq / q`
+    pub fn band_pass(freq: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(freq, q, sample_rate);
+        Self::normalized([alpha, 0.0, -alpha], [1.0 + alpha, -2.0 * cos, 1.0 - alpha])
+    }
+
+    /// Bell shaped boost or cut of `gain_db` around `freq`
+    pub fn peak(freq: f64, gain_db: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(freq, q, sample_rate);
+        let a = 10f64.powf(gain_db / 40.0);
+        Self::normalized(
+            [1.0 + alpha * a, -2.0 * cos, 1.0 - alpha * a],
+            [1.0 + alpha / a, -2.0 * cos, 1.0 - alpha / a],
+        )
+    }
+
+    /// Gain of `gain_db` below `freq`, unity gain above it
+    pub fn low_shelf(freq: f64, gain_db: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(freq, q, sample_rate);
+        let a = 10f64.powf(gain_db / 40.0);
+        let k = 2.0 * a.sqrt() * alpha;
+        Self::normalized(
+            [
+                a * ((a + 1.0) - (a - 1.0) * cos + k),
+                2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                a * ((a + 1.0) - (a - 1.0) * cos - k),
+            ],
+            [
+                (a + 1.0) + (a - 1.0) * cos + k,
+                -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                (a + 1.0) + (a - 1.0) * cos - k,
+            ],
+        )
+    }
+
+    /// Gain of `gain_db` above `freq`, unity gain below it
+    pub fn high_shelf(freq: f64, gain_db: f64, q: f64, sample_rate: f64) -> Self {
+        let (cos, alpha) = Self::prewarp(freq, q, sample_rate);
+        let a = 10f64.powf(gain_db / 40.0);
+        let k = 2.0 * a.sqrt() * alpha;
+        Self::normalized(
+            [
+                a * ((a + 1.0) + (a - 1.0) * cos + k),
+                -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                a * ((a + 1.0) + (a - 1.0) * cos - k),
+            ],
+            [
+                (a + 1.0) - (a - 1.0) * cos + k,
+                2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                (a + 1.0) - (a - 1.0) * cos - k,
+            ],
+        )
+    }
+
+    /// Magnitude of the frequency response at `freq` Hz
+    pub fn magnitude(&self, freq: f64, sample_rate: f64) -> f64 {
+        let w = TAU * freq / sample_rate;
+        // evaluates c0 + c1 z^-1 + c2 z^-2 on the unit circle
+        let eval = |c0: f64, c1: f64, c2: f64| {
+            let re = c0 + c1 * w.cos() + c2 * (2.0 * w).cos();
+            let im = -c1 * w.sin() - c2 * (2.0 * w).sin();
+            re.hypot(im)
+        };
+        eval(self.b0, self.b1, self.b2) / eval(1.0, self.a1, self.a2)
+    }
+}
+
+struct BiquadVt<T> {
+    apply: unsafe extern "C-unwind" fn(
+        *const BiquadSetup<T>,
+        *mut T,
+        *const T,
+        Stride,
+        *mut T,
+        Stride,
+        Len,
+    ),
+    destroy: unsafe extern "C-unwind" fn(*mut BiquadSetup<T>),
+}
+
+/// Cascade of second order sections applied to one or more channels.
+///
+/// The coefficients are shared, every channel keeps its own delay state
+/// between calls, so signals can be processed in chunks of any size.
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// let sr = 48_000.0;
+/// let sections = [
+///     vdsp::BiquadCoefs::high_pass(80.0, vdsp::BiquadCoefs::BUTTERWORTH_Q, sr),
+///     vdsp::BiquadCoefs::high_shelf(8_000.0, 6.0, vdsp::BiquadCoefs::BUTTERWORTH_Q, sr),
+/// ];
+/// let mut eq = vdsp::Biquad::new_f32(&sections, 2).unwrap();
+///
+/// // interleaved stereo
+/// let x = vec![0.5f32; 2 * 480];
+/// let mut y = vec![0.0f32; x.len()];
+/// eq.process_interleaved(&x, &mut y);
+/// // DC is removed
+/// assert!(y[y.len() - 2..].iter().all(|v| v.abs() < 0.01));
+/// ```
+pub struct Biquad<T> {
+    setup: NonNull<BiquadSetup<T>>,
+    vt: BiquadVt<T>,
+    sections: usize,
+    /// `2 * sections + 2` values per channel
+    delay: Vec<T>,
+}
+
+impl<T: Copy + Default> Biquad<T> {
+    #[inline]
+    pub fn sections(&self) -> usize {
+        self.sections
+    }
+
+    #[inline]
+    pub fn channels(&self) -> usize {
+        self.delay.len() / self.delay_len()
+    }
+
+    #[inline]
+    fn delay_len(&self) -> usize {
+        2 * self.sections + 2
+    }
+
+    /// Clears the state of all channels
+    pub fn reset(&mut self) {
+        self.delay.fill(T::default());
+    }
+
+    /// Filters `x` into `y` continuing from the state of `channel`
+    pub fn process(&mut self, channel: usize, x: &[T], y: &mut [T]) {
+        let n = x.len();
+        assert_eq!(n, y.len());
+        let delay = self.delay(channel);
+        unsafe {
+            (self.vt.apply)(
+                self.setup.as_ptr(),
+                delay,
+                x.as_ptr(),
+                1,
+                y.as_mut_ptr(),
+                1,
+                n,
+            )
+        }
+    }
+
+    /// Filters every channel with frames of [`Biquad::channels`] samples
+    pub fn process_interleaved(&mut self, x: &[T], y: &mut [T]) {
+        let channels = self.channels();
+        assert_eq!(x.len(), y.len());
+        assert_eq!(0, x.len() % channels, "partial frame");
+        if x.is_empty() {
+            return;
+        }
+        let n = x.len() / channels;
+        for ch in 0..channels {
+            let delay = self.delay(ch);
+            let (x, y) = (x[ch..].as_ptr(), y[ch..].as_mut_ptr());
+            let s = channels as Stride;
+            unsafe { (self.vt.apply)(self.setup.as_ptr(), delay, x, s, y, s, n) }
+        }
+    }
+
+    /// Filters non interleaved buffers, one per channel
+    pub fn process_channels(&mut self, x: &[&[T]], y: &mut [&mut [T]]) {
+        assert_eq!(self.channels(), x.len());
+        assert_eq!(self.channels(), y.len());
+        for (ch, (x, y)) in x.iter().zip(y.iter_mut()).enumerate() {
+            self.process(ch, x, y);
+        }
+    }
+
+    #[inline]
+    fn delay(&mut self, channel: usize) -> *mut T {
+        let len = self.delay_len();
+        self.delay[channel * len..(channel + 1) * len].as_mut_ptr()
+    }
+}
+
+impl Biquad<f32> {
+    /// Returns `None` if `sections` or `channels` is empty
+    pub fn new_f32(sections: &[BiquadCoefs], channels: usize) -> Option<Self> {
+        if sections.is_empty() || channels == 0 {
+            return None;
+        }
+        let m = sections.len();
+        let setup = unsafe { vdsp::_biquad_create_setup_f32(sections.as_ptr().cast(), m) }?;
+        Some(Self {
+            setup,
+            vt: BiquadVt {
+                apply: vdsp::_biquad_f32,
+                destroy: vdsp::_biquad_destroy_setup_f32,
+            },
+            sections: m,
+            delay: vec![0.0; (2 * m + 2) * channels],
+        })
+    }
+}
+
+impl Biquad<f64> {
+    /// Returns `None` if `sections` or `channels` is empty
+    pub fn new_f64(sections: &[BiquadCoefs], channels: usize) -> Option<Self> {
+        if sections.is_empty() || channels == 0 {
+            return None;
+        }
+        let m = sections.len();
+        let setup = unsafe { vdsp::_biquad_create_setup_f64(sections.as_ptr().cast(), m) }?;
+        Some(Self {
+            setup,
+            vt: BiquadVt {
+                apply: vdsp::_biquad_f64,
+                destroy: vdsp::_biquad_destroy_setup_f64,
+            },
+            sections: m,
+            delay: vec![0.0; (2 * m + 2) * channels],
+        })
+    }
+}
+
+impl<T> Drop for Biquad<T> {
+    fn drop(&mut self) {
+        unsafe { (self.vt.destroy)(self.setup.as_ptr()) };
+    }
+}
+
+type ConvFn<T> =
+    unsafe extern "C-unwind" fn(*const T, Stride, *const T, Stride, *mut T, Stride, Len, Len);
+
+type DesampFn<T> = unsafe extern "C-unwind" fn(*const T, Stride, *const T, *mut T, Len, Len);
+
+/// Windowed-sinc low-pass taps with unity gain at DC.
+///
+/// `len` should be odd so that the filter delays the signal by a whole `(len - 1) / 2` samples.
+pub fn fir_low_pass(len: usize, cutoff: f64, sample_rate: f64, window: Window) -> Vec<f64> {
+    assert!(len > 0);
+    assert!(
+        cutoff > 0.0 && cutoff <= sample_rate / 2.0,
+        "cutoff must be below Nyquist"
+    );
+    // periodic window of len - 1 points repeated once is symmetric over len points
+    let mut taps = window.new_f64(len - 1);
+    taps.push(taps.first().copied().unwrap_or(1.0));
+    let fc = cutoff / sample_rate;
+    let mid = (len - 1) as f64 / 2.0;
+    for (i, t) in taps.iter_mut().enumerate() {
+        let x = i as f64 - mid;
+        let sinc = if x == 0.0 {
+            2.0 * fc
+        } else {
+            (TAU * fc * x).sin() / (PI * x)
+        };
+        *t *= sinc;
+    }
+    let scale = 1.0 / vdsp::se_f64(&taps);
+    let t = taps.as_mut_ptr();
+    unsafe { vdsp::_smul_f64(t, 1, &scale, t, 1, taps.len()) };
+    taps
+}
+
+/// Streaming FIR filter.
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// let mut fir = vdsp::Fir::new_f32(&[0.5, 0.5]);
+/// let mut y = [0.0f32; 3];
+/// fir.process(&[2.0, 4.0, 6.0], &mut y);
+/// assert_eq!(y, [1.0, 3.0, 5.0]);
+/// fir.process(&[8.0, 8.0, 8.0], &mut y);
+/// assert_eq!(y, [7.0, 8.0, 8.0]);
+/// ```
+pub struct Fir<T> {
+    /// Taps in reverse order, so that correlation computes the convolution
+    rev: Vec<T>,
+    /// Last `taps - 1` input samples followed by the current input
+    buf: Vec<T>,
+    conv: ConvFn<T>,
+}
+
+impl<T: Copy + Default> Fir<T> {
+    fn with_conv(taps: &[T], conv: ConvFn<T>) -> Self {
+        assert!(!taps.is_empty(), "at least one tap is required");
+        Self {
+            rev: taps.iter().rev().copied().collect(),
+            buf: vec![T::default(); taps.len() - 1],
+            conv,
+        }
+    }
+
+    #[inline]
+    pub fn taps(&self) -> usize {
+        self.rev.len()
+    }
+
+    /// Clears the input history
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.buf.resize(self.taps() - 1, T::default());
+    }
+
+    /// Filters `x` into `y`, both have the same length
+    pub fn process(&mut self, x: &[T], y: &mut [T]) {
+        let (n, p) = (x.len(), self.taps());
+        assert_eq!(n, y.len());
+        self.buf.extend_from_slice(x);
+        let (a, f) = (self.buf.as_ptr(), self.rev.as_ptr());
+        unsafe { (self.conv)(a, 1, f, 1, y.as_mut_ptr(), 1, n, p) };
+        self.buf.drain(..n);
+    }
+}
+
+impl Fir<f32> {
+    pub fn new_f32(taps: &[f32]) -> Self {
+        Self::with_conv(taps, vdsp::_conv_f32)
+    }
+}
+
+impl Fir<f64> {
+    pub fn new_f64(taps: &[f64]) -> Self {
+        Self::with_conv(taps, vdsp::_conv_f64)
+    }
+}
+
+/// Streaming FIR filter keeping every `factor`-th output sample.
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// // 48 kHz to 16 kHz
+/// let taps = vdsp::filter::fir_low_pass(63, 7_000.0, 48_000.0, vdsp::Window::Blackman);
+/// let taps: Vec<f32> = taps.iter().map(|t| *t as f32).collect();
+/// let mut dec = vdsp::Decimator::new_f32(3, &taps);
+///
+/// let x = vec![1.0f32; 480];
+/// let mut y = vec![0.0f32; dec.output_len(x.len())];
+/// assert_eq!(dec.process(&x, &mut y), 160);
+/// assert!((y[159] - 1.0).abs() < 1e-4);
+/// ```
+pub struct Decimator<T> {
+    factor: usize,
+    rev: Vec<T>,
+    /// Input not consumed by an output sample yet, including `taps - 1` history samples
+    buf: Vec<T>,
+    /// Input samples to drop before buffering, when factor is above the number of taps
+    skip: usize,
+    desamp: DesampFn<T>,
+}
+
+impl<T: Copy + Default> Decimator<T> {
+    fn with_desamp(factor: usize, taps: &[T], desamp: DesampFn<T>) -> Self {
+        assert!(factor > 0, "factor must be positive");
+        assert!(!taps.is_empty(), "at least one tap is required");
+        Self {
+            factor,
+            rev: taps.iter().rev().copied().collect(),
+            buf: vec![T::default(); taps.len() - 1],
+            skip: 0,
+            desamp,
+        }
+    }
+
+    #[inline]
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    #[inline]
+    pub fn taps(&self) -> usize {
+        self.rev.len()
+    }
+
+    /// Clears the input history and the decimation phase
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.buf.resize(self.taps() - 1, T::default());
+        self.skip = 0;
+    }
+
+    /// Number of samples the next [`Decimator::process`] of `input_len` samples produces
+    #[inline]
+    pub fn output_len(&self, input_len: usize) -> usize {
+        let len = self.buf.len() + input_len.saturating_sub(self.skip);
+        match len.checked_sub(self.taps()) {
+            Some(extra) => extra / self.factor + 1,
+            None => 0,
+        }
+    }
+
+    /// Filters and decimates `x` into the beginning of `y`, returns number of written samples.
+    ///
+    /// `y` has to hold at least [`Decimator::output_len`] samples.
+    pub fn process(&mut self, x: &[T], y: &mut [T]) -> usize {
+        let n = self.output_len(x.len());
+        assert!(y.len() >= n, "output is too short");
+        let skipped = self.skip.min(x.len());
+        self.skip -= skipped;
+        self.buf.extend_from_slice(&x[skipped..]);
+        let (a, f) = (self.buf.as_ptr(), self.rev.as_ptr());
+        let df = self.factor as Stride;
+        unsafe { (self.desamp)(a, df, f, y.as_mut_ptr(), n, self.taps()) };
+        let consumed = n * self.factor;
+        let drained = consumed.min(self.buf.len());
+        self.buf.drain(..drained);
+        self.skip += consumed - drained;
+        n
+    }
+}
+
+impl Decimator<f32> {
+    pub fn new_f32(factor: usize, taps: &[f32]) -> Self {
+        Self::with_desamp(factor, taps, vdsp::_desamp_f32)
+    }
+}
+
+impl Decimator<f64> {
+    pub fn new_f64(factor: usize, taps: &[f64]) -> Self {
+        Self::with_desamp(factor, taps, vdsp::_desamp_f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use crate::vdsp::{self, Biquad, BiquadCoefs, Decimator, Fir, Window, filter::fir_low_pass};
+
+    const SR: f64 = 48_000.0;
+    const Q: f64 = BiquadCoefs::BUTTERWORTH_Q;
+
+    fn tone(freq: f64, len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| (TAU * freq * i as f64 / SR).sin())
+            .collect()
+    }
+
+    fn db(gain: f64) -> f64 {
+        20.0 * gain.log10()
+    }
+
+    #[test]
+    fn design() {
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-3;
+        let nyquist = SR / 2.0;
+
+        let lp = BiquadCoefs::low_pass(1_000.0, Q, SR);
+        assert!(close(lp.magnitude(0.0, SR), 1.0));
+        assert!(close(db(lp.magnitude(1_000.0, SR)), -3.0103));
+        assert!(lp.magnitude(nyquist, SR) < 1e-6);
+
+        let hp = BiquadCoefs::high_pass(1_000.0, Q, SR);
+        assert!(hp.magnitude(0.0, SR) < 1e-6);
+        assert!(close(db(hp.magnitude(1_000.0, SR)), -3.0103));
+        assert!(close(hp.magnitude(nyquist, SR), 1.0));
+
+        let bp = BiquadCoefs::band_pass(2_000.0, 4.0, SR);
+        assert!(close(bp.magnitude(2_000.0, SR), 1.0));
+        assert!(bp.magnitude(0.0, SR) < 1e-6);
+        assert!(bp.magnitude(500.0, SR) < 0.1);
+
+        let pk = BiquadCoefs::peak(2_000.0, -12.0, 2.0, SR);
+        assert!(close(db(pk.magnitude(2_000.0, SR)), -12.0));
+        assert!(close(pk.magnitude(0.0, SR), 1.0));
+
+        let ls = BiquadCoefs::low_shelf(200.0, 6.0, Q, SR);
+        assert!(close(db(ls.magnitude(0.0, SR)), 6.0));
+        assert!(close(db(ls.magnitude(200.0, SR)), 3.0));
+        assert!(close(ls.magnitude(nyquist, SR), 1.0));
+
+        let hs = BiquadCoefs::high_shelf(8_000.0, -9.0, Q, SR);
+        assert!(close(hs.magnitude(0.0, SR), 1.0));
+        assert!(close(db(hs.magnitude(8_000.0, SR)), -4.5));
+        assert!(close(db(hs.magnitude(nyquist, SR)), -9.0));
+
+        assert_eq!(BiquadCoefs::default().magnitude(1234.0, SR), 1.0);
+    }
+
+    #[test]
+    fn biquad() {
+        assert!(Biquad::new_f32(&[], 1).is_none());
+        assert!(Biquad::new_f32(&[BiquadCoefs::IDENTITY], 0).is_none());
+
+        let sections = [
+            BiquadCoefs::low_pass(2_000.0, Q, SR),
+            BiquadCoefs::low_pass(2_000.0, Q, SR),
+        ];
+        let mut bq = Biquad::new_f64(&sections, 2).unwrap();
+        assert_eq!(bq.sections(), 2);
+        assert_eq!(bq.channels(), 2);
+
+        // steady state amplitude matches the designed response
+        let n = 4_800;
+        for freq in [500.0, 2_000.0, 8_000.0] {
+            let x = tone(freq, n);
+            let mut y = vec![0.0; n];
+            bq.reset();
+            bq.process(1, &x, &mut y);
+            let peak = y[n / 2..].iter().fold(0.0f64, |m, v| m.max(v.abs()));
+            let want = sections[0].magnitude(freq, SR).powi(2);
+            assert!((peak - want).abs() < 1e-3, "{freq}: {peak} != {want}");
+        }
+
+        // chunks, interleaved and per channel processing produce the same output
+        let (l, r) = (tone(440.0, 300), tone(6_000.0, 300));
+        let mut bq = Biquad::new_f32(&sections, 2).unwrap();
+        bq.process_interleaved(&[], &mut []);
+        let l32: Vec<f32> = l.iter().map(|v| *v as f32).collect();
+        let r32: Vec<f32> = r.iter().map(|v| *v as f32).collect();
+        let mut left = vec![0.0f32; 300];
+        let mut right = vec![0.0f32; 300];
+        bq.process_channels(&[&l32, &r32], &mut [&mut left, &mut right]);
+
+        bq.reset();
+        let x: Vec<f32> = l32.iter().zip(&r32).flat_map(|(l, r)| [*l, *r]).collect();
+        let mut y = vec![0.0f32; x.len()];
+        for (x, y) in x.chunks(34).zip(y.chunks_mut(34)) {
+            bq.process_interleaved(x, y);
+        }
+        let want: Vec<f32> = left
+            .iter()
+            .zip(&right)
+            .flat_map(|(l, r)| [*l, *r])
+            .collect();
+        assert_eq!(y, want);
+    }
+
+    #[test]
+    fn fir() {
+        let taps = [0.25, -0.5, 1.0, 0.125];
+        let x: Vec<f64> = tone(1_000.0, 50).iter().map(|v| v + 0.1).collect();
+        let want: Vec<f64> = (0..x.len())
+            .map(|n| {
+                (0..taps.len())
+                    .filter(|k| *k <= n)
+                    .map(|k| taps[k] * x[n - k])
+                    .sum()
+            })
+            .collect();
+
+        let mut fir = Fir::new_f64(&taps);
+        assert_eq!(fir.taps(), 4);
+        let mut y = vec![0.0; x.len()];
+        for (x, y) in x.chunks(7).zip(y.chunks_mut(7)) {
+            fir.process(x, y);
+        }
+        assert!(y.iter().zip(&want).all(|(y, w)| (y - w).abs() < 1e-12));
+
+        fir.reset();
+        let mut again = vec![0.0; x.len()];
+        fir.process(&x, &mut again);
+        assert_eq!(y, again);
+
+        let lp = fir_low_pass(31, 4_000.0, SR, Window::Hamming);
+        assert_eq!(lp.len(), 31);
+        assert!((vdsp::se_f64(&lp) - 1.0).abs() < 1e-12);
+        assert!((0..15).all(|i| (lp[i] - lp[30 - i]).abs() < 1e-15));
+        assert_eq!(fir_low_pass(1, 1_000.0, SR, Window::Hann), [1.0]);
+    }
+
+    #[test]
+    fn decimator() {
+        let taps: Vec<f32> = fir_low_pass(15, 5_000.0, SR, Window::Blackman)
+            .iter()
+            .map(|t| *t as f32)
+            .collect();
+        let x: Vec<f32> = tone(700.0, 200).iter().map(|v| *v as f32).collect();
+
+        // full convolution sampled at every 4th position
+        let mut full = vec![0.0f32; x.len()];
+        Fir::new_f32(&taps).process(&x, &mut full);
+        let want: Vec<f32> = full.iter().step_by(4).copied().collect();
+
+        let mut dec = Decimator::new_f32(4, &taps);
+        assert_eq!(dec.factor(), 4);
+        assert_eq!(dec.output_len(200), 50);
+        let mut y = vec![];
+        for chunk in x.chunks(9) {
+            let mut out = vec![0.0f32; dec.output_len(chunk.len())];
+            assert_eq!(dec.process(chunk, &mut out), out.len());
+            y.extend(out);
+        }
+        assert_eq!(y.len(), want.len());
+        assert!(y.iter().zip(&want).all(|(y, w)| (y - w).abs() < 1e-6));
+        // the next output sample needs x[200]
+        assert_eq!(dec.output_len(0), 0);
+        assert_eq!(dec.output_len(1), 1);
+        assert_eq!(dec.output_len(5), 2);
+
+        // factor above the number of taps skips samples between outputs
+        let taps = [0.25f32, 0.5, 0.25];
+        let mut full = vec![0.0f32; x.len()];
+        Fir::new_f32(&taps).process(&x, &mut full);
+        let want: Vec<f32> = full.iter().step_by(4).copied().collect();
+        let mut dec = Decimator::new_f32(4, &taps);
+        let mut y = vec![];
+        for chunk in x.chunks(1) {
+            let mut out = vec![0.0f32; dec.output_len(chunk.len())];
+            assert_eq!(dec.process(chunk, &mut out), out.len());
+            y.extend(out);
+        }
+        assert_eq!(y.len(), want.len());
+        assert!(y.iter().zip(&want).all(|(y, w)| (y - w).abs() < 1e-6));
+    }
+}
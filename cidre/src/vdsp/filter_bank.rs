@@ -0,0 +1,186 @@
+use crate::vdsp;
+
+/// HTK mel scale
+#[inline]
+pub fn hz_to_mel(hz: f64) -> f64 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+/// Inverse of [`hz_to_mel`]
+#[inline]
+pub fn mel_to_hz(mel: f64) -> f64 {
+    700.0 * (10f64.powf(mel / 2595.0) - 1.0)
+}
+
+/// Triangular filters over the bins of a real FFT spectrum.
+///
+/// Filter `i` rises from edge `i` to edge `i + 1` and falls to edge `i + 2`,
+/// with peak weight 1. Weights are stored sparsely, only for the bins a filter covers.
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// // 40 mel bands for 16 kHz audio and 512 point frames
+/// let bank = vdsp::FilterBank::mel(40, 0.0, 8_000.0, 16_000.0, 512);
+/// assert_eq!(bank.len(), 40);
+/// assert_eq!(bank.bins(), 257);
+///
+/// let power = vec![1.0f32; bank.bins()];
+/// let mut bands = vec![0.0f32; bank.len()];
+/// bank.apply(&power, &mut bands);
+/// assert!(bands.iter().all(|b| *b > 0.0));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterBank {
+    bins: usize,
+    filters: Vec<(usize, Vec<f32>)>,
+}
+
+impl FilterBank {
+    /// `edges` are ascending frequencies in Hz, `edges.len() - 2` filters are created.
+    pub fn with_edges(edges: &[f64], sample_rate: f64, fft_len: usize) -> Self {
+        assert!(edges.len() >= 3, "at least one filter is required");
+        assert!(edges.windows(2).all(|w| w[0] < w[1]), "edges must ascend");
+        let bins = fft_len / 2 + 1;
+        let bin_hz = sample_rate / fft_len as f64;
+        let filters = edges
+            .windows(3)
+            .map(|e| {
+                let (lo, center, hi) = (e[0], e[1], e[2]);
+                let first = ((lo / bin_hz).floor() as usize + 1).min(bins);
+                let last = ((hi / bin_hz).ceil() as usize).min(bins);
+                let weights = (first..last.max(first))
+                    .map(|k| {
+                        let f = k as f64 * bin_hz;
+                        let w = if f <= center {
+                            (f - lo) / (center - lo)
+                        } else {
+                            (hi - f) / (hi - center)
+                        };
+                        w.max(0.0) as f32
+                    })
+                    .collect();
+                (first, weights)
+            })
+            .collect();
+        Self { bins, filters }
+    }
+
+    /// `count` filters equally spaced on the mel scale between `f_min` and `f_max` Hz.
+    pub fn mel(count: usize, f_min: f64, f_max: f64, sample_rate: f64, fft_len: usize) -> Self {
+        let (lo, hi) = (hz_to_mel(f_min), hz_to_mel(f_max));
+        let step = (hi - lo) / (count + 1) as f64;
+        let edges: Vec<f64> = (0..count + 2)
+            .map(|i| mel_to_hz(lo + step * i as f64))
+            .collect();
+        Self::with_edges(&edges, sample_rate, fft_len)
+    }
+
+    /// `count` filters with logarithmically spaced edges between `f_min` and `f_max` Hz,
+    /// `f_min` has to be positive.
+    pub fn log(count: usize, f_min: f64, f_max: f64, sample_rate: f64, fft_len: usize) -> Self {
+        assert!(f_min > 0.0, "log spaced filters need positive f_min");
+        let ratio = (f_max / f_min).powf(1.0 / (count + 1) as f64);
+        let edges: Vec<f64> = (0..count + 2)
+            .map(|i| f_min * ratio.powi(i as i32))
+            .collect();
+        Self::with_edges(&edges, sample_rate, fft_len)
+    }
+
+    /// Number of filters
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Spectrum length expected by [`FilterBank::apply`]
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// First bin and weights of the filter `index`
+    #[inline]
+    pub fn filter(&self, index: usize) -> (usize, &[f32]) {
+        let (first, weights) = &self.filters[index];
+        (*first, weights)
+    }
+
+    /// Scales every filter so that its weights sum to 1.
+    pub fn normalize(&mut self) {
+        for (_, weights) in self.filters.iter_mut() {
+            let sum = vdsp::se_f32(weights);
+            if sum > 0.0 {
+                let w = weights.as_mut_ptr();
+                unsafe { vdsp::_smul_f32(w, 1, &(1.0 / sum), w, 1, weights.len()) };
+            }
+        }
+    }
+
+    /// Weighted sums of `spectrum` (power or magnitude), one per filter.
+    pub fn apply(&self, spectrum: &[f32], out: &mut [f32]) {
+        assert_eq!(self.bins, spectrum.len());
+        assert_eq!(self.filters.len(), out.len());
+        for ((first, weights), out) in self.filters.iter().zip(out.iter_mut()) {
+            *out = vdsp::dotpr_f32(&spectrum[*first..*first + weights.len()], weights);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vdsp::{
+        FilterBank,
+        filter_bank::{hz_to_mel, mel_to_hz},
+    };
+
+    #[test]
+    fn scale() {
+        assert_eq!(hz_to_mel(0.0), 0.0);
+        assert!((hz_to_mel(1000.0) - 1000.0).abs() < 0.1);
+        for hz in [20.0, 440.0, 8000.0, 20000.0] {
+            assert!((mel_to_hz(hz_to_mel(hz)) - hz).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn filters() {
+        // bin spacing is 100 Hz
+        let bank = FilterBank::with_edges(&[100.0, 300.0, 500.0, 900.0], 6400.0, 64);
+        assert_eq!(bank.bins(), 33);
+        assert_eq!(bank.len(), 2);
+        assert_eq!(bank.filter(0), (2, &[0.5, 1.0, 0.5][..]));
+        assert_eq!(bank.filter(1), (4, &[0.5, 1.0, 0.75, 0.5, 0.25][..]));
+
+        let mut spectrum = vec![0.0f32; 33];
+        spectrum[3] = 2.0;
+        spectrum[5] = 4.0;
+        let mut out = [0.0f32; 2];
+        bank.apply(&spectrum, &mut out);
+        assert_eq!(out, [2.0, 4.0]);
+
+        let mut norm = bank.clone();
+        norm.normalize();
+        assert_eq!(norm.filter(0).1, [0.25, 0.5, 0.25]);
+
+        let mel = FilterBank::mel(26, 0.0, 8000.0, 16000.0, 512);
+        let mut last = 0;
+        for i in 0..mel.len() {
+            let (first, weights) = mel.filter(i);
+            assert!(!weights.is_empty());
+            assert!(first >= last);
+            assert!(first + weights.len() <= mel.bins());
+            assert!(weights.iter().all(|w| *w <= 1.0));
+            last = first;
+        }
+
+        let log = FilterBank::log(12, 50.0, 8000.0, 16000.0, 4096);
+        let widths: Vec<usize> = (0..log.len()).map(|i| log.filter(i).1.len()).collect();
+        assert!(widths.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,2649 @@
+//! Pure-Rust implementations of the vDSP entry points.
+//!
+//! Every function here has exactly the same signature and semantics as its
+//! Accelerate counterpart in [`crate::vdsp`], so the safe wrappers and [`crate::vdsp::Fft`]
+//! work unchanged on targets without Accelerate. Contiguous vectors are processed
+//! in blocks of `LANES` elements, which the compiler lowers to SIMD instructions;
+//! strided vectors fall back to a scalar loop.
+//!
+//! The functions are crate private. On Apple targets the module is only compiled
+//! for tests, which check it against Accelerate.
+//!
+//! Safety requirements are the same as for the vDSP functions: pointers must be
+//! valid for `N` elements at the given strides, and output may alias input only
+//! element for element.
+#![allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ptr::NonNull;
+
+use super::{
+    BiquadSetup, Complex, FftDirection, FftRadix, FftSetup, Len, SplitComplex, Stride, WindowFlags,
+};
+
+/// Number of elements processed per block on contiguous vectors.
+const LANES: usize = 8;
+
+trait Float:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+
+    fn abs(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn log10(self) -> Self;
+    fn from_usize(val: usize) -> Self;
+    fn from_f64(val: f64) -> Self;
+}
+
+macro_rules! impl_float {
+    ($t:ty) => {
+        impl Float for $t {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const TWO: Self = 2.0;
+
+            #[inline(always)]
+            fn abs(self) -> Self {
+                <$t>::abs(self)
+            }
+
+            #[inline(always)]
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+
+            #[inline(always)]
+            fn log10(self) -> Self {
+                <$t>::log10(self)
+            }
+
+            #[inline(always)]
+            fn from_usize(val: usize) -> Self {
+                val as _
+            }
+
+            #[inline(always)]
+            fn from_f64(val: f64) -> Self {
+                val as _
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+#[inline(always)]
+unsafe fn at<T>(ptr: *const T, stride: Stride, i: usize) -> *const T {
+    unsafe { ptr.offset(i as isize * stride) }
+}
+
+#[inline(always)]
+unsafe fn at_mut<T>(ptr: *mut T, stride: Stride, i: usize) -> *mut T {
+    unsafe { ptr.offset(i as isize * stride) }
+}
+
+#[inline(always)]
+unsafe fn load<T: Copy>(ptr: *const T, i: usize) -> [T; LANES] {
+    unsafe { ptr.add(i).cast::<[T; LANES]>().read_unaligned() }
+}
+
+#[inline(always)]
+unsafe fn store<T>(ptr: *mut T, i: usize, val: [T; LANES]) {
+    unsafe { ptr.add(i).cast::<[T; LANES]>().write_unaligned(val) }
+}
+
+/// C[n] = f(n)
+#[inline(always)]
+unsafe fn generate<C>(c: *mut C, ic: Stride, n: Len, f: impl Fn(usize) -> C) {
+    let mut i = 0;
+    if ic == 1 {
+        while i + LANES <= n {
+            unsafe { store(c, i, std::array::from_fn(|j| f(i + j))) };
+            i += LANES;
+        }
+    }
+    while i < n {
+        unsafe { *at_mut(c, ic, i) = f(i) };
+        i += 1;
+    }
+}
+
+/// C[n] = f(A[n])
+#[inline(always)]
+unsafe fn map1<A: Copy, C>(
+    a: *const A,
+    ia: Stride,
+    c: *mut C,
+    ic: Stride,
+    n: Len,
+    f: impl Fn(A) -> C,
+) {
+    let mut i = 0;
+    if ia == 1 && ic == 1 {
+        while i + LANES <= n {
+            unsafe { store(c, i, load(a, i).map(&f)) };
+            i += LANES;
+        }
+    }
+    while i < n {
+        unsafe { *at_mut(c, ic, i) = f(*at(a, ia, i)) };
+        i += 1;
+    }
+}
+
+/// C[n] = f(A[n], B[n])
+#[inline(always)]
+unsafe fn map2<A: Copy, B: Copy, C>(
+    a: *const A,
+    ia: Stride,
+    b: *const B,
+    ib: Stride,
+    c: *mut C,
+    ic: Stride,
+    n: Len,
+    f: impl Fn(A, B) -> C,
+) {
+    let mut i = 0;
+    if ia == 1 && ib == 1 && ic == 1 {
+        while i + LANES <= n {
+            let (va, vb) = unsafe { (load(a, i), load(b, i)) };
+            unsafe { store(c, i, std::array::from_fn(|j| f(va[j], vb[j]))) };
+            i += LANES;
+        }
+    }
+    while i < n {
+        unsafe { *at_mut(c, ic, i) = f(*at(a, ia, i), *at(b, ib, i)) };
+        i += 1;
+    }
+}
+
+/// D[n] = f(A[n], B[n], C[n])
+#[inline(always)]
+unsafe fn map3<T: Copy>(
+    a: *const T,
+    ia: Stride,
+    b: *const T,
+    ib: Stride,
+    c: *const T,
+    ic: Stride,
+    d: *mut T,
+    id: Stride,
+    n: Len,
+    f: impl Fn(T, T, T) -> T,
+) {
+    let mut i = 0;
+    if ia == 1 && ib == 1 && ic == 1 && id == 1 {
+        while i + LANES <= n {
+            let (va, vb, vc) = unsafe { (load(a, i), load(b, i), load(c, i)) };
+            unsafe { store(d, i, std::array::from_fn(|j| f(va[j], vb[j], vc[j]))) };
+            i += LANES;
+        }
+    }
+    while i < n {
+        unsafe { *at_mut(d, id, i) = f(*at(a, ia, i), *at(b, ib, i), *at(c, ic, i)) };
+        i += 1;
+    }
+}
+
+/// Folds A into per-lane accumulators which are merged at the end.
+#[inline(always)]
+unsafe fn reduce<T: Copy>(
+    a: *const T,
+    ia: Stride,
+    n: Len,
+    init: T,
+    f: impl Fn(T, T) -> T,
+    merge: impl Fn(T, T) -> T,
+) -> T {
+    let mut acc = [init; LANES];
+    let mut i = 0;
+    if ia == 1 {
+        while i + LANES <= n {
+            for (acc, v) in acc.iter_mut().zip(unsafe { load(a, i) }) {
+                *acc = f(*acc, v);
+            }
+            i += LANES;
+        }
+    }
+    while i < n {
+        acc[0] = f(acc[0], unsafe { *at(a, ia, i) });
+        i += 1;
+    }
+    acc.into_iter().reduce(merge).unwrap_or(init)
+}
+
+/// Folds f(A[n], B[n]) into per-lane sums.
+#[inline(always)]
+unsafe fn sum2<T: Float>(
+    a: *const T,
+    ia: Stride,
+    b: *const T,
+    ib: Stride,
+    n: Len,
+    f: impl Fn(T, T) -> T,
+) -> T {
+    let mut acc = [T::ZERO; LANES];
+    let mut i = 0;
+    if ia == 1 && ib == 1 {
+        while i + LANES <= n {
+            let (va, vb) = unsafe { (load(a, i), load(b, i)) };
+            for ((acc, a), b) in acc.iter_mut().zip(va).zip(vb) {
+                *acc = *acc + f(a, b);
+            }
+            i += LANES;
+        }
+    }
+    while i < n {
+        acc[0] = acc[0] + f(unsafe { *at(a, ia, i) }, unsafe { *at(b, ib, i) });
+        i += 1;
+    }
+    acc.into_iter().fold(T::ZERO, |a, b| a + b)
+}
+
+#[inline(always)]
+unsafe fn sum<T: Float>(a: *const T, ia: Stride, n: Len, f: impl Fn(T) -> T) -> T {
+    unsafe { reduce(a, ia, n, T::ZERO, |acc, v| acc + f(v), |a, b| a + b) }
+}
+
+#[inline(always)]
+fn max<T: Float>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+#[inline(always)]
+fn min<T: Float>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+#[inline(always)]
+fn ssq<T: Float>(a: T) -> T {
+    a * a.abs()
+}
+
+#[inline(always)]
+unsafe fn gen_<T: Float>(a: &T, b: &T, c: *mut T, ic: Stride, n: Len) {
+    let (a, b) = (*a, *b);
+    let d = if n > 1 {
+        (b - a) / T::from_usize(n - 1)
+    } else {
+        T::ZERO
+    };
+    unsafe { generate(c, ic, n, |i| a + d * T::from_usize(i)) }
+}
+
+#[inline(always)]
+unsafe fn rampmul<T: Float>(
+    i: *const T,
+    is: Stride,
+    start: &mut T,
+    step: &T,
+    o: *mut T,
+    os: Stride,
+    n: Len,
+) {
+    for k in 0..n {
+        unsafe { *at_mut(o, os, k) = *start * *at(i, is, k) };
+        *start = *start + *step;
+    }
+}
+
+#[inline(always)]
+unsafe fn rampmul2<T: Float>(
+    i0: *const T,
+    i1: *const T,
+    is: Stride,
+    start: &mut T,
+    step: &T,
+    o0: *mut T,
+    o1: *mut T,
+    os: Stride,
+    n: Len,
+) {
+    for k in 0..n {
+        unsafe {
+            *at_mut(o0, os, k) = *start * *at(i0, is, k);
+            *at_mut(o1, os, k) = *start * *at(i1, is, k);
+        }
+        *start = *start + *step;
+    }
+}
+
+#[inline(always)]
+unsafe fn tmerg<T: Float>(
+    a: *const T,
+    ia: Stride,
+    b: *const T,
+    ib: Stride,
+    c: *mut T,
+    ic: Stride,
+    n: Len,
+) {
+    let d = if n > 1 {
+        T::ONE / T::from_usize(n - 1)
+    } else {
+        T::ZERO
+    };
+    for k in 0..n {
+        unsafe {
+            let (a, b) = (*at(a, ia, k), *at(b, ib, k));
+            *at_mut(c, ic, k) = a + (b - a) * (T::from_usize(k) * d);
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn ctoz<T: Copy>(
+    c: *const Complex<T>,
+    ic: Stride,
+    z: *const SplitComplex<T>,
+    iz: Stride,
+    n: Len,
+) {
+    // ic is measured in real elements, so 2 means adjacent complex values
+    let c = c.cast::<T>();
+    let z = unsafe { &*z };
+    for k in 0..n {
+        unsafe {
+            let p = at(c, ic, k);
+            *at_mut(z.re, iz, k) = *p;
+            *at_mut(z.im, iz, k) = *p.add(1);
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn ztoc<T: Copy>(
+    z: *const SplitComplex<T>,
+    iz: Stride,
+    c: *mut Complex<T>,
+    ic: Stride,
+    n: Len,
+) {
+    let c = c.cast::<T>();
+    let z = unsafe { &*z };
+    for k in 0..n {
+        unsafe {
+            let p = at_mut(c, ic, k);
+            *p = *at(z.re, iz, k);
+            *p.add(1) = *at(z.im, iz, k);
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn zaspec<T: Float>(a: *const SplitComplex<T>, c: *mut T, n: Len) {
+    let a = unsafe { &*a };
+    unsafe {
+        map3(a.re, 1, a.im, 1, c, 1, c, 1, n, |re, im, c| {
+            c + re * re + im * im
+        })
+    }
+}
+
+#[inline(always)]
+unsafe fn dbcon<T: Float>(
+    a: *const T,
+    ia: Stride,
+    b: *const T,
+    c: *mut T,
+    ic: Stride,
+    n: Len,
+    f: u32,
+) {
+    let alpha = T::from_usize(if f == 0 { 10 } else { 20 });
+    let b = unsafe { *b };
+    unsafe { map1(a, ia, c, ic, n, |a| alpha * (a / b).log10()) }
+}
+
+#[inline(always)]
+unsafe fn clip<T: Float>(
+    a: *const T,
+    ia: Stride,
+    b: *const T,
+    c: *const T,
+    d: *mut T,
+    id: Stride,
+    n: Len,
+) {
+    let (lo, hi) = unsafe { (*b, *c) };
+    unsafe { map1(a, ia, d, id, n, |a| min(max(a, lo), hi)) }
+}
+
+#[inline(always)]
+unsafe fn zmags<T: Float>(a: *const SplitComplex<T>, ia: Stride, c: *mut T, ic: Stride, n: Len) {
+    let a = unsafe { &*a };
+    unsafe { map2(a.re, ia, a.im, ia, c, ic, n, |re, im| re * re + im * im) }
+}
+
+#[inline(always)]
+unsafe fn zabs<T: Float>(a: *const SplitComplex<T>, ia: Stride, c: *mut T, ic: Stride, n: Len) {
+    let a = unsafe { &*a };
+    unsafe {
+        map2(a.re, ia, a.im, ia, c, ic, n, |re, im| {
+            (re * re + im * im).sqrt()
+        })
+    }
+}
+
+/// C[n] = f(2πn/N), only the first half with [`WindowFlags::HALF_WINDOW`]
+#[inline(always)]
+unsafe fn window<T: Float>(c: *mut T, n: Len, flags: WindowFlags, f: impl Fn(f64) -> f64) {
+    let len = if flags.0 & WindowFlags::HALF_WINDOW.0 != 0 {
+        n.div_ceil(2)
+    } else {
+        n
+    };
+    let step = std::f64::consts::TAU / n as f64;
+    unsafe { generate(c, 1, len, |i| T::from_f64(f(step * i as f64))) }
+}
+
+#[inline(always)]
+unsafe fn hann_window<T: Float>(c: *mut T, n: Len, flags: WindowFlags) {
+    let scale = if flags.0 & WindowFlags::HANN_NORM.0 != 0 {
+        0.8165
+    } else {
+        0.5
+    };
+    unsafe { window(c, n, flags, |x| scale * (1.0 - x.cos())) }
+}
+
+#[inline(always)]
+unsafe fn hamm_window<T: Float>(c: *mut T, n: Len, flags: WindowFlags) {
+    unsafe { window(c, n, flags, |x| 0.54 - 0.46 * x.cos()) }
+}
+
+#[inline(always)]
+unsafe fn blkman_window<T: Float>(c: *mut T, n: Len, flags: WindowFlags) {
+    unsafe {
+        window(c, n, flags, |x| {
+            0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos()
+        })
+    }
+}
+
+/// C[n] = sum(A[n + p] * F[p]), negative `if_` walks the filter backwards
+#[inline(always)]
+unsafe fn conv<T: Float>(
+    a: *const T,
+    ia: Stride,
+    f: *const T,
+    if_: Stride,
+    c: *mut T,
+    ic: Stride,
+    n: Len,
+    p: Len,
+) {
+    for k in 0..n {
+        unsafe { *at_mut(c, ic, k) = sum2(at(a, ia, k), ia, f, if_, p, |a, f| a * f) };
+    }
+}
+
+/// C[n] = sum(A[n * DF + p] * F[p])
+#[inline(always)]
+unsafe fn desamp<T: Float>(a: *const T, df: Stride, f: *const T, c: *mut T, n: Len, p: Len) {
+    for k in 0..n {
+        unsafe { *c.add(k) = sum2(at(a, df, k), 1, f, 1, p, |a, f| a * f) };
+    }
+}
+
+/// b0, b1, b2, a1, a2 of every section of a biquad cascade.
+type Sections<T> = Vec<[T; 5]>;
+
+#[inline(always)]
+fn biquad_create_setup<T: Float>(coefs: *const f64, m: Len) -> Option<NonNull<BiquadSetup<T>>> {
+    if coefs.is_null() || m == 0 {
+        return None;
+    }
+    let coefs = unsafe { std::slice::from_raw_parts(coefs, m.checked_mul(5)?) };
+    let sections: Sections<T> = coefs
+        .chunks_exact(5)
+        .map(|c| std::array::from_fn(|i| T::from_f64(c[i])))
+        .collect();
+    NonNull::new(Box::into_raw(Box::new(sections)).cast())
+}
+
+/// Direct form I cascade.
+///
+/// `delay` holds `2 * M + 2` values: the two previous inputs of every section
+/// followed by the two previous outputs of the last one, older value first.
+#[inline(always)]
+unsafe fn biquad<T: Float>(
+    setup: *const BiquadSetup<T>,
+    delay: *mut T,
+    x: *const T,
+    ix: Stride,
+    y: *mut T,
+    iy: Stride,
+    n: Len,
+) {
+    let sections = unsafe { &*setup.cast::<Sections<T>>() };
+    let m = sections.len();
+    let d = unsafe { std::slice::from_raw_parts_mut(delay, 2 * m + 2) };
+    for k in 0..n {
+        let mut v = unsafe { *at(x, ix, k) };
+        for (s, [b0, b1, b2, a1, a2]) in sections.iter().enumerate() {
+            let (i2, i1, o2, o1) = (d[2 * s], d[2 * s + 1], d[2 * s + 2], d[2 * s + 3]);
+            let out = *b0 * v + *b1 * i1 + *b2 * i2 - *a1 * o1 - *a2 * o2;
+            (d[2 * s], d[2 * s + 1]) = (i1, v);
+            v = out;
+        }
+        (d[2 * m], d[2 * m + 1]) = (d[2 * m + 1], v);
+        unsafe { *at_mut(y, iy, k) = v };
+    }
+}
+
+/// Twiddle factors e^(-2πik/N) for the largest transform of the setup.
+struct Setup<T> {
+    log2n: Len,
+    re: Vec<T>,
+    im: Vec<T>,
+}
+
+impl<T: Float> Setup<T> {
+    fn new(log2n: Len) -> Option<Self> {
+        if log2n >= usize::BITS as usize - 1 {
+            return None;
+        }
+        let n = 1usize << log2n;
+        let (re, im) = (0..n / 2)
+            .map(|k| {
+                let theta = std::f64::consts::TAU * k as f64 / n as f64;
+                (T::from_f64(theta.cos()), T::from_f64(-theta.sin()))
+            })
+            .unzip();
+        Some(Self { log2n, re, im })
+    }
+
+    /// e^(∓2πik/2^log2n)
+    #[inline]
+    fn twiddle(&self, k: usize, log2n: Len, direction: FftDirection) -> (T, T) {
+        let k = k << (self.log2n - log2n);
+        match direction {
+            FftDirection::Forward => (self.re[k], self.im[k]),
+            FftDirection::Inverse => (self.re[k], -self.im[k]),
+        }
+    }
+
+    /// In-place radix-2 complex transform, not scaled.
+    unsafe fn zip(&self, re: *mut T, im: *mut T, ic: Stride, log2n: Len, direction: FftDirection) {
+        assert!(log2n <= self.log2n, "fft setup is too small");
+        let n = 1usize << log2n;
+        if n < 2 {
+            return;
+        }
+        let shift = usize::BITS - log2n as u32;
+        for i in 0..n {
+            let j = i.reverse_bits() >> shift;
+            if j > i {
+                unsafe {
+                    std::ptr::swap(at_mut(re, ic, i), at_mut(re, ic, j));
+                    std::ptr::swap(at_mut(im, ic, i), at_mut(im, ic, j));
+                }
+            }
+        }
+        let mut half = 1;
+        let mut level = 1;
+        while half < n {
+            for k in 0..half {
+                let (wr, wi) = self.twiddle(k, level, direction);
+                for start in (0..n).step_by(half * 2) {
+                    unsafe {
+                        let (ar, ai) = (at_mut(re, ic, start + k), at_mut(im, ic, start + k));
+                        let (br, bi) = (
+                            at_mut(re, ic, start + k + half),
+                            at_mut(im, ic, start + k + half),
+                        );
+                        let tr = *br * wr - *bi * wi;
+                        let ti = *br * wi + *bi * wr;
+                        *br = *ar - tr;
+                        *bi = *ai - ti;
+                        *ar = *ar + tr;
+                        *ai = *ai + ti;
+                    }
+                }
+            }
+            half *= 2;
+            level += 1;
+        }
+    }
+
+    /// In-place real transform in vDSP packed format.
+    ///
+    /// Forward output is twice the DFT with the Nyquist bin stored in im[0],
+    /// inverse output is 2N times the signal.
+    unsafe fn zrip(&self, re: *mut T, im: *mut T, ic: Stride, log2n: Len, direction: FftDirection) {
+        assert!(log2n <= self.log2n, "fft setup is too small");
+        if log2n == 0 {
+            return;
+        }
+        let h = 1usize << (log2n - 1);
+        if direction == FftDirection::Forward {
+            unsafe { self.zip(re, im, ic, log2n - 1, direction) };
+        }
+        unsafe {
+            let (r0, i0) = (at_mut(re, ic, 0), at_mut(im, ic, 0));
+            let (a, b) = (*r0, *i0);
+            match direction {
+                FftDirection::Forward => (*r0, *i0) = (T::TWO * (a + b), T::TWO * (a - b)),
+                FftDirection::Inverse => (*r0, *i0) = (a + b, a - b),
+            }
+        }
+        // forward: X[k] = (Z[k] + Z*[h-k]) - i W^k (Z[k] - Z*[h-k])
+        // inverse: Z[k] = (X[k] + X*[h-k]) + i W^-k (X[k] - X*[h-k])
+        let split = |ar: T, ai: T, br: T, bi: T, k: usize| -> (T, T) {
+            let (wr, wi) = self.twiddle(k, log2n, direction);
+            let (sr, si) = (ar + br, ai - bi);
+            let (dr, di) = (ar - br, ai + bi);
+            let (pr, pi) = (wr * dr - wi * di, wr * di + wi * dr);
+            match direction {
+                FftDirection::Forward => (sr + pi, si - pr),
+                FftDirection::Inverse => (sr - pi, si + pr),
+            }
+        };
+        for k in 1..=h / 2 {
+            let m = h - k;
+            unsafe {
+                let (kr, ki) = (at_mut(re, ic, k), at_mut(im, ic, k));
+                let (mr, mi) = (at_mut(re, ic, m), at_mut(im, ic, m));
+                let (xk, xm) = (split(*kr, *ki, *mr, *mi, k), split(*mr, *mi, *kr, *ki, m));
+                (*kr, *ki) = xk;
+                (*mr, *mi) = xm;
+            }
+        }
+        if direction == FftDirection::Inverse {
+            unsafe { self.zip(re, im, ic, log2n - 1, direction) };
+        }
+    }
+}
+
+#[inline(always)]
+fn create_fftsetup<T: Float>(log2n: Len) -> Option<NonNull<FftSetup<T>>> {
+    let setup = Box::new(Setup::<T>::new(log2n)?);
+    NonNull::new(Box::into_raw(setup).cast())
+}
+
+#[inline(always)]
+unsafe fn setup_ref<'a, T>(setup: *mut FftSetup<T>) -> &'a Setup<T> {
+    unsafe { &*setup.cast::<Setup<T>>() }
+}
+
+#[doc(alias = "vDSP_vadd")]
+pub unsafe extern "C-unwind" fn _add_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a + b) }
+}
+
+#[doc(alias = "vDSP_vaddD")]
+pub unsafe extern "C-unwind" fn _add_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a + b) }
+}
+
+#[doc(alias = "vDSP_vaddi")]
+pub unsafe extern "C-unwind" fn _add_i32(
+    a: *const i32,
+    ia: Stride,
+    b: *const i32,
+    ib: Stride,
+    c: *mut i32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, i32::wrapping_add) }
+}
+
+#[doc(alias = "vDSP_vsub")]
+pub unsafe extern "C-unwind" fn _sub_f32(
+    b: *const f32,
+    ib: Stride,
+    a: *const f32,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a - b) }
+}
+
+#[doc(alias = "vDSP_vsubD")]
+pub unsafe extern "C-unwind" fn _sub_f64(
+    b: *const f64,
+    ib: Stride,
+    a: *const f64,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a - b) }
+}
+
+#[doc(alias = "vDSP_vsubi")]
+pub unsafe extern "C-unwind" fn _sub_i32(
+    b: *const i32,
+    ib: Stride,
+    a: *const i32,
+    ia: Stride,
+    c: *mut i32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, i32::wrapping_sub) }
+}
+
+#[doc(alias = "vDSP_vmul")]
+pub unsafe extern "C-unwind" fn _mul_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a * b) }
+}
+
+#[doc(alias = "vDSP_vmulD")]
+pub unsafe extern "C-unwind" fn _mul_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a * b) }
+}
+
+#[doc(alias = "vDSP_vdiv")]
+pub unsafe extern "C-unwind" fn _div_f32(
+    b: *const f32,
+    ib: Stride,
+    a: *const f32,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a / b) }
+}
+
+#[doc(alias = "vDSP_vdivD")]
+pub unsafe extern "C-unwind" fn _div_f64(
+    b: *const f64,
+    ib: Stride,
+    a: *const f64,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| a / b) }
+}
+
+#[doc(alias = "vDSP_vdivi")]
+pub unsafe extern "C-unwind" fn _div_i32(
+    b: *const i32,
+    ib: Stride,
+    a: *const i32,
+    ia: Stride,
+    c: *mut i32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, i32::wrapping_div) }
+}
+
+#[doc(alias = "vDSP_vsmul")]
+pub unsafe extern "C-unwind" fn _smul_f32(
+    a: *const f32,
+    ia: Stride,
+    b: &f32,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    let b = *b;
+    unsafe { map1(a, ia, c, ic, n, |a| a * b) }
+}
+
+#[doc(alias = "vDSP_vsmulD")]
+pub unsafe extern "C-unwind" fn _smul_f64(
+    a: *const f64,
+    ia: Stride,
+    b: &f64,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    let b = *b;
+    unsafe { map1(a, ia, c, ic, n, |a| a * b) }
+}
+
+#[doc(alias = "vDSP_vsq")]
+pub unsafe extern "C-unwind" fn _sq_f32(
+    a: *const f32,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, |a| a * a) }
+}
+
+#[doc(alias = "vDSP_vsqD")]
+pub unsafe extern "C-unwind" fn _sq_f64(
+    a: *const f64,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, |a| a * a) }
+}
+
+#[doc(alias = "vDSP_vssq")]
+pub unsafe extern "C-unwind" fn _ssq_f32(
+    a: *const f32,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, ssq) }
+}
+
+#[doc(alias = "vDSP_vssqD")]
+pub unsafe extern "C-unwind" fn _ssq_f64(
+    a: *const f64,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, ssq) }
+}
+
+#[doc(alias = "vDSP_meanv")]
+pub unsafe extern "C-unwind" fn _mean_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a) / n as f32 }
+}
+
+#[doc(alias = "vDSP_meanvD")]
+pub unsafe extern "C-unwind" fn _mean_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a) / n as f64 }
+}
+
+#[doc(alias = "vDSP_measqv")]
+pub unsafe extern "C-unwind" fn _meansq_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a * a) / n as f32 }
+}
+
+#[doc(alias = "vDSP_measqvD")]
+pub unsafe extern "C-unwind" fn _meansq_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a * a) / n as f64 }
+}
+
+#[doc(alias = "vDSP_distancesq")]
+pub unsafe extern "C-unwind" fn _distance_sq_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *mut f32,
+    n: Len,
+) {
+    unsafe { *c = sum2(a, ia, b, ib, n, |a, b| (a - b) * (a - b)) }
+}
+
+#[doc(alias = "vDSP_distancesqD")]
+pub unsafe extern "C-unwind" fn _distance_sq_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *mut f64,
+    n: Len,
+) {
+    unsafe { *c = sum2(a, ia, b, ib, n, |a, b| (a - b) * (a - b)) }
+}
+
+#[doc(alias = "vDSP_dotpr")]
+pub unsafe extern "C-unwind" fn _dotpr_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *mut f32,
+    n: Len,
+) {
+    unsafe { *c = sum2(a, ia, b, ib, n, |a, b| a * b) }
+}
+
+#[doc(alias = "vDSP_dotprD")]
+pub unsafe extern "C-unwind" fn _dotpr_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *mut f64,
+    n: Len,
+) {
+    unsafe { *c = sum2(a, ia, b, ib, n, |a, b| a * b) }
+}
+
+#[doc(alias = "vDSP_vam")]
+pub unsafe extern "C-unwind" fn _am_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *const f32,
+    ic: Stride,
+    d: *mut f32,
+    id: Stride,
+    n: Len,
+) {
+    unsafe { map3(a, ia, b, ib, c, ic, d, id, n, |a, b, c| (a + b) * c) }
+}
+
+#[doc(alias = "vDSP_vamD")]
+pub unsafe extern "C-unwind" fn _am_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *const f64,
+    ic: Stride,
+    d: *mut f64,
+    id: Stride,
+    n: Len,
+) {
+    unsafe { map3(a, ia, b, ib, c, ic, d, id, n, |a, b, c| (a + b) * c) }
+}
+
+#[doc(alias = "vDSP_vma")]
+pub unsafe extern "C-unwind" fn _ma_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *const f32,
+    ic: Stride,
+    d: *mut f32,
+    id: Stride,
+    n: Len,
+) {
+    unsafe { map3(a, ia, b, ib, c, ic, d, id, n, |a, b, c| a * b + c) }
+}
+
+#[doc(alias = "vDSP_vmaD")]
+pub unsafe extern "C-unwind" fn _ma_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *const f64,
+    ic: Stride,
+    d: *mut f64,
+    id: Stride,
+    n: Len,
+) {
+    unsafe { map3(a, ia, b, ib, c, ic, d, id, n, |a, b, c| a * b + c) }
+}
+
+#[doc(alias = "vDSP_vdpsp")]
+pub unsafe extern "C-unwind" fn _f64_f32(
+    a: *const f64,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, |a| a as f32) }
+}
+
+#[doc(alias = "vDSP_vspdp")]
+pub unsafe extern "C-unwind" fn _f32_f64(
+    a: *const f32,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, |a| a as f64) }
+}
+
+#[doc(alias = "vDSP_vabs")]
+pub unsafe extern "C-unwind" fn _abs_f32(
+    a: *const f32,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, f32::abs) }
+}
+
+#[doc(alias = "vDSP_vabsD")]
+pub unsafe extern "C-unwind" fn _abs_f64(
+    a: *const f64,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, f64::abs) }
+}
+
+#[doc(alias = "vDSP_vabsi")]
+pub unsafe extern "C-unwind" fn _abs_i32(
+    a: *const i32,
+    ia: Stride,
+    c: *mut i32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, i32::wrapping_abs) }
+}
+
+#[doc(alias = "vDSP_veqvi")]
+pub unsafe extern "C-unwind" fn _eqv_i32(
+    a: *const i32,
+    ia: Stride,
+    b: *const i32,
+    ib: Stride,
+    c: *mut i32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map2(a, ia, b, ib, c, ic, n, |a, b| !(a ^ b)) }
+}
+
+#[doc(alias = "vDSP_vfill")]
+pub unsafe extern "C-unwind" fn _fill_f32(a: &f32, c: *mut f32, ic: Stride, n: Len) {
+    let a = *a;
+    unsafe { generate(c, ic, n, |_| a) }
+}
+
+#[doc(alias = "vDSP_vfillD")]
+pub unsafe extern "C-unwind" fn _fill_f64(a: &f64, c: *mut f64, ic: Stride, n: Len) {
+    let a = *a;
+    unsafe { generate(c, ic, n, |_| a) }
+}
+
+#[doc(alias = "vDSP_vfilli")]
+pub unsafe extern "C-unwind" fn _fill_i32(a: &i32, c: *mut i32, ic: Stride, n: Len) {
+    let a = *a;
+    unsafe { generate(c, ic, n, |_| a) }
+}
+
+#[doc(alias = "vDSP_vclr")]
+pub unsafe extern "C-unwind" fn _clr_f32(c: *mut f32, ic: Stride, n: Len) {
+    unsafe { generate(c, ic, n, |_| 0.0) }
+}
+
+#[doc(alias = "vDSP_vclrD")]
+pub unsafe extern "C-unwind" fn _clr_f64(c: *mut f64, ic: Stride, n: Len) {
+    unsafe { generate(c, ic, n, |_| 0.0) }
+}
+
+#[doc(alias = "vDSP_vsadd")]
+pub unsafe extern "C-unwind" fn _sadd_f32(
+    a: *const f32,
+    ia: Stride,
+    b: &f32,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    let b = *b;
+    unsafe { map1(a, ia, c, ic, n, |a| a + b) }
+}
+
+#[doc(alias = "vDSP_vsaddD")]
+pub unsafe extern "C-unwind" fn _sadd_f64(
+    a: *const f64,
+    ia: Stride,
+    b: &f64,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    let b = *b;
+    unsafe { map1(a, ia, c, ic, n, |a| a + b) }
+}
+
+#[doc(alias = "vDSP_vsaddi")]
+pub unsafe extern "C-unwind" fn _sadd_i32(
+    a: *const i32,
+    ia: Stride,
+    b: &i32,
+    c: *mut i32,
+    ic: Stride,
+    n: Len,
+) {
+    let b = *b;
+    unsafe { map1(a, ia, c, ic, n, |a| a.wrapping_add(b)) }
+}
+
+#[doc(alias = "vDSP_sve")]
+pub unsafe extern "C-unwind" fn _se_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a) }
+}
+
+#[doc(alias = "vDSP_sveD")]
+pub unsafe extern "C-unwind" fn _se_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a) }
+}
+
+#[doc(alias = "vDSP_svemg")]
+pub unsafe extern "C-unwind" fn _semg_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = sum(a, ia, n, f32::abs) }
+}
+
+#[doc(alias = "vDSP_svemgD")]
+pub unsafe extern "C-unwind" fn _semg_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = sum(a, ia, n, f64::abs) }
+}
+
+#[doc(alias = "vDSP_svesq")]
+pub unsafe extern "C-unwind" fn _sesq_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a * a) }
+}
+
+#[doc(alias = "vDSP_svesqD")]
+pub unsafe extern "C-unwind" fn _sesq_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = sum(a, ia, n, |a| a * a) }
+}
+
+#[doc(alias = "vDSP_svs")]
+pub unsafe extern "C-unwind" fn _svs_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = sum(a, ia, n, ssq) }
+}
+
+#[doc(alias = "vDSP_svsD")]
+pub unsafe extern "C-unwind" fn _svs_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = sum(a, ia, n, ssq) }
+}
+
+#[doc(alias = "vDSP_maxmgv")]
+pub unsafe extern "C-unwind" fn _maxmg_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = reduce(a, ia, n, 0.0, |m, a| max(m, a.abs()), max) }
+}
+
+#[doc(alias = "vDSP_maxmgvD")]
+pub unsafe extern "C-unwind" fn _maxmg_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = reduce(a, ia, n, 0.0, |m, a| max(m, a.abs()), max) }
+}
+
+#[doc(alias = "vDSP_minmgv")]
+pub unsafe extern "C-unwind" fn _minmg_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = reduce(a, ia, n, f32::INFINITY, |m, a| min(m, a.abs()), min) }
+}
+
+#[doc(alias = "vDSP_minmgvD")]
+pub unsafe extern "C-unwind" fn _minmg_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = reduce(a, ia, n, f64::INFINITY, |m, a| min(m, a.abs()), min) }
+}
+
+#[doc(alias = "vDSP_maxv")]
+pub unsafe extern "C-unwind" fn _max_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = reduce(a, ia, n, -f32::INFINITY, max, max) }
+}
+
+#[doc(alias = "vDSP_maxvD")]
+pub unsafe extern "C-unwind" fn _max_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = reduce(a, ia, n, -f64::INFINITY, max, max) }
+}
+
+#[doc(alias = "vDSP_minv")]
+pub unsafe extern "C-unwind" fn _min_f32(a: *const f32, ia: Stride, c: *mut f32, n: Len) {
+    unsafe { *c = reduce(a, ia, n, f32::INFINITY, min, min) }
+}
+
+#[doc(alias = "vDSP_minvD")]
+pub unsafe extern "C-unwind" fn _min_f64(a: *const f64, ia: Stride, c: *mut f64, n: Len) {
+    unsafe { *c = reduce(a, ia, n, f64::INFINITY, min, min) }
+}
+
+#[doc(alias = "vDSP_vgen")]
+pub unsafe extern "C-unwind" fn _gen_f32(a: &f32, b: &f32, c: *mut f32, ic: Stride, n: Len) {
+    unsafe { gen_(a, b, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_vgenD")]
+pub unsafe extern "C-unwind" fn _gen_f64(a: &f64, b: &f64, c: *mut f64, ic: Stride, n: Len) {
+    unsafe { gen_(a, b, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_vramp")]
+pub unsafe extern "C-unwind" fn _ramp_f32(a: &f32, b: &f32, c: *mut f32, ic: Stride, n: Len) {
+    let (a, b) = (*a, *b);
+    unsafe { generate(c, ic, n, |i| a + i as f32 * b) }
+}
+
+#[doc(alias = "vDSP_vrampD")]
+pub unsafe extern "C-unwind" fn _ramp_f64(a: &f64, b: &f64, c: *mut f64, ic: Stride, n: Len) {
+    let (a, b) = (*a, *b);
+    unsafe { generate(c, ic, n, |i| a + i as f64 * b) }
+}
+
+#[doc(alias = "vDSP_vrampmul")]
+pub unsafe extern "C-unwind" fn _rampmul_f32(
+    i: *const f32,
+    is: Stride,
+    start: &mut f32,
+    step: &f32,
+    o: *mut f32,
+    os: Stride,
+    n: Len,
+) {
+    unsafe { rampmul(i, is, start, step, o, os, n) }
+}
+
+#[doc(alias = "vDSP_vrampmulD")]
+pub unsafe extern "C-unwind" fn _rampmul_f64(
+    i: *const f64,
+    is: Stride,
+    start: &mut f64,
+    step: &f64,
+    o: *mut f64,
+    os: Stride,
+    n: Len,
+) {
+    unsafe { rampmul(i, is, start, step, o, os, n) }
+}
+
+#[doc(alias = "vDSP_vrampmul2")]
+pub unsafe extern "C-unwind" fn _rampmul2_f32(
+    i0: *const f32,
+    i1: *const f32,
+    is: Stride,
+    start: &mut f32,
+    step: &f32,
+    o0: *mut f32,
+    o1: *mut f32,
+    os: Stride,
+    n: Len,
+) {
+    unsafe { rampmul2(i0, i1, is, start, step, o0, o1, os, n) }
+}
+
+#[doc(alias = "vDSP_vrampmul2D")]
+pub unsafe extern "C-unwind" fn _rampmul2_f64(
+    i0: *const f64,
+    i1: *const f64,
+    is: Stride,
+    start: &mut f64,
+    step: &f64,
+    o0: *mut f64,
+    o1: *mut f64,
+    os: Stride,
+    n: Len,
+) {
+    unsafe { rampmul2(i0, i1, is, start, step, o0, o1, os, n) }
+}
+
+#[doc(alias = "vDSP_vneg")]
+pub unsafe extern "C-unwind" fn _neg_f32(
+    a: *const f32,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, |a: f32| -a) }
+}
+
+#[doc(alias = "vDSP_vnegD")]
+pub unsafe extern "C-unwind" fn _neg_f64(
+    a: *const f64,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, |a: f64| -a) }
+}
+
+#[doc(alias = "vDSP_vtmerg")]
+pub unsafe extern "C-unwind" fn _tmerg_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    ib: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { tmerg(a, ia, b, ib, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_vtmergD")]
+pub unsafe extern "C-unwind" fn _tmerg_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    ib: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { tmerg(a, ia, b, ib, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_ctoz")]
+pub unsafe extern "C-unwind" fn _ctoz_f32(
+    c: *const Complex<f32>,
+    ic: Stride,
+    z: *const SplitComplex<f32>,
+    iz: Stride,
+    n: Len,
+) {
+    unsafe { ctoz(c, ic, z, iz, n) }
+}
+
+#[doc(alias = "vDSP_ctozD")]
+pub unsafe extern "C-unwind" fn _ctoz_f64(
+    c: *const Complex<f64>,
+    ic: Stride,
+    z: *const SplitComplex<f64>,
+    iz: Stride,
+    n: Len,
+) {
+    unsafe { ctoz(c, ic, z, iz, n) }
+}
+
+#[doc(alias = "vDSP_ztoc")]
+pub unsafe extern "C-unwind" fn _ztoc_f32(
+    z: *const SplitComplex<f32>,
+    iz: Stride,
+    c: *mut Complex<f32>,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { ztoc(z, iz, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_ztocD")]
+pub unsafe extern "C-unwind" fn _ztoc_f64(
+    z: *const SplitComplex<f64>,
+    iz: Stride,
+    c: *mut Complex<f64>,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { ztoc(z, iz, c, ic, n) }
+}
+
+/// Only radix 2 transforms are implemented, other radixes only affect table sizes in vDSP.
+#[doc(alias = "vDSP_create_fftsetup")]
+pub unsafe extern "C-unwind" fn _create_fftsetup_f32(
+    log2n: Len,
+    _radix: FftRadix,
+) -> Option<NonNull<FftSetup<f32>>> {
+    create_fftsetup(log2n)
+}
+
+/// Only radix 2 transforms are implemented, other radixes only affect table sizes in vDSP.
+#[doc(alias = "vDSP_create_fftsetupD")]
+pub unsafe extern "C-unwind" fn _create_fftsetup_f64(
+    log2n: Len,
+    _radix: FftRadix,
+) -> Option<NonNull<FftSetup<f64>>> {
+    create_fftsetup(log2n)
+}
+
+#[doc(alias = "vDSP_destroy_fftsetup")]
+pub unsafe extern "C-unwind" fn _destroy_fftsetup_f32(setup: *mut FftSetup<f32>) {
+    if !setup.is_null() {
+        drop(unsafe { Box::from_raw(setup.cast::<Setup<f32>>()) });
+    }
+}
+
+#[doc(alias = "vDSP_destroy_fftsetupD")]
+pub unsafe extern "C-unwind" fn _destroy_fftsetup_f64(setup: *mut FftSetup<f64>) {
+    if !setup.is_null() {
+        drop(unsafe { Box::from_raw(setup.cast::<Setup<f64>>()) });
+    }
+}
+
+#[doc(alias = "vDSP_fft_zip")]
+pub unsafe extern "C-unwind" fn _fft_zip_f32(
+    setup: *mut FftSetup<f32>,
+    c: *mut SplitComplex<f32>,
+    ic: Stride,
+    log2n: Len,
+    direction: FftDirection,
+) {
+    unsafe {
+        let c = &*c;
+        setup_ref(setup).zip(c.re, c.im, ic, log2n, direction)
+    }
+}
+
+#[doc(alias = "vDSP_fft_zipD")]
+pub unsafe extern "C-unwind" fn _fft_zip_f64(
+    setup: *mut FftSetup<f64>,
+    c: *mut SplitComplex<f64>,
+    ic: Stride,
+    log2n: Len,
+    direction: FftDirection,
+) {
+    unsafe {
+        let c = &*c;
+        setup_ref(setup).zip(c.re, c.im, ic, log2n, direction)
+    }
+}
+
+/// The buffer is not needed by the portable implementation.
+#[doc(alias = "vDSP_fft_zipt")]
+pub unsafe extern "C-unwind" fn _fft_zipt_f32(
+    setup: *mut FftSetup<f32>,
+    c: *const SplitComplex<f32>,
+    ic: Stride,
+    _buffer: *mut SplitComplex<f32>,
+    log2n: Len,
+    direction: FftDirection,
+) {
+    unsafe {
+        let c = &*c;
+        setup_ref(setup).zip(c.re, c.im, ic, log2n, direction)
+    }
+}
+
+/// The buffer is not needed by the portable implementation.
+#[doc(alias = "vDSP_fft_ziptD")]
+pub unsafe extern "C-unwind" fn _fft_zipt_f64(
+    setup: *mut FftSetup<f64>,
+    c: *const SplitComplex<f64>,
+    ic: Stride,
+    _buffer: *mut SplitComplex<f64>,
+    log2n: Len,
+    direction: FftDirection,
+) {
+    unsafe {
+        let c = &*c;
+        setup_ref(setup).zip(c.re, c.im, ic, log2n, direction)
+    }
+}
+
+#[doc(alias = "vDSP_fft_zrip")]
+pub unsafe extern "C-unwind" fn _fft_zrip_f32(
+    setup: *mut FftSetup<f32>,
+    c: *const SplitComplex<f32>,
+    ic: Stride,
+    log2n: Len,
+    direction: FftDirection,
+) {
+    unsafe {
+        let c = &*c;
+        setup_ref(setup).zrip(c.re, c.im, ic, log2n, direction)
+    }
+}
+
+#[doc(alias = "vDSP_fft_zripD")]
+pub unsafe extern "C-unwind" fn _fft_zrip_f64(
+    setup: *mut FftSetup<f64>,
+    c: *const SplitComplex<f64>,
+    ic: Stride,
+    log2n: Len,
+    direction: FftDirection,
+) {
+    unsafe {
+        let c = &*c;
+        setup_ref(setup).zrip(c.re, c.im, ic, log2n, direction)
+    }
+}
+
+#[doc(alias = "vDSP_zaspec")]
+pub unsafe extern "C-unwind" fn _zaspec_f32(a: *const SplitComplex<f32>, c: *mut f32, n: Len) {
+    unsafe { zaspec(a, c, n) }
+}
+
+#[doc(alias = "vDSP_zaspecD")]
+pub unsafe extern "C-unwind" fn _zaspec_f64(a: *const SplitComplex<f64>, c: *mut f64, n: Len) {
+    unsafe { zaspec(a, c, n) }
+}
+
+#[doc(alias = "vDSP_vflt16")]
+pub unsafe extern "C-unwind" fn _i16_f32(
+    a: *const i16,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, f32::from) }
+}
+
+#[doc(alias = "vDSP_vfltu16")]
+pub unsafe extern "C-unwind" fn _u16_f32(
+    a: *const u16,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { map1(a, ia, c, ic, n, f32::from) }
+}
+
+#[doc(alias = "vDSP_vdbcon")]
+pub unsafe extern "C-unwind" fn _dbcon_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+    f: u32,
+) {
+    unsafe { dbcon(a, ia, b, c, ic, n, f) }
+}
+
+#[doc(alias = "vDSP_vdbconD")]
+pub unsafe extern "C-unwind" fn _dbcon_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+    f: u32,
+) {
+    unsafe { dbcon(a, ia, b, c, ic, n, f) }
+}
+
+#[doc(alias = "vDSP_vclip")]
+pub unsafe extern "C-unwind" fn _clip_f32(
+    a: *const f32,
+    ia: Stride,
+    b: *const f32,
+    c: *const f32,
+    d: *mut f32,
+    id: Stride,
+    n: Len,
+) {
+    unsafe { clip(a, ia, b, c, d, id, n) }
+}
+
+#[doc(alias = "vDSP_vclipD")]
+pub unsafe extern "C-unwind" fn _clip_f64(
+    a: *const f64,
+    ia: Stride,
+    b: *const f64,
+    c: *const f64,
+    d: *mut f64,
+    id: Stride,
+    n: Len,
+) {
+    unsafe { clip(a, ia, b, c, d, id, n) }
+}
+
+#[doc(alias = "vDSP_zvmags")]
+pub unsafe extern "C-unwind" fn _zmags_f32(
+    a: *const SplitComplex<f32>,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { zmags(a, ia, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_zvmagsD")]
+pub unsafe extern "C-unwind" fn _zmags_f64(
+    a: *const SplitComplex<f64>,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { zmags(a, ia, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_zvabs")]
+pub unsafe extern "C-unwind" fn _zabs_f32(
+    a: *const SplitComplex<f32>,
+    ia: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { zabs(a, ia, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_zvabsD")]
+pub unsafe extern "C-unwind" fn _zabs_f64(
+    a: *const SplitComplex<f64>,
+    ia: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+) {
+    unsafe { zabs(a, ia, c, ic, n) }
+}
+
+#[doc(alias = "vDSP_hann_window")]
+pub unsafe extern "C-unwind" fn _hann_window_f32(c: *mut f32, n: Len, flags: WindowFlags) {
+    unsafe { hann_window(c, n, flags) }
+}
+
+#[doc(alias = "vDSP_hann_windowD")]
+pub unsafe extern "C-unwind" fn _hann_window_f64(c: *mut f64, n: Len, flags: WindowFlags) {
+    unsafe { hann_window(c, n, flags) }
+}
+
+#[doc(alias = "vDSP_hamm_window")]
+pub unsafe extern "C-unwind" fn _hamm_window_f32(c: *mut f32, n: Len, flags: WindowFlags) {
+    unsafe { hamm_window(c, n, flags) }
+}
+
+#[doc(alias = "vDSP_hamm_windowD")]
+pub unsafe extern "C-unwind" fn _hamm_window_f64(c: *mut f64, n: Len, flags: WindowFlags) {
+    unsafe { hamm_window(c, n, flags) }
+}
+
+#[doc(alias = "vDSP_blkman_window")]
+pub unsafe extern "C-unwind" fn _blkman_window_f32(c: *mut f32, n: Len, flags: WindowFlags) {
+    unsafe { blkman_window(c, n, flags) }
+}
+
+#[doc(alias = "vDSP_blkman_windowD")]
+pub unsafe extern "C-unwind" fn _blkman_window_f64(c: *mut f64, n: Len, flags: WindowFlags) {
+    unsafe { blkman_window(c, n, flags) }
+}
+
+#[doc(alias = "vDSP_conv")]
+pub unsafe extern "C-unwind" fn _conv_f32(
+    a: *const f32,
+    ia: Stride,
+    f: *const f32,
+    if_: Stride,
+    c: *mut f32,
+    ic: Stride,
+    n: Len,
+    p: Len,
+) {
+    unsafe { conv(a, ia, f, if_, c, ic, n, p) }
+}
+
+#[doc(alias = "vDSP_convD")]
+pub unsafe extern "C-unwind" fn _conv_f64(
+    a: *const f64,
+    ia: Stride,
+    f: *const f64,
+    if_: Stride,
+    c: *mut f64,
+    ic: Stride,
+    n: Len,
+    p: Len,
+) {
+    unsafe { conv(a, ia, f, if_, c, ic, n, p) }
+}
+
+#[doc(alias = "vDSP_desamp")]
+pub unsafe extern "C-unwind" fn _desamp_f32(
+    a: *const f32,
+    df: Stride,
+    f: *const f32,
+    c: *mut f32,
+    n: Len,
+    p: Len,
+) {
+    unsafe { desamp(a, df, f, c, n, p) }
+}
+
+#[doc(alias = "vDSP_desampD")]
+pub unsafe extern "C-unwind" fn _desamp_f64(
+    a: *const f64,
+    df: Stride,
+    f: *const f64,
+    c: *mut f64,
+    n: Len,
+    p: Len,
+) {
+    unsafe { desamp(a, df, f, c, n, p) }
+}
+
+#[doc(alias = "vDSP_biquad_CreateSetup")]
+pub unsafe extern "C-unwind" fn _biquad_create_setup_f32(
+    coefs: *const f64,
+    m: Len,
+) -> Option<NonNull<BiquadSetup<f32>>> {
+    biquad_create_setup(coefs, m)
+}
+
+#[doc(alias = "vDSP_biquad_CreateSetupD")]
+pub unsafe extern "C-unwind" fn _biquad_create_setup_f64(
+    coefs: *const f64,
+    m: Len,
+) -> Option<NonNull<BiquadSetup<f64>>> {
+    biquad_create_setup(coefs, m)
+}
+
+#[doc(alias = "vDSP_biquad_DestroySetup")]
+pub unsafe extern "C-unwind" fn _biquad_destroy_setup_f32(setup: *mut BiquadSetup<f32>) {
+    if !setup.is_null() {
+        drop(unsafe { Box::from_raw(setup.cast::<Sections<f32>>()) });
+    }
+}
+
+#[doc(alias = "vDSP_biquad_DestroySetupD")]
+pub unsafe extern "C-unwind" fn _biquad_destroy_setup_f64(setup: *mut BiquadSetup<f64>) {
+    if !setup.is_null() {
+        drop(unsafe { Box::from_raw(setup.cast::<Sections<f64>>()) });
+    }
+}
+
+#[doc(alias = "vDSP_biquad")]
+pub unsafe extern "C-unwind" fn _biquad_f32(
+    setup: *const BiquadSetup<f32>,
+    delay: *mut f32,
+    x: *const f32,
+    ix: Stride,
+    y: *mut f32,
+    iy: Stride,
+    n: Len,
+) {
+    unsafe { biquad(setup, delay, x, ix, y, iy, n) }
+}
+
+#[doc(alias = "vDSP_biquadD")]
+pub unsafe extern "C-unwind" fn _biquad_f64(
+    setup: *const BiquadSetup<f64>,
+    delay: *mut f64,
+    x: *const f64,
+    ix: Stride,
+    y: *mut f64,
+    iy: Stride,
+    n: Len,
+) {
+    unsafe { biquad(setup, delay, x, ix, y, iy, n) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr::NonNull;
+
+    use crate::vdsp::{
+        self, BiquadSetup, Complex, FftDirection, FftRadix, FftSetup, Len, SplitComplex, Stride,
+    };
+
+    type Unary<A, C> = unsafe extern "C-unwind" fn(*const A, Stride, *mut C, Stride, Len);
+    type Binary<T> =
+        unsafe extern "C-unwind" fn(*const T, Stride, *const T, Stride, *mut T, Stride, Len);
+    type Scalar<T> = unsafe extern "C-unwind" fn(*const T, Stride, &T, *mut T, Stride, Len);
+    type Ternary<T> = unsafe extern "C-unwind" fn(
+        *const T,
+        Stride,
+        *const T,
+        Stride,
+        *const T,
+        Stride,
+        *mut T,
+        Stride,
+        Len,
+    );
+    type Reduce<T> = unsafe extern "C-unwind" fn(*const T, Stride, *mut T, Len);
+    type Reduce2<T> = unsafe extern "C-unwind" fn(*const T, Stride, *const T, Stride, *mut T, Len);
+    type Create<T> = unsafe extern "C-unwind" fn(Len, FftRadix) -> Option<NonNull<FftSetup<T>>>;
+    type Zip<T> = unsafe extern "C-unwind" fn(
+        *mut FftSetup<T>,
+        *mut SplitComplex<T>,
+        Stride,
+        Len,
+        FftDirection,
+    );
+    type Zrip<T> = unsafe extern "C-unwind" fn(
+        *mut FftSetup<T>,
+        *const SplitComplex<T>,
+        Stride,
+        Len,
+        FftDirection,
+    );
+    type Destroy<T> = unsafe extern "C-unwind" fn(*mut FftSetup<T>);
+    type Conv<T> =
+        unsafe extern "C-unwind" fn(*const T, Stride, *const T, Stride, *mut T, Stride, Len, Len);
+    type BiquadCreate<T> =
+        unsafe extern "C-unwind" fn(*const f64, Len) -> Option<NonNull<BiquadSetup<T>>>;
+    type BiquadRun<T> = unsafe extern "C-unwind" fn(
+        *const BiquadSetup<T>,
+        *mut T,
+        *const T,
+        Stride,
+        *mut T,
+        Stride,
+        Len,
+    );
+    type BiquadDestroy<T> = unsafe extern "C-unwind" fn(*mut BiquadSetup<T>);
+
+    const F32: f64 = 1e-5;
+    const F64: f64 = 1e-12;
+
+    /// Evaluates the body with the portable implementation and, on Apple targets,
+    /// with the Accelerate one.
+    macro_rules! backends {
+        ($($f:ident),+ => |$arg:pat_param| $body:expr) => {{
+            #[allow(unused_mut)]
+            let mut res = vec![("portable", {
+                let $arg = ($(super::$f),+);
+                $body
+            })];
+            #[cfg(target_vendor = "apple")]
+            res.push(("accelerate", {
+                let $arg = ($(vdsp::$f),+);
+                $body
+            }));
+            res
+        }};
+    }
+
+    fn close(got: f64, want: f64, tol: f64) -> bool {
+        got == want
+            || (got.is_nan() && want.is_nan())
+            || (got - want).abs() <= tol * want.abs().max(1.0)
+    }
+
+    /// Every back-end has to match the reference and all back-ends have to agree.
+    #[track_caller]
+    fn check<T: Copy + Into<f64>>(res: Vec<(&str, Vec<T>)>, want: &[f64], tol: f64) {
+        let (_, first) = &res[0];
+        for (name, got) in &res {
+            assert_eq!(got.len(), want.len(), "{name}");
+            for (i, ((g, w), f)) in got.iter().zip(want).zip(first).enumerate() {
+                let (g, f) = ((*g).into(), (*f).into());
+                assert!(close(g, *w, tol), "{name}[{i}]: {g} != {w}");
+                assert!(close(g, f, tol), "{name}[{i}]: {g} != portable {f}");
+            }
+        }
+    }
+
+    /// Deterministic noise in [-1, 1)
+    fn signal(n: usize, seed: u32) -> Vec<f64> {
+        let mut state = seed.wrapping_mul(747796405).wrapping_add(2891336453);
+        (0..n)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state >> 8) as f64 / (1u32 << 23) as f64 - 1.0
+            })
+            .collect()
+    }
+
+    fn f32s(a: &[f64]) -> Vec<f32> {
+        a.iter().map(|v| *v as f32).collect()
+    }
+
+    fn spread<T: Copy + Default>(a: &[T], stride: usize) -> Vec<T> {
+        let mut res = vec![T::default(); a.len() * stride];
+        for (dst, v) in res.iter_mut().step_by(stride).zip(a) {
+            *dst = *v;
+        }
+        res
+    }
+
+    fn unary<A: Copy, C: Copy + Default>(f: Unary<A, C>, a: &[A]) -> Vec<C> {
+        let mut c = vec![C::default(); a.len()];
+        unsafe { f(a.as_ptr(), 1, c.as_mut_ptr(), 1, a.len()) };
+        c
+    }
+
+    fn binary<T: Copy + Default>(f: Binary<T>, a: &[T], b: &[T], stride: usize) -> Vec<T> {
+        let n = a.len();
+        let (a, b) = (spread(a, stride), spread(b, stride));
+        let mut c = vec![T::default(); n * stride];
+        let s = stride as Stride;
+        unsafe { f(a.as_ptr(), s, b.as_ptr(), s, c.as_mut_ptr(), s, n) };
+        c.into_iter().step_by(stride).collect()
+    }
+
+    fn reduce<T: Copy + Default>(f: Reduce<T>, a: &[T], stride: usize) -> Vec<T> {
+        let a = spread(a, stride);
+        let mut c = T::default();
+        unsafe { f(a.as_ptr(), stride as _, &mut c, a.len() / stride) };
+        vec![c]
+    }
+
+    fn zip(a: &[f64], b: &[f64], f: impl Fn(f64, f64) -> f64) -> Vec<f64> {
+        a.iter().zip(b).map(|(a, b)| f(*a, *b)).collect()
+    }
+
+    #[test]
+    fn binary_ops() {
+        for n in [0, 1, 7, 8, 37] {
+            let a = signal(n, 1);
+            let b: Vec<f64> = signal(n, 2).iter().map(|v| v + 2.0).collect();
+            let (a32, b32) = (f32s(&a), f32s(&b));
+            for stride in [1, 3] {
+                let res = backends!(_add_f32 => |f| binary(f, &a32, &b32, stride));
+                check(res, &zip(&a, &b, |a, b| a + b), F32);
+                let res = backends!(_sub_f32 => |f| binary(f, &b32, &a32, stride));
+                check(res, &zip(&a, &b, |a, b| a - b), F32);
+                let res = backends!(_mul_f32 => |f| binary(f, &a32, &b32, stride));
+                check(res, &zip(&a, &b, |a, b| a * b), F32);
+                let res = backends!(_div_f32 => |f| binary(f, &b32, &a32, stride));
+                check(res, &zip(&a, &b, |a, b| a / b), F32);
+
+                let res = backends!(_add_f64 => |f| binary(f, &a, &b, stride));
+                check(res, &zip(&a, &b, |a, b| a + b), F64);
+                let res = backends!(_sub_f64 => |f| binary(f, &b, &a, stride));
+                check(res, &zip(&a, &b, |a, b| a - b), F64);
+                let res = backends!(_mul_f64 => |f| binary(f, &a, &b, stride));
+                check(res, &zip(&a, &b, |a, b| a * b), F64);
+                let res = backends!(_div_f64 => |f| binary(f, &b, &a, stride));
+                check(res, &zip(&a, &b, |a, b| a / b), F64);
+            }
+        }
+    }
+
+    #[test]
+    fn unary_ops() {
+        let a = signal(37, 3);
+        let a32 = f32s(&a);
+
+        let res = backends!(_abs_f32 => |f| unary(f, &a32));
+        check(res, &a.iter().map(|v| v.abs()).collect::<Vec<_>>(), F32);
+        let res = backends!(_neg_f64 => |f| unary(f, &a));
+        check(res, &a.iter().map(|v| -v).collect::<Vec<_>>(), F64);
+        let res = backends!(_sq_f32 => |f| unary(f, &a32));
+        check(res, &a.iter().map(|v| v * v).collect::<Vec<_>>(), F32);
+        let res = backends!(_ssq_f64 => |f| unary(f, &a));
+        check(res, &a.iter().map(|v| v * v.abs()).collect::<Vec<_>>(), F64);
+        let res = backends!(_f64_f32 => |f| unary(f, &a));
+        check(res, &a, F32);
+        let res = backends!(_f32_f64 => |f| unary(f, &a32));
+        check(res, &a, F32);
+
+        let ints: Vec<i16> = (-20..20).map(|v| v * 1000).collect();
+        let want: Vec<f64> = ints.iter().map(|v| *v as f64).collect();
+        let res = backends!(_i16_f32 => |f| unary(f, &ints));
+        check(res, &want, 0.0);
+        let uints: Vec<u16> = (0..40).map(|v| v * 1600).collect();
+        let want: Vec<f64> = uints.iter().map(|v| *v as f64).collect();
+        let res = backends!(_u16_f32 => |f| unary(f, &uints));
+        check(res, &want, 0.0);
+
+        let scalar = |f: Scalar<f32>, b: f32| {
+            let mut c = vec![0.0; a32.len()];
+            unsafe { f(a32.as_ptr(), 1, &b, c.as_mut_ptr(), 1, a32.len()) };
+            c
+        };
+        let res = backends!(_smul_f32 => |f| scalar(f, -0.5));
+        check(res, &a.iter().map(|v| v * -0.5).collect::<Vec<_>>(), F32);
+        let res = backends!(_sadd_f32 => |f| scalar(f, 0.25));
+        check(res, &a.iter().map(|v| v + 0.25).collect::<Vec<_>>(), F32);
+
+        let res = backends!(_clip_f32 => |f| {
+            let mut c = vec![0.0; a32.len()];
+            unsafe { f(a32.as_ptr(), 1, &-0.5, &0.25, c.as_mut_ptr(), 1, a32.len()) };
+            c
+        });
+        check(
+            res,
+            &a.iter().map(|v| v.clamp(-0.5, 0.25)).collect::<Vec<_>>(),
+            F32,
+        );
+
+        let mag: Vec<f64> = a.iter().map(|v| v.abs() + 0.01).collect();
+        for (flag, alpha) in [(0, 10.0), (1, 20.0)] {
+            let res = backends!(_dbcon_f64 => |f| {
+                let mut c = vec![0.0; mag.len()];
+                unsafe { f(mag.as_ptr(), 1, &0.5, c.as_mut_ptr(), 1, mag.len(), flag) };
+                c
+            });
+            let want: Vec<f64> = mag.iter().map(|v| alpha * (v / 0.5).log10()).collect();
+            check(res, &want, F64);
+        }
+    }
+
+    #[test]
+    fn ternary_ops() {
+        let (a, b, c) = (signal(37, 4), signal(37, 5), signal(37, 6));
+        let (a32, b32, c32) = (f32s(&a), f32s(&b), f32s(&c));
+        let ternary = |f: Ternary<f32>| {
+            let mut d = vec![0.0; a32.len()];
+            unsafe {
+                f(
+                    a32.as_ptr(),
+                    1,
+                    b32.as_ptr(),
+                    1,
+                    c32.as_ptr(),
+                    1,
+                    d.as_mut_ptr(),
+                    1,
+                    a32.len(),
+                )
+            };
+            d
+        };
+        let want: Vec<f64> = (0..a.len()).map(|i| (a[i] + b[i]) * c[i]).collect();
+        check(backends!(_am_f32 => |f| ternary(f)), &want, F32);
+        let want: Vec<f64> = (0..a.len()).map(|i| a[i] * b[i] + c[i]).collect();
+        check(backends!(_ma_f32 => |f| ternary(f)), &want, F32);
+    }
+
+    #[test]
+    fn in_place() {
+        let a = signal(37, 7);
+        let mut io = f32s(&a);
+        let ptr = io.as_mut_ptr();
+        unsafe { super::_mul_f32(ptr, 1, ptr, 1, ptr, 1, io.len()) };
+        check(vec![("portable", io)], &zip(&a, &a, |a, b| a * b), F32);
+    }
+
+    #[test]
+    fn reductions() {
+        for n in [1, 7, 8, 37, 1000] {
+            let a = signal(n, 8);
+            let b = signal(n, 9);
+            let (a32, b32) = (f32s(&a), f32s(&b));
+            let sum = |f: fn(f64) -> f64| a.iter().map(|v| f(*v)).sum::<f64>();
+            let tol = F32 * n as f64;
+
+            for stride in [1, 2] {
+                let red = |f: Reduce<f32>| reduce(f, &a32, stride);
+                check(backends!(_se_f32 => |f| red(f)), &[sum(|v| v)], tol);
+                check(backends!(_semg_f32 => |f| red(f)), &[sum(f64::abs)], tol);
+                check(backends!(_sesq_f32 => |f| red(f)), &[sum(|v| v * v)], tol);
+                check(
+                    backends!(_svs_f32 => |f| red(f)),
+                    &[sum(|v| v * v.abs())],
+                    tol,
+                );
+                check(
+                    backends!(_mean_f32 => |f| red(f)),
+                    &[sum(|v| v) / n as f64],
+                    tol,
+                );
+                check(
+                    backends!(_meansq_f32 => |f| red(f)),
+                    &[sum(|v| v * v) / n as f64],
+                    tol,
+                );
+
+                let max = a.iter().cloned().fold(f64::MIN, f64::max);
+                let min = a.iter().cloned().fold(f64::MAX, f64::min);
+                let maxmg = a.iter().map(|v| v.abs()).fold(0.0, f64::max);
+                let minmg = a.iter().map(|v| v.abs()).fold(f64::MAX, f64::min);
+                check(backends!(_max_f32 => |f| red(f)), &[max], F32);
+                check(backends!(_min_f32 => |f| red(f)), &[min], F32);
+                check(backends!(_maxmg_f32 => |f| red(f)), &[maxmg], F32);
+                check(backends!(_minmg_f32 => |f| red(f)), &[minmg], F32);
+
+                let red = |f: Reduce<f64>| reduce(f, &a, stride);
+                check(backends!(_se_f64 => |f| red(f)), &[sum(|v| v)], F64);
+                check(backends!(_max_f64 => |f| red(f)), &[max], F64);
+                check(backends!(_minmg_f64 => |f| red(f)), &[minmg], F64);
+            }
+
+            let red2 = |f: Reduce2<f32>| {
+                let mut c = 0.0;
+                unsafe { f(a32.as_ptr(), 1, b32.as_ptr(), 1, &mut c, n) };
+                vec![c]
+            };
+            let dot = zip(&a, &b, |a, b| a * b).iter().sum::<f64>();
+            let dist = zip(&a, &b, |a, b| (a - b) * (a - b)).iter().sum::<f64>();
+            check(backends!(_dotpr_f32 => |f| red2(f)), &[dot], tol);
+            check(backends!(_distance_sq_f32 => |f| red2(f)), &[dist], tol);
+        }
+    }
+
+    #[test]
+    fn empty_reductions() {
+        let red = |f: Reduce<f32>| reduce(f, &[], 1);
+        check(backends!(_se_f32 => |f| red(f)), &[0.0], 0.0);
+        check(backends!(_mean_f32 => |f| red(f)), &[f64::NAN], 0.0);
+        check(backends!(_max_f32 => |f| red(f)), &[f64::NEG_INFINITY], 0.0);
+        check(backends!(_min_f32 => |f| red(f)), &[f64::INFINITY], 0.0);
+        check(backends!(_maxmg_f32 => |f| red(f)), &[0.0], 0.0);
+    }
+
+    #[test]
+    fn ramps() {
+        for n in [1, 3, 9, 37] {
+            let res = backends!(_gen_f32 => |f| {
+                let mut c = vec![0.0f32; n];
+                unsafe { f(&-1.0, &2.0, c.as_mut_ptr(), 1, n) };
+                c
+            });
+            let want: Vec<f64> = if n == 1 {
+                vec![-1.0]
+            } else {
+                (0..n)
+                    .map(|i| -1.0 + 3.0 * i as f64 / (n - 1) as f64)
+                    .collect()
+            };
+            check(res, &want, F32);
+
+            let res = backends!(_ramp_f64 => |f| {
+                let mut c = vec![0.0; n];
+                unsafe { f(&0.5, &0.25, c.as_mut_ptr(), 1, n) };
+                c
+            });
+            check(
+                res,
+                &(0..n).map(|i| 0.5 + 0.25 * i as f64).collect::<Vec<_>>(),
+                F64,
+            );
+        }
+
+        let a = signal(37, 10);
+        let b = signal(37, 11);
+        let (a32, b32) = (f32s(&a), f32s(&b));
+
+        let res = backends!(_rampmul_f32 => |f| {
+            let mut o = vec![0.0f32; a32.len()];
+            let mut start = 0.5f32;
+            unsafe {
+                f(
+                    a32.as_ptr(),
+                    1,
+                    &mut start,
+                    &0.125,
+                    o.as_mut_ptr(),
+                    1,
+                    a32.len(),
+                )
+            };
+            o.push(start);
+            o
+        });
+        let mut want: Vec<f64> = (0..a.len())
+            .map(|i| (0.5 + 0.125 * i as f64) * a[i])
+            .collect();
+        want.push(0.5 + 0.125 * a.len() as f64);
+        check(res, &want, F32);
+
+        let res = backends!(_rampmul2_f32 => |f| {
+            let mut o0 = vec![0.0f32; a32.len()];
+            let mut o1 = vec![0.0f32; a32.len()];
+            let mut start = 1.0f32;
+            unsafe {
+                f(
+                    a32.as_ptr(),
+                    b32.as_ptr(),
+                    1,
+                    &mut start,
+                    &-0.0625,
+                    o0.as_mut_ptr(),
+                    o1.as_mut_ptr(),
+                    1,
+                    a32.len(),
+                )
+            };
+            o0.extend(o1);
+            o0
+        });
+        let gain = |i: usize| 1.0 - 0.0625 * i as f64;
+        let want: Vec<f64> = (0..a.len())
+            .map(|i| gain(i) * a[i])
+            .chain((0..b.len()).map(|i| gain(i) * b[i]))
+            .collect();
+        check(res, &want, F32);
+
+        let res = backends!(_tmerg_f32 => |f| binary(f, &a32, &b32, 1));
+        let want: Vec<f64> = (0..a.len())
+            .map(|i| a[i] + (b[i] - a[i]) * i as f64 / (a.len() - 1) as f64)
+            .collect();
+        check(res, &want, F32);
+    }
+
+    #[test]
+    fn integers() {
+        let a: Vec<i32> = (-20..20).map(|v| v * 7919).collect();
+        let b: Vec<i32> = (-20..20).map(|v| v * 3 + 1).collect();
+        let want = |f: fn(i32, i32) -> i32| -> Vec<f64> {
+            a.iter().zip(&b).map(|(a, b)| f(*a, *b) as f64).collect()
+        };
+        check(
+            backends!(_add_i32 => |f| binary(f, &a, &b, 1)),
+            &want(|a, b| a + b),
+            0.0,
+        );
+        check(
+            backends!(_sub_i32 => |f| binary(f, &b, &a, 1)),
+            &want(|a, b| a - b),
+            0.0,
+        );
+        check(
+            backends!(_div_i32 => |f| binary(f, &b, &a, 1)),
+            &want(|a, b| a / b),
+            0.0,
+        );
+        check(
+            backends!(_eqv_i32 => |f| binary(f, &a, &b, 1)),
+            &want(|a, b| !(a ^ b)),
+            0.0,
+        );
+        check(
+            backends!(_abs_i32 => |f| unary(f, &a)),
+            &want(|a, _| a.abs()),
+            0.0,
+        );
+
+        let res = backends!(_sadd_i32 => |f| {
+            let mut c = vec![0; a.len()];
+            unsafe { f(a.as_ptr(), 1, &5, c.as_mut_ptr(), 1, a.len()) };
+            c
+        });
+        check(res, &want(|a, _| a + 5), 0.0);
+
+        let res = backends!(_fill_i32 => |f| {
+            let mut c = vec![0; 11];
+            unsafe { f(&-3, c.as_mut_ptr(), 1, c.len()) };
+            c
+        });
+        check(res, &[-3.0; 11], 0.0);
+    }
+
+    #[test]
+    fn complex() {
+        let a = signal(38, 12);
+        let a32 = f32s(&a);
+        let c: Vec<Complex<f32>> = a32
+            .chunks(2)
+            .map(|v| Complex { re: v[0], im: v[1] })
+            .collect();
+        let n = c.len();
+
+        let res = backends!(_ctoz_f32 => |f| {
+            let (mut re, mut im) = (vec![0.0; n], vec![0.0; n]);
+            let split = SplitComplex::new_mut(&mut re, &mut im);
+            unsafe { f(c.as_ptr(), 2, &split, 1, n) };
+            re.extend(im);
+            re
+        });
+        let want: Vec<f64> = a
+            .iter()
+            .step_by(2)
+            .chain(a.iter().skip(1).step_by(2))
+            .cloned()
+            .collect();
+        check(res, &want, F32);
+
+        let (re, im): (Vec<f32>, Vec<f32>) = c.iter().map(|c| (c.re, c.im)).unzip();
+        let res = backends!(_ztoc_f32 => |f| {
+            let mut out = vec![Complex::default(); n];
+            let split = SplitComplex::new(&re, &im);
+            unsafe { f(&split, 1, out.as_mut_ptr(), 2, n) };
+            out.iter().flat_map(|c| [c.re, c.im]).collect()
+        });
+        check(res, &a, F32);
+
+        let res = backends!(_zaspec_f32 => |f| {
+            let mut out = vec![1.0f32; n];
+            let split = SplitComplex::new(&re, &im);
+            unsafe { f(&split, out.as_mut_ptr(), n) };
+            out
+        });
+        let want: Vec<f64> = a
+            .chunks(2)
+            .map(|v| 1.0 + v[0] * v[0] + v[1] * v[1])
+            .collect();
+        check(res, &want, F32);
+    }
+
+    #[test]
+    fn complex_magnitudes() {
+        let (re, im) = (signal(37, 18), signal(37, 19));
+        let (re32, im32) = (f32s(&re), f32s(&im));
+        let mags = zip(&re, &im, |r, i| r * r + i * i);
+        let abs: Vec<f64> = mags.iter().map(|v| v.sqrt()).collect();
+
+        let res = backends!(_zmags_f32 => |f| {
+            let mut c = vec![0.0f32; re32.len()];
+            let split = SplitComplex::new(&re32, &im32);
+            unsafe { f(&split, 1, c.as_mut_ptr(), 1, c.len()) };
+            c
+        });
+        check(res, &mags, F32);
+
+        let res = backends!(_zabs_f64 => |f| {
+            let mut c = vec![0.0; re.len()];
+            let split = SplitComplex::new(&re, &im);
+            unsafe { f(&split, 1, c.as_mut_ptr(), 1, c.len()) };
+            c
+        });
+        check(res, &abs, F64);
+    }
+
+    #[test]
+    fn windows() {
+        use vdsp::WindowFlags;
+
+        let n = 33;
+        let phase = |i: usize| std::f64::consts::TAU * i as f64 / n as f64;
+        let hann: Vec<f64> = (0..n).map(|i| 0.5 * (1.0 - phase(i).cos())).collect();
+        let hamm: Vec<f64> = (0..n).map(|i| 0.54 - 0.46 * phase(i).cos()).collect();
+        let blkman: Vec<f64> = (0..n)
+            .map(|i| 0.42 - 0.5 * phase(i).cos() + 0.08 * (2.0 * phase(i)).cos())
+            .collect();
+
+        let window = |f: unsafe extern "C-unwind" fn(*mut f32, Len, WindowFlags), flags| {
+            let mut c = vec![-1.0f32; n];
+            unsafe { f(c.as_mut_ptr(), n, flags) };
+            c
+        };
+        let res = backends!(_hann_window_f32 => |f| window(f, WindowFlags::HANN_DENORM));
+        check(res, &hann, F32);
+        let res = backends!(_hann_window_f32 => |f| window(f, WindowFlags::HANN_NORM));
+        let norm: Vec<f64> = hann.iter().map(|v| v * 2.0 * 0.8165).collect();
+        check(res, &norm, 1e-4);
+        let res = backends!(_hamm_window_f32 => |f| window(f, WindowFlags::DEFAULT));
+        check(res, &hamm, F32);
+        let res = backends!(_blkman_window_f32 => |f| window(f, WindowFlags::DEFAULT));
+        check(res, &blkman, F32);
+
+        // the second half is left untouched
+        let res = backends!(_hamm_window_f32 => |f| window(f, WindowFlags::HALF_WINDOW));
+        let mut half = hamm[..n.div_ceil(2)].to_vec();
+        half.resize(n, -1.0);
+        check(res, &half, F32);
+
+        let res = backends!(_hann_window_f64 => |f| {
+            let mut c = vec![0.0; n];
+            unsafe { f(c.as_mut_ptr(), n, WindowFlags::DEFAULT) };
+            c
+        });
+        check(res, &hann, F32);
+    }
+
+    /// Not scaled, e^(-2πikn/N) for forward
+    fn dft(re: &[f64], im: &[f64], direction: FftDirection) -> (Vec<f64>, Vec<f64>) {
+        let n = re.len();
+        let sign = if direction == FftDirection::Forward {
+            -1.0
+        } else {
+            1.0
+        };
+        (0..n)
+            .map(|k| {
+                (0..n).fold((0.0, 0.0), |(sr, si), j| {
+                    let theta = sign * std::f64::consts::TAU * (k * j % n) as f64 / n as f64;
+                    let (c, s) = (theta.cos(), theta.sin());
+                    (sr + re[j] * c - im[j] * s, si + re[j] * s + im[j] * c)
+                })
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn fft_zip() {
+        for log2n in [0, 1, 2, 5, 10] {
+            let n = 1usize << log2n;
+            let (re, im) = (signal(n, 13), signal(n, 14));
+            let (re32, im32) = (f32s(&re), f32s(&im));
+            for direction in [FftDirection::Forward, FftDirection::Inverse] {
+                let (want_re, want_im) = dft(&re, &im, direction);
+                let mut want = want_re;
+                want.extend(want_im);
+
+                let transform = |create: Create<f32>, zip: Zip<f32>, destroy: Destroy<f32>| {
+                    // strided, to cover the non-contiguous path as well
+                    let (mut r, mut i) = (spread(&re32, 2), spread(&im32, 2));
+                    unsafe {
+                        let setup = create(log2n + 1, FftRadix::_2).unwrap().as_ptr();
+                        let mut split = SplitComplex::new_mut(&mut r, &mut i);
+                        zip(setup, &mut split, 2, log2n, direction);
+                        destroy(setup);
+                    }
+                    r.into_iter()
+                        .step_by(2)
+                        .chain(i.into_iter().step_by(2))
+                        .collect()
+                };
+                let res = backends!(
+                    _create_fftsetup_f32, _fft_zip_f32, _destroy_fftsetup_f32
+                        => |(c, z, d)| transform(c, z, d)
+                );
+                check(res, &want, F32 * 4.0 * n as f64);
+
+                let transform = |create: Create<f64>, zip: Zip<f64>, destroy: Destroy<f64>| {
+                    let (mut r, mut i) = (re.clone(), im.clone());
+                    unsafe {
+                        let setup = create(log2n, FftRadix::_2).unwrap().as_ptr();
+                        let mut split = SplitComplex::new_mut(&mut r, &mut i);
+                        zip(setup, &mut split, 1, log2n, direction);
+                        destroy(setup);
+                    }
+                    r.extend(i);
+                    r
+                };
+                let res = backends!(
+                    _create_fftsetup_f64, _fft_zip_f64, _destroy_fftsetup_f64
+                        => |(c, z, d)| transform(c, z, d)
+                );
+                check(res, &want, F64 * n as f64);
+            }
+        }
+    }
+
+    #[test]
+    fn fft_zrip() {
+        for log2n in [1, 2, 3, 6, 10] {
+            let n = 1usize << log2n;
+            let h = n / 2;
+            let x = signal(n, 15);
+            let (spec_re, spec_im) = dft(&x, &vec![0.0; n], FftDirection::Forward);
+
+            // packed: 2 * X[k], with X[N/2] in im[0]
+            let mut want: Vec<f64> = (0..h).map(|k| 2.0 * spec_re[k]).collect();
+            want.extend((0..h).map(|k| 2.0 * spec_im[k]));
+            want[h] = 2.0 * spec_re[h];
+
+            let transform = |create: Create<f64>,
+                             zrip: Zrip<f64>,
+                             destroy: Destroy<f64>,
+                             direction,
+                             input: &[f64]| {
+                let mut r: Vec<f64> = input[..h].to_vec();
+                let mut i: Vec<f64> = input[h..].to_vec();
+                unsafe {
+                    let setup = create(log2n, FftRadix::_2).unwrap().as_ptr();
+                    let split = SplitComplex::new_mut(&mut r, &mut i);
+                    zrip(setup, &split, 1, log2n, direction);
+                    destroy(setup);
+                }
+                r.extend(i);
+                r
+            };
+
+            // even samples in re, odd samples in im
+            let packed: Vec<f64> = x
+                .iter()
+                .step_by(2)
+                .chain(x.iter().skip(1).step_by(2))
+                .cloned()
+                .collect();
+            let res = backends!(
+                _create_fftsetup_f64, _fft_zrip_f64, _destroy_fftsetup_f64
+                    => |(c, z, d)| transform(c, z, d, FftDirection::Forward, &packed)
+            );
+            check(res, &want, F64 * n as f64);
+
+            // inverse of the packed spectrum is 2N times the signal
+            let res = backends!(
+                _create_fftsetup_f64, _fft_zrip_f64, _destroy_fftsetup_f64
+                    => |(c, z, d)| transform(c, z, d, FftDirection::Inverse, &want)
+            );
+            let scaled: Vec<f64> = packed.iter().map(|v| v * 2.0 * n as f64).collect();
+            check(res, &scaled, F64 * n as f64);
+        }
+    }
+
+    #[test]
+    fn filtering() {
+        let (n, p) = (37, 5);
+        let a = signal(n + p - 1, 18);
+        let f = signal(p, 19);
+        let a32 = f32s(&a);
+        let f32 = f32s(&f);
+
+        let corr: Vec<f64> = (0..n)
+            .map(|k| (0..p).map(|i| a[k + i] * f[i]).sum())
+            .collect();
+        let conv: Vec<f64> = (0..n)
+            .map(|k| (0..p).map(|i| a[k + i] * f[p - 1 - i]).sum())
+            .collect();
+
+        let run = |conv: Conv<f64>, backwards: bool, stride: usize| {
+            let a = spread(&a, stride);
+            let mut c = vec![0.0; n * stride];
+            let (fp, fs) = if backwards {
+                (f[p - 1..].as_ptr(), -1)
+            } else {
+                (f.as_ptr(), 1)
+            };
+            let s = stride as Stride;
+            unsafe { conv(a.as_ptr(), s, fp, fs, c.as_mut_ptr(), s, n, p) };
+            c.into_iter().step_by(stride).collect::<Vec<_>>()
+        };
+        for stride in [1, 2] {
+            check(
+                backends!(_conv_f64 => |f| run(f, false, stride)),
+                &corr,
+                F64,
+            );
+            check(backends!(_conv_f64 => |f| run(f, true, stride)), &conv, F64);
+        }
+        let res = backends!(_conv_f32 => |conv| {
+            let mut c = vec![0.0f32; n];
+            unsafe { conv(a32.as_ptr(), 1, f32.as_ptr(), 1, c.as_mut_ptr(), 1, n, p) };
+            c
+        });
+        check(res, &corr, F32);
+
+        let df = 3;
+        let m = (a.len() - p) / df + 1;
+        let want: Vec<f64> = (0..m)
+            .map(|k| (0..p).map(|i| a[k * df + i] * f[i]).sum())
+            .collect();
+        let res = backends!(_desamp_f64 => |desamp| {
+            let mut c = vec![0.0; m];
+            unsafe { desamp(a.as_ptr(), df as _, f.as_ptr(), c.as_mut_ptr(), m, p) };
+            c
+        });
+        check(res, &want, F64);
+        let res = backends!(_desamp_f32 => |desamp| {
+            let mut c = vec![0.0f32; m];
+            unsafe { desamp(a32.as_ptr(), df as _, f32.as_ptr(), c.as_mut_ptr(), m, p) };
+            c
+        });
+        check(res, &want, F32);
+    }
+
+    #[test]
+    fn biquads() {
+        // a resonant low-pass followed by a high shelf
+        let coefs = [
+            0.0675, 0.135, 0.0675, -1.143, 0.413, //
+            1.2, -0.5, 0.1, -0.3, 0.05,
+        ];
+        let x = signal(50, 20);
+
+        let mut want = x.clone();
+        for c in coefs.chunks(5) {
+            let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+            for v in want.iter_mut() {
+                let y = c[0] * *v + c[1] * x1 + c[2] * x2 - c[3] * y1 - c[4] * y2;
+                (x2, x1, y2, y1) = (x1, *v, y1, y);
+                *v = y;
+            }
+        }
+
+        // processing in two calls continues from the delay values
+        let run = |create: BiquadCreate<f64>,
+                   biquad: BiquadRun<f64>,
+                   destroy: BiquadDestroy<f64>,
+                   stride: usize| {
+            let x = spread(&x, stride);
+            let mut y = vec![0.0; x.len()];
+            let mut delay = [0.0; 6];
+            let s = stride as Stride;
+            let n = x.len() / stride;
+            let h = n / 2;
+            unsafe {
+                let setup = create(coefs.as_ptr(), 2).unwrap().as_ptr();
+                biquad(
+                    setup,
+                    delay.as_mut_ptr(),
+                    x.as_ptr(),
+                    s,
+                    y.as_mut_ptr(),
+                    s,
+                    h,
+                );
+                let (x, y) = (x[h * stride..].as_ptr(), y[h * stride..].as_mut_ptr());
+                biquad(setup, delay.as_mut_ptr(), x, s, y, s, n - h);
+                destroy(setup);
+            }
+            y.into_iter().step_by(stride).collect::<Vec<_>>()
+        };
+        for stride in [1, 3] {
+            let res = backends!(
+                _biquad_create_setup_f64, _biquad_f64, _biquad_destroy_setup_f64
+                    => |(c, b, d)| run(c, b, d, stride)
+            );
+            check(res, &want, F64);
+        }
+
+        let x32 = f32s(&x);
+        let res = backends!(
+            _biquad_create_setup_f32, _biquad_f32, _biquad_destroy_setup_f32
+                => |(create, biquad, destroy)| {
+                let mut y = vec![0.0f32; x32.len()];
+                let mut delay = [0.0f32; 6];
+                unsafe {
+                    let setup = create(coefs.as_ptr(), 2).unwrap().as_ptr();
+                    biquad(setup, delay.as_mut_ptr(), x32.as_ptr(), 1, y.as_mut_ptr(), 1, y.len());
+                    destroy(setup);
+                }
+                y
+            }
+        );
+        check(res, &want, 1e-4);
+    }
+
+    #[test]
+    fn fft_wrapper() {
+        let n = 64;
+        let (re, im) = (signal(n, 16), signal(n, 17));
+        let mut fft = vdsp::Fft::new_f32(6, FftRadix::_2).unwrap();
+        let (mut r, mut i) = (f32s(&re), f32s(&im));
+        fft.forward_io(&mut r, &mut i);
+        fft.inverse_io(&mut r, &mut i);
+        r.extend(i);
+        let mut want = re;
+        want.extend(im);
+        let want: Vec<f64> = want.iter().map(|v| v * n as f64).collect();
+        check(vec![("active", r)], &want, 1e-4);
+    }
+}
@@ -0,0 +1,316 @@
+use crate::vdsp::{self, DbKind, Fft, FftDirection, FftRadix, Len, Window};
+
+/// Streaming short-time Fourier transform of a real signal.
+///
+/// Samples are pushed in chunks of any size, every `hop` samples a frame of
+/// `2^log2n` samples is windowed, transformed and passed to the callback.
+///
+/// ```
+/// use cidre::vdsp;
+///
+/// let mut stft = vdsp::Stft::new(6, 16, vdsp::Window::Hann).unwrap();
+/// let signal: Vec<f32> = (0..256)
+///     .map(|i| (std::f32::consts::TAU * 8.0 * i as f32 / 64.0).sin())
+///     .collect();
+///
+/// let mut power = vec![0.0f32; stft.bins()];
+/// let mut frames = 0;
+/// stft.push(&signal, |frame| {
+///     frame.power(&mut power);
+///     let peak = (0..power.len()).max_by(|a, b| power[*a].total_cmp(&power[*b]));
+///     assert_eq!(peak, Some(8));
+///     frames += 1;
+/// });
+/// assert_eq!(frames, (256 - 64) / 16 + 1);
+/// ```
+pub struct Stft {
+    fft: Fft<f32>,
+    hop: usize,
+    window: Vec<f32>,
+    /// Samples not consumed yet, starting at the next frame.
+    pending: Vec<f32>,
+    /// How many samples of `pending` were already seen by a frame.
+    covered: usize,
+    index: u64,
+    frame: Vec<f32>,
+    re: Vec<f32>,
+    im: Vec<f32>,
+}
+
+impl Stft {
+    /// Frames of `2^log2n` samples, `hop` samples apart.
+    ///
+    /// Returns `None` if `hop` is zero or longer than a frame.
+    pub fn new(log2n: Len, hop: usize, window: Window) -> Option<Self> {
+        if log2n == 0 {
+            return None;
+        }
+        let n = 1usize.checked_shl(log2n as _)?;
+        if hop == 0 || hop > n {
+            return None;
+        }
+        let fft = Fft::new_f32(log2n, FftRadix::_2)?;
+        Some(Self {
+            fft,
+            hop,
+            window: window.new_f32(n),
+            pending: Vec::with_capacity(n * 2),
+            covered: 0,
+            index: 0,
+            frame: vec![0.0; n],
+            re: vec![0.0; n / 2],
+            im: vec![0.0; n / 2],
+        })
+    }
+
+    /// Same as [`Stft::new`] with hop derived from the overlap between frames.
+    ///
+    /// `overlap` must be smaller than the frame length.
+    pub fn with_overlap(log2n: Len, overlap: usize, window: Window) -> Option<Self> {
+        let n = 1usize.checked_shl(log2n as _)?;
+        Self::new(log2n, n.checked_sub(overlap)?, window)
+    }
+
+    /// Frame length in samples
+    #[inline]
+    pub fn frame_len(&self) -> usize {
+        self.window.len()
+    }
+
+    #[inline]
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    #[inline]
+    pub fn overlap(&self) -> usize {
+        self.frame_len() - self.hop
+    }
+
+    /// Number of frequency bins from DC to Nyquist inclusive
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.frame_len() / 2 + 1
+    }
+
+    #[inline]
+    pub fn window(&self) -> &[f32] {
+        &self.window
+    }
+
+    /// Appends samples and calls `f` for every frame completed by them.
+    pub fn push(&mut self, samples: &[f32], mut f: impl FnMut(&Frame)) {
+        let n = self.frame_len();
+        let mut samples = samples;
+        loop {
+            // never buffer more than one frame
+            let take = (n - self.pending.len()).min(samples.len());
+            self.pending.extend_from_slice(&samples[..take]);
+            samples = &samples[take..];
+            if self.pending.len() < n {
+                break;
+            }
+            self.process(&mut f);
+        }
+    }
+
+    /// Zero-pads samples which were not part of any frame yet and emits the last frame.
+    pub fn flush(&mut self, mut f: impl FnMut(&Frame)) {
+        if self.pending.len() > self.covered {
+            self.pending.resize(self.frame_len(), 0.0);
+            self.process(&mut f);
+        }
+        self.reset();
+    }
+
+    /// Drops buffered samples and restarts frame numbering.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.covered = 0;
+        self.index = 0;
+    }
+
+    fn process(&mut self, f: &mut impl FnMut(&Frame)) {
+        let n = self.frame_len();
+        vdsp::mul_f32(&self.pending[..n], &self.window, &mut self.frame);
+        vdsp::actoz_f32(&self.frame, &mut self.re, &mut self.im);
+        self.fft
+            .zr_io(&mut self.re, &mut self.im, FftDirection::Forward);
+        f(&Frame {
+            index: self.index,
+            start: self.index * self.hop as u64,
+            re: &self.re,
+            im: &self.im,
+        });
+        self.pending.drain(..self.hop);
+        self.covered = n - self.hop;
+        self.index += 1;
+    }
+}
+
+/// Spectrum of one windowed frame.
+pub struct Frame<'a> {
+    index: u64,
+    start: u64,
+    re: &'a [f32],
+    im: &'a [f32],
+}
+
+impl<'a> Frame<'a> {
+    /// Frame number since creation or the last reset
+    #[inline]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+
+    /// Position of the first sample of the frame in the pushed stream
+    #[inline]
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// Number of frequency bins from DC to Nyquist inclusive
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.re.len() + 1
+    }
+
+    /// Spectrum in vDSP packed format as produced by [`Fft::zr_io`]:
+    /// values are twice the DFT, and `im[0]` holds the real Nyquist bin.
+    #[inline]
+    pub fn packed(&self) -> (&'a [f32], &'a [f32]) {
+        (self.re, self.im)
+    }
+
+    /// Squared magnitudes of the DFT of the windowed frame, `out` has [`Frame::bins`] elements.
+    pub fn power(&self, out: &mut [f32]) {
+        let h = self.re.len();
+        assert_eq!(h + 1, out.len());
+        vdsp::zmags_f32(&self.re[1..], &self.im[1..], &mut out[1..h]);
+        out[0] = self.re[0] * self.re[0];
+        out[h] = self.im[0] * self.im[0];
+        // packed values are twice the DFT
+        let ptr = out.as_mut_ptr();
+        unsafe { vdsp::_smul_f32(ptr, 1, &0.25, ptr, 1, out.len()) };
+    }
+
+    /// Magnitudes of the DFT of the windowed frame, `out` has [`Frame::bins`] elements.
+    pub fn magnitude(&self, out: &mut [f32]) {
+        let h = self.re.len();
+        assert_eq!(h + 1, out.len());
+        vdsp::zabs_f32(&self.re[1..], &self.im[1..], &mut out[1..h]);
+        out[0] = self.re[0].abs();
+        out[h] = self.im[0].abs();
+        let ptr = out.as_mut_ptr();
+        unsafe { vdsp::_smul_f32(ptr, 1, &0.5, ptr, 1, out.len()) };
+    }
+
+    /// Power in decibels relative to `zero_ref`, clamped to `min_db` to avoid -inf.
+    pub fn power_db(&self, zero_ref: f32, min_db: f32, out: &mut [f32]) {
+        self.power(out);
+        let floor = zero_ref * 10f32.powf(min_db / 10.0);
+        let ptr = out.as_mut_ptr();
+        unsafe { vdsp::_clip_f32(ptr, 1, &floor, &f32::MAX, ptr, 1, out.len()) };
+        unsafe { vdsp::_dbcon_f32(ptr, 1, &zero_ref, ptr, 1, out.len(), DbKind::Power as _) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use crate::vdsp::{Stft, Window};
+
+    fn tone(bin: f32, n: usize, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (TAU * bin * i as f32 / n as f32).cos())
+            .collect()
+    }
+
+    #[test]
+    fn frames() {
+        assert!(Stft::new(6, 0, Window::Hann).is_none());
+        assert!(Stft::new(usize::BITS as _, 1, Window::Hann).is_none());
+        assert!(Stft::new(6, 65, Window::Hann).is_none());
+        assert!(Stft::with_overlap(6, 64, Window::Hann).is_none());
+
+        let mut stft = Stft::with_overlap(6, 48, Window::Hann).unwrap();
+        assert_eq!(stft.frame_len(), 64);
+        assert_eq!(stft.hop(), 16);
+        assert_eq!(stft.overlap(), 48);
+        assert_eq!(stft.bins(), 33);
+
+        let signal = tone(5.0, 64, 300);
+        let mut one_shot = vec![];
+        stft.push(&signal, |f| {
+            let mut p = vec![0.0; f.bins()];
+            f.power(&mut p);
+            one_shot.push((f.index(), f.start(), p));
+        });
+        assert_eq!(one_shot.len(), (300 - 64) / 16 + 1);
+        stft.flush(|f| {
+            assert_eq!(f.index(), 15);
+            assert_eq!(f.start(), 240);
+        });
+
+        // odd chunk sizes produce the same frames
+        let mut chunked = vec![];
+        for chunk in signal.chunks(7) {
+            stft.push(chunk, |f| {
+                let mut p = vec![0.0; f.bins()];
+                f.power(&mut p);
+                chunked.push((f.index(), f.start(), p));
+            });
+        }
+        assert_eq!(one_shot, chunked);
+
+        // frames ending exactly at the end of the signal leave nothing to flush
+        stft.reset();
+        stft.push(&signal[..64 + 16 * 3], |_| {});
+        stft.flush(|_| panic!("no samples left"));
+    }
+
+    #[test]
+    fn spectrum() {
+        let n = 64;
+        let mut stft = Stft::new(6, n, Window::Rect).unwrap();
+
+        // rectangular window keeps the DFT exact: amplitude * n / 2 for a cosine
+        let signal: Vec<f32> = tone(4.0, n, n).iter().map(|v| v + 0.5).collect();
+        let mut frames = 0;
+        stft.push(&signal, |f| {
+            let mut mag = vec![0.0; f.bins()];
+            f.magnitude(&mut mag);
+            for (k, m) in mag.iter().enumerate() {
+                let expected = match k {
+                    0 => 0.5 * n as f32,
+                    4 => 0.5 * n as f32,
+                    _ => 0.0,
+                };
+                assert!((m - expected).abs() < 1e-3, "bin {k}: {m}");
+            }
+
+            let mut power = vec![0.0; f.bins()];
+            f.power(&mut power);
+            assert!((power[4] - 32.0 * 32.0).abs() < 0.1);
+
+            let mut db = vec![0.0; f.bins()];
+            f.power_db(1.0, -100.0, &mut db);
+            assert!((db[4] - 10.0 * 1024f32.log10()).abs() < 1e-3);
+            assert!(db.iter().all(|v| *v >= -100.0 - 1e-3));
+            frames += 1;
+        });
+        assert_eq!(frames, 1);
+
+        // Nyquist lands in the packed im[0]
+        let nyquist: Vec<f32> = (0..n)
+            .map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+            .collect();
+        stft.push(&nyquist, |f| {
+            let mut mag = vec![0.0; f.bins()];
+            f.magnitude(&mut mag);
+            assert!((mag[32] - n as f32).abs() < 1e-3);
+            assert!(mag[..32].iter().all(|v| v.abs() < 1e-3));
+        });
+    }
+}
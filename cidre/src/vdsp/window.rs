@@ -0,0 +1,125 @@
+use crate::vdsp::{self, WindowFlags};
+
+/// Periodic window functions for spectral analysis.
+///
+/// Hann, Hamming and Blackman windows are generated by the vDSP back-end
+/// (see [`vdsp::hann_window_f32`]), so they match Accelerate exactly on Apple targets.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Window {
+    Rect,
+    Hann,
+    Hamming,
+    Blackman,
+    /// Kaiser window with the shape parameter beta.
+    /// 0 is a rectangular window, ~8.6 is close to Blackman.
+    Kaiser(f64),
+}
+
+impl Window {
+    /// Fills the whole `c` with the window of `c.len()` points
+    ///
+    /// ```
+    /// use cidre::vdsp;
+    ///
+    /// let w = vdsp::Window::Hann.new_f32(4);
+    /// for (w, e) in w.iter().zip([0.0, 0.5, 1.0, 0.5]) {
+    ///     assert!((w - e).abs() < 1e-6);
+    /// }
+    /// ```
+    pub fn fill_f32(&self, c: &mut [f32]) {
+        match *self {
+            Self::Rect => vdsp::fill_f32(&1.0, c),
+            Self::Hann => vdsp::hann_window_f32(c, WindowFlags::HANN_DENORM),
+            Self::Hamming => vdsp::hamm_window_f32(c, WindowFlags::DEFAULT),
+            Self::Blackman => vdsp::blkman_window_f32(c, WindowFlags::DEFAULT),
+            Self::Kaiser(beta) => {
+                let n = c.len();
+                for (i, c) in c.iter_mut().enumerate() {
+                    *c = kaiser(beta, i, n) as f32;
+                }
+            }
+        }
+    }
+
+    /// Fills the whole `c` with the window of `c.len()` points
+    pub fn fill_f64(&self, c: &mut [f64]) {
+        match *self {
+            Self::Rect => vdsp::fill_f64(&1.0, c),
+            Self::Hann => vdsp::hann_window_f64(c, WindowFlags::HANN_DENORM),
+            Self::Hamming => vdsp::hamm_window_f64(c, WindowFlags::DEFAULT),
+            Self::Blackman => vdsp::blkman_window_f64(c, WindowFlags::DEFAULT),
+            Self::Kaiser(beta) => {
+                let n = c.len();
+                for (i, c) in c.iter_mut().enumerate() {
+                    *c = kaiser(beta, i, n);
+                }
+            }
+        }
+    }
+
+    pub fn new_f32(&self, len: usize) -> Vec<f32> {
+        let mut res = vec![0.0; len];
+        self.fill_f32(&mut res);
+        res
+    }
+
+    pub fn new_f64(&self, len: usize) -> Vec<f64> {
+        let mut res = vec![0.0; len];
+        self.fill_f64(&mut res);
+        res
+    }
+}
+
+/// Zeroth order modified Bessel function of the first kind
+fn bessel_i0(x: f64) -> f64 {
+    let q = x * x / 4.0;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+    while term > sum * 1e-17 {
+        term *= q / (k * k);
+        sum += term;
+        k += 1.0;
+    }
+    sum
+}
+
+/// Periodic Kaiser window, peaks at n / 2 like the other vDSP windows
+fn kaiser(beta: f64, i: usize, n: usize) -> f64 {
+    let r = 2.0 * i as f64 / n as f64 - 1.0;
+    bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(beta)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vdsp::Window;
+
+    #[test]
+    fn basics() {
+        assert_eq!(Window::Rect.new_f32(3), [1.0; 3]);
+        assert_eq!(Window::Kaiser(0.0).new_f64(5), [1.0; 5]);
+
+        for window in [
+            Window::Hann,
+            Window::Hamming,
+            Window::Blackman,
+            Window::Kaiser(8.6),
+        ] {
+            let w = window.new_f64(64);
+            assert!((w[32] - 1.0).abs() < 1e-12, "{window:?}");
+            for i in 1..32 {
+                assert!((w[i] - w[64 - i]).abs() < 1e-12, "{window:?}");
+                assert!(w[i] > w[i - 1], "{window:?}");
+            }
+            let w32 = window.new_f32(64);
+            for (a, b) in w.iter().zip(&w32) {
+                assert!((*a as f32 - b).abs() < 1e-6, "{window:?}");
+            }
+        }
+
+        // Kaiser approaches Blackman around beta = 8.6
+        let k = Window::Kaiser(8.6).new_f64(64);
+        let b = Window::Blackman.new_f64(64);
+        assert!(k.iter().zip(&b).all(|(k, b)| (k - b).abs() < 0.05));
+    }
+}